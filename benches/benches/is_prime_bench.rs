@@ -5,7 +5,7 @@
 use std::{hint::black_box, path::PathBuf};
 
 use criterion::{Criterion, criterion_group, criterion_main};
-use midenc_benchmark_runner::BenchmarkRunner;
+use midenc_benchmark_runner::{BenchmarkMatrix, BenchmarkRunner};
 
 fn bench_is_prime_compilation(c: &mut Criterion) {
     let runner = BenchmarkRunner::new().expect("Failed to create benchmark runner");
@@ -21,26 +21,14 @@ fn bench_is_prime_compilation(c: &mut Criterion) {
 }
 
 fn bench_is_prime_execution(c: &mut Criterion) {
-    let runner = BenchmarkRunner::new().expect("Failed to create benchmark runner");
-    let source_path = PathBuf::from("../examples/is-prime/src/lib.rs");
-
-    // Pre-compile the program
-    let masm_path = runner.compile_rust_to_masm(&source_path).expect("Failed to compile program");
-
-    let mut group = c.benchmark_group("is_prime_execution");
+    let mut matrix = BenchmarkMatrix::new("is_prime_execution", "../examples/is-prime/src/lib.rs");
 
     // Test with different input values
-    for input in [7, 29, 97, 997, 9973].iter() {
-        group.bench_with_input(format!("is_prime({input})"), input, |b, &input| {
-            b.iter(|| {
-                runner
-                    .execute_masm(black_box(&masm_path), black_box(&[input as u64]))
-                    .expect("Execution failed")
-            })
-        });
+    for input in [7, 29, 97, 997, 9973] {
+        matrix = matrix.input(format!("is_prime({input})"), [input]);
     }
 
-    group.finish();
+    matrix.bench(c);
 }
 
 fn bench_is_prime_full_pipeline(c: &mut Criterion) {
@@ -0,0 +1,218 @@
+//! Baselines of benchmark results, used to detect performance regressions
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::ExecutionStats;
+
+/// What a [crate::BenchmarkRunner] does with a baseline after running a benchmark
+#[derive(Debug, Clone)]
+pub enum BaselineMode {
+    /// Compare the results with the baseline at the given path, failing on regressions
+    Compare {
+        path: std::path::PathBuf,
+        tolerance: Tolerance,
+    },
+    /// Record the results in the baseline at the given path, replacing any previous results of
+    /// the same benchmark
+    Save(std::path::PathBuf),
+}
+
+/// How much worse than its baseline a result may be before it is considered a regression
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tolerance {
+    /// The allowed increase in VM cycles, in percent
+    pub cycles_percent: f64,
+    /// The allowed increase in compilation and execution time, in percent
+    pub wall_time_percent: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            cycles_percent: 2.0,
+            wall_time_percent: 20.0,
+        }
+    }
+}
+
+/// Wall times which increase by less than this many milliseconds are never regressions, as such
+/// differences are within the resolution of the measurements
+const MIN_WALL_TIME_REGRESSION_MS: f64 = 1.0;
+
+/// The results of a set of benchmarks, keyed by program name and inputs
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub benchmarks: BTreeMap<String, ExecutionStats>,
+}
+
+impl Baseline {
+    /// Load the baseline at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline: {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse baseline: {}", path.display()))
+    }
+
+    /// Load the baseline at `path`, or return an empty baseline if it does not exist yet
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Write this baseline to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write baseline: {}", path.display()))
+    }
+
+    /// The key under which the results of `program_name` for `inputs` are recorded
+    pub fn key(program_name: &str, inputs: &[u64]) -> String {
+        let inputs = inputs.iter().map(|input| input.to_string()).collect::<Vec<_>>();
+        format!("{program_name} [{}]", inputs.join(", "))
+    }
+
+    /// Get the results recorded for `program_name` with `inputs`, if any
+    pub fn get(&self, program_name: &str, inputs: &[u64]) -> Option<&ExecutionStats> {
+        self.benchmarks.get(&Self::key(program_name, inputs))
+    }
+
+    /// Record `stats` as the results of `program_name` for `inputs`
+    pub fn insert(&mut self, program_name: &str, inputs: &[u64], stats: ExecutionStats) {
+        self.benchmarks.insert(Self::key(program_name, inputs), stats);
+    }
+
+    /// Compare `stats` with the results recorded for `program_name` with `inputs`
+    pub fn compare(
+        &self,
+        program_name: &str,
+        inputs: &[u64],
+        stats: &ExecutionStats,
+        tolerance: Tolerance,
+    ) -> ComparisonReport {
+        let key = Self::key(program_name, inputs);
+        let Some(baseline) = self.benchmarks.get(&key) else {
+            return ComparisonReport {
+                benchmark: key,
+                metrics: Vec::new(),
+            };
+        };
+
+        let metrics = vec![
+            MetricComparison::new(
+                "VM cycles",
+                baseline.vm_cycles as f64,
+                stats.vm_cycles as f64,
+                tolerance.cycles_percent,
+                0.0,
+            ),
+            MetricComparison::new(
+                "Compilation time (ms)",
+                baseline.compile_time_ms as f64,
+                stats.compile_time_ms as f64,
+                tolerance.wall_time_percent,
+                MIN_WALL_TIME_REGRESSION_MS,
+            ),
+            MetricComparison::new(
+                "Execution time (ms)",
+                baseline.execution_time_ms as f64,
+                stats.execution_time_ms as f64,
+                tolerance.wall_time_percent,
+                MIN_WALL_TIME_REGRESSION_MS,
+            ),
+        ];
+        ComparisonReport {
+            benchmark: key,
+            metrics,
+        }
+    }
+}
+
+/// The comparison of a single metric of a benchmark with its baseline
+#[derive(Debug, Clone)]
+pub struct MetricComparison {
+    /// The name of the metric
+    pub name: &'static str,
+    /// The value recorded in the baseline
+    pub baseline: f64,
+    /// The value measured now
+    pub current: f64,
+    /// The allowed increase, in percent
+    pub tolerance_percent: f64,
+    /// Whether the increase exceeds the tolerance
+    pub regressed: bool,
+}
+
+impl MetricComparison {
+    fn new(
+        name: &'static str,
+        baseline: f64,
+        current: f64,
+        tolerance_percent: f64,
+        min_regression: f64,
+    ) -> Self {
+        let allowed = baseline * tolerance_percent / 100.0;
+        let regressed = current - baseline > allowed.max(min_regression);
+        Self {
+            name,
+            baseline,
+            current,
+            tolerance_percent,
+            regressed,
+        }
+    }
+
+    /// The change from the baseline, in percent, if the baseline is non-zero
+    pub fn change_percent(&self) -> Option<f64> {
+        (self.baseline != 0.0).then(|| (self.current - self.baseline) / self.baseline * 100.0)
+    }
+}
+
+/// The comparison of the results of a benchmark with its baseline
+#[derive(Debug, Clone)]
+pub struct ComparisonReport {
+    /// The key of the benchmark in the baseline
+    pub benchmark: String,
+    /// The compared metrics, empty if the baseline has no results for this benchmark
+    pub metrics: Vec<MetricComparison>,
+}
+
+impl ComparisonReport {
+    /// Returns true if any metric regressed beyond its tolerance
+    pub fn has_regressions(&self) -> bool {
+        self.metrics.iter().any(|metric| metric.regressed)
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Comparison with baseline for: {}", self.benchmark)?;
+        if self.metrics.is_empty() {
+            return writeln!(f, "  no baseline recorded");
+        }
+        for metric in self.metrics.iter() {
+            let change = match metric.change_percent() {
+                Some(change) => format!("{change:+.2}%"),
+                None => "n/a".to_string(),
+            };
+            let status = if metric.regressed {
+                format!("REGRESSION (tolerance {}%)", metric.tolerance_percent)
+            } else {
+                "ok".to_string()
+            };
+            writeln!(
+                f,
+                "  {:<22} {:>12} -> {:<12} {:>9}  {status}",
+                metric.name, metric.baseline, metric.current, change
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -6,7 +6,9 @@
 use std::path::PathBuf;
 
 use clap::Parser;
-use midenc_benchmark_runner::BenchmarkRunner;
+use midenc_benchmark_runner::{
+    BaselineMode, BenchmarkRunner, RunnerMode, SecurityLevel, Tolerance,
+};
 
 #[derive(Parser)]
 struct Config {
@@ -24,6 +26,28 @@ struct Config {
     /// Number of iterations to run
     #[arg(short = 'n', long, value_name = "COUNT", default_value = "1")]
     iterations: usize,
+    /// Whether to compile and execute the program in-process, or with the installed `cargo miden`
+    /// and `midenc`
+    #[arg(long, value_enum, default_value_t = RunnerMode::InProcess)]
+    mode: RunnerMode,
+    /// Compare the results with the baseline at `<PATH>`, failing if they regressed
+    #[arg(long, value_name = "PATH", conflicts_with = "save_baseline")]
+    baseline: Option<PathBuf>,
+    /// Record the results in the baseline at `<PATH>`
+    #[arg(long, value_name = "PATH")]
+    save_baseline: Option<PathBuf>,
+    /// The allowed increase in VM cycles over the baseline, in percent
+    #[arg(long, value_name = "PERCENT", default_value = "2")]
+    cycles_tolerance: f64,
+    /// The allowed increase in compilation and execution time over the baseline, in percent
+    #[arg(long, value_name = "PERCENT", default_value = "20")]
+    time_tolerance: f64,
+    /// Also prove the execution of the program, measuring proving time and proof size
+    #[arg(long)]
+    prove: bool,
+    /// The security level of the proofs, in bits
+    #[arg(long, value_enum, default_value_t = SecurityLevel::Bits96, requires = "prove")]
+    security: SecurityLevel,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -36,7 +60,19 @@ fn main() -> anyhow::Result<()> {
     println!("Iterations: {}", config.iterations);
     println!();
 
-    let runner = BenchmarkRunner::new()?;
+    let mut runner = BenchmarkRunner::with_mode(config.mode)?;
+    if let Some(path) = config.baseline.clone() {
+        let tolerance = Tolerance {
+            cycles_percent: config.cycles_tolerance,
+            wall_time_percent: config.time_tolerance,
+        };
+        runner = runner.with_baseline(BaselineMode::Compare { path, tolerance });
+    } else if let Some(path) = config.save_baseline.clone() {
+        runner = runner.with_baseline(BaselineMode::Save(path));
+    }
+    if config.prove {
+        runner = runner.with_proving(config.security);
+    }
 
     let mut total_cycles = 0;
     let mut total_compile_time = 0;
@@ -2,23 +2,61 @@
 //!
 //! This module provides utilities for compiling Rust programs to Miden assembly
 //! and measuring their execution performance in the Miden VM.
+//!
+//! By default, programs are compiled and executed in-process, using the same APIs as
+//! `cargo miden build` and `cargo miden run`. The [RunnerMode::Subprocess] mode instead runs the
+//! installed `cargo miden` and `midenc` executables, for end-to-end benchmarking.
+//!
+//! With [BenchmarkRunner::with_proving], the execution of each program is also proven, to measure
+//! the cost of proving it, see [ProvingStats].
+//!
+//! Results can be recorded in a [Baseline], against which later results are compared to detect
+//! performance regressions, see [BaselineMode].
 
 use std::{
     path::{Path, PathBuf},
+    sync::Arc,
     time::Instant,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
+use miden_core::{Felt, serde::Deserializable};
+use miden_core_lib::CoreLibrary;
+use miden_debug::Executor;
+use miden_mast_package::Package;
+use midenc_session::{
+    STDLIB,
+    diagnostics::{DefaultSourceManager, PrintDiagnostic, SourceManager},
+};
+use serde::{Deserialize, Serialize};
+
+mod baseline;
+#[cfg(feature = "criterion")]
+mod matrix;
+mod prove;
+
+#[cfg(feature = "criterion")]
+pub use self::matrix::{BenchmarkMatrix, bench_package_execution};
+pub use self::{
+    baseline::{Baseline, BaselineMode, ComparisonReport, MetricComparison, Tolerance},
+    prove::{ProvingStats, SecurityLevel},
+};
 
 /// Execution statistics for a Miden program
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionStats {
     /// Total VM cycles executed
     pub vm_cycles: usize,
+    /// The padded length of the execution trace, which may exceed the number of VM cycles, e.g.
+    /// when the chiplets need more rows
+    pub trace_len: usize,
     /// Compilation time in milliseconds
     pub compile_time_ms: u128,
     /// Execution time in milliseconds
     pub execution_time_ms: u128,
+    /// Statistics about the proof of the execution, if it was proven
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proving: Option<ProvingStats>,
 }
 
 impl ExecutionStats {
@@ -29,55 +67,118 @@ impl ExecutionStats {
         execution_time_ms: u128,
     ) -> Result<Self> {
         // Parse the VM cycles from midenc output
-        let vm_cycles = Self::parse_vm_cycles(output)?;
+        let (vm_cycles, trace_len) = Self::parse_vm_cycles(output)?;
 
         Ok(Self {
             vm_cycles,
+            trace_len,
             compile_time_ms,
             execution_time_ms,
+            proving: None,
         })
     }
 
-    /// Parse VM cycles from midenc run output
-    fn parse_vm_cycles(output: &str) -> Result<usize> {
+    /// Parse VM cycles and the trace length from midenc run output
+    fn parse_vm_cycles(output: &str) -> Result<(usize, usize)> {
         for line in output.lines() {
-            if line.contains("VM cycles:") {
-                // Look for pattern like "VM cycles: 805 extended to 1024 steps"
-                if let Some(cycles_part) = line.split("VM cycles:").nth(1)
-                    && let Some(cycles_str) = cycles_part.split_whitespace().next()
-                {
-                    return cycles_str
+            // Look for pattern like "VM cycles: 805 extended to 1024 steps"
+            if let Some(cycles_part) = line.split("VM cycles:").nth(1) {
+                let mut words = cycles_part.split_whitespace();
+                let cycles_str = words.next().unwrap_or_default();
+                let vm_cycles: usize = cycles_str
+                    .parse()
+                    .with_context(|| format!("Failed to parse VM cycles from: {cycles_str}"))?;
+                let trace_len = match (words.next(), words.next(), words.next()) {
+                    (Some("extended"), Some("to"), Some(steps)) => steps
                         .parse()
-                        .with_context(|| format!("Failed to parse VM cycles from: {cycles_str}"));
-                }
+                        .with_context(|| format!("Failed to parse trace length from: {steps}"))?,
+                    _ => vm_cycles.next_power_of_two(),
+                };
+                return Ok((vm_cycles, trace_len));
             }
         }
         Err(anyhow::anyhow!("Could not find VM cycles in output"))
     }
 
+    /// Serialize these statistics to JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserialize statistics from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("Failed to parse execution statistics")
+    }
+
     /// Print formatted execution statistics
     pub fn print(&self, program_name: &str) {
         println!("===============================================================================");
         println!("Benchmark results for: {program_name}");
         println!("-------------------------------------------------------------------------------");
-        println!(
-            "VM cycles: {} extended to {} steps",
-            self.vm_cycles,
-            self.vm_cycles.next_power_of_two()
-        );
+        println!("VM cycles: {} extended to {} steps", self.vm_cycles, self.trace_len);
         println!("Compilation time: {} ms", self.compile_time_ms);
         println!("Execution time: {} ms", self.execution_time_ms);
+        if let Some(proving) = &self.proving {
+            println!("Proving time: {} ms", proving.proving_time_ms);
+            println!(
+                "Proof size: {} bytes ({} bits of security)",
+                proving.proof_size_bytes, proving.security_bits
+            );
+            if let Some(peak_rss_bytes) = proving.peak_rss_bytes {
+                println!("Peak RSS: {} MiB", peak_rss_bytes / (1024 * 1024));
+            }
+        }
         println!("===============================================================================");
     }
 }
 
+/// How a [BenchmarkRunner] compiles and executes programs
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum RunnerMode {
+    /// Compile and execute programs in-process, so that the measured times only include the
+    /// compiler and the VM
+    #[default]
+    InProcess,
+    /// Run the installed `cargo miden build` and `midenc run`, so that the measured times also
+    /// include starting them
+    Subprocess,
+}
+
 /// A benchmark runner for Miden programs
-pub struct BenchmarkRunner;
+pub struct BenchmarkRunner {
+    mode: RunnerMode,
+    baseline: Option<BaselineMode>,
+    proving: Option<SecurityLevel>,
+}
 
 impl BenchmarkRunner {
-    /// Create a new benchmark runner
+    /// Create a new benchmark runner, which compiles and executes programs in-process
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        Self::with_mode(RunnerMode::default())
+    }
+
+    /// Create a new benchmark runner, which compiles and executes programs as given by `mode`
+    pub fn with_mode(mode: RunnerMode) -> Result<Self> {
+        Ok(Self {
+            mode,
+            baseline: None,
+            proving: None,
+        })
+    }
+
+    /// Also prove the execution of each benchmarked program, with proofs of the given security
+    ///
+    /// Proving is done in-process, regardless of the [RunnerMode], and the proofs are verified.
+    pub fn with_proving(mut self, security: SecurityLevel) -> Self {
+        self.proving = Some(security);
+        self
+    }
+
+    /// Compare the results of each benchmark with a baseline, or record them in it, see
+    /// [BaselineMode]
+    pub fn with_baseline(mut self, baseline: BaselineMode) -> Self {
+        self.baseline = Some(baseline);
+        self
     }
 
     /// Compile a Rust source file to Miden assembly using cargo miden
@@ -96,36 +197,14 @@ impl BenchmarkRunner {
             .and_then(|p| p.parent()) // Go up from src/ to project root
             .ok_or_else(|| anyhow::anyhow!("Could not determine project directory"))?;
 
-        // Use cargo miden to build the project
-        let mut cmd = std::process::Command::new("cargo");
-        cmd.arg("miden")
-            .arg("build")
-            .arg("--release")
-            .arg("--manifest-path")
-            .arg(project_dir.join("Cargo.toml"))
-            .current_dir(project_dir);
-
-        let output = cmd.output().with_context(|| "Failed to execute cargo miden build")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("cargo miden build failed: {stderr}"));
-        }
+        let masp_path = match self.mode {
+            RunnerMode::InProcess => compile_in_process(project_dir)?,
+            RunnerMode::Subprocess => compile_in_subprocess(project_dir)?,
+        };
 
         let compile_time = compile_start.elapsed();
         println!("Compilation completed in {} ms", compile_time.as_millis());
 
-        // Find the generated .masp file
-        let target_dir = project_dir.join("target").join("miden").join("release");
-        let project_name = project_dir
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| anyhow::anyhow!("Could not determine project name"))?;
-
-        // Convert hyphens to underscores for the MASP filename
-        let masp_filename = project_name.replace('-', "_");
-        let masp_path = target_dir.join(format!("{masp_filename}.masp"));
-
         if !masp_path.exists() {
             return Err(anyhow::anyhow!("Expected MASP file not found: {}", masp_path.display()));
         }
@@ -133,45 +212,17 @@ impl BenchmarkRunner {
         Ok(masp_path)
     }
 
-    /// Execute a Miden assembly program using midenc run and return execution statistics
+    /// Execute a Miden assembly program and return execution statistics
     pub fn execute_masm(&self, masm_path: &Path, inputs: &[u64]) -> Result<ExecutionStats> {
-        let execution_start = Instant::now();
-
-        // Create inputs file in TOML format
-        let inputs_content = format!(
-            r#"[inputs]
-stack = [{}]"#,
-            inputs.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", ")
-        );
-
-        let inputs_file = masm_path.with_extension("inputs");
-        std::fs::write(&inputs_file, inputs_content)
-            .with_context(|| format!("Failed to write inputs file: {}", inputs_file.display()))?;
-
-        // Use midenc run to execute the program
-        let mut cmd = std::process::Command::new("midenc");
-        cmd.arg("run").arg(masm_path).arg("--inputs").arg(&inputs_file);
-
-        let output = cmd.output().with_context(|| "Failed to execute midenc run")?;
-
-        let execution_time = execution_start.elapsed();
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("midenc run failed: {stderr}"));
+        match self.mode {
+            RunnerMode::InProcess => execute_in_process(masm_path, inputs),
+            RunnerMode::Subprocess => execute_in_subprocess(masm_path, inputs),
         }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        println!("Program executed successfully");
-        println!("{stdout}");
-
-        // Clean up inputs file
-        let _ = std::fs::remove_file(&inputs_file);
-
-        ExecutionStats::from_midenc_output(&stdout, 0, execution_time.as_millis())
     }
 
     /// Run a complete benchmark: compile Rust to MASM and execute
+    ///
+    /// If the results are compared with a baseline, this returns an error when they regressed.
     pub fn run_benchmark(
         &self,
         source_path: &Path,
@@ -186,9 +237,32 @@ stack = [{}]"#,
 
         let mut stats = self.execute_masm(&masm_path, inputs)?;
         stats.compile_time_ms = compile_time.as_millis();
+        if let Some(security) = self.proving {
+            let package = load_package(&masm_path)?;
+            stats.proving = Some(prove::prove_package(&package, inputs, security)?);
+        }
 
         stats.print(program_name);
 
+        match &self.baseline {
+            None => (),
+            Some(BaselineMode::Save(path)) => {
+                // Other benchmarks may already be recorded in the same baseline
+                let mut baseline = Baseline::load_or_default(path)?;
+                baseline.insert(program_name, inputs, stats.clone());
+                baseline.save(path)?;
+                println!("Saved baseline to {}", path.display());
+            }
+            Some(BaselineMode::Compare { path, tolerance }) => {
+                let report =
+                    Baseline::load(path)?.compare(program_name, inputs, &stats, *tolerance);
+                print!("{report}");
+                if report.has_regressions() {
+                    bail!("Performance regression detected\n{report}");
+                }
+            }
+        }
+
         Ok(stats)
     }
 }
@@ -198,3 +272,163 @@ impl Default for BenchmarkRunner {
         Self::new().expect("Failed to create benchmark runner")
     }
 }
+
+/// Compile the Cargo project in `project_dir` in-process, as `cargo miden build` does, returning
+/// the path of the compiled package
+fn compile_in_process(project_dir: &Path) -> Result<PathBuf> {
+    let manifest_path = project_dir.join("Cargo.toml");
+    let args = ["cargo", "miden", "build", "--release", "--manifest-path"]
+        .into_iter()
+        .map(String::from)
+        .chain([manifest_path.display().to_string()])
+        // Measure the compiler, rather than the cache of packages compiled previously
+        .chain(["--no-cache".to_string()]);
+    let output = cargo_miden::run(args)
+        .context("cargo miden build failed")?
+        .ok_or_else(|| anyhow!("cargo miden build did not produce a package"))?;
+    output
+        .unwrap_build_output()
+        .pop()
+        .ok_or_else(|| anyhow!("cargo miden build did not produce a package"))
+}
+
+/// Compile the Cargo project in `project_dir` by running `cargo miden build`, returning the path
+/// of the compiled package
+fn compile_in_subprocess(project_dir: &Path) -> Result<PathBuf> {
+    // Use cargo miden to build the project
+    let mut cmd = std::process::Command::new("cargo");
+    cmd.arg("miden")
+        .arg("build")
+        .arg("--release")
+        .arg("--manifest-path")
+        .arg(project_dir.join("Cargo.toml"))
+        // Measure the compiler, rather than the cache of packages compiled previously
+        .arg("--no-cache")
+        .current_dir(project_dir);
+
+    let output = cmd.output().with_context(|| "Failed to execute cargo miden build")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("cargo miden build failed: {stderr}"));
+    }
+
+    // Find the generated .masp file
+    let target_dir = project_dir.join("target").join("miden").join("release");
+    let project_name = project_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Could not determine project name"))?;
+
+    // Convert hyphens to underscores for the MASP filename
+    let masp_filename = project_name.replace('-', "_");
+    Ok(target_dir.join(format!("{masp_filename}.masp")))
+}
+
+/// Execute the program at `masp_path` in-process, as `midenc run` does, and return execution
+/// statistics
+fn execute_in_process(masp_path: &Path, inputs: &[u64]) -> Result<ExecutionStats> {
+    let package = load_package(masp_path)?;
+
+    let execution_start = Instant::now();
+    let (stack, vm_cycles, trace_len) = execute_package(&package, inputs)?;
+    let execution_time = execution_start.elapsed();
+
+    println!("Program executed successfully");
+    println!("Output stack: {stack:?}");
+
+    Ok(ExecutionStats {
+        vm_cycles,
+        trace_len,
+        compile_time_ms: 0,
+        execution_time_ms: execution_time.as_millis(),
+        proving: None,
+    })
+}
+
+/// Load the program package at `masp_path`
+fn load_package(masp_path: &Path) -> Result<Package> {
+    let bytes = std::fs::read(masp_path)
+        .with_context(|| format!("Failed to read package: {}", masp_path.display()))?;
+    let package = Package::read_from_bytes(&bytes)
+        .map_err(|err| anyhow!("Failed to decode package '{}': {err}", masp_path.display()))?;
+    if !package.is_program() {
+        bail!("unable to run '{}': the package is a library", package.name);
+    }
+    Ok(package)
+}
+
+/// Execute the program `package` with `inputs` on the operand stack, returning the output stack,
+/// the number of VM cycles it took, and the padded length of its execution trace
+fn execute_package(package: &Package, inputs: &[u64]) -> Result<(Vec<u64>, usize, usize)> {
+    let mut exec = Executor::new(felts(inputs)?);
+    let core_library = CoreLibrary::default();
+    for (event, handler) in core_library.handlers() {
+        exec.register_event_handler(event, handler)
+            .map_err(|err| anyhow!("failed to register core library event handler: {err}"))?;
+    }
+    let std_library = (*STDLIB).clone();
+    exec.dependency_resolver_mut().insert(*std_library.digest(), std_library);
+    exec.with_dependencies(package.manifest.dependencies())
+        .map_err(|err| anyhow!("{}", PrintDiagnostic::new(err)))?;
+
+    let source_manager =
+        Arc::new(DefaultSourceManager::default()) as Arc<dyn SourceManager + Send + Sync>;
+    let mut executor = exec.capture_trace(&package.unwrap_program(), source_manager);
+    while !executor.stopped {
+        if let Err(err) = executor.step() {
+            bail!("execution failed at cycle {}: {err}", executor.cycle);
+        }
+    }
+    let vm_cycles = executor.cycle;
+    let trace = executor.into_execution_trace();
+    let trace_len = trace.trace_len_summary().padded_trace_len();
+
+    Ok((trace.outputs().as_int_vec(), vm_cycles, trace_len))
+}
+
+/// Convert `inputs` to field elements, rejecting those which are not canonical
+fn felts(inputs: &[u64]) -> Result<Vec<Felt>> {
+    inputs
+        .iter()
+        .map(|&input| Felt::new(input).map_err(|_| anyhow!("{input} is not a valid felt")))
+        .collect()
+}
+
+/// Execute the program at `masm_path` using midenc run and return execution statistics
+fn execute_in_subprocess(masm_path: &Path, inputs: &[u64]) -> Result<ExecutionStats> {
+    let execution_start = Instant::now();
+
+    // Create inputs file in TOML format
+    let inputs_content = format!(
+        r#"[inputs]
+stack = [{}]"#,
+        inputs.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", ")
+    );
+
+    let inputs_file = masm_path.with_extension("inputs");
+    std::fs::write(&inputs_file, inputs_content)
+        .with_context(|| format!("Failed to write inputs file: {}", inputs_file.display()))?;
+
+    // Use midenc run to execute the program
+    let mut cmd = std::process::Command::new("midenc");
+    cmd.arg("run").arg(masm_path).arg("--inputs").arg(&inputs_file);
+
+    let output = cmd.output().with_context(|| "Failed to execute midenc run")?;
+
+    let execution_time = execution_start.elapsed();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("midenc run failed: {stderr}"));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("Program executed successfully");
+    println!("{stdout}");
+
+    // Clean up inputs file
+    let _ = std::fs::remove_file(&inputs_file);
+
+    ExecutionStats::from_midenc_output(&stdout, 0, execution_time.as_millis())
+}
@@ -0,0 +1,73 @@
+//! Integration with [criterion], to measure the execution of a program over a set of inputs
+
+use std::{hint::black_box, path::PathBuf};
+
+use criterion::{Bencher, Criterion};
+use miden_mast_package::Package;
+
+use crate::{BenchmarkRunner, execute_package, load_package};
+
+/// Measure the execution of the program `package` with `inputs` on the operand stack.
+///
+/// The package is compiled once by the caller, and only executed in each iteration.
+pub fn bench_package_execution(bencher: &mut Bencher<'_>, package: &Package, inputs: &[u64]) {
+    bencher.iter(|| execute_package(package, black_box(inputs)).expect("Execution failed"));
+}
+
+/// A program benchmarked over a set of named inputs, with one benchmark per input.
+///
+/// The program is compiled once, and executed in-process. The VM cycles executed for each input
+/// are printed once, before it is benchmarked, as they do not vary between runs.
+///
+/// ```ignore
+/// BenchmarkMatrix::new("fibonacci", "../examples/fibonacci/src/lib.rs")
+///     .input("10", [10])
+///     .input("20", [20])
+///     .bench(criterion);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BenchmarkMatrix {
+    name: String,
+    source_path: PathBuf,
+    inputs: Vec<(String, Vec<u64>)>,
+}
+
+impl BenchmarkMatrix {
+    /// Create a benchmark of the program whose Rust source file is at `source_path`
+    pub fn new(name: impl Into<String>, source_path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            source_path: source_path.into(),
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Benchmark the program with `inputs` on the operand stack, under the name `name`
+    pub fn input(mut self, name: impl Into<String>, inputs: impl Into<Vec<u64>>) -> Self {
+        self.inputs.push((name.into(), inputs.into()));
+        self
+    }
+
+    /// Compile the program, and run one benchmark for each of its inputs, in a group named after
+    /// the program
+    pub fn bench(&self, criterion: &mut Criterion) {
+        let runner = BenchmarkRunner::new().expect("Failed to create benchmark runner");
+        let masp_path = runner
+            .compile_rust_to_masm(&self.source_path)
+            .expect("Failed to compile program");
+        let package = load_package(&masp_path).expect("Failed to load program");
+
+        let mut group = criterion.benchmark_group(&self.name);
+        for (name, inputs) in self.inputs.iter() {
+            let (_, vm_cycles) = execute_package(&package, inputs).expect("Execution failed");
+            println!(
+                "{}/{name}: {vm_cycles} VM cycles extended to {} steps",
+                self.name,
+                vm_cycles.next_power_of_two()
+            );
+            group
+                .bench_function(name, |bencher| bench_package_execution(bencher, &package, inputs));
+        }
+        group.finish();
+    }
+}
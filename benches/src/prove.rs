@@ -0,0 +1,99 @@
+//! Proving of benchmarked programs, to measure the cost of generating their execution proofs
+
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
+use miden_core_lib::CoreLibrary;
+use miden_mast_package::Package;
+use miden_processor::{DefaultHost, ProgramInfo, StackInputs, advice::AdviceInputs};
+use miden_prover::{HashFunction, ProvingOptions, prove_sync};
+use serde::{Deserialize, Serialize};
+
+use crate::felts;
+
+/// The security level of the proofs generated when benchmarking with proving enabled
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum SecurityLevel {
+    /// Proofs with 96 bits of conjectured security
+    #[default]
+    #[value(name = "96")]
+    Bits96,
+    /// Proofs with 128 bits of conjectured security
+    #[value(name = "128")]
+    Bits128,
+}
+
+impl SecurityLevel {
+    /// The options the prover is configured with for this security level
+    pub fn proving_options(self) -> ProvingOptions {
+        match self {
+            Self::Bits96 => ProvingOptions::with_96_bit_security(HashFunction::Rpo256),
+            Self::Bits128 => ProvingOptions::with_128_bit_security(HashFunction::Rpo256),
+        }
+    }
+}
+
+/// Statistics about the proof of a program's execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingStats {
+    /// Proving time in milliseconds
+    pub proving_time_ms: u128,
+    /// The size of the serialized proof in bytes
+    pub proof_size_bytes: usize,
+    /// The conjectured security of the proof in bits, as reported by the verifier
+    pub security_bits: u32,
+    /// The peak resident set size of the benchmark process after proving, in bytes, if it could
+    /// be determined
+    ///
+    /// This is the peak of the whole process, and thus an upper bound of the memory used by the
+    /// prover.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// Prove the execution of the program `package` with `inputs` on the operand stack, and verify
+/// the resulting proof.
+pub(crate) fn prove_package(
+    package: &Package,
+    inputs: &[u64],
+    security: SecurityLevel,
+) -> Result<ProvingStats> {
+    let program = package.unwrap_program();
+    let stack_inputs =
+        StackInputs::new(&felts(inputs)?).map_err(|err| anyhow!("invalid stack inputs: {err}"))?;
+    let mut host = DefaultHost::default();
+    host.load_library(&CoreLibrary::default())
+        .map_err(|err| anyhow!("failed to load core library into host: {err}"))?;
+
+    let proving_start = Instant::now();
+    let (stack_outputs, proof) = prove_sync(
+        &program,
+        stack_inputs.clone(),
+        AdviceInputs::default(),
+        &mut host,
+        security.proving_options(),
+    )
+    .map_err(|err| anyhow!("proving failed: {err}"))?;
+    let proving_time = proving_start.elapsed();
+    let peak_rss_bytes = peak_rss_bytes();
+    let proof_size_bytes = proof.to_bytes().len();
+
+    let program_info = ProgramInfo::from(program.as_ref().clone());
+    let security_bits = miden_verifier::verify(program_info, stack_inputs, stack_outputs, proof)
+        .map_err(|err| anyhow!("the proof failed to verify: {err}"))?;
+
+    Ok(ProvingStats {
+        proving_time_ms: proving_time.as_millis(),
+        proof_size_bytes,
+        security_bits,
+        peak_rss_bytes,
+    })
+}
+
+/// Returns the peak resident set size of this process, in bytes, on platforms where it is known
+fn peak_rss_bytes() -> Option<u64> {
+    // `VmHWM` is the high water mark of the resident set size, in kB
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
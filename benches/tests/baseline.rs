@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use midenc_benchmark_runner::{Baseline, BaselineMode, BenchmarkRunner, ExecutionStats, Tolerance};
+
+fn stats(vm_cycles: usize, compile_time_ms: u128, execution_time_ms: u128) -> ExecutionStats {
+    ExecutionStats {
+        vm_cycles,
+        trace_len: vm_cycles.next_power_of_two(),
+        compile_time_ms,
+        execution_time_ms,
+        proving: None,
+    }
+}
+
+#[test]
+fn execution_stats_json_roundtrip() {
+    let stats = stats(805, 1200, 15);
+    let json = stats.to_json().unwrap();
+    assert_eq!(ExecutionStats::from_json(&json).unwrap(), stats);
+}
+
+#[test]
+fn baseline_is_keyed_by_program_and_inputs() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("baseline.json");
+
+    let mut baseline = Baseline::load_or_default(&path).unwrap();
+    baseline.insert("is_prime", &[29], stats(533, 1000, 10));
+    baseline.insert("is_prime", &[97], stats(805, 1000, 10));
+    baseline.save(&path).unwrap();
+
+    let baseline = Baseline::load(&path).unwrap();
+    assert_eq!(baseline.benchmarks.len(), 2);
+    assert_eq!(baseline.get("is_prime", &[97]).unwrap().vm_cycles, 805);
+    assert!(baseline.get("is_prime", &[13]).is_none());
+}
+
+#[test]
+fn regressions_beyond_tolerance_are_reported() {
+    let mut baseline = Baseline::default();
+    baseline.insert("is_prime", &[97], stats(800, 1000, 10));
+    let tolerance = Tolerance::default();
+
+    // Within tolerance: +1.25% cycles, +10% compilation time, +1 ms execution time
+    let report = baseline.compare("is_prime", &[97], &stats(810, 1100, 11), tolerance);
+    assert!(!report.has_regressions(), "{report}");
+
+    let report = baseline.compare("is_prime", &[97], &stats(900, 1100, 11), tolerance);
+    assert!(report.has_regressions());
+    let regressed = report
+        .metrics
+        .iter()
+        .filter(|metric| metric.regressed)
+        .map(|metric| metric.name)
+        .collect::<Vec<_>>();
+    assert_eq!(regressed, ["VM cycles"]);
+    let report = report.to_string();
+    assert!(report.contains("is_prime [97]"), "{report}");
+    assert!(report.contains("+12.50%"), "{report}");
+    assert!(report.contains("REGRESSION (tolerance 2%)"), "{report}");
+
+    let report = baseline.compare("is_prime", &[97], &stats(800, 1500, 10), tolerance);
+    assert!(report.has_regressions(), "{report}");
+
+    // Benchmarks missing from the baseline are not regressions
+    let report = baseline.compare("is_prime", &[29], &stats(900, 1000, 10), tolerance);
+    assert!(!report.has_regressions());
+    assert!(report.to_string().contains("no baseline recorded"));
+}
+
+#[test]
+fn benchmark_fails_against_doctored_baseline() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("baseline.json");
+    let source = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/is-prime/src/lib.rs");
+
+    let runner = BenchmarkRunner::new().unwrap().with_baseline(BaselineMode::Save(path.clone()));
+    let stats = runner.run_benchmark(&source, &[97], "is_prime").unwrap();
+    assert_eq!(Baseline::load(&path).unwrap().get("is_prime", &[97]), Some(&stats));
+
+    // Pretend the program used to take half as many cycles, and ignore wall times
+    let mut baseline = Baseline::load(&path).unwrap();
+    baseline.insert(
+        "is_prime",
+        &[97],
+        ExecutionStats {
+            vm_cycles: stats.vm_cycles / 2,
+            ..stats.clone()
+        },
+    );
+    baseline.save(&path).unwrap();
+    let tolerance = Tolerance {
+        wall_time_percent: f64::INFINITY,
+        ..Tolerance::default()
+    };
+
+    let runner = BenchmarkRunner::new()
+        .unwrap()
+        .with_baseline(BaselineMode::Compare { path, tolerance });
+    let err = runner.run_benchmark(&source, &[97], "is_prime").unwrap_err().to_string();
+    assert!(err.contains("Performance regression detected"), "{err}");
+    assert!(err.contains("is_prime [97]"), "{err}");
+    assert!(err.contains("REGRESSION (tolerance 2%)"), "{err}");
+}
@@ -0,0 +1,20 @@
+//! Runs a [BenchmarkMatrix] under criterion.
+//!
+//! This target has no test harness: when run by `cargo test`, criterion runs each benchmark once,
+//! rather than measuring it.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use midenc_benchmark_runner::BenchmarkMatrix;
+
+fn bench_fibonacci(c: &mut Criterion) {
+    BenchmarkMatrix::new(
+        "fibonacci",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/fibonacci/src/lib.rs"),
+    )
+    .input("1", [1])
+    .input("10", [10])
+    .bench(c);
+}
+
+criterion_group!(benches, bench_fibonacci);
+criterion_main!(benches);
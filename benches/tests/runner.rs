@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use midenc_benchmark_runner::{BenchmarkRunner, ExecutionStats, RunnerMode, SecurityLevel};
+
+fn is_prime_source() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/is-prime/src/lib.rs")
+}
+
+#[test]
+fn midenc_output_is_parsed() {
+    let output = "Executed program\nVM cycles: 805 extended to 1024 steps\n";
+    let stats = ExecutionStats::from_midenc_output(output, 1, 2).unwrap();
+    assert_eq!(stats.vm_cycles, 805);
+    assert_eq!(stats.trace_len, 1024);
+
+    assert!(ExecutionStats::from_midenc_output("Executed program\n", 1, 2).is_err());
+}
+
+#[test]
+fn in_process_benchmark_reports_cycles() {
+    let runner = BenchmarkRunner::new().unwrap();
+    let stats = runner.run_benchmark(&is_prime_source(), &[97], "is_prime(97)").unwrap();
+    assert!(stats.vm_cycles > 0);
+    assert!(stats.trace_len >= stats.vm_cycles, "{stats:?}");
+    assert!(stats.trace_len.is_power_of_two(), "{stats:?}");
+}
+
+/// The in-process runner executes programs the same way `midenc run` does
+#[test]
+fn in_process_and_subprocess_cycles_match() {
+    let in_process = BenchmarkRunner::new()
+        .unwrap()
+        .run_benchmark(&is_prime_source(), &[97], "is_prime(97)")
+        .unwrap();
+    let subprocess = BenchmarkRunner::with_mode(RunnerMode::Subprocess)
+        .unwrap()
+        .run_benchmark(&is_prime_source(), &[97], "is_prime(97)")
+        .unwrap();
+    assert_eq!(in_process.vm_cycles, subprocess.vm_cycles);
+    assert_eq!(in_process.trace_len, subprocess.trace_len);
+}
+
+#[test]
+fn proving_benchmark_reports_proof_stats() {
+    let runner = BenchmarkRunner::new().unwrap().with_proving(SecurityLevel::Bits96);
+    let stats = runner.run_benchmark(&is_prime_source(), &[2], "is_prime(2)").unwrap();
+    let proving = stats.proving.as_ref().expect("the execution should have been proven");
+    assert!(proving.proof_size_bytes > 0);
+    assert!(proving.security_bits > 0);
+
+    let json = stats.to_json().unwrap();
+    assert!(json.contains("proof_size_bytes"), "{json}");
+    assert_eq!(ExecutionStats::from_json(&json).unwrap(), stats);
+}
@@ -10,7 +10,7 @@ use midenc_session::{
     diagnostics::{IntoDiagnostic, Report, SourceSpan, Span, WrapErr},
 };
 
-use crate::{TraceEvent, lower::NativePtr, masm};
+use crate::{TraceEvent, linker::MergedProcedure, lower::NativePtr, masm};
 
 mod project_support;
 
@@ -25,10 +25,11 @@ pub struct MasmComponent {
     /// This function is responsible for initializing global variables and writing data segments
     /// into memory at program startup, and at cross-context call boundaries (in callee prologue).
     pub init: Option<masm::InvocationTarget>,
-    /// The symbol name of the program entrypoint, if this component is executable.
+    /// The symbol names of the requested program entrypoints.
     ///
-    /// If unset, it indicates that the component is a library, even if it could be made executable.
-    pub entrypoint: Option<masm::InvocationTarget>,
+    /// If empty, it indicates that the component is a library, even if it could be made executable.
+    /// If there is more than one, the component is a library which exports each entrypoint.
+    pub entrypoints: Vec<masm::InvocationTarget>,
     /// The kernel library to link against
     pub kernel: Option<masm::KernelLibrary>,
     /// The rodata segments of this component keyed by the offset of the segment
@@ -39,6 +40,38 @@ pub struct MasmComponent {
     pub stack_pointer: Option<u32>,
     /// The set of modules in this component
     pub modules: Vec<Arc<masm::Module>>,
+    /// The procedures which were merged into an identical procedure of the same module, in the
+    /// order in which they were merged
+    pub merged_procedures: Vec<MergedProcedure>,
+    /// Whether the emitted Miden Assembly was optimized, see `--no-masm-opt`
+    pub optimized: bool,
+}
+
+/// A package exporting a single entrypoint of a [MasmComponent], as emitted by
+/// `--emit=masp-per-entrypoint`
+pub struct EntrypointPackage {
+    /// The name of this package, i.e. `<package>::<entrypoint>`
+    pub name: Symbol,
+    pub package: Arc<Package>,
+}
+
+impl Emit for EntrypointPackage {
+    fn name(&self) -> Option<Symbol> {
+        Some(self.name)
+    }
+
+    fn output_type(&self, mode: OutputMode) -> OutputType {
+        self.package.output_type(mode)
+    }
+
+    fn write_to<W: Writer>(
+        &self,
+        writer: W,
+        mode: OutputMode,
+        session: &Session,
+    ) -> anyhow::Result<()> {
+        self.package.write_to(writer, mode, session)
+    }
 }
 
 impl Emit for MasmComponent {
@@ -150,25 +183,19 @@ inventory::submit! {
 
 impl fmt::Display for MasmComponent {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        use crate::intrinsics::INTRINSICS_MODULE_NAMES;
-
+        let optimizations = if self.optimized {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        writeln!(f, "# masm optimizations: {optimizations}\n")?;
         for module in self.modules.iter() {
             // Skip printing the standard library modules and intrinsics modules to focus on the
             // user-defined modules and avoid the
             // stack overflow error when printing large programs
             // https://github.com/0xMiden/miden-formatting/issues/4
-            let module_name = module.path().as_str();
-            let module_name_trimmed = module_name.trim_start_matches("::");
-            if INTRINSICS_MODULE_NAMES.contains(&module_name) {
-                continue;
-            }
-            if module.is_in_namespace(Path::new("std"))
-                || module_name_trimmed.starts_with("miden::core")
-                || module_name_trimmed.starts_with("miden::protocol")
-            {
-                continue;
-            } else {
-                writeln!(f, "# mod {}\n", &module_name)?;
+            if Self::is_user_module(module) {
+                writeln!(f, "# mod {}\n", module.path().as_str())?;
                 writeln!(f, "{module}")?;
             }
         }
@@ -177,6 +204,27 @@ impl fmt::Display for MasmComponent {
 }
 
 impl MasmComponent {
+    /// Returns true if `module` is user-defined, i.e. it is neither an intrinsics module, nor a
+    /// module of the standard library or of the protocol library.
+    pub fn is_user_module(module: &masm::Module) -> bool {
+        use crate::intrinsics::INTRINSICS_MODULE_NAMES;
+
+        let module_name = module.path().as_str();
+        let module_name_trimmed = module_name.trim_start_matches("::");
+        !(INTRINSICS_MODULE_NAMES.contains(&module_name)
+            || module.is_in_namespace(Path::new("std"))
+            || module_name_trimmed.starts_with("miden::core")
+            || module_name_trimmed.starts_with("miden::protocol"))
+    }
+
+    /// The symbol name of the program entrypoint, if this component is executable.
+    pub fn entrypoint(&self) -> Option<&masm::InvocationTarget> {
+        match self.entrypoints.as_slice() {
+            [entrypoint] => Some(entrypoint),
+            _ => None,
+        }
+    }
+
     /// Assemble this component into a Miden package.
     pub fn assemble(
         &self,
@@ -186,6 +234,14 @@ impl MasmComponent {
         project_support::assemble(self, account_component_metadata_bytes, session)
     }
 
+    /// Split `package`, assembled from this component, into one package per entrypoint.
+    pub fn packages_per_entrypoint(
+        &self,
+        package: &Package,
+    ) -> Result<Vec<EntrypointPackage>, Report> {
+        project_support::packages_per_entrypoint(self, package)
+    }
+
     /// Assemble this component into a Miden package using a pre-populated package registry.
     pub fn assemble_with_registry(
         &self,
@@ -1,18 +1,19 @@
 //! Project-assembler support for compiler-generated MASM components.
 
-use alloc::{collections::BTreeMap, string::ToString, sync::Arc, vec::Vec};
+use alloc::{collections::BTreeMap, format, string::ToString, sync::Arc, vec::Vec};
 
 use miden_assembly::{
     Assembler, Library, Path, ProjectSourceInputs, ProjectTargetSelector,
     library::{LibraryExport, ProcedureExport},
 };
 use miden_mast_package::{PackageManifest, Section, SectionId};
+use midenc_hir::interner::Symbol;
 use midenc_session::{
     Session,
     diagnostics::{Report, Span},
 };
 
-use super::{MasmComponent, Package, Rodata};
+use super::{EntrypointPackage, MasmComponent, Package, Rodata};
 use crate::{intrinsics::INTRINSICS_MODULE_NAMES, masm};
 
 /// Assemble a MASM component through the VM project assembler.
@@ -49,8 +50,10 @@ pub(super) fn assemble_with_registry(
     }
     assembler.compile_and_statically_link_all(link_modules)?;
 
-    // Link libraries which are not direct dependencies of the package
+    // Link libraries which are not direct dependencies of the package, skipping any which resolve
+    // to a package we've already linked, e.g. an in-memory copy of a library also found on disk
     let project_package = session.project.package();
+    let mut linked = Vec::new();
     for link_lib in session.options.link_libraries.iter() {
         if !project_package
             .dependencies()
@@ -58,15 +61,22 @@ pub(super) fn assemble_with_registry(
             .any(|dep| dep.name().as_ref() == link_lib.name.as_ref())
         {
             let package = link_lib.load(&session.options)?;
+            let digest = package.digest();
+            if linked.contains(&digest) {
+                continue;
+            }
+            linked.push(digest);
             assembler.link_package(package, link_lib.linkage)?;
         }
     }
 
-    let is_executable_target = session.options.target_type.is_some_and(|tt| tt.is_executable())
-        || project_package.library_target().is_none()
-        || session.options.target.as_deref().is_some_and(|tname| {
-            project_package.executable_targets().iter().any(|t| tname == &**t.name)
-        });
+    // Multiple entrypoints can only be exported from a library
+    let is_executable_target = component.entrypoints.len() <= 1
+        && (session.options.target_type.is_some_and(|tt| tt.is_executable())
+            || project_package.library_target().is_none()
+            || session.options.target.as_deref().is_some_and(|tname| {
+                project_package.executable_targets().iter().any(|t| tname == &**t.name)
+            }));
     let sources = prepare_sources(
         component,
         &mut assembler,
@@ -92,9 +102,60 @@ pub(super) fn assemble_with_registry(
         extend_rodata_advice_map(package, &component.rodata);
         normalize_library_exports(package)?;
     }
+    if component.entrypoints.len() > 1 {
+        for entrypoint in component.entrypoints.iter() {
+            find_entrypoint_export(package.mast.as_ref(), entrypoint)?;
+        }
+    }
     Ok(package)
 }
 
+/// Split `package`, assembled from `component`, into one package per entrypoint, each of which
+/// exports only that entrypoint.
+pub(super) fn packages_per_entrypoint(
+    component: &MasmComponent,
+    package: &Package,
+) -> Result<Vec<EntrypointPackage>, Report> {
+    let dependencies = package.manifest.dependencies().cloned().collect::<Vec<_>>();
+    let mut packages = Vec::with_capacity(component.entrypoints.len());
+    for entrypoint in component.entrypoints.iter() {
+        let export = find_entrypoint_export(package.mast.as_ref(), entrypoint)?;
+        let path = export.path();
+        let name = format!("{}::{}", &package.name, path.last().unwrap_or_default());
+
+        let mut entrypoint_package = package.clone();
+        let exports = BTreeMap::from_iter([(path, export.clone())]);
+        entrypoint_package.mast =
+            Arc::new(Library::new(package.mast.mast_forest().clone(), exports)?);
+        entrypoint_package.manifest =
+            PackageManifest::from_library(entrypoint_package.mast.as_ref())
+                .with_dependencies(dependencies.clone())
+                .map_err(|error| Report::msg(error.to_string()))?;
+        packages.push(EntrypointPackage {
+            name: Symbol::intern(name),
+            package: Arc::new(entrypoint_package),
+        });
+    }
+    Ok(packages)
+}
+
+/// Find the export of `library` which corresponds to `entrypoint`
+fn find_entrypoint_export<'a>(
+    library: &'a Library,
+    entrypoint: &masm::InvocationTarget,
+) -> Result<&'a LibraryExport, Report> {
+    let masm::InvocationTarget::Path(path) = entrypoint else {
+        return Err(Report::msg(format!("invalid entrypoint '{entrypoint}'")));
+    };
+    let path = path.as_str().trim_start_matches("::");
+    library
+        .exports()
+        .find(|export| export.path().as_str().trim_start_matches("::") == path)
+        .ok_or_else(|| {
+            Report::msg(format!("entrypoint '{path}' is not exported from the assembled package"))
+        })
+}
+
 fn selected_executable_target_name<'a>(
     project_package: &'a midenc_session::miden_project::Package,
     session: &'a Session,
@@ -142,7 +203,7 @@ fn prepare_sources(
         support.push(Box::new(Arc::unwrap_or_clone(module.clone())));
     }
 
-    if generate_executable_main && let Some(entrypoint) = component.entrypoint.as_ref() {
+    if generate_executable_main && let Some(entrypoint) = component.entrypoint() {
         // Our generated main module takes precedence here, so move the root module into support
         support.extend(root);
         let root = component.generate_main(
@@ -0,0 +1,410 @@
+//! Static estimation of the VM cycles spent in each procedure of a [MasmComponent].
+//!
+//! The estimate for a procedure is computed by summing the cost of each instruction in its body,
+//! as given by [costs::instruction_cycles], along with the estimate of each procedure it invokes:
+//!
+//! * Only the costlier branch of an `if.true` is counted
+//! * The body of a `repeat.N` is counted `N` times
+//! * The body of a `while.true` is counted once, as the number of iterations is not known
+//!   statically. Such procedures, and the procedures invoking them, are flagged as _dynamic_, i.e.
+//!   their actual cost scales with the number of iterations.
+//! * Recursive invocations are not counted, and are flagged as dynamic as well
+//! * Invocations of procedures which are not part of the component, e.g. from the standard
+//!   library, only count the overhead of the invocation itself
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use midenc_hir::interner::Symbol;
+use midenc_session::{Emit, OutputMode, OutputType, Session, Writer};
+use serde::Serialize;
+
+use crate::{
+    MasmComponent,
+    masm::{self, Instruction as Inst, Op},
+};
+
+mod costs;
+
+pub use self::costs::instruction_cycles;
+
+/// A static estimate of the VM cycles spent in each procedure of a [MasmComponent], sorted in
+/// descending order of the total cycles spent in each procedure.
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleReport {
+    pub procedures: Vec<ProcedureCycles>,
+}
+
+/// The static estimate of the VM cycles spent in a single procedure
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcedureCycles {
+    /// The fully-qualified name of the procedure
+    pub name: String,
+    /// Whether this procedure is exported from its module
+    pub exported: bool,
+    /// The cycles spent in the body of this procedure, excluding the procedures it invokes
+    pub cycles: u64,
+    /// The cycles spent in this procedure, including the procedures it invokes
+    pub total_cycles: u64,
+    /// If true, the cycles spent in this procedure depend on a loop, or on recursion, so the
+    /// estimate only accounts for a single iteration.
+    pub dynamic: bool,
+    /// If true, this procedure invokes procedures which are not part of the component, whose
+    /// cycles are not included in the estimate.
+    pub unknown_callees: bool,
+}
+
+impl CycleReport {
+    /// Estimate the cycles spent in each procedure of the user-defined modules of `component`.
+    ///
+    /// Invocations are resolved across all modules of `component`, including the intrinsics and
+    /// standard library modules linked into it, but only user-defined procedures are reported.
+    pub fn new(component: &MasmComponent) -> Self {
+        let procedures = component.modules.iter().flat_map(|module| {
+            let reported = MasmComponent::is_user_module(module);
+            module.procedures().map(move |procedure| (module.path(), procedure, reported))
+        });
+        Self::from_procedures(procedures)
+    }
+
+    /// Estimate the cycles spent in each of `procedures`, given as the path of the module in which
+    /// the procedure is defined, the procedure itself, and whether to include it in the report.
+    fn from_procedures<'a>(
+        procedures: impl IntoIterator<Item = (&'a masm::LibraryPathRef, &'a masm::Procedure, bool)>,
+    ) -> Self {
+        let mut analysis = Analysis::default();
+        let mut reported = Vec::new();
+        for (module, procedure, report) in procedures {
+            let key = module.join(procedure.name()).to_absolute().to_string();
+            if report {
+                reported.push(key.clone());
+            }
+            analysis.procedures.insert(key, (module, procedure));
+        }
+
+        let mut procedures = reported
+            .into_iter()
+            .map(|name| {
+                let estimate = analysis.procedure(&name);
+                let exported = analysis.procedures[&name].1.visibility().is_public();
+                ProcedureCycles {
+                    name,
+                    exported,
+                    cycles: estimate.cycles,
+                    total_cycles: estimate.total_cycles,
+                    dynamic: estimate.dynamic,
+                    unknown_callees: estimate.unknown_callees,
+                }
+            })
+            .collect::<Vec<_>>();
+        procedures
+            .sort_by(|a, b| b.total_cycles.cmp(&a.total_cycles).then_with(|| a.name.cmp(&b.name)));
+
+        Self { procedures }
+    }
+
+    /// Get the estimate for the procedure whose fully-qualified name is `name`
+    pub fn get(&self, name: &str) -> Option<&ProcedureCycles> {
+        self.procedures.iter().find(|procedure| procedure.name == name)
+    }
+}
+
+impl Emit for CycleReport {
+    fn name(&self) -> Option<Symbol> {
+        None
+    }
+
+    fn output_type(&self, _mode: OutputMode) -> OutputType {
+        OutputType::CycleReport
+    }
+
+    /// The text form of the report is a table, while the binary form is JSON
+    fn write_to<W: Writer>(
+        &self,
+        mut writer: W,
+        mode: OutputMode,
+        _session: &Session,
+    ) -> anyhow::Result<()> {
+        match mode {
+            OutputMode::Text => writer.write_fmt(format_args!("{self}")),
+            OutputMode::Binary => {
+                let json = serde_json::to_string_pretty(self)?;
+                writer.write_all(json.as_bytes())
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for CycleReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "{:>12} {:>12}  procedure", "total", "self")?;
+        for procedure in self.procedures.iter() {
+            write!(
+                f,
+                "{:>12} {:>12}  {}",
+                procedure.total_cycles, procedure.cycles, procedure.name
+            )?;
+            if procedure.dynamic {
+                f.write_str(" × dynamic")?;
+            }
+            if procedure.unknown_callees {
+                f.write_str(" + external")?;
+            }
+            f.write_str("\n")?;
+        }
+        writeln!(
+            f,
+            "\n× dynamic: depends on a loop or recursion, only a single iteration is counted\n+ \
+             external: excludes the cycles of procedures outside of this component"
+        )
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+struct Estimate {
+    cycles: u64,
+    total_cycles: u64,
+    dynamic: bool,
+    unknown_callees: bool,
+}
+
+impl Estimate {
+    fn add(&mut self, other: Estimate) {
+        self.cycles = self.cycles.saturating_add(other.cycles);
+        self.total_cycles = self.total_cycles.saturating_add(other.total_cycles);
+        self.dynamic |= other.dynamic;
+        self.unknown_callees |= other.unknown_callees;
+    }
+
+    fn add_cycles(&mut self, cycles: u64) {
+        self.cycles = self.cycles.saturating_add(cycles);
+        self.total_cycles = self.total_cycles.saturating_add(cycles);
+    }
+}
+
+#[derive(Default)]
+struct Analysis<'a> {
+    /// All known procedures, keyed by fully-qualified name
+    procedures: BTreeMap<String, (&'a masm::LibraryPathRef, &'a masm::Procedure)>,
+    /// The estimates computed so far
+    estimates: BTreeMap<String, Estimate>,
+    /// The procedures whose estimate is currently being computed, used to detect recursion
+    visiting: BTreeSet<String>,
+}
+
+impl Analysis<'_> {
+    fn procedure(&mut self, name: &str) -> Estimate {
+        if let Some(estimate) = self.estimates.get(name) {
+            return *estimate;
+        }
+        let (module, procedure) = self.procedures[name];
+        self.visiting.insert(name.to_string());
+        let estimate = self.block(module, procedure.body());
+        self.visiting.remove(name);
+        self.estimates.insert(name.to_string(), estimate);
+        estimate
+    }
+
+    fn block(&mut self, module: &masm::LibraryPathRef, block: &masm::Block) -> Estimate {
+        let mut estimate = Estimate::default();
+        for op in block.iter() {
+            match op {
+                Op::Inst(inst) => {
+                    let inst = inst.inner();
+                    estimate.add_cycles(costs::instruction_cycles(inst));
+                    if let Inst::Exec(target) | Inst::Call(target) | Inst::SysCall(target) = inst {
+                        estimate.add(self.callee(module, target));
+                    } else if matches!(inst, Inst::DynExec | Inst::DynCall) {
+                        estimate.unknown_callees = true;
+                    }
+                }
+                Op::If {
+                    then_blk, else_blk, ..
+                } => {
+                    let then_blk = self.block(module, then_blk);
+                    let else_blk = self.block(module, else_blk);
+                    estimate.add_cycles(costs::IF_OVERHEAD);
+                    estimate.add(Estimate {
+                        cycles: then_blk.cycles.max(else_blk.cycles),
+                        total_cycles: then_blk.total_cycles.max(else_blk.total_cycles),
+                        dynamic: then_blk.dynamic || else_blk.dynamic,
+                        unknown_callees: then_blk.unknown_callees || else_blk.unknown_callees,
+                    });
+                }
+                Op::While { body, .. } => {
+                    let body = self.block(module, body);
+                    estimate.add_cycles(costs::WHILE_OVERHEAD);
+                    estimate.add(body);
+                    estimate.dynamic = true;
+                }
+                Op::Repeat { count, body, .. } => {
+                    let count = match count {
+                        masm::Immediate::Value(count) => u64::from(*count.inner()),
+                        // Constants have been resolved by the time a procedure is lowered
+                        masm::Immediate::Constant(_) => 1,
+                    };
+                    let body = self.block(module, body);
+                    estimate.add(Estimate {
+                        cycles: body.cycles.saturating_mul(count),
+                        total_cycles: body.total_cycles.saturating_mul(count),
+                        ..body
+                    });
+                }
+            }
+        }
+        estimate
+    }
+
+    /// Get the estimate of the procedure invoked by `target`, from a procedure in `module`.
+    ///
+    /// Only the cycles of the callee count towards the total cycles of the caller.
+    fn callee(
+        &mut self,
+        module: &masm::LibraryPathRef,
+        target: &masm::InvocationTarget,
+    ) -> Estimate {
+        let name = match target {
+            masm::InvocationTarget::Symbol(name) => {
+                module.join(name.as_str()).to_absolute().to_string()
+            }
+            masm::InvocationTarget::Path(path) => path.inner().to_absolute().to_string(),
+            masm::InvocationTarget::MastRoot(_) => {
+                return Estimate {
+                    unknown_callees: true,
+                    ..Default::default()
+                };
+            }
+        };
+        if self.visiting.contains(&name) {
+            return Estimate {
+                dynamic: true,
+                ..Default::default()
+            };
+        }
+        if !self.procedures.contains_key(&name) {
+            return Estimate {
+                unknown_callees: true,
+                ..Default::default()
+            };
+        }
+        let callee = self.procedure(&name);
+        Estimate {
+            cycles: 0,
+            total_cycles: callee.total_cycles,
+            dynamic: callee.dynamic,
+            unknown_callees: callee.unknown_callees,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::masm::{SourceSpan, Span};
+
+    fn block(ops: Vec<Op>) -> masm::Block {
+        masm::Block::new(SourceSpan::default(), ops)
+    }
+
+    fn inst(inst: Inst) -> Op {
+        Op::Inst(Span::unknown(inst))
+    }
+
+    fn exec(name: &str) -> Op {
+        let name = masm::Ident::new(name).unwrap();
+        inst(Inst::Exec(masm::InvocationTarget::Symbol(name)))
+    }
+
+    fn procedure(name: &str, visibility: masm::Visibility, body: Vec<Op>) -> masm::Procedure {
+        let name = masm::ProcedureName::new(name).unwrap();
+        masm::Procedure::new(SourceSpan::default(), visibility, name, 0, block(body))
+    }
+
+    fn report(procedures: &[masm::Procedure]) -> CycleReport {
+        let module = masm::LibraryPath::new("::test").unwrap();
+        CycleReport::from_procedures(
+            procedures.iter().map(|procedure| (module.as_path(), procedure, true)),
+        )
+    }
+
+    #[test]
+    fn cycle_report_sums_executed_procedures() {
+        let procedures = [
+            procedure("leaf", masm::Visibility::Private, vec![inst(Inst::Add), inst(Inst::U32Lt)]),
+            procedure(
+                "main",
+                masm::Visibility::Public,
+                vec![exec("leaf"), exec("leaf"), inst(Inst::Mul)],
+            ),
+        ];
+        let report = report(&procedures);
+
+        let leaf = report.get("::test::leaf").unwrap();
+        assert_eq!((leaf.cycles, leaf.total_cycles), (4, 4));
+        assert!(!leaf.exported);
+        let main = report.get("::test::main").unwrap();
+        assert_eq!((main.cycles, main.total_cycles), (1, 9));
+        assert!(main.exported);
+        assert!(!main.dynamic);
+
+        // Sorted by total cycles, descending
+        assert_eq!(report.procedures[0].name, "::test::main");
+    }
+
+    #[test]
+    fn cycle_report_multiplies_repeated_blocks() {
+        let procedures = [procedure(
+            "main",
+            masm::Visibility::Public,
+            vec![Op::Repeat {
+                span: SourceSpan::default(),
+                count: masm::Immediate::Value(Span::unknown(8)),
+                body: block(vec![inst(Inst::Add), inst(Inst::Sub)]),
+            }],
+        )];
+        let report = report(&procedures);
+
+        let main = report.get("::test::main").unwrap();
+        assert_eq!(main.total_cycles, 24);
+        assert!(!main.dynamic);
+    }
+
+    #[test]
+    fn cycle_report_flags_loops_as_dynamic() {
+        let procedures = [
+            procedure(
+                "hot_loop",
+                masm::Visibility::Private,
+                vec![Op::While {
+                    span: SourceSpan::default(),
+                    body: block(vec![inst(Inst::Add), inst(Inst::Eq)]),
+                }],
+            ),
+            procedure("caller", masm::Visibility::Public, vec![exec("hot_loop")]),
+            procedure("straight", masm::Visibility::Public, vec![inst(Inst::Add)]),
+        ];
+        let report = report(&procedures);
+
+        let hot_loop = report.get("::test::hot_loop").unwrap();
+        assert!(hot_loop.dynamic);
+        assert_eq!(hot_loop.total_cycles, costs::WHILE_OVERHEAD + 2);
+        assert!(report.get("::test::caller").unwrap().dynamic);
+        assert!(!report.get("::test::straight").unwrap().dynamic);
+        assert!(report.to_string().contains("::test::hot_loop × dynamic"), "{report}");
+    }
+
+    #[test]
+    fn cycle_report_terminates_on_recursion() {
+        let procedures = [
+            procedure("even", masm::Visibility::Public, vec![inst(Inst::Add), exec("odd")]),
+            procedure("odd", masm::Visibility::Private, vec![inst(Inst::Mul), exec("even")]),
+        ];
+        let report = report(&procedures);
+
+        let even = report.get("::test::even").unwrap();
+        assert_eq!(even.total_cycles, 2);
+        assert!(even.dynamic);
+    }
+}
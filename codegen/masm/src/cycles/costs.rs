@@ -0,0 +1,98 @@
+//! The estimated number of VM cycles taken by each Miden Assembly instruction.
+//!
+//! This table is the only place in which instruction costs are defined, and must be kept in sync
+//! with the instruction set documentation of the Miden VM as it evolves. Costs are approximate:
+//! where the cost of an instruction depends on its operands, the worst case is assumed.
+use crate::masm::{self, Instruction as Inst};
+
+/// The cycles spent entering and leaving a procedure invoked with `call` or `syscall`, i.e. the
+/// `CALL`/`SYSCALL` and `END` operations, in addition to the cycles of the callee itself.
+pub const CALL_OVERHEAD: u64 = 2;
+
+/// The cycles spent entering and leaving a procedure invoked with `dynexec` or `dyncall`, in
+/// addition to the cycles of the callee itself, which is not known statically.
+pub const DYNAMIC_CALL_OVERHEAD: u64 = 8;
+
+/// The cycles spent on an `if.true` block, i.e. the `SPLIT` and `END` operations, in addition to
+/// the cycles of the branch taken.
+pub const IF_OVERHEAD: u64 = 2;
+
+/// The cycles spent on a `while.true` loop, i.e. the `LOOP` and `END` operations, in addition to
+/// the cycles of its body.
+pub const WHILE_OVERHEAD: u64 = 2;
+
+/// Returns the estimated number of cycles taken by `inst`.
+///
+/// Invocations (e.g. `exec`) only account for the overhead of the invocation here, the cycles of
+/// the callee are accounted for by the caller of this function.
+pub fn instruction_cycles(inst: &Inst) -> u64 {
+    match inst {
+        // Decorators are not executed
+        Inst::Trace(_) | Inst::Debug(_) | Inst::DebugVar(_) => 0,
+        // Invocations
+        Inst::Exec(_) => 0,
+        Inst::Call(_) | Inst::SysCall(_) => CALL_OVERHEAD,
+        Inst::DynExec | Inst::DynCall => DYNAMIC_CALL_OVERHEAD,
+        Inst::ProcRef(_) => 4,
+        // Literals
+        Inst::Push(masm::Immediate::Value(value)) => match value.inner() {
+            masm::PushValue::Int(_) => 1,
+            masm::PushValue::Word(_) => 4,
+        },
+        Inst::PushFeltList(values) => values.len() as u64,
+        // Assertions
+        Inst::AssertzWithError(_) | Inst::AssertEqWithError(_) => 2,
+        Inst::AssertEqwWithError(_) => 11,
+        Inst::U32Assert | Inst::U32AssertWithError(_) => 3,
+        Inst::U32Assert2 => 1,
+        Inst::U32AssertW => 6,
+        // Field arithmetic and comparisons
+        Inst::Sub | Inst::SubImm(_) | Inst::Div | Inst::Neq | Inst::NeqImm(_) => 2,
+        Inst::IsOdd => 5,
+        Inst::Lt => 14,
+        Inst::Lte | Inst::Gt => 15,
+        Inst::Gte | Inst::Eqw | Inst::Pow2 => 16,
+        Inst::ILog2 => 44,
+        // The exponent is assumed to be a full 64 bits
+        Inst::Exp | Inst::ExpImm(_) => 74,
+        // u32 arithmetic
+        Inst::U32Cast => 2,
+        Inst::U32WrappingAdd | Inst::U32WrappingAddImm(_) => 2,
+        Inst::U32WrappingSub | Inst::U32WrappingSubImm(_) => 3,
+        Inst::U32WrappingMul | Inst::U32WrappingMulImm(_) => 2,
+        Inst::U32Div | Inst::U32DivImm(_) | Inst::U32Mod | Inst::U32ModImm(_) => 3,
+        Inst::U32DivMod | Inst::U32DivModImm(_) => 2,
+        // u32 comparisons
+        Inst::U32Lt => 3,
+        Inst::U32Gt | Inst::U32Gte => 4,
+        Inst::U32Lte => 5,
+        Inst::U32Min => 8,
+        Inst::U32Max => 9,
+        // u32 bitwise operations
+        Inst::Xor => 7,
+        Inst::U32Or => 6,
+        Inst::U32Not => 5,
+        Inst::U32ShlImm(_) | Inst::U32ShrImm(_) | Inst::U32RotlImm(_) | Inst::U32RotrImm(_) => 3,
+        Inst::U32Shl | Inst::U32Shr | Inst::U32Rotl => 18,
+        Inst::U32Rotr => 22,
+        Inst::U32Popcnt | Inst::U32Cto => 33,
+        Inst::U32Ctz => 34,
+        Inst::U32Clo => 41,
+        Inst::U32Clz => 42,
+        // Stack manipulation
+        Inst::CDrop => 2,
+        Inst::Reversew => 3,
+        Inst::DropW | Inst::PadW | Inst::DupW0 | Inst::DupW1 | Inst::DupW2 | Inst::DupW3 => 4,
+        Inst::CDropW => 5,
+        // Memory and locals
+        Inst::MemLoadImm(_) | Inst::MemStore | Inst::Locaddr(_) => 2,
+        Inst::MemStoreImm(_) | Inst::LocLoad(_) => 3,
+        Inst::LocStore(_) => 4,
+        // Cryptographic operations
+        Inst::MTreeGet => 9,
+        Inst::HMerge | Inst::MTreeMerge => 16,
+        Inst::Hash => 20,
+        Inst::MTreeSet => 29,
+        _ => 1,
+    }
+}
@@ -4,9 +4,30 @@ use midenc_hir::{
     dialects::builtin::attributes::LocalVariable,
 };
 
-use super::{OpEmitter, masm};
+use super::{OpEmitter, dup_from_offset, masm};
 use crate::{OperandStack, lower::NativePtr};
 
+/// The maximum number of words copied by `memcpy`, or written by `memset`, for which the copy is
+/// unrolled when the number of words is known statically
+pub const MAX_UNROLLED_WORDS: u32 = 4;
+
+/// The unit in which the `count` operand of a word copy is expressed
+#[derive(Debug, Copy, Clone)]
+enum WordCopyCount {
+    /// `count` is a number of bytes, which must be a multiple of the word size
+    Bytes,
+    /// `count` is a number of values, each of which is the given number of words in size
+    Values(u32),
+}
+
+/// Returns the number of words occupied by `count` values of `value_size` bytes, if it is
+/// non-zero, and small enough for the copy to be unrolled.
+fn unrolled_word_count(count: u32, value_size: u32) -> Option<u32> {
+    let size = count.checked_mul(value_size)?;
+    let words = size / 16;
+    (size.is_multiple_of(16) && (1..=MAX_UNROLLED_WORDS).contains(&words)).then_some(words)
+}
+
 /// Allocation
 impl OpEmitter<'_> {
     /// Emit the loop header for a counted `while.true` loop.
@@ -719,7 +740,14 @@ impl OpEmitter<'_> {
     }
 
     /// Write `count` copies of `value` to `dst`
-    pub fn memset(&mut self, span: SourceSpan) {
+    ///
+    /// If `count` is known to be a constant, `constant_count` is its value, which is used to
+    /// select a more efficient lowering where possible.
+    ///
+    /// Byte fills of a byte-addressable destination are performed a word at a time, when the
+    /// destination is word-aligned, and `count` is a multiple of the word size. This is checked at
+    /// runtime, falling back to the generic loop otherwise.
+    pub fn memset(&mut self, constant_count: Option<u32>, span: SourceSpan) {
         let dst = self.stack.pop().expect("operand stack is empty");
         let count = self.stack.pop().expect("operand stack is empty");
         let value = self.stack.pop().expect("operand stack is empty");
@@ -728,7 +756,139 @@ impl OpEmitter<'_> {
         assert!(dst.ty().is_pointer());
         assert_eq!(&ty, dst.ty().pointee().unwrap(), "expected value and pointee type to match");
         let value_size = u32::try_from(ty.size_in_bytes()).expect("invalid value size");
+        let is_byte_pointer = match dst.ty() {
+            Type::Ptr(ptr_ty) => ptr_ty.is_byte_pointer(),
+            _ => unreachable!("memset expects a pointer operand"),
+        };
+
+        if ty == Type::U8 && is_byte_pointer {
+            let unrolled_words = constant_count.and_then(|count| unrolled_word_count(count, 1));
+            // Stack: [dst, count, value]
+            //
+            // A constant `count` is already known to be a multiple of the word size
+            if unrolled_words.is_some() {
+                self.emit_all_aligned(&[0], 16, span);
+            } else {
+                self.emit_all_aligned(&[0, 1], 16, span);
+            }
+            let then_blk = self.build_masm_block(span, |then_emitter| {
+                then_emitter.emit_memset_words(unrolled_words, span);
+            });
+            let else_blk = self.build_masm_block(span, |else_emitter| {
+                else_emitter.emit_memset_fallback_loop(
+                    dst.clone(),
+                    count.clone(),
+                    value.clone(),
+                    value_size,
+                    span,
+                );
+            });
+            self.current_block.push(masm::Op::If {
+                span,
+                then_blk,
+                else_blk,
+            });
+            return;
+        }
+
+        self.emit_memset_fallback_loop(dst, count, value, value_size, span);
+    }
+
+    /// Fill memory with a byte value a word at a time.
+    ///
+    /// Stack transition: `[dst, count, value] -> []`, where `dst` is a word-aligned byte pointer,
+    /// and `count` is a multiple of the word size, in bytes. If `unrolled_words` is given, it is
+    /// the number of words to write, and the writes are unrolled.
+    fn emit_memset_words(&mut self, unrolled_words: Option<u32>, span: SourceSpan) {
+        // Convert `dst` to an element address, and replicate the byte value to fill a word
+        self.emit_word_aligned_element_addr_from_byte_ptr(span);
+        self.emit_all(
+            [
+                // [value, dst, count]
+                masm::Instruction::MovUp2,
+                // Each element holds 4 bytes
+                masm::Instruction::U32WrappingMulImm(0x01010101.into()),
+                masm::Instruction::Dup0,
+                masm::Instruction::Dup0,
+                masm::Instruction::Dup0,
+                // [word, dst, count]
+            ],
+            span,
+        );
+
+        if let Some(words) = unrolled_words {
+            for i in 0..words {
+                self.emit(masm::Instruction::Dup4, span);
+                if i > 0 {
+                    self.emit(
+                        masm::Instruction::AddImm(Felt::new_unchecked(4 * i as u64).into()),
+                        span,
+                    );
+                }
+                self.emit(masm::Instruction::MemStoreWLe, span);
+            }
+            self.emit_all(
+                [masm::Instruction::DropW, masm::Instruction::Drop, masm::Instruction::Drop],
+                span,
+            );
+            return;
+        }
+
+        // Convert `count` to a number of words
+        self.emit_all(
+            [
+                masm::Instruction::MovUp5,
+                masm::Instruction::U32DivModImm(16.into()),
+                Self::assertz_with_message_inst(
+                    "memset word-fill fast path expected the byte count to be divisible by 16",
+                    span,
+                ),
+                // [word, n, dst]
+                masm::Instruction::MovDn4,
+                // Loop header - loop until all `n` words have been written
+                masm::Instruction::Dup4,
+                masm::Instruction::NeqImm(Felt::ZERO.into()),
+            ],
+            span,
+        );
+        let body = self.build_masm_block(span, |body_emitter| {
+            body_emitter.emit_all(
+                [
+                    // Write the next word
+                    masm::Instruction::Dup5,
+                    masm::Instruction::MemStoreWLe,
+                    // Advance `dst` to the next word
+                    masm::Instruction::MovUp5,
+                    masm::Instruction::AddImm(Felt::new_unchecked(4).into()),
+                    masm::Instruction::MovDn5,
+                    // Decrement `n`, and continue while it is non-zero
+                    masm::Instruction::MovUp4,
+                    masm::Instruction::SubImm(Felt::new_unchecked(1).into()),
+                    masm::Instruction::Dup0,
+                    masm::Instruction::MovDn5,
+                    masm::Instruction::NeqImm(Felt::ZERO.into()),
+                ],
+                span,
+            );
+        });
+        self.current_block.push(masm::Op::While { span, body });
 
+        // Cleanup - drop the word, `n`, and `dst`
+        self.emit_all(
+            [masm::Instruction::DropW, masm::Instruction::Drop, masm::Instruction::Drop],
+            span,
+        );
+    }
+
+    /// Emit the default memset loop, which writes one value per iteration.
+    fn emit_memset_fallback_loop(
+        &mut self,
+        dst: crate::Operand,
+        count: crate::Operand,
+        value: crate::Operand,
+        value_size: u32,
+        span: SourceSpan,
+    ) {
         // Create new block for loop body and switch to it temporarily
         let mut body = Vec::default();
         let mut body_emitter = OpEmitter::new(self.invoked, &mut body, self.stack);
@@ -805,7 +965,12 @@ impl OpEmitter<'_> {
     ///   pointer type
     /// * optimized word-copy fast paths are only used for byte-addressable pointers; native
     ///   pointers fall back to the generic loop
-    pub fn memcpy(&mut self, span: SourceSpan) {
+    /// * word copies are used without checking alignment only if the alignment of the pointee
+    ///   type guarantees that both pointers are word-aligned, otherwise alignment is checked at
+    ///   runtime, falling back to the generic loop if either pointer is unaligned
+    /// * if `count` is a constant, given by `constant_count`, copies of up to
+    ///   [MAX_UNROLLED_WORDS] words are unrolled
+    pub fn memcpy(&mut self, constant_count: Option<u32>, span: SourceSpan) {
         let src = self.stack.pop().expect("operand stack is empty");
         let dst = self.stack.pop().expect("operand stack is empty");
         let count = self.stack.pop().expect("operand stack is empty");
@@ -823,82 +988,32 @@ impl OpEmitter<'_> {
         // Use optimized intrinsics when available
         match value_size {
             // Byte copies (Wasm `memory.copy`) can often be performed more efficiently by copying
-            // whole felt elements when the source, destination, and length are all
-            // element-aligned.
-            1 => {
-                // Compute: use_elements = (src % 4 == 0) && (dst % 4 == 0) && (count % 4 == 0)
-                //
+            // whole words, or failing that, whole felt elements, when the source, destination, and
+            // length are all suitably aligned.
+            1 if is_byte_pointer => {
+                let unrolled_words = constant_count.and_then(|count| unrolled_word_count(count, 1));
                 // Stack: [src, dst, count]
-                self.emit_all(
-                    [
-                        // src % 4 == 0
-                        masm::Instruction::Dup0,
-                        masm::Instruction::U32DivModImm(4.into()),
-                        masm::Instruction::Swap1,
-                        masm::Instruction::Drop,
-                        masm::Instruction::EqImm(Felt::ZERO.into()),
-                        // dst % 4 == 0
-                        masm::Instruction::Dup2,
-                        masm::Instruction::U32DivModImm(4.into()),
-                        masm::Instruction::Swap1,
-                        masm::Instruction::Drop,
-                        masm::Instruction::EqImm(Felt::ZERO.into()),
-                        masm::Instruction::And,
-                        // count % 4 == 0
-                        masm::Instruction::Dup3,
-                        masm::Instruction::U32DivModImm(4.into()),
-                        masm::Instruction::Swap1,
-                        masm::Instruction::Drop,
-                        masm::Instruction::EqImm(Felt::ZERO.into()),
-                        masm::Instruction::And,
-                    ],
-                    span,
-                );
+                //
+                // A constant `count` is already known to be a multiple of the word size
+                if unrolled_words.is_some() {
+                    self.emit_all_aligned(&[0, 1], 16, span);
+                } else {
+                    self.emit_all_aligned(&[0, 1, 2], 16, span);
+                }
 
-                // then: convert byte addresses/count to element units and delegate to core
-                let then_blk = self.build_masm_block(span, |then_emitter| {
-                    then_emitter.emit_all(
-                        [
-                            // Convert `src` to element address
-                            masm::Instruction::U32DivModImm(4.into()),
-                            Self::assertz_with_message_inst(
-                                "memcpy byte-copy fast path expected the source pointer to be \
-                                 4-byte aligned",
-                                span,
-                            ),
-                            // Convert `dst` to an element address
-                            masm::Instruction::Swap1,
-                            masm::Instruction::U32DivModImm(4.into()),
-                            Self::assertz_with_message_inst(
-                                "memcpy byte-copy fast path expected the destination pointer to \
-                                 be 4-byte aligned",
-                                span,
-                            ),
-                            // Bring `count` to top to convert to element count
-                            masm::Instruction::Swap2,
-                            masm::Instruction::U32DivModImm(4.into()),
-                            Self::assertz_with_message_inst(
-                                "memcpy byte-copy fast path expected the byte count to be \
-                                 divisible by 4",
-                                span,
-                            ),
-                        ],
-                        span,
-                    );
-                    then_emitter.raw_exec("::miden::core::mem::memcopy_elements", span);
+                let then_blk = self.build_masm_block(span, |then_emitter| match unrolled_words {
+                    Some(words) => then_emitter.emit_memcpy_words_unrolled(words, span),
+                    None => then_emitter.emit_memcpy_words(WordCopyCount::Bytes, span),
                 });
-
                 let else_blk = self.build_masm_block(span, |else_emitter| {
-                    else_emitter.emit_memcpy_fallback_loop(
+                    else_emitter.emit_memcpy_bytes(
                         src.clone(),
                         dst.clone(),
                         count.clone(),
                         value_ty.clone(),
-                        value_size,
                         span,
                     );
                 });
-
                 self.current_block.push(masm::Op::If {
                     span,
                     then_blk,
@@ -906,43 +1021,42 @@ impl OpEmitter<'_> {
                 });
                 return;
             }
-            // Word-sized values have an optimized intrinsic we can lean on
-            16 if is_byte_pointer => {
-                // Convert `src` to a word-aligned element address.
-                self.emit_word_aligned_element_addr_from_byte_ptr(span);
-                // Convert `dst` to an element address the same way.
-                self.emit(masm::Instruction::Swap1, span);
-                self.emit_word_aligned_element_addr_from_byte_ptr(span);
-                // Swap with `count` to get us into the correct ordering: [count, src, dst].
-                self.emit(masm::Instruction::Swap2, span);
-                self.raw_exec("::miden::core::mem::memcopy_words", span);
+            1 => {
+                self.emit_memcpy_bytes(src, dst, count, value_ty, span);
                 return;
             }
-            // Values which can be broken up into word-sized chunks can piggy-back on the
-            // intrinsic for word-sized values, but we have to compute a new `count` by
-            // multiplying `count` by the number of words in each value
-            size if is_byte_pointer && size > 16 && size.is_multiple_of(16) => {
-                let factor = size / 16;
-                // Convert `src` to a word-aligned element address.
-                self.emit_word_aligned_element_addr_from_byte_ptr(span);
-                // Convert `dst` to an element address the same way.
-                self.emit(masm::Instruction::Swap1, span);
-                self.emit_word_aligned_element_addr_from_byte_ptr(span);
-                self.emit_all(
-                    [
-                        // Swap with `count` to get us into the correct ordering: [count, src, dst]
-                        masm::Instruction::Swap2,
-                        // Compute the corrected count
-                        masm::Instruction::U32WideningMulImm(factor.into()),
-                        masm::Instruction::Swap1,
-                        Self::assertz_with_message_inst(
-                            "memcpy word-copy fast path element count overflowed",
-                            span,
-                        ), // [count * (size / 16), src, dst]
-                    ],
+            // Values which are a whole number of words in size can be copied a word at a time
+            size if is_byte_pointer && size.is_multiple_of(16) => {
+                let words_per_value = size / 16;
+                let unrolled_words =
+                    constant_count.and_then(|count| unrolled_word_count(count, size));
+
+                // The alignment of the pointee type guarantees that both pointers are word-aligned
+                if value_ty.min_alignment() >= 16 {
+                    self.emit_memcpy_word_aligned(unrolled_words, words_per_value, span);
+                    return;
+                }
+
+                // Stack: [src, dst, count]
+                self.emit_all_aligned(&[0, 1], 16, span);
+                let then_blk = self.build_masm_block(span, |then_emitter| {
+                    then_emitter.emit_memcpy_word_aligned(unrolled_words, words_per_value, span);
+                });
+                let else_blk = self.build_masm_block(span, |else_emitter| {
+                    else_emitter.emit_memcpy_fallback_loop(
+                        src.clone(),
+                        dst.clone(),
+                        count.clone(),
+                        value_ty.clone(),
+                        value_size,
+                        span,
+                    );
+                });
+                self.current_block.push(masm::Op::If {
                     span,
-                );
-                self.raw_exec("::miden::core::mem::memcopy_words", span);
+                    then_blk,
+                    else_blk,
+                });
                 return;
             }
             // For now, all other values fallback to the default implementation
@@ -952,6 +1066,171 @@ impl OpEmitter<'_> {
         self.emit_memcpy_fallback_loop(src, dst, count, value_ty, value_size, span);
     }
 
+    /// Push a boolean which is true if each of the operands at `offsets` from the top of the
+    /// stack is a multiple of `align`.
+    fn emit_all_aligned(&mut self, offsets: &[usize], align: u32, span: SourceSpan) {
+        for (i, offset) in offsets.iter().copied().enumerate() {
+            // Once the first condition has been pushed, the operands are one element deeper
+            let offset = if i == 0 { offset } else { offset + 1 };
+            self.emit_all(
+                [
+                    dup_from_offset(offset),
+                    masm::Instruction::U32DivModImm(align.into()),
+                    masm::Instruction::Swap1,
+                    masm::Instruction::Drop,
+                    masm::Instruction::EqImm(Felt::ZERO.into()),
+                ],
+                span,
+            );
+            if i > 0 {
+                self.emit(masm::Instruction::And, span);
+            }
+        }
+    }
+
+    /// Copy bytes, a felt element at a time if the source, destination, and length are all
+    /// element-aligned, or a byte at a time otherwise.
+    fn emit_memcpy_bytes(
+        &mut self,
+        src: crate::Operand,
+        dst: crate::Operand,
+        count: crate::Operand,
+        value_ty: Type,
+        span: SourceSpan,
+    ) {
+        // Stack: [src, dst, count]
+        self.emit_all_aligned(&[0, 1, 2], 4, span);
+
+        // then: convert byte addresses/count to element units and delegate to core
+        let then_blk = self.build_masm_block(span, |then_emitter| {
+            then_emitter.emit_all(
+                [
+                    // Convert `src` to element address
+                    masm::Instruction::U32DivModImm(4.into()),
+                    Self::assertz_with_message_inst(
+                        "memcpy byte-copy fast path expected the source pointer to be 4-byte \
+                         aligned",
+                        span,
+                    ),
+                    // Convert `dst` to an element address
+                    masm::Instruction::Swap1,
+                    masm::Instruction::U32DivModImm(4.into()),
+                    Self::assertz_with_message_inst(
+                        "memcpy byte-copy fast path expected the destination pointer to be 4-byte \
+                         aligned",
+                        span,
+                    ),
+                    // Bring `count` to top to convert to element count
+                    masm::Instruction::Swap2,
+                    masm::Instruction::U32DivModImm(4.into()),
+                    Self::assertz_with_message_inst(
+                        "memcpy byte-copy fast path expected the byte count to be divisible by 4",
+                        span,
+                    ),
+                ],
+                span,
+            );
+            then_emitter.raw_exec("::miden::core::mem::memcopy_elements", span);
+        });
+
+        let else_blk = self.build_masm_block(span, |else_emitter| {
+            else_emitter.emit_memcpy_fallback_loop(src, dst, count, value_ty, 1, span);
+        });
+
+        self.current_block.push(masm::Op::If {
+            span,
+            then_blk,
+            else_blk,
+        });
+    }
+
+    /// Copy values which are `words_per_value` words in size, a word at a time.
+    ///
+    /// Stack transition: `[src, dst, count] -> []`, where `src` and `dst` are word-aligned byte
+    /// pointers. If `unrolled_words` is given, it is the number of words to copy, and the copy is
+    /// unrolled.
+    fn emit_memcpy_word_aligned(
+        &mut self,
+        unrolled_words: Option<u32>,
+        words_per_value: u32,
+        span: SourceSpan,
+    ) {
+        match unrolled_words {
+            Some(words) => self.emit_memcpy_words_unrolled(words, span),
+            None => self.emit_memcpy_words(WordCopyCount::Values(words_per_value), span),
+        }
+    }
+
+    /// Copy whole words using the `memcopy_words` intrinsic.
+    ///
+    /// Stack transition: `[src, dst, count] -> []`, where `src` and `dst` are word-aligned byte
+    /// pointers, and `count` is interpreted according to `unit`.
+    fn emit_memcpy_words(&mut self, unit: WordCopyCount, span: SourceSpan) {
+        // Convert `src` to a word-aligned element address.
+        self.emit_word_aligned_element_addr_from_byte_ptr(span);
+        // Convert `dst` to an element address the same way.
+        self.emit(masm::Instruction::Swap1, span);
+        self.emit_word_aligned_element_addr_from_byte_ptr(span);
+        // Swap with `count` to get us into the correct ordering: [count, src, dst].
+        self.emit(masm::Instruction::Swap2, span);
+        // Convert `count` to a number of words
+        match unit {
+            WordCopyCount::Bytes => self.emit_all(
+                [
+                    masm::Instruction::U32DivModImm(16.into()),
+                    Self::assertz_with_message_inst(
+                        "memcpy word-copy fast path expected the byte count to be divisible by 16",
+                        span,
+                    ),
+                ],
+                span,
+            ),
+            WordCopyCount::Values(1) => (),
+            WordCopyCount::Values(factor) => self.emit_all(
+                [
+                    masm::Instruction::U32WideningMulImm(factor.into()),
+                    masm::Instruction::Swap1,
+                    Self::assertz_with_message_inst(
+                        "memcpy word-copy fast path element count overflowed",
+                        span,
+                    ), // [count * factor, src, dst]
+                ],
+                span,
+            ),
+        }
+        self.raw_exec("::miden::core::mem::memcopy_words", span);
+    }
+
+    /// Copy `words` whole words, with one load and store per word.
+    ///
+    /// Stack transition: `[src, dst, count] -> []`, where `src` and `dst` are word-aligned byte
+    /// pointers. The `count` operand is ignored, as the number of words to copy is known.
+    fn emit_memcpy_words_unrolled(&mut self, words: u32, span: SourceSpan) {
+        // Convert `src` and `dst` to word-aligned element addresses: [dst, src, count]
+        self.emit_word_aligned_element_addr_from_byte_ptr(span);
+        self.emit(masm::Instruction::Swap1, span);
+        self.emit_word_aligned_element_addr_from_byte_ptr(span);
+        for i in 0..words {
+            let offset = masm::Instruction::AddImm(Felt::new_unchecked(4 * i as u64).into());
+            // Load the next word from `src`
+            self.emit_all([masm::Instruction::PadW, masm::Instruction::Dup5], span);
+            if i > 0 {
+                self.emit(offset.clone(), span);
+            }
+            self.emit(masm::Instruction::MemLoadWLe, span);
+            // Store it to `dst`
+            self.emit(masm::Instruction::Dup4, span);
+            if i > 0 {
+                self.emit(offset, span);
+            }
+            self.emit_all([masm::Instruction::MemStoreWLe, masm::Instruction::DropW], span);
+        }
+        self.emit_all(
+            [masm::Instruction::Drop, masm::Instruction::Drop, masm::Instruction::Drop],
+            span,
+        );
+    }
+
     /// Emit the default memcpy loop for types which do not have a specialized intrinsic.
     fn emit_memcpy_fallback_loop(
         &mut self,
@@ -1342,4 +1621,150 @@ mod tests {
         assert!(emitter.stack().iter().all(|ty| *ty == Type::Felt));
         assert_eq!(&block[0], &Op::Inst(masm::Span::new(span, masm::Instruction::MemStream)));
     }
+
+    /// Estimate the cycles spent executing `ops`, assuming each loop runs for `iterations`, and
+    /// the costlier branch of each conditional is taken.
+    fn estimate_cycles<'a>(ops: impl IntoIterator<Item = &'a Op>, iterations: u64) -> u64 {
+        ops.into_iter()
+            .map(|op| match op {
+                Op::Inst(inst) => crate::cycles::instruction_cycles(inst.inner()),
+                Op::If {
+                    then_blk, else_blk, ..
+                } => estimate_cycles(then_blk.iter(), iterations)
+                    .max(estimate_cycles(else_blk.iter(), iterations)),
+                Op::While { body, .. } | Op::Repeat { body, .. } => {
+                    estimate_cycles(body.iter(), iterations) * iterations
+                }
+            })
+            .sum()
+    }
+
+    fn contains_loop<'a>(ops: impl IntoIterator<Item = &'a Op>) -> bool {
+        ops.into_iter().any(|op| match op {
+            Op::Inst(_) => false,
+            Op::If {
+                then_blk, else_blk, ..
+            } => contains_loop(then_blk.iter()) || contains_loop(else_blk.iter()),
+            Op::While { .. } | Op::Repeat { .. } => true,
+        })
+    }
+
+    fn count_inst<'a>(ops: impl IntoIterator<Item = &'a Op>, inst: &masm::Instruction) -> usize {
+        ops.into_iter()
+            .map(|op| match op {
+                Op::Inst(i) => usize::from(i.inner() == inst),
+                Op::If {
+                    then_blk, else_blk, ..
+                } => count_inst(then_blk.iter(), inst) + count_inst(else_blk.iter(), inst),
+                Op::While { body, .. } | Op::Repeat { body, .. } => count_inst(body.iter(), inst),
+            })
+            .sum()
+    }
+
+    /// Emit a `memcpy` of `count` values of type `pointee`, returning the emitted code
+    fn emit_memcpy(pointee: Type, count: Option<u32>) -> Vec<Op> {
+        let mut block = Vec::default();
+        let context = Rc::new(Context::default());
+        let mut stack = OperandStack::new(context);
+        let mut invoked = BTreeSet::default();
+        let mut emitter = OpEmitter::new(&mut invoked, &mut block, &mut stack);
+        let ptr = Type::from(PointerType::new(pointee));
+        emitter.push(Type::U32);
+        emitter.push(ptr.clone());
+        emitter.push(ptr);
+
+        emitter.memcpy(count, SourceSpan::default());
+        assert_eq!(emitter.stack_len(), 0);
+        block
+    }
+
+    /// Returns the branches of the runtime alignment check emitted for a word copy
+    fn split_on_alignment(block: &[Op]) -> (&masm::Block, &masm::Block) {
+        match block.last() {
+            Some(Op::If {
+                then_blk, else_blk, ..
+            }) => (then_blk, else_blk),
+            _ => panic!("expected the word copy to be guarded by an alignment check"),
+        }
+    }
+
+    #[test]
+    fn memcpy_unrolls_small_constant_word_copies() {
+        // 64 bytes, i.e. 4 words, can be copied without a loop if both pointers are word-aligned
+        let block = emit_memcpy(Type::U8, Some(64));
+        let (word_copy, byte_copy) = split_on_alignment(&block);
+        assert!(!contains_loop(word_copy.iter()));
+        assert_eq!(count_inst(word_copy.iter(), &masm::Instruction::MemLoadWLe), 4);
+        assert_eq!(count_inst(word_copy.iter(), &masm::Instruction::MemStoreWLe), 4);
+        assert!(contains_loop(byte_copy.iter()));
+
+        // Larger copies use the word copy intrinsic
+        let block = emit_memcpy(Type::U8, Some(80));
+        let (word_copy, _) = split_on_alignment(&block);
+        assert_eq!(count_inst(word_copy.iter(), &masm::Instruction::MemLoadWLe), 0);
+        assert!(invoked_intrinsic(word_copy, "memcopy_words"));
+    }
+
+    #[test]
+    fn memcpy_word_copies_are_cheaper_than_the_fallback() {
+        let block = emit_memcpy(Type::U8, Some(64));
+        let (word_copy, byte_copy) = split_on_alignment(&block);
+        // The byte loop runs once per byte
+        let word_cycles = estimate_cycles(word_copy.iter(), 64);
+        let byte_cycles = estimate_cycles(byte_copy.iter(), 64);
+        assert!(
+            word_cycles * 10 < byte_cycles,
+            "expected the unrolled word copy ({word_cycles} cycles) to be much cheaper than the \
+             byte copy ({byte_cycles} cycles)"
+        );
+    }
+
+    #[test]
+    fn memcpy_of_word_sized_values_checks_alignment_at_runtime() {
+        // Without proof that `u128` values are word-aligned, the alignment is checked at runtime
+        let u128_alignment = Type::U128.min_alignment();
+        let block = emit_memcpy(Type::U128, Some(2));
+        if u128_alignment >= 16 {
+            assert!(!block.iter().any(|op| matches!(op, Op::If { .. })));
+            assert!(!contains_loop(block.iter()));
+        } else {
+            let (word_copy, fallback) = split_on_alignment(&block);
+            assert!(!contains_loop(word_copy.iter()));
+            assert!(contains_loop(fallback.iter()));
+        }
+        assert_eq!(count_inst(block.iter(), &masm::Instruction::MemStoreWLe), 2);
+    }
+
+    fn invoked_intrinsic(block: &masm::Block, name: &str) -> bool {
+        block.iter().any(|op| match op {
+            Op::Inst(inst) => match inst.inner() {
+                masm::Instruction::Exec(target) => target.to_string().ends_with(name),
+                _ => false,
+            },
+            _ => false,
+        })
+    }
+
+    #[test]
+    fn memset_fills_word_aligned_bytes_a_word_at_a_time() {
+        for (count, unrolled) in [(Some(32), true), (None, false)] {
+            let mut block = Vec::default();
+            let context = Rc::new(Context::default());
+            let mut stack = OperandStack::new(context);
+            let mut invoked = BTreeSet::default();
+            let mut emitter = OpEmitter::new(&mut invoked, &mut block, &mut stack);
+            emitter.push(Type::U8);
+            emitter.push(Type::U32);
+            emitter.push(Type::from(PointerType::new(Type::U8)));
+
+            emitter.memset(count, SourceSpan::default());
+            assert_eq!(emitter.stack_len(), 0);
+
+            let (word_fill, byte_fill) = split_on_alignment(&block);
+            assert_eq!(contains_loop(word_fill.iter()), !unrolled);
+            let stores = count_inst(word_fill.iter(), &masm::Instruction::MemStoreWLe);
+            assert_eq!(stores, if unrolled { 2 } else { 1 });
+            assert!(contains_loop(byte_fill.iter()));
+        }
+    }
 }
@@ -90,6 +90,7 @@ pub mod int32;
 pub mod int64;
 pub mod mem;
 pub mod merkle;
+pub mod overflow;
 pub mod primop;
 pub mod smallint;
 pub mod unary;
@@ -104,6 +105,7 @@ use super::{Operand, OperandStack};
 use crate::{
     TraceEvent,
     masm::{self as masm, Op},
+    stack::{MAX_ADDRESSABLE_OFFSET, spills_required},
 };
 
 /// This structure is used to emit the Miden Assembly ops corresponding to an IR instruction.
@@ -395,10 +397,12 @@ impl<'a> OpEmitter<'a> {
     }
 
     /// Duplicate an item on the stack to the top
+    ///
+    /// If any element of the operand is deeper than [MAX_ADDRESSABLE_OFFSET], the elements above
+    /// it are temporarily spilled to procedure locals, see [Self::raw_dup].
     #[inline]
     #[track_caller]
     pub fn dup(&mut self, i: u8, span: SourceSpan) {
-        assert_valid_stack_index!(i);
         let index = i as usize;
         let i = self.stack.effective_index(index);
         self.stack.dup(index);
@@ -407,15 +411,17 @@ impl<'a> OpEmitter<'a> {
         let n = last.size();
         let offset = n - 1;
         for _ in 0..n {
-            self.emit(dup_from_offset(i + offset), span);
+            self.raw_dup(i + offset, span);
         }
     }
 
     /// Move an item on the stack to the top
+    ///
+    /// If any element of the operand is deeper than [MAX_ADDRESSABLE_OFFSET], the elements above
+    /// it are temporarily spilled to procedure locals, see [Self::raw_movup].
     #[inline]
     #[track_caller]
     pub fn movup(&mut self, i: u8, span: SourceSpan) {
-        assert_valid_stack_index!(i);
         let index = i as usize;
         let i = self.stack.effective_index(index);
         self.stack.movup(index);
@@ -424,15 +430,17 @@ impl<'a> OpEmitter<'a> {
         let n = moved.size();
         let offset = n - 1;
         for _ in 0..n {
-            self.emit(movup_from_offset(i + offset), span);
+            self.raw_movup(i + offset, span);
         }
     }
 
     /// Move an item from the top of the stack to the `n`th position
+    ///
+    /// If the destination is deeper than [MAX_ADDRESSABLE_OFFSET], the elements above it are
+    /// temporarily spilled to procedure locals, see [Self::raw_movdn].
     #[inline]
     #[track_caller]
     pub fn movdn(&mut self, i: u8, span: SourceSpan) {
-        assert_valid_stack_index!(i);
         let index = i as usize;
         let i = self.stack.effective_index_inclusive(index);
         let top = self.stack.peek().expect("operand stack is empty");
@@ -440,44 +448,51 @@ impl<'a> OpEmitter<'a> {
         self.stack.movdn(index);
         // Emit low-level instructions corresponding to the operand we moved
         for _ in 0..top_size {
-            self.emit(movdn_from_offset(i), span);
+            self.raw_movdn(i, span);
         }
     }
 
     /// Swap an item with the top of the stack
+    ///
+    /// If any element of either operand is deeper than [MAX_ADDRESSABLE_OFFSET], the elements
+    /// above it are temporarily spilled to procedure locals.
     #[inline]
     #[track_caller]
     pub fn swap(&mut self, i: u8, span: SourceSpan) {
         assert!(i > 0, "swap requires a non-zero index");
-        assert_valid_stack_index!(i);
         let index = i as usize;
         let src = self.stack[0].size();
         let dst = self.stack[index].size();
         let i = self.stack.effective_index(index);
         self.stack.swap(index);
         match (src, dst) {
-            (1, 1) => {
+            (1, 1) if spills_required(i) == 0 => {
                 self.emit(swap_from_offset(i), span);
             }
+            (1, 1) => {
+                // `swap.N` is equivalent to `movdn.N`, followed by `movup.(N - 1)`
+                self.raw_movdn(i, span);
+                self.raw_movup(i - 1, span);
+            }
             (1, n) if i == 1 => {
                 // We can simply move the top element below the `dst` operand
-                self.emit(movdn_from_offset(i + (n - 1)), span);
+                self.raw_movdn(i + (n - 1), span);
             }
             (n, 1) if i == n => {
                 // We can simply move the `dst` element to the top
-                self.emit(movup_from_offset(i), span);
+                self.raw_movup(i, span);
             }
             (n, m) if i == n => {
                 // We can simply move `dst` down
                 for _ in 0..n {
-                    self.emit(movdn_from_offset(i + (m - 1)), span);
+                    self.raw_movdn(i + (m - 1), span);
                 }
             }
             (n, m) => {
                 assert!(i >= n);
                 let offset = m - 1;
                 for _ in 0..n {
-                    self.emit(movdn_from_offset(i + offset), span);
+                    self.raw_movdn(i + offset, span);
                 }
                 let i = (i as i8 + (m as i8 - n as i8)) as u8 as usize;
                 match i - 1 {
@@ -487,7 +502,7 @@ impl<'a> OpEmitter<'a> {
                     }
                     i => {
                         for _ in 0..m {
-                            self.emit(movup_from_offset(i), span);
+                            self.raw_movup(i, span);
                         }
                     }
                 }
@@ -495,6 +510,80 @@ impl<'a> OpEmitter<'a> {
         }
     }
 
+    /// Emit `dup.N` for the element at `offset` on the VM's operand stack.
+    ///
+    /// If `offset` is deeper than [MAX_ADDRESSABLE_OFFSET], the elements above it are spilled to
+    /// procedure locals, so that the element is duplicated with `dup.15`. The copy is then
+    /// spilled as well, so that it ends up above the spilled elements once they are reloaded.
+    fn raw_dup(&mut self, offset: usize, span: SourceSpan) {
+        let n = spills_required(offset);
+        if n == 0 {
+            self.emit(dup_from_offset(offset), span);
+            return;
+        }
+
+        let slot = self.stack.spill_slots().reserve(n + 1);
+        self.spill_to_locals(slot, n, span);
+        self.emit(dup_from_offset(MAX_ADDRESSABLE_OFFSET), span);
+        self.emit(masm::Instruction::LocStore((slot + n as u16).into()), span);
+        self.reload_from_locals(slot, n, span);
+        self.emit(masm::Instruction::LocLoad((slot + n as u16).into()), span);
+    }
+
+    /// Emit `movup.N` for the element at `offset` on the VM's operand stack.
+    ///
+    /// If `offset` is deeper than [MAX_ADDRESSABLE_OFFSET], this works like [Self::raw_dup], but
+    /// the element is moved with `movup.15` rather than copied.
+    fn raw_movup(&mut self, offset: usize, span: SourceSpan) {
+        let n = spills_required(offset);
+        if n == 0 {
+            self.emit(movup_from_offset(offset), span);
+            return;
+        }
+
+        let slot = self.stack.spill_slots().reserve(n + 1);
+        self.spill_to_locals(slot, n, span);
+        self.emit(movup_from_offset(MAX_ADDRESSABLE_OFFSET), span);
+        self.emit(masm::Instruction::LocStore((slot + n as u16).into()), span);
+        self.reload_from_locals(slot, n, span);
+        self.emit(masm::Instruction::LocLoad((slot + n as u16).into()), span);
+    }
+
+    /// Emit `movdn.N`, moving the element on top of the VM's operand stack to `offset`.
+    ///
+    /// If `offset` is deeper than [MAX_ADDRESSABLE_OFFSET], the element on top is spilled to a
+    /// procedure local, followed by the elements which must remain above it, beyond the first
+    /// 15. The element is then reloaded and moved down with `movdn.15`, and the remaining spilled
+    /// elements are reloaded on top of it.
+    fn raw_movdn(&mut self, offset: usize, span: SourceSpan) {
+        let n = spills_required(offset);
+        if n == 0 {
+            self.emit(movdn_from_offset(offset), span);
+            return;
+        }
+
+        let slot = self.stack.spill_slots().reserve(n + 1);
+        self.emit(masm::Instruction::LocStore((slot + n as u16).into()), span);
+        self.spill_to_locals(slot, n, span);
+        self.emit(masm::Instruction::LocLoad((slot + n as u16).into()), span);
+        self.emit(movdn_from_offset(MAX_ADDRESSABLE_OFFSET), span);
+        self.reload_from_locals(slot, n, span);
+    }
+
+    /// Store the top `n` elements of the VM's operand stack to the spill slots starting at `slot`
+    fn spill_to_locals(&mut self, slot: u16, n: usize, span: SourceSpan) {
+        for i in 0..(n as u16) {
+            self.emit(masm::Instruction::LocStore((slot + i).into()), span);
+        }
+    }
+
+    /// Reload `n` elements spilled by [Self::spill_to_locals], in their original order
+    fn reload_from_locals(&mut self, slot: u16, n: usize, span: SourceSpan) {
+        for i in (0..(n as u16)).rev() {
+            self.emit(masm::Instruction::LocLoad((slot + i).into()), span);
+        }
+    }
+
     /// Drop the top operand on the stack
     #[inline]
     #[track_caller]
@@ -725,7 +814,6 @@ pub fn swap_from_offset(offset: usize) -> masm::Instruction {
     }
 }
 
-#[allow(unused)]
 pub fn swapw_from_offset(offset: usize) -> masm::Instruction {
     match offset {
         1 => masm::Instruction::SwapW1,
@@ -775,7 +863,6 @@ pub fn movdn_from_offset(offset: usize) -> masm::Instruction {
     }
 }
 
-#[allow(unused)]
 pub fn movupw_from_offset(offset: usize) -> masm::Instruction {
     match offset {
         2 => masm::Instruction::MovUpW2,
@@ -784,7 +871,6 @@ pub fn movupw_from_offset(offset: usize) -> masm::Instruction {
     }
 }
 
-#[allow(unused)]
 pub fn movdnw_from_offset(offset: usize) -> masm::Instruction {
     match offset {
         2 => masm::Instruction::MovDnW2,
@@ -1020,6 +1106,91 @@ mod tests {
         assert_eq!(emitter.stack()[4], one);
     }
 
+    /// Apply the stack manipulation instructions in `ops` to `stack`, a model of the VM's operand
+    /// stack with the top at index 0, using `locals` as the procedure locals.
+    fn simulate_stack_manipulation(
+        ops: &[Op],
+        stack: &mut Vec<u32>,
+        locals: &mut alloc::collections::BTreeMap<u16, u32>,
+    ) {
+        for op in ops {
+            let Op::Inst(inst) = op else {
+                panic!("unexpected op: {op:?}");
+            };
+            match inst.inner() {
+                masm::Instruction::LocStore(masm::Immediate::Value(local)) => {
+                    locals.insert(*local.inner(), stack.remove(0));
+                }
+                masm::Instruction::LocLoad(masm::Immediate::Value(local)) => {
+                    stack.insert(0, locals[local.inner()]);
+                }
+                inst => {
+                    let offset = |f: fn(usize) -> masm::Instruction, start: usize| {
+                        (start..=MAX_ADDRESSABLE_OFFSET).find(|offset| f(*offset) == *inst)
+                    };
+                    if let Some(offset) = offset(dup_from_offset, 0) {
+                        stack.insert(0, stack[offset]);
+                    } else if let Some(offset) = offset(swap_from_offset, 1) {
+                        stack.swap(0, offset);
+                    } else if let Some(offset) = offset(movup_from_offset, 2) {
+                        let elem = stack.remove(offset);
+                        stack.insert(0, elem);
+                    } else if let Some(offset) = offset(movdn_from_offset, 2) {
+                        let elem = stack.remove(0);
+                        stack.insert(offset, elem);
+                    } else {
+                        panic!("unexpected instruction: {inst:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn op_emitter_spills_to_reach_deep_operands_test() {
+        let mut block = Vec::default();
+
+        let context = Rc::new(Context::default());
+        let mut stack = OperandStack::new(context.clone()).with_spill_slots_at(2);
+        let mut invoked = BTreeSet::default();
+        let mut emitter = OpEmitter::new(&mut invoked, &mut block, &mut stack);
+
+        // A stack of 20 operands: [19, 18, .., 0]
+        let span = SourceSpan::default();
+        for i in 0..20u32 {
+            emitter.literal(Immediate::U32(i), span);
+        }
+        let num_pushes = emitter.current_block().len();
+
+        emitter.movup(18, span);
+        emitter.dup(19, span);
+        emitter.movdn(20, span);
+        emitter.swap(17, span);
+        emitter.swap(2, span);
+
+        // Every element beyond the addressable window was reached by spilling to locals
+        let ops = emitter.current_block()[num_pushes..].to_vec();
+        assert!(
+            ops.iter().any(|op| matches!(op, Op::Inst(inst) if matches!(inst.inner(), masm::Instruction::LocStore(_)))),
+            "expected operands to be spilled: {ops:?}"
+        );
+
+        // Executing the emitted code leaves the VM's operand stack in the same state as the
+        // emitter's model of it
+        let mut vm_stack = (0..20u32).rev().collect::<Vec<_>>();
+        let mut locals = Default::default();
+        simulate_stack_manipulation(&ops, &mut vm_stack, &mut locals);
+        assert_eq!(vm_stack.len(), emitter.stack_len());
+        for (index, value) in vm_stack.into_iter().enumerate() {
+            assert_eq!(emitter.stack()[index], Immediate::U32(value), "at index {index}");
+        }
+
+        // Only the spill slots reserved for this function were used
+        assert!(locals.keys().all(|local| *local >= 2));
+        let spill_slots = emitter.stack().spill_slots().clone();
+        assert_eq!(spill_slots.used() as usize, locals.len());
+    }
+
     #[test]
     fn op_emitter_copy_operand_to_position_test() {
         let mut block = Vec::default();
@@ -0,0 +1,296 @@
+//! The convention used to pass arguments which do not fit on the operand stack.
+//!
+//! A procedure can only be passed as many arguments on the operand stack as can be addressed by
+//! its body, i.e. [MAX_STACK_ARGUMENT_FELTS] field elements. When the flattened parameters of a
+//! function exceed this, its leading parameters are still passed on the operand stack, but the
+//! remaining _overflow_ arguments are passed in memory:
+//!
+//! * The caller stores the overflow arguments to its own procedure locals, and passes the address
+//!   of the first of them as an extra argument, immediately following the arguments passed on the
+//!   operand stack.
+//! * The callee begins with a prologue which loads the overflow arguments from that address,
+//!   after which the operand stack is laid out as if all arguments had been passed on it.
+//!
+//! Parameters are never split between the operand stack and memory. Since the convention only
+//! depends on the signature of the callee, the caller and callee always agree on it. It is only
+//! used with `exec`, as procedure locals are not accessible from another memory context; the
+//! canonical ABI already passes such arguments indirectly when crossing a context boundary.
+use midenc_hir::{Felt, SourceSpan, dialects::builtin::attributes::Signature};
+
+use super::{OpEmitter, dup_from_offset, movdn_from_offset, movup_from_offset};
+use crate::masm;
+
+/// The maximum number of field elements of arguments which may be passed on the operand stack
+pub const MAX_STACK_ARGUMENT_FELTS: usize = 16;
+
+/// Describes how the arguments of a function are split between the operand stack and memory
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OverflowArguments {
+    /// The number of field elements of arguments passed on the operand stack, not including the
+    /// address of the overflow arguments
+    pub stack_felts: usize,
+    /// The number of field elements of arguments passed in memory
+    pub overflow_felts: usize,
+}
+
+impl OverflowArguments {
+    /// Determine how the arguments of a function with `signature` are passed, returning `None` if
+    /// all of them are passed on the operand stack.
+    pub fn for_signature(signature: &Signature) -> Option<Self> {
+        let total_felts = signature.params.iter().map(|param| param.ty.size_in_felts()).sum();
+        if total_felts <= MAX_STACK_ARGUMENT_FELTS {
+            return None;
+        }
+
+        // Pass as many leading parameters as possible on the operand stack, while leaving room
+        // for the address of the overflow arguments
+        let mut stack_felts = 0;
+        for param in signature.params.iter() {
+            let size = param.ty.size_in_felts();
+            if stack_felts + size >= MAX_STACK_ARGUMENT_FELTS {
+                break;
+            }
+            stack_felts += size;
+        }
+
+        Some(Self {
+            stack_felts,
+            overflow_felts: total_felts - stack_felts,
+        })
+    }
+}
+
+impl OpEmitter<'_> {
+    /// Store the overflow arguments of a call to procedure locals, and replace them on the
+    /// operand stack with their address.
+    ///
+    /// This expects the arguments of the call to be on top of the VM's operand stack, and must be
+    /// emitted immediately before the call. The operand stack model is not updated, as the
+    /// arguments are consumed by the call.
+    pub fn store_overflow_arguments(&mut self, overflow: OverflowArguments, span: SourceSpan) {
+        let OverflowArguments {
+            stack_felts,
+            overflow_felts,
+        } = overflow;
+        // The overflow arguments only need to live until the callee has loaded them in its
+        // prologue, so the same procedure locals used for spills can be used to hold them
+        let slot = self.stack.spill_slots().reserve(overflow_felts);
+        for i in 0..overflow_felts {
+            // Each argument moves up to the first position below the stack arguments, once the
+            // one before it has been stored
+            self.move_up_from(stack_felts, span);
+            self.emit(masm::Instruction::LocStore((slot + i as u16).into()), span);
+        }
+        self.emit(masm::Instruction::Locaddr(slot.into()), span);
+        self.move_down_to(stack_felts, span);
+    }
+
+    /// Load the overflow arguments of the current function from memory, so that the VM's
+    /// operand stack is laid out as if all of its arguments were passed on it.
+    ///
+    /// This must be emitted at the start of the function, before any other code.
+    pub fn load_overflow_arguments(&mut self, overflow: OverflowArguments, span: SourceSpan) {
+        let OverflowArguments {
+            stack_felts,
+            overflow_felts,
+        } = overflow;
+        // The address of the overflow arguments follows the arguments passed on the stack. Each
+        // argument is loaded from the last to the first, and moved below the address, so that they
+        // end up in order.
+        for i in (0..overflow_felts).rev() {
+            self.emit(dup_from_offset(stack_felts), span);
+            if i > 0 {
+                self.emit(masm::Instruction::AddImm(Felt::new_unchecked(i as u64).into()), span);
+            }
+            self.emit(masm::Instruction::MemLoad, span);
+            if stack_felts == 0 {
+                self.emit(masm::Instruction::Swap1, span);
+            } else {
+                self.raw_movdn(stack_felts + 1, span);
+            }
+        }
+        // Drop the address
+        self.move_up_from(stack_felts, span);
+        self.emit(masm::Instruction::Drop, span);
+    }
+
+    /// Move the element at `offset`, which is at most 15, to the top of the operand stack.
+    fn move_up_from(&mut self, offset: usize, span: SourceSpan) {
+        match offset {
+            0 => (),
+            1 => self.emit(masm::Instruction::Swap1, span),
+            offset => self.emit(movup_from_offset(offset), span),
+        }
+    }
+
+    /// Move the element on top of the operand stack to `offset`, which is at most 15.
+    fn move_down_to(&mut self, offset: usize, span: SourceSpan) {
+        match offset {
+            0 => (),
+            1 => self.emit(masm::Instruction::Swap1, span),
+            offset => self.emit(movdn_from_offset(offset), span),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeSet, rc::Rc};
+
+    use midenc_hir::{ArrayType, Context, Type};
+
+    use super::*;
+    use crate::{OperandStack, masm::Op};
+
+    fn signature(context: &Rc<Context>, params: Vec<Type>) -> Signature {
+        Signature::new(context, params, [])
+    }
+
+    #[test]
+    fn overflow_arguments_are_not_used_when_arguments_fit_on_the_stack() {
+        let context = Rc::new(Context::default());
+        let felts = signature(&context, vec![Type::Felt; 16]);
+        assert_eq!(OverflowArguments::for_signature(&felts), None);
+        let u64s = signature(&context, vec![Type::U64; 8]);
+        assert_eq!(OverflowArguments::for_signature(&u64s), None);
+    }
+
+    #[test]
+    fn overflow_arguments_do_not_split_parameters() {
+        let context = Rc::new(Context::default());
+        let felts = signature(&context, vec![Type::Felt; 20]);
+        assert_eq!(
+            OverflowArguments::for_signature(&felts),
+            Some(OverflowArguments {
+                stack_felts: 15,
+                overflow_felts: 5,
+            })
+        );
+        // The fourth 4-felt parameter would leave no room for the address of the others
+        let u128s = signature(&context, vec![Type::U128; 8]);
+        assert_eq!(
+            OverflowArguments::for_signature(&u128s),
+            Some(OverflowArguments {
+                stack_felts: 12,
+                overflow_felts: 20,
+            })
+        );
+    }
+
+    /// Emit the instructions which store `overflow` in the caller, or load it in the callee
+    fn emitted(overflow: OverflowArguments, load: bool) -> (Vec<masm::Instruction>, usize) {
+        let context = Rc::new(Context::default());
+        let mut block = Vec::default();
+        let mut stack = OperandStack::new(context).with_spill_slots_at(2);
+        let mut invoked = BTreeSet::default();
+        let mut emitter = OpEmitter::new(&mut invoked, &mut block, &mut stack);
+        if load {
+            emitter.load_overflow_arguments(overflow, SourceSpan::default());
+        } else {
+            emitter.store_overflow_arguments(overflow, SourceSpan::default());
+        }
+
+        let insts = block
+            .iter()
+            .map(|op| match op {
+                Op::Inst(inst) => inst.inner().clone(),
+                _ => panic!("unexpected control flow"),
+            })
+            .collect::<Vec<_>>();
+        (insts, stack.spill_slots().used())
+    }
+
+    #[test]
+    fn overflow_arguments_are_stored_to_locals_by_the_caller() {
+        let overflow = OverflowArguments {
+            stack_felts: 15,
+            overflow_felts: 5,
+        };
+        let (insts, used) = emitted(overflow, false);
+
+        let mut expected = Vec::new();
+        for i in 0..5u16 {
+            expected.push(movup_from_offset(15));
+            expected.push(masm::Instruction::LocStore((2 + i).into()));
+        }
+        expected.push(masm::Instruction::Locaddr(2u16.into()));
+        expected.push(movdn_from_offset(15));
+        assert_eq!(insts, expected);
+        assert_eq!(used, 5);
+    }
+
+    #[test]
+    fn overflow_arguments_with_few_stack_arguments() {
+        let context = Rc::new(Context::default());
+        // A first parameter of 16 felts leaves no argument on the stack
+        let words =
+            signature(&context, vec![Type::from(ArrayType::new(Type::Felt, 16)), Type::Felt]);
+        let none_on_stack = OverflowArguments::for_signature(&words).unwrap();
+        assert_eq!(none_on_stack.stack_felts, 0);
+        // A felt followed by a parameter of 15 felts leaves one argument on the stack
+        let felt_first =
+            signature(&context, vec![Type::Felt, Type::from(ArrayType::new(Type::Felt, 15))]);
+        let one_on_stack = OverflowArguments::for_signature(&felt_first).unwrap();
+        assert_eq!(one_on_stack.stack_felts, 1);
+
+        let overflow = OverflowArguments {
+            stack_felts: 0,
+            overflow_felts: 2,
+        };
+        let (stored, _) = emitted(overflow, false);
+        assert_eq!(
+            stored,
+            vec![
+                masm::Instruction::LocStore(2u16.into()),
+                masm::Instruction::LocStore(3u16.into()),
+                masm::Instruction::Locaddr(2u16.into()),
+            ]
+        );
+        let (loaded, _) = emitted(overflow, true);
+        assert_eq!(
+            loaded,
+            vec![
+                masm::Instruction::Dup0,
+                masm::Instruction::AddImm(Felt::new_unchecked(1).into()),
+                masm::Instruction::MemLoad,
+                masm::Instruction::Swap1,
+                masm::Instruction::Dup0,
+                masm::Instruction::MemLoad,
+                masm::Instruction::Swap1,
+                masm::Instruction::Drop,
+            ]
+        );
+
+        let overflow = OverflowArguments {
+            stack_felts: 1,
+            overflow_felts: 2,
+        };
+        let (stored, _) = emitted(overflow, false);
+        assert_eq!(
+            stored,
+            vec![
+                masm::Instruction::Swap1,
+                masm::Instruction::LocStore(2u16.into()),
+                masm::Instruction::Swap1,
+                masm::Instruction::LocStore(3u16.into()),
+                masm::Instruction::Locaddr(2u16.into()),
+                masm::Instruction::Swap1,
+            ]
+        );
+        let (loaded, _) = emitted(overflow, true);
+        assert_eq!(
+            loaded,
+            vec![
+                masm::Instruction::Dup1,
+                masm::Instruction::AddImm(Felt::new_unchecked(1).into()),
+                masm::Instruction::MemLoad,
+                masm::Instruction::MovDn2,
+                masm::Instruction::Dup1,
+                masm::Instruction::MemLoad,
+                masm::Instruction::MovDn2,
+                masm::Instruction::Swap1,
+                masm::Instruction::Drop,
+            ]
+        );
+    }
+}
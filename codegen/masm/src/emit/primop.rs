@@ -2,10 +2,11 @@ use miden_assembly_syntax::parser::WordValue;
 use midenc_dialect_hir::assertions;
 use midenc_hir::{
     ArrayType, Felt, Immediate, SourceSpan, Type,
+    demangle::Demangled,
     dialects::builtin::attributes::{ArgumentExtension, Signature},
 };
 
-use super::{OpEmitter, int64, masm};
+use super::{OpEmitter, int64, masm, overflow::OverflowArguments};
 use crate::TraceEvent;
 
 impl OpEmitter<'_> {
@@ -317,6 +318,11 @@ impl OpEmitter<'_> {
         signature: &Signature,
         span: SourceSpan,
     ) {
+        // Arguments which do not fit on the operand stack are passed in memory, see
+        // [super::overflow]
+        if let Some(overflow) = OverflowArguments::for_signature(signature) {
+            self.store_overflow_arguments(overflow, span);
+        }
         self.process_call_signature(&callee, signature, span);
 
         self.emit(masm::Instruction::Trace(TraceEvent::FrameStart.as_u32().into()), span);
@@ -335,6 +341,12 @@ impl OpEmitter<'_> {
         signature: &Signature,
         span: SourceSpan,
     ) {
+        assert!(
+            OverflowArguments::for_signature(signature).is_none(),
+            "invalid call to {}: arguments which do not fit on the operand stack cannot be passed \
+             to another memory context",
+            Demangled(&callee)
+        );
         self.process_call_signature(&callee, signature, span);
 
         self.emit(masm::Instruction::Trace(TraceEvent::FrameStart.as_u32().into()), span);
@@ -351,6 +363,12 @@ impl OpEmitter<'_> {
         signature: &Signature,
         span: SourceSpan,
     ) {
+        assert!(
+            OverflowArguments::for_signature(signature).is_none(),
+            "invalid syscall to {}: arguments which do not fit on the operand stack cannot be \
+             passed to another memory context",
+            Demangled(&callee)
+        );
         self.process_call_signature(&callee, signature, span);
 
         self.emit(masm::Instruction::Trace(TraceEvent::FrameStart.as_u32().into()), span);
@@ -360,12 +378,40 @@ impl OpEmitter<'_> {
         self.emit(masm::Instruction::Nop, span);
     }
 
+    /// Splice `body`, the parsed template of an inline assembly op, into the current block.
+    ///
+    /// The arguments are expected on top of the operand stack, with the first argument on top, and
+    /// `body` is expected to replace them with the results described by `signature`.
+    pub fn inline_asm(&mut self, body: &masm::Block, signature: &Signature) {
+        assert!(
+            OverflowArguments::for_signature(signature).is_none(),
+            "invalid inline assembly: arguments which do not fit on the operand stack cannot be \
+             passed to inline assembly"
+        );
+        for (i, param) in signature.params().iter().enumerate() {
+            let arg = self.stack.pop().expect("operand stack is empty");
+            assert_eq!(
+                arg.ty(),
+                param.ty,
+                "invalid inline assembly: invalid argument type for parameter at index {i}"
+            );
+        }
+
+        self.current_block.extend(body.iter().cloned());
+
+        for result in signature.results().iter().rev() {
+            self.push(result.ty.clone());
+        }
+    }
+
     fn process_call_signature(
         &mut self,
         callee: &masm::InvocationTarget,
         signature: &Signature,
         span: SourceSpan,
     ) {
+        // The callee is only displayed if the signature is invalid
+        let callee = Demangled(callee);
         for i in 0..signature.arity() {
             let param = &signature.params[i];
             let arg = self.stack.pop().expect("operand stack is empty");
@@ -5,6 +5,7 @@
 extern crate alloc;
 
 mod artifact;
+mod cycles;
 mod data_segments;
 mod emit;
 mod emitter;
@@ -15,6 +16,7 @@ mod linker;
 mod lower;
 mod opt;
 mod stack;
+mod verify;
 
 pub mod masm {
     pub use miden_assembly_syntax::{
@@ -39,9 +41,11 @@ use midenc_hir::{
 
 pub(crate) use self::lower::HirLowering;
 pub use self::{
-    artifact::{MasmComponent, Rodata},
+    artifact::{EntrypointPackage, MasmComponent, Rodata},
+    cycles::{CycleReport, ProcedureCycles},
     events::{TRACE_FRAME_END, TRACE_FRAME_START, TRACE_PRINT_LN, TraceEvent},
     legalization::{LegalizeForMasm, masm_legalization_target, populate_masm_legalization_target},
+    linker::MergedProcedure,
     lower::{NativePtr, ToMasmComponent},
     stack::{Constraint, Operand, OperandStack},
 };
@@ -171,6 +175,7 @@ fn lower_hir_ops(info: &mut midenc_hir::DialectInfo) {
     info.register_operation_trait::<hir::ExecFpi, dyn HirLowering>();
     info.register_operation_trait::<hir::Call, dyn HirLowering>();
     info.register_operation_trait::<hir::Syscall, dyn HirLowering>();
+    info.register_operation_trait::<hir::InlineAsm, dyn HirLowering>();
     info.register_operation_trait::<hir::Store, dyn HirLowering>();
     info.register_operation_trait::<hir::StoreLocal, dyn HirLowering>();
     info.register_operation_trait::<hir::Load, dyn HirLowering>();
@@ -1,8 +1,16 @@
+mod dedup;
+mod rename;
+
 use midenc_hir::{
     Alignable, FxHashMap, Symbol,
     dialects::builtin::{self, DataSegmentError, SegmentRef},
 };
 
+pub use self::{
+    dedup::{LoweredProcedure, MergedProcedure, dedup_procedures},
+    rename::demangle_private_procedures,
+};
+
 const DEFAULT_PAGE_SIZE: u32 = 2u32.pow(16);
 /// Currently, Wasm modules produced by rustc reserve 16 pages for the Rust stack
 /// (see __stack_pointer global variable value in Wasm).
@@ -0,0 +1,336 @@
+//! Deduplication of identical procedures.
+//!
+//! Monomorphization frequently produces functions which are identical once lowered to Miden
+//! Assembly, e.g. the same `core::ptr` helper instantiated for several types. Each of these would
+//! otherwise become a distinct procedure in the assembled component.
+//!
+//! Procedures are deduplicated per module, before they are defined, by merging procedures with
+//! identical bodies into a single canonical procedure, and redirecting all references to the
+//! merged procedures to the canonical one. Exported procedures keep their names: rather than being
+//! removed, their body is replaced with one which forwards to the canonical procedure.
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+
+use midenc_hir::{FxHashMap, demangle::Demangled, formatter::PrettyPrint};
+
+use crate::masm::{self, Instruction as Inst, Op, SourceSpan, Span, Spanned};
+
+/// A procedure which has been lowered to Miden Assembly, but not yet defined in its module
+pub struct LoweredProcedure {
+    pub span: SourceSpan,
+    pub name: masm::ProcedureName,
+    /// The documentation attached to this procedure, i.e. the demangled name of the function it
+    /// was lowered from, if that differs from [Self::name]
+    pub docs: Option<String>,
+    /// The target by which other procedures invoke this one
+    pub target: masm::InvocationTarget,
+    pub visibility: masm::Visibility,
+    pub signature: masm::FunctionType,
+    pub attributes: Vec<masm::Attribute>,
+    pub num_locals: u16,
+    pub body: masm::Block,
+    pub invoked: BTreeSet<masm::Invoke>,
+}
+
+impl LoweredProcedure {
+    /// Convert this into a [masm::Procedure] which can be defined in a module
+    pub fn into_procedure(self) -> masm::Procedure {
+        let docs = self.docs.map(|docs| Span::new(self.span, docs));
+        let mut procedure =
+            masm::Procedure::new(self.span, self.visibility, self.name, self.num_locals, self.body)
+                .with_docs(docs);
+        procedure.set_signature(self.signature);
+        for attribute in self.attributes {
+            procedure.attributes_mut().insert(attribute);
+        }
+        procedure.extend_invoked(self.invoked);
+        procedure
+    }
+
+    /// Returns true if this procedure may be merged with, or into, an identical procedure
+    fn is_mergeable(&self) -> bool {
+        // Attributes such as `auth_script` mark a specific procedure, so it must remain distinct
+        self.attributes.is_empty()
+    }
+}
+
+/// A record of a procedure which was merged into an identical procedure
+#[derive(Debug, Clone)]
+pub struct MergedProcedure {
+    /// The procedure which was merged
+    pub merged: masm::InvocationTarget,
+    /// The procedure whose body was kept
+    pub canonical: masm::InvocationTarget,
+    /// If true, `merged` is exported, and was kept as a procedure which forwards to `canonical`,
+    /// otherwise it was removed.
+    pub forwarded: bool,
+}
+
+/// Merge the identical procedures in `procedures`, returning a record of each procedure merged.
+///
+/// Merging is repeated until no more procedures are found to be identical, as redirecting
+/// references to merged procedures can make the procedures which invoke them identical as well.
+///
+/// The result only depends on the order of `procedures`, so deduplication is deterministic.
+pub fn dedup_procedures(procedures: &mut Vec<LoweredProcedure>) -> Vec<MergedProcedure> {
+    let mut merged = Vec::new();
+    // Procedures which were removed, or replaced with a forwarder, are no longer candidates
+    let mut retired = vec![false; procedures.len()];
+    let mut removed = vec![false; procedures.len()];
+    loop {
+        let mut groups = FxHashMap::<String, Vec<usize>>::default();
+        let mut fingerprints = Vec::new();
+        for (index, procedure) in procedures.iter().enumerate() {
+            if retired[index] || !procedure.is_mergeable() {
+                continue;
+            }
+            let fingerprint = fingerprint(procedure);
+            let group = groups.entry(fingerprint.clone()).or_default();
+            if group.is_empty() {
+                fingerprints.push(fingerprint);
+            }
+            group.push(index);
+        }
+
+        let mut redirects = BTreeMap::<masm::InvocationTarget, masm::InvocationTarget>::new();
+        for fingerprint in fingerprints {
+            let group = &groups[&fingerprint];
+            if group.len() < 2 {
+                continue;
+            }
+
+            // Prefer an exported procedure as the canonical one, as it cannot be removed
+            let canonical = group
+                .iter()
+                .copied()
+                .find(|index| procedures[*index].visibility.is_public())
+                .unwrap_or(group[0]);
+            let canonical_target = procedures[canonical].target.clone();
+            for index in group.iter().copied().filter(|index| *index != canonical) {
+                let procedure = &mut procedures[index];
+                let forwarded = procedure.visibility.is_public();
+                if forwarded {
+                    make_forwarder(procedure, &canonical_target);
+                } else {
+                    removed[index] = true;
+                }
+                retired[index] = true;
+
+                log::debug!(
+                    target: "linker",
+                    "merging procedure '{}' into identical procedure '{}'",
+                    Demangled(&procedure.target),
+                    Demangled(&canonical_target)
+                );
+                redirects.insert(procedure.target.clone(), canonical_target.clone());
+                merged.push(MergedProcedure {
+                    merged: procedure.target.clone(),
+                    canonical: canonical_target.clone(),
+                    forwarded,
+                });
+            }
+        }
+
+        if redirects.is_empty() {
+            break;
+        }
+
+        for (index, procedure) in procedures.iter_mut().enumerate() {
+            if !removed[index] {
+                redirect(procedure, &redirects);
+            }
+        }
+    }
+
+    let mut removed = removed.into_iter();
+    procedures.retain(|_| !removed.next().unwrap());
+
+    merged
+}
+
+/// Compute the key by which identical procedures are grouped.
+///
+/// Procedures are lowered with locals allocated in the same order, so no renumbering is needed for
+/// the bodies of identical functions to be printed identically. Source locations are not printed,
+/// so they do not prevent procedures from being merged.
+fn fingerprint(procedure: &LoweredProcedure) -> String {
+    format!("locals={}\n{}", procedure.num_locals, procedure.body.to_pretty_string())
+}
+
+/// Replace the body of `procedure` with one which simply invokes `canonical`
+fn make_forwarder(procedure: &mut LoweredProcedure, canonical: &masm::InvocationTarget) {
+    let span = procedure.body.span();
+    procedure.num_locals = 0;
+    procedure.body =
+        masm::Block::new(span, vec![Op::Inst(Span::new(span, Inst::Exec(canonical.clone())))]);
+    procedure.invoked =
+        BTreeSet::from_iter([masm::Invoke::new(masm::InvokeKind::Exec, canonical.clone())]);
+}
+
+/// Rewrite all references in `procedure` to the keys of `redirects`, to the corresponding value
+pub(super) fn redirect(
+    procedure: &mut LoweredProcedure,
+    redirects: &BTreeMap<masm::InvocationTarget, masm::InvocationTarget>,
+) {
+    redirect_block(&mut procedure.body, redirects);
+    procedure.invoked = core::mem::take(&mut procedure.invoked)
+        .into_iter()
+        .map(|invoke| match redirects.get(&invoke.target) {
+            Some(target) => masm::Invoke::new(invoke.kind, target.clone()),
+            None => invoke,
+        })
+        .collect();
+}
+
+fn redirect_block(
+    block: &mut masm::Block,
+    redirects: &BTreeMap<masm::InvocationTarget, masm::InvocationTarget>,
+) {
+    let span = block.span();
+    let mut ops = block.iter().cloned().collect::<Vec<_>>();
+    for op in ops.iter_mut() {
+        match op {
+            Op::If {
+                then_blk, else_blk, ..
+            } => {
+                redirect_block(then_blk, redirects);
+                redirect_block(else_blk, redirects);
+            }
+            Op::While { body, .. } | Op::Repeat { body, .. } => {
+                redirect_block(body, redirects);
+            }
+            Op::Inst(inst) => match inst.inner_mut() {
+                Inst::Exec(target)
+                | Inst::Call(target)
+                | Inst::SysCall(target)
+                | Inst::ProcRef(target) => {
+                    if let Some(canonical) = redirects.get(target) {
+                        *target = canonical.clone();
+                    }
+                }
+                _ => (),
+            },
+        }
+    }
+    *block = masm::Block::new(span, ops);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn procedure(name: &str, visibility: masm::Visibility, body: Vec<Inst>) -> LoweredProcedure {
+        let span = SourceSpan::default();
+        let name = masm::ProcedureName::new(name).unwrap();
+        let module = masm::LibraryPath::new("::root::test").unwrap();
+        let qualified = masm::QualifiedProcedureName::new(module.as_path(), name.clone());
+        let target = masm::InvocationTarget::Path(Span::new(span, qualified.into_inner()));
+        let invoked = body
+            .iter()
+            .filter_map(|inst| match inst {
+                Inst::Exec(target) => {
+                    Some(masm::Invoke::new(masm::InvokeKind::Exec, target.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        LoweredProcedure {
+            span,
+            name,
+            docs: None,
+            target,
+            visibility,
+            signature: masm::FunctionType::new(midenc_hir::CallConv::Fast, vec![], vec![]),
+            attributes: vec![],
+            num_locals: 0,
+            body: masm::Block::new(
+                span,
+                body.into_iter().map(|inst| Op::Inst(Span::new(span, inst))).collect(),
+            ),
+            invoked,
+        }
+    }
+
+    fn names(procedures: &[LoweredProcedure]) -> Vec<String> {
+        procedures.iter().map(|procedure| procedure.name.to_string()).collect()
+    }
+
+    #[test]
+    fn dedup_merges_identical_private_procedures() {
+        let a = procedure("a", masm::Visibility::Private, vec![Inst::Add, Inst::Mul]);
+        let b = procedure("b", masm::Visibility::Private, vec![Inst::Add, Inst::Mul]);
+        let (a_target, b_target) = (a.target.clone(), b.target.clone());
+        let main = procedure(
+            "main",
+            masm::Visibility::Public,
+            vec![Inst::Exec(a_target.clone()), Inst::Exec(b_target.clone())],
+        );
+
+        let mut procedures = vec![a, b, main];
+        let merged = dedup_procedures(&mut procedures);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].merged, b_target);
+        assert_eq!(merged[0].canonical, a_target);
+        assert!(!merged[0].forwarded);
+        assert_eq!(names(&procedures), ["a", "main"]);
+
+        // All references to `b` were redirected to `a`
+        let main = &procedures[1];
+        assert!(main.body.iter().all(|op| match op {
+            Op::Inst(inst) => inst.inner() == &Inst::Exec(a_target.clone()),
+            _ => false,
+        }));
+        assert!(main.invoked.iter().all(|invoke| invoke.target == a_target));
+    }
+
+    #[test]
+    fn dedup_keeps_exported_procedures_as_forwarders() {
+        let a = procedure("a", masm::Visibility::Private, vec![Inst::Add]);
+        let b = procedure("b", masm::Visibility::Public, vec![Inst::Add]);
+        let c = procedure("c", masm::Visibility::Public, vec![Inst::Add]);
+        let b_target = b.target.clone();
+
+        let mut procedures = vec![a, b, c];
+        let merged = dedup_procedures(&mut procedures);
+
+        // The exported `b` is preferred as the canonical procedure, so the private `a` is removed,
+        // and the exported `c` forwards to `b`
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().all(|merged| merged.canonical == b_target));
+        assert_eq!(names(&procedures), ["b", "c"]);
+        let c = &procedures[1];
+        assert_eq!(c.body.iter().count(), 1);
+        assert!(
+            matches!(c.body.iter().next(), Some(Op::Inst(inst)) if inst.inner() == &Inst::Exec(b_target.clone()))
+        );
+    }
+
+    #[test]
+    fn dedup_merges_procedures_which_become_identical() {
+        // `c` and `d` only become identical once `b` is merged into `a`
+        let a = procedure("a", masm::Visibility::Private, vec![Inst::Add]);
+        let b = procedure("b", masm::Visibility::Private, vec![Inst::Add]);
+        let c = procedure("c", masm::Visibility::Private, vec![Inst::Exec(a.target.clone())]);
+        let d = procedure("d", masm::Visibility::Private, vec![Inst::Exec(b.target.clone())]);
+
+        let mut procedures = vec![a, b, c, d];
+        let merged = dedup_procedures(&mut procedures);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(names(&procedures), ["a", "c"]);
+    }
+
+    #[test]
+    fn dedup_does_not_merge_distinct_procedures() {
+        let a = procedure("a", masm::Visibility::Private, vec![Inst::Add]);
+        let b = procedure("b", masm::Visibility::Private, vec![Inst::Mul]);
+
+        let mut procedures = vec![a, b];
+        assert!(dedup_procedures(&mut procedures).is_empty());
+        assert_eq!(names(&procedures), ["a", "b"]);
+    }
+}
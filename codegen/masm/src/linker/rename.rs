@@ -0,0 +1,169 @@
+//! Renaming of private procedures after the demangled names of the functions they were lowered
+//! from.
+//!
+//! Rust symbols are mangled, which makes the emitted Miden Assembly hard to read. Private
+//! procedures can be freely renamed, as they are only referenced from within their own module, so
+//! when requested, each of them is renamed after its demangled name. Exported procedures keep
+//! their names, as they are part of the interface of the module.
+use alloc::{collections::BTreeMap, format, string::String};
+use core::hash::{Hash, Hasher};
+
+use midenc_hir::{FxHasher, demangle::demangle};
+
+use super::{LoweredProcedure, dedup::redirect};
+use crate::masm::{self, Span};
+
+/// Rename the private procedures in `procedures`, whose name is a mangled Rust symbol, after their
+/// demangled name, redirecting all references to them accordingly.
+///
+/// Demangled names are not unique, e.g. the legacy mangling scheme omits the generic arguments of
+/// a monomorphized function, so a hash of the mangled name is appended to each of them.
+pub fn demangle_private_procedures(
+    module: &masm::LibraryPathRef,
+    procedures: &mut [LoweredProcedure],
+) {
+    let mut redirects = BTreeMap::default();
+    for procedure in procedures.iter_mut() {
+        if !matches!(procedure.visibility, masm::Visibility::Private) {
+            continue;
+        }
+        let Some(name) = demangled_procedure_name(procedure.name.as_str()) else {
+            continue;
+        };
+
+        let span = procedure.span;
+        let name = masm::ProcedureName::from_raw_parts(masm::Ident::from_raw_parts(Span::new(
+            span,
+            name.into(),
+        )));
+        let qualified = masm::QualifiedProcedureName::new(module, name.clone());
+        let target = masm::InvocationTarget::Path(Span::new(span, qualified.into_inner()));
+        let mangled = core::mem::replace(&mut procedure.target, target.clone());
+        redirects.insert(mangled, target);
+        procedure.name = name;
+    }
+
+    if redirects.is_empty() {
+        return;
+    }
+    for procedure in procedures.iter_mut() {
+        redirect(procedure, &redirects);
+    }
+}
+
+/// Returns the name a procedure named `mangled` should be renamed to, or `None` if `mangled` is
+/// not a mangled Rust symbol.
+fn demangled_procedure_name(mangled: &str) -> Option<String> {
+    let demangled = demangle(mangled);
+    if demangled == mangled {
+        return None;
+    }
+
+    // Path separators would be interpreted as part of the path of the procedure
+    let demangled = demangled.replace("::", ".");
+    let mut hasher = FxHasher::default();
+    mangled.hash(&mut hasher);
+    Some(format!("{demangled}.h{:08x}", hasher.finish() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeSet, vec::Vec};
+
+    use super::*;
+    use crate::masm::{Instruction as Inst, Op, SourceSpan};
+
+    fn procedure(
+        module: &masm::LibraryPath,
+        name: &str,
+        visibility: masm::Visibility,
+        body: Vec<Inst>,
+    ) -> LoweredProcedure {
+        let span = SourceSpan::default();
+        let name = masm::ProcedureName::new(name).unwrap();
+        let qualified = masm::QualifiedProcedureName::new(module.as_path(), name.clone());
+        let target = masm::InvocationTarget::Path(Span::new(span, qualified.into_inner()));
+        let invoked = body
+            .iter()
+            .filter_map(|inst| match inst {
+                Inst::Exec(target) => {
+                    Some(masm::Invoke::new(masm::InvokeKind::Exec, target.clone()))
+                }
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+        LoweredProcedure {
+            span,
+            name,
+            docs: None,
+            target,
+            visibility,
+            signature: masm::FunctionType::new(midenc_hir::CallConv::Fast, vec![], vec![]),
+            attributes: vec![],
+            num_locals: 0,
+            body: masm::Block::new(
+                span,
+                body.into_iter().map(|inst| Op::Inst(Span::new(span, inst))).collect(),
+            ),
+            invoked,
+        }
+    }
+
+    #[test]
+    fn demangle_renames_private_procedures_and_their_references() {
+        const MANGLED: &str = "_ZN4core3ptr13drop_in_place17h0123456789abcdefE";
+        const EXPORTED: &str = "_ZN3foo3bar17hfedcba9876543210E";
+
+        let module = masm::LibraryPath::new("::root::test").unwrap();
+        let private = procedure(&module, MANGLED, masm::Visibility::Private, vec![Inst::Add]);
+        let exported = procedure(&module, EXPORTED, masm::Visibility::Public, vec![Inst::Mul]);
+        let unmangled = procedure(&module, "helper", masm::Visibility::Private, vec![Inst::Add]);
+        let main = procedure(
+            &module,
+            "main",
+            masm::Visibility::Public,
+            vec![
+                Inst::Exec(private.target.clone()),
+                Inst::Exec(exported.target.clone()),
+                Inst::Exec(unmangled.target.clone()),
+            ],
+        );
+        let (exported_target, unmangled_target) =
+            (exported.target.clone(), unmangled.target.clone());
+
+        let mut procedures = vec![private, exported, unmangled, main];
+        demangle_private_procedures(module.as_path(), &mut procedures);
+
+        let renamed = procedures[0].name.as_str();
+        assert!(renamed.starts_with("core.ptr.drop_in_place.h"), "{renamed}");
+        assert_eq!(procedures[1].name.as_str(), EXPORTED);
+        assert_eq!(procedures[2].name.as_str(), "helper");
+
+        // References to the renamed procedure were redirected to its new name
+        let expected = [
+            Inst::Exec(procedures[0].target.clone()),
+            Inst::Exec(exported_target),
+            Inst::Exec(unmangled_target),
+        ];
+        let main = &procedures[3];
+        let body = main
+            .body
+            .iter()
+            .map(|op| match op {
+                Op::Inst(inst) => inst.inner().clone(),
+                _ => panic!("unexpected control flow"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(body, expected);
+        assert!(main.invoked.iter().any(|invoke| invoke.target == procedures[0].target));
+    }
+
+    #[test]
+    fn demangled_names_of_distinct_symbols_are_distinct() {
+        // The legacy mangling scheme only distinguishes these by their hash
+        let a = demangled_procedure_name("_ZN3foo7generic17h0000000000000001E").unwrap();
+        let b = demangled_procedure_name("_ZN3foo7generic17h0000000000000002E").unwrap();
+        assert!(a.starts_with("foo.generic.h"), "{a}");
+        assert_ne!(a, b);
+    }
+}
@@ -19,11 +19,13 @@ use midenc_hir_analysis::analyses::LivenessAnalysis;
 use midenc_session::diagnostics::{Report, Spanned, WrapErr};
 use smallvec::SmallVec;
 
+use super::lowering::invocation_target_from_symbol_path;
 use crate::{
     OperandStack, TraceEvent,
     artifact::MasmComponent,
+    emit::overflow::OverflowArguments,
     emitter::BlockEmitter,
-    linker::{LinkInfo, Linker},
+    linker::{LinkInfo, Linker, LoweredProcedure, MergedProcedure},
     masm,
 };
 
@@ -48,25 +50,8 @@ impl ToMasmComponent for builtin::World {
         // Run the linker for this component in order to compute its data layout
         let link_info = Linker::default().link(None, self.as_operation()).map_err(Report::msg)?;
 
-        // Get the entrypoint, if specified
-        let entrypoint = match context.session().options.entrypoint.as_deref() {
-            Some(entry) => {
-                let entry_id = entry.parse::<FunctionIdent>().map_err(|_| {
-                    Report::msg(format!("invalid entrypoint identifier: '{entry}'"))
-                })?;
-                let name = masm::ProcedureName::from_raw_parts(masm::Ident::from_raw_parts(
-                    Span::new(entry_id.function.span, entry_id.function.as_str().into()),
-                ));
-
-                let path = LibraryPath::new(entry_id.module.as_str()).into_diagnostic()?;
-                let qualified = masm::QualifiedProcedureName::new(path.as_path(), name);
-                Some(masm::InvocationTarget::Path(Span::new(
-                    entry_id.function.span,
-                    qualified.into_inner(),
-                )))
-            }
-            None => None,
-        };
+        // Get the entrypoints, if specified
+        let entrypoints = parse_entrypoints(&context.session().options, None)?;
 
         // If we have global variables or data segments, we will require a component initializer
         // function, as well as a module to hold component-level functions such as init
@@ -111,12 +96,14 @@ impl ToMasmComponent for builtin::World {
             id: None,
             root,
             init,
-            entrypoint,
+            entrypoints,
             kernel,
             rodata,
             heap_base,
             stack_pointer,
             modules,
+            merged_procedures: Vec::new(),
+            optimized: context.session().options.optimize_masm(),
         };
         let builder = MasmComponentBuilder {
             analysis_manager,
@@ -151,42 +138,13 @@ impl ToMasmComponent for builtin::Component {
         // Get the library path of the component
         let component_path = id.to_library_path();
 
-        // Get the entrypoint, if specified
-        let entrypoint = match context.session().options.entrypoint.as_deref() {
-            Some(entry) => {
-                let entry_id = entry.parse::<FunctionIdent>().map_err(|_| {
-                    Report::msg(format!("invalid entrypoint identifier: '{entry}'"))
-                })?;
-                let name = masm::ProcedureName::from_raw_parts(masm::Ident::from_raw_parts(
-                    Span::new(entry_id.function.span, entry_id.function.as_str().into()),
-                ));
-
-                // Check if we're inside the synthetic "wrapper" component used for pure Rust
-                // compilation. Since the user does not know about it, their entrypoint does not
-                // include the synthetic component path. We append the user-provided path to the
-                // root component path here if needed.
-                //
-                // TODO(pauls): Narrow this to only be true if the target env is not 'rollup', we
-                // cannot currently do so because we do not have sufficient Cargo metadata yet in
-                // 'cargo miden build' to detect the target env, and we default it to 'rollup'
-                let is_wrapper = id.is_synthetic_wrapper();
-                let path = if is_wrapper {
-                    let mut path = component_path.clone();
-                    path.push(entry_id.module.as_str());
-                    path
-                } else {
-                    // We're compiling a Wasm component and the component id is included
-                    // in the entrypoint.
-                    LibraryPath::new(entry_id.module.as_str()).into_diagnostic()?
-                };
-                let qualified = masm::QualifiedProcedureName::new(path.as_path(), name);
-                Some(masm::InvocationTarget::Path(Span::new(
-                    entry_id.function.span,
-                    qualified.into_inner(),
-                )))
-            }
-            None => None,
-        };
+        // Get the entrypoints, if specified
+        //
+        // TODO(pauls): Narrow the wrapper check to only be true if the target env is not
+        // 'rollup', we cannot currently do so because we do not have sufficient Cargo metadata
+        // yet in 'cargo miden build' to detect the target env, and we default it to 'rollup'
+        let wrapper_path = id.is_synthetic_wrapper().then_some(&component_path);
+        let entrypoints = parse_entrypoints(&context.session().options, wrapper_path)?;
 
         // If we have global variables or data segments, we will require a component initializer
         // function, as well as a module to hold component-level functions such as init
@@ -231,12 +189,14 @@ impl ToMasmComponent for builtin::Component {
             id: Some(id),
             root,
             init,
-            entrypoint,
+            entrypoints,
             kernel,
             rodata,
             heap_base,
             stack_pointer,
             modules,
+            merged_procedures: Vec::new(),
+            optimized: context.session().options.optimize_masm(),
         };
         let builder = MasmComponentBuilder {
             analysis_manager,
@@ -253,6 +213,62 @@ impl ToMasmComponent for builtin::Component {
     }
 }
 
+/// Parse the entrypoints requested via `--entrypoint`.
+///
+/// If `wrapper_path` is set, we're inside the synthetic "wrapper" component used for pure Rust
+/// compilation. Since the user does not know about it, their entrypoints do not include the
+/// synthetic component path, so we append the user-provided path to the root component path.
+/// Otherwise, we're compiling a Wasm component, and the component id is included in each
+/// entrypoint.
+fn parse_entrypoints(
+    options: &midenc_session::Options,
+    wrapper_path: Option<&LibraryPath>,
+) -> Result<Vec<masm::InvocationTarget>, Report> {
+    let mut entrypoints = Vec::with_capacity(options.entrypoints.len());
+    for entry in options.entrypoints.iter() {
+        let entry_id = entry
+            .parse::<FunctionIdent>()
+            .map_err(|_| Report::msg(format!("invalid entrypoint identifier: '{entry}'")))?;
+        let name = masm::ProcedureName::from_raw_parts(masm::Ident::from_raw_parts(Span::new(
+            entry_id.function.span,
+            entry_id.function.as_str().into(),
+        )));
+
+        let path = match wrapper_path {
+            Some(wrapper_path) => {
+                let mut path = wrapper_path.clone();
+                path.push(entry_id.module.as_str());
+                path
+            }
+            None => LibraryPath::new(entry_id.module.as_str()).into_diagnostic()?,
+        };
+        let qualified = masm::QualifiedProcedureName::new(path.as_path(), name);
+        entrypoints.push(masm::InvocationTarget::Path(Span::new(
+            entry_id.function.span,
+            qualified.into_inner(),
+        )));
+    }
+    Ok(entrypoints)
+}
+
+/// Returns true if the procedure `name` in `module` is one of `entrypoints`
+fn is_entrypoint(
+    entrypoints: &[masm::InvocationTarget],
+    module: &miden_assembly_syntax::Path,
+    name: &str,
+) -> bool {
+    let module = module.as_str().trim_start_matches("::");
+    entrypoints.iter().any(|entrypoint| match entrypoint {
+        masm::InvocationTarget::Path(path) => path
+            .as_str()
+            .trim_start_matches("::")
+            .strip_prefix(module)
+            .and_then(|rest| rest.strip_prefix("::"))
+            .is_some_and(|rest| rest == name),
+        _ => false,
+    })
+}
+
 fn data_segments_to_rodata(link_info: &LinkInfo) -> Result<Vec<crate::Rodata>, Report> {
     use midenc_hir::constants::ConstantData;
 
@@ -393,6 +409,7 @@ impl MasmComponentBuilder<'_> {
             Box::new(masm::Module::new(masm::ModuleKind::Library, interface_path));
         let builder = MasmModuleBuilder {
             module: &mut masm_module,
+            entrypoints: &self.component.entrypoints,
             analysis_manager: self
                 .analysis_manager
                 .nest(interface.as_operation().as_operation_ref()),
@@ -400,6 +417,8 @@ impl MasmComponentBuilder<'_> {
             source_manager: self.source_manager.clone(),
             init_body: &mut self.init_body,
             invoked_from_init: &mut self.invoked_from_init,
+            procedures: Vec::new(),
+            merged_procedures: &mut self.component.merged_procedures,
         };
         builder.build_from_interface(interface)?;
 
@@ -419,11 +438,14 @@ impl MasmComponentBuilder<'_> {
         let mut masm_module = Box::new(masm::Module::new(masm::ModuleKind::Library, module_path));
         let builder = MasmModuleBuilder {
             module: &mut masm_module,
+            entrypoints: &self.component.entrypoints,
             analysis_manager: self.analysis_manager.nest(module.as_operation_ref()),
             link_info: self.link_info,
             source_manager: self.source_manager.clone(),
             init_body: &mut self.init_body,
             invoked_from_init: &mut self.invoked_from_init,
+            procedures: Vec::new(),
+            merged_procedures: &mut self.component.merged_procedures,
         };
         builder.build(module)?;
 
@@ -433,12 +455,21 @@ impl MasmComponentBuilder<'_> {
     }
 
     fn define_function(&mut self, function: &builtin::Function) -> Result<(), Report> {
-        let builder = MasmFunctionBuilder::new(function)?;
-        let procedure = builder.build(
-            function,
-            self.analysis_manager.nest(function.as_operation_ref()),
-            self.link_info,
-        )?;
+        let mut builder = MasmFunctionBuilder::new(function)?;
+        if is_entrypoint(
+            &self.component.entrypoints,
+            self.component.modules[0].path(),
+            function.get_name().as_str(),
+        ) {
+            builder.visibility = masm::Visibility::Public;
+        }
+        let procedure = builder
+            .build(
+                function,
+                self.analysis_manager.nest(function.as_operation_ref()),
+                self.link_info,
+            )?
+            .into_procedure();
 
         let module =
             Arc::get_mut(&mut self.component.modules[0]).expect("expected unique reference");
@@ -520,11 +551,15 @@ impl MasmComponentBuilder<'_> {
 
 struct MasmModuleBuilder<'a> {
     module: &'a mut masm::Module,
+    entrypoints: &'a [masm::InvocationTarget],
     analysis_manager: AnalysisManager,
     link_info: &'a LinkInfo,
     source_manager: Arc<dyn midenc_session::SourceManager + Send + Sync>,
     init_body: &'a mut Vec<masm::Op>,
     invoked_from_init: &'a mut BTreeSet<masm::Invoke>,
+    /// The procedures lowered so far, which have yet to be defined in `module`
+    procedures: Vec<LoweredProcedure>,
+    merged_procedures: &'a mut Vec<MergedProcedure>,
 }
 
 impl MasmModuleBuilder<'_> {
@@ -546,7 +581,7 @@ impl MasmModuleBuilder<'_> {
             }
         }
 
-        Ok(())
+        self.define_procedures(module.as_operation())
     }
 
     pub fn build_from_interface(mut self, interface: &builtin::Interface) -> Result<(), Report> {
@@ -563,21 +598,45 @@ impl MasmModuleBuilder<'_> {
             }
         }
 
-        Ok(())
+        self.define_procedures(interface.as_operation())
     }
 
     fn define_function(&mut self, function: &builtin::Function) -> Result<(), Report> {
-        let builder = MasmFunctionBuilder::new(function)?;
+        let mut builder = MasmFunctionBuilder::new(function)?;
+        // Entrypoints must always be exported, so that they remain reachable from outside the
+        // component
+        if is_entrypoint(self.entrypoints, self.module.path(), function.get_name().as_str()) {
+            builder.visibility = masm::Visibility::Public;
+        }
 
         let procedure = builder.build(
             function,
             self.analysis_manager.nest(function.as_operation_ref()),
             self.link_info,
         )?;
+        self.procedures.push(procedure);
 
-        self.module
-            .define_procedure(procedure, self.source_manager.clone())
-            .map_err(|e| Report::msg(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Define the procedures lowered from the functions of this module, once all of them have
+    /// been lowered, so that private procedures can be renamed first, if requested, and identical
+    /// procedures merged, unless the emitted code was requested as-is.
+    fn define_procedures(&mut self, op: &midenc_hir::Operation) -> Result<(), Report> {
+        let mut procedures = core::mem::take(&mut self.procedures);
+        if op.context().session().options.demangle_procedure_names {
+            crate::linker::demangle_private_procedures(self.module.path(), &mut procedures);
+        }
+        if op.context().session().options.optimize_masm() {
+            let merged = crate::linker::dedup_procedures(&mut procedures);
+            self.merged_procedures.extend(merged);
+        }
+
+        for procedure in procedures {
+            self.module
+                .define_procedure(procedure.into_procedure(), self.source_manager.clone())
+                .map_err(|e| Report::msg(e.to_string()))?;
+        }
 
         Ok(())
     }
@@ -647,6 +706,10 @@ impl MasmFunctionBuilder {
     pub fn new(function: &builtin::Function) -> Result<Self, Report> {
         use midenc_hir::{Symbol, Visibility};
 
+        // Code generation checks for cancellation before each function, as large components can
+        // take a while to emit
+        function.as_operation().context().session().options.cancel_token.check()?;
+
         let name = *function.get_name();
         let name = masm::ProcedureName::from_raw_parts(masm::Ident::from_raw_parts(Span::new(
             name.span,
@@ -690,7 +753,7 @@ impl MasmFunctionBuilder {
         function: &builtin::Function,
         analysis_manager: AnalysisManager,
         link_info: &LinkInfo,
-    ) -> Result<masm::Procedure, Report> {
+    ) -> Result<LoweredProcedure, Report> {
         use alloc::collections::BTreeSet;
 
         use midenc_hir_analysis::analyses::LivenessAnalysis;
@@ -705,7 +768,11 @@ impl MasmFunctionBuilder {
 
         let mut invoked = BTreeSet::default();
         let entry = function.entry_block();
-        let mut stack = crate::OperandStack::new(function.as_operation().context_rc());
+        // Operands which must be spilled to reach deeper into the operand stack are stored in
+        // procedure locals allocated after those of the function itself
+        let mut stack = crate::OperandStack::new(function.as_operation().context_rc())
+            .with_spill_slots_at(self.num_locals);
+        let spill_slots = stack.spill_slots().clone();
         {
             let entry_block = entry.borrow();
             for arg in entry_block.arguments().iter().rev().copied() {
@@ -718,9 +785,15 @@ impl MasmFunctionBuilder {
             invoked: &mut invoked,
             target: Default::default(),
             stack,
-            trace_target,
+            trace_target: trace_target.clone(),
         };
 
+        // Arguments which do not fit on the operand stack are passed in memory, and must be loaded
+        // before anything else, see [crate::emit::overflow]
+        if let Some(overflow) = OverflowArguments::for_signature(&function.signature()) {
+            emitter.emitter().load_overflow_arguments(overflow, function.span());
+        }
+
         // For component export functions, invoke the `init` procedure first if needed.
         // It loads the data segments and global vars into memory.
         if function.signature().cc.is_wasm_canonical_abi()
@@ -764,6 +837,15 @@ impl MasmFunctionBuilder {
             invoked.insert(masm::Invoke::new(masm::InvokeKind::Exec, truncate_stack.clone()));
             body.push(masm::Op::Inst(Span::new(span, masm::Instruction::Exec(truncate_stack))));
         }
+        // Clean up redundant stack manipulation left behind by operand scheduling, unless the
+        // emitted code was requested as-is
+        if function.as_operation().context().session().options.optimize_masm() {
+            let removed = crate::opt::peephole::optimize_block(&mut body);
+            log::trace!(target: &trace_target, "peephole optimizer removed {removed} instructions");
+        }
+        // Check that the emitted code agrees with the operand stack model it was emitted from
+        verify_stack_depth(function, &body)?;
+
         let Self {
             span,
             name,
@@ -771,6 +853,20 @@ impl MasmFunctionBuilder {
             visibility,
             num_locals,
         } = self;
+        let num_locals = num_locals.checked_add(spill_slots.used()).ok_or_else(|| {
+            function
+                .as_operation()
+                .context()
+                .diagnostics()
+                .diagnostic(miden_assembly::diagnostics::Severity::Error)
+                .with_message("cannot emit masm for function")
+                .with_primary_label(
+                    function.span(),
+                    "local storage exceeds procedure limit: too many operands were spilled from \
+                     the operand stack",
+                )
+                .into_report()
+        })?;
 
         // Align num_locals to WORD_SIZE, matching the assembler's FMP frame sizing.
         // num_locals already counts all HIR locals (including those allocated for params).
@@ -798,18 +894,30 @@ impl MasmFunctionBuilder {
             body.push(masm::Op::Inst(Span::unknown(masm::Instruction::Nop)));
         }
 
-        let mut procedure = masm::Procedure::new(span, visibility, name, num_locals, body);
-        procedure.set_signature(signature);
-        for attribute in ["auth_script", "note_script"] {
-            if function.has_attribute(attribute) {
-                procedure
-                    .attributes_mut()
-                    .insert(Attribute::Marker(masm::Ident::new(attribute).unwrap()));
-            }
-        }
-        procedure.extend_invoked(invoked);
+        let attributes = ["auth_script", "note_script"]
+            .into_iter()
+            .filter(|attribute| function.has_attribute(attribute))
+            .map(|attribute| Attribute::Marker(masm::Ident::new(attribute).unwrap()))
+            .collect();
 
-        Ok(procedure)
+        // The mangled name is kept as the name of the procedure, but the demangled one is attached
+        // to it as documentation, for readability
+        let mangled_name = function.get_name();
+        let docs = Some(midenc_hir::demangle::demangle(mangled_name.as_str()))
+            .filter(|demangled| demangled != mangled_name.as_str());
+
+        Ok(LoweredProcedure {
+            span,
+            name,
+            docs,
+            target: invocation_target_from_symbol_path(&function.path(), span),
+            visibility,
+            signature,
+            attributes,
+            num_locals,
+            body,
+            invoked,
+        })
     }
 }
 
@@ -824,6 +932,83 @@ fn lowered_signature(function: &builtin::Function) -> masm::FunctionType {
     masm::FunctionType::new(sig.cc, args, results)
 }
 
+/// Verify the operand stack depth of the code emitted for `function`, see [crate::verify].
+///
+/// A violation is reported as a diagnostic pointing at the HIR op the offending code was emitted
+/// for, when it can be identified.
+fn verify_stack_depth(function: &builtin::Function, body: &masm::Block) -> Result<(), Report> {
+    use alloc::{collections::BTreeMap, string::String};
+
+    use midenc_hir::{CallOpInterface, CallableOpInterface, Operation};
+
+    use crate::verify::{StackEffect, verify_procedure};
+
+    // The effect of each invocation of another function, keyed by the invoked procedure
+    let mut invocations = BTreeMap::<String, StackEffect>::default();
+    // The `init` procedure invoked by component exports leaves the operand stack as it found it
+    invocations.insert("init".into(), StackEffect::new(0, 0, 0));
+    function.as_operation().prewalk_all(|op: &Operation| {
+        let (callee, exec) = if let Some(exec) = op.downcast_ref::<crate::hir::Exec>() {
+            (exec.resolve(), true)
+        } else if let Some(call) = op.downcast_ref::<crate::hir::Call>() {
+            (call.resolve(), false)
+        } else if let Some(syscall) = op.downcast_ref::<crate::hir::Syscall>() {
+            (syscall.resolve(), false)
+        } else {
+            return;
+        };
+        let Some(callee) = callee else {
+            return;
+        };
+        let callee = callee.borrow();
+        let Some(callable) = callee.as_symbol_operation().as_trait::<dyn CallableOpInterface>()
+        else {
+            return;
+        };
+        let target = invocation_target_from_symbol_path(&callee.path(), op.span());
+        invocations
+            .insert(target.to_string(), StackEffect::of_invocation(&callable.signature(), exec));
+    });
+
+    let StackEffect {
+        consumed: params,
+        produced: results,
+        ..
+    } = StackEffect::of_invocation(&function.signature(), true);
+    let Err(violation) = verify_procedure(body, params, results, &|target| {
+        invocations.get(&target.to_string()).copied()
+    }) else {
+        return Ok(());
+    };
+
+    let span = violation
+        .span()
+        .filter(|span| !span.is_unknown())
+        .unwrap_or_else(|| function.span());
+    let mut responsible = None;
+    function.as_operation().prewalk_all(|op: &Operation| {
+        if responsible.is_none() && op.span() == span && !op.is::<builtin::Function>() {
+            responsible = Some(op.name());
+        }
+    });
+    let label = match responsible {
+        Some(op) => format!("{violation}, in the code emitted for this '{op}' op"),
+        None => violation.to_string(),
+    };
+    Err(function
+        .as_operation()
+        .context()
+        .diagnostics()
+        .diagnostic(miden_assembly::diagnostics::Severity::Error)
+        .with_message(format!(
+            "invalid operand stack usage in the code emitted for '{}'",
+            midenc_hir::demangle::demangle(function.get_name().as_str())
+        ))
+        .with_primary_label(span, label)
+        .with_help("this is a bug in the code generator, please report it")
+        .into_report())
+}
+
 fn semantic_debug_signature(function: &builtin::Function) -> Option<masm::FunctionType> {
     let subprogram = function
         .as_operation()
@@ -16,7 +16,15 @@ use smallvec::smallvec;
 
 use super::*;
 use crate::{
-    Constraint, emit::OpEmitter, emitter::BlockEmitter, masm, opt::operands::SolverOptions,
+    Constraint, OperandStack,
+    emit::{
+        OpEmitter,
+        overflow::{MAX_STACK_ARGUMENT_FELTS, OverflowArguments},
+    },
+    emitter::BlockEmitter,
+    masm,
+    opt::operands::SolverOptions,
+    stack::MAX_ADDRESSABLE_OFFSET,
 };
 
 /// Convert a resolved callee [`midenc_hir::SymbolPath`] into a MASM [`masm::InvocationTarget`].
@@ -33,6 +41,39 @@ pub(super) fn invocation_target_from_symbol_path(
     masm::InvocationTarget::Path(masm::Span::new(span, qualified.into_inner()))
 }
 
+/// Reject `op` if it passes arguments which do not fit on the operand stack to `target`, which
+/// can only be passed arguments on the operand stack.
+///
+/// Overflow arguments are stored to procedure locals of the caller, which are not accessible from
+/// another memory context, nor from inline assembly.
+fn ensure_no_overflow_arguments(
+    op: &midenc_hir::Operation,
+    signature: &midenc_hir::dialects::builtin::attributes::Signature,
+    target: &str,
+) -> Result<(), Report> {
+    let Some(overflow) = OverflowArguments::for_signature(signature) else {
+        return Ok(());
+    };
+    let total_felts = overflow.stack_felts + overflow.overflow_felts;
+    Err(op
+        .context()
+        .diagnostics()
+        .diagnostic(Severity::Error)
+        .with_message(format!("invalid '{}' operation: too many arguments", op.name()))
+        .with_primary_label(
+            op.span(),
+            format!(
+                "the arguments of this operation are {total_felts} field elements, but at most \
+                 {MAX_STACK_ARGUMENT_FELTS} can be passed to {target}"
+            ),
+        )
+        .with_help(
+            "pass the arguments indirectly, through a pointer to memory, as the canonical ABI \
+             does for calls across memory contexts",
+        )
+        .into_report())
+}
+
 /// This trait is registered with all ops, of all dialects, which are legal for lowering to MASM.
 ///
 /// The [BlockEmitter] is responsible for then invoking the methods of this trait to facilitate
@@ -492,12 +533,46 @@ impl HirLowering for arith::Constant {
     fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
         let value = *self.get_value();
 
-        emitter.inst_emitter(self.as_operation()).literal(value, self.span());
+        // If the same constant is already live on the operand stack, copying it is no more
+        // expensive than pushing it again, and results in more compact code
+        match find_live_constant(&emitter.stack, &value) {
+            Some(index) => emitter.inst_emitter(self.as_operation()).dup(index as u8, self.span()),
+            None => emitter.inst_emitter(self.as_operation()).literal(value, self.span()),
+        }
 
         Ok(())
     }
 }
 
+/// Returns the index of an operand on `stack` which is known to hold `value`, if one can be
+/// copied to the top of the stack without spilling.
+///
+/// An operand holds `value` if it is a literal, or the result of an `arith.constant` op, of the
+/// same type, i.e. a `u32` constant is never substituted for a `felt` constant of the same value.
+fn find_live_constant(stack: &OperandStack, value: &Immediate) -> Option<usize> {
+    let mut depth = 0;
+    for (index, operand) in stack.iter().rev().enumerate() {
+        depth += operand.size();
+        if depth > MAX_ADDRESSABLE_OFFSET + 1 {
+            break;
+        }
+
+        let is_same_constant = operand == value
+            || operand.as_value().is_some_and(|v| {
+                v.borrow().get_defining_op().is_some_and(|op| {
+                    op.borrow()
+                        .downcast_ref::<arith::Constant>()
+                        .is_some_and(|constant| constant.get_value() == value)
+                })
+            });
+        if is_same_constant {
+            return Some(index);
+        }
+    }
+
+    None
+}
+
 impl HirLowering for hir::Assert {
     fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
         let code = *self.get_code();
@@ -1027,6 +1102,12 @@ impl HirLowering for hir::Call {
             }
         };
 
+        ensure_no_overflow_arguments(
+            self.as_operation(),
+            &signature,
+            "a procedure in another memory context",
+        )?;
+
         // Convert the symbol path to a fully-qualified procedure path
         let callee = invocation_target_from_symbol_path(&callee_path, self.span());
 
@@ -1077,6 +1158,8 @@ impl HirLowering for hir::Syscall {
             }
         };
 
+        ensure_no_overflow_arguments(self.as_operation(), &signature, "a kernel procedure")?;
+
         // Convert the symbol path to a fully-qualified procedure path
         let callee = invocation_target_from_symbol_path(&callee_path, self.span());
 
@@ -1088,6 +1171,21 @@ impl HirLowering for hir::Syscall {
     }
 }
 
+impl HirLowering for hir::InlineAsm {
+    fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
+        // The template was validated when this op was built, but it is only kept as source
+        let context = self.as_operation().context();
+        let body = self.parse_template(context)?;
+        ensure_no_overflow_arguments(self.as_operation(), self.get_signature(), "inline assembly")?;
+
+        emitter
+            .inst_emitter(self.as_operation())
+            .inline_asm(&body, self.get_signature());
+
+        Ok(())
+    }
+}
+
 impl HirLowering for hir::Load {
     fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
         let result = self.result();
@@ -1213,18 +1311,31 @@ impl HirLowering for hir::MemSize {
 
 impl HirLowering for hir::MemSet {
     fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
-        emitter.inst_emitter(self.as_operation()).memset(self.span());
+        let count = constant_u32(self.count().as_value_ref());
+        emitter.inst_emitter(self.as_operation()).memset(count, self.span());
         Ok(())
     }
 }
 
 impl HirLowering for hir::MemCpy {
     fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
-        emitter.inst_emitter(self.as_operation()).memcpy(self.span());
+        let count = constant_u32(self.count().as_value_ref());
+        emitter.inst_emitter(self.as_operation()).memcpy(count, self.span());
         Ok(())
     }
 }
 
+/// Returns the value of `value`, if it is the result of a `u32` `arith.constant` op
+fn constant_u32(value: ValueRef) -> Option<u32> {
+    let defining_op = value.borrow().get_defining_op()?;
+    let op = defining_op.borrow();
+    let constant = op.downcast_ref::<arith::Constant>()?;
+    match *constant.get_value() {
+        Immediate::U32(value) => Some(value),
+        _ => None,
+    }
+}
+
 impl HirLowering for hir::PrintLn {
     fn emit(&self, emitter: &mut BlockEmitter<'_>) -> Result<(), Report> {
         emitter.inst_emitter(self.as_operation()).println(self.span());
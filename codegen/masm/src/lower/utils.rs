@@ -496,7 +496,7 @@ mod tests {
     use midenc_dialect_scf::StructuredControlFlowOpBuilder;
     use midenc_expect_test::expect_file;
     use midenc_hir::{
-        TraceTarget, Type,
+        Felt, TraceTarget, Type,
         dialects::builtin::{self, BuiltinOpBuilder, FunctionRef},
         formatter::PrettyPrint,
         pass::AnalysisManager,
@@ -628,6 +628,125 @@ mod tests {
         Ok(())
     }
 
+    /// Lower the entry block of `function_ref`, with `args` on the operand stack, `args[0]` on top
+    fn lower_entry_block(
+        test: &Test,
+        function_ref: FunctionRef,
+        args: &[ValueRef],
+    ) -> Result<masm::Block, Report> {
+        let analysis_manager = AnalysisManager::new(function_ref.as_operation_ref(), None);
+        let liveness = analysis_manager.get_analysis::<LivenessAnalysis>()?;
+        let link_info = LinkInfo::new(Some(builtin::ComponentId {
+            namespace: "root".into(),
+            name: "root".into(),
+            version: Version::new(1, 0, 0),
+        }));
+
+        let mut stack = OperandStack::new(test.context_rc());
+        for arg in args.iter().rev() {
+            stack.push(*arg);
+        }
+
+        let function_name = *function_ref.borrow().get_name();
+        let mut invoked = Default::default();
+        let emitter = BlockEmitter {
+            liveness: &liveness,
+            link_info: &link_info,
+            invoked: &mut invoked,
+            target: Default::default(),
+            stack,
+            trace_target: TraceTarget::category("codegen")
+                .with_relevant_symbol(function_name.as_symbol()),
+        };
+
+        let function = function_ref.borrow();
+        let entry = function.entry_block();
+        Ok(emitter.emit(&entry.borrow()))
+    }
+
+    /// Count the instructions in `block` for which `predicate` returns true
+    fn count_insts(block: &masm::Block, predicate: impl Fn(&masm::Instruction) -> bool) -> usize {
+        block
+            .iter()
+            .filter(|op| matches!(op, masm::Op::Inst(inst) if predicate(inst.inner())))
+            .count()
+    }
+
+    fn is_push(inst: &masm::Instruction) -> bool {
+        matches!(inst, masm::Instruction::Push(_))
+    }
+
+    fn is_dup(inst: &masm::Instruction) -> bool {
+        (0..16).any(|n| crate::emit::dup_from_offset(n) == *inst)
+    }
+
+    #[test]
+    fn lowering_reuses_live_constants_test() -> Result<(), Report> {
+        const NUM_CONSTANTS: usize = 8;
+
+        let mut test = Test::new("lowering_reuses_live_constants", &[Type::U32], &[Type::U32]);
+        let function_ref = test.function();
+
+        let a = {
+            let span = function_ref.span();
+            let mut builder = test.function_builder();
+            let a = builder.entry_block().borrow().arguments()[0] as ValueRef;
+
+            // All of the constants are live at once, so each one after the first can be copied
+            // from the stack, rather than pushed again
+            let constants = (0..NUM_CONSTANTS).map(|_| builder.u32(7, span)).collect::<Vec<_>>();
+            let mut sum = a;
+            for constant in constants {
+                sum = builder.add_unchecked(sum, constant, span)?;
+            }
+            builder.ret(Some(sum), span)?;
+
+            a
+        };
+
+        let body = lower_entry_block(&test, function_ref, &[a])?;
+
+        // Without reuse, each constant would be pushed separately
+        assert_eq!(count_insts(&body, is_push), 1, "{}", body.to_pretty_string());
+        assert!(count_insts(&body, is_dup) >= NUM_CONSTANTS - 1, "{}", body.to_pretty_string());
+        Ok(())
+    }
+
+    #[test]
+    fn lowering_does_not_reuse_constants_of_a_different_type_test() -> Result<(), Report> {
+        let mut test = Test::new(
+            "lowering_does_not_reuse_constants_of_a_different_type",
+            &[Type::U32, Type::Felt],
+            &[Type::U32, Type::Felt],
+        );
+        let function_ref = test.function();
+
+        let (a, b) = {
+            let span = function_ref.span();
+            let mut builder = test.function_builder();
+            let a = builder.entry_block().borrow().arguments()[0] as ValueRef;
+            let b = builder.entry_block().borrow().arguments()[1] as ValueRef;
+
+            // `7 : u32` and `7 : felt` must each be pushed once, and only reused for themselves
+            let x1 = builder.u32(7, span);
+            let y1 = builder.felt(Felt::new_unchecked(7), span);
+            let x2 = builder.u32(7, span);
+            let y2 = builder.felt(Felt::new_unchecked(7), span);
+            let y = builder.add_unchecked(b, y1, span)?;
+            let y = builder.add_unchecked(y, y2, span)?;
+            let x = builder.add_unchecked(a, x1, span)?;
+            let x = builder.add_unchecked(x, x2, span)?;
+            builder.ret([x, y], span)?;
+
+            (a, b)
+        };
+
+        let body = lower_entry_block(&test, function_ref, &[a, b])?;
+
+        assert_eq!(count_insts(&body, is_push), 2, "{}", body.to_pretty_string());
+        Ok(())
+    }
+
     #[test]
     fn util_emit_if_test() -> Result<(), Report> {
         let mut test = Test::new("util_emit_if_test", &[Type::U32, Type::U32], &[Type::U32]);
@@ -1,3 +1,4 @@
 pub mod operands;
+pub mod peephole;
 
 pub use self::operands::{OperandMovementConstraintSolver, SolverError};
@@ -262,6 +262,10 @@ impl OperandMovementConstraintSolver {
         // apply those solutions in such a way that we produce code like we would
         // by hand when preparing instruction operands
         let mut best_solution: Option<Vec<Action>> = None;
+        // The shortest solution which requires access beyond the addressable window of the operand
+        // stack. Such solutions are only used if no other solution is found, as the code generator
+        // must spill operands to procedure locals in order to apply them.
+        let mut spilling_solution: Option<Vec<Action>> = None;
         let mut builder = SolutionBuilder::new(&self.context);
         while let Some(mut tactic) = self.tactics.pop() {
             match tactic.apply(&mut builder) {
@@ -277,9 +281,13 @@ impl OperandMovementConstraintSolver {
                                 target: self.trace_target(),
                                 symbol = self.trace_target().relevant_symbol();
                                 "a solution was found using tactic {}, but it requires stack \
-                                 access deeper than supported by MASM; rejecting it",
+                                 access deeper than supported by MASM; keeping it only as a \
+                                 fallback",
                                 tactic.name()
                             );
+                            if spilling_solution.as_ref().is_none_or(|s| s.len() > solution.len()) {
+                                spilling_solution = Some(solution);
+                            }
                         } else {
                             let solution_size = solution.len();
                             let best_size = best_solution.as_ref().map(|best| best.len());
@@ -348,6 +356,18 @@ impl OperandMovementConstraintSolver {
             }
         }
 
+        if best_solution.is_none()
+            && let Some(solution) = spilling_solution
+        {
+            log::trace!(
+                target: self.trace_target(),
+                symbol = self.trace_target().relevant_symbol();
+                "no solution within the addressable window of the operand stack was found, using \
+                 a solution which requires spilling operands"
+            );
+            return Ok(solution);
+        }
+
         best_solution.take().ok_or(SolverError::NoSolution)
     }
 
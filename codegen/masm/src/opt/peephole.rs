@@ -0,0 +1,328 @@
+//! A peephole optimizer for Miden Assembly emitted by the code generator.
+//!
+//! The operand scheduler places operands for each instruction independently, so it is common for
+//! the code emitted for one instruction to undo the stack manipulation performed for the previous
+//! one, e.g. `movup.3` followed by `movdn.3`, or a value which was copied with `dup.N` only to be
+//! dropped as dead. This pass removes such adjacent pairs of instructions which cancel each other
+//! out.
+//!
+//! Only instructions which are adjacent in the same block are considered: control flow, as well
+//! as any instruction not known to be a pure stack manipulation (including decorators, whose
+//! stack offsets would be invalidated), acts as a barrier.
+//!
+//! Once redundant pairs have been removed, runs of adjacent pushes of single field elements are
+//! batched into a single `push` of up to [MAX_PUSH_ELEMENTS] elements, e.g. `push.1 push.2` is
+//! rewritten to `push.1.2`.
+use alloc::vec::Vec;
+
+use midenc_hir::Felt;
+
+use crate::{
+    emit::{
+        dup_from_offset, movdn_from_offset, movdnw_from_offset, movup_from_offset,
+        movupw_from_offset, swap_from_offset, swapw_from_offset,
+    },
+    masm::{self, Instruction as Inst, Op, Spanned},
+};
+
+/// The maximum number of elements which can be pushed by a single `push` instruction
+const MAX_PUSH_ELEMENTS: usize = 16;
+
+/// Remove redundant stack manipulation from `block`, and the blocks nested within it, and batch
+/// adjacent pushes of literal values.
+///
+/// Returns the number of instructions which were removed.
+pub fn optimize_block(block: &mut masm::Block) -> usize {
+    let span = block.span();
+    let ops = block.iter().cloned().collect::<Vec<_>>();
+    let num_ops = ops.len();
+    let mut removed = 0;
+    let mut optimized = Vec::<Op>::with_capacity(num_ops);
+    for mut op in ops {
+        match &mut op {
+            Op::If {
+                then_blk, else_blk, ..
+            } => {
+                removed += optimize_block(then_blk);
+                removed += optimize_block(else_blk);
+            }
+            Op::While { body, .. } | Op::Repeat { body, .. } => {
+                removed += optimize_block(body);
+            }
+            Op::Inst(inst) => {
+                // Removing a pair can expose another pair which cancels out, e.g. the outer
+                // instructions of `movup.3 swap.1 swap.1 movdn.3`, so we compare against the last
+                // instruction kept, rather than the last one seen.
+                if let Some(Op::Inst(prev)) = optimized.last()
+                    && cancels(prev.inner(), inst.inner())
+                {
+                    optimized.pop();
+                    removed += 2;
+                    continue;
+                }
+            }
+        }
+        optimized.push(op);
+    }
+
+    // Batching is done last, as pushes which are batched together can no longer be cancelled out
+    let (optimized, batched) = batch_pushes(optimized);
+    removed += batched;
+
+    if removed > 0 {
+        *block = masm::Block::new(span, optimized);
+    }
+
+    removed
+}
+
+/// Merge runs of adjacent pushes of single field elements in `ops` into multi-element pushes.
+///
+/// Returns the rewritten ops, and the number of instructions which were removed.
+fn batch_pushes(ops: Vec<Op>) -> (Vec<Op>, usize) {
+    let mut removed = 0;
+    let mut batched = Vec::<Op>::with_capacity(ops.len());
+    for op in ops {
+        if let Op::Inst(inst) = &op
+            && let Some(value) = pushed_felt(inst.inner())
+            && let Some(Op::Inst(prev)) = batched.last_mut()
+        {
+            if let Some(first) = pushed_felt(prev.inner()) {
+                *prev = masm::Span::new(prev.span(), Inst::PushFeltList(vec![first, value]));
+                removed += 1;
+                continue;
+            }
+            if let Inst::PushFeltList(values) = prev.inner_mut()
+                && values.len() < MAX_PUSH_ELEMENTS
+            {
+                values.push(value);
+                removed += 1;
+                continue;
+            }
+        }
+        batched.push(op);
+    }
+
+    (batched, removed)
+}
+
+/// Returns the value pushed by `inst`, if it is a `push` of a single field element
+pub(crate) fn pushed_felt(inst: &Inst) -> Option<Felt> {
+    let Inst::Push(masm::Immediate::Value(value)) = inst else {
+        return None;
+    };
+    match value.inner() {
+        masm::PushValue::Int(masm::IntValue::U8(v)) => Some(Felt::new_unchecked(*v as u64)),
+        masm::PushValue::Int(masm::IntValue::U16(v)) => Some(Felt::new_unchecked(*v as u64)),
+        masm::PushValue::Int(masm::IntValue::U32(v)) => Some(Felt::new_unchecked(*v as u64)),
+        masm::PushValue::Int(masm::IntValue::Felt(v)) => Some(*v),
+        masm::PushValue::Word(_) => None,
+    }
+}
+
+/// Returns true if executing `first`, followed by `second`, leaves the operand stack unchanged
+fn cancels(first: &Inst, second: &Inst) -> bool {
+    match (first, second) {
+        // Copying or pushing a value, only to drop it
+        (first, Inst::Drop) => {
+            offset_of(first, 0..=15, dup_from_offset).is_some() || pushes_elements(first) == Some(1)
+        }
+        (first, Inst::DropW) => {
+            matches!(first, Inst::PadW | Inst::DupW0 | Inst::DupW1 | Inst::DupW2 | Inst::DupW3)
+                || pushes_elements(first) == Some(4)
+        }
+        // Instructions which are their own inverse
+        (Inst::SwapDw, Inst::SwapDw)
+        | (Inst::Reversew, Inst::Reversew)
+        | (Inst::Reversedw, Inst::Reversedw) => true,
+        (first, second) if first == second => {
+            offset_of(first, 1..=15, swap_from_offset).is_some()
+                || offset_of(first, 1..=3, swapw_from_offset).is_some()
+        }
+        // Moving a value up, and then back down to where it was, or vice versa
+        (first, second) => {
+            let inverse = |up: fn(usize) -> Inst,
+                           down: fn(usize) -> Inst,
+                           offsets: core::ops::RangeInclusive<usize>| {
+                let up_then_down = offset_of(first, offsets.clone(), up)
+                    .is_some_and(|n| offset_of(second, n..=n, down).is_some());
+                let down_then_up = offset_of(first, offsets, down)
+                    .is_some_and(|n| offset_of(second, n..=n, up).is_some());
+                up_then_down || down_then_up
+            };
+            inverse(movup_from_offset, movdn_from_offset, 2..=15)
+                || inverse(movupw_from_offset, movdnw_from_offset, 2..=3)
+        }
+    }
+}
+
+/// Returns the offset `n` in `offsets`, for which `inst` is the instruction `make(n)`
+fn offset_of(
+    inst: &Inst,
+    mut offsets: core::ops::RangeInclusive<usize>,
+    make: fn(usize) -> Inst,
+) -> Option<usize> {
+    offsets.find(|n| make(*n) == *inst)
+}
+
+/// Returns the number of elements pushed on the operand stack by `inst`, if it is a `push` of a
+/// literal value.
+fn pushes_elements(inst: &Inst) -> Option<usize> {
+    match inst {
+        Inst::Push(masm::Immediate::Value(value)) => match value.inner() {
+            masm::PushValue::Int(_) => Some(1),
+            masm::PushValue::Word(_) => Some(4),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use midenc_expect_test::expect_file;
+    use midenc_hir::formatter::PrettyPrint;
+
+    use super::*;
+    use crate::masm::{IntValue, PushValue, SourceSpan, Span};
+
+    fn inst(inst: Inst) -> Op {
+        Op::Inst(Span::unknown(inst))
+    }
+
+    fn push(value: u32) -> Op {
+        inst(Inst::Push(masm::Immediate::Value(Span::unknown(PushValue::Int(
+            IntValue::U32(value),
+        )))))
+    }
+
+    fn block(ops: Vec<Op>) -> masm::Block {
+        masm::Block::new(SourceSpan::default(), ops)
+    }
+
+    #[test]
+    fn peephole_removes_inverse_shuffles() {
+        let mut body = block(vec![
+            push(1),
+            inst(movup_from_offset(3)),
+            inst(movdn_from_offset(3)),
+            inst(Inst::Swap1),
+            inst(Inst::Swap1),
+            inst(movdn_from_offset(2)),
+            inst(movup_from_offset(2)),
+            inst(Inst::SwapW2),
+            inst(Inst::SwapW2),
+            inst(movupw_from_offset(2)),
+            inst(movdnw_from_offset(2)),
+            inst(Inst::Add),
+        ]);
+        assert_eq!(optimize_block(&mut body), 10);
+        expect_file!["expected/peephole_removes_inverse_shuffles.masm"]
+            .assert_eq(&body.to_pretty_string());
+    }
+
+    #[test]
+    fn peephole_removes_values_which_are_immediately_dropped() {
+        let mut body = block(vec![
+            inst(dup_from_offset(0)),
+            inst(Inst::Drop),
+            push(7),
+            inst(Inst::Drop),
+            inst(Inst::PadW),
+            inst(Inst::DropW),
+            inst(Inst::DupW1),
+            inst(Inst::DropW),
+            // A `dup` which is consumed is preserved
+            inst(dup_from_offset(2)),
+            inst(Inst::Mul),
+            inst(Inst::Drop),
+        ]);
+        assert_eq!(optimize_block(&mut body), 8);
+        expect_file!["expected/peephole_removes_values_which_are_immediately_dropped.masm"]
+            .assert_eq(&body.to_pretty_string());
+    }
+
+    #[test]
+    fn peephole_removes_nested_pairs() {
+        // Removing `swap.1 swap.1` exposes `movup.3 movdn.3`, which is removed as well
+        let mut body = block(vec![
+            inst(movup_from_offset(3)),
+            inst(Inst::Swap1),
+            inst(Inst::Swap1),
+            inst(movdn_from_offset(3)),
+            inst(Inst::Eq),
+        ]);
+        assert_eq!(optimize_block(&mut body), 4);
+        expect_file!["expected/peephole_removes_nested_pairs.masm"]
+            .assert_eq(&body.to_pretty_string());
+    }
+
+    #[test]
+    fn peephole_respects_block_boundaries() {
+        let mut body = block(vec![
+            inst(movup_from_offset(2)),
+            Op::If {
+                span: SourceSpan::default(),
+                then_blk: block(vec![
+                    inst(movdn_from_offset(2)),
+                    inst(dup_from_offset(1)),
+                    inst(Inst::Drop),
+                ]),
+                else_blk: block(vec![inst(Inst::Swap1), inst(Inst::Swap1), inst(Inst::Mul)]),
+            },
+            inst(Inst::Drop),
+            Op::While {
+                span: SourceSpan::default(),
+                body: block(vec![inst(dup_from_offset(0)), inst(Inst::Drop), push(1)]),
+            },
+            inst(Inst::Drop),
+        ]);
+        assert_eq!(optimize_block(&mut body), 6);
+        expect_file!["expected/peephole_respects_block_boundaries.masm"]
+            .assert_eq(&body.to_pretty_string());
+    }
+
+    #[test]
+    fn peephole_batches_adjacent_pushes() {
+        let felt = |value: u64| {
+            inst(Inst::Push(masm::Immediate::Value(Span::unknown(PushValue::Int(
+                IntValue::Felt(Felt::new_unchecked(value)),
+            )))))
+        };
+        let mut ops = vec![
+            // `push.3` is cancelled by the `drop`, before the remaining pushes are batched
+            push(1),
+            felt(2),
+            push(3),
+            inst(Inst::Drop),
+            inst(Inst::Add),
+            // Decorators are a barrier to batching
+            push(4),
+            inst(Inst::Trace(0u32.into())),
+            push(5),
+            inst(Inst::Mul),
+        ];
+        // A run of pushes is split into batches of no more than 16 elements
+        ops.extend((0..18).map(push));
+        let mut body = block(ops);
+        assert_eq!(optimize_block(&mut body), 2 + 1 + 16);
+        expect_file!["expected/peephole_batches_adjacent_pushes.masm"]
+            .assert_eq(&body.to_pretty_string());
+    }
+
+    #[test]
+    fn peephole_does_not_remove_pairs_separated_by_other_instructions() {
+        let ops = vec![
+            inst(movup_from_offset(3)),
+            inst(Inst::Trace(0u32.into())),
+            inst(movdn_from_offset(3)),
+            inst(dup_from_offset(4)),
+            inst(Inst::Swap1),
+            inst(Inst::Drop),
+            inst(Inst::Swap2),
+            inst(Inst::Swap3),
+        ];
+        let mut body = block(ops.clone());
+        assert_eq!(optimize_block(&mut body), 0);
+        assert_eq!(body.iter().cloned().collect::<Vec<_>>(), ops);
+    }
+}
@@ -1,5 +1,6 @@
 use alloc::rc::Rc;
 use core::{
+    cell::Cell,
     fmt,
     ops::{Index, IndexMut},
 };
@@ -298,6 +299,68 @@ impl Operand {
     }
 }
 
+/// The deepest position on the VM's operand stack, in field elements, which can be addressed by
+/// stack manipulation instructions such as `movup.N`, `movdn.N`, `dup.N` and `swap.N`.
+pub const MAX_ADDRESSABLE_OFFSET: usize = 15;
+
+/// Returns the number of field elements which must be spilled from the top of the operand stack,
+/// in order for the element at `offset` to become addressable.
+///
+/// This is zero if the element at `offset` is already addressable.
+#[inline]
+pub const fn spills_required(offset: usize) -> usize {
+    offset.saturating_sub(MAX_ADDRESSABLE_OFFSET)
+}
+
+/// The procedure locals used to temporarily spill elements from the top of the operand stack,
+/// so that elements deeper than [MAX_ADDRESSABLE_OFFSET] can be reached.
+///
+/// Spill slots are allocated after the locals of the function being emitted, and are shared by
+/// every [OperandStack] cloned from the one the function started with, so that once the function
+/// has been emitted, [SpillSlots::used] is the number of additional locals it requires.
+#[derive(Debug, Default)]
+pub struct SpillSlots {
+    /// The index of the first procedure local used for spills
+    base: u16,
+    /// The largest number of spill slots in use at any one time
+    used: Cell<u16>,
+}
+impl SpillSlots {
+    pub fn new(base: u16) -> Self {
+        Self {
+            base,
+            used: Cell::new(0),
+        }
+    }
+
+    /// The index of the first procedure local used for spills
+    #[inline]
+    pub fn base(&self) -> u16 {
+        self.base
+    }
+
+    /// The number of procedure locals which must be allocated for spills
+    #[inline]
+    pub fn used(&self) -> u16 {
+        self.used.get()
+    }
+
+    /// Reserve `n` consecutive spill slots, returning the index of the procedure local
+    /// corresponding to the first of them.
+    ///
+    /// Spills never outlive the stack manipulation which required them, so the same slots are
+    /// handed out every time, and only the high-water mark is tracked.
+    pub fn reserve(&self, n: usize) -> u16 {
+        let n = u16::try_from(n).expect("too many operand stack spills");
+        assert!(
+            self.base.checked_add(n).is_some(),
+            "operand stack spills exceed the maximum number of procedure locals"
+        );
+        self.used.set(self.used.get().max(n));
+        self.base
+    }
+}
+
 /// This structure emulates the state of the VM's operand stack while
 /// generating code from the SSA representation of a function.
 ///
@@ -314,6 +377,7 @@ impl Operand {
 pub struct OperandStack {
     context: Rc<Context>,
     stack: Vec<Operand>,
+    spills: Rc<SpillSlots>,
 }
 impl Eq for OperandStack {}
 impl PartialEq for OperandStack {
@@ -326,9 +390,25 @@ impl OperandStack {
         Self {
             context,
             stack: Vec::with_capacity(16),
+            spills: Rc::new(SpillSlots::default()),
         }
     }
 
+    /// Use the procedure locals starting at `base` to spill operands, see [SpillSlots].
+    ///
+    /// This must be called with the number of locals of the function being emitted, before any
+    /// operands are pushed on the stack.
+    pub fn with_spill_slots_at(mut self, base: u16) -> Self {
+        self.spills = Rc::new(SpillSlots::new(base));
+        self
+    }
+
+    /// Get the spill slots used when accessing elements deeper than [MAX_ADDRESSABLE_OFFSET]
+    #[inline(always)]
+    pub fn spill_slots(&self) -> &Rc<SpillSlots> {
+        &self.spills
+    }
+
     #[inline(always)]
     pub fn context_rc(&self) -> Rc<Context> {
         self.context.clone()
@@ -394,6 +474,13 @@ impl OperandStack {
         self.stack.iter().rev().take(index + 1).map(|o| o.size()).sum::<usize>() - 1
     }
 
+    /// Returns the number of field elements which must be spilled from the top of the stack, in
+    /// order for every element of the operand at `index` to become addressable.
+    #[track_caller]
+    pub fn spills_required(&self, index: usize) -> usize {
+        spills_required(self.effective_index_inclusive(index))
+    }
+
     /// Returns the number of operands on the stack
     #[inline]
     pub fn len(&self) -> usize {
@@ -518,7 +605,6 @@ impl OperandStack {
         r.rotate_right(1);
     }
 
-    #[allow(unused)]
     #[inline(always)]
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Operand> {
         self.stack.iter()
@@ -929,4 +1015,42 @@ mod tests {
         assert_eq!(stack[1], two);
         assert_eq!(stack[2], zero);
     }
+
+    #[test]
+    fn operand_stack_spills_required_for_deep_operands_test() {
+        let context = Rc::new(Context::default());
+        let mut stack = OperandStack::new(context.clone()).with_spill_slots_at(3);
+
+        // A stack of 20 single-element operands: [19, 18, .., 0]
+        for i in 0..20u32 {
+            stack.push_immediate(Immediate::U32(i));
+        }
+        assert_eq!(stack.raw_len(), 20);
+        for index in 0..=MAX_ADDRESSABLE_OFFSET {
+            assert_eq!(stack.spills_required(index), 0);
+        }
+        assert_eq!(stack.spills_required(16), 1);
+        assert_eq!(stack.spills_required(19), 4);
+
+        // Wide operands push operands with a small index out of reach: each u64 is 2 elements, so
+        // eight of them fill the addressable window, and the operand at index 8 is at offset 16
+        for i in 0..8u64 {
+            stack.push_immediate(Immediate::U64(i));
+        }
+        assert_eq!(stack.len(), 28);
+        assert_eq!(stack.raw_len(), 36);
+        assert_eq!(stack.spills_required(7), 0);
+        assert_eq!(stack.spills_required(8), 1);
+        assert_eq!(stack.spills_required(27), 20);
+
+        // Spill slots are allocated after the function's own locals, and are shared by clones
+        let slots = stack.spill_slots().clone();
+        assert_eq!(slots.base(), 3);
+        assert_eq!(slots.used(), 0);
+        let cloned = stack.clone();
+        assert_eq!(cloned.spill_slots().reserve(10), 3);
+        assert_eq!(slots.reserve(4), 3);
+        assert_eq!(slots.used(), 10);
+        assert_eq!(spills_required(36), 21);
+    }
 }
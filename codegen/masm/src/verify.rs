@@ -0,0 +1,611 @@
+//! Verification of the operand stack depth of emitted procedures.
+//!
+//! The code generator places the operands of each HIR op using a model of the operand stack, so a
+//! mistake in the instructions emitted for an op, e.g. a `movup.N` which is off by one, or a branch
+//! which leaves an extra value behind, is otherwise only caught by the assembler or the VM, far
+//! from its cause. This pass symbolically executes the body of a procedure, tracking the number of
+//! field elements on the operand stack, and checks that:
+//!
+//! * No instruction accesses elements below those known to be on the operand stack, i.e. the
+//!   arguments of the procedure, and the elements pushed since
+//! * Both branches of an `if.true` leave the same number of elements on the operand stack, and the
+//!   body of a `while.true` leaves as many as there were when the loop was entered
+//! * The procedure leaves exactly as many elements on the operand stack as its results occupy
+//!
+//! A branch which ends by asserting that `0` is `1`, i.e. code emitted for an unreachable point in
+//! the program, is not required to agree with the other branch.
+//!
+//! Once an instruction, or invocation, whose effect on the operand stack is not known is reached,
+//! e.g. an `exec` of an intrinsic, the depth of the operand stack can no longer be tracked, and the
+//! remainder of that path through the procedure is not checked.
+use alloc::string::String;
+use core::fmt;
+
+use midenc_hir::{Felt, dialects::builtin::attributes::Signature, formatter::PrettyPrint};
+
+use crate::{
+    emit::overflow::OverflowArguments,
+    masm::{self, Instruction as Inst, Op, SourceSpan, Spanned},
+    opt::peephole::pushed_felt,
+};
+
+/// The effect of an instruction on the operand stack
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StackEffect {
+    /// The number of elements which the instruction accesses, and so must be on the operand stack
+    pub required: usize,
+    /// The number of elements popped from the operand stack
+    pub consumed: usize,
+    /// The number of elements pushed on the operand stack, once those consumed are popped
+    pub produced: usize,
+}
+
+impl StackEffect {
+    pub const fn new(required: usize, consumed: usize, produced: usize) -> Self {
+        Self {
+            required,
+            consumed,
+            produced,
+        }
+    }
+
+    /// An instruction which replaces its `consumed` operands with `produced` results
+    const fn pure(consumed: usize, produced: usize) -> Self {
+        Self::new(consumed, consumed, produced)
+    }
+
+    /// An instruction which accesses `required` elements, without changing the depth of the
+    /// operand stack, e.g. a shuffle
+    const fn reads(required: usize) -> Self {
+        Self::new(required, 0, 0)
+    }
+
+    /// The effect of invoking a function with `signature`, using `exec` if `exec` is true, and
+    /// `call` or `syscall` otherwise.
+    pub fn of_invocation(signature: &Signature, exec: bool) -> Self {
+        let params = match OverflowArguments::for_signature(signature) {
+            // Only the address of the arguments passed in memory follows those on the stack
+            Some(overflow) if exec => overflow.stack_felts + 1,
+            _ => signature.params.iter().map(|param| param.ty.size_in_felts()).sum(),
+        };
+        let results = signature.results.iter().map(|result| result.ty.size_in_felts()).sum();
+        Self::pure(params, results)
+    }
+
+    /// Get the effect of `inst` on the operand stack, if known.
+    ///
+    /// The effect of an invocation is obtained from `invocations`, given the invoked procedure.
+    pub fn of(
+        inst: &Inst,
+        invocations: &dyn Fn(&masm::InvocationTarget) -> Option<Self>,
+    ) -> Option<Self> {
+        use crate::emit::{
+            dup_from_offset, movdn_from_offset, movdnw_from_offset, movup_from_offset,
+            movupw_from_offset, swap_from_offset, swapw_from_offset,
+        };
+
+        let offset_of = |offsets: core::ops::RangeInclusive<usize>, make: fn(usize) -> Inst| {
+            offsets.into_iter().find(|n| make(*n) == *inst)
+        };
+        if let Some(n) = offset_of(0..=15, dup_from_offset) {
+            return Some(Self::new(n + 1, 0, 1));
+        }
+        if let Some(n) = offset_of(1..=15, swap_from_offset)
+            .or_else(|| offset_of(2..=15, movup_from_offset))
+            .or_else(|| offset_of(2..=15, movdn_from_offset))
+        {
+            return Some(Self::reads(n + 1));
+        }
+        if let Some(n) = offset_of(1..=3, swapw_from_offset)
+            .or_else(|| offset_of(2..=3, movupw_from_offset))
+            .or_else(|| offset_of(2..=3, movdnw_from_offset))
+        {
+            return Some(Self::reads((n + 1) * 4));
+        }
+
+        Some(match inst {
+            // Decorators, and instructions without effect on the operand stack
+            Inst::Nop | Inst::Trace(_) | Inst::Debug(_) | Inst::DebugVar(_) | Inst::EmitImm(_) => {
+                Self::pure(0, 0)
+            }
+            // Invocations
+            Inst::Exec(target) | Inst::Call(target) | Inst::SysCall(target) => {
+                return invocations(target);
+            }
+            Inst::ProcRef(_) => Self::pure(0, 4),
+            // Literals
+            Inst::Push(masm::Immediate::Value(value)) => match value.inner() {
+                masm::PushValue::Int(_) => Self::pure(0, 1),
+                masm::PushValue::Word(_) => Self::pure(0, 4),
+            },
+            Inst::PushFeltList(values) => Self::pure(0, values.len()),
+            // Stack manipulation
+            Inst::Drop => Self::pure(1, 0),
+            Inst::DropW => Self::pure(4, 0),
+            Inst::PadW => Self::pure(0, 4),
+            Inst::DupW0 => Self::new(4, 0, 4),
+            Inst::DupW1 => Self::new(8, 0, 4),
+            Inst::DupW2 => Self::new(12, 0, 4),
+            Inst::DupW3 => Self::new(16, 0, 4),
+            Inst::SwapDw => Self::reads(16),
+            Inst::Reversew => Self::reads(4),
+            Inst::Reversedw => Self::reads(8),
+            Inst::CSwap => Self::new(3, 1, 0),
+            Inst::CSwapW => Self::new(9, 1, 0),
+            Inst::CDrop => Self::pure(3, 1),
+            Inst::CDropW => Self::pure(9, 4),
+            Inst::Sdepth | Inst::Clk => Self::pure(0, 1),
+            // Assertions
+            Inst::Assert | Inst::AssertWithError(_) | Inst::Assertz | Inst::AssertzWithError(_) => {
+                Self::pure(1, 0)
+            }
+            Inst::AssertEq | Inst::AssertEqWithError(_) => Self::pure(2, 0),
+            Inst::AssertEqw | Inst::AssertEqwWithError(_) => Self::pure(8, 0),
+            Inst::U32Assert | Inst::U32AssertWithError(_) => Self::reads(1),
+            Inst::U32Assert2 | Inst::U32Assert2WithError(_) => Self::reads(2),
+            Inst::U32AssertW | Inst::U32AssertWWithError(_) => Self::reads(4),
+            // Field arithmetic and comparisons
+            Inst::Add
+            | Inst::Sub
+            | Inst::Mul
+            | Inst::Div
+            | Inst::Exp
+            | Inst::Eq
+            | Inst::Neq
+            | Inst::Lt
+            | Inst::Lte
+            | Inst::Gt
+            | Inst::Gte
+            | Inst::And
+            | Inst::Or
+            | Inst::Xor => Self::pure(2, 1),
+            Inst::AddImm(_)
+            | Inst::SubImm(_)
+            | Inst::MulImm(_)
+            | Inst::DivImm(_)
+            | Inst::ExpImm(_)
+            | Inst::EqImm(_)
+            | Inst::NeqImm(_)
+            | Inst::Neg
+            | Inst::Inv
+            | Inst::Incr
+            | Inst::Not
+            | Inst::Pow2
+            | Inst::IsOdd
+            | Inst::ILog2 => Self::pure(1, 1),
+            Inst::Eqw => Self::new(8, 0, 1),
+            Inst::Ext2Add | Inst::Ext2Sub | Inst::Ext2Mul | Inst::Ext2Div => Self::pure(4, 2),
+            Inst::Ext2Neg | Inst::Ext2Inv => Self::pure(2, 2),
+            // u32 operations
+            Inst::U32Test => Self::new(1, 0, 1),
+            Inst::U32TestW => Self::new(4, 0, 1),
+            Inst::U32Split => Self::pure(1, 2),
+            Inst::U32WrappingAdd
+            | Inst::U32WrappingSub
+            | Inst::U32WrappingMul
+            | Inst::U32Div
+            | Inst::U32Mod
+            | Inst::U32And
+            | Inst::U32Or
+            | Inst::U32Xor
+            | Inst::U32Shl
+            | Inst::U32Shr
+            | Inst::U32Rotl
+            | Inst::U32Rotr
+            | Inst::U32Lt
+            | Inst::U32Lte
+            | Inst::U32Gt
+            | Inst::U32Gte
+            | Inst::U32Min
+            | Inst::U32Max => Self::pure(2, 1),
+            Inst::U32Cast
+            | Inst::U32WrappingAddImm(_)
+            | Inst::U32WrappingSubImm(_)
+            | Inst::U32WrappingMulImm(_)
+            | Inst::U32DivImm(_)
+            | Inst::U32ModImm(_)
+            | Inst::U32ShlImm(_)
+            | Inst::U32ShrImm(_)
+            | Inst::U32RotlImm(_)
+            | Inst::U32RotrImm(_)
+            | Inst::U32Not
+            | Inst::U32Popcnt
+            | Inst::U32Clz
+            | Inst::U32Ctz
+            | Inst::U32Clo
+            | Inst::U32Cto => Self::pure(1, 1),
+            Inst::U32OverflowingAdd
+            | Inst::U32OverflowingSub
+            | Inst::U32WideningAdd
+            | Inst::U32WideningMul
+            | Inst::U32DivMod => Self::pure(2, 2),
+            Inst::U32OverflowingAddImm(_)
+            | Inst::U32OverflowingSubImm(_)
+            | Inst::U32WideningAddImm(_)
+            | Inst::U32WideningMulImm(_)
+            | Inst::U32DivModImm(_) => Self::pure(1, 2),
+            Inst::U32OverflowingAdd3 | Inst::U32WideningAdd3 | Inst::U32WideningMadd => {
+                Self::pure(3, 2)
+            }
+            Inst::U32WrappingAdd3 | Inst::U32WrappingMadd => Self::pure(3, 1),
+            // Memory and locals
+            Inst::MemLoad => Self::pure(1, 1),
+            Inst::MemLoadImm(_) | Inst::LocLoad(_) | Inst::Locaddr(_) => Self::pure(0, 1),
+            Inst::MemStore => Self::pure(2, 0),
+            Inst::MemStoreImm(_) | Inst::LocStore(_) => Self::pure(1, 0),
+            // Cryptographic operations
+            Inst::Hash => Self::pure(4, 4),
+            Inst::HMerge => Self::pure(8, 4),
+            Inst::HPerm => Self::pure(12, 12),
+            Inst::AdvPipe | Inst::MemStream => Self::pure(13, 13),
+            _ => return None,
+        })
+    }
+}
+
+/// A violation of the expected operand stack depth, found by [verify_procedure]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackViolation {
+    /// An instruction accesses more elements than are known to be on the operand stack
+    Underflow {
+        span: SourceSpan,
+        inst: String,
+        required: usize,
+        depth: usize,
+    },
+    /// The branches of an `if.true` leave a different number of elements on the operand stack
+    BranchMismatch {
+        span: SourceSpan,
+        then_depth: usize,
+        else_depth: usize,
+    },
+    /// The body of a `while.true` does not leave as many elements on the operand stack as there
+    /// were when the loop was entered
+    LoopMismatch {
+        span: SourceSpan,
+        expected: usize,
+        found: usize,
+    },
+    /// The procedure does not leave exactly its results on the operand stack
+    ResultMismatch { expected: usize, found: usize },
+}
+
+impl StackViolation {
+    /// The span of the code responsible for this violation, if it is not the procedure itself
+    pub fn span(&self) -> Option<SourceSpan> {
+        match self {
+            Self::Underflow { span, .. }
+            | Self::BranchMismatch { span, .. }
+            | Self::LoopMismatch { span, .. } => Some(*span),
+            Self::ResultMismatch { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for StackViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Underflow {
+                inst,
+                required,
+                depth,
+                ..
+            } => write!(
+                f,
+                "'{inst}' accesses {required} elements of the operand stack, but only {depth} are \
+                 known to be there"
+            ),
+            Self::BranchMismatch {
+                then_depth,
+                else_depth,
+                ..
+            } => write!(
+                f,
+                "the branches of this 'if.true' leave {then_depth} and {else_depth} elements on \
+                 the operand stack, respectively"
+            ),
+            Self::LoopMismatch {
+                expected, found, ..
+            } => write!(
+                f,
+                "the body of this 'while.true' leaves {found} elements on the operand stack, but \
+                 {expected} were there when the loop was entered"
+            ),
+            Self::ResultMismatch { expected, found } => write!(
+                f,
+                "the procedure leaves {found} elements on the operand stack, but its results \
+                 occupy {expected}"
+            ),
+        }
+    }
+}
+
+/// Verify the operand stack depth of a procedure with `body`, which is passed `params` elements on
+/// the operand stack, and leaves `results` elements on it.
+///
+/// The effect of each invocation in `body` is obtained from `invocations`, see
+/// [StackEffect::of].
+pub fn verify_procedure(
+    body: &masm::Block,
+    params: usize,
+    results: usize,
+    invocations: &dyn Fn(&masm::InvocationTarget) -> Option<StackEffect>,
+) -> Result<(), StackViolation> {
+    let verifier = Verifier { invocations };
+    match verifier.block(body, Depth::Known(params))? {
+        Depth::Known(found) if found != results => Err(StackViolation::ResultMismatch {
+            expected: results,
+            found,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// The number of elements on the operand stack at some point in a procedure
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Depth {
+    Known(usize),
+    /// An instruction with an unknown effect on the operand stack was executed
+    Unknown,
+    /// The code is unreachable, as the code before it is guaranteed to trap
+    Diverged,
+}
+
+struct Verifier<'a> {
+    invocations: &'a dyn Fn(&masm::InvocationTarget) -> Option<StackEffect>,
+}
+
+impl Verifier<'_> {
+    fn block(&self, block: &masm::Block, mut depth: Depth) -> Result<Depth, StackViolation> {
+        // Whether the last instruction pushed a zero, so that an `assert` following it always fails
+        let mut pushed_zero = false;
+        for op in block.iter() {
+            let Depth::Known(current) = depth else {
+                break;
+            };
+            match op {
+                Op::Inst(inst) => {
+                    let span = inst.span();
+                    let inst = inst.inner();
+                    if pushed_zero && matches!(inst, Inst::Assert | Inst::AssertWithError(_)) {
+                        depth = Depth::Diverged;
+                        continue;
+                    }
+                    pushed_zero = pushes_zero(inst);
+                    depth = match StackEffect::of(inst, self.invocations) {
+                        Some(effect) => {
+                            if effect.required > current {
+                                return Err(StackViolation::Underflow {
+                                    span,
+                                    inst: inst.to_pretty_string(),
+                                    required: effect.required,
+                                    depth: current,
+                                });
+                            }
+                            Depth::Known(current - effect.consumed + effect.produced)
+                        }
+                        None => Depth::Unknown,
+                    };
+                }
+                Op::If {
+                    span,
+                    then_blk,
+                    else_blk,
+                } => {
+                    pushed_zero = false;
+                    let inner = pop_condition(*span, "if.true", current)?;
+                    let then_depth = self.block(then_blk, Depth::Known(inner))?;
+                    let else_depth = self.block(else_blk, Depth::Known(inner))?;
+                    depth = match (then_depth, else_depth) {
+                        (Depth::Diverged, depth) | (depth, Depth::Diverged) => depth,
+                        (Depth::Unknown, _) | (_, Depth::Unknown) => Depth::Unknown,
+                        (Depth::Known(then_depth), Depth::Known(else_depth)) => {
+                            if then_depth != else_depth {
+                                return Err(StackViolation::BranchMismatch {
+                                    span: *span,
+                                    then_depth,
+                                    else_depth,
+                                });
+                            }
+                            Depth::Known(then_depth)
+                        }
+                    };
+                }
+                Op::While { span, body } => {
+                    pushed_zero = false;
+                    let inner = pop_condition(*span, "while.true", current)?;
+                    depth = match self.block(body, Depth::Known(inner))? {
+                        Depth::Known(found) if found != current => {
+                            return Err(StackViolation::LoopMismatch {
+                                span: *span,
+                                expected: current,
+                                found,
+                            });
+                        }
+                        Depth::Unknown => Depth::Unknown,
+                        // The loop is exited once the condition on top of the stack is false
+                        Depth::Known(_) | Depth::Diverged => Depth::Known(inner),
+                    };
+                }
+                Op::Repeat { count, body, .. } => {
+                    pushed_zero = false;
+                    let count = match count {
+                        masm::Immediate::Value(count) => u64::from(*count.inner()),
+                        masm::Immediate::Constant(_) => {
+                            depth = Depth::Unknown;
+                            continue;
+                        }
+                    };
+                    for _ in 0..count {
+                        let next = self.block(body, depth)?;
+                        // Once an iteration leaves the depth unchanged, so will the remaining ones
+                        let unchanged = next == depth;
+                        depth = next;
+                        if unchanged || !matches!(depth, Depth::Known(_)) {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(depth)
+    }
+}
+
+/// Pop the condition of the control flow op `name` from an operand stack of `depth` elements
+fn pop_condition(span: SourceSpan, name: &str, depth: usize) -> Result<usize, StackViolation> {
+    depth.checked_sub(1).ok_or_else(|| StackViolation::Underflow {
+        span,
+        inst: name.into(),
+        required: 1,
+        depth,
+    })
+}
+
+/// Returns true if `inst` leaves a zero on top of the operand stack
+fn pushes_zero(inst: &Inst) -> bool {
+    match inst {
+        Inst::PushFeltList(values) => values.last() == Some(&Felt::ZERO),
+        inst => pushed_felt(inst) == Some(Felt::ZERO),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{
+        emit::{dup_from_offset, movup_from_offset},
+        masm::Span,
+    };
+
+    fn block(ops: Vec<Op>) -> masm::Block {
+        masm::Block::new(SourceSpan::default(), ops)
+    }
+
+    fn inst(inst: Inst) -> Op {
+        Op::Inst(Span::unknown(inst))
+    }
+
+    fn push(value: u32) -> Op {
+        inst(Inst::Push(masm::Immediate::Value(Span::unknown(masm::PushValue::Int(
+            masm::IntValue::U32(value),
+        )))))
+    }
+
+    fn if_true(then_blk: Vec<Op>, else_blk: Vec<Op>) -> Op {
+        Op::If {
+            span: SourceSpan::default(),
+            then_blk: block(then_blk),
+            else_blk: block(else_blk),
+        }
+    }
+
+    fn exec(name: &str) -> Op {
+        let name = masm::Ident::new(name).unwrap();
+        inst(Inst::Exec(masm::InvocationTarget::Symbol(name)))
+    }
+
+    fn verify(params: usize, results: usize, ops: Vec<Op>) -> Result<(), StackViolation> {
+        verify_procedure(
+            &block(ops),
+            params,
+            results,
+            &|target| match target.to_string().as_str() {
+                "add3" => Some(StackEffect::pure(3, 1)),
+                _ => None,
+            },
+        )
+    }
+
+    #[test]
+    fn stack_verifier_accepts_balanced_code() {
+        let ops =
+            vec![dup_from_offset(1), movup_from_offset(2), Inst::Add, Inst::Swap1, Inst::Drop];
+        assert_eq!(verify(2, 1, ops.into_iter().map(inst).collect()), Ok(()));
+
+        let ops =
+            vec![push(1), if_true(vec![inst(Inst::Add)], vec![inst(Inst::Mul)]), exec("add3")];
+        assert_eq!(verify(4, 1, ops), Ok(()));
+    }
+
+    #[test]
+    fn stack_verifier_rejects_shuffles_below_the_known_operands() {
+        let ops = vec![push(1), inst(movup_from_offset(3)), inst(Inst::Drop)];
+        assert_eq!(
+            verify(2, 2, ops),
+            Err(StackViolation::Underflow {
+                span: SourceSpan::default(),
+                inst: "movup.3".into(),
+                required: 4,
+                depth: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn stack_verifier_rejects_mismatched_branches() {
+        let ops = vec![if_true(vec![inst(Inst::Drop)], vec![])];
+        let error = verify(2, 1, ops).unwrap_err();
+        assert_eq!(
+            error,
+            StackViolation::BranchMismatch {
+                span: SourceSpan::default(),
+                then_depth: 0,
+                else_depth: 1,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "the branches of this 'if.true' leave 0 and 1 elements on the operand stack, \
+             respectively"
+        );
+    }
+
+    #[test]
+    fn stack_verifier_allows_unreachable_branches() {
+        let unreachable = vec![push(0), inst(Inst::Assert)];
+        let ops = vec![if_true(vec![inst(Inst::Drop)], unreachable)];
+        assert_eq!(verify(2, 0, ops), Ok(()));
+    }
+
+    #[test]
+    fn stack_verifier_rejects_unbalanced_loops() {
+        // The body pushes the condition for the next iteration, but does not consume the value
+        // it loaded
+        let ops = vec![
+            push(1),
+            Op::While {
+                span: SourceSpan::default(),
+                body: block(vec![inst(Inst::MemLoadImm(0u32.into())), push(0)]),
+            },
+        ];
+        assert_eq!(
+            verify(0, 0, ops),
+            Err(StackViolation::LoopMismatch {
+                span: SourceSpan::default(),
+                expected: 1,
+                found: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn stack_verifier_rejects_extra_results() {
+        let ops = vec![inst(dup_from_offset(0)), inst(Inst::Add), push(1)];
+        assert_eq!(
+            verify(1, 1, ops),
+            Err(StackViolation::ResultMismatch {
+                expected: 1,
+                found: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn stack_verifier_stops_at_unknown_invocations() {
+        let ops = vec![exec("unknown"), inst(Inst::Drop), inst(Inst::Drop)];
+        assert_eq!(verify(0, 0, ops), Ok(()));
+    }
+}
@@ -974,16 +974,24 @@ pub trait HirOpBuilder<'f, B: ?Sized + Builder> {
         op_builder(callee, signature, args)
     }
 
-    /*
-    fn inline_asm(
-        self,
-        args: &[Value],
-        results: impl IntoIterator<Item = Type>,
+    /// Splice the Miden Assembly in `template` into the current function.
+    ///
+    /// `signature` describes the effect of `template` on the operand stack, see
+    /// [crate::ops::InlineAsm] for details.
+    fn inline_asm<A>(
+        &mut self,
+        template: impl Into<CompactString>,
+        signature: Signature,
+        args: A,
         span: SourceSpan,
-    ) -> MasmBuilder<Self> {
-        MasmBuilder::new(self, args, results.into_iter().collect(), span)
+    ) -> Result<UnsafeIntrusiveEntityRef<crate::ops::InlineAsm>, Report>
+    where
+        A: IntoIterator<Item = ValueRef>,
+    {
+        let op_builder =
+            self.builder_mut().create::<crate::ops::InlineAsm, (CompactString, _, A)>(span);
+        op_builder(template.into(), signature, args)
     }
-     */
 
     fn builder(&self) -> &B;
     fn builder_mut(&mut self) -> &mut B;
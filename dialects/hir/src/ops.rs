@@ -1,4 +1,5 @@
 mod advice;
+mod asm;
 mod assertions;
 mod cast;
 mod constants;
@@ -10,6 +11,6 @@ mod primop;
 mod spills;
 
 pub use self::{
-    advice::*, assertions::*, cast::*, constants::*, crypto::*, events::*, invoke::*, mem::*,
-    primop::*, spills::*,
+    advice::*, asm::*, assertions::*, cast::*, constants::*, crypto::*, events::*, invoke::*,
+    mem::*, primop::*, spills::*,
 };
@@ -0,0 +1,209 @@
+use alloc::{format, sync::Arc};
+
+use miden_assembly_syntax::{
+    PathBuf as LibraryPath,
+    ast::{self as masm, ModuleKind},
+};
+use midenc_hir::{
+    derive::{EffectOpInterface, operation},
+    diagnostics::{SourceLanguage, SourceManager, Uri},
+    dialects::builtin::attributes::{SignatureAttr, StringAttr},
+    effects::*,
+    print::AsmPrinter,
+    traits::*,
+    *,
+};
+
+use crate::HirDialect;
+
+/// Splice raw Miden Assembly into the current function.
+///
+/// The `template` is the body of a procedure, e.g. `u32clz`, which is emitted in place of this op.
+/// The `signature` describes its effect on the operand stack: on entry, the arguments are on top
+/// of the operand stack, with the first argument on top, and on exit, the template must have
+/// replaced them with the results, with the first result on top. Operands which do not fit in a
+/// single element are laid out as for any other procedure call, see the calling conventions.
+///
+/// The template is parsed when this op is built, so a malformed template is rejected early. It may
+/// contain control flow, but may not invoke other procedures, or refer to procedure locals, as
+/// those are not visible from the template.
+#[derive(EffectOpInterface)]
+#[operation(
+    dialect = HirDialect,
+    implements(
+        InferTypeOpInterface,
+        AdviceEffectOpInterface,
+        MemoryEffectOpInterface,
+        OperandRangeRequirementOpInterface,
+        OpPrinter
+    )
+)]
+#[effects(
+    MemoryEffect(MemoryEffect::Read, MemoryEffect::Write),
+    AdviceEffect(
+        AdviceEffect::Read,
+        AdviceEffect::Write,
+        AdviceEffect::Allocate,
+        AdviceEffect::Free,
+    )
+)]
+pub struct InlineAsm {
+    #[attr]
+    template: StringAttr,
+    #[attr(hidden)]
+    signature: SignatureAttr,
+    #[operands]
+    arguments: AnyType,
+}
+
+impl InlineAsm {
+    /// Parse the template of this op, returning the block of Miden Assembly it represents
+    pub fn parse_template(&self, context: &Context) -> Result<masm::Block, Report> {
+        parse_template(self.get_template(), context.source_manager())
+    }
+}
+
+/// Parse `template` as the body of a procedure
+pub fn parse_template(
+    template: &str,
+    source_manager: Arc<dyn SourceManager + Send + Sync>,
+) -> Result<masm::Block, Report> {
+    let source = format!("proc inline_asm\n{template}\nend\n");
+    let source_file = source_manager.load(SourceLanguage::Masm, Uri::new("inline_asm"), source);
+    let path = LibraryPath::new("::inline_asm").expect("invalid module name");
+    let module = masm::Module::parse(path, ModuleKind::Library, source_file, source_manager)
+        .map_err(|err| err.wrap_err(format!("invalid hir.inline_asm template '{template}'")))?;
+
+    // The template could close the procedure we wrapped it in, and define others
+    let mut procedures = module.procedures();
+    let (Some(procedure), None) = (procedures.next(), procedures.next()) else {
+        return Err(Report::msg(format!(
+            "invalid hir.inline_asm template '{template}': expected the body of a single procedure"
+        )));
+    };
+    let body = procedure.body().clone();
+    if let Some(invoked) = find_invocation(&body) {
+        return Err(Report::msg(format!(
+            "invalid hir.inline_asm template '{template}': invoking procedures is not supported, \
+             found '{invoked}'"
+        )));
+    }
+
+    Ok(body)
+}
+
+/// Returns the first instruction in `block` which invokes or references a procedure, if any
+fn find_invocation(block: &masm::Block) -> Option<&masm::Instruction> {
+    block.iter().find_map(|op| match op {
+        masm::Op::Inst(inst) => match inst.inner() {
+            inst @ (masm::Instruction::Exec(_)
+            | masm::Instruction::Call(_)
+            | masm::Instruction::SysCall(_)
+            | masm::Instruction::ProcRef(_)) => Some(inst),
+            _ => None,
+        },
+        masm::Op::If {
+            then_blk, else_blk, ..
+        } => find_invocation(then_blk).or_else(|| find_invocation(else_blk)),
+        masm::Op::While { body, .. } | masm::Op::Repeat { body, .. } => find_invocation(body),
+    })
+}
+
+impl InferTypeOpInterface for InlineAsm {
+    fn infer_return_types(&mut self, context: &Context) -> Result<(), Report> {
+        let arity = self.get_signature().arity();
+        if self.arguments().len() != arity {
+            return Err(Report::msg(format!(
+                "invalid hir.inline_asm: expected {arity} operand(s), but got {}",
+                self.arguments().len()
+            )));
+        }
+
+        self.parse_template(context)?;
+
+        let span = self.span();
+        let sig = self.signature.borrow();
+        let owner = self.as_operation_ref();
+        for (i, result) in sig.results().iter().enumerate() {
+            let value = context.make_result(span, result.ty.clone(), owner, i as u8);
+            self.op.results.push(value);
+        }
+        Ok(())
+    }
+}
+
+impl OperandRangeRequirementOpInterface for InlineAsm {
+    fn operand_range_requirement(&self, _operand_index: usize) -> OperandRangeRequirement {
+        OperandRangeRequirement::None
+    }
+}
+
+impl OpPrinter for InlineAsm {
+    fn print(&self, printer: &mut AsmPrinter<'_>) {
+        use formatter::*;
+
+        printer.print_space();
+        printer.print_string(self.get_template().as_str());
+        printer.print_operand_list(self.arguments());
+        *printer += const_text(" : ");
+        self.signature().print(printer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use midenc_hir::{SourceSpan, Type, dialects::builtin::attributes::Signature, testing::Test};
+
+    use crate::HirOpBuilder;
+
+    fn build_inline_asm(name: &str, template: &str) -> Result<(), midenc_hir::Report> {
+        let mut test = Test::named(name).in_module("test");
+        test.with_function("f", &[Type::U32], &[Type::U32]);
+        let signature = Signature::new(&test.context_rc(), [Type::U32], [Type::U32]);
+        let mut builder = test.function_builder();
+        let entry = builder.entry_block();
+        let arg = entry.borrow().arguments()[0].borrow().as_value_ref();
+        builder
+            .inline_asm(template, signature, [arg], SourceSpan::default())
+            .map(|_| ())
+    }
+
+    #[test]
+    fn inline_asm_accepts_valid_template() {
+        build_inline_asm("inline_asm_accepts_valid_template", "u32clz\nadd.1").unwrap();
+    }
+
+    #[test]
+    fn inline_asm_rejects_malformed_template() {
+        let err = build_inline_asm("inline_asm_rejects_malformed_template", "u32clz.foo")
+            .expect_err("expected malformed template to be rejected");
+        assert!(format!("{err}").contains("invalid hir.inline_asm template"), "{err}");
+    }
+
+    #[test]
+    fn inline_asm_rejects_procedure_definitions() {
+        let err = build_inline_asm(
+            "inline_asm_rejects_procedure_definitions",
+            "u32clz\nend\n\nproc other\nadd",
+        )
+        .expect_err("expected template defining a procedure to be rejected");
+        assert!(format!("{err}").contains("body of a single procedure"), "{err}");
+    }
+
+    #[test]
+    fn inline_asm_rejects_invocations() {
+        let err = build_inline_asm("inline_asm_rejects_invocations", "exec.::foo::bar")
+            .expect_err("expected template invoking a procedure to be rejected");
+        assert!(format!("{err}").contains("invoking procedures is not supported"), "{err}");
+    }
+
+    #[test]
+    fn parse_template_splits_instructions() {
+        let test = Test::default();
+        let body =
+            super::parse_template("u32clz\nadd.1", test.context_rc().source_manager()).unwrap();
+        assert_eq!(body.iter().count(), 2);
+    }
+}
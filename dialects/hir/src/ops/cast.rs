@@ -134,8 +134,8 @@ pub struct IntToPtr {
 }
 
 impl InferTypeOpInterface for IntToPtr {
-    fn infer_return_types(&mut self, _context: &Context) -> Result<(), Report> {
-        let ty = self.get_ty().clone();
+    fn infer_return_types(&mut self, context: &Context) -> Result<(), Report> {
+        let ty = context.intern_type(self.get_ty().clone());
         self.result_mut().set_type(ty);
         Ok(())
     }
@@ -238,8 +238,8 @@ pub struct Bitcast {
 }
 
 impl InferTypeOpInterface for Bitcast {
-    fn infer_return_types(&mut self, _context: &Context) -> Result<(), Report> {
-        let ty = self.get_ty().clone();
+    fn infer_return_types(&mut self, context: &Context) -> Result<(), Report> {
+        let ty = context.intern_type(self.get_ty().clone());
         self.result_mut().set_type(ty);
         Ok(())
     }
@@ -29,8 +29,9 @@ pub struct ConstantPointer {
 }
 
 impl InferTypeOpInterface for ConstantPointer {
-    fn infer_return_types(&mut self, _context: &Context) -> Result<(), Report> {
-        let ty = Type::from(PointerType::new(self.value().pointee_type().clone()));
+    fn infer_return_types(&mut self, context: &Context) -> Result<(), Report> {
+        let ty =
+            context.intern_type(Type::from(PointerType::new(self.value().pointee_type().clone())));
         self.result_mut().set_type(ty);
 
         Ok(())
@@ -25,11 +25,11 @@ pub struct LocalAddress {
 }
 
 impl InferTypeOpInterface for LocalAddress {
-    fn infer_return_types(&mut self, _context: &Context) -> Result<(), Report> {
-        let ty = Type::from(PointerType::new_with_address_space(
+    fn infer_return_types(&mut self, context: &Context) -> Result<(), Report> {
+        let ty = context.intern_type(Type::from(PointerType::new_with_address_space(
             self.get_local().ty(),
             AddressSpace::Element,
-        ));
+        )));
         self.result_mut().set_type(ty);
         Ok(())
     }
@@ -40,10 +40,10 @@ impl Pass for TransformSpills {
         state: &mut PassExecutionState,
     ) -> Result<(), Report> {
         let function = op.into_entity_ref();
-        log::debug!(target: "insert-spills", "computing and inserting spills for {}", function.as_operation());
+        midenc_hir::scoped_debug!(target: "insert-spills", "computing and inserting spills for {}", function.as_operation());
 
         if function.is_declaration() {
-            log::debug!(target: "insert-spills", "function has no body, no spills needed!");
+            midenc_hir::scoped_debug!(target: "insert-spills", "function has no body, no spills needed!");
             state.preserved_analyses_mut().preserve_all();
             state.set_post_pass_status(PostPassStatus::Unchanged);
             return Ok(());
@@ -52,7 +52,7 @@ impl Pass for TransformSpills {
             state.analysis_manager().get_analysis_for::<SpillAnalysis, Function>()?;
 
         if !analysis.has_spills() {
-            log::debug!(target: "insert-spills", "no spills needed!");
+            midenc_hir::scoped_debug!(target: "insert-spills", "no spills needed!");
             state.preserved_analyses_mut().preserve_all();
             state.set_post_pass_status(PostPassStatus::Unchanged);
             return Ok(());
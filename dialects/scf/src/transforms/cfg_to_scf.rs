@@ -5,7 +5,7 @@ use midenc_dialect_cf::{self as cf, ControlFlowOpBuilder};
 use midenc_dialect_ub::UndefinedBehaviorOpBuilder;
 use midenc_hir::{
     Builder, EntityMut, Forward, Op, Operation, OperationName, OperationRef, RawWalk, Report,
-    SmallVec, Spanned, Type, ValueRange, ValueRef, WalkResult,
+    SmallVec, Spanned, TraceTarget, Type, ValueRange, ValueRef, WalkResult,
     diagnostics::Severity,
     dialects::builtin,
     dominance::DominanceInfo,
@@ -73,7 +73,7 @@ impl Pass for LiftControlFlowToSCF {
         let root = op.as_operation_ref();
         drop(op);
 
-        log::debug!(target: "cfg-to-scf", "applying control flow lifting transformation pass starting from {}", root.borrow());
+        midenc_hir::scoped_debug!(target: "cfg-to-scf", "applying control flow lifting transformation pass starting from {}", root.borrow());
 
         let result = root.raw_prewalk::<Forward, _, _>(|operation: OperationRef| -> WalkResult {
             let op = operation.borrow();
@@ -94,8 +94,17 @@ impl Pass for LiftControlFlowToSCF {
                 };
                 let dominfo = Rc::make_mut(&mut dominfo);
 
+                let mut trace_target = TraceTarget::category("cfg-to-scf");
+                if let Some(name) = op.symbol_name_if_symbol() {
+                    trace_target = trace_target.with_relevant_symbol(name);
+                }
+                // Scope the logs of the transformation to the function it is applied to, rather
+                // than the operation the pass is running on
+                let symbol = op.symbol_name_if_symbol().map(|name| name.as_str());
+                let _log_scope = midenc_hir::logging::scope("pass", "", symbol);
+
                 let visitor = |inner: OperationRef| -> WalkResult {
-                    log::debug!(target: "cfg-to-scf", "applying control flow lifting to {}", inner.borrow());
+                    midenc_hir::scoped_debug!(target: &trace_target, "applying control flow lifting to {}", inner.borrow());
                     let mut next_region = inner.borrow().regions().front().as_pointer();
                     while let Some(region) = next_region.take() {
                         next_region = region.next();
@@ -104,8 +113,8 @@ impl Pass for LiftControlFlowToSCF {
                             transforms::transform_cfg_to_scf(region, &mut transformation, dominfo);
                         match result {
                             Ok(did_change) => {
-                                log::trace!(
-                                    target: "cfg-to-scf",
+                                midenc_hir::scoped_trace!(
+                                    target: &trace_target,
                                     "control flow lifting completed for region \
                                      (did_change={did_change})"
                                 );
@@ -131,7 +140,7 @@ impl Pass for LiftControlFlowToSCF {
                 || op.is::<builtin::Module>()
             {
                 // We only care to recurse into ops that can contain functions
-                log::trace!(
+                midenc_hir::scoped_trace!(
                     target: "cfg-to-scf",
                     "looking for functions to apply control flow lifting to in '{}'",
                     op.name()
@@ -139,7 +148,7 @@ impl Pass for LiftControlFlowToSCF {
                 WalkResult::Continue(())
             } else {
                 // Skip all other ops
-                log::trace!("skipping control flow lifting for '{}'", op.name());
+                midenc_hir::scoped_trace!("skipping control flow lifting for '{}'", op.name());
                 WalkResult::Skip
             }
         });
@@ -149,7 +158,7 @@ impl Pass for LiftControlFlowToSCF {
             return result.into_result();
         }
 
-        log::debug!(
+        midenc_hir::scoped_debug!(
             target: "cfg-to-scf",
             "control flow lifting transformation pass completed successfully (changed = {changed}"
         );
@@ -373,7 +382,7 @@ impl CFGToSCFInterface for ControlFlowToSCFTransformation {
         builder: &mut midenc_hir::OpBuilder,
         _region: midenc_hir::RegionRef,
     ) -> Result<midenc_hir::OperationRef, midenc_hir::Report> {
-        log::trace!(target: "cfg-to-scf", "creating unreachable terminator at {}", builder.insertion_point());
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "creating unreachable terminator at {}", builder.insertion_point());
         let op = builder.unreachable(span);
         Ok(op.as_operation_ref())
     }
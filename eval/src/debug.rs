@@ -0,0 +1,90 @@
+use core::fmt;
+
+use midenc_hir::{
+    CallableOpInterface, Operation, OperationRef, SmallVec, SourceSpan, Spanned, SymbolPath,
+    formatter::DisplayValues,
+};
+
+use crate::{TraceRecord, Value};
+
+/// A point at which [crate::HirEvaluator::continue_to_breakpoint] suspends evaluation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop on entry to the callable with the given symbol path, before its first operation is
+    /// evaluated
+    Symbol(SymbolPath),
+    /// Stop before evaluating any operation whose source span lies within the given span
+    Span(SourceSpan),
+}
+
+impl Breakpoint {
+    /// Returns true if evaluation should stop before evaluating `op`
+    pub fn matches(&self, op: &Operation) -> bool {
+        match self {
+            Self::Symbol(path) => is_callable_entry(op, path),
+            Self::Span(span) => {
+                let op_span = op.span();
+                op_span.source_id() == span.source_id()
+                    && op_span.start().to_usize() >= span.start().to_usize()
+                    && op_span.end().to_usize() <= span.end().to_usize()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Symbol(path) => write!(f, "{path}"),
+            Self::Span(span) => write!(f, "{span:?}"),
+        }
+    }
+}
+
+/// Returns true if `op` is the first operation of the callable region of the callable at `path`
+fn is_callable_entry(op: &Operation, path: &SymbolPath) -> bool {
+    if op.as_operation_ref().prev().is_some() {
+        return false;
+    }
+    let Some(block) = op.parent() else {
+        return false;
+    };
+    let Some(region) = block.parent() else {
+        return false;
+    };
+    let Some(callable_op) = region.parent() else {
+        return false;
+    };
+    let callable_op = callable_op.borrow();
+    let Some(callable) = callable_op.as_trait::<dyn CallableOpInterface>() else {
+        return false;
+    };
+    let is_entry = callable.get_callable_region().is_some_and(|callable_region| {
+        callable_region == region && callable_region.borrow().entry_block_ref() == Some(block)
+    });
+    is_entry && callable_op.as_symbol().is_some_and(|symbol| &symbol.path() == path)
+}
+
+/// The outcome of advancing evaluation via [crate::HirEvaluator::step] or
+/// [crate::HirEvaluator::continue_to_breakpoint]
+#[derive(Debug, Clone)]
+pub enum StepEvent {
+    /// An operation was evaluated, and evaluation is suspended before the next one
+    Evaluated(TraceRecord),
+    /// Evaluation is suspended before the given operation, as it matches a breakpoint
+    Breakpoint(OperationRef),
+    /// Evaluation finished, producing the given results
+    Finished(SmallVec<[Value; 1]>),
+}
+
+impl fmt::Display for StepEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Evaluated(record) => write!(f, "{record}"),
+            Self::Breakpoint(op) => write!(f, "breakpoint at {}", op.borrow().name()),
+            Self::Finished(results) => {
+                write!(f, "finished with [{}]", DisplayValues::new(results.iter()))
+            }
+        }
+    }
+}
@@ -67,14 +67,49 @@ pub enum ControlFlowEffect {
     },
 }
 
+/// Initialize the memory shared by the modules of this component
 impl Initialize for builtin::Component {
-    fn initialize(&self, _evaluator: &mut HirEvaluator) -> Result<(), Report> {
-        todo!("visit all global variables in all modules of this component")
+    fn initialize(&self, evaluator: &mut HirEvaluator) -> Result<(), Report> {
+        let body = self.body();
+        let Some(block) = body.entry_block_ref() else {
+            return Ok(());
+        };
+        for op in block.borrow().body() {
+            if let Some(module) = op.downcast_ref::<builtin::Module>() {
+                module.initialize(evaluator)?;
+            }
+        }
+        Ok(())
     }
 }
+
+/// Initialize memory with the data segments of this module
+///
+/// NOTE: Global variables are not yet supported, as their addresses are only assigned when linking
+/// the component during code generation.
 impl Initialize for builtin::Module {
-    fn initialize(&self, _evaluator: &mut HirEvaluator) -> Result<(), Report> {
-        todo!("visit all global variables in this module")
+    fn initialize(&self, evaluator: &mut HirEvaluator) -> Result<(), Report> {
+        let body = self.body();
+        let Some(block) = body.entry_block_ref() else {
+            return Ok(());
+        };
+        for op in block.borrow().body() {
+            if let Some(segment) = op.downcast_ref::<builtin::Segment>() {
+                segment.initialize(evaluator)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Initialize for builtin::Segment {
+    fn initialize(&self, evaluator: &mut HirEvaluator) -> Result<(), Report> {
+        let data = self.initializer();
+        evaluator.current_context_mut().initialize_memory(
+            *self.get_offset(),
+            data.as_slice(),
+            self.span(),
+        )
     }
 }
 
@@ -108,12 +143,26 @@ impl Eval for ub::Unreachable {
 }
 
 // Debug info operations are purely observational and have no runtime semantics.
+//
+// The variables they refer to are recorded in the trace, if enabled, see [TraceRecord::variable].
 impl Eval for debuginfo::DebugValue {
     fn eval(&self, _evaluator: &mut HirEvaluator) -> Result<ControlFlowEffect, Report> {
         Ok(ControlFlowEffect::None)
     }
 }
 
+impl Eval for debuginfo::DebugDeclare {
+    fn eval(&self, _evaluator: &mut HirEvaluator) -> Result<ControlFlowEffect, Report> {
+        Ok(ControlFlowEffect::None)
+    }
+}
+
+impl Eval for debuginfo::DebugKill {
+    fn eval(&self, _evaluator: &mut HirEvaluator) -> Result<ControlFlowEffect, Report> {
+        Ok(ControlFlowEffect::None)
+    }
+}
+
 impl Eval for ub::Poison {
     fn eval(&self, evaluator: &mut HirEvaluator) -> Result<ControlFlowEffect, Report> {
         let value = match self.value().as_immediate() {
@@ -454,33 +503,51 @@ impl Eval for hir::Bitcast {
 
 impl Eval for hir::Exec {
     fn eval(&self, evaluator: &mut HirEvaluator) -> Result<ControlFlowEffect, Report> {
-        let Some(symbol_table) = self.as_operation().nearest_symbol_table() else {
-            return Err(evaluator.report(
-                "evaluation failed",
-                self.span(),
-                "cannot evaluate function calls without a symbol table in scope",
-            ));
-        };
+        let callee = self.callee();
+        eval_call(self.as_operation(), callee.path(), self.arguments(), evaluator)
+    }
+}
 
-        let symbol_table = symbol_table.borrow();
-        let symbol_table = symbol_table.as_symbol_table().unwrap();
+impl Eval for hir::Call {
+    fn eval(&self, evaluator: &mut HirEvaluator) -> Result<ControlFlowEffect, Report> {
         let callee = self.callee();
-        let symbol_path = callee.path();
-        let Some(symbol) = symbol_table.resolve(symbol_path) else {
-            return Err(evaluator.report(
-                "evaluation failed",
-                self.span(),
-                format!("unable to resolve callee '{symbol_path}'"),
-            ));
-        };
+        eval_call(self.as_operation(), callee.path(), self.arguments(), evaluator)
+    }
+}
+
+/// Resolve `symbol_path` from the nearest symbol table of `op`, and transfer control to it.
+///
+/// The evaluator is responsible for managing the call stack, see [HirEvaluator::eval_callable].
+fn eval_call(
+    op: &midenc_hir::Operation,
+    symbol_path: &midenc_hir::SymbolPath,
+    arguments: midenc_hir::OpOperandRange<'_>,
+    evaluator: &mut HirEvaluator,
+) -> Result<ControlFlowEffect, Report> {
+    let Some(symbol_table) = op.nearest_symbol_table() else {
+        return Err(evaluator.report(
+            "evaluation failed",
+            op.span(),
+            "cannot evaluate function calls without a symbol table in scope",
+        ));
+    };
 
-        let arguments = ValueRange::<4>::from(self.arguments()).into_owned();
+    let symbol_table = symbol_table.borrow();
+    let symbol_table = symbol_table.as_symbol_table().unwrap();
+    let Some(symbol) = symbol_table.resolve(symbol_path) else {
+        return Err(evaluator.report(
+            "evaluation failed",
+            op.span(),
+            format!("unable to resolve callee '{symbol_path}'"),
+        ));
+    };
 
-        Ok(ControlFlowEffect::Call {
-            callee: symbol.borrow().as_operation_ref(),
-            arguments,
-        })
-    }
+    let arguments = ValueRange::<4>::from(arguments).into_owned();
+
+    Ok(ControlFlowEffect::Call {
+        callee: symbol.borrow().as_operation_ref(),
+        arguments,
+    })
 }
 
 impl Eval for hir::Store {
@@ -610,13 +677,13 @@ impl Eval for hir::MemGrow {
             ));
         };
 
-        let current_size = {
-            let current_context = evaluator.current_context_mut();
-            let current_size = current_context.memory_size();
-            current_context.memory_grow(pages as usize);
-            current_size as u32
-        };
-        evaluator.set_value(self.result().as_value_ref(), Immediate::U32(current_size));
+        // Like `memory.grow` in Wasm, this returns the previous size in pages, or -1 on failure
+        let previous_size = evaluator
+            .current_context_mut()
+            .memory_grow(pages as usize)
+            .map(|size| size as u32)
+            .unwrap_or(u32::MAX);
+        evaluator.set_value(self.result().as_value_ref(), Immediate::U32(previous_size));
         Ok(ControlFlowEffect::None)
     }
 }
@@ -670,9 +737,10 @@ impl Eval for hir::MemSet {
             ));
         }
 
-        // Perform memset
-        for offset in 0..count {
-            let addr = addr_value + offset;
+        // Perform memset, `count` is in units of the pointee type
+        let stride = value_ty.size_in_bytes() as u32;
+        for index in 0..count {
+            let addr = addr_value + index * stride;
             evaluator.write_memory(addr, value)?;
         }
 
@@ -728,11 +796,15 @@ impl Eval for hir::MemCpy {
             ));
         }
 
-        // Perform memcpy
-        for offset in 0..count {
-            let src = source_value + offset;
-            let dst = dest_value + offset;
-            let value = evaluator.read_memory(src, &source_ty)?;
+        // Perform memcpy, `count` is in units of the pointee type
+        let value_ty = source_ty
+            .pointee()
+            .expect("expected pointer type to have been verified already");
+        let stride = value_ty.size_in_bytes() as u32;
+        for index in 0..count {
+            let src = source_value + index * stride;
+            let dst = dest_value + index * stride;
+            let value = evaluator.read_memory(src, value_ty)?;
             evaluator.write_memory(dst, value)?;
         }
 
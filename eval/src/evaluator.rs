@@ -1,4 +1,5 @@
 mod context;
+mod cursor;
 mod frame;
 mod memory;
 
@@ -13,15 +14,44 @@ use alloc::{
 use midenc_hir::{
     CallableOpInterface, Context, Immediate, Operation, OperationRef, RegionBranchPoint, RegionRef,
     Report, SmallVec, SourceSpan, Spanned, SymbolPath, Type, Value as _, ValueRange, ValueRef,
-    dialects::builtin::{ComponentId, attributes::LocalVariable},
+    dialects::builtin::{self, ComponentId, attributes::LocalVariable},
     formatter::DisplayValues,
     smallvec,
 };
-use midenc_session::diagnostics::{InFlightDiagnosticBuilder, Severity};
+use midenc_session::diagnostics::{Diagnostic, InFlightDiagnosticBuilder, Severity, miette};
 
-use self::{context::ExecutionContext, frame::CallFrame};
+use self::{context::ExecutionContext, cursor::Cursor, frame::CallFrame};
 use crate::{value::MaterializedValue, *};
 
+/// The default value of [HirEvaluator::max_call_depth]
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1024;
+
+/// The error raised when a call would exceed [HirEvaluator::max_call_depth], e.g. due to unbounded
+/// recursion.
+#[derive(Debug, thiserror::Error, Diagnostic)]
+#[error("evaluation failed: maximum call depth of {max_depth} exceeded")]
+#[diagnostic()]
+pub struct CallDepthExceededError {
+    /// The maximum call depth that was exceeded
+    pub max_depth: usize,
+    /// The callee of the call which exceeded the maximum call depth
+    pub callee: String,
+    #[label("this call to '{callee}' exceeds the maximum call depth")]
+    pub at: SourceSpan,
+}
+
+/// The error raised when evaluation exhausts the fuel given by [HirEvaluator::with_fuel], i.e. the
+/// program did not terminate within the given budget.
+#[derive(Debug, thiserror::Error, Diagnostic)]
+#[error("evaluation failed: ran out of fuel after evaluating {consumed} operations")]
+#[diagnostic()]
+pub struct OutOfFuelError {
+    /// The number of operations evaluated before running out of fuel
+    pub consumed: u64,
+    #[label("evaluation ran out of fuel here")]
+    pub at: SourceSpan,
+}
+
 pub struct HirEvaluator {
     /// The context in which all IR objects are allocated
     context: Rc<Context>,
@@ -29,6 +59,25 @@ pub struct HirEvaluator {
     contexts: Vec<ExecutionContext>,
     /// The stack of call frames maintained during execution, for use in constructing stack traces.
     call_stack: Vec<CallFrame>,
+    /// The maximum number of frames on `call_stack`, see [HirEvaluator::with_max_call_depth]
+    max_call_depth: usize,
+    /// The maximum number of operations to evaluate, see [HirEvaluator::with_fuel]
+    fuel: Option<u64>,
+    /// The number of operations evaluated so far
+    fuel_consumed: u64,
+    /// The trace of evaluated operations, if enabled, see [HirEvaluator::with_trace]
+    trace: Option<Trace>,
+    /// The breakpoints at which [HirEvaluator::continue_to_breakpoint] suspends evaluation
+    breakpoints: Vec<Breakpoint>,
+    /// The position of an evaluation started by [HirEvaluator::start], while it is suspended
+    suspended: Option<Cursor>,
+    /// Whether evaluation is proceeding one operation at a time, see [HirEvaluator::step]
+    stepping: bool,
+    /// The record of the operation evaluated by the current call to [HirEvaluator::step]
+    last_step: Option<TraceRecord>,
+    /// The operation at which evaluation was last suspended due to a breakpoint, so that resuming
+    /// evaluation does not stop there again
+    stopped_at: Option<OperationRef>,
     /// An unspecified set of bit flags that can be manipulated by an operation when transferring
     /// control to a successor operation. The semantics of the bits are dictated entirely by the
     /// ops in question.
@@ -54,18 +103,80 @@ impl HirEvaluator {
             contexts: vec![ExecutionContext::default()],
             printed_lines: Default::default(),
             call_stack: Default::default(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            fuel: None,
+            fuel_consumed: 0,
+            trace: None,
+            breakpoints: Default::default(),
+            suspended: None,
+            stepping: false,
+            last_step: None,
+            stopped_at: None,
             condition: 0,
             condition_set_by: None,
             ip: None,
         }
     }
 
+    /// Set the maximum depth of the call stack, [DEFAULT_MAX_CALL_DEPTH] by default.
+    ///
+    /// Calls are evaluated without recursing natively, so deeply recursive programs can be
+    /// evaluated, but a call which would exceed this depth fails with [CallDepthExceededError].
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// The maximum depth of the call stack
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+
+    /// Limit each evaluation to `fuel` operations, unlimited by default.
+    ///
+    /// Every operation evaluated consumes one unit of fuel, including each iteration of a loop or
+    /// branch, so evaluation of a program which does not terminate within this budget fails with
+    /// [OutOfFuelError], rather than hanging.
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = Some(fuel);
+        self
+    }
+
+    /// The number of operations evaluated since the last call to [HirEvaluator::reset]
+    pub fn fuel_consumed(&self) -> u64 {
+        self.fuel_consumed
+    }
+
+    /// Record a trace of each evaluation, keeping at most `capacity` operations.
+    ///
+    /// See [HirEvaluator::take_trace].
+    pub fn with_trace(mut self, capacity: usize) -> Self {
+        self.trace = Some(Trace::new(capacity));
+        self
+    }
+
+    /// Take the trace of the last evaluation, if tracing is enabled, leaving an empty trace in its
+    /// place.
+    pub fn take_trace(&mut self) -> Option<Trace> {
+        let trace = self.trace.as_mut()?;
+        let capacity = trace.capacity();
+        Some(core::mem::replace(trace, Trace::new(capacity)))
+    }
+
     /// Reset the evaluator state to start the next evaluation with a clean slate.
     pub fn reset(&mut self) {
         self.contexts.truncate(1);
         self.current_context_mut().reset();
         self.printed_lines.clear();
         self.call_stack.clear();
+        self.fuel_consumed = 0;
+        if let Some(trace) = self.trace.as_mut() {
+            trace.clear();
+        }
+        self.suspended = None;
+        self.stepping = false;
+        self.last_step = None;
+        self.stopped_at = None;
         self.condition = 0;
         self.condition_set_by = None;
         self.ip = None;
@@ -156,6 +267,9 @@ impl HirEvaluator {
             }
         }
 
+        // Initialize memory with the data segments visible to `op`
+        self.initialize_enclosing_memory(op)?;
+
         // If the root operation implements [Initialize], perform initialization now.
         if let Some(initializable) = op.as_trait::<dyn Initialize>() {
             initializable.initialize(self)?;
@@ -190,6 +304,24 @@ impl HirEvaluator {
         callable: &dyn CallableOpInterface,
         args: I,
     ) -> Result<SmallVec<[Value; 1]>, Report>
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let callable_region = self.enter_callable(callable, args)?;
+
+        // Evaluate the callable region
+        self.eval_region(callable.as_operation(), callable_region)
+    }
+
+    /// Reset the evaluator, validate `args` against the signature of `callable`, and push the
+    /// initial call frame, returning the callable region to be evaluated.
+    ///
+    /// See [HirEvaluator::eval_callable] for the conditions under which this fails.
+    fn enter_callable<I>(
+        &mut self,
+        callable: &dyn CallableOpInterface,
+        args: I,
+    ) -> Result<RegionRef, Report>
     where
         I: IntoIterator<Item = Value>,
     {
@@ -238,6 +370,9 @@ impl HirEvaluator {
             }
         }
 
+        // Initialize memory with the data segments visible to `callable`
+        self.initialize_enclosing_memory(callable.as_operation())?;
+
         // If the callable also implements [Initialize], perform initialization now.
         if let Some(initializable) = callable.as_operation().as_trait::<dyn Initialize>() {
             initializable.initialize(self)?;
@@ -254,8 +389,7 @@ impl HirEvaluator {
 
         self.call_stack.push(frame);
 
-        // Evaluate the callable region
-        self.eval_region(callable.as_operation(), callable_region)
+        Ok(callable_region)
     }
 
     /// Evaluate `symbol` in `symbol_table` with `args`, returning the results, if any, it produces.
@@ -372,6 +506,32 @@ impl HirEvaluator {
         self.eval_region(&op, callable_region)
     }
 
+    /// Initialize memory from the data segments of the component in which `op` is defined, or of
+    /// its module, if it is not part of a component.
+    fn initialize_enclosing_memory(&mut self, op: &Operation) -> Result<(), Report> {
+        let mut outermost = None;
+        let mut parent = op.parent_op();
+        while let Some(ancestor) = parent {
+            let ancestor_op = ancestor.borrow();
+            if ancestor_op.is::<builtin::Module>() {
+                outermost = Some(ancestor);
+            } else if ancestor_op.is::<builtin::Component>() {
+                outermost = Some(ancestor);
+                break;
+            }
+            parent = ancestor_op.parent_op();
+        }
+
+        let Some(outermost) = outermost else {
+            return Ok(());
+        };
+        let outermost = outermost.borrow();
+        match outermost.as_trait::<dyn Initialize>() {
+            Some(initializable) => initializable.initialize(self),
+            None => Ok(()),
+        }
+    }
+
     /// Read a value of type `ty` from `addr`
     ///
     /// Returns an error if `addr` is invalid, `ty` is not a valid immediate type, or the specified
@@ -476,6 +636,137 @@ impl HirEvaluator {
     }
 }
 
+/// Stepping
+///
+/// These allow evaluation of a callable to proceed one operation at a time, or up to the next
+/// breakpoint, inspecting the evaluator state in between, e.g. from a debugger REPL:
+///
+/// 1. Set any breakpoints via [HirEvaluator::set_breakpoint]
+/// 2. Start evaluation via [HirEvaluator::start], which suspends before the first operation
+/// 3. Advance via [HirEvaluator::step] or [HirEvaluator::continue_to_breakpoint] until
+///    [StepEvent::Finished] is produced, inspecting the suspended state via
+///    [HirEvaluator::next_op], [HirEvaluator::bindings] and [HirEvaluator::memory_range]
+impl HirEvaluator {
+    /// Suspend evaluation before any operation matching `breakpoint`.
+    ///
+    /// Breakpoints are kept when the evaluator is reset.
+    pub fn set_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    /// Remove `breakpoint`, returning true if it was set
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|bp| bp != breakpoint);
+        self.breakpoints.len() != len
+    }
+
+    /// The breakpoints which are currently set
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Start evaluating `callable` with `args`, suspended before its first operation.
+    ///
+    /// This fails under the same conditions as [HirEvaluator::eval_callable], except for errors
+    /// during evaluation, which are instead produced by the calls which advance evaluation.
+    pub fn start<I>(&mut self, callable: &dyn CallableOpInterface, args: I) -> Result<(), Report>
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let callable_region = self.enter_callable(callable, args)?;
+        let root = callable.as_operation().as_operation_ref();
+        self.ip = Some(root);
+        self.suspended = Some(Cursor::new(root, callable_region));
+        self.stepping = true;
+        Ok(())
+    }
+
+    /// Returns true if an evaluation started via [HirEvaluator::start] is suspended, i.e. it has
+    /// neither finished nor failed.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.is_some()
+    }
+
+    /// The operation that will be evaluated next, if evaluation is suspended
+    pub fn next_op(&self) -> Option<OperationRef> {
+        self.suspended.as_ref().and_then(|cursor| cursor.next_op)
+    }
+
+    /// Evaluate the next operation of a suspended evaluation.
+    ///
+    /// Produces [StepEvent::Evaluated] with the operation that was evaluated, or
+    /// [StepEvent::Finished] if that operation finished evaluation. Calls are stepped into, i.e.
+    /// the next operation after a call is the first operation of the callee.
+    pub fn step(&mut self) -> Result<StepEvent, Report> {
+        let Some(mut cursor) = self.suspended.take() else {
+            return Err(Report::msg("cannot step: there is no suspended evaluation"));
+        };
+
+        self.stopped_at = None;
+        self.last_step = None;
+        match self.step_cursor(&mut cursor) {
+            Ok(None) => {
+                self.suspended = Some(cursor);
+                let record = self.last_step.take().expect("expected an operation to be evaluated");
+                Ok(StepEvent::Evaluated(record))
+            }
+            Ok(Some(results)) => {
+                self.stepping = false;
+                Ok(StepEvent::Finished(results))
+            }
+            Err(err) => {
+                self.stepping = false;
+                Err(err)
+            }
+        }
+    }
+
+    /// Resume a suspended evaluation, until it reaches an operation matching one of the
+    /// breakpoints, producing [StepEvent::Breakpoint], or finishes, producing
+    /// [StepEvent::Finished].
+    ///
+    /// If evaluation is suspended at a breakpoint, that operation is evaluated before looking for
+    /// the next one.
+    pub fn continue_to_breakpoint(&mut self) -> Result<StepEvent, Report> {
+        loop {
+            if let Some(next_op) = self.next_op()
+                && self.stopped_at != Some(next_op)
+                && self.breakpoints.iter().any(|bp| bp.matches(&next_op.borrow()))
+            {
+                self.stopped_at = Some(next_op);
+                return Ok(StepEvent::Breakpoint(next_op));
+            }
+
+            if let finished @ StepEvent::Finished(_) = self.step()? {
+                return Ok(finished);
+            }
+        }
+    }
+
+    /// The values bound in the current call frame, ordered by value id
+    pub fn bindings(&self) -> Vec<(ValueRef, Value)> {
+        let Some(frame) = self.call_stack.last() else {
+            return vec![];
+        };
+        let mut bindings = frame.values().collect::<Vec<_>>();
+        bindings.sort_by_key(|(value, _)| value.borrow().id().as_u32());
+        bindings
+    }
+
+    /// The callees of the frames on the call stack, outermost first
+    pub fn backtrace(&self) -> Vec<OperationRef> {
+        self.call_stack.iter().map(|frame| frame.callee().as_operation_ref()).collect()
+    }
+
+    /// Read `len` bytes of memory starting at `addr`, in the current execution context
+    pub fn memory_range(&self, addr: u32, len: u32) -> Result<Vec<u8>, Report> {
+        self.current_context().read_memory_bytes(addr, len, self.current_span())
+    }
+}
+
 impl HirEvaluator {
     /// This function implements the core interpreter loop.
     ///
@@ -518,309 +809,310 @@ impl HirEvaluator {
 
         self.ip = Some(op.as_operation_ref());
 
-        let mut next_region = Some(region);
-        'region: while let Some(mut region) = next_region.take() {
-            let mut next_block = region.borrow().entry_block_ref();
-            'block: while let Some(mut block) = next_block.take() {
-                let mut next_op = block.borrow().body().front().as_pointer();
-                'op: while let Some(op) = next_op.take() {
-                    next_op = op.next();
-                    let op = op.borrow();
-                    match self.eval_op(&op)? {
-                        ControlFlowEffect::None => continue,
-                        ControlFlowEffect::Trap { span, reason } => {
-                            return Err(self
-                                .error("evaluation failed")
-                                .with_primary_label(
-                                    op.span(),
-                                    "execution trapped due to this operation",
-                                )
-                                .with_secondary_label(span, reason)
-                                .into_report());
-                        }
-                        ControlFlowEffect::Return(returned) => {
-                            // If this is the end of the call stack, we're returning from the
-                            // top-level operation
-                            let is_final_return = self.call_stack.len() == 1;
-                            let frame = self.call_stack.pop().unwrap();
-
-                            // Set up the resumption point if we're resuming execution after the
-                            // caller
-                            if !is_final_return {
-                                // Restore the instruction pointer to the point where control was
-                                // transferred to the callee
-                                let caller_block = frame.caller_block().unwrap();
-                                next_op = frame.return_to();
-                                // NOTE: We change `block` here, rather than updating `next_block`,
-                                // because we're resuming control with `next_op`, which doesn't
-                                // revisit the outer `'block` loop.
-                                block = caller_block;
-                                next_region = caller_block.parent();
-                            }
-
-                            // Verify the results that were returned
-                            let callee = frame.callee();
-                            let callable = callee.as_trait::<dyn CallableOpInterface>().unwrap();
-                            let signature = callable.signature();
-                            let call = frame.caller();
-                            let results_returned = returned.is_some() as usize;
-                            let results_expected = signature.results().len();
-                            if let Some(call) = call.as_ref() {
-                                assert_eq!(
-                                    call.num_results(),
-                                    results_expected,
-                                    "expected to have caught call/callee signature mismatch \
-                                     during verification"
-                                );
-                            }
-                            if results_returned != results_expected {
-                                return Err(self
-                                    .error("evaluation failed")
-                                    .with_primary_label(
-                                        callee.span(),
-                                        format!(
-                                            "callee returned {results_returned} results, but \
-                                             {results_expected} were expected"
-                                        ),
-                                    )
-                                    .with_secondary_label(
-                                        callee.span(),
-                                        "this callable returned incorrect number of results",
-                                    )
-                                    .into_report());
-                            }
-                            if let Some(return_ty) = returned.as_ref().map(|v| v.ty()) {
-                                let expected_ty = &signature.results[0].ty;
-                                if &return_ty != expected_ty {
-                                    return Err(self
-                                        .error("evaluation failed")
-                                        .with_primary_label(
-                                            callee.span(),
-                                            format!(
-                                                "callee returned result type that does not match \
-                                                 its signature, got {return_ty} but signature \
-                                                 requires {expected_ty}"
-                                            ),
-                                        )
-                                        .with_secondary_label(
-                                            callee.span(),
-                                            "this callable returned a value that does not match \
-                                             its signature",
-                                        )
-                                        .into_report());
-                                }
-                            }
-
-                            if is_final_return {
-                                // We're done executing the top-level operation, return to the
-                                // evaluator directly to terminate.
-                                return Ok(SmallVec::from_iter(returned));
-                            } else if let Some(value) = returned {
-                                // Make sure we bind the result values of the call op before
-                                // resuming execution after the call
-                                let call = call.unwrap();
-                                let result = call.results()[0] as ValueRef;
-                                self.set_value(result, value);
-                            }
-
-                            // Return to after the call
-                            continue 'op;
-                        }
-                        ControlFlowEffect::Jump(successor) => {
-                            let dest = successor.successor();
-
-                            // Check that arguments match successor block signature
-                            let arguments = successor.successor_operands();
-                            let block = dest.borrow();
-                            if block.num_arguments() != arguments.len() {
-                                return Err(self
-                                    .error("evaluation failed")
-                                    .with_primary_label(
-                                        op.span(),
-                                        format!(
-                                            "attempted to branch to {dest} with {} arguments, but \
-                                             {} were expected",
-                                            block.num_arguments(),
-                                            arguments.len()
-                                        ),
-                                    )
-                                    .into_report());
-                            }
-                            for (param, arg) in block.arguments().iter().zip(arguments.iter()) {
-                                let expected = param.borrow();
-                                let expected_ty = expected.ty();
-                                let given = arg.borrow();
-                                let given_ty = given.ty();
-                                if expected_ty != given_ty {
-                                    return Err(self
-                                        .error("evaluation failed")
-                                        .with_primary_label(
-                                            op.span(),
-                                            format!(
-                                                "attempted to branch to {dest} with mismatched \
-                                                 argument types"
-                                            ),
-                                        )
-                                        .with_secondary_label(
-                                            expected.span(),
-                                            format!("expected {expected_ty}, got {given_ty}"),
-                                        )
-                                        .into_report());
-                                }
-                                let value = self.get_value(&arg)?;
-                                self.set_value(*param as ValueRef, value);
-                            }
-
-                            // Jump
-                            next_block = Some(dest);
-                            continue 'block;
-                        }
-                        ControlFlowEffect::Yield {
-                            successor: RegionBranchPoint::Parent,
-                            arguments,
-                        } => {
-                            // We're returning to the parent operation from a child region
-                            //
-                            // Check that arguments match parent op results
-                            let parent = region.parent().unwrap();
-                            let parent_op = parent.borrow();
-                            if parent_op.num_results() != arguments.len() {
-                                return Err(self
-                                    .error("evaluation failed")
-                                    .with_primary_label(
-                                        op.span(),
-                                        format!(
-                                            "attempted to yield to parent with {} arguments, but \
-                                             {} were expected",
-                                            parent_op.num_results(),
-                                            arguments.len()
-                                        ),
-                                    )
-                                    .with_secondary_label(
-                                        parent_op.span(),
-                                        "this is the parent operation",
-                                    )
-                                    .into_report());
-                            }
-                            log::debug!(target: "eval", "  <= {}",
-                                DisplayValues::new(parent_op.results().iter().zip(arguments.iter()).map(|(result, arg)| {
-                                MaterializedValue {
-                                    id: *result as ValueRef,
-                                    value: self.get_value(&arg).unwrap(),
-                                }
-                            })));
-                            for (result, arg) in parent_op.results().iter().zip(arguments) {
-                                let expected = result.borrow();
-                                let expected_ty = expected.ty();
-                                let given = arg.borrow();
-                                let given_ty = given.ty();
-                                if expected_ty != given_ty {
-                                    return Err(self
-                                        .error("evaluation failed")
-                                        .with_primary_label(
-                                            op.span(),
-                                            "attempted to yield to parent with mismatched \
-                                             argument types",
-                                        )
-                                        .with_secondary_label(
-                                            expected.span(),
-                                            format!("expected {expected_ty}, got {given_ty}"),
-                                        )
-                                        .into_report());
-                                }
-
-                                let value = self.get_value(&arg)?;
-                                self.set_value(*result as ValueRef, value);
-                            }
-
-                            // Yield
-                            next_op = parent.next();
-                            next_block = parent.parent();
-                            if let Some(parent_region) = next_block.and_then(|block| block.parent())
-                            {
-                                region = parent_region;
-                                next_region = Some(parent_region);
-                            } else {
-                                next_region = None;
-                            }
-
-                            // If we're yielding from a standalone op, terminate evaluation
-                            if next_op.is_none() {
-                                break 'region;
-                            }
-                            continue 'op;
-                        }
-                        ControlFlowEffect::Yield {
-                            successor: RegionBranchPoint::Child(successor),
-                            arguments,
-                        } => {
-                            // Check that arguments match successor region entry block signature
-                            let successor_region = successor.borrow();
-                            let dest = successor_region.entry();
-                            if dest.num_arguments() != arguments.len() {
-                                return Err(self
-                                    .error("evaluation failed")
-                                    .with_primary_label(
-                                        op.span(),
-                                        format!(
-                                            "attempted to yield to {dest} with {} arguments, but \
-                                             {} were expected",
-                                            dest.num_arguments(),
-                                            arguments.len()
-                                        ),
-                                    )
-                                    .into_report());
-                            }
-                            for (param, arg) in dest.arguments().iter().zip(arguments.iter()) {
-                                let expected = param.borrow();
-                                let expected_ty = expected.ty();
-                                let given = arg.borrow();
-                                let given_ty = given.ty();
-                                if expected_ty != given_ty {
-                                    return Err(self
-                                        .error("evaluation failed")
-                                        .with_primary_label(
-                                            op.span(),
-                                            format!(
-                                                "attempted to yield to {dest} with mismatched \
-                                                 argument types"
-                                            ),
-                                        )
-                                        .with_secondary_label(
-                                            expected.span(),
-                                            format!("expected {expected_ty}, got {given_ty}"),
-                                        )
-                                        .into_report());
-                                }
-
-                                let value = self.get_value(&arg)?;
-                                self.set_value(*param as ValueRef, value);
-                            }
-
-                            // Yield
-                            next_region = Some(successor);
-                            continue 'region;
-                        }
-                        ControlFlowEffect::Call { callee, arguments } => {
-                            let callable_region = self.prepare_call(&op, callee, arguments)?;
-                            // Yield control to the callee
-                            next_region = Some(callable_region);
-                            continue 'region;
-                        }
+        let mut cursor = Cursor::new(op.as_operation_ref(), region);
+        loop {
+            if let Some(results) = self.step_cursor(&mut cursor)? {
+                return Ok(results);
+            }
+        }
+    }
+
+    /// Evaluate the next operation at `cursor`, and advance it according to the control flow
+    /// effect produced by that operation, see [HirEvaluator::eval_region].
+    ///
+    /// Returns the results of the root operation of `cursor` once evaluation of it has finished.
+    fn step_cursor(&mut self, cursor: &mut Cursor) -> Result<Option<SmallVec<[Value; 1]>>, Report> {
+        let Some(op) = cursor.next_op.take() else {
+            let Some(block) = cursor.block else {
+                // The region being entered is empty
+                return self.gather_results(&cursor.root.borrow()).map(Some);
+            };
+            return Err(self.report(
+                "evaluation failed",
+                block.grandparent().unwrap().span(),
+                format!(
+                    "execution reached end of {block}, but no terminating control flow effects \
+                     were emitted"
+                ),
+            ));
+        };
+        cursor.next_op = op.next();
+        let op = op.borrow();
+        match self.eval_op(&op)? {
+            ControlFlowEffect::None => Ok(None),
+            ControlFlowEffect::Trap { span, reason } => {
+                return Err(self
+                    .error("evaluation failed")
+                    .with_primary_label(op.span(), "execution trapped due to this operation")
+                    .with_secondary_label(span, reason)
+                    .into_report());
+            }
+            ControlFlowEffect::Return(returned) => {
+                // If this is the end of the call stack, we're returning from the
+                // top-level operation
+                let is_final_return = self.call_stack.len() == 1;
+                let frame = self.call_stack.pop().unwrap();
+                if frame.entered_context() {
+                    self.exit_context();
+                }
+
+                // Set up the resumption point if we're resuming execution after the
+                // caller
+                if !is_final_return {
+                    // Restore the instruction pointer to the point where control was
+                    // transferred to the callee
+                    let caller_block = frame.caller_block().unwrap();
+                    cursor.next_op = frame.return_to();
+                    cursor.block = Some(caller_block);
+                    // The caller may be nested in a region of the callable, e.g. the
+                    // branch of an `scf.if`, which the rest of the caller block yields
+                    // from, so we must resume in that region too
+                    cursor.region = caller_block.parent().unwrap();
+                }
+
+                // Verify the results that were returned
+                let callee = frame.callee();
+                let callable = callee.as_trait::<dyn CallableOpInterface>().unwrap();
+                let signature = callable.signature();
+                let call = frame.caller();
+                let results_returned = returned.is_some() as usize;
+                let results_expected = signature.results().len();
+                if let Some(call) = call.as_ref() {
+                    assert_eq!(
+                        call.num_results(),
+                        results_expected,
+                        "expected to have caught call/callee signature mismatch during \
+                         verification"
+                    );
+                }
+                if results_returned != results_expected {
+                    return Err(self
+                        .error("evaluation failed")
+                        .with_primary_label(
+                            callee.span(),
+                            format!(
+                                "callee returned {results_returned} results, but \
+                                 {results_expected} were expected"
+                            ),
+                        )
+                        .with_secondary_label(
+                            callee.span(),
+                            "this callable returned incorrect number of results",
+                        )
+                        .into_report());
+                }
+                if let Some(return_ty) = returned.as_ref().map(|v| v.ty()) {
+                    let expected_ty = &signature.results[0].ty;
+                    if &return_ty != expected_ty {
+                        return Err(self
+                            .error("evaluation failed")
+                            .with_primary_label(
+                                callee.span(),
+                                format!(
+                                    "callee returned result type that does not match its \
+                                     signature, got {return_ty} but signature requires \
+                                     {expected_ty}"
+                                ),
+                            )
+                            .with_secondary_label(
+                                callee.span(),
+                                "this callable returned a value that does not match its signature",
+                            )
+                            .into_report());
                     }
                 }
 
-                return Err(self.report(
-                    "evaluation failed",
-                    block.grandparent().unwrap().span(),
-                    format!(
-                        "execution reached end of {block}, but no terminating control flow \
-                         effects were emitted"
-                    ),
-                ));
+                if is_final_return {
+                    // We're done executing the top-level operation, return to the
+                    // evaluator directly to terminate.
+                    return Ok(Some(SmallVec::from_iter(returned)));
+                } else if let Some(value) = returned {
+                    // Make sure we bind the result values of the call op before
+                    // resuming execution after the call
+                    let call = call.unwrap();
+                    let result = call.results()[0] as ValueRef;
+                    self.set_value(result, value);
+                }
+
+                // Return to after the call
+                Ok(None)
+            }
+            ControlFlowEffect::Jump(successor) => {
+                let dest = successor.successor();
+
+                // Check that arguments match successor block signature
+                let arguments = successor.successor_operands();
+                let block = dest.borrow();
+                if block.num_arguments() != arguments.len() {
+                    return Err(self
+                        .error("evaluation failed")
+                        .with_primary_label(
+                            op.span(),
+                            format!(
+                                "attempted to branch to {dest} with {} arguments, but {} were \
+                                 expected",
+                                block.num_arguments(),
+                                arguments.len()
+                            ),
+                        )
+                        .into_report());
+                }
+                for (param, arg) in block.arguments().iter().zip(arguments.iter()) {
+                    let expected = param.borrow();
+                    let expected_ty = expected.ty();
+                    let given = arg.borrow();
+                    let given_ty = given.ty();
+                    if expected_ty != given_ty {
+                        return Err(self
+                            .error("evaluation failed")
+                            .with_primary_label(
+                                op.span(),
+                                format!(
+                                    "attempted to branch to {dest} with mismatched argument types"
+                                ),
+                            )
+                            .with_secondary_label(
+                                expected.span(),
+                                format!("expected {expected_ty}, got {given_ty}"),
+                            )
+                            .into_report());
+                    }
+                    let value = self.get_value(&arg)?;
+                    self.set_value(*param as ValueRef, value);
+                }
+
+                // Jump
+                cursor.enter_block(dest);
+                Ok(None)
+            }
+            ControlFlowEffect::Yield {
+                successor: RegionBranchPoint::Parent,
+                arguments,
+            } => {
+                // We're returning to the parent operation from a child region
+                //
+                // Check that arguments match parent op results
+                let parent = cursor.region.parent().unwrap();
+                let parent_op = parent.borrow();
+                if parent_op.num_results() != arguments.len() {
+                    return Err(self
+                        .error("evaluation failed")
+                        .with_primary_label(
+                            op.span(),
+                            format!(
+                                "attempted to yield to parent with {} arguments, but {} were \
+                                 expected",
+                                parent_op.num_results(),
+                                arguments.len()
+                            ),
+                        )
+                        .with_secondary_label(parent_op.span(), "this is the parent operation")
+                        .into_report());
+                }
+                log::debug!(target: "eval", "  <= {}",
+                    DisplayValues::new(parent_op.results().iter().zip(arguments.iter()).map(|(result, arg)| {
+                    MaterializedValue {
+                        id: *result as ValueRef,
+                        value: self.get_value(&arg).unwrap(),
+                    }
+                })));
+                for (result, arg) in parent_op.results().iter().zip(arguments) {
+                    let expected = result.borrow();
+                    let expected_ty = expected.ty();
+                    let given = arg.borrow();
+                    let given_ty = given.ty();
+                    if expected_ty != given_ty {
+                        return Err(self
+                            .error("evaluation failed")
+                            .with_primary_label(
+                                op.span(),
+                                "attempted to yield to parent with mismatched argument types",
+                            )
+                            .with_secondary_label(
+                                expected.span(),
+                                format!("expected {expected_ty}, got {given_ty}"),
+                            )
+                            .into_report());
+                    }
+
+                    let value = self.get_value(&arg)?;
+                    self.set_value(*result as ValueRef, value);
+                }
+
+                // Yield
+                cursor.next_op = parent.next();
+
+                // If we're yielding from a standalone op, terminate evaluation
+                if cursor.next_op.is_none() {
+                    return self.gather_results(&cursor.root.borrow()).map(Some);
+                }
+                cursor.block = parent.parent();
+                if let Some(parent_region) = cursor.block.and_then(|block| block.parent()) {
+                    cursor.region = parent_region;
+                }
+                Ok(None)
+            }
+            ControlFlowEffect::Yield {
+                successor: RegionBranchPoint::Child(successor),
+                arguments,
+            } => {
+                // Check that arguments match successor region entry block signature
+                let successor_region = successor.borrow();
+                let dest = successor_region.entry();
+                if dest.num_arguments() != arguments.len() {
+                    return Err(self
+                        .error("evaluation failed")
+                        .with_primary_label(
+                            op.span(),
+                            format!(
+                                "attempted to yield to {dest} with {} arguments, but {} were \
+                                 expected",
+                                dest.num_arguments(),
+                                arguments.len()
+                            ),
+                        )
+                        .into_report());
+                }
+                for (param, arg) in dest.arguments().iter().zip(arguments.iter()) {
+                    let expected = param.borrow();
+                    let expected_ty = expected.ty();
+                    let given = arg.borrow();
+                    let given_ty = given.ty();
+                    if expected_ty != given_ty {
+                        return Err(self
+                            .error("evaluation failed")
+                            .with_primary_label(
+                                op.span(),
+                                format!(
+                                    "attempted to yield to {dest} with mismatched argument types"
+                                ),
+                            )
+                            .with_secondary_label(
+                                expected.span(),
+                                format!("expected {expected_ty}, got {given_ty}"),
+                            )
+                            .into_report());
+                    }
+
+                    let value = self.get_value(&arg)?;
+                    self.set_value(*param as ValueRef, value);
+                }
+
+                // Yield
+                cursor.enter_region(successor);
+                Ok(None)
+            }
+            ControlFlowEffect::Call { callee, arguments } => {
+                let callable_region = self.prepare_call(&op, callee, arguments)?;
+                // Yield control to the callee
+                cursor.enter_region(callable_region);
+                Ok(None)
             }
         }
+    }
 
+    /// Obtain the results produced by `op`, once control has returned from it
+    fn gather_results(&mut self, op: &Operation) -> Result<SmallVec<[Value; 1]>, Report> {
         self.ip = Some(op.as_operation_ref());
 
         // Obtain any results this operation produced
@@ -856,6 +1148,14 @@ impl HirEvaluator {
     fn eval_op(&mut self, op: &Operation) -> Result<ControlFlowEffect, Report> {
         self.ip = Some(op.as_operation_ref());
 
+        if self.fuel.is_some_and(|fuel| self.fuel_consumed >= fuel) {
+            return Err(Report::new(OutOfFuelError {
+                consumed: self.fuel_consumed,
+                at: op.span(),
+            }));
+        }
+        self.fuel_consumed += 1;
+
         // Ensure the op is evaluatable
         let Some(evaluatable) = op.as_trait::<dyn Eval>() else {
             return Err(self.report(
@@ -870,9 +1170,43 @@ impl HirEvaluator {
             value: self.get_value(&v).unwrap(),
         })));
 
+        // Capture the operands before evaluation, in case evaluation overwrites them
+        let recording = self.trace.is_some() || self.stepping;
+        let operands = if recording {
+            ValueRange::<2>::from(op.operands().all())
+                .into_iter()
+                .map(|v| self.get_value(&v))
+                .collect::<Result<SmallVec<[_; 2]>, _>>()?
+        } else {
+            SmallVec::new()
+        };
+
         // Evaluate it
         let effect = evaluatable.eval(self)?;
 
+        if recording {
+            let results = match effect {
+                ControlFlowEffect::None => ValueRange::<2>::from(op.results().all())
+                    .into_iter()
+                    .filter_map(|v| self.current_frame().try_get_value(&v))
+                    .collect(),
+                _ => SmallVec::new(),
+            };
+            let record = TraceRecord {
+                op: op.name(),
+                span: op.span(),
+                variable: crate::trace::debug_variable(op),
+                operands,
+                results,
+            };
+            if self.stepping {
+                self.last_step = Some(record.clone());
+            }
+            if let Some(trace) = self.trace.as_mut() {
+                trace.push(record);
+            }
+        }
+
         // Do not check results if control flow effect does not support results
         match effect {
             effect @ (ControlFlowEffect::Jump(_)
@@ -1017,6 +1351,18 @@ impl HirEvaluator {
                 .into_report());
         };
 
+        if self.call_stack.len() >= self.max_call_depth {
+            let callee = match callee_op.as_symbol() {
+                Some(symbol) => symbol.path().to_string(),
+                None => callee_op.name().to_string(),
+            };
+            return Err(Report::new(CallDepthExceededError {
+                max_depth: self.max_call_depth,
+                callee,
+                at: caller.span(),
+            }));
+        }
+
         let signature = callable.signature();
         if arguments.len() != signature.arity() {
             return Err(self
@@ -1060,6 +1406,13 @@ impl HirEvaluator {
             }
         }
 
+        // Calls to other components execute in a fresh context, which must be entered only after
+        // reading the arguments from the caller
+        if caller.is::<hir::Call>() {
+            self.enter_context(None);
+            frame = frame.with_new_context();
+        }
+
         // Push new call frame
         self.call_stack.push(frame);
 
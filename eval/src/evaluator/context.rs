@@ -28,10 +28,17 @@ impl ExecutionContext {
         }
     }
 
-    /// Grow the heap of this context to be at least `n` pages
-    pub fn memory_grow(&mut self, n: usize) {
-        assert!(((n * PAGE_SIZE) as u32) < u32::MAX, "cannot grow heap larger than u32::MAX");
-        self.memory.resize(n * PAGE_SIZE, 0);
+    /// Grow the heap of this context by `n` pages, returning its previous size in pages.
+    ///
+    /// Returns `None`, leaving the heap unchanged, if it cannot grow that large.
+    pub fn memory_grow(&mut self, n: usize) -> Option<usize> {
+        let size = self.memory_size();
+        let new_size = size.checked_add(n)?;
+        if new_size * PAGE_SIZE > MAX_ADDRESSABLE_HEAP + 1 {
+            return None;
+        }
+        self.memory.resize(new_size * PAGE_SIZE, 0);
+        Some(size)
     }
 
     /// Return the size of this context's heap in pages
@@ -111,6 +118,36 @@ impl ExecutionContext {
         Ok(bytes)
     }
 
+    /// Initialize the heap memory starting at `addr` with `bytes`, e.g. from a data segment.
+    ///
+    /// The heap is grown to cover the initialized memory, as data segments are part of the
+    /// initial size of the heap.
+    pub fn initialize_memory(
+        &mut self,
+        addr: u32,
+        bytes: &[u8],
+        at: SourceSpan,
+    ) -> Result<(), Report> {
+        let addr = addr as usize;
+        let end_addr = addr.checked_add(bytes.len());
+        if end_addr.is_none_or(|addr| addr > MAX_ADDRESSABLE_HEAP) {
+            return Err(WriteFailed::SizeOutOfBounds {
+                addr: addr as u32,
+                size: bytes.len() as u32,
+                at,
+            })
+            .wrap_err("invalid memory initializer");
+        }
+
+        let size = end_addr.unwrap().next_multiple_of(PAGE_SIZE);
+        if self.memory.len() < size {
+            self.memory.resize(size, 0);
+        }
+        memory::write_bytes(addr, bytes, &mut self.memory);
+
+        Ok(())
+    }
+
     /// Write `value` to `addr` in heap memory.
     ///
     /// Returns an error if `addr` is invalid, or `value` could not be written to `addr` (either the
@@ -0,0 +1,47 @@
+use midenc_hir::{BlockRef, OperationRef, RegionRef};
+
+/// The position of the interpreter loop within the IR, see `HirEvaluator::eval_region`.
+///
+/// This is kept separate from the loop itself, so that evaluation can be suspended after any
+/// operation, and resumed later, see `HirEvaluator::step`.
+pub struct Cursor {
+    /// The operation whose region evaluation started in. Evaluation finishes when control returns
+    /// to it, producing its results.
+    pub root: OperationRef,
+    /// The region containing `block`
+    pub region: RegionRef,
+    /// The block containing `next_op`, if the region is not empty
+    pub block: Option<BlockRef>,
+    /// The next operation to evaluate, if any remain in `block`
+    pub next_op: Option<OperationRef>,
+}
+
+impl Cursor {
+    /// Start evaluating `region` of `root` in its entry block
+    pub fn new(root: OperationRef, region: RegionRef) -> Self {
+        let mut cursor = Self {
+            root,
+            region,
+            block: None,
+            next_op: None,
+        };
+        cursor.enter_region(region);
+        cursor
+    }
+
+    /// Transfer control to the entry block of `region`
+    pub fn enter_region(&mut self, region: RegionRef) {
+        self.region = region;
+        self.block = None;
+        self.next_op = None;
+        if let Some(block) = region.borrow().entry_block_ref() {
+            self.enter_block(block);
+        }
+    }
+
+    /// Transfer control to the first operation of `block`
+    pub fn enter_block(&mut self, block: BlockRef) {
+        self.block = Some(block);
+        self.next_op = block.borrow().body().front().as_pointer();
+    }
+}
@@ -41,6 +41,9 @@ pub struct CallFrame {
     registers: FxHashMap<ValueRef, Value>,
     /// Function-local memory reserved as scratch space for local variables
     locals: SmallVec<[u8; 64]>,
+    /// Whether a fresh execution context was entered for this frame, which must be exited when
+    /// returning from it
+    entered_context: bool,
 }
 
 impl CallFrame {
@@ -61,6 +64,7 @@ impl CallFrame {
             caller: None,
             registers: Default::default(),
             locals,
+            entered_context: false,
         }
     }
 
@@ -69,6 +73,15 @@ impl CallFrame {
         self
     }
 
+    pub fn with_new_context(mut self) -> Self {
+        self.entered_context = true;
+        self
+    }
+
+    pub fn entered_context(&self) -> bool {
+        self.entered_context
+    }
+
     pub fn caller(&self) -> Option<EntityRef<'_, Operation>> {
         self.caller.as_ref().map(|caller| caller.borrow())
     }
@@ -89,6 +102,11 @@ impl CallFrame {
         self.callee.borrow().as_symbol().map(|symbol| symbol.path())
     }
 
+    /// The values bound in this frame, in no particular order
+    pub fn values(&self) -> impl Iterator<Item = (ValueRef, Value)> + '_ {
+        self.registers.iter().map(|(id, value)| (*id, *value))
+    }
+
     pub fn is_defined(&self, value: &ValueRef) -> bool {
         self.registers.contains_key(value)
     }
@@ -55,6 +55,9 @@ pub enum WriteFailed {
 
 /// Read a value of type `ty`, starting from offset `addr` in `memory`
 ///
+/// Values are encoded in little-endian byte order, as they are in the linear memory of Wasm, and
+/// by the code generator, so that the contents of data segments can be read as-is.
+///
 /// This operation can fail if `ty` is not a supported immediate type, or if the bytes in memory
 /// are not valid for that type.
 ///
@@ -74,39 +77,39 @@ pub fn read_value(addr: usize, ty: &Type, memory: &[u8]) -> Result<Value, ReadFa
             Immediate::U8(value)
         }
         Type::I16 => {
-            let value = i16::from_be_bytes(read_bytes(addr, memory));
+            let value = i16::from_le_bytes(read_bytes(addr, memory));
             Immediate::I16(value)
         }
         Type::U16 => {
-            let value = u16::from_be_bytes(read_bytes(addr, memory));
+            let value = u16::from_le_bytes(read_bytes(addr, memory));
             Immediate::U16(value)
         }
         Type::I32 => {
-            let value = i32::from_be_bytes(read_bytes(addr, memory));
+            let value = i32::from_le_bytes(read_bytes(addr, memory));
             Immediate::I32(value)
         }
         Type::U32 => {
-            let value = u32::from_be_bytes(read_bytes(addr, memory));
+            let value = u32::from_le_bytes(read_bytes(addr, memory));
             Immediate::U32(value)
         }
         Type::I64 => {
-            let value = i64::from_be_bytes(read_bytes(addr, memory));
+            let value = i64::from_le_bytes(read_bytes(addr, memory));
             Immediate::I64(value)
         }
         Type::U64 => {
-            let value = u64::from_be_bytes(read_bytes(addr, memory));
+            let value = u64::from_le_bytes(read_bytes(addr, memory));
             Immediate::U64(value)
         }
         Type::I128 => {
-            let value = i128::from_be_bytes(read_bytes(addr, memory));
+            let value = i128::from_le_bytes(read_bytes(addr, memory));
             Immediate::I128(value)
         }
         Type::U128 => {
-            let value = u128::from_be_bytes(read_bytes(addr, memory));
+            let value = u128::from_le_bytes(read_bytes(addr, memory));
             Immediate::U128(value)
         }
         Type::F64 => {
-            let value = f64::from_be_bytes(read_bytes(addr, memory));
+            let value = f64::from_le_bytes(read_bytes(addr, memory));
             Immediate::F64(value)
         }
         Type::Felt => {
@@ -120,7 +123,7 @@ pub fn read_value(addr: usize, ty: &Type, memory: &[u8]) -> Result<Value, ReadFa
             Immediate::Felt(Felt::new_unchecked(value))
         }
         Type::Ptr(_) => {
-            let value = u32::from_be_bytes(read_bytes(addr, memory));
+            let value = u32::from_le_bytes(read_bytes(addr, memory));
             Immediate::U32(value)
         }
         _ => {
@@ -208,7 +211,7 @@ impl<const N: usize> Buffer for SmallVec<[u8; N]> {
     }
 }
 
-/// Write `value` to `memory` starting at offset `addr`.
+/// Write `value` to `memory` starting at offset `addr`, in little-endian byte order.
 ///
 /// If `addr`, or the resulting write, would go out of bounds of `memory`, it is resized such that
 /// there is sufficient space for the write, i.e. a write never fails unless allocating the
@@ -222,15 +225,15 @@ pub fn write_value<B: Buffer>(addr: usize, value: Value, memory: &mut B) {
         Immediate::I1(value) => write_byte(addr, value as u8, memory),
         Immediate::I8(value) => write_byte(addr, value as u8, memory),
         Immediate::U8(value) => write_byte(addr, value, memory),
-        Immediate::I16(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::U16(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::I32(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::U32(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::I64(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::U64(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::I128(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::U128(value) => write_bytes(addr, &value.to_be_bytes(), memory),
-        Immediate::F64(value) => write_bytes(addr, &value.to_be_bytes(), memory),
+        Immediate::I16(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::U16(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::I32(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::U32(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::I64(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::U64(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::I128(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::U128(value) => write_bytes(addr, &value.to_le_bytes(), memory),
+        Immediate::F64(value) => write_bytes(addr, &value.to_le_bytes(), memory),
         Immediate::Felt(value) => {
             write_bytes(addr, &value.as_canonical_u64().to_le_bytes(), memory)
         }
@@ -7,10 +7,12 @@ extern crate std;
 
 extern crate alloc;
 
+mod debug;
 mod eval;
 mod evaluator;
 #[cfg(test)]
 mod tests;
+mod trace;
 mod value;
 
 use midenc_dialect_arith as arith;
@@ -25,8 +27,10 @@ use midenc_hir::{
 };
 
 pub use self::{
+    debug::{Breakpoint, StepEvent},
     eval::{ControlFlowEffect, Eval, Initialize},
-    evaluator::HirEvaluator,
+    evaluator::{CallDepthExceededError, DEFAULT_MAX_CALL_DEPTH, HirEvaluator, OutOfFuelError},
+    trace::{DEFAULT_TRACE_CAPACITY, Trace, TraceDivergence, TraceRecord},
     value::Value,
 };
 
@@ -56,6 +60,9 @@ inventory::submit!(::midenc_hir::DialectRegistrationHookInfo::new::<debuginfo::D
 ));
 
 fn eval_builtin_dialect(info: &mut ::midenc_hir::DialectInfo) {
+    info.register_operation_trait::<builtin::Component, dyn Initialize>();
+    info.register_operation_trait::<builtin::Module, dyn Initialize>();
+    info.register_operation_trait::<builtin::Segment, dyn Initialize>();
     info.register_operation_trait::<builtin::Ret, dyn Eval>();
     info.register_operation_trait::<builtin::RetImm, dyn Eval>();
 }
@@ -142,6 +149,7 @@ fn eval_hir_dialect(info: &mut ::midenc_hir::DialectInfo) {
     info.register_operation_trait::<hir::Bitcast, dyn Eval>();
     //info.register_operation_trait::<hir::ConstantBytes, dyn Eval>();
     info.register_operation_trait::<hir::Exec, dyn Eval>();
+    info.register_operation_trait::<hir::Call, dyn Eval>();
     info.register_operation_trait::<hir::Store, dyn Eval>();
     info.register_operation_trait::<hir::StoreLocal, dyn Eval>();
     info.register_operation_trait::<hir::Load, dyn Eval>();
@@ -165,4 +173,6 @@ fn eval_wasm_dialect(info: &mut ::midenc_hir::DialectInfo) {
 
 fn eval_debuginfo_dialect(info: &mut ::midenc_hir::DialectInfo) {
     info.register_operation_trait::<debuginfo::DebugValue, dyn Eval>();
+    info.register_operation_trait::<debuginfo::DebugDeclare, dyn Eval>();
+    info.register_operation_trait::<debuginfo::DebugKill, dyn Eval>();
 }
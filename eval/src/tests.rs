@@ -1,12 +1,22 @@
+use alloc::string::ToString;
 use core::ops::{Deref, DerefMut};
 
 use midenc_dialect_arith::ArithOpBuilder;
 use midenc_dialect_cf::ControlFlowOpBuilder;
 use midenc_dialect_hir::HirOpBuilder;
 use midenc_dialect_scf::StructuredControlFlowOpBuilder;
+use midenc_dialect_wasm::WasmOpBuilder;
 use midenc_hir::{
     Builder, Op, PointerType, Report, SourceSpan, Type, ValueRef,
-    dialects::builtin::{BuiltinOpBuilder, FunctionBuilder},
+    diagnostics::{SourceLanguage, SourceManager, Uri},
+    dialects::{
+        builtin::{BuiltinOpBuilder, FunctionBuilder, FunctionRef},
+        debuginfo::{
+            DIBuilder,
+            attributes::{Expression, Variable},
+        },
+    },
+    interner::Symbol,
     testing::Test,
 };
 
@@ -277,3 +287,528 @@ fn println_reports_invalid_utf8() -> Result<(), Report> {
 
     Ok(())
 }
+
+/// Test evaluation of a function which calls itself recursively from within a nested region.
+///
+/// This verifies that evaluation resumes in the correct region of the caller when a call returns.
+#[test]
+fn recursive_call_test() -> Result<(), Report> {
+    let test = Test::named("fib").in_module("test");
+    let evaluator = HirEvaluator::new(test.context_rc());
+    let mut test = EvalTest { test, evaluator };
+
+    test.with_function(&[Type::U32], &[Type::U32]);
+
+    let fib = test.function();
+    let signature = fib.borrow().get_signature().clone();
+    {
+        let span = SourceSpan::default();
+        let mut builder = test.function_builder();
+        let n = builder.current_block().borrow().arguments()[0] as ValueRef;
+        let two = builder.u32(2, span);
+        let is_base_case = builder.lt(n, two, span)?;
+        let conditional = builder.r#if(is_base_case, &[Type::U32], span)?;
+        let result = conditional.borrow().results()[0] as ValueRef;
+        builder.ret(Some(result), span)?;
+
+        let then_region = conditional.borrow().then_body().as_region_ref();
+        let then_block = builder.create_block_in_region(then_region);
+        builder.switch_to_block(then_block);
+        builder.r#yield([n], span)?;
+
+        let else_region = conditional.borrow().else_body().as_region_ref();
+        let else_block = builder.create_block_in_region(else_region);
+        builder.switch_to_block(else_block);
+        let one = builder.u32(1, span);
+        let n1 = builder.sub(n, one, span)?;
+        let fib1 = builder.exec(fib, signature.clone(), [n1], span)?;
+        let fib1 = fib1.borrow().results()[0] as ValueRef;
+        let n2 = builder.sub(n, two, span)?;
+        let fib2 = builder.exec(fib, signature, [n2], span)?;
+        let fib2 = fib2.borrow().results()[0] as ValueRef;
+        let sum = builder.add(fib1, fib2, span)?;
+        builder.r#yield([sum], span)?;
+    }
+
+    let callable = fib.borrow();
+    for (n, expected) in [(0u32, 0u32), (1, 1), (2, 1), (10, 55), (15, 610)] {
+        let results = test.evaluator.eval_callable(&*callable, [n.into()])?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0], Value::Immediate(expected.into()), "unexpected result for fib({n})");
+    }
+
+    Ok(())
+}
+
+/// Test evaluation of a pair of mutually recursive functions
+#[test]
+fn mutual_recursion_test() -> Result<(), Report> {
+    let test = Test::named("mutual_recursion").in_module("test");
+    let evaluator = HirEvaluator::new(test.context_rc());
+    let mut test = EvalTest { test, evaluator };
+
+    let is_even = test.define_function("is_even", &[Type::U32], &[Type::I1]);
+    let is_odd = test.define_function("is_odd", &[Type::U32], &[Type::I1]);
+    define_parity(&mut test, is_even, is_odd, true)?;
+    define_parity(&mut test, is_odd, is_even, false)?;
+
+    let callable = is_even.borrow();
+    for n in [0u32, 1, 2, 7, 64] {
+        let results = test.evaluator.eval_callable(&*callable, [n.into()])?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0], Value::Immediate((n % 2 == 0).into()), "unexpected result for {n}");
+    }
+
+    Ok(())
+}
+
+/// Define `function(n)` as `n == 0 ? base_case : other(n - 1)`
+fn define_parity(
+    test: &mut EvalTest,
+    function: FunctionRef,
+    other: FunctionRef,
+    base_case: bool,
+) -> Result<(), Report> {
+    let span = SourceSpan::default();
+    let signature = other.borrow().get_signature().clone();
+    let mut builder = FunctionBuilder::new(function, test.builder_mut());
+    let n = builder.current_block().borrow().arguments()[0] as ValueRef;
+    let zero = builder.u32(0, span);
+    let is_zero = builder.eq(n, zero, span)?;
+    let conditional = builder.r#if(is_zero, &[Type::I1], span)?;
+    let result = conditional.borrow().results()[0] as ValueRef;
+    builder.ret(Some(result), span)?;
+
+    let then_region = conditional.borrow().then_body().as_region_ref();
+    let then_block = builder.create_block_in_region(then_region);
+    builder.switch_to_block(then_block);
+    let base_case = builder.i1(base_case, span);
+    builder.r#yield([base_case], span)?;
+
+    let else_region = conditional.borrow().else_body().as_region_ref();
+    let else_block = builder.create_block_in_region(else_region);
+    builder.switch_to_block(else_block);
+    let one = builder.u32(1, span);
+    let n1 = builder.sub(n, one, span)?;
+    let call = builder.exec(other, signature, [n1], span)?;
+    let result = call.borrow().results()[0] as ValueRef;
+    builder.r#yield([result], span)?;
+
+    Ok(())
+}
+
+/// Test that unbounded recursion is reported as an error, rather than exhausting the host stack
+#[test]
+fn call_depth_exceeded_test() -> Result<(), Report> {
+    let test = Test::named("forever").in_module("test");
+    let evaluator = HirEvaluator::new(test.context_rc()).with_max_call_depth(16);
+    let mut test = EvalTest { test, evaluator };
+
+    test.with_function(&[Type::U32], &[Type::U32]);
+
+    let forever = test.function();
+    let signature = forever.borrow().get_signature().clone();
+    {
+        let span = SourceSpan::default();
+        let mut builder = test.function_builder();
+        let n = builder.current_block().borrow().arguments()[0] as ValueRef;
+        let call = builder.exec(forever, signature, [n], span)?;
+        let result = call.borrow().results()[0] as ValueRef;
+        builder.ret(Some(result), span)?;
+    }
+
+    let callable = forever.borrow();
+    let err = test
+        .evaluator
+        .eval_callable(&*callable, [1u32.into()])
+        .expect_err("unbounded recursion should produce an evaluation error");
+    let err = err
+        .downcast_ref::<CallDepthExceededError>()
+        .expect("expected the call depth to be exceeded");
+    assert_eq!(err.max_depth, 16);
+    assert!(err.callee.ends_with("forever"), "unexpected callee '{}'", err.callee);
+
+    Ok(())
+}
+
+/// Build a function which counts from zero up to its argument with an `scf.while`, or forever if
+/// `unbounded` is set, returning the final count
+fn define_counting_loop(test: &mut EvalTest, unbounded: bool) -> Result<(), Report> {
+    test.with_function(&[Type::U32], &[Type::U32]);
+
+    let span = SourceSpan::default();
+    let mut builder = test.function_builder();
+    let limit = builder.current_block().borrow().arguments()[0] as ValueRef;
+    let zero = builder.u32(0, span);
+    let while_op = builder.r#while([zero], &[Type::U32], span)?;
+    let result = while_op.borrow().results()[0] as ValueRef;
+    builder.ret(Some(result), span)?;
+
+    let before_block = while_op.borrow().before().entry().as_block_ref();
+    builder.switch_to_block(before_block);
+    let count = before_block.borrow().arguments()[0] as ValueRef;
+    let keep_going = if unbounded {
+        builder.i1(true, span)
+    } else {
+        builder.neq(count, limit, span)?
+    };
+    builder.condition(keep_going, [count], span)?;
+
+    let after_block = while_op.borrow().after().entry().as_block_ref();
+    builder.switch_to_block(after_block);
+    let count = after_block.borrow().arguments()[0] as ValueRef;
+    let next = builder.incr(count, span)?;
+    builder.r#yield([next], span)?;
+
+    Ok(())
+}
+
+#[test]
+fn out_of_fuel_test() -> Result<(), Report> {
+    let test = Test::named("out_of_fuel");
+    let evaluator = HirEvaluator::new(test.context_rc()).with_fuel(1000);
+    let mut test = EvalTest { test, evaluator };
+    define_counting_loop(&mut test, true)?;
+
+    let callable = test.function().borrow();
+    let err = test
+        .evaluator
+        .eval_callable(&*callable, [0u32.into()])
+        .expect_err("an infinite loop should run out of fuel");
+    let err = err.downcast_ref::<OutOfFuelError>().expect("expected to run out of fuel");
+    assert_eq!(err.consumed, 1000);
+
+    Ok(())
+}
+
+#[test]
+fn sufficient_fuel_test() -> Result<(), Report> {
+    let test = Test::named("sufficient_fuel");
+    let evaluator = HirEvaluator::new(test.context_rc()).with_fuel(100_000);
+    let mut test = EvalTest { test, evaluator };
+    define_counting_loop(&mut test, false)?;
+
+    let callable = test.function().borrow();
+    let results = test.evaluator.eval_callable(&*callable, [10_000u32.into()])?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0], Value::Immediate(10_000u32.into()));
+    let consumed = test.evaluator.fuel_consumed();
+    assert!(
+        consumed > 10_000 && consumed <= 100_000,
+        "unexpected fuel consumption: {consumed}"
+    );
+
+    // Fuel is replenished for each evaluation
+    let results = test.evaluator.eval_callable(&*callable, [10_000u32.into()])?;
+    assert_eq!(results[0], Value::Immediate(10_000u32.into()));
+    assert_eq!(test.evaluator.fuel_consumed(), consumed);
+
+    Ok(())
+}
+
+/// Evaluate `(a + b) * scale` for `a = 3` and `b = 4` with tracing enabled, returning the trace
+fn trace_scaled_sum(name: &'static str, scale: u32) -> Result<Trace, Report> {
+    let test = Test::named(name);
+    let evaluator = HirEvaluator::new(test.context_rc()).with_trace(DEFAULT_TRACE_CAPACITY);
+    let mut test = EvalTest { test, evaluator };
+    test.with_function(&[Type::U32, Type::U32], &[Type::U32]);
+
+    {
+        let span = SourceSpan::default();
+        let mut builder = test.function_builder();
+        let a = builder.current_block().borrow().arguments()[0] as ValueRef;
+        let b = builder.current_block().borrow().arguments()[1] as ValueRef;
+        let sum = builder.add(a, b, span)?;
+        let scale = builder.u32(scale, span);
+        let product = builder.mul(sum, scale, span)?;
+        builder.ret(Some(product), span)?;
+    }
+
+    let callable = test.function().borrow();
+    test.evaluator.eval_callable(&*callable, [3u32.into(), 4u32.into()])?;
+    Ok(test.evaluator.take_trace().expect("expected tracing to be enabled"))
+}
+
+#[test]
+fn trace_records_evaluated_ops() -> Result<(), Report> {
+    let trace = trace_scaled_sum("trace_records_evaluated_ops", 2)?;
+
+    assert_eq!(trace.len(), 4);
+    assert_eq!(trace.truncated(), 0);
+    let records = trace.records();
+    assert_eq!(records[0].op.to_string(), "arith.add");
+    assert_eq!(records[0].operands.as_slice(), &[Value::from(3u32), Value::from(4u32)]);
+    assert_eq!(records[0].results.as_slice(), &[Value::from(7u32)]);
+    assert_eq!(records[2].op.to_string(), "arith.mul");
+    assert_eq!(records[2].results.as_slice(), &[Value::from(14u32)]);
+    assert!(records[3].results.is_empty());
+
+    assert_eq!(
+        trace.to_string(),
+        "0: arith.add 3, 4 -> 7\n1: arith.constant -> 2\n2: arith.mul 7, 2 -> 14\n3: builtin.ret \
+         14\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn trace_diff_reports_first_divergence() -> Result<(), Report> {
+    let expected = trace_scaled_sum("trace_diff_expected", 2)?;
+    assert!(expected.diff(&expected).is_none());
+
+    let perturbed = trace_scaled_sum("trace_diff_perturbed", 3)?;
+    let divergence = expected.diff(&perturbed).expect("expected traces to diverge");
+    assert_eq!(divergence.step, 1);
+    assert_eq!(
+        divergence.to_string(),
+        "traces diverge at step 1:\n  - arith.constant -> 2\n  + arith.constant -> 3\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn trace_is_bounded() -> Result<(), Report> {
+    let test = Test::named("trace_is_bounded");
+    let evaluator = HirEvaluator::new(test.context_rc()).with_trace(8);
+    let mut test = EvalTest { test, evaluator };
+    define_counting_loop(&mut test, false)?;
+
+    let callable = test.function().borrow();
+    test.evaluator.eval_callable(&*callable, [100u32.into()])?;
+    let trace = test.evaluator.take_trace().unwrap();
+    assert_eq!(trace.len(), 8);
+    assert!(trace.truncated() > 0);
+
+    Ok(())
+}
+
+/// The address of the lookup table defined by [define_lookup_table]
+const LOOKUP_TABLE_ADDR: u32 = 0x2_0000;
+
+/// Define a data segment containing a table of four u32 values, `[10, 20, 30, 40]`, in the module
+/// of `test`
+fn define_lookup_table(test: &mut EvalTest) -> Result<(), Report> {
+    let module_body = test.module().borrow().body().entry_block_ref().unwrap();
+    let builder = test.builder_mut();
+    builder.set_insertion_point_to_end(module_body);
+    let data = [10u32, 20, 30, 40]
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect::<alloc::vec::Vec<_>>();
+    builder.create_data_segment(LOOKUP_TABLE_ADDR, data, true, SourceSpan::default())?;
+    Ok(())
+}
+
+#[test]
+fn data_segment_lookup_test() -> Result<(), Report> {
+    let test = Test::named("lookup").in_module("test");
+    let evaluator = HirEvaluator::new(test.context_rc());
+    let mut test = EvalTest { test, evaluator };
+    define_lookup_table(&mut test)?;
+    test.with_function(&[Type::U32], &[Type::U32]);
+
+    {
+        let span = SourceSpan::default();
+        let mut builder = test.function_builder();
+        let index = builder.current_block().borrow().arguments()[0] as ValueRef;
+        let four = builder.u32(4, span);
+        let offset = builder.mul(index, four, span)?;
+        let base = builder.u32(LOOKUP_TABLE_ADDR, span);
+        let addr = builder.add(base, offset, span)?;
+        let ptr = builder.inttoptr(addr, Type::from(PointerType::new(Type::U32)), span)?;
+        let value = builder.load(ptr, span)?;
+        builder.ret(Some(value), span)?;
+    }
+
+    let callable = test.function().borrow();
+    for (index, expected) in [10u32, 20, 30, 40].into_iter().enumerate() {
+        let results = test.evaluator.eval_callable(&*callable, [(index as u32).into()])?;
+        assert_eq!(results[0], Value::Immediate(expected.into()), "unexpected value at {index}");
+    }
+
+    Ok(())
+}
+
+/// Test that `hir.mem_size` covers the data segments, that `hir.mem_grow` grows the heap by the
+/// given number of pages, and that `hir.memcpy` copies whole elements
+#[test]
+fn data_segment_memory_test() -> Result<(), Report> {
+    let test = Test::named("memory").in_module("test");
+    let evaluator = HirEvaluator::new(test.context_rc());
+    let mut test = EvalTest { test, evaluator };
+    define_lookup_table(&mut test)?;
+    test.with_function(&[], &[Type::U32]);
+
+    let heap_pages = LOOKUP_TABLE_ADDR / (64 * 1024) + 1;
+    {
+        let span = SourceSpan::default();
+        let mut builder = test.function_builder();
+        let size = builder.mem_size(span)?;
+        let expected_size = builder.u32(heap_pages, span);
+        builder.assert_eq(size, expected_size, span)?;
+
+        let one = builder.u32(1, span);
+        let previous_size = builder.mem_grow(one, span)?;
+        builder.assert_eq(previous_size, expected_size, span)?;
+        let size = builder.mem_size(span)?;
+        let expected_size = builder.u32(heap_pages + 1, span);
+        builder.assert_eq(size, expected_size, span)?;
+
+        let ptr_ty = Type::from(PointerType::new(Type::U32));
+        let src = builder.u32(LOOKUP_TABLE_ADDR, span);
+        let src = builder.inttoptr(src, ptr_ty.clone(), span)?;
+        let dst = builder.u32(LOOKUP_TABLE_ADDR + 0x100, span);
+        let dst = builder.inttoptr(dst, ptr_ty.clone(), span)?;
+        let count = builder.u32(4, span);
+        builder.memcpy(src, dst, count, span)?;
+
+        let last = builder.u32(LOOKUP_TABLE_ADDR + 0x100 + 12, span);
+        let last = builder.inttoptr(last, ptr_ty, span)?;
+        let value = builder.load(last, span)?;
+        builder.ret(Some(value), span)?;
+    }
+
+    let callable = test.function().borrow();
+    let results = test.evaluator.eval_callable(&*callable, [])?;
+    assert_eq!(results[0], Value::Immediate(40u32.into()));
+
+    Ok(())
+}
+
+/// Define `(a + b) * a + b`, with each operation given its own span in a synthetic source file,
+/// returning the span of the multiplication, and the value of `a + b`
+fn define_debuggee(test: &mut EvalTest) -> Result<(SourceSpan, ValueRef), Report> {
+    let source_manager = test.context_rc().session().source_manager.clone();
+    let file = source_manager.load(
+        SourceLanguage::Rust,
+        Uri::new("debuggee.rs"),
+        "s = a + b\np = s * a\nq = p + b\nreturn q\n".to_string(),
+    );
+    let line = |n: u32| SourceSpan::new(file.id(), (n * 10)..(n * 10 + 9));
+
+    test.with_function(&[Type::U32, Type::U32], &[Type::U32]);
+    let mut builder = test.function_builder();
+    let a = builder.current_block().borrow().arguments()[0] as ValueRef;
+    let b = builder.current_block().borrow().arguments()[1] as ValueRef;
+    let sum = builder.add(a, b, line(0))?;
+    let product = builder.mul(sum, a, line(1))?;
+    let result = builder.add(product, b, line(2))?;
+    builder.ret(Some(result), line(3))?;
+
+    Ok((line(1), sum))
+}
+
+/// Script a debugger session: stop at a breakpoint on a span, inspect a value, and step twice
+#[test]
+fn debug_session_test() -> Result<(), Report> {
+    let mut test = EvalTest::named("debug_session");
+    let (mul_span, sum) = define_debuggee(&mut test)?;
+
+    test.evaluator.set_breakpoint(Breakpoint::Span(mul_span));
+    let callable = test.function().borrow();
+    test.evaluator.start(&*callable, [3u32.into(), 4u32.into()])?;
+    assert!(test.evaluator.is_suspended());
+
+    let StepEvent::Breakpoint(op) = test.evaluator.continue_to_breakpoint()? else {
+        panic!("expected to stop at the breakpoint");
+    };
+    assert_eq!(op.borrow().name().to_string(), "arith.mul");
+    assert_eq!(test.evaluator.next_op(), Some(op));
+    assert_eq!(test.evaluator.get_value(&sum)?, Value::from(7u32));
+    assert!(test.evaluator.bindings().contains(&(sum, Value::from(7u32))));
+
+    let StepEvent::Evaluated(record) = test.evaluator.step()? else {
+        panic!("expected the multiplication to be evaluated");
+    };
+    assert_eq!(record.op.to_string(), "arith.mul");
+    assert_eq!(record.operands.as_slice(), &[Value::from(7u32), Value::from(3u32)]);
+    assert_eq!(record.results.as_slice(), &[Value::from(21u32)]);
+
+    let StepEvent::Evaluated(record) = test.evaluator.step()? else {
+        panic!("expected the addition to be evaluated");
+    };
+    assert_eq!(record.op.to_string(), "arith.add");
+    assert_eq!(record.operands.as_slice(), &[Value::from(21u32), Value::from(4u32)]);
+    assert_eq!(record.results.as_slice(), &[Value::from(25u32)]);
+
+    let StepEvent::Finished(results) = test.evaluator.continue_to_breakpoint()? else {
+        panic!("expected evaluation to finish");
+    };
+    assert_eq!(results.as_slice(), &[Value::from(25u32)]);
+    assert!(!test.evaluator.is_suspended());
+    assert!(test.evaluator.step().is_err());
+
+    Ok(())
+}
+
+/// Test that a breakpoint on a symbol stops before the first operation of the callable
+#[test]
+fn symbol_breakpoint_test() -> Result<(), Report> {
+    let mut test = EvalTest::named("symbol_breakpoint");
+    define_debuggee(&mut test)?;
+
+    let callable = test.function().borrow();
+    let path = callable.as_operation().as_symbol().unwrap().path();
+    test.evaluator.set_breakpoint(Breakpoint::Symbol(path));
+    test.evaluator.start(&*callable, [3u32.into(), 4u32.into()])?;
+
+    let StepEvent::Breakpoint(op) = test.evaluator.continue_to_breakpoint()? else {
+        panic!("expected to stop on entry to the function");
+    };
+    assert_eq!(op.borrow().name().to_string(), "arith.add");
+    assert_eq!(test.evaluator.fuel_consumed(), 0);
+
+    // Resuming evaluates the operation at the breakpoint, rather than stopping there again
+    let StepEvent::Finished(results) = test.evaluator.continue_to_breakpoint()? else {
+        panic!("expected evaluation to finish");
+    };
+    assert_eq!(results.as_slice(), &[Value::from(25u32)]);
+
+    Ok(())
+}
+
+/// Test evaluation of a Wasm sign extension, surrounded by debug info ops, which have no effect
+/// other than naming the variable they refer to in the trace
+#[test]
+fn wasm_and_debuginfo_test() -> Result<(), Report> {
+    let test = Test::named("wasm_and_debuginfo");
+    let evaluator = HirEvaluator::new(test.context_rc()).with_trace(DEFAULT_TRACE_CAPACITY);
+    let mut test = EvalTest { test, evaluator };
+    test.with_function(&[Type::I32], &[Type::I32]);
+
+    {
+        let span = SourceSpan::default();
+        let x = Variable::new(Symbol::intern("x"), Symbol::intern("test.rs"), 1, Some(1));
+        let mut builder = test.function_builder();
+        let input = builder.current_block().borrow().arguments()[0] as ValueRef;
+        builder.builder_mut().debug_declare(x.clone(), Expression::default(), span)?;
+        let extended = builder.sign_extend(input, Type::I8, Type::I32, span)?;
+        builder.builder_mut().debug_value(extended, x.clone(), span)?;
+        builder.builder_mut().debug_kill(x, span)?;
+        builder.ret(Some(extended), span)?;
+    }
+
+    let callable = test.function().borrow();
+    let results = test.evaluator.eval_callable(&*callable, [0x80i32.into()])?;
+    assert_eq!(results.as_slice(), &[Value::from(-128i32)]);
+
+    // Only the low 8 bits are extended
+    let results = test.evaluator.eval_callable(&*callable, [0x17fi32.into()])?;
+    assert_eq!(results.as_slice(), &[Value::from(127i32)]);
+
+    let trace = test.evaluator.take_trace().expect("expected tracing to be enabled");
+    let records = trace.records();
+    assert_eq!(records.len(), 5);
+    let x = Some(Symbol::intern("x"));
+    assert_eq!(records[0].variable, x);
+    assert_eq!(records[1].variable, None);
+    assert_eq!(records[1].results.as_slice(), &[Value::from(127i32)]);
+    assert_eq!(records[2].variable, x);
+    assert!(records[2].to_string().ends_with(" x = 127"), "{}", &records[2]);
+    assert_eq!(records[3].variable, x);
+    assert!(records[3].to_string().ends_with(" x"), "{}", &records[3]);
+
+    Ok(())
+}
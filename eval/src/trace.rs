@@ -0,0 +1,191 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use midenc_hir::{
+    Operation, OperationName, SmallVec, SourceSpan, dialects::debuginfo, formatter::DisplayValues,
+    interner::Symbol,
+};
+
+use crate::Value;
+
+/// A reasonable default for the capacity given to [crate::HirEvaluator::with_trace]
+pub const DEFAULT_TRACE_CAPACITY: usize = 1 << 16;
+
+/// A record of the evaluation of a single operation
+#[derive(Debug, Clone)]
+pub struct TraceRecord {
+    /// The name of the operation that was evaluated
+    pub op: OperationName,
+    /// The source span of the operation that was evaluated
+    pub span: SourceSpan,
+    /// The source-level variable the operation refers to, if it is a debug info operation, e.g.
+    /// `debuginfo.value`, in which case its operand is the value of that variable.
+    pub variable: Option<Symbol>,
+    /// The values of the operands of the operation, at the time it was evaluated
+    pub operands: SmallVec<[Value; 2]>,
+    /// The values of the results produced by the operation.
+    ///
+    /// This is empty for operations which transfer control elsewhere, e.g. branches, as their
+    /// results, if any, are only produced when control returns to them.
+    pub results: SmallVec<[Value; 1]>,
+}
+
+impl TraceRecord {
+    /// Returns true if `other` records the evaluation of the same kind of operation, with the same
+    /// operand and result values.
+    ///
+    /// Source spans are not compared, as they are not expected to be preserved by rewrites.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.op == other.op
+            && self.variable == other.variable
+            && self.operands == other.operands
+            && self.results == other.results
+    }
+}
+
+/// Returns the source-level variable `op` refers to, if it is a debug info operation
+pub(crate) fn debug_variable(op: &Operation) -> Option<Symbol> {
+    if let Some(op) = op.downcast_ref::<debuginfo::DebugValue>() {
+        Some(op.get_variable().name)
+    } else if let Some(op) = op.downcast_ref::<debuginfo::DebugDeclare>() {
+        Some(op.get_variable().name)
+    } else {
+        op.downcast_ref::<debuginfo::DebugKill>().map(|op| op.get_variable().name)
+    }
+}
+
+impl fmt::Display for TraceRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", &self.op)?;
+        if let Some(variable) = self.variable {
+            write!(f, " {variable}")?;
+            if !self.operands.is_empty() {
+                f.write_str(" =")?;
+            }
+        }
+        if !self.operands.is_empty() {
+            write!(f, " {}", DisplayValues::new(self.operands.iter()))?;
+        }
+        if !self.results.is_empty() {
+            write!(f, " -> {}", DisplayValues::new(self.results.iter()))?;
+        }
+        Ok(())
+    }
+}
+
+/// A bounded, in-memory trace of the operations evaluated by [crate::HirEvaluator].
+///
+/// Only the first `capacity` operations evaluated are recorded, as the first point at which two
+/// traces diverge is usually the most interesting one. Operations evaluated after that are only
+/// counted, see [Trace::truncated].
+#[derive(Debug, Clone)]
+pub struct Trace {
+    records: Vec<TraceRecord>,
+    capacity: usize,
+    truncated: usize,
+}
+
+impl Trace {
+    /// Create an empty trace which keeps at most `capacity` records
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Vec::new(),
+            capacity,
+            truncated: 0,
+        }
+    }
+
+    /// The maximum number of records kept by this trace
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The records of this trace, in evaluation order
+    pub fn records(&self) -> &[TraceRecord] {
+        &self.records
+    }
+
+    /// The number of operations evaluated after this trace reached its capacity
+    pub fn truncated(&self) -> usize {
+        self.truncated
+    }
+
+    /// Returns true if no operations were recorded
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The number of operations recorded
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Record the evaluation of an operation
+    pub fn push(&mut self, record: TraceRecord) {
+        if self.records.len() < self.capacity {
+            self.records.push(record);
+        } else {
+            self.truncated += 1;
+        }
+    }
+
+    /// Remove all records from this trace, keeping its capacity
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.truncated = 0;
+    }
+
+    /// Compare this trace against `other`, e.g. a trace of the same function with the same inputs,
+    /// after some rewrite was applied, returning the first step at which they differ, if any.
+    ///
+    /// See [TraceRecord::is_equivalent] for what is considered a difference.
+    pub fn diff<'a>(&'a self, other: &'a Self) -> Option<TraceDivergence<'a>> {
+        let len = self.records.len().max(other.records.len());
+        (0..len).find_map(|step| {
+            let left = self.records.get(step);
+            let right = other.records.get(step);
+            match (left, right) {
+                (Some(l), Some(r)) if l.is_equivalent(r) => None,
+                _ => Some(TraceDivergence { step, left, right }),
+            }
+        })
+    }
+}
+
+/// Prints one record per line, prefixed by its step number
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (step, record) in self.records.iter().enumerate() {
+            writeln!(f, "{step}: {record}")?;
+        }
+        if self.truncated > 0 {
+            writeln!(f, "... {} more operations were not recorded", self.truncated)?;
+        }
+        Ok(())
+    }
+}
+
+/// The first step at which two traces differ, see [Trace::diff]
+#[derive(Debug)]
+pub struct TraceDivergence<'a> {
+    /// The index of the first differing record
+    pub step: usize,
+    /// The record of the first trace at `step`, if it has not ended
+    pub left: Option<&'a TraceRecord>,
+    /// The record of the second trace at `step`, if it has not ended
+    pub right: Option<&'a TraceRecord>,
+}
+
+impl fmt::Display for TraceDivergence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "traces diverge at step {}:", self.step)?;
+        match self.left {
+            Some(record) => writeln!(f, "  - {record}")?,
+            None => writeln!(f, "  - <end of trace>")?,
+        }
+        match self.right {
+            Some(record) => writeln!(f, "  + {record}"),
+            None => writeln!(f, "  + <end of trace>"),
+        }
+    }
+}
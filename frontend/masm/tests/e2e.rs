@@ -206,7 +206,7 @@ fn assert_roundtrip_outputs_with_advice(
 
 fn e2e_context() -> Rc<Context> {
     let options = Box::new(Options {
-        entrypoint: Some("test::entry".to_owned()),
+        entrypoints: vec!["test::entry".to_owned()],
         ..Options::default()
     })
     .with_output_types(Default::default(), None);
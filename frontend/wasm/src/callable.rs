@@ -26,6 +26,15 @@ pub enum CallableFunction {
         /// Function signature parsed from the core Wasm module
         signature: Signature,
     },
+    /// Raw Miden Assembly imported from the [crate::intrinsics::INLINE_ASM_MODULE] module, calls to
+    /// which are lowered to an inline assembly op
+    InlineAsm {
+        /// Module and function name parsed from the core Wasm module, the latter being the Miden
+        /// Assembly to splice into the caller
+        wasm_id: SymbolPath,
+        /// Function signature parsed from the core Wasm module
+        signature: Signature,
+    },
     /// All other functions
     Function {
         /// Module and function name parsed from the core Wasm module
@@ -44,7 +53,7 @@ impl CallableFunction {
 
     pub fn function_ref(&self) -> Option<FunctionRef> {
         match self {
-            Self::Instruction { .. } => None,
+            Self::Instruction { .. } | Self::InlineAsm { .. } => None,
             Self::Intrinsic { function_ref, .. } | Self::Function { function_ref, .. } => {
                 Some(*function_ref)
             }
@@ -56,7 +65,7 @@ impl CallableFunction {
             Self::Instruction { intrinsic, .. } | Self::Intrinsic { intrinsic, .. } => {
                 intrinsic.function_name()
             }
-            Self::Function { wasm_id, .. } => wasm_id.name(),
+            Self::InlineAsm { wasm_id, .. } | Self::Function { wasm_id, .. } => wasm_id.name(),
         }
     }
 
@@ -64,6 +73,7 @@ impl CallableFunction {
         match self {
             Self::Instruction { signature, .. }
             | Self::Intrinsic { signature, .. }
+            | Self::InlineAsm { signature, .. }
             | Self::Function { signature, .. } => signature,
         }
     }
@@ -73,7 +83,7 @@ impl CallableFunction {
             Self::Instruction { intrinsic, .. } | Self::Intrinsic { intrinsic, .. } => {
                 intrinsic.into_symbol_path()
             }
-            Self::Function { wasm_id, .. } => wasm_id.clone(),
+            Self::InlineAsm { wasm_id, .. } | Self::Function { wasm_id, .. } => wasm_id.clone(),
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Translation of floating-point operators, which Miden Assembly has no native support for.
+//!
+//! How these operators are translated is chosen with [FloatMode]. Note that `f32` values are
+//! represented as field elements, as that is how the Miden SDK passes felts across the Wasm
+//! boundary, so only the operators which interpret them as IEEE 754 numbers are handled here,
+//! while `f64` values are represented by their bit pattern, as `i64`.
+//!
+//! With [FloatMode::SoftFloat], each operator is translated to an `exec` of a function of the
+//! `miden-softfloat` library, in the `::miden::softfloat` module, named after the operator, e.g.
+//! `f32.add` calls `::miden::softfloat::f32_add`. The library is not shipped with the compiler, so
+//! it must implement the following ABI:
+//!
+//! * `f32` operands and results are passed by their IEEE 754 bit pattern, as an `i32`, i.e. a
+//!   single `u32` element on the operand stack, and `f64` operands and results likewise as an
+//!   `i64`, i.e. two `u32` limbs
+//! * Comparisons, e.g. `f32_lt(a: i32, b: i32) -> i32`, return `1` if the comparison holds, and
+//!   `0` otherwise
+//! * Conversions take or return integers as the Wasm operator does, e.g.
+//!   `i32_trunc_f32_s(a: i32) -> i32`, and trap where it would trap
+//!
+//! See [float_op] for the name and signature of the function implementing each operator.
+
+use midenc_dialect_hir::HirOpBuilder;
+use midenc_dialect_ub::UndefinedBehaviorOpBuilder;
+use midenc_hir::{
+    Builder, SmallVec, SymbolNameComponent, SymbolPath, Type, ValueRef, Visibility,
+    diagnostics::WrapErr,
+    dialects::builtin::{FunctionRef, ModuleBuilder, attributes::Signature},
+    interner::{Symbol, symbols},
+};
+use midenc_session::{
+    FloatMode,
+    diagnostics::{DiagnosticsHandler, Severity, SourceSpan},
+};
+use wasmparser::Operator;
+
+use crate::{
+    error::WasmResult,
+    module::{
+        func_translation_state::FuncTranslationState, function_builder_ext::FunctionBuilderExt,
+        module_translation_state::ModuleTranslationState,
+    },
+};
+
+/// The module of the `miden-softfloat` library in which the functions implementing floating-point
+/// operators are defined, see [FloatMode::SoftFloat]
+pub(crate) const MODULE_PREFIX: &[SymbolNameComponent] = &[
+    SymbolNameComponent::Root,
+    SymbolNameComponent::Component(symbols::Miden),
+    SymbolNameComponent::Component(symbols::Softfloat),
+];
+
+/// The types of the operands and results of floating-point operators
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operand {
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl Operand {
+    /// The type of the values of this operand in the IR
+    fn ir_type(self) -> Type {
+        match self {
+            Self::I32 => Type::I32,
+            Self::I64 | Self::F64 => Type::I64,
+            Self::F32 => Type::Felt,
+        }
+    }
+
+    /// The type used for this operand in the signatures of the `miden-softfloat` library, in
+    /// which `f32` values are passed by their bit pattern
+    fn abi_type(self) -> Type {
+        match self {
+            Self::I32 | Self::F32 => Type::I32,
+            Self::I64 | Self::F64 => Type::I64,
+        }
+    }
+}
+
+/// A floating-point operator, and the function of the `miden-softfloat` library implementing it
+struct FloatOp {
+    function: &'static str,
+    params: &'static [Operand],
+    result: Operand,
+}
+
+impl FloatOp {
+    const fn new(function: &'static str, params: &'static [Operand], result: Operand) -> Self {
+        Self {
+            function,
+            params,
+            result,
+        }
+    }
+}
+
+/// Get the floating-point operator `op`, if it is one which requires floating-point support.
+///
+/// Operators which only move `f32` or `f64` values around, e.g. loads and stores, are not
+/// included, as they are translated regardless of [FloatMode].
+fn float_op(op: &Operator) -> Option<FloatOp> {
+    use Operand::*;
+
+    const F32_UNARY: &[Operand] = &[F32];
+    const F32_BINARY: &[Operand] = &[F32, F32];
+    const F64_UNARY: &[Operand] = &[F64];
+    const F64_BINARY: &[Operand] = &[F64, F64];
+
+    Some(match op {
+        Operator::F32Abs => FloatOp::new("f32_abs", F32_UNARY, F32),
+        Operator::F32Neg => FloatOp::new("f32_neg", F32_UNARY, F32),
+        Operator::F32Ceil => FloatOp::new("f32_ceil", F32_UNARY, F32),
+        Operator::F32Floor => FloatOp::new("f32_floor", F32_UNARY, F32),
+        Operator::F32Trunc => FloatOp::new("f32_trunc", F32_UNARY, F32),
+        Operator::F32Nearest => FloatOp::new("f32_nearest", F32_UNARY, F32),
+        Operator::F32Sqrt => FloatOp::new("f32_sqrt", F32_UNARY, F32),
+        Operator::F32Add => FloatOp::new("f32_add", F32_BINARY, F32),
+        Operator::F32Sub => FloatOp::new("f32_sub", F32_BINARY, F32),
+        Operator::F32Mul => FloatOp::new("f32_mul", F32_BINARY, F32),
+        Operator::F32Div => FloatOp::new("f32_div", F32_BINARY, F32),
+        Operator::F32Min => FloatOp::new("f32_min", F32_BINARY, F32),
+        Operator::F32Max => FloatOp::new("f32_max", F32_BINARY, F32),
+        Operator::F32Copysign => FloatOp::new("f32_copysign", F32_BINARY, F32),
+        Operator::F32Eq => FloatOp::new("f32_eq", F32_BINARY, I32),
+        Operator::F32Ne => FloatOp::new("f32_ne", F32_BINARY, I32),
+        Operator::F32Lt => FloatOp::new("f32_lt", F32_BINARY, I32),
+        Operator::F32Gt => FloatOp::new("f32_gt", F32_BINARY, I32),
+        Operator::F32Le => FloatOp::new("f32_le", F32_BINARY, I32),
+        Operator::F32Ge => FloatOp::new("f32_ge", F32_BINARY, I32),
+        Operator::F64Abs => FloatOp::new("f64_abs", F64_UNARY, F64),
+        Operator::F64Neg => FloatOp::new("f64_neg", F64_UNARY, F64),
+        Operator::F64Ceil => FloatOp::new("f64_ceil", F64_UNARY, F64),
+        Operator::F64Floor => FloatOp::new("f64_floor", F64_UNARY, F64),
+        Operator::F64Trunc => FloatOp::new("f64_trunc", F64_UNARY, F64),
+        Operator::F64Nearest => FloatOp::new("f64_nearest", F64_UNARY, F64),
+        Operator::F64Sqrt => FloatOp::new("f64_sqrt", F64_UNARY, F64),
+        Operator::F64Add => FloatOp::new("f64_add", F64_BINARY, F64),
+        Operator::F64Sub => FloatOp::new("f64_sub", F64_BINARY, F64),
+        Operator::F64Mul => FloatOp::new("f64_mul", F64_BINARY, F64),
+        Operator::F64Div => FloatOp::new("f64_div", F64_BINARY, F64),
+        Operator::F64Min => FloatOp::new("f64_min", F64_BINARY, F64),
+        Operator::F64Max => FloatOp::new("f64_max", F64_BINARY, F64),
+        Operator::F64Copysign => FloatOp::new("f64_copysign", F64_BINARY, F64),
+        Operator::F64Eq => FloatOp::new("f64_eq", F64_BINARY, I32),
+        Operator::F64Ne => FloatOp::new("f64_ne", F64_BINARY, I32),
+        Operator::F64Lt => FloatOp::new("f64_lt", F64_BINARY, I32),
+        Operator::F64Gt => FloatOp::new("f64_gt", F64_BINARY, I32),
+        Operator::F64Le => FloatOp::new("f64_le", F64_BINARY, I32),
+        Operator::F64Ge => FloatOp::new("f64_ge", F64_BINARY, I32),
+        Operator::I32TruncF32S => FloatOp::new("i32_trunc_f32_s", F32_UNARY, I32),
+        Operator::I32TruncF32U => FloatOp::new("i32_trunc_f32_u", F32_UNARY, I32),
+        Operator::I32TruncF64S => FloatOp::new("i32_trunc_f64_s", F64_UNARY, I32),
+        Operator::I32TruncF64U => FloatOp::new("i32_trunc_f64_u", F64_UNARY, I32),
+        Operator::I64TruncF32S => FloatOp::new("i64_trunc_f32_s", F32_UNARY, I64),
+        Operator::I64TruncF32U => FloatOp::new("i64_trunc_f32_u", F32_UNARY, I64),
+        Operator::I64TruncF64S => FloatOp::new("i64_trunc_f64_s", F64_UNARY, I64),
+        Operator::I64TruncF64U => FloatOp::new("i64_trunc_f64_u", F64_UNARY, I64),
+        Operator::I32TruncSatF32S => FloatOp::new("i32_trunc_sat_f32_s", F32_UNARY, I32),
+        Operator::I32TruncSatF32U => FloatOp::new("i32_trunc_sat_f32_u", F32_UNARY, I32),
+        Operator::I32TruncSatF64S => FloatOp::new("i32_trunc_sat_f64_s", F64_UNARY, I32),
+        Operator::I32TruncSatF64U => FloatOp::new("i32_trunc_sat_f64_u", F64_UNARY, I32),
+        Operator::I64TruncSatF32S => FloatOp::new("i64_trunc_sat_f32_s", F32_UNARY, I64),
+        Operator::I64TruncSatF32U => FloatOp::new("i64_trunc_sat_f32_u", F32_UNARY, I64),
+        Operator::I64TruncSatF64S => FloatOp::new("i64_trunc_sat_f64_s", F64_UNARY, I64),
+        Operator::I64TruncSatF64U => FloatOp::new("i64_trunc_sat_f64_u", F64_UNARY, I64),
+        Operator::F32ConvertI32S => FloatOp::new("f32_convert_i32_s", &[I32], F32),
+        Operator::F32ConvertI32U => FloatOp::new("f32_convert_i32_u", &[I32], F32),
+        Operator::F32ConvertI64S => FloatOp::new("f32_convert_i64_s", &[I64], F32),
+        Operator::F32ConvertI64U => FloatOp::new("f32_convert_i64_u", &[I64], F32),
+        Operator::F64ConvertI32S => FloatOp::new("f64_convert_i32_s", &[I32], F64),
+        Operator::F64ConvertI32U => FloatOp::new("f64_convert_i32_u", &[I32], F64),
+        Operator::F64ConvertI64S => FloatOp::new("f64_convert_i64_s", &[I64], F64),
+        Operator::F64ConvertI64U => FloatOp::new("f64_convert_i64_u", &[I64], F64),
+        Operator::F32DemoteF64 => FloatOp::new("f32_demote_f64", F64_UNARY, F32),
+        Operator::F64PromoteF32 => FloatOp::new("f64_promote_f32", F32_UNARY, F64),
+        _ => return None,
+    })
+}
+
+/// Returns true if `op` is a floating-point operator, see [translate_float_operator]
+pub(crate) fn is_float_operator(op: &Operator) -> bool {
+    float_op(op).is_some()
+}
+
+/// Translates the floating-point operator `op` according to `module_state.float_mode`.
+///
+/// * [FloatMode::Reject] raises an error, which the caller attributes to the function being
+///   translated
+/// * [FloatMode::SoftFloat] calls the function of the `miden-softfloat` library implementing `op`
+/// * [FloatMode::Unreachable] traps, and the rest of the current block is treated as unreachable
+pub(crate) fn translate_float_operator<B: ?Sized + Builder>(
+    op: &Operator,
+    builder: &mut FunctionBuilderExt<'_, B>,
+    state: &mut FuncTranslationState,
+    module_state: &mut ModuleTranslationState,
+    diagnostics: &DiagnosticsHandler,
+    span: SourceSpan,
+) -> WasmResult<()> {
+    let float_op = float_op(op).expect("expected a floating-point operator");
+    match module_state.float_mode {
+        FloatMode::Reject => Err(diagnostics
+            .diagnostic(Severity::Error)
+            .with_message(format!(
+                "floating-point operator {op:?} is not supported by Miden Assembly"
+            ))
+            .with_help(
+                "use `--float-mode=soft-float` to implement floating-point operations in \
+                 software, or `--float-mode=unreachable` if this code is never executed",
+            )
+            .into_report()),
+        FloatMode::Unreachable => {
+            builder.unreachable(span);
+            state.reachable = false;
+            Ok(())
+        }
+        FloatMode::SoftFloat => {
+            let callee = softfloat_function(&float_op, module_state)?;
+            let args = state.peekn(float_op.params.len());
+            let mut abi_args = SmallVec::<[ValueRef; 2]>::default();
+            for (arg, operand) in args.iter().copied().zip(float_op.params) {
+                abi_args.push(bitcast_if_needed(arg, operand.abi_type(), builder, span)?);
+            }
+            state.popn(float_op.params.len());
+            let signature = callee.borrow().get_signature().clone();
+            let exec = builder.exec(callee, signature, abi_args, span)?;
+            let result = exec.borrow().results()[0].borrow().as_value_ref();
+            let result = bitcast_if_needed(result, float_op.result.ir_type(), builder, span)?;
+            state.push1(result);
+            Ok(())
+        }
+    }
+}
+
+/// Get the declaration of the `miden-softfloat` library function implementing `op`, declaring it
+/// in the world on first use
+fn softfloat_function(
+    op: &FloatOp,
+    module_state: &mut ModuleTranslationState,
+) -> WasmResult<FunctionRef> {
+    let mut path = SymbolPath::from_iter(MODULE_PREFIX.iter().copied());
+    path.set_name(Symbol::intern(op.function));
+
+    let module_ref = module_state
+        .world_builder
+        .declare_module_tree(&path.without_leaf())
+        .wrap_err("failed to create module for softfloat imports")?;
+    let mut module_builder = ModuleBuilder::new(module_ref);
+    if let Some(function_ref) = module_builder.get_function(op.function) {
+        return Ok(function_ref);
+    }
+
+    let context = module_state.world_builder.context_rc();
+    let signature = Signature::new(
+        &context,
+        op.params.iter().map(|operand| operand.abi_type()),
+        [op.result.abi_type()],
+    );
+    module_builder
+        .define_function(path.name().into(), Visibility::Public, signature)
+        .wrap_err("failed to create softfloat function ref")
+}
+
+fn bitcast_if_needed<B: ?Sized + Builder>(
+    value: ValueRef,
+    ty: Type,
+    builder: &mut FunctionBuilderExt<'_, B>,
+    span: SourceSpan,
+) -> WasmResult<ValueRef> {
+    if value.borrow().ty() == &ty {
+        Ok(value)
+    } else {
+        builder.bitcast(value, ty, span)
+    }
+}
@@ -13,16 +13,18 @@
 //!
 //! Based on Cranelift's Wasm -> CLIF translator v11.0.0
 
+use alloc::collections::BTreeMap;
+
 use midenc_dialect_arith::ArithOpBuilder;
 use midenc_dialect_cf::{ControlFlowOpBuilder, SwitchCase};
 use midenc_dialect_hir::HirOpBuilder;
 use midenc_dialect_ub::UndefinedBehaviorOpBuilder;
 use midenc_dialect_wasm::{WasmMemArg, WasmOpBuilder, prepare_addr};
 use midenc_hir::{
-    BlockRef, Builder, Immediate, Op,
+    BlockRef, Builder, Immediate, Op, SmallVec,
     Type::{self, *},
     ValueRef,
-    dialects::builtin::BuiltinOpBuilder,
+    dialects::builtin::{BuiltinOpBuilder, GlobalVariableRef},
 };
 use midenc_session::diagnostics::{DiagnosticsHandler, IntoDiagnostic, Report, SourceSpan};
 use wasmparser::{MemArg, Operator};
@@ -36,12 +38,16 @@ use crate::{
         func_translation_state::{ControlStackFrame, ElseData, FuncTranslationState},
         function_builder_ext::FunctionBuilderExt,
         module_translation_state::ModuleTranslationState,
-        types::{BlockType, FuncIndex, GlobalIndex, ModuleTypesBuilder},
+        types::{
+            BlockType, DataIndex, FuncIndex, GlobalIndex, ModuleTypesBuilder, TableIndex,
+            TypeIndex, ir_func_type,
+        },
     },
     ssa::Variable,
     unsupported_diag,
 };
 
+mod float;
 #[cfg(test)]
 mod tests;
 
@@ -165,7 +171,8 @@ pub fn translate_operator<B: ?Sized + Builder>(
                     let cond = builder.neq(cond, imm, span)?;
                     state.push1(builder.select(cond, arg1, arg2, span)?);
                 }
-                wasmparser::ValType::I64 => {
+                // `f64` values are represented by their bit pattern
+                wasmparser::ValType::I64 | wasmparser::ValType::F64 => {
                     let imm = builder.imm(Immediate::I64(0), span);
                     let cond = builder.neq(cond, imm, span)?;
                     state.push1(builder.select(cond, arg1, arg2, span)?);
@@ -210,10 +217,20 @@ pub fn translate_operator<B: ?Sized + Builder>(
             )?;
         }
         Operator::CallIndirect {
-            type_index: _,
-            table_index: _,
+            type_index,
+            table_index,
         } => {
-            todo!("CallIndirect is not supported yet");
+            translate_call_indirect(
+                state,
+                module_state,
+                builder,
+                module,
+                mod_types,
+                TypeIndex::from_u32(*type_index),
+                TableIndex::from_u32(*table_index),
+                span,
+                diagnostics,
+            )?;
         }
         /******************************* Memory management *********************************/
         Operator::MemoryGrow { .. } => {
@@ -256,6 +273,33 @@ pub fn translate_operator<B: ?Sized + Builder>(
             let dst = prepare_addr(dst_i32, &U8, None, builder, span)?;
             builder.memset(dst, num_bytes, value, span)?;
         }
+        Operator::MemoryInit { data_index, mem } => {
+            // See semantics at https://webassembly.github.io/spec/core/exec/instructions.html#exec-memory-init
+            if *mem != 0 {
+                unsupported_diag!(diagnostics, "MemoryInit: only single memory is supported");
+            }
+            let (dst_i32, offset_i32, count_i32) = state.pop3();
+            translate_memory_init(
+                DataIndex::from_u32(*data_index),
+                dst_i32,
+                offset_i32,
+                count_i32,
+                module,
+                module_state,
+                builder,
+                span,
+            )?;
+        }
+        Operator::DataDrop { data_index } => {
+            // Active segments are dropped on instantiation already, so only passive ones are
+            // tracked
+            let data_index = DataIndex::from_u32(*data_index);
+            if module.passive_data_map.contains_key(&data_index) {
+                let gv = passive_data_dropped_flag(data_index, module, module_state);
+                let dropped = builder.i32(1, span);
+                builder.store_global(gv, dropped, span)?;
+            }
+        }
         /******************************* Load instructions ***********************************/
         Operator::I32Load8U { memarg } => {
             translate_load_zext(U8, U32, memarg, state, builder, span)?;
@@ -320,10 +364,12 @@ pub fn translate_operator<B: ?Sized + Builder>(
         Operator::I32Load { memarg } => translate_load(I32, memarg, state, builder, span)?,
         Operator::I64Load { memarg } => translate_load(I64, memarg, state, builder, span)?,
         Operator::F32Load { memarg } => translate_load(Felt, memarg, state, builder, span)?,
+        Operator::F64Load { memarg } => translate_load(I64, memarg, state, builder, span)?,
         /****************************** Store instructions ***********************************/
         Operator::I32Store { memarg } => translate_store(I32, memarg, state, builder, span)?,
         Operator::I64Store { memarg } => translate_store(I64, memarg, state, builder, span)?,
         Operator::F32Store { memarg } => translate_store(Felt, memarg, state, builder, span)?,
+        Operator::F64Store { memarg } => translate_store(I64, memarg, state, builder, span)?,
         Operator::I32Store8 { memarg } | Operator::I64Store8 { memarg } => {
             translate_store(U8, memarg, state, builder, span)?;
         }
@@ -337,6 +383,7 @@ pub fn translate_operator<B: ?Sized + Builder>(
         Operator::F32Const { value } => {
             state.push1(builder.felt(midenc_hir::Felt::from(value.bits()), span));
         }
+        Operator::F64Const { value } => state.push1(builder.i64(value.bits() as i64, span)),
 
         /******************************* Unary Operators *************************************/
         Operator::I32Clz => {
@@ -421,6 +468,8 @@ pub fn translate_operator<B: ?Sized + Builder>(
             let val = state.pop1_bitcasted(I32, builder, span);
             state.push1(val);
         }
+        // `f64` values are already represented by their bit pattern
+        Operator::F64ReinterpretI64 | Operator::I64ReinterpretF64 => {}
         /****************************** Binary Operators ************************************/
         Operator::I32Add | Operator::I64Add => {
             let (arg1, arg2) = state.pop2();
@@ -735,6 +784,9 @@ pub fn translate_operator<B: ?Sized + Builder>(
             let extended = builder.zext(val, U32, span)?;
             state.push1(builder.bitcast(extended, I32, span)?);
         }
+        op if float::is_float_operator(op) => {
+            float::translate_float_operator(op, builder, state, module_state, diagnostics, span)?;
+        }
         op => {
             unsupported_diag!(diagnostics, "Wasm op {:?} is not supported", op);
         }
@@ -742,6 +794,66 @@ pub fn translate_operator<B: ?Sized + Builder>(
     Ok(())
 }
 
+/// Translates `memory.init`, which copies `count` bytes of the given data segment, from `offset`
+/// on, to `dst`, trapping if they are not all within the segment.
+///
+/// The contents of a passive data segment are placed in memory at compile time, and copied from
+/// there, unless the segment was dropped, in which case it is empty. Active data segments are
+/// dropped on instantiation.
+#[allow(clippy::too_many_arguments)]
+fn translate_memory_init<B: ?Sized + Builder>(
+    data_index: DataIndex,
+    dst_i32: ValueRef,
+    offset_i32: ValueRef,
+    count_i32: ValueRef,
+    module: &Module,
+    module_state: &mut ModuleTranslationState,
+    builder: &mut FunctionBuilderExt<'_, B>,
+    span: SourceSpan,
+) -> WasmResult<()> {
+    let offset = builder.bitcast(offset_i32, U32, span)?;
+    let count = builder.bitcast(count_i32, U32, span)?;
+    let (len, src_base) = match module.passive_data_map.get(&data_index) {
+        Some(range) => {
+            let gv = passive_data_dropped_flag(data_index, module, module_state);
+            let dropped = builder.load_global(gv, span)?;
+            let zero = builder.i32(0, span);
+            let dropped = builder.neq(dropped, zero, span)?;
+            let empty = builder.u64(0, span);
+            let len = builder.u64((range.end - range.start) as u64, span);
+            let len = builder.select(dropped, empty, len, span)?;
+            (len, module.passive_data_base + range.start)
+        }
+        None => (builder.u64(0, span), 0),
+    };
+
+    // The end of the copied range is computed in 64 bits, so that it cannot overflow
+    let offset_u64 = builder.zext(offset, U64, span)?;
+    let count_u64 = builder.zext(count, U64, span)?;
+    let end = builder.add_unchecked(offset_u64, count_u64, span)?;
+    let in_bounds = builder.lte(end, len, span)?;
+    builder.assert_with_message(in_bounds, "out of bounds memory.init", span)?;
+
+    let src_base = builder.i32(src_base as i32, span);
+    let src_i32 = builder.add_unchecked(src_base, offset_i32, span)?;
+    let src = prepare_addr(src_i32, &U8, None, builder, span)?;
+    let dst = prepare_addr(dst_i32, &U8, None, builder, span)?;
+    builder.memcpy(src, dst, count, span)?;
+    Ok(())
+}
+
+/// Returns the global variable recording whether the given passive data segment was dropped
+fn passive_data_dropped_flag(
+    data_index: DataIndex,
+    module: &Module,
+    module_state: &mut ModuleTranslationState,
+) -> GlobalVariableRef {
+    let name = module.passive_data_dropped_name(data_index);
+    module_state.module_builder.get_global_var(name).unwrap_or_else(|| {
+        panic!("passive data segment flag not found: index={}", data_index.as_u32())
+    })
+}
+
 fn translate_load<B: ?Sized + Builder>(
     ptr_ty: Type,
     memarg: &MemArg,
@@ -853,6 +965,19 @@ fn translate_call<B: ?Sized + Builder>(
             func_state.popn(arity);
             func_state.pushn(&results);
         }
+        CallableFunction::InlineAsm { wasm_id, signature } => {
+            let arity = signature.arity();
+            let args = func_state.peekn(arity);
+            let template = wasm_id.name();
+            let asm =
+                builder.inline_asm(template.as_str(), signature, args.iter().copied(), span)?;
+            let borrow = asm.borrow();
+            let results = borrow.results();
+            func_state.popn(arity);
+            let result_vals: Vec<ValueRef> =
+                results.iter().map(|op_res| op_res.borrow().as_value_ref()).collect();
+            func_state.pushn(&result_vals);
+        }
         CallableFunction::Function {
             function_ref,
             signature,
@@ -872,6 +997,85 @@ fn translate_call<B: ?Sized + Builder>(
     Ok(())
 }
 
+/// Translates `call_indirect` to a dispatch over the entries of the table, see [FunctionTable].
+///
+/// Each entry whose signature matches the expected one is a case of a switch on the callee index,
+/// which calls that entry directly. Out-of-bounds and null entries, as well as entries of any other
+/// signature, take the default case, which traps, as in Wasm.
+///
+/// [FunctionTable]: crate::module::function_table::FunctionTable
+#[allow(clippy::too_many_arguments)]
+fn translate_call_indirect<B: ?Sized + Builder>(
+    func_state: &mut FuncTranslationState,
+    module_state: &mut ModuleTranslationState,
+    builder: &mut FunctionBuilderExt<'_, B>,
+    module: &Module,
+    mod_types: &ModuleTypesBuilder,
+    type_index: TypeIndex,
+    table_index: TableIndex,
+    span: SourceSpan,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let signature = module.types[type_index].unwrap_function();
+    let func_type = ir_func_type(&mod_types[signature], diagnostics)?;
+
+    // Group the slots by callee, so that each callee is called from a single block
+    let mut callees = BTreeMap::<FuncIndex, SmallVec<[u32; 1]>>::new();
+    let table = module_state.function_table(module, table_index, diagnostics)?;
+    for (slot, func) in table.entries() {
+        if module.functions[func].signature == signature {
+            callees.entry(func).or_default().push(slot);
+        }
+    }
+
+    // Like the `br_table` selector, the callee index is interpreted as unsigned
+    let selector = func_state.pop1_bitcasted(U32, builder, span);
+    let args = func_state.peekn(func_type.params.len()).to_vec();
+    func_state.popn(args.len());
+
+    if callees.is_empty() {
+        builder.unreachable(span);
+        func_state.reachable = false;
+        return Ok(());
+    }
+
+    let trap_block = builder.create_block();
+    let next_block = builder.create_block_with_params(func_type.results.iter().cloned(), span);
+    let mut cases = Vec::new();
+    let mut callee_blocks = Vec::with_capacity(callees.len());
+    for (func, slots) in callees {
+        let block = builder.create_block();
+        cases.extend(slots.into_iter().map(|slot| SwitchCase::create(slot, block, Vec::new())));
+        callee_blocks.push((func, block));
+    }
+    builder.switch(selector, cases, trap_block, [], span)?;
+
+    builder.seal_block(trap_block);
+    builder.switch_to_block(trap_block);
+    builder.unreachable(span);
+
+    for (func, block) in callee_blocks {
+        builder.seal_block(block);
+        builder.switch_to_block(block);
+        func_state.pushn(&args);
+        translate_call(func_state, module_state, builder, func, span, diagnostics)?;
+        let results = func_state.peekn(func_type.results.len()).to_vec();
+        func_state.popn(results.len());
+        builder.br(next_block, results, span)?;
+    }
+
+    builder.seal_block(next_block);
+    builder.switch_to_block(next_block);
+    let results: Vec<ValueRef> = next_block
+        .borrow()
+        .arguments()
+        .iter()
+        .map(|ba| ba.borrow().as_value_ref())
+        .collect();
+    func_state.pushn(&results);
+    Ok(())
+}
+
 fn translate_return<B: ?Sized + Builder>(
     state: &mut FuncTranslationState,
     builder: &mut FunctionBuilderExt<'_, B>,
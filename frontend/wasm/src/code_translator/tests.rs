@@ -4,14 +4,11 @@ use std::rc::Rc;
 use midenc_expect_test::expect_file;
 use midenc_hir::{Op, Operation, WalkResult, dialects::builtin};
 
-use crate::{WasmTranslationConfig, translate};
+use crate::{FloatMode, ImportMap, WasmTranslationConfig, translate};
 
 /// Check IR generated for a Wasm op(s).
 /// Wrap Wasm ops in a function and check the IR generated for the entry block of that function.
 fn check_op(wat_op: &str, expected_ir: midenc_expect_test::ExpectFile) {
-    let ctx = midenc_hir::Context::default();
-    let context = Rc::new(ctx);
-
     let wat = format!(
         r#"
         (module
@@ -23,8 +20,21 @@ fn check_op(wat_op: &str, expected_ir: midenc_expect_test::ExpectFile) {
             (export "test_wrapper" (func $test_wrapper))
         )"#,
     );
+    expected_ir.assert_eq(&translate_functions(&wat));
+}
+
+/// Translate the Wasm module `wat`, and print the IR of all of its functions
+fn translate_functions(wat: &str) -> String {
+    translate_functions_with_config(wat, &WasmTranslationConfig::default())
+}
+
+/// Translate the Wasm module `wat` using `config`, and print the IR of all of its functions
+fn translate_functions_with_config(wat: &str, config: &WasmTranslationConfig) -> String {
+    let ctx = midenc_hir::Context::default();
+    let context = Rc::new(ctx);
+
     let wasm = wat::parse_str(wat).unwrap();
-    let output = translate(&wasm, &WasmTranslationConfig::default(), context.clone())
+    let output = translate(&wasm, config, context.clone())
         .map_err(|e| {
             if let Some(labels) = e.labels() {
                 for label in labels {
@@ -53,7 +63,7 @@ fn check_op(wat_op: &str, expected_ir: midenc_expect_test::ExpectFile) {
         .into_result()
         .unwrap();
 
-    expected_ir.assert_eq(&w);
+    w
 }
 
 #[test]
@@ -1203,3 +1213,271 @@ fn globals() {
         expect_file!("./expected/globals.hir"),
     )
 }
+
+#[test]
+fn call_indirect() {
+    let w = translate_functions(
+        r#"
+        (module
+            (type $binop (func (param i32 i32) (result i32)))
+            (type $unop (func (param i32) (result i32)))
+            (table 4 funcref)
+            (elem (i32.const 1) $add $neg $sub)
+            (func $add (type $binop) local.get 0 local.get 1 i32.add)
+            (func $neg (type $unop) i32.const 0 local.get 0 i32.sub)
+            (func $sub (type $binop) local.get 0 local.get 1 i32.sub)
+            (func $dispatch (param i32 i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                local.get 2
+                call_indirect (type $binop)
+            )
+            (export "dispatch" (func $dispatch))
+        )"#,
+    );
+
+    // Only the entries of the expected type are callable, anything else traps
+    let (_, dispatch) = w.split_once("@dispatch(").unwrap();
+    assert!(dispatch.contains("cf.switch"), "{dispatch}");
+    assert!(dispatch.contains("ub.unreachable"), "{dispatch}");
+    assert_eq!(dispatch.matches("hir.exec").count(), 2, "{dispatch}");
+    assert!(dispatch.contains("@add("), "{dispatch}");
+    assert!(dispatch.contains("@sub("), "{dispatch}");
+    assert!(!dispatch.contains("@neg("), "{dispatch}");
+}
+
+#[test]
+fn translation_errors_name_the_function() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (table 1 funcref)
+            (func $uses_table_size (result i32)
+                table.size 0
+            )
+            (export "entry" (func $uses_table_size))
+        )"#,
+    )
+    .unwrap();
+    let context = Rc::new(midenc_hir::Context::default());
+    let Err(err) = translate(&wasm, &WasmTranslationConfig::default(), context) else {
+        panic!("expected translation of table.size to fail");
+    };
+
+    let report = midenc_session::diagnostics::PrintDiagnostic::new(err).to_string();
+    assert!(report.contains("failed to translate function 'uses_table_size'"), "{report}");
+    assert!(report.contains("is not supported"), "{report}");
+}
+
+/// A function performing floating-point arithmetic, which is only ever executed if its argument is
+/// non-zero
+const LIVE_FLOAT_WAT: &str = r#"
+    (module
+        (func $average (param f64 f64) (result f64)
+            local.get 0
+            local.get 1
+            f64.add
+            f64.const 2
+            f64.div
+        )
+        (export "average" (func $average))
+    )"#;
+
+/// A function containing floating-point arithmetic after a trap, where it can never be executed
+const DEAD_FLOAT_WAT: &str = r#"
+    (module
+        (func $checked (param i32) (result i32)
+            unreachable
+            f32.const 1
+            f32.const 2
+            f32.add
+            i32.trunc_f32_s
+        )
+        (export "checked" (func $checked))
+    )"#;
+
+fn float_mode_config(float_mode: FloatMode) -> WasmTranslationConfig {
+    WasmTranslationConfig {
+        float_mode,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn dead_float_code_is_translated_in_every_mode() {
+    for float_mode in [FloatMode::Reject, FloatMode::SoftFloat, FloatMode::Unreachable] {
+        let w = translate_functions_with_config(DEAD_FLOAT_WAT, &float_mode_config(float_mode));
+        assert!(w.contains("ub.unreachable"), "{float_mode:?}: {w}");
+        assert!(!w.contains("softfloat"), "{float_mode:?}: {w}");
+    }
+}
+
+#[test]
+fn live_float_code_is_rejected_by_default() {
+    let wasm = wat::parse_str(LIVE_FLOAT_WAT).unwrap();
+    let context = Rc::new(midenc_hir::Context::default());
+    let Err(err) = translate(&wasm, &WasmTranslationConfig::default(), context) else {
+        panic!("expected translation of f64.add to fail");
+    };
+
+    let report = midenc_session::diagnostics::PrintDiagnostic::new(err).to_string();
+    assert!(report.contains("failed to translate function 'average'"), "{report}");
+    assert!(report.contains("floating-point operator F64Add"), "{report}");
+}
+
+#[test]
+fn live_float_code_calls_softfloat_library() {
+    let w =
+        translate_functions_with_config(LIVE_FLOAT_WAT, &float_mode_config(FloatMode::SoftFloat));
+    let (_, average) = w.split_once("@average(").unwrap();
+    assert_eq!(average.matches("hir.exec").count(), 2, "{average}");
+    assert!(average.contains("f64_add"), "{average}");
+    assert!(average.contains("f64_div"), "{average}");
+    assert!(!average.contains("ub.unreachable"), "{average}");
+}
+
+#[test]
+fn live_float_code_traps_when_unreachable() {
+    let w =
+        translate_functions_with_config(LIVE_FLOAT_WAT, &float_mode_config(FloatMode::Unreachable));
+    let (_, average) = w.split_once("@average(").unwrap();
+    assert!(average.contains("ub.unreachable"), "{average}");
+    assert!(!average.contains("hir.exec"), "{average}");
+}
+
+/// A linker stub for `tx::get_block_number`, and a function calling it
+const BLOCK_NUMBER_STUB_WAT: &str = r#"
+(module
+    (func $miden::protocol::tx::get_block_number (result f32)
+        unreachable
+    )
+    (func $block_number (export "block_number") (result f32)
+        call $miden::protocol::tx::get_block_number
+    )
+)"#;
+
+/// Maps `tx::get_block_number` to `fake_kernel::tx::block_height`
+const FAKE_KERNEL_ABI: &str = r#"
+[[imports]]
+module = "miden::protocol::tx"
+name = "get_block_number"
+library = "fake_kernel::tx"
+procedure = "block_height"
+params = []
+results = ["felt"]
+adaptor = "no-transform"
+"#;
+
+#[test]
+fn linker_stubs_call_mapped_procedures() {
+    let config = WasmTranslationConfig {
+        import_map: ImportMap::from_toml(&Rc::new(midenc_hir::Context::default()), FAKE_KERNEL_ABI)
+            .unwrap()
+            .into(),
+        ..Default::default()
+    };
+    let w = translate_functions_with_config(BLOCK_NUMBER_STUB_WAT, &config);
+    assert!(
+        w.lines().any(|line| line.contains("hir.exec") && line.contains("block_height")),
+        "{w}"
+    );
+}
+
+#[test]
+fn unmapped_linker_stubs_are_reported() {
+    let wat = BLOCK_NUMBER_STUB_WAT.replace("get_block_number", "get_block_height");
+    let wasm = wat::parse_str(wat).unwrap();
+    let context = Rc::new(midenc_hir::Context::default());
+    let Err(err) = translate(&wasm, &WasmTranslationConfig::default(), context) else {
+        panic!("expected translation of an unmapped linker stub to fail");
+    };
+
+    let report = midenc_session::diagnostics::PrintDiagnostic::new(err).to_string();
+    assert!(report.contains("no Miden Assembly procedure is known"), "{report}");
+    assert!(report.contains("miden::protocol::tx::get_block_height"), "{report}");
+}
+
+#[test]
+fn kernel_abi_files_are_validated() {
+    let context = Rc::new(midenc_hir::Context::default());
+    let invalid = FAKE_KERNEL_ABI.replace("no-transform", "by-magic");
+    let err = ImportMap::from_toml(&context, &invalid).unwrap_err();
+    assert!(err.to_string().contains("unknown adaptor 'by-magic'"), "{err}");
+
+    let invalid = FAKE_KERNEL_ABI.replace(r#"["felt"]"#, r#"["f32"]"#);
+    let err = ImportMap::from_toml(&context, &invalid).unwrap_err();
+    assert!(err.to_string().contains("invalid type 'f32'"), "{err}");
+
+    // Types are written as in IR assembly
+    let pointer = FAKE_KERNEL_ABI.replace(r#"["felt"]"#, r#"["ptr<struct<u32, felt>, byte>"]"#);
+    let map = ImportMap::from_toml(&context, &pointer).unwrap();
+    let mapped = map.get_import("miden::protocol::tx", "get_block_number").unwrap();
+    assert!(mapped.signature.results[0].is_pointer(), "{:?}", mapped.signature);
+}
+
+#[test]
+fn passive_data_requires_the_end_of_used_memory() {
+    let wat = r#"(module
+  (memory 1)
+  (data $passive "\01\02\03\04")
+  (func (export "entrypoint")
+    i32.const 0
+    i32.const 0
+    i32.const 4
+    memory.init $passive
+  )
+)"#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let context = Rc::new(midenc_hir::Context::default());
+    let Err(err) = translate(&wasm, &WasmTranslationConfig::default(), context) else {
+        panic!("expected translation of passive data without `__heap_base` to fail");
+    };
+
+    let report = midenc_session::diagnostics::PrintDiagnostic::new(err).to_string();
+    assert!(report.contains("unable to place passive data segments"), "{report}");
+}
+
+#[test]
+fn pointer_types_are_interned() {
+    let wat = r#"(module
+  (memory 1)
+  (func (export "entrypoint") (param i32 i32) (result i64)
+    local.get 0
+    local.get 1
+    i32.load offset=4
+    i32.store offset=8
+    local.get 1
+    i64.load
+    local.get 0
+    i64.load offset=16
+    i64.add
+  )
+)"#;
+    let wasm = wat::parse_str(wat).unwrap();
+    let context = Rc::new(midenc_hir::Context::default());
+    let output = translate(&wasm, &WasmTranslationConfig::default(), context).unwrap();
+
+    let mut pointers = Vec::new();
+    output
+        .component
+        .borrow()
+        .as_operation()
+        .prewalk(|op: &Operation| {
+            for result in op.results().iter() {
+                if let midenc_hir::Type::Ptr(pointer) = result.borrow().ty().clone() {
+                    pointers.push(pointer);
+                }
+            }
+            WalkResult::<()>::Continue(())
+        })
+        .into_result()
+        .unwrap();
+
+    // Each access builds its own pointer type, but equal ones share the same allocation
+    assert!(pointers.len() >= 4, "{pointers:?}");
+    for a in pointers.iter() {
+        for b in pointers.iter() {
+            assert_eq!(a == b, std::sync::Arc::ptr_eq(a, b), "{a:?} and {b:?}");
+        }
+    }
+}
@@ -0,0 +1,451 @@
+//! Passing `list<u8>` and `string` values across contexts.
+//!
+//! A cross-context `call` cannot pass a pointer into the linear memory of the caller, as each
+//! context has its own memory. Instead, the sender inserts the words of its memory holding the
+//! bytes into the advice map, under the commitment to those words, and passes the commitment
+//! along with the position of the bytes within the words. The receiver allocates a buffer in its
+//! own memory with `cabi_realloc`, and copies the words from the advice provider into it, which
+//! checks them against the commitment.
+//!
+//! Only byte lists which are a parameter or the result of a component function are passed this
+//! way: lists of other element types, and lists nested in another type, are still rejected.
+
+use alloc::rc::Rc;
+
+use midenc_dialect_arith::ArithOpBuilder;
+use midenc_dialect_hir::HirOpBuilder;
+use midenc_hir::{
+    Builder, CallConv, Context, FunctionType, SmallVec, SourceSpan, StructType,
+    SymbolNameComponent, SymbolPath, Type, ValueRef, Visibility,
+    diagnostics::WrapErr,
+    dialects::builtin::{
+        FunctionRef, ModuleBuilder, WorldBuilder,
+        attributes::{AbiParam, Signature},
+    },
+    interner::{Symbol, symbols},
+};
+use midenc_session::diagnostics::Report;
+
+use super::{
+    MAX_DIRECT_STACK_FELTS, MAX_FLAT_PARAMS, MAX_FLAT_RESULTS,
+    flat::{CanonicalAbiMode, flatten_type, flatten_types},
+};
+use crate::{
+    error::WasmResult,
+    intrinsics::{
+        Intrinsic, IntrinsicEffect, IntrinsicsConversionResult, attach_effects_to_function,
+    },
+    miden_abi::{
+        miden_abi_function_effects, miden_abi_function_type,
+        stdlib::{crypto::hashes::poseidon2::HASH_WORDS, mem},
+    },
+    module::function_builder_ext::FunctionBuilderExt,
+};
+
+/// The number of flat values a byte list is passed across contexts as, see [transfer_type]
+pub const TRANSFER_FLAT_VALUES: usize = 6;
+
+/// Returns true if `ty` is a `list<u8>`, which is also how a `string` is represented.
+pub fn is_byte_list(ty: &Type) -> bool {
+    matches!(ty, Type::List(elem_ty) if **elem_ty == Type::U8)
+}
+
+/// Returns true if a parameter or the result of the function type `ty` is a byte list.
+pub fn has_byte_lists(ty: &FunctionType) -> bool {
+    ty.params.iter().chain(ty.results.iter()).any(is_byte_list)
+}
+
+/// The type a byte list is passed across contexts as: the commitment to the words holding the
+/// bytes, the offset of the first byte in the first of those words, and the number of bytes.
+fn transfer_type() -> Type {
+    Type::from(StructType::new([
+        Type::Felt,
+        Type::Felt,
+        Type::Felt,
+        Type::Felt,
+        Type::I32,
+        Type::I32,
+    ]))
+}
+
+/// The type of the (pointer, length) pair a byte list is stored in memory as.
+fn pointer_and_length_type() -> Type {
+    Type::from(StructType::new([Type::I32, Type::I32]))
+}
+
+/// Returns the function type `ty`, with each byte list parameter and result replaced by the type
+/// it is passed across contexts as.
+pub fn cross_context_function_type(ty: &FunctionType) -> FunctionType {
+    let transfer = |ty: &Type| {
+        if is_byte_list(ty) {
+            transfer_type()
+        } else {
+            ty.clone()
+        }
+    };
+    FunctionType {
+        params: ty.params.iter().map(transfer).collect(),
+        results: ty.results.iter().map(transfer).collect(),
+        abi: ty.abi,
+    }
+}
+
+/// Returns the signature across contexts of the component function `func_path` of type `ty`.
+///
+/// Both the parameters and the results are passed on the operand stack, as the callee cannot
+/// return a pointer into its own memory.
+pub fn cross_context_signature(
+    context: &Rc<Context>,
+    func_path: &SymbolPath,
+    ty: &FunctionType,
+) -> WasmResult<Signature> {
+    let cross_ty = cross_context_function_type(ty);
+    let params = flatten_types(context, &cross_ty.params)?;
+    let results = flatten_types(context, &cross_ty.results)?;
+    for (kind, values) in [("parameters", &params), ("results", &results)] {
+        let felts = values.iter().map(|value| value.ty.size_in_felts()).sum::<usize>();
+        if felts > MAX_DIRECT_STACK_FELTS {
+            return Err(Report::msg(format!(
+                "the {kind} of '{func_path}' are passed across contexts as {felts} felts, each \
+                 `string` and `list<u8>` taking {TRANSFER_FLAT_VALUES}, but at most \
+                 {MAX_DIRECT_STACK_FELTS} are supported"
+            )));
+        }
+    }
+
+    Ok(Signature {
+        params,
+        results,
+        cc: CallConv::ComponentModel,
+    })
+}
+
+/// Returns the core Wasm values the parameter or result type `ty` is flattened to.
+pub fn core_flat_values(context: &Rc<Context>, ty: &Type) -> WasmResult<Vec<AbiParam>> {
+    if is_byte_list(ty) {
+        Ok(vec![AbiParam::new(Type::I32); 2])
+    } else {
+        Ok(flatten_type(context, ty)?)
+    }
+}
+
+/// Returns the signature of the core Wasm function which the component function `func_path` of
+/// type `ty` is lowered to (for an import) or lifted from (for an export).
+///
+/// A result which does not fit in a single core Wasm value is passed through a pointer: an extra
+/// parameter for an import, and the result for an export.
+pub fn core_signature(
+    context: &Rc<Context>,
+    func_path: &SymbolPath,
+    ty: &FunctionType,
+    mode: CanonicalAbiMode,
+) -> WasmResult<Signature> {
+    let mut params = Vec::new();
+    for param in ty.params.iter() {
+        params.extend(core_flat_values(context, param)?);
+    }
+    if params.len() > MAX_FLAT_PARAMS {
+        return Err(Report::msg(format!(
+            "'{func_path}' takes {} core Wasm parameters, but passing parameters through a \
+             pointer to a tuple is not supported alongside `string` and `list<u8>` parameters",
+            params.len()
+        )));
+    }
+    let mut results = Vec::new();
+    for result in ty.results.iter() {
+        results.extend(core_flat_values(context, result)?);
+    }
+    if results.len() > MAX_FLAT_RESULTS {
+        results = match mode {
+            CanonicalAbiMode::Import => {
+                params.push(AbiParam::new(Type::I32));
+                vec![]
+            }
+            CanonicalAbiMode::Export => vec![AbiParam::new(Type::I32)],
+        };
+    }
+
+    Ok(Signature {
+        params,
+        results,
+        cc: CallConv::ComponentModel,
+    })
+}
+
+/// Makes the `len` bytes at `ptr` in the memory of the current context available to another
+/// context, and returns the values they are passed across contexts as, see [transfer_type].
+pub fn send_bytes<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    world_builder: &mut WorldBuilder,
+    ptr: ValueRef,
+    len: ValueRef,
+    span: SourceSpan,
+) -> WasmResult<[ValueRef; TRANSFER_FLAT_VALUES]> {
+    let ptr = fb.bitcast(ptr, Type::U32, span)?;
+    let len_u32 = fb.bitcast(len, Type::U32, span)?;
+    let word_mask = fb.u32(15, span);
+    let offset = fb.band(ptr, word_mask, span)?;
+    let start = fb.sub_unchecked(ptr, offset, span)?;
+    let num_words = num_words(fb, offset, len_u32, span)?;
+    let start_addr = element_address(fb, start, span)?;
+    let elements_per_word = fb.u32(4, span);
+    let num_elements = fb.mul(num_words, elements_per_word, span)?;
+    let end_addr = fb.add(start_addr, num_elements, span)?;
+
+    let hash_words = stdlib_function(world_builder, hash_words_path())?;
+    let start_arg = fb.bitcast(start_addr, Type::I32, span)?;
+    let end_arg = fb.bitcast(end_addr, Type::I32, span)?;
+    let commitment = exec_function(fb, hash_words, [start_arg, end_arg], span)?;
+
+    let insert_mem = advice_intrinsic(world_builder, "adv_insert_mem")?;
+    let start_arg = fb.bitcast(start_addr, Type::Felt, span)?;
+    let end_arg = fb.bitcast(end_addr, Type::Felt, span)?;
+    exec_function(fb, insert_mem, commitment.iter().copied().chain([start_arg, end_arg]), span)?;
+
+    let offset = fb.bitcast(offset, Type::I32, span)?;
+    Ok([commitment[0], commitment[1], commitment[2], commitment[3], offset, len])
+}
+
+/// Copies the bytes passed across contexts as `values` (see [transfer_type]) into a buffer
+/// allocated with `realloc` in the memory of the current context, and returns the pointer to and
+/// the length of the bytes.
+pub fn receive_bytes<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    world_builder: &mut WorldBuilder,
+    realloc: FunctionRef,
+    values: &[ValueRef],
+    span: SourceSpan,
+) -> WasmResult<[ValueRef; 2]> {
+    let &[c0, c1, c2, c3, offset, len] = values else {
+        return Err(Report::msg(format!(
+            "expected {TRANSFER_FLAT_VALUES} values for a byte list passed across contexts, got {}",
+            values.len()
+        )));
+    };
+    let realloc_sig = realloc.borrow().get_signature().clone();
+    let realloc_takes_i32 = |values: &[AbiParam], count: usize| {
+        values.len() == count && values.iter().all(|value| value.ty == Type::I32)
+    };
+    if !realloc_takes_i32(realloc_sig.params(), 4) || !realloc_takes_i32(realloc_sig.results(), 1) {
+        return Err(Report::msg(format!(
+            "expected `cabi_realloc` to have the signature (i32, i32, i32, i32) -> i32, got \
+             {realloc_sig:?}"
+        )));
+    }
+
+    // The offset and length come from another context, so the buffer size is computed with
+    // overflow checks
+    let offset = fb.bitcast(offset, Type::U32, span)?;
+    let len_u32 = fb.bitcast(len, Type::U32, span)?;
+    let num_words = num_words(fb, offset, len_u32, span)?;
+    let word_size = fb.u32(16, span);
+    let size = fb.mul(num_words, word_size, span)?;
+    let size = fb.bitcast(size, Type::I32, span)?;
+    // cabi_realloc(old_ptr, old_len, align, new_len)
+    let null = fb.i32(0, span);
+    let align = fb.i32(16, span);
+    let buf = exec_function(fb, realloc, [null, null, align, size], span)?[0];
+
+    let push_mapval = advice_intrinsic(world_builder, "adv_push_mapval")?;
+    exec_function(fb, push_mapval, [c0, c1, c2, c3], span)?;
+    let buf = fb.bitcast(buf, Type::U32, span)?;
+    let write_addr = element_address(fb, buf, span)?;
+    let write_addr = fb.bitcast(write_addr, Type::I32, span)?;
+    let num_words = fb.bitcast(num_words, Type::Felt, span)?;
+    let pipe_preimage = stdlib_function(world_builder, pipe_preimage_to_memory_path())?;
+    exec_function(fb, pipe_preimage, [num_words, write_addr, c0, c1, c2, c3], span)?;
+
+    let ptr = fb.add_unchecked(buf, offset, span)?;
+    let ptr = fb.bitcast(ptr, Type::I32, span)?;
+    Ok([ptr, len])
+}
+
+/// Loads the (pointer, length) pair of a byte list stored at `ptr`.
+pub fn load_pointer_and_length<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    ptr: ValueRef,
+    span: SourceSpan,
+) -> WasmResult<[ValueRef; 2]> {
+    let mut values = SmallVec::<[ValueRef; 8]>::new();
+    super::canon_abi_utils::load(fb, ptr, &pointer_and_length_type(), &mut values, span)?;
+    Ok([values[0], values[1]])
+}
+
+/// Stores the (pointer, length) pair of a byte list at `ptr`.
+pub fn store_pointer_and_length<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    ptr: ValueRef,
+    pointer_and_length: [ValueRef; 2],
+    span: SourceSpan,
+) -> WasmResult<()> {
+    super::canon_abi_utils::store(
+        fb,
+        ptr,
+        &pointer_and_length_type(),
+        &mut pointer_and_length.into_iter(),
+        span,
+    )
+}
+
+/// Returns the number of words holding `len` bytes, the first of which is `offset` bytes into the
+/// first word.
+///
+/// This counts one word past the last byte when the bytes end on a word boundary, so that even an
+/// empty byte list is passed as a word, which keeps the generated code free of branches.
+fn num_words<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    offset: ValueRef,
+    len: ValueRef,
+    span: SourceSpan,
+) -> WasmResult<ValueRef> {
+    let end = fb.add(offset, len, span)?;
+    let word_shift = fb.u32(4, span);
+    let full_words = fb.shr(end, word_shift, span)?;
+    let one = fb.u32(1, span);
+    fb.add(full_words, one, span)
+}
+
+/// Converts the byte address `addr` into the address of the element holding it, as each element of
+/// Miden memory holds 4 bytes.
+fn element_address<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    addr: ValueRef,
+    span: SourceSpan,
+) -> WasmResult<ValueRef> {
+    let element_shift = fb.u32(2, span);
+    fb.shr(addr, element_shift, span)
+}
+
+/// Calls `callee` in the current context, and returns its results.
+fn exec_function<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<'_, B>,
+    callee: FunctionRef,
+    args: impl IntoIterator<Item = ValueRef>,
+    span: SourceSpan,
+) -> WasmResult<SmallVec<[ValueRef; 4]>> {
+    let signature = callee.borrow().get_signature().clone();
+    let exec = fb.exec(callee, signature, args, span)?;
+    let borrow = exec.borrow();
+    Ok(borrow.results().iter().map(|op_res| op_res.borrow().as_value_ref()).collect())
+}
+
+/// The path of `miden::core::crypto::hashes::poseidon2::hash_words`
+fn hash_words_path() -> SymbolPath {
+    let mut path = SymbolPath::from_iter([
+        SymbolNameComponent::Root,
+        SymbolNameComponent::Component(symbols::Miden),
+        SymbolNameComponent::Component(symbols::Core),
+        SymbolNameComponent::Component(symbols::Crypto),
+        SymbolNameComponent::Component(symbols::Hashes),
+        SymbolNameComponent::Component(symbols::Poseidon2),
+    ]);
+    path.set_name(Symbol::intern(HASH_WORDS));
+    path
+}
+
+/// The path of `miden::core::mem::pipe_preimage_to_memory`
+fn pipe_preimage_to_memory_path() -> SymbolPath {
+    let mut path = SymbolPath::from_iter(mem::MODULE_PREFIX.iter().copied());
+    path.set_name(Symbol::intern(mem::PIPE_PREIMAGE_TO_MEMORY));
+    path
+}
+
+/// Get the declaration of the core library procedure at `path`, declaring it in the world on
+/// first use
+fn stdlib_function(world_builder: &mut WorldBuilder, path: SymbolPath) -> WasmResult<FunctionRef> {
+    let ty = miden_abi_function_type(&path);
+    let effects = miden_abi_function_effects(&path);
+    declare_function(world_builder, &path, ty, &effects)
+}
+
+/// Get the declaration of the advice intrinsic `name`, declaring it in the world on first use
+fn advice_intrinsic(world_builder: &mut WorldBuilder, name: &str) -> WasmResult<FunctionRef> {
+    let intrinsic = Intrinsic::Advice(Symbol::intern(name));
+    let Some(IntrinsicsConversionResult::FunctionType { ty, effects }) =
+        intrinsic.conversion_result()
+    else {
+        unreachable!("the advice intrinsic '{name}' is implemented as a function");
+    };
+    declare_function(world_builder, &intrinsic.into_symbol_path(), ty, &effects)
+}
+
+fn declare_function(
+    world_builder: &mut WorldBuilder,
+    path: &SymbolPath,
+    ty: FunctionType,
+    effects: &[IntrinsicEffect],
+) -> WasmResult<FunctionRef> {
+    let module_ref = world_builder
+        .declare_module_tree(&path.without_leaf())
+        .wrap_err("failed to create module for byte list transfer")?;
+    let mut module_builder = ModuleBuilder::new(module_ref);
+    if let Some(function_ref) = module_builder.get_function(path.name().as_str()) {
+        return Ok(function_ref);
+    }
+
+    let context = world_builder.context_rc();
+    let signature = Signature::new(&context, ty.params, ty.results);
+    let mut function_ref = module_builder
+        .define_function(path.name().into(), Visibility::Public, signature)
+        .wrap_err("failed to create byte list transfer function ref")?;
+    {
+        let mut function = function_ref.borrow_mut();
+        attach_effects_to_function(&mut function, effects.iter());
+    }
+    Ok(function_ref)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn byte_lists_are_passed_across_contexts_as_commitment_offset_and_length() {
+        let context = Rc::new(Context::default());
+        let path = SymbolPath::from_iter([
+            SymbolNameComponent::Root,
+            SymbolNameComponent::Component(Symbol::intern("miden")),
+            SymbolNameComponent::Leaf(Symbol::intern("shout")),
+        ]);
+        let byte_list = Type::List(Arc::new(Type::U8));
+        let ty = FunctionType::new(
+            CallConv::ComponentModel,
+            vec![byte_list.clone(), Type::U32],
+            vec![byte_list],
+        );
+
+        let cross_sig = cross_context_signature(&context, &path, &ty).unwrap();
+        let cross_params: Vec<_> = cross_sig.params().iter().map(|param| &param.ty).collect();
+        assert_eq!(
+            cross_params,
+            [
+                &Type::Felt,
+                &Type::Felt,
+                &Type::Felt,
+                &Type::Felt,
+                &Type::I32,
+                &Type::I32,
+                &Type::I32
+            ]
+        );
+        assert_eq!(cross_sig.results().len(), TRANSFER_FLAT_VALUES);
+
+        let import_sig = core_signature(&context, &path, &ty, CanonicalAbiMode::Import).unwrap();
+        assert_eq!(import_sig.params().len(), 4, "expected (ptr, len, u32, out_ptr)");
+        assert!(import_sig.results().is_empty());
+        let export_sig = core_signature(&context, &path, &ty, CanonicalAbiMode::Export).unwrap();
+        assert_eq!(export_sig.params().len(), 3);
+        assert_eq!(export_sig.results().len(), 1, "expected a pointer to (ptr, len)");
+    }
+
+    #[test]
+    fn only_top_level_byte_lists_are_passed_across_contexts() {
+        let nested = Type::from(StructType::new([Type::List(Arc::new(Type::U8))]));
+        let words = Type::List(Arc::new(Type::U32));
+        let ty = FunctionType::new(CallConv::ComponentModel, vec![nested, words], vec![]);
+
+        assert!(!has_byte_lists(&ty));
+    }
+}
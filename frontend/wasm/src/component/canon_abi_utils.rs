@@ -194,6 +194,31 @@ pub fn store<B: ?Sized + Builder>(
     Ok(())
 }
 
+/// Clears the bits of the flattened `flags` limbs among `values` which do not correspond to any
+/// flag, as required when lifting them, returning the resulting values.
+///
+/// `masks` holds the mask of each of `values`, see [super::FlatFlagsMasks].
+pub fn mask_flat_flags<B: ?Sized + Builder>(
+    fb: &mut FunctionBuilderExt<B>,
+    values: &[ValueRef],
+    masks: &[Option<u32>],
+    span: SourceSpan,
+) -> WasmResult<Vec<ValueRef>> {
+    let mut masked = Vec::with_capacity(values.len());
+    for (index, value) in values.iter().copied().enumerate() {
+        let Some(mask) = masks.get(index).copied().flatten() else {
+            masked.push(value);
+            continue;
+        };
+        let ty = value.borrow().ty().clone();
+        let limb = convert_flat_value(fb, value, &Type::U32, span)?;
+        let mask = fb.u32(mask, span);
+        let limb = fb.band(limb, mask, span)?;
+        masked.push(convert_flat_value(fb, limb, &ty, span)?);
+    }
+    Ok(masked)
+}
+
 /// Validates variant discriminants in a sequence of flattened canonical ABI values.
 pub fn validate_flat_variants<B: ?Sized + Builder>(
     fb: &mut FunctionBuilderExt<B>,
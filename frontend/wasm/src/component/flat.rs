@@ -104,7 +104,10 @@ pub fn flatten_type(context: &Rc<Context>, ty: &Type) -> Result<Vec<AbiParam>, C
         }
         Type::List(elem_ty) => vec![
             // pointer to the list element type
-            AbiParam::sret(Type::from(PointerType::new(elem_ty.as_ref().clone())), context),
+            AbiParam::sret(
+                context.intern_type(Type::from(PointerType::new(elem_ty.as_ref().clone()))),
+                context,
+            ),
             // length of the list
             AbiParam::new(Type::I32),
         ],
@@ -267,7 +270,8 @@ pub fn flatten_function_type(
         // (pointing to a tuple in linear memory). When lowering into linear memory, this requires the
         // Canonical ABI to call `realloc` to allocate space to put the tuple.
         let tuple = Type::from(StructType::new(func_ty.params.clone()));
-        flat_params = vec![AbiParam::sret(Type::from(PointerType::new(tuple)), context)];
+        let tuple_ptr = context.intern_type(Type::from(PointerType::new(tuple)));
+        flat_params = vec![AbiParam::sret(tuple_ptr, context)];
     }
     if flat_results.len() > MAX_FLAT_RESULTS {
         // from https://github.com/WebAssembly/component-model/blob/main/design/mvp/CanonicalABI.md#flattening
@@ -278,12 +282,13 @@ pub fn flatten_function_type(
         // returning an `i32` as a return value.
         assert_eq!(func_ty.results.len(), 1, "expected a single result");
         let result = func_ty.results.first().expect("unexpected empty results").clone();
+        let result_ptr = context.intern_type(Type::from(PointerType::new(result)));
         match mode {
             CanonicalAbiMode::Export => {
-                flat_results = vec![AbiParam::sret(Type::from(PointerType::new(result)), context)];
+                flat_results = vec![AbiParam::sret(result_ptr, context)];
             }
             CanonicalAbiMode::Import => {
-                flat_params.push(AbiParam::sret(Type::from(PointerType::new(result)), context));
+                flat_params.push(AbiParam::sret(result_ptr, context));
                 flat_results = vec![];
             }
         }
@@ -9,7 +9,7 @@ use midenc_hir::{
     Visibility,
     dialects::{
         builtin::{
-            BuiltinOpBuilder, ComponentBuilder, ModuleBuilder,
+            BuiltinOpBuilder, ComponentBuilder, FunctionRef, ModuleBuilder, WorldBuilder,
             attributes::{AbiParam, Signature, UnitAttr},
         },
         debuginfo::attributes::{CompileUnit, CompileUnitAttr, Subprogram, SubprogramAttr},
@@ -21,9 +21,14 @@ use midenc_session::{
 };
 
 use super::{
-    ComponentFunctionType,
-    canon_abi_utils::{load, validate_flat_variants},
-    contains_unsupported_canonical_abi_type,
+    ComponentFunctionType, MAX_FLAT_RESULTS, UNSUPPORTED_LIST_NOTE,
+    byte_lists::{
+        TRANSFER_FLAT_VALUES, core_flat_values, core_signature, cross_context_function_type,
+        cross_context_signature, has_byte_lists, is_byte_list, load_pointer_and_length,
+        receive_bytes, send_bytes,
+    },
+    canon_abi_utils::{load, mask_flat_flags, validate_flat_variants},
+    contains_list_type, contains_unsupported_canonical_abi_type,
     flat::{
         CanonicalAbiMode, check_core_wasm_signature_equivalence, classify_function_type,
         flatten_function_type, flatten_types,
@@ -38,21 +43,41 @@ use crate::{
 
 struct ComponentExportMetadata<'a> {
     ty: &'a FunctionType,
+    param_flags_masks: &'a [Option<u32>],
     param_names: &'a [String],
     protocol_export_kind: Option<ProtocolExportKind>,
 }
 
 /// Generates a lifted component export wrapper around a lowered core Wasm export.
+///
+/// `core_realloc_func_path` is the path of the `realloc` canonical option of the export, i.e. the
+/// `cabi_realloc` function of the core module, which is required to receive a `string` or
+/// `list<u8>` parameter.
+#[allow(clippy::too_many_arguments)]
 pub fn generate_export_lifting_function(
     component_builder: &mut ComponentBuilder,
+    world_builder: &mut WorldBuilder,
     export_func_name: &str,
     export_func_ty: ComponentFunctionType,
     export_param_names: &[String],
     core_export_func_path: SymbolPath,
+    core_realloc_func_path: Option<SymbolPath>,
     protocol_export_kind: Option<ProtocolExportKind>,
     diagnostics: &DiagnosticsHandler,
 ) -> WasmResult<()> {
     reject_unsupported_export_canonical_abi_types(&core_export_func_path, &export_func_ty)?;
+    if has_byte_lists(&export_func_ty.ir) {
+        return generate_lifting_with_byte_lists(
+            component_builder,
+            world_builder,
+            export_func_name,
+            &export_func_ty,
+            export_param_names,
+            &core_export_func_path,
+            core_realloc_func_path,
+            protocol_export_kind,
+        );
+    }
     let context = { component_builder.component.borrow().as_operation().context_rc() };
     let cross_ctx_export_sig_flat =
         flatten_function_type(&context, &export_func_ty.ir, CanonicalAbiMode::Export).map_err(
@@ -82,6 +107,7 @@ pub fn generate_export_lifting_function(
     })?;
     let export_metadata = ComponentExportMetadata {
         ty: &export_func_ty.ir,
+        param_flags_masks: &export_func_ty.flags_masks.params,
         param_names: export_param_names,
         protocol_export_kind,
     };
@@ -254,6 +280,7 @@ fn generate_lifting_with_transformation(
     // 1. Load the data from that pointer into the "flattened" representation (primitive types)
     // 2. Return it as individual values (tuple)
 
+    let args = mask_flat_flags(&mut fb, &args, export_metadata.param_flags_masks, span)?;
     validate_flat_variants(&mut fb, &export_metadata.ty.params, &args, span)?;
 
     let exec = fb.exec(core_export_func_ref, core_export_func_sig, args, span)?;
@@ -374,6 +401,7 @@ fn generate_direct_lifting(
         .map(|ba| ba as ValueRef)
         .collect();
 
+    let args = mask_flat_flags(&mut fb, &args, export_metadata.param_flags_masks, span)?;
     validate_flat_variants(&mut fb, &export_metadata.ty.params, &args, span)?;
 
     let exec = fb
@@ -399,18 +427,174 @@ fn generate_direct_lifting(
     Ok(())
 }
 
+/// Generates a lifting function for component exports passing a `string` or `list<u8>`.
+///
+/// The bytes of each such parameter are copied from the advice provider into a buffer allocated
+/// with the `realloc` function of the core module, see [super::byte_lists], and the bytes of such a
+/// result are made available to the caller via the advice map.
+#[allow(clippy::too_many_arguments)]
+fn generate_lifting_with_byte_lists(
+    component_builder: &mut ComponentBuilder,
+    world_builder: &mut WorldBuilder,
+    export_func_name: &str,
+    export_func_ty: &ComponentFunctionType,
+    export_param_names: &[String],
+    core_export_func_path: &SymbolPath,
+    core_realloc_func_path: Option<SymbolPath>,
+    protocol_export_kind: Option<ProtocolExportKind>,
+) -> WasmResult<()> {
+    let context = { component_builder.component.borrow().as_operation().context_rc() };
+    let cross_ctx_ty = cross_context_function_type(&export_func_ty.ir);
+    let cross_ctx_sig =
+        cross_context_signature(&context, core_export_func_path, &export_func_ty.ir)?;
+    let expected_core_sig = core_signature(
+        &context,
+        core_export_func_path,
+        &export_func_ty.ir,
+        CanonicalAbiMode::Export,
+    )?;
+
+    let core_export_func_ref = resolve_core_function(component_builder, core_export_func_path)
+        .expect("failed to find the core module export function");
+    let core_export_func_sig = core_export_func_ref.borrow().get_signature().clone();
+    check_core_wasm_signature_equivalence(&core_export_func_sig, &expected_core_sig).map_err(
+        |message| {
+            Report::msg(format!(
+                "component export lifting for '{core_export_func_path}' has core Wasm signature \
+                 mismatch: {message}"
+            ))
+        },
+    )?;
+    let realloc = match core_realloc_func_path {
+        Some(path) => {
+            Some(resolve_core_function(component_builder, &path).ok_or_else(|| {
+                Report::msg(format!("failed to find the realloc function '{path}'"))
+            })?)
+        }
+        None => None,
+    };
+    if realloc.is_none() && export_func_ty.ir.params.iter().any(is_byte_list) {
+        return Err(Report::msg(format!(
+            "component export lifting for '{core_export_func_path}' requires a `realloc` \
+             function, to allocate the bytes of the parameters"
+        )));
+    }
+
+    let export_func_span = core_export_func_ref.borrow().span();
+    let export_func_ident =
+        Ident::new(midenc_hir::interner::Symbol::intern(export_func_name), export_func_span);
+    let mut core_module_builder = ModuleBuilder::new(
+        component_builder
+            .resolve_module(&core_export_func_path.without_leaf())
+            .expect("failed to find the core module"),
+    );
+    core_module_builder
+        .set_function_visibility(core_export_func_path.name().as_str(), Visibility::Internal);
+
+    let export_func_ref =
+        component_builder.define_function(export_func_ident, Visibility::Public, cross_ctx_sig)?;
+    annotate_protocol_export(export_func_ref, protocol_export_kind);
+    annotate_component_export_debug_signature(
+        export_func_ref,
+        export_func_name,
+        &export_func_ty.ir,
+        export_param_names,
+    );
+
+    let span = export_func_ref.borrow().name().span;
+    let func_ctx = Rc::new(RefCell::new(FunctionBuilderContext::new(context.clone())));
+    let mut op_builder = midenc_hir::OpBuilder::new(context.clone())
+        .with_listener(SSABuilderListener::new(func_ctx));
+    let mut fb = FunctionBuilderExt::new(export_func_ref, &mut op_builder);
+
+    let entry_block = fb.current_block();
+    fb.seal_block(entry_block);
+    let args: Vec<ValueRef> = entry_block
+        .borrow()
+        .arguments()
+        .iter()
+        .copied()
+        .map(|ba| ba as ValueRef)
+        .collect();
+
+    let args = mask_flat_flags(&mut fb, &args, &export_func_ty.flags_masks.params, span)?;
+    validate_flat_variants(&mut fb, &cross_ctx_ty.params, &args, span)?;
+
+    // Convert the values passed across contexts into the core Wasm arguments
+    let mut core_args = Vec::with_capacity(expected_core_sig.params().len());
+    let mut next_arg = 0;
+    for param in export_func_ty.ir.params.iter() {
+        match realloc {
+            Some(realloc) if is_byte_list(param) => {
+                let values = &args[next_arg..next_arg + TRANSFER_FLAT_VALUES];
+                core_args.extend(receive_bytes(&mut fb, world_builder, realloc, values, span)?);
+                next_arg += TRANSFER_FLAT_VALUES;
+            }
+            _ => {
+                let num_values = core_flat_values(&context, param)?.len();
+                core_args.extend_from_slice(&args[next_arg..next_arg + num_values]);
+                next_arg += num_values;
+            }
+        }
+    }
+
+    let exec = fb.exec(core_export_func_ref, core_export_func_sig, core_args, span)?;
+    let results: Vec<ValueRef> = {
+        let borrow = exec.borrow();
+        borrow.results().iter().map(|op_res| op_res.borrow().as_value_ref()).collect()
+    };
+
+    let return_values = match export_func_ty.ir.results.first() {
+        Some(result_ty) if is_byte_list(result_ty) => {
+            let [ptr, len] = load_pointer_and_length(&mut fb, results[0], span)?;
+            send_bytes(&mut fb, world_builder, ptr, len, span)?.to_vec()
+        }
+        Some(result_ty) if core_flat_values(&context, result_ty)?.len() > MAX_FLAT_RESULTS => {
+            let mut values = SmallVec::<[ValueRef; 8]>::new();
+            load(&mut fb, results[0], result_ty, &mut values, span)?;
+            values.to_vec()
+        }
+        _ => {
+            validate_flat_variants(&mut fb, &export_func_ty.ir.results, &results, span)?;
+            results
+        }
+    };
+
+    let exit_block = fb.create_block();
+    fb.br(exit_block, return_values.clone(), span)?;
+    fb.append_block_params_for_function_returns(exit_block);
+    fb.seal_block(exit_block);
+    fb.switch_to_block(exit_block);
+    fb.ret(return_values, span)?;
+
+    Ok(())
+}
+
+/// Returns the function at `path` in a core module of the component.
+fn resolve_core_function(
+    component_builder: &ComponentBuilder,
+    path: &SymbolPath,
+) -> Option<FunctionRef> {
+    let module_ref = component_builder.resolve_module(&path.without_leaf())?;
+    ModuleBuilder::new(module_ref).get_function(path.name().as_str())
+}
+
 /// Rejects component export signatures containing unsupported canonical ABI shapes.
 fn reject_unsupported_export_canonical_abi_types(
     core_export_func_path: &SymbolPath,
     export_func_ty: &ComponentFunctionType,
 ) -> WasmResult<()> {
     for ty in export_func_ty.ir.params.iter().chain(export_func_ty.ir.results.iter()) {
-        if contains_unsupported_canonical_abi_type(ty) {
-            return Err(Report::msg(format!(
+        if contains_unsupported_canonical_abi_type(ty) && !is_byte_list(ty) {
+            let mut message = format!(
                 "component export lifting for '{core_export_func_path}' has unsupported canonical \
                  ABI type {:?}",
                 ty
-            )));
+            );
+            if contains_list_type(ty) {
+                message = format!("{message}: {UNSUPPORTED_LIST_NOTE}");
+            }
+            return Err(Report::msg(message));
         }
     }
 
@@ -494,8 +678,8 @@ mod tests {
 
     use super::*;
     use crate::component::test_support::{
-        component_function, component_with_core_module, count_validation_ops,
-        two_field_record_type, unit_only_variant_type,
+        component_function, component_with_core_module, count_ops, count_validation_ops,
+        define_realloc, two_field_record_type, unit_only_variant_type,
     };
 
     fn component_export_path(function: &str) -> SymbolPath {
@@ -511,13 +695,14 @@ mod tests {
 
     #[test]
     fn transformed_export_lifting_validates_flat_variant_params() {
-        let (_context, mut component_builder, mut module_builder) = component_with_core_module();
+        let (_context, mut world_builder, mut component_builder, mut module_builder) =
+            component_with_core_module();
 
         let variant_ty = unit_only_variant_type();
         let result_ty = two_field_record_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![variant_ty], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let export_func_ty = ComponentFunctionType { ir };
+        let export_func_ty = ComponentFunctionType::new(ir);
         // Lowered core export signatures carry bare core types without extension attributes.
         let core_sig = Signature {
             params: vec![AbiParam::new(Type::I32)],
@@ -534,11 +719,13 @@ mod tests {
 
         generate_export_lifting_function(
             &mut component_builder,
+            &mut world_builder,
             "roundtrip",
             export_func_ty,
             &["value".to_string()],
             component_export_path("roundtrip_core"),
             None,
+            None,
             &DiagnosticsHandler::default(),
         )
         .expect("export lifting should build");
@@ -554,12 +741,13 @@ mod tests {
 
     #[test]
     fn rejects_direct_export_lifting_with_mismatched_core_signature() {
-        let (_context, mut component_builder, mut module_builder) = component_with_core_module();
+        let (_context, mut world_builder, mut component_builder, mut module_builder) =
+            component_with_core_module();
 
         let result_ty = scalar_u64_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let export_func_ty = ComponentFunctionType { ir };
+        let export_func_ty = ComponentFunctionType::new(ir);
         // The flattened export signature returns a single i64, but the core export returns i32.
         let core_sig = Signature {
             params: vec![],
@@ -576,11 +764,13 @@ mod tests {
 
         let result = generate_export_lifting_function(
             &mut component_builder,
+            &mut world_builder,
             "mismatched",
             export_func_ty,
             &[],
             component_export_path("mismatched_core"),
             None,
+            None,
             &DiagnosticsHandler::default(),
         );
 
@@ -597,13 +787,14 @@ mod tests {
 
     #[test]
     fn rejects_transformed_export_lifting_with_mismatched_core_params() {
-        let (_context, mut component_builder, mut module_builder) = component_with_core_module();
+        let (_context, mut world_builder, mut component_builder, mut module_builder) =
+            component_with_core_module();
 
         let variant_ty = unit_only_variant_type();
         let result_ty = two_field_record_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![variant_ty], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let export_func_ty = ComponentFunctionType { ir };
+        let export_func_ty = ComponentFunctionType::new(ir);
         // The flattened export parameter is a single i32 discriminant, but the core export
         // takes i64.
         let core_sig = Signature {
@@ -621,11 +812,13 @@ mod tests {
 
         let result = generate_export_lifting_function(
             &mut component_builder,
+            &mut world_builder,
             "mismatched",
             export_func_ty,
             &["value".to_string()],
             component_export_path("mismatched_core"),
             None,
+            None,
             &DiagnosticsHandler::default(),
         );
 
@@ -642,12 +835,13 @@ mod tests {
 
     #[test]
     fn rejects_export_lifting_with_unsupported_list_param() {
-        let (_context, mut component_builder, mut module_builder) = component_with_core_module();
+        let (_context, mut world_builder, mut component_builder, mut module_builder) =
+            component_with_core_module();
 
-        let list_ty = Type::List(Arc::new(Type::U8));
+        let list_ty = Type::List(Arc::new(Type::U32));
         let mut ir = FunctionType::new(CallConv::Fast, vec![list_ty], vec![]);
         ir.abi = CallConv::ComponentModel;
-        let export_func_ty = ComponentFunctionType { ir };
+        let export_func_ty = ComponentFunctionType::new(ir);
         let core_sig = Signature {
             params: vec![AbiParam::new(Type::I32), AbiParam::new(Type::I32)],
             results: vec![],
@@ -663,11 +857,13 @@ mod tests {
 
         let result = generate_export_lifting_function(
             &mut component_builder,
+            &mut world_builder,
             "list_param",
             export_func_ty,
             &["value".to_string()],
             component_export_path("list_core"),
             None,
+            None,
             &DiagnosticsHandler::default(),
         );
 
@@ -678,6 +874,98 @@ mod tests {
                     err.to_string().contains("unsupported canonical ABI"),
                     "unexpected diagnostic: {err}"
                 );
+                assert!(
+                    err.to_string().contains("advice provider"),
+                    "unexpected diagnostic: {err}"
+                );
+            }
+        }
+    }
+
+    fn string_export_type() -> ComponentFunctionType {
+        let string_ty = Type::List(Arc::new(Type::U8));
+        ComponentFunctionType::new(FunctionType::new(
+            CallConv::ComponentModel,
+            vec![string_ty.clone()],
+            vec![string_ty],
+        ))
+    }
+
+    #[test]
+    fn byte_list_export_lifting_passes_bytes_via_the_advice_map() {
+        let (_context, mut world_builder, mut component_builder, mut module_builder) =
+            component_with_core_module();
+        define_realloc(&mut module_builder);
+        // (ptr, len) -> ret_ptr
+        let core_sig = Signature {
+            params: vec![AbiParam::new(Type::I32); 2],
+            results: vec![AbiParam::new(Type::I32)],
+            cc: CallConv::ComponentModel,
+        };
+        module_builder
+            .define_function(
+                Ident::with_empty_span("shout_core".into()),
+                Visibility::Public,
+                core_sig,
+            )
+            .expect("failed to define core export");
+
+        generate_export_lifting_function(
+            &mut component_builder,
+            &mut world_builder,
+            "shout",
+            string_export_type(),
+            &["s".to_string()],
+            component_export_path("shout_core"),
+            Some(component_export_path("cabi_realloc")),
+            None,
+            &DiagnosticsHandler::default(),
+        )
+        .expect("byte list export lifting should build");
+
+        let function = component_function(&component_builder, "shout");
+        let signature = function.borrow().get_signature().clone();
+        assert_eq!(signature.params().len(), TRANSFER_FLAT_VALUES);
+        assert_eq!(signature.results().len(), TRANSFER_FLAT_VALUES);
+        // Receiving allocates a buffer, pushes the words onto the advice stack, and copies them
+        // into the buffer, then the core export is called, and sending hashes the words of the
+        // result and inserts them into the advice map
+        assert_eq!(count_ops(function, |op| op.is::<midenc_dialect_hir::Exec>()), 6);
+    }
+
+    #[test]
+    fn rejects_byte_list_export_param_without_realloc() {
+        let (_context, mut world_builder, mut component_builder, mut module_builder) =
+            component_with_core_module();
+        let core_sig = Signature {
+            params: vec![AbiParam::new(Type::I32); 2],
+            results: vec![AbiParam::new(Type::I32)],
+            cc: CallConv::ComponentModel,
+        };
+        module_builder
+            .define_function(
+                Ident::with_empty_span("shout_core".into()),
+                Visibility::Public,
+                core_sig,
+            )
+            .expect("failed to define core export");
+
+        let result = generate_export_lifting_function(
+            &mut component_builder,
+            &mut world_builder,
+            "shout",
+            string_export_type(),
+            &["s".to_string()],
+            component_export_path("shout_core"),
+            None,
+            None,
+            &DiagnosticsHandler::default(),
+        );
+
+        match result {
+            Ok(_) => panic!("expected a byte list param without `realloc` to be rejected"),
+            Err(err) => {
+                assert!(err.to_string().contains("realloc"), "unexpected diagnostic: {err}");
             }
         }
     }
@@ -11,7 +11,7 @@ use midenc_hir::{
     Visibility,
     diagnostics::WrapErr,
     dialects::builtin::{
-        BuiltinOpBuilder, ComponentBuilder, ComponentId, ModuleBuilder, WorldBuilder,
+        BuiltinOpBuilder, ComponentBuilder, ComponentId, FunctionRef, ModuleBuilder, WorldBuilder,
         attributes::{AbiParam, Signature},
     },
 };
@@ -19,8 +19,13 @@ use midenc_session::diagnostics::Report;
 
 use super::{
     ComponentFunctionType, MAX_DIRECT_STACK_FELTS, MAX_FLAT_PARAMS, MAX_FLAT_RESULTS,
-    canon_abi_utils::{store, validate_flat_variants},
-    contains_unsupported_canonical_abi_type,
+    UNSUPPORTED_LIST_NOTE,
+    byte_lists::{
+        core_flat_values, core_signature, cross_context_function_type, cross_context_signature,
+        has_byte_lists, is_byte_list, receive_bytes, send_bytes, store_pointer_and_length,
+    },
+    canon_abi_utils::{mask_flat_flags, store, validate_flat_variants},
+    contains_list_type, contains_unsupported_canonical_abi_type,
     flat::{
         CanonicalAbiIndirection, CanonicalAbiMode, check_core_wasm_signature_equivalence,
         classify_function_type, flat_params_need_tuple, flatten_function_type, flatten_types,
@@ -42,6 +47,9 @@ const FPI_EXEC_INPUTS: usize = ExecFpi::MAX_INPUT_FELTS;
 const FPI_EXEC_RESULTS: usize = ExecFpi::EXECUTOR_RESULT_FELTS;
 
 /// Generates the lowering function (cross-context Miden ABI -> Wasm CABI) for the given import function.
+///
+/// `realloc` is the `cabi_realloc` function of the importing module, which is required to receive
+/// a `string` or `list<u8>` result.
 pub fn generate_import_lowering_function(
     world_builder: &mut WorldBuilder,
     module_builder: &mut ModuleBuilder,
@@ -49,6 +57,7 @@ pub fn generate_import_lowering_function(
     import_func_ty: &ComponentFunctionType,
     core_func_path: SymbolPath,
     core_func_sig: Signature,
+    realloc: Option<FunctionRef>,
 ) -> WasmResult<CallableFunction> {
     let context = module_builder.builder().context_rc();
     // FPI imports bypass canonical ABI validation and classification: they use their own
@@ -57,6 +66,17 @@ pub fn generate_import_lowering_function(
     let is_fpi = is_fpi_import(&import_func_path, &import_func_ty.ir)?;
     if !is_fpi {
         reject_unsupported_import_canonical_abi_types(&import_func_path, import_func_ty)?;
+        if has_byte_lists(&import_func_ty.ir) {
+            return generate_lowering_with_byte_lists(
+                world_builder,
+                module_builder,
+                &import_func_path,
+                import_func_ty,
+                core_func_path,
+                core_func_sig,
+                realloc,
+            );
+        }
     }
     let import_lowered_sig =
         flatten_function_type(&context, &import_func_ty.ir, CanonicalAbiMode::Import)
@@ -897,6 +917,7 @@ fn generate_lowering_with_transformation(
     let results_storage = borrow.results();
     let results: Vec<ValueRef> =
         results_storage.iter().map(|op_res| op_res.borrow().as_value_ref()).collect();
+    let results = mask_flat_flags(fb, &results, &import_func_ty.flags_masks.results, span)?;
     validate_flat_variants(fb, &import_func_ty.ir.results, &results, span)?;
 
     // Store values recursively based on the component-level type
@@ -1015,6 +1036,7 @@ fn generate_direct_lowering(
         "For direct lowering the component import function {import_func_path} expected a single \
          result or none"
     );
+    let results = mask_flat_flags(fb, &results, &import_func_ty.flags_masks.results, span)?;
     validate_flat_variants(fb, &import_func_ty.ir.results, &results, span)?;
 
     let exit_block = fb.create_block();
@@ -1031,18 +1053,154 @@ fn generate_direct_lowering(
     })
 }
 
+/// Generates a lowering function for component imports passing a `string` or `list<u8>`.
+///
+/// The bytes of each such parameter are made available to the callee via the advice map, see
+/// [super::byte_lists], and the bytes of such a result are copied into a buffer allocated with
+/// `realloc`, whose pointer and length are stored through the result out-pointer.
+fn generate_lowering_with_byte_lists(
+    world_builder: &mut WorldBuilder,
+    module_builder: &mut ModuleBuilder,
+    import_func_path: &SymbolPath,
+    import_func_ty: &ComponentFunctionType,
+    core_func_path: SymbolPath,
+    core_func_sig: Signature,
+    realloc: Option<FunctionRef>,
+) -> WasmResult<CallableFunction> {
+    let context = module_builder.builder().context_rc();
+    let cross_ctx_ty = cross_context_function_type(&import_func_ty.ir);
+    let cross_ctx_sig = cross_context_signature(&context, import_func_path, &import_func_ty.ir)?;
+    let expected_core_sig =
+        core_signature(&context, import_func_path, &import_func_ty.ir, CanonicalAbiMode::Import)?;
+    check_core_wasm_signature_equivalence(&core_func_sig, &expected_core_sig).map_err(
+        |message| {
+            Report::msg(format!(
+                "component import lowering for '{import_func_path}' has core Wasm signature \
+                 mismatch: {message}"
+            ))
+        },
+    )?;
+    let result_ty = import_func_ty.ir.results.first();
+    if result_ty.is_some_and(is_byte_list) && realloc.is_none() {
+        return Err(Report::msg(format!(
+            "component import lowering for '{import_func_path}' requires the importing module to \
+             export `cabi_realloc`, to allocate the bytes of the result"
+        )));
+    }
+    let has_output_ptr = expected_core_sig.params().len()
+        > import_func_ty
+            .ir
+            .params
+            .iter()
+            .map(|param| core_flat_values(&context, param).map(|values| values.len()))
+            .sum::<WasmResult<usize>>()?;
+
+    let core_func_ref = module_builder
+        .define_function(core_func_path.name().into(), Visibility::Internal, core_func_sig.clone())
+        .expect("failed to define the core function");
+
+    let span = core_func_ref.borrow().name().span;
+    let func_ctx = Rc::new(RefCell::new(FunctionBuilderContext::new(context.clone())));
+    let mut op_builder = midenc_hir::OpBuilder::new(context.clone())
+        .with_listener(SSABuilderListener::new(func_ctx));
+    let mut fb = FunctionBuilderExt::new(core_func_ref, &mut op_builder);
+
+    let entry_block = fb.current_block();
+    fb.seal_block(entry_block);
+    let args: Vec<ValueRef> = entry_block
+        .borrow()
+        .arguments()
+        .iter()
+        .copied()
+        .map(|ba| ba as ValueRef)
+        .collect();
+
+    // Convert the core Wasm arguments into the values passed across contexts
+    let mut cross_ctx_args = Vec::with_capacity(cross_ctx_sig.params().len());
+    let mut next_arg = 0;
+    for param in import_func_ty.ir.params.iter() {
+        if is_byte_list(param) {
+            let (ptr, len) = (args[next_arg], args[next_arg + 1]);
+            cross_ctx_args.extend(send_bytes(&mut fb, world_builder, ptr, len, span)?);
+            next_arg += 2;
+        } else {
+            let num_values = core_flat_values(&context, param)?.len();
+            cross_ctx_args.extend_from_slice(&args[next_arg..next_arg + num_values]);
+            next_arg += num_values;
+        }
+    }
+    validate_flat_variants(&mut fb, &cross_ctx_ty.params, &cross_ctx_args, span)?;
+
+    let id = ComponentId::try_from(import_func_path)
+        .wrap_err("path does not start with a valid component id")?;
+    let component_ref = if let Some(component_ref) = world_builder.find_component(&id) {
+        component_ref
+    } else {
+        world_builder
+            .define_component(id.namespace.into(), id.name.into(), id.version)
+            .expect("failed to define the component")
+    };
+    let mut component_builder = ComponentBuilder::new(component_ref);
+    let import_func_ref = component_builder
+        .define_function(
+            import_func_path.name().into(),
+            Visibility::Internal,
+            cross_ctx_sig.clone(),
+        )
+        .expect("failed to define the import function");
+
+    let call = fb.call(import_func_ref, cross_ctx_sig, cross_ctx_args, span)?;
+    let results: Vec<ValueRef> = {
+        let borrow = call.borrow();
+        borrow.results().iter().map(|op_res| op_res.borrow().as_value_ref()).collect()
+    };
+    let results = mask_flat_flags(&mut fb, &results, &import_func_ty.flags_masks.results, span)?;
+    validate_flat_variants(&mut fb, &cross_ctx_ty.results, &results, span)?;
+
+    let returning = match (result_ty, realloc) {
+        (Some(result_ty), Some(realloc)) if is_byte_list(result_ty) => {
+            let output_ptr = *args.last().expect("expected pointer argument");
+            let received = receive_bytes(&mut fb, world_builder, realloc, &results, span)?;
+            store_pointer_and_length(&mut fb, output_ptr, received, span)?;
+            vec![]
+        }
+        (Some(result_ty), _) if has_output_ptr => {
+            let output_ptr = *args.last().expect("expected pointer argument");
+            store(&mut fb, output_ptr, result_ty, &mut results.into_iter(), span)?;
+            vec![]
+        }
+        _ => results,
+    };
+
+    let exit_block = fb.create_block();
+    fb.br(exit_block, [], span)?;
+    fb.seal_block(exit_block);
+    fb.switch_to_block(exit_block);
+    fb.ret(returning, span)?;
+
+    Ok(CallableFunction::Function {
+        wasm_id: core_func_path,
+        function_ref: core_func_ref,
+        signature: core_func_sig,
+    })
+}
+
 /// Rejects component import signatures containing unsupported canonical ABI shapes.
 fn reject_unsupported_import_canonical_abi_types(
     import_func_path: &SymbolPath,
     import_func_ty: &ComponentFunctionType,
 ) -> WasmResult<()> {
     for ty in import_func_ty.ir.params.iter().chain(import_func_ty.ir.results.iter()) {
-        if contains_unsupported_canonical_abi_type(ty) {
-            return Err(Report::msg(format!(
+        if contains_unsupported_canonical_abi_type(ty) && !is_byte_list(ty) {
+            let mut message = format!(
                 "component import lowering for '{import_func_path}' has unsupported canonical ABI \
                  type {:?}",
                 ty
-            )));
+            );
+            if contains_list_type(ty) {
+                message = format!("{message}: {UNSUPPORTED_LIST_NOTE}");
+            }
+            return Err(Report::msg(message));
         }
     }
 
@@ -1061,8 +1219,8 @@ mod tests {
 
     use super::*;
     use crate::component::test_support::{
-        count_validation_ops, scalar_payload_variant_type, two_field_record_type,
-        unit_only_variant_type, world_with_core_module,
+        count_ops, count_validation_ops, define_realloc, scalar_payload_variant_type,
+        two_field_record_type, unit_only_variant_type, world_with_core_module,
     };
 
     fn test_import_path(name: &str) -> SymbolPath {
@@ -1492,7 +1650,7 @@ mod tests {
 
         let mut ir = FunctionType::new(CallConv::Fast, vec![Type::I32; 17], vec![]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
 
         let tuple = Type::from(StructType::new(vec![Type::I32; 17]));
         let core_func_sig = Signature {
@@ -1508,6 +1666,7 @@ mod tests {
             &import_func_ty,
             core_function_path("too_many_params"),
             core_func_sig,
+            None,
         );
 
         match result {
@@ -1528,7 +1687,7 @@ mod tests {
         let result_ty = two_field_record_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![Type::I32; 16], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
 
         let core_func_sig = Signature {
             params: vec![AbiParam::new(Type::I32); 17],
@@ -1543,6 +1702,7 @@ mod tests {
             &import_func_ty,
             core_function_path("too_many_params_with_result"),
             core_func_sig,
+            None,
         );
 
         match result {
@@ -1565,7 +1725,7 @@ mod tests {
         let mut ir =
             FunctionType::new(CallConv::Fast, vec![variant_ty.clone()], vec![result_ty.clone()]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
         let core_func_sig = Signature {
             params: vec![AbiParam::zext(Type::I32, &context), AbiParam::new(Type::I32)],
             results: vec![],
@@ -1579,6 +1739,7 @@ mod tests {
             &import_func_ty,
             core_function_path("roundtrip"),
             core_func_sig,
+            None,
         )
         .expect("import lowering should build");
 
@@ -1598,7 +1759,7 @@ mod tests {
         let result_ty = scalar_payload_variant_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
         let core_func_sig = Signature {
             params: vec![AbiParam::new(Type::I32)],
             results: vec![],
@@ -1612,6 +1773,7 @@ mod tests {
             &import_func_ty,
             core_function_path("variant_result"),
             core_func_sig,
+            None,
         )
         .expect("import lowering should build");
 
@@ -1634,7 +1796,7 @@ mod tests {
         let result_ty = scalar_u64_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
         let core_func_sig = Signature {
             params: vec![],
             results: vec![AbiParam::new(Type::I32)],
@@ -1648,6 +1810,7 @@ mod tests {
             &import_func_ty,
             core_function_path("mismatched_result"),
             core_func_sig,
+            None,
         );
 
         match result {
@@ -1669,7 +1832,7 @@ mod tests {
         let result_ty = two_field_record_type();
         let mut ir = FunctionType::new(CallConv::Fast, vec![variant_ty], vec![result_ty]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
         // The flattened import parameters are an i32 discriminant plus the i32 result
         // out-pointer, but the core import declares an i64 discriminant.
         let core_func_sig = Signature {
@@ -1685,6 +1848,7 @@ mod tests {
             &import_func_ty,
             core_function_path("mismatched_params"),
             core_func_sig,
+            None,
         );
 
         match result {
@@ -1702,14 +1866,14 @@ mod tests {
     fn rejects_direct_import_lowering_with_unsupported_list_param() {
         let (context, mut world_builder, mut module_builder) = world_with_core_module();
 
-        let list_ty = Type::List(Arc::new(Type::U8));
+        let list_ty = Type::List(Arc::new(Type::U32));
         let mut ir = FunctionType::new(CallConv::Fast, vec![list_ty.clone()], vec![]);
         ir.abi = CallConv::ComponentModel;
-        let import_func_ty = ComponentFunctionType { ir };
+        let import_func_ty = ComponentFunctionType::new(ir);
 
         let core_func_sig = Signature {
             params: vec![
-                AbiParam::sret(Type::from(PointerType::new(Type::U8)), &context),
+                AbiParam::sret(Type::from(PointerType::new(Type::U32)), &context),
                 AbiParam::new(Type::I32),
             ],
             results: vec![],
@@ -1723,6 +1887,7 @@ mod tests {
             &import_func_ty,
             core_function_path("list_param"),
             core_func_sig,
+            None,
         );
 
         match result {
@@ -1735,4 +1900,69 @@ mod tests {
             }
         }
     }
+
+    fn string_import_type() -> ComponentFunctionType {
+        let string_ty = Type::List(Arc::new(Type::U8));
+        ComponentFunctionType::new(FunctionType::new(
+            CallConv::ComponentModel,
+            vec![string_ty.clone()],
+            vec![string_ty],
+        ))
+    }
+
+    #[test]
+    fn byte_list_import_lowering_passes_bytes_via_the_advice_map() {
+        let (_context, mut world_builder, mut module_builder) = world_with_core_module();
+        let realloc = define_realloc(&mut module_builder);
+        // (ptr, len, out_ptr)
+        let core_func_sig = Signature {
+            params: vec![AbiParam::new(Type::I32); 3],
+            results: vec![],
+            cc: CallConv::ComponentModel,
+        };
+
+        let lowered = generate_import_lowering_function(
+            &mut world_builder,
+            &mut module_builder,
+            component_import_path("shout"),
+            &string_import_type(),
+            core_function_path("shout"),
+            core_func_sig,
+            Some(realloc),
+        )
+        .expect("byte list import lowering should build");
+
+        let function = lowered.function_ref().expect("expected function lowering");
+        assert_eq!(count_ops(function, |op| op.is::<midenc_dialect_hir::Call>()), 1);
+        // Sending hashes the words and inserts them into the advice map, and receiving allocates
+        // a buffer, pushes the words onto the advice stack, and copies them into the buffer
+        assert_eq!(count_ops(function, |op| op.is::<midenc_dialect_hir::Exec>()), 5);
+    }
+
+    #[test]
+    fn rejects_byte_list_import_result_without_realloc() {
+        let (_context, mut world_builder, mut module_builder) = world_with_core_module();
+        let core_func_sig = Signature {
+            params: vec![AbiParam::new(Type::I32); 3],
+            results: vec![],
+            cc: CallConv::ComponentModel,
+        };
+
+        let result = generate_import_lowering_function(
+            &mut world_builder,
+            &mut module_builder,
+            component_import_path("shout"),
+            &string_import_type(),
+            core_function_path("shout"),
+            core_func_sig,
+            None,
+        );
+
+        match result {
+            Ok(_) => panic!("expected a byte list result without `cabi_realloc` to be rejected"),
+            Err(err) => {
+                assert!(err.to_string().contains("cabi_realloc"), "unexpected diagnostic: {err}");
+            }
+        }
+    }
 }
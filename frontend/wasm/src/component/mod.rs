@@ -4,6 +4,7 @@
 //! translate the component model.
 
 pub(crate) mod build_ir;
+pub(crate) mod byte_lists;
 mod canon_abi_utils;
 mod flat;
 mod lift_exports;
@@ -29,7 +30,8 @@ pub(super) mod test_support {
         StructType, SymbolName, SymbolTable, Type, ValueRef, Variant, Visibility, WalkResult,
         dialects::builtin::{
             BuiltinOpBuilder, ComponentBuilder, Function, FunctionRef, ModuleBuilder, World,
-            WorldBuilder, attributes::Signature,
+            WorldBuilder,
+            attributes::{AbiParam, Signature},
         },
         version::Version,
     };
@@ -163,7 +165,8 @@ pub(super) mod test_support {
 
     /// Creates a world fixture with a "miden:test" component and its "core" module for export
     /// lifting tests.
-    pub fn component_with_core_module() -> (Rc<Context>, ComponentBuilder, ModuleBuilder) {
+    pub fn component_with_core_module()
+    -> (Rc<Context>, WorldBuilder, ComponentBuilder, ModuleBuilder) {
         let (context, mut world_builder) = test_world();
         let component = world_builder
             .define_component("miden".into(), "test".into(), Version::new(1, 0, 0))
@@ -173,7 +176,23 @@ pub(super) mod test_support {
             .define_module(Ident::with_empty_span("core".into()))
             .expect("failed to define core module");
         let module_builder = ModuleBuilder::new(core_module);
-        (context, component_builder, module_builder)
+        (context, world_builder, component_builder, module_builder)
+    }
+
+    /// Defines a `cabi_realloc` function in the core module of a fixture.
+    pub fn define_realloc(module_builder: &mut ModuleBuilder) -> FunctionRef {
+        let signature = Signature {
+            params: vec![AbiParam::new(Type::I32); 4],
+            results: vec![AbiParam::new(Type::I32)],
+            cc: CallConv::ComponentModel,
+        };
+        module_builder
+            .define_function(
+                Ident::with_empty_span("cabi_realloc".into()),
+                Visibility::Public,
+                signature,
+            )
+            .expect("failed to define cabi_realloc")
     }
 
     /// Builds a single-function module fixture with `params` and runs `build` in its entry block.
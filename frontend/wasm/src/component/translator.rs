@@ -11,6 +11,7 @@ use midenc_hir::{
     interner::Symbol,
     smallvec,
 };
+use midenc_session::Producers;
 use wasmparser::{component_types::ComponentEntityType, types::TypesRef};
 
 use super::{
@@ -172,6 +173,11 @@ impl<'a> ComponentTranslator<'a> {
             &self.lifted_export_names,
         )?;
 
+        let mut producers = Producers::default();
+        for (_, module) in self.nested_modules.iter() {
+            producers.extend(&module.producers);
+        }
+
         let account_component_metadata_bytes_vec: Vec<Vec<u8>> = self
             .nested_modules
             .into_iter()
@@ -187,6 +193,7 @@ impl<'a> ComponentTranslator<'a> {
         let output = FrontendOutput {
             component: self.result.component,
             account_component_metadata_bytes,
+            producers,
         };
         Ok(output)
     }
@@ -230,24 +237,19 @@ impl<'a> ComponentTranslator<'a> {
             LocalInitializer::Lift(lift) => {
                 frame.component_funcs.push(ComponentFuncDef::Lifted(lift.clone()));
             }
-            LocalInitializer::Resource(..) => {
-                unsupported_diag!(
-                    self.context.diagnostics(),
-                    "Resource initializers are not supported"
-                )
-            }
-            LocalInitializer::ResourceNew(..) => {
-                unsupported_diag!(self.context.diagnostics(), "Resource creation is not supported")
-            }
-            LocalInitializer::ResourceRep(..) => {
+            LocalInitializer::Resource(..)
+            | LocalInitializer::ResourceNew(..)
+            | LocalInitializer::ResourceRep(..)
+            | LocalInitializer::ResourceDrop(..)
+            | LocalInitializer::ResourceDropAsync(..) => {
                 unsupported_diag!(
                     self.context.diagnostics(),
-                    "Resource representation is not supported"
+                    "component resource types are not supported: the handle table of a resource \
+                     lives in the memory of the component defining it, which does not outlive a \
+                     cross-context call, so a handle cannot be passed from one call to the next; \
+                     pass the data the resource would wrap by value instead"
                 )
             }
-            LocalInitializer::ResourceDrop(..) | LocalInitializer::ResourceDropAsync(..) => {
-                unsupported_diag!(self.context.diagnostics(), "Resource dropping is not supported")
-            }
             LocalInitializer::ModuleStatic(static_module_idx) => {
                 let module_idx = frame.modules.len() as u32;
                 frame.modules.push(ModuleDef::Static(*static_module_idx));
@@ -498,7 +500,11 @@ impl<'a> ComponentTranslator<'a> {
         let type_func = component_types[type_func_idx].clone();
         let func_ty =
             convert_lifted_func_ty(CanonicalAbiMode::Export, &type_func_idx, component_types);
-        let core_export_func_path = self.core_module_export_func_path(frame, canon_lift);
+        let core_export_func_path = self.core_module_export_func_path(frame, canon_lift.func);
+        let core_realloc_func_path = canon_lift
+            .options
+            .realloc
+            .map(|realloc| self.core_module_export_func_path(frame, realloc));
         let protocol_export_kind: Option<ProtocolExportKind> = self
             .component_frontend_metadata
             .as_ref()
@@ -506,10 +512,12 @@ impl<'a> ComponentTranslator<'a> {
 
         generate_export_lifting_function(
             &mut self.result,
+            &mut self.world_builder,
             name,
             func_ty,
             &type_func.param_names,
             core_export_func_path,
+            core_realloc_func_path,
             protocol_export_kind,
             self.context.diagnostics(),
         )?;
@@ -520,9 +528,9 @@ impl<'a> ComponentTranslator<'a> {
     fn core_module_export_func_path(
         &self,
         frame: &ComponentFrame<'a>,
-        canon_lift: &CanonLift,
+        func: FuncIndex,
     ) -> SymbolPath {
-        match &frame.funcs[canon_lift.func] {
+        match &frame.funcs[func] {
             CoreDef::Export(module_instance_idx, name) => {
                 match &frame.module_instances[*module_instance_idx] {
                     ModuleInstanceDef::Instantiated {
@@ -677,6 +685,7 @@ impl<'a> ComponentTranslator<'a> {
                     &mut self.world_builder,
                     module_types,
                     import_canon_lower_args,
+                    self.config,
                     self.context.diagnostics(),
                 )?;
 
@@ -1093,3 +1102,38 @@ impl<'a> ComponentFrame<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resources are not supported, see the known limitations, so a component defining one must be
+    /// rejected with a diagnostic explaining why, rather than miscompiled.
+    #[test]
+    fn rejects_component_resource_types() {
+        let component = wat::parse_str(
+            r#"
+            (component
+                (type $counter (resource (rep i32)))
+                (export "counter" (type $counter))
+            )
+            "#,
+        )
+        .expect("component wat should compile");
+        let context = Rc::new(Context::default());
+
+        let result = crate::translate(&component, &WasmTranslationConfig::default(), context);
+
+        match result {
+            Ok(_) => panic!("expected a component defining a resource to be rejected"),
+            Err(err) => {
+                let message = err.to_string();
+                assert!(
+                    message.contains("component resource types are not supported"),
+                    "unexpected diagnostic: {message}"
+                );
+                assert!(message.contains("by value"), "unexpected diagnostic: {message}");
+            }
+        }
+    }
+}
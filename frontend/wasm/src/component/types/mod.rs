@@ -23,8 +23,11 @@ use wasmparser::{
 };
 
 use self::resources::ResourcesBuilder;
-use super::flat::{
-    CanonicalTypeError, canonical_flat_scalar_type, join_flat_types, join_variant_payloads,
+use super::{
+    byte_lists::{TRANSFER_FLAT_VALUES, is_byte_list},
+    flat::{
+        CanonicalTypeError, canonical_flat_scalar_type, join_flat_types, join_variant_payloads,
+    },
 };
 use crate::{
     indices,
@@ -1297,9 +1300,32 @@ impl VariantInfo {
 pub struct ComponentFunctionType {
     /// The HIR function type used to build component wrapper signatures.
     pub ir: FunctionType,
+    /// The valid bits of the flattened `flags` limbs among the parameters and results.
+    pub flags_masks: FlatFlagsMasks,
+}
+
+/// The masks of the bits which correspond to a flag, for each flattened value of a component
+/// function signature which is a limb of a `flags` value, and `None` for every other value.
+///
+/// Lifting a `flags` value must ignore any bit which does not correspond to a flag, which the
+/// wrappers ensure by clearing those bits before handing the value over.
+#[derive(Clone, Debug, Default)]
+pub struct FlatFlagsMasks {
+    /// The masks of the flattened parameters
+    pub params: Vec<Option<u32>>,
+    /// The masks of the flattened results
+    pub results: Vec<Option<u32>>,
 }
 
 impl ComponentFunctionType {
+    /// Builds a component function type whose signature has no `flags` values.
+    pub fn new(ir: FunctionType) -> Self {
+        Self {
+            ir,
+            flags_masks: FlatFlagsMasks::default(),
+        }
+    }
+
     /// Builds a component function type from a parsed component-model function type.
     pub fn from_component_type(ty: &TypeFunc, component_types: &ComponentTypes) -> Self {
         let params_types = component_types[ty.params].clone().types;
@@ -1312,12 +1338,64 @@ impl ComponentFunctionType {
             .iter()
             .map(|ty| interface_type_to_ir_for_component_signature(ty, component_types))
             .collect();
+        let mut flags_masks = FlatFlagsMasks::default();
+        for ty in params_types.iter() {
+            flat_flags_masks(ty, component_types, &mut flags_masks.params);
+        }
+        for ty in results_types.iter() {
+            flat_flags_masks(ty, component_types, &mut flags_masks.results);
+        }
         Self {
             ir: FunctionType {
                 params,
                 results,
                 abi: CallConv::ComponentModel,
             },
+            flags_masks,
+        }
+    }
+}
+
+/// Appends the flags masks of the flattened values of `ty` to `masks`, see [FlatFlagsMasks].
+///
+/// The payloads of variants are not masked, as a flattened payload value may hold a `flags` limb
+/// in one case, and something else entirely in another.
+fn flat_flags_masks(
+    ty: &InterfaceType,
+    component_types: &ComponentTypes,
+    masks: &mut Vec<Option<u32>>,
+) {
+    match ty {
+        InterfaceType::Record(idx) => {
+            for field in component_types[*idx].fields.iter() {
+                flat_flags_masks(&field.ty, component_types, masks);
+            }
+        }
+        InterfaceType::Tuple(idx) => {
+            for ty in component_types[*idx].types.iter() {
+                flat_flags_masks(ty, component_types, masks);
+            }
+        }
+        InterfaceType::Flags(idx) => {
+            let count = component_types[*idx].names.len();
+            let limbs = match FlagsSize::from_count(count) {
+                FlagsSize::Size0 => 0,
+                FlagsSize::Size1 | FlagsSize::Size2 => 1,
+                FlagsSize::Size4Plus(limbs) => limbs as usize,
+            };
+            for limb in 0..limbs {
+                let bits = (count - limb * 32).min(32);
+                masks.push((bits < 32).then(|| (1u32 << bits) - 1));
+            }
+        }
+        _ => {
+            let ir = interface_type_to_ir_for_component_signature(ty, component_types);
+            let len = if is_byte_list(&ir) {
+                TRANSFER_FLAT_VALUES
+            } else {
+                canonical_flat_types(&ir).map_or(0, |flat| flat.len())
+            };
+            masks.extend(core::iter::repeat_n(None, len));
         }
     }
 }
@@ -1337,11 +1415,9 @@ fn interface_type_to_ir_for_component_signature(
         {
             Type::Unknown
         }
-        InterfaceType::String
-        | InterfaceType::Char
+        InterfaceType::Char
         | InterfaceType::Float64
         | InterfaceType::ErrorContext
-        | InterfaceType::Flags(_)
         | InterfaceType::Own(_)
         | InterfaceType::Borrow(_) => unsupported_interface_type_to_ir(ty, component_types),
         _ => interface_type_to_ir(ty, component_types),
@@ -1364,7 +1440,8 @@ fn interface_type_requires_unsupported_canonical_abi(
         | InterfaceType::S64
         | InterfaceType::U64
         | InterfaceType::Float32
-        | InterfaceType::Enum(_) => false,
+        | InterfaceType::Enum(_)
+        | InterfaceType::Flags(_) => false,
         InterfaceType::Record(idx) => component_types[*idx].fields.iter().any(|field| {
             interface_type_requires_unsupported_canonical_abi(&field.ty, component_types)
         }),
@@ -1391,7 +1468,6 @@ fn interface_type_requires_unsupported_canonical_abi(
         | InterfaceType::Char
         | InterfaceType::Float64
         | InterfaceType::ErrorContext
-        | InterfaceType::Flags(_)
         | InterfaceType::Own(_)
         | InterfaceType::Borrow(_) => true,
     }
@@ -1404,6 +1480,7 @@ fn unsupported_interface_type_to_ir(ty: &InterfaceType, component_types: &Compon
             &component_types[*idx].element,
             component_types,
         ))),
+        InterfaceType::String => string_type(),
         InterfaceType::Float64 => Type::F64,
         InterfaceType::Bool
         | InterfaceType::S8
@@ -1415,16 +1492,15 @@ fn unsupported_interface_type_to_ir(ty: &InterfaceType, component_types: &Compon
         | InterfaceType::S64
         | InterfaceType::U64
         | InterfaceType::Float32
-        | InterfaceType::Enum(_) => interface_type_to_ir(ty, component_types),
+        | InterfaceType::Enum(_)
+        | InterfaceType::Flags(_) => interface_type_to_ir(ty, component_types),
         InterfaceType::Record(_)
         | InterfaceType::Tuple(_)
         | InterfaceType::Variant(_)
         | InterfaceType::Option(_)
         | InterfaceType::Result(_)
-        | InterfaceType::String
         | InterfaceType::Char
         | InterfaceType::ErrorContext
-        | InterfaceType::Flags(_)
         | InterfaceType::Own(_)
         | InterfaceType::Borrow(_) => Type::Unknown,
     }
@@ -1467,6 +1543,29 @@ pub fn contains_unsupported_canonical_abi_type(ty: &Type) -> bool {
     canonical_abi_info(ty).is_err()
 }
 
+/// Returns true if a HIR type contains a `list` (and thus also a `string`) anywhere in its shape.
+pub fn contains_list_type(ty: &Type) -> bool {
+    match ty {
+        Type::List(_) => true,
+        Type::Struct(struct_ty) => {
+            struct_ty.fields().iter().any(|field| contains_list_type(&field.ty))
+        }
+        Type::Enum(enum_ty) => enum_ty
+            .variants()
+            .iter()
+            .filter_map(|variant| variant.value.as_ref())
+            .any(contains_list_type),
+        Type::Array(array_ty) => contains_list_type(array_ty.element_type()),
+        _ => false,
+    }
+}
+
+/// The explanation appended to diagnostics rejecting a canonical ABI type containing a `list`
+pub const UNSUPPORTED_LIST_NOTE: &str = "`string` and `list` values are passed as a pointer into \
+                                         the linear memory of the caller, and only a `string` or \
+                                         `list<u8>` parameter or result, outside of any other \
+                                         type, is copied across contexts via the advice provider";
+
 /// Returns this type's flattened canonical ABI value types.
 ///
 /// This is the type-only counterpart to `flat::flatten_type`: callers use it when they need to
@@ -2200,6 +2299,23 @@ fn enum_type_to_ir(
     ))
 }
 
+/// Converts a component-model flags type into the corresponding HIR type.
+///
+/// Flags are a bit set with one bit per flag, held in a `u8` or a `u16` for up to 16 flags, and in
+/// a tuple of as many `u32` limbs as needed otherwise, the first flag being the least significant
+/// bit of the first limb.
+fn flags_type_to_ir(idx: TypeFlagsIndex, component_types: &ComponentTypes) -> Type {
+    match FlagsSize::from_count(component_types.flags[idx].names.len()) {
+        FlagsSize::Size1 => Type::U8,
+        FlagsSize::Size2 => Type::U16,
+        FlagsSize::Size4Plus(1) => Type::U32,
+        FlagsSize::Size0 => Type::from(StructType::new(Vec::<Type>::new())),
+        FlagsSize::Size4Plus(limbs) => {
+            Type::from(StructType::new(core::iter::repeat_n(Type::U32, limbs as usize)))
+        }
+    }
+}
+
 /// Converts a component-model option into the corresponding HIR enum type.
 fn option_type_to_ir(
     ty: &InterfaceType,
@@ -2250,6 +2366,13 @@ fn result_type_to_ir(
     ))
 }
 
+/// The HIR type of a component-model `string`, i.e. its UTF-8 encoded bytes.
+///
+/// The canonical ABI represents strings exactly like a `list<u8>`, as a (pointer, length) pair.
+fn string_type() -> Type {
+    Type::List(Arc::new(Type::U8))
+}
+
 /// Converts a component-model interface type into the corresponding HIR type.
 pub fn interface_type_to_ir(ty: &InterfaceType, component_types: &ComponentTypes) -> Type {
     match ty {
@@ -2265,7 +2388,7 @@ pub fn interface_type_to_ir(ty: &InterfaceType, component_types: &ComponentTypes
         InterfaceType::Float32 => Type::Felt,
         InterfaceType::Float64 => todo!(),
         InterfaceType::Char => todo!(),
-        InterfaceType::String => todo!(),
+        InterfaceType::String => string_type(),
         InterfaceType::ErrorContext => todo!("the async proposal is not currently supported"),
         InterfaceType::Record(idx) => record_type_to_ir(ty, *idx, component_types),
         InterfaceType::Variant(idx) => variant_type_to_ir(ty, *idx, component_types),
@@ -2281,7 +2404,7 @@ pub fn interface_type_to_ir(ty: &InterfaceType, component_types: &ComponentTypes
                 .map(|t| interface_type_to_ir(t, component_types));
             Type::from(StructType::new(tys))
         }
-        InterfaceType::Flags(_) => todo!(),
+        InterfaceType::Flags(idx) => flags_type_to_ir(*idx, component_types),
         InterfaceType::Enum(idx) => enum_type_to_ir(ty, *idx, component_types),
         InterfaceType::Option(idx) => option_type_to_ir(ty, *idx, component_types),
         InterfaceType::Result(idx) => result_type_to_ir(ty, *idx, component_types),
@@ -2517,4 +2640,100 @@ mod tests {
             "option<list<u8>> should be unsupported instead of dropping the payload shape"
         );
     }
+
+    #[test]
+    fn string_lowers_to_byte_list() {
+        let component = wat::parse_str(
+            r#"
+            (component
+                (type $memo (record (field "text" string)))
+                (export "memo" (type $memo))
+            )
+            "#,
+        )
+        .expect("component wat should compile");
+        let context = Context::default();
+        let config = WasmTranslationConfig::default();
+        let mut validator = Validator::new_with_features(supported_component_model_features());
+        let mut types = ComponentTypesBuilder::default();
+        let parser = ComponentParser::new(&config, context.session(), &mut validator, &mut types);
+
+        let parsed = parser.parse(&component).expect("component should parse");
+        let memo_id = parsed
+            .root_component
+            .exports
+            .get("memo")
+            .and_then(|item| match item {
+                ComponentItem::Type(id) => Some(*id),
+                _ => None,
+            })
+            .expect("component should export the record type");
+        let type_def = types
+            .convert_type(parsed.root_component.types_ref(), memo_id)
+            .expect("component record type should lower");
+        let component_types = types.finish();
+        let TypeDef::Interface(memo_ty) = type_def else {
+            panic!("expected exported component record type");
+        };
+
+        let ir_ty = interface_type_to_ir(&memo_ty, &component_types);
+        let Type::Struct(memo) = &ir_ty else {
+            panic!("expected the record to lower to a HIR struct, got {ir_ty}");
+        };
+        assert_eq!(memo.fields()[0].ty, Type::List(Arc::new(Type::U8)));
+        assert!(contains_list_type(&ir_ty));
+        assert!(contains_unsupported_canonical_abi_type(&ir_ty));
+    }
+
+    #[test]
+    fn flags_lower_to_masked_u32_limbs() {
+        let names = (0..40).map(|i| format!("\"f{i}\"")).collect::<Vec<_>>().join(" ");
+        let component = wat::parse_str(format!(
+            r#"
+            (component
+                (type $perms (flags {names}))
+                (export $perms-export "perms" (type $perms))
+                (type $holder (record (field "tag" u8) (field "perms" $perms-export)))
+                (export "holder" (type $holder))
+            )
+            "#
+        ))
+        .expect("component wat should compile");
+        let context = Context::default();
+        let config = WasmTranslationConfig::default();
+        let mut validator = Validator::new_with_features(supported_component_model_features());
+        let mut types = ComponentTypesBuilder::default();
+        let parser = ComponentParser::new(&config, context.session(), &mut validator, &mut types);
+
+        let parsed = parser.parse(&component).expect("component should parse");
+        let holder_id = parsed
+            .root_component
+            .exports
+            .get("holder")
+            .and_then(|item| match item {
+                ComponentItem::Type(id) => Some(*id),
+                _ => None,
+            })
+            .expect("component should export the record type");
+        let type_def = types
+            .convert_type(parsed.root_component.types_ref(), holder_id)
+            .expect("component record type should lower");
+        let component_types = types.finish();
+        let TypeDef::Interface(holder_ty) = type_def else {
+            panic!("expected exported component record type");
+        };
+
+        let ir_ty = interface_type_to_ir_for_component_signature(&holder_ty, &component_types);
+        assert!(!contains_unsupported_canonical_abi_type(&ir_ty));
+        assert_eq!(
+            canonical_flat_types(&ir_ty).expect("flags should flatten").len(),
+            3,
+            "expected the tag, followed by two flags limbs"
+        );
+
+        // The first limb holds 32 flags, the second only 8 of them
+        let mut masks = Vec::new();
+        flat_flags_masks(&holder_ty, &component_types, &mut masks);
+        assert_eq!(masks, vec![None, None, Some(0xff)]);
+    }
 }
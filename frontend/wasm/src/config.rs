@@ -1,7 +1,13 @@
-use alloc::{borrow::Cow, vec::Vec};
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
 
+pub use midenc_session::FloatMode;
 use midenc_session::RemapPathPrefix;
 
+pub use crate::miden_abi::{
+    import_map::{ImportMap, MappedImport},
+    transform::TransformStrategy,
+};
+
 /// Configuration for the WASM translation.
 #[derive(Clone)]
 pub struct WasmTranslationConfig {
@@ -24,6 +30,12 @@ pub struct WasmTranslationConfig {
 
     /// Whether or not to retain DWARF sections in compiled modules.
     pub parse_wasm_debuginfo: bool,
+
+    /// How floating-point operations are translated
+    pub float_mode: FloatMode,
+
+    /// The Miden Assembly procedures that calls to linker stubs are lowered to
+    pub import_map: Arc<ImportMap>,
 }
 
 impl core::fmt::Debug for WasmTranslationConfig {
@@ -36,6 +48,8 @@ impl core::fmt::Debug for WasmTranslationConfig {
             .field("world", &world)
             .field("generate_native_debuginfo", &self.generate_native_debuginfo)
             .field("parse_wasm_debuginfo", &self.parse_wasm_debuginfo)
+            .field("float_mode", &self.float_mode)
+            .field("import_map", &self.import_map.len())
             .finish()
     }
 }
@@ -49,6 +63,8 @@ impl Default for WasmTranslationConfig {
             world: None,
             generate_native_debuginfo: false,
             parse_wasm_debuginfo: true,
+            float_mode: FloatMode::default(),
+            import_map: ImportMap::builtin(),
         }
     }
 }
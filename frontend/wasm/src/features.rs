@@ -0,0 +1,310 @@
+//! Up-front detection of the use of Wasm features which are not supported.
+//!
+//! Without this, a module using e.g. SIMD is rejected by the validator with a terse message, which
+//! neither names the function that uses the feature, nor suggests how to avoid it.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use midenc_hir::FxHashMap;
+use midenc_session::diagnostics::{DiagnosticsHandler, Report, Severity};
+use wasmparser::{
+    BinaryReaderError, Encoding, FuncToValidate, FunctionBody, NameSectionReader, Naming, Parser,
+    Payload, ValidPayload, Validator, ValidatorResources, WasmFeatures,
+};
+
+use crate::error::WasmResult;
+
+/// A Wasm feature which is commonly enabled by the toolchain, but is not supported
+struct KnownFeature {
+    features: WasmFeatures,
+    name: &'static str,
+    help: &'static str,
+}
+
+fn known_features() -> [KnownFeature; 4] {
+    [
+        KnownFeature {
+            features: WasmFeatures::SIMD | WasmFeatures::RELAXED_SIMD,
+            name: "SIMD",
+            help: "compile without the `simd128` target feature, i.e. remove `-C \
+                   target-feature=+simd128` from RUSTFLAGS, and check for crates enabling it with \
+                   `#[target_feature(enable = \"simd128\")]`",
+        },
+        KnownFeature {
+            features: WasmFeatures::THREADS | WasmFeatures::SHARED_EVERYTHING_THREADS,
+            name: "threads (atomics)",
+            help: "compile without the `atomics` target feature, i.e. remove `-C \
+                   target-feature=+atomics` from RUSTFLAGS, and do not build the standard library \
+                   with it",
+        },
+        KnownFeature {
+            features: WasmFeatures::REFERENCE_TYPES,
+            name: "reference types",
+            help: "compile with `-C target-feature=-reference-types` in RUSTFLAGS",
+        },
+        KnownFeature {
+            features: WasmFeatures::EXCEPTIONS | WasmFeatures::LEGACY_EXCEPTIONS,
+            name: "exception handling",
+            help: "compile with `-C panic=abort`, and without the `exception-handling` target \
+                   feature",
+        },
+    ]
+}
+
+/// A use of a Wasm feature which is not supported
+#[derive(Debug)]
+pub(crate) struct UnsupportedFeature {
+    /// The function in which the feature is used, if it is used by an instruction
+    pub function: Option<String>,
+    /// The name of the feature, if known
+    pub feature: Option<&'static str>,
+    /// A hint on how to avoid using the feature
+    pub help: Option<&'static str>,
+    /// The offset in the Wasm binary at which the feature is used
+    pub offset: usize,
+    /// The message of the validation error raised for the use of the feature
+    pub message: String,
+}
+
+impl fmt::Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.function {
+            Some(function) => write!(f, "function '{function}'")?,
+            None => f.write_str("the module")?,
+        }
+        match self.feature {
+            Some(feature) => write!(f, " uses {feature}, which is not supported")?,
+            None => f.write_str(" uses a Wasm feature which is not supported")?,
+        }
+        write!(f, " ({} at offset {:#x})", self.message, self.offset)
+    }
+}
+
+/// Check that `wasm` only uses the given `features`, emitting a diagnostic for each use of any
+/// other feature.
+///
+/// Binaries which are invalid regardless of the enabled features are left to be reported by the
+/// translation itself.
+pub(crate) fn check_features(
+    wasm: &[u8],
+    features: WasmFeatures,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    let unsupported = find_unsupported_features(wasm, features);
+    if unsupported.is_empty() {
+        return Ok(());
+    }
+
+    for use_ in unsupported.iter() {
+        let mut diagnostic =
+            diagnostics.diagnostic(Severity::Error).with_message(format!("{use_}"));
+        if let Some(help) = use_.help {
+            diagnostic = diagnostic.with_help(help);
+        }
+        diagnostic.emit();
+    }
+
+    Err(Report::msg(format!(
+        "the Wasm binary uses {} unsupported feature(s), see the errors above",
+        unsupported.len()
+    )))
+}
+
+/// The state of a core module whose function bodies are yet to be checked
+#[derive(Default)]
+struct PendingModule<'a> {
+    bodies: Vec<(FuncToValidate<ValidatorResources>, FunctionBody<'a>)>,
+    names: FxHashMap<u32, String>,
+}
+
+/// Locate the uses of features of `wasm` which are not among `features`
+pub(crate) fn find_unsupported_features(
+    wasm: &[u8],
+    features: WasmFeatures,
+) -> Vec<UnsupportedFeature> {
+    let Err(err) = Validator::new_with_features(features).validate_all(wasm) else {
+        return Vec::new();
+    };
+
+    // Validate again with all features enabled, to find the function bodies, which are then
+    // validated one at a time with the supported features only
+    let mut validator = Validator::new_with_features(WasmFeatures::all());
+    let mut modules = Vec::<Option<PendingModule<'_>>>::new();
+    let mut unsupported = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let Ok(payload) = payload else {
+            return Vec::new();
+        };
+        let Ok(valid) = validator.payload(&payload) else {
+            return Vec::new();
+        };
+        match payload {
+            Payload::Version { encoding, .. } => {
+                modules.push(matches!(encoding, Encoding::Module).then(PendingModule::default));
+            }
+            Payload::CustomSection(section) if section.name() == "name" => {
+                if let Some(Some(module)) = modules.last_mut() {
+                    let reader =
+                        wasmparser::BinaryReader::new(section.data(), section.data_offset());
+                    module.names = function_names(NameSectionReader::new(reader));
+                }
+            }
+            Payload::End(_) => {
+                if let Some(Some(module)) = modules.pop() {
+                    unsupported.extend(check_bodies(module, features));
+                }
+            }
+            _ => (),
+        }
+        if let ValidPayload::Func(func, body) = valid
+            && let Some(Some(module)) = modules.last_mut()
+        {
+            module.bodies.push((func, body));
+        }
+    }
+
+    // The feature is used outside of function bodies, e.g. by a shared memory
+    if unsupported.is_empty() {
+        let feature = known_features().into_iter().find(|known| {
+            Validator::new_with_features(features | known.features)
+                .validate_all(wasm)
+                .map_or_else(|e| e.offset() > err.offset(), |_| true)
+        });
+        unsupported.push(UnsupportedFeature {
+            function: None,
+            feature: feature.as_ref().map(|known| known.name),
+            help: feature.as_ref().map(|known| known.help),
+            offset: err.offset(),
+            message: err.message().into(),
+        });
+    }
+
+    unsupported
+}
+
+/// Validate the function bodies of `module` with `features`, returning the first use of any other
+/// feature in each of them
+fn check_bodies(module: PendingModule<'_>, features: WasmFeatures) -> Vec<UnsupportedFeature> {
+    let mut unsupported = Vec::new();
+    for (func, body) in module.bodies {
+        let Err(err) = validate_body(&func, &body, features) else {
+            continue;
+        };
+        // The feature is the one which allows validation to get past the offending instruction
+        let feature = known_features().into_iter().find(|known| {
+            validate_body(&func, &body, features | known.features)
+                .map_or_else(|e| e.offset() > err.offset(), |_| true)
+        });
+        let function = module
+            .names
+            .get(&func.index)
+            .cloned()
+            .unwrap_or_else(|| format!("func{}", func.index));
+        unsupported.push(UnsupportedFeature {
+            function: Some(function),
+            feature: feature.as_ref().map(|known| known.name),
+            help: feature.as_ref().map(|known| known.help),
+            offset: err.offset(),
+            message: err.message().into(),
+        });
+    }
+    unsupported
+}
+
+fn validate_body(
+    func: &FuncToValidate<ValidatorResources>,
+    body: &FunctionBody<'_>,
+    features: WasmFeatures,
+) -> Result<(), BinaryReaderError> {
+    let func = FuncToValidate {
+        resources: func.resources.clone(),
+        index: func.index,
+        ty: func.ty,
+        features,
+    };
+    func.into_validator(Default::default()).validate(body)
+}
+
+/// Collect the function names of a name section, ignoring any malformed entries
+fn function_names(names: NameSectionReader<'_>) -> FxHashMap<u32, String> {
+    let mut function_names = FxHashMap::default();
+    for subsection in names.into_iter().flatten() {
+        if let wasmparser::Name::Function(names) = subsection {
+            for Naming { index, name } in names.into_iter().flatten() {
+                function_names.insert(index, name.into());
+            }
+        }
+    }
+    function_names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unsupported_features(wat: &str) -> Vec<UnsupportedFeature> {
+        let wasm = wat::parse_str(wat).unwrap();
+        find_unsupported_features(&wasm, crate::supported_features())
+    }
+
+    #[test]
+    fn supported_features_are_accepted() {
+        let unsupported = unsupported_features(
+            r#"
+            (module
+                (func $add (param i32 i32) (result i32)
+                    local.get 0
+                    local.get 1
+                    i32.add
+                )
+            )"#,
+        );
+        assert!(unsupported.is_empty(), "{unsupported:?}");
+    }
+
+    #[test]
+    fn simd_is_attributed_to_its_function() {
+        let unsupported = unsupported_features(
+            r#"
+            (module
+                (func $scalar (param i32) (result i32)
+                    local.get 0
+                )
+                (func $splat (param i32)
+                    local.get 0
+                    i32x4.splat
+                    drop
+                )
+            )"#,
+        );
+        assert_eq!(unsupported.len(), 1, "{unsupported:?}");
+        let message = unsupported[0].to_string();
+        assert!(
+            message.starts_with("function 'splat' uses SIMD, which is not supported"),
+            "{message}"
+        );
+        assert!(unsupported[0].help.unwrap().contains("simd128"));
+    }
+
+    #[test]
+    fn atomics_are_attributed_to_their_function() {
+        let unsupported = unsupported_features(
+            r#"
+            (module
+                (memory 1 1 shared)
+                (func $fetch_add (param i32) (result i32)
+                    i32.const 0
+                    local.get 0
+                    i32.atomic.rmw.add
+                )
+            )"#,
+        );
+        let message =
+            unsupported.iter().map(|use_| use_.to_string()).collect::<Vec<_>>().join("\n");
+        assert!(
+            message.contains("function 'fetch_add' uses threads (atomics), which is not supported"),
+            "{message}"
+        );
+    }
+}
@@ -36,6 +36,18 @@ pub fn function_type(function: Symbol) -> Option<FunctionType> {
             );
             Some(sig)
         }
+        "adv_push_mapval" => {
+            // Signature: (key0..key3) -> ()
+            Some(FunctionType::new(
+                midenc_hir::CallConv::Wasm,
+                vec![Type::Felt, Type::Felt, Type::Felt, Type::Felt],
+                vec![],
+            ))
+        }
+        "adv_pop" => {
+            // Signature: () -> felt
+            Some(FunctionType::new(midenc_hir::CallConv::Wasm, vec![], vec![Type::Felt]))
+        }
         "adv_insert_mem" => {
             // Signature: (key0..key3, start_ptr, end_ptr) -> ()
             Some(FunctionType::new(
@@ -44,6 +56,14 @@ pub fn function_type(function: Symbol) -> Option<FunctionType> {
                 vec![],
             ))
         }
+        "emit_with_commitment" => {
+            // Signature: (event_id, commitment0..commitment3) -> ()
+            Some(FunctionType::new(
+                midenc_hir::CallConv::Wasm,
+                vec![Type::Felt, Type::Felt, Type::Felt, Type::Felt, Type::Felt],
+                vec![],
+            ))
+        }
         "emit_falcon_sig_to_stack" => {
             // (msg0..msg3, pk0..pk3) -> ()
             Some(FunctionType::new(
@@ -81,6 +101,34 @@ pub fn function_effects(function: Symbol) -> Option<SmallVec<[IntrinsicEffect; 2
                 argument: None,
             }
         ]),
+        "adv_push_mapval" => Some(smallvec![
+            IntrinsicEffect::Advice {
+                effect: midenc_hir::effects::AdviceEffect::Read,
+                resource: Box::new(AdviceMapResource),
+                result: None,
+                argument: None,
+            },
+            IntrinsicEffect::Advice {
+                effect: midenc_hir::effects::AdviceEffect::Allocate,
+                resource: Box::new(AdviceStackResource),
+                result: None,
+                argument: None,
+            }
+        ]),
+        "adv_pop" => Some(smallvec![
+            IntrinsicEffect::Advice {
+                effect: midenc_hir::effects::AdviceEffect::Read,
+                resource: Box::new(AdviceStackResource),
+                result: None,
+                argument: None,
+            },
+            IntrinsicEffect::Advice {
+                effect: midenc_hir::effects::AdviceEffect::Free,
+                resource: Box::new(AdviceStackResource),
+                result: None,
+                argument: None,
+            }
+        ]),
         "adv_insert_mem" => Some(smallvec![
             IntrinsicEffect::Advice {
                 effect: midenc_hir::effects::AdviceEffect::Allocate,
@@ -95,6 +143,20 @@ pub fn function_effects(function: Symbol) -> Option<SmallVec<[IntrinsicEffect; 2
                 argument: None,
             }
         ]),
+        "emit_with_commitment" => Some(smallvec![
+            IntrinsicEffect::Advice {
+                effect: midenc_hir::effects::AdviceEffect::Read,
+                resource: Box::new(AdviceMapResource),
+                result: None,
+                argument: None,
+            },
+            IntrinsicEffect::Advice {
+                effect: midenc_hir::effects::AdviceEffect::Allocate,
+                resource: Box::new(AdviceStackResource),
+                result: None,
+                argument: None,
+            }
+        ]),
         "emit_falcon_sig_to_stack" => Some(smallvec![IntrinsicEffect::Advice {
             effect: midenc_hir::effects::AdviceEffect::Allocate,
             resource: Box::new(AdviceStackResource),
@@ -106,6 +168,11 @@ pub fn function_effects(function: Symbol) -> Option<SmallVec<[IntrinsicEffect; 2
 }
 
 pub fn as_intrinsic(function: Symbol) -> Option<IntrinsicsConversionResult> {
+    // `emit` is lowered to the native instruction at each call site
+    if function.as_str() == "emit" {
+        return Some(IntrinsicsConversionResult::MidenVmOp);
+    }
+
     let ty = function_type(function)?;
     let effects = function_effects(function)?;
 
@@ -120,6 +187,13 @@ pub fn convert_advice_intrinsics<B: ?Sized + Builder>(
     builder: &mut FunctionBuilderExt<'_, B>,
     span: SourceSpan,
 ) -> WasmResult<SmallVec<[ValueRef; 1]>> {
+    if function.as_str() == "emit" {
+        assert_eq!(args.len(), 1, "{function} takes exactly one argument (event_id)");
+        // The event id stays on the operand stack, but the intrinsic has no results
+        let _ = builder.emit_event(args[0], span)?;
+        return Ok(SmallVec::new());
+    }
+
     let function_ref =
         function_ref.unwrap_or_else(|| panic!("expected '{function}' to have been declared"));
 
@@ -145,6 +219,31 @@ pub fn convert_advice_intrinsics<B: ?Sized + Builder>(
             // The function returns the number of elements pushed as i32
             Ok(result_vals)
         }
+        "adv_push_mapval" => {
+            assert_eq!(args.len(), 4, "{function} takes exactly four arguments (key0-3)");
+            let func = function_ref.borrow();
+            let signature = func.get_signature().clone();
+            drop(func);
+            let _ = builder.exec(function_ref, signature, args.iter().copied(), span)?;
+            Ok(SmallVec::new())
+        }
+        "adv_pop" => {
+            assert!(args.is_empty(), "{function} takes no arguments");
+            let func = function_ref.borrow();
+            let signature = func.get_signature().clone();
+            drop(func);
+            let exec = builder.exec(function_ref, signature, args.iter().copied(), span)?;
+            let borrow = exec.borrow();
+            Ok(borrow.results().iter().map(|op_res| op_res.borrow().as_value_ref()).collect())
+        }
+        "emit_with_commitment" => {
+            assert_eq!(args.len(), 5, "{function} takes exactly five arguments");
+            let func = function_ref.borrow();
+            let signature = func.get_signature().clone();
+            drop(func);
+            let _ = builder.exec(function_ref, signature, args.iter().copied(), span)?;
+            Ok(SmallVec::new())
+        }
         "emit_falcon_sig_to_stack" => {
             assert_eq!(args.len(), 8, "{function} takes exactly eight arguments");
             let func = function_ref.borrow();
@@ -197,4 +296,28 @@ mod tests {
             )
         }));
     }
+
+    #[test]
+    fn emit_is_a_native_op() {
+        let emit = Symbol::intern("emit");
+        assert!(function_type(emit).is_none());
+        assert!(as_intrinsic(emit).is_some_and(|result| result.is_operation()));
+        assert!(as_intrinsic(Symbol::intern("emit_with_commitment")).is_some());
+    }
+
+    #[test]
+    fn adv_pop_frees_advice_stack() {
+        let effects = function_effects(Symbol::intern("adv_pop"))
+            .expect("adv_pop should be modeled as an intrinsic function");
+
+        assert!(effects.iter().any(|effect| {
+            matches!(
+                effect,
+                IntrinsicEffect::Advice {
+                    effect: midenc_hir::effects::AdviceEffect::Free,
+                    ..
+                }
+            )
+        }));
+    }
 }
@@ -12,6 +12,10 @@ use midenc_hir::{Builder, SmallVec, SourceSpan, ValueRef, dialects::builtin::Fun
 
 use crate::{error::WasmResult, module::function_builder_ext::FunctionBuilderExt};
 
+/// The core Wasm import module whose functions are raw Miden Assembly, named by their source, e.g.
+/// an import of `u32clz` from this module is lowered to that instruction at each call site.
+pub const INLINE_ASM_MODULE: &str = "miden:intrinsics/inline-asm";
+
 /// Convert a call to a Miden intrinsic function into instruction(s)
 pub fn convert_intrinsics_call<B: ?Sized + Builder>(
     intrinsic: Intrinsic,
@@ -16,6 +16,7 @@ mod component;
 mod config;
 mod emit;
 mod error;
+mod features;
 mod fpi;
 mod intrinsics;
 mod miden_abi;
@@ -28,6 +29,7 @@ use alloc::rc::Rc;
 use component::build_ir::translate_component;
 use error::WasmResult;
 use midenc_hir::{Context, dialects::builtin};
+use midenc_session::Producers;
 use module::build_ir::translate_module_as_component;
 use wasmparser::WasmFeatures;
 
@@ -41,6 +43,8 @@ pub struct FrontendOutput {
     pub component: builtin::ComponentRef,
     /// The serialized AccountComponentMetadata (name, description, storage layout, etc.)
     pub account_component_metadata_bytes: Option<Vec<u8>>,
+    /// The toolchains which produced the Wasm, from the `producers` sections of its core modules
+    pub producers: Producers,
 }
 
 /// Translate a valid Wasm core module or Wasm Component Model binary into Miden
@@ -53,17 +57,21 @@ pub fn translate(
     if wasm[4..8] == [0x01, 0x00, 0x00, 0x00] {
         // Wasm core module
         // see https://github.com/WebAssembly/component-model/blob/main/design/mvp/Binary.md#component-definitions
-        let component = translate_module_as_component(wasm, config, context)?;
-        Ok(FrontendOutput {
-            component,
-            account_component_metadata_bytes: None,
-        })
+        features::check_features(wasm, supported_features(), context.diagnostics())?;
+        translate_module_as_component(wasm, config, context)
     } else {
+        features::check_features(
+            wasm,
+            supported_component_model_features(),
+            context.diagnostics(),
+        )?;
         translate_component(wasm, config, context)
     }
 }
 
-/// The set of core WebAssembly features which we need to or wish to support
+/// The set of core WebAssembly features which we need to or wish to support.
+///
+/// The use of any other feature is diagnosed up front, see [features::check_features].
 pub(crate) fn supported_features() -> WasmFeatures {
     WasmFeatures::BULK_MEMORY
         | WasmFeatures::FLOATS
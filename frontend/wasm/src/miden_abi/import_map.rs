@@ -0,0 +1,208 @@
+//! The mapping of linker stubs to the Miden Assembly procedures that calls to them are lowered to.
+//!
+//! A linker stub is imported from Wasm as a function named after a fully-qualified Miden Assembly
+//! procedure, e.g. `miden::protocol::active_account::get_id`, see [crate::module::linker_stubs].
+//! The [ImportMap] determines which procedure is actually called, with what signature, and how
+//! the call is adapted to the ABI of that procedure. By default, it maps the stubs of the Miden
+//! standard library and transaction kernel bindings to themselves, but a custom kernel can be
+//! targeted by loading additional mappings from a TOML file, see [ImportMap::from_toml].
+//!
+//! The procedures exported by the libraries linked against are mapped as well, see
+//! [ImportMap::insert_library_export], and can either be called via a linker stub, or imported
+//! from the Wasm module named after their Miden Assembly module.
+
+#[cfg(feature = "std")]
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+use midenc_hir::{
+    CallConv, Context, Type,
+    diagnostics::Uri,
+    parse::{ParserConfig, parse_type},
+};
+use midenc_hir::{FunctionType, FxHashMap, SymbolPath, interner::Symbol};
+use midenc_hir_symbol::sync::LazyLock;
+#[cfg(feature = "std")]
+use midenc_session::diagnostics::Report;
+
+use super::{stdlib, transform::TransformStrategy, tx_kernel};
+
+/// The Miden Assembly procedure that calls to a linker stub are lowered to
+#[derive(Debug, Clone)]
+pub struct MappedImport {
+    /// The fully-qualified path of the procedure
+    pub procedure: SymbolPath,
+    /// The signature of the procedure
+    pub signature: FunctionType,
+    /// How calls to the stub are adapted to the ABI of the procedure
+    pub strategy: TransformStrategy,
+}
+
+/// Maps the paths of linker stubs to the Miden Assembly procedures they call
+#[derive(Debug, Clone, Default)]
+pub struct ImportMap {
+    imports: FxHashMap<SymbolPath, MappedImport>,
+}
+
+impl ImportMap {
+    /// Get the mapping for the standard library and transaction kernel bindings of the Miden SDK
+    pub fn builtin() -> Arc<Self> {
+        static BUILTIN: LazyLock<Arc<ImportMap>> = LazyLock::new(|| {
+            let mut map = ImportMap::default();
+            let modules = stdlib::signatures().iter().chain(tx_kernel::signatures().iter());
+            for (module_path, functions) in modules {
+                for (function, signature) in functions.iter() {
+                    let mut path = module_path.clone();
+                    path.set_name(*function);
+                    // Functions without a known ABI are left unmapped, and rejected if used
+                    let Some(strategy) = super::transform::get_transform_strategy(&path) else {
+                        continue;
+                    };
+                    map.imports.insert(
+                        path.clone(),
+                        MappedImport {
+                            procedure: path,
+                            signature: signature.clone(),
+                            strategy,
+                        },
+                    );
+                }
+            }
+            Arc::new(map)
+        });
+        Arc::clone(&BUILTIN)
+    }
+
+    /// Get the procedure that calls to the linker stub at `path` are lowered to, if mapped
+    pub fn get(&self, path: &SymbolPath) -> Option<&MappedImport> {
+        self.imports.get(path)
+    }
+
+    /// Map the linker stub `name` of the Wasm import module `module` to `import`, replacing any
+    /// previous mapping of that stub
+    pub fn insert(&mut self, module: &str, name: &str, import: MappedImport) {
+        self.imports.insert(procedure_path(module, name), import);
+    }
+
+    /// Get the procedure that calls to the function `name` imported from the Wasm module `module`
+    /// are lowered to, if mapped
+    pub fn get_import(&self, module: &str, name: &str) -> Option<&MappedImport> {
+        self.imports.get(&procedure_path(module, name))
+    }
+
+    /// Map the procedure `name` of the Miden Assembly module `module`, exported with `signature`
+    /// by a linked library, to itself, so that it can be called from Wasm as is.
+    ///
+    /// Existing mappings are kept, so that a library cannot shadow the bindings of the Miden SDK.
+    pub fn insert_library_export(&mut self, module: &str, name: &str, signature: FunctionType) {
+        let procedure = procedure_path(module, name);
+        self.imports.entry(procedure.clone()).or_insert(MappedImport {
+            procedure,
+            signature,
+            strategy: TransformStrategy::NoTransform,
+        });
+    }
+
+    /// Returns the number of mapped linker stubs
+    pub fn len(&self) -> usize {
+        self.imports.len()
+    }
+
+    /// Returns true if no linker stubs are mapped
+    pub fn is_empty(&self) -> bool {
+        self.imports.is_empty()
+    }
+
+    /// Load the mappings in the TOML file at `path` on top of the builtin ones, see
+    /// [Self::from_toml]
+    #[cfg(feature = "std")]
+    pub fn load(context: &Rc<Context>, path: &std::path::Path) -> Result<Self, Report> {
+        let source = std::fs::read_to_string(path).map_err(|err| {
+            Report::msg(format!("unable to read kernel ABI file '{}': {err}", path.display()))
+        })?;
+        Self::from_toml(context, &source).map_err(|err| {
+            Report::msg(format!("invalid kernel ABI file '{}': {err}", path.display()))
+        })
+    }
+
+    /// Parse the mappings in `source` on top of the builtin ones, overriding those of the same
+    /// linker stubs. The types of the signatures are parsed in `context`.
+    ///
+    /// Each mapping is an entry of the `imports` array:
+    ///
+    /// ```toml
+    /// [[imports]]
+    /// # The Wasm import module and name of the linker stub
+    /// module = "miden::protocol::active_account"
+    /// name = "get_id"
+    /// # The Miden Assembly library path and procedure to call instead
+    /// library = "my_kernel::account"
+    /// procedure = "get_account_id"
+    /// # The signature of the procedure, with types written as in IR assembly, e.g. `felt`, `u32`
+    /// # or `ptr<u8, byte>`
+    /// params = []
+    /// results = ["felt", "felt"]
+    /// # One of `no-transform`, `return-via-pointer` or `fpi-indirect-return-via-pointer`
+    /// adaptor = "return-via-pointer"
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_toml(context: &Rc<Context>, source: &str) -> Result<Self, Report> {
+        let document = toml_edit::Document::parse(source)
+            .map_err(|err| Report::msg(format!("failed to parse TOML: {err}")))?;
+        let mut map = Self::builtin().as_ref().clone();
+        let Some(imports) = document.as_table().get("imports") else {
+            return Ok(map);
+        };
+        let Some(imports) = imports.as_array_of_tables() else {
+            return Err(Report::msg("expected 'imports' to be an array of tables"));
+        };
+        for (index, import) in imports.iter().enumerate() {
+            let string = |key: &str| {
+                import.get(key).and_then(|item| item.as_str()).ok_or_else(|| {
+                    Report::msg(format!("expected 'imports[{index}].{key}' to be a string"))
+                })
+            };
+            let types = |key: &str| -> Result<alloc::vec::Vec<Type>, Report> {
+                let invalid = || {
+                    Report::msg(format!(
+                        "expected 'imports[{index}].{key}' to be an array of type names"
+                    ))
+                };
+                let array = import.get(key).and_then(|item| item.as_array()).ok_or_else(invalid)?;
+                array
+                    .iter()
+                    .map(|value| {
+                        let name = value.as_str().ok_or_else(invalid)?;
+                        let config = ParserConfig::new(context.clone());
+                        let uri = Uri::new(format!("imports[{index}].{key}").as_str());
+                        parse_type(config, uri, name).map_err(|err| {
+                            Report::msg(format!(
+                                "invalid type '{name}' in 'imports[{index}].{key}': {err}"
+                            ))
+                        })
+                    })
+                    .collect()
+            };
+
+            let adaptor = string("adaptor")?;
+            let strategy = adaptor.parse::<TransformStrategy>().map_err(|_| {
+                Report::msg(format!("unknown adaptor '{adaptor}' in 'imports[{index}].adaptor'"))
+            })?;
+            let mapped = MappedImport {
+                procedure: procedure_path(string("library")?, string("procedure")?),
+                signature: FunctionType::new(CallConv::Wasm, types("params")?, types("results")?),
+                strategy,
+            };
+            map.insert(string("module")?, string("name")?, mapped);
+        }
+        Ok(map)
+    }
+}
+
+/// Get the path of the procedure `name` of the Miden Assembly module `module`
+fn procedure_path(module: &str, name: &str) -> SymbolPath {
+    let mut path = SymbolPath::from_masm_module_id(module);
+    path.set_name(Symbol::intern(name));
+    path
+}
@@ -1,3 +1,4 @@
+pub(crate) mod import_map;
 pub(crate) mod stdlib;
 pub(crate) mod transform;
 pub(crate) mod tx_kernel;
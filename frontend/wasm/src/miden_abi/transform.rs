@@ -1,3 +1,5 @@
+use core::str::FromStr;
+
 use midenc_dialect_arith::ArithOpBuilder;
 use midenc_dialect_hir::{ExecFpi, HirOpBuilder};
 use midenc_hir::{
@@ -16,7 +18,8 @@ const RAW_FPI_FLATTENED_ARG_COUNT: u32 = ExecFpi::EXECUTOR_INPUT_FELTS as u32;
 const RAW_FPI_FLATTENED_ARG_COUNT_USIZE: usize = ExecFpi::EXECUTOR_INPUT_FELTS;
 
 /// The strategy to use for transforming a function call
-enum TransformStrategy {
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransformStrategy {
     /// The Miden ABI function returns on the stack and we want to return via a pointer argument
     ReturnViaPointer,
     /// The import is the raw FPI binding, which passes the executor ABI through one pointer.
@@ -25,8 +28,21 @@ enum TransformStrategy {
     NoTransform,
 }
 
+impl FromStr for TransformStrategy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "return-via-pointer" => Ok(Self::ReturnViaPointer),
+            "fpi-indirect-return-via-pointer" => Ok(Self::FpiIndirectReturnViaPointer),
+            "no-transform" => Ok(Self::NoTransform),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Get the transformation strategy for a function name
-fn get_transform_strategy(path: &SymbolPath) -> Option<TransformStrategy> {
+pub(super) fn get_transform_strategy(path: &SymbolPath) -> Option<TransformStrategy> {
     let mut components = path.components().peekable();
     components.next_if_eq(&SymbolNameComponent::Root);
 
@@ -299,22 +315,22 @@ fn get_transform_strategy(path: &SymbolPath) -> Option<TransformStrategy> {
 /// Transform a Miden ABI function call based on the transformation strategy
 ///
 /// `import_func` - import function that we're transforming a call to (think of a MASM function)
+/// `strategy` - how the call is adapted to the ABI of `import_func`
 /// `args` - arguments to the generated synthetic function
 /// Returns results that will be returned from the synthetic function
 pub fn transform_miden_abi_call<B: ?Sized + Builder>(
     import_func_ref: FunctionRef,
-    import_path: &SymbolPath,
+    strategy: TransformStrategy,
     args: &[ValueRef],
     builder: &mut FunctionBuilderExt<'_, B>,
 ) -> WasmResult<Vec<ValueRef>> {
     use TransformStrategy::*;
-    match get_transform_strategy(import_path) {
-        Some(ReturnViaPointer) => return_via_pointer(import_func_ref, args, builder),
-        Some(FpiIndirectReturnViaPointer) => {
+    match strategy {
+        ReturnViaPointer => return_via_pointer(import_func_ref, args, builder),
+        FpiIndirectReturnViaPointer => {
             fpi_indirect_return_via_pointer(import_func_ref, args, builder)
         }
-        Some(NoTransform) => no_transform(import_func_ref, args, builder),
-        None => Err(Report::msg(format!("no transform strategy implemented for '{import_path}'"))),
+        NoTransform => no_transform(import_func_ref, args, builder),
     }
 }
 
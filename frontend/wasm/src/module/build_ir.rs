@@ -2,14 +2,14 @@ use core::{mem, str::FromStr};
 use std::rc::Rc;
 
 use midenc_hir::{
-    Builder, BuilderExt, Context, FunctionIdent, FxHashMap, Ident, Op, OpBuilder, SymbolPath,
-    Visibility,
+    Builder, BuilderExt, Context, FunctionIdent, FxHashMap, Ident, Immediate, Op, OpBuilder,
+    SymbolPath, Type, Visibility,
     constants::ConstantData,
-    dialects::builtin::{
-        self, BuiltinOpBuilder, ComponentBuilder, ModuleBuilder, World, WorldBuilder,
-    },
+    diagnostics::WrapErr,
+    dialects::builtin::{BuiltinOpBuilder, ComponentBuilder, ModuleBuilder, World, WorldBuilder},
     version::Version,
 };
+use midenc_hir_symbol::symbols;
 use midenc_session::diagnostics::{DiagnosticsHandler, IntoDiagnostic, Severity, SourceSpan};
 use wasmparser::Validator;
 
@@ -18,7 +18,7 @@ use super::{
     module_translation_state::ModuleTranslationState, types::ModuleTypesBuilder,
 };
 use crate::{
-    WasmTranslationConfig,
+    FrontendOutput, WasmTranslationConfig,
     error::WasmResult,
     intrinsics::Intrinsic,
     module::{
@@ -40,7 +40,7 @@ pub fn translate_module_as_component(
     wasm: &[u8],
     config: &WasmTranslationConfig,
     context: Rc<Context>,
-) -> WasmResult<builtin::ComponentRef> {
+) -> WasmResult<FrontendOutput> {
     let mut validator = Validator::new_with_features(crate::supported_features());
     let parser = wasmparser::Parser::new(0);
     let mut module_types_builder = Default::default();
@@ -78,11 +78,16 @@ pub fn translate_module_as_component(
         &mut world_builder,
         &module_types,
         FxHashMap::default(),
+        config,
         context.diagnostics(),
     )?;
     build_ir_module(&mut parsed_module, &module_types, &mut module_state, config, context)?;
 
-    Ok(component_ref)
+    Ok(FrontendOutput {
+        component: component_ref,
+        account_component_metadata_bytes: None,
+        producers: mem::take(&mut parsed_module.producers),
+    })
 }
 
 pub fn build_ir_module(
@@ -141,7 +146,10 @@ pub fn build_ir_module(
     // This ensures that when we translate function bodies in pass 2,
     // all inline-able stubs are known and calls to them will be inlined regardless
     // of function ordering in the WASM file.
+    // Stubs in the `miden` namespace which are neither intrinsics nor mapped to a MASM procedure
+    // are collected along the way, and reported together.
     let mut inlined_stub_indices: Vec<DefinedFuncIndex> = Vec::new();
+    let mut unmapped_stubs: Vec<&str> = Vec::new();
     for (defined_func_idx, body_data) in &func_body_inputs {
         if !is_unreachable_stub(&body_data.body) {
             continue;
@@ -158,6 +166,11 @@ pub fn build_ir_module(
 
         // Try to recognize as an intrinsic
         let Ok(intrinsic) = Intrinsic::try_from(&import_path) else {
+            if import_path.namespace() == Some(symbols::Miden)
+                && module_state.import_map.get(&import_path).is_none()
+            {
+                unmapped_stubs.push(func_name);
+            }
             continue;
         };
 
@@ -178,6 +191,21 @@ pub fn build_ir_module(
         }
     }
 
+    if !unmapped_stubs.is_empty() {
+        return Err(context
+            .diagnostics()
+            .diagnostic(Severity::Error)
+            .with_message(format!(
+                "no Miden Assembly procedure is known for the imports: {}",
+                unmapped_stubs.join(", ")
+            ))
+            .with_help(
+                "check that the Miden SDK version matches the compiler, or map these imports to \
+                 the procedures of your kernel with `--kernel-abi`",
+            )
+            .into_report());
+    }
+
     // Pass 2: Translate function bodies.
     //   - Inline-able stubs were registered in pass 1 and are skipped here.
     //   - Function-type stubs get their bodies synthesized.
@@ -208,18 +236,20 @@ pub fn build_ir_module(
         let mut func_validator = validator.into_validator(Default::default());
         let debug_info = parsed_module.function_debug.get(&func_index).cloned();
 
-        func_translator.translate_body(
-            &body,
-            function_ref,
-            module_state,
-            parsed_module,
-            module_types,
-            &addr2line,
-            context.session(),
-            &mut func_validator,
-            _config,
-            debug_info,
-        )?;
+        func_translator
+            .translate_body(
+                &body,
+                function_ref,
+                module_state,
+                parsed_module,
+                module_types,
+                &addr2line,
+                context.session(),
+                &mut func_validator,
+                _config,
+                debug_info,
+            )
+            .wrap_err_with(|| format!("failed to translate function '{func_name}'"))?;
     }
     Ok(())
 }
@@ -287,5 +317,52 @@ fn build_data_segments(
             )));
         }
     }
+    build_passive_data_segments(translation, module_builder, diagnostics)
+}
+
+/// Place the contents of the passive data segments in memory, from which `memory.init` copies
+/// them, and define the flags recording which of them were dropped with `data.drop`
+fn build_passive_data_segments(
+    translation: &ParsedModule,
+    module_builder: &mut ModuleBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<()> {
+    if translation.passive_data.is_empty() {
+        return Ok(());
+    }
+
+    let offset = translation.module.passive_data_base;
+    let init = ConstantData::from(translation.passive_data.concat());
+    let size = init.len() as u32;
+    module_builder
+        .define_data_segment(offset, init, true, SourceSpan::default())
+        .map_err(|e| {
+            e.wrap_err(format!(
+                "Failed to declare the passive data segments with size '{size}' at '{offset:#x}'"
+            ))
+        })?;
+
+    let span = SourceSpan::default();
+    for data_index in translation.module.passive_data_map.keys() {
+        let name = translation.module.passive_data_dropped_name(*data_index);
+        let mut global_var_ref = module_builder
+            .define_global_variable(name.into(), Visibility::Private, Type::I32)
+            .map_err(|e| {
+                diagnostics
+                    .diagnostic(Severity::Error)
+                    .with_message(format!(
+                        "Failed to declare global variable '{name}' with error: {e:?}"
+                    ))
+                    .into_report()
+            })?;
+        let context = global_var_ref.borrow().as_operation().context_rc().clone();
+        let init_region_ref = {
+            let mut global_var = global_var_ref.borrow_mut();
+            global_var.initializer_mut().as_region_ref()
+        };
+        let mut op_builder = OpBuilder::new(context);
+        op_builder.create_block(init_region_ref, None, &[]);
+        op_builder.ret_imm(Immediate::I32(0), span)?;
+    }
     Ok(())
 }
@@ -0,0 +1,94 @@
+//! Compile-time contents of the tables of function references used by `call_indirect`.
+
+use alloc::collections::BTreeMap;
+
+use cranelift_entity::packed_option::ReservedValue;
+use midenc_session::DiagnosticsHandler;
+
+use super::{
+    Module, TableInitialValue,
+    types::{FuncIndex, TableIndex},
+};
+use crate::{error::WasmResult, unsupported_diag};
+
+/// The contents of a Wasm table of function references, as they are after instantiation of the
+/// module which defines it.
+///
+/// Miden has no first-class function references, so tables are not materialized at runtime.
+/// Instead, `call_indirect` is lowered to a dispatch over the entries of this table, which is
+/// sound as long as the table is not modified after instantiation, i.e. it is not imported, nor
+/// the target of `table.set`, `table.init` and the like, none of which are supported.
+#[derive(Debug, Default)]
+pub struct FunctionTable {
+    /// The non-null entries of the table, keyed by their index in the table
+    entries: BTreeMap<u32, FuncIndex>,
+}
+
+impl FunctionTable {
+    /// Compute the contents of the table at `index` in `module`, by applying its initializer, and
+    /// then its active element segments, in the order in which they are declared
+    pub fn build(
+        module: &Module,
+        index: TableIndex,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<Self> {
+        let Some(defined_index) = module.defined_table_index(index) else {
+            unsupported_diag!(
+                diagnostics,
+                "call_indirect through imported table {} is not supported",
+                index.as_u32()
+            );
+        };
+
+        let mut table = Self::default();
+        match &module.table_initialization.initial_values[defined_index] {
+            TableInitialValue::Null { precomputed } => {
+                for (slot, func) in precomputed.iter().enumerate() {
+                    table.set(slot as u32, *func);
+                }
+            }
+            TableInitialValue::FuncRef(func) => {
+                for slot in 0..module.tables[index].minimum {
+                    table.set(slot, *func);
+                }
+            }
+        }
+
+        for segment in module.table_initialization.segments.iter() {
+            if segment.table_index != index {
+                continue;
+            }
+            let base = match segment.base {
+                Some(global) => module
+                    .try_global_initializer(global, diagnostics)?
+                    .as_i32(module, diagnostics)? as u32,
+                None => 0,
+            };
+            let offset = base.wrapping_add(segment.offset);
+            for (i, func) in segment.elements.iter().enumerate() {
+                table.set(offset + i as u32, *func);
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// Set the entry at `slot` to `func`, or to null if `func` is the reserved value
+    fn set(&mut self, slot: u32, func: FuncIndex) {
+        if func.is_reserved_value() {
+            self.entries.remove(&slot);
+        } else {
+            self.entries.insert(slot, func);
+        }
+    }
+
+    /// Returns the function at `slot`, or `None` if it is null or out of bounds
+    pub fn get(&self, slot: u32) -> Option<FuncIndex> {
+        self.entries.get(&slot).copied()
+    }
+
+    /// The non-null entries of this table, in slot order
+    pub fn entries(&self) -> impl Iterator<Item = (u32, FuncIndex)> + '_ {
+        self.entries.iter().map(|(slot, func)| (*slot, *func))
+    }
+}
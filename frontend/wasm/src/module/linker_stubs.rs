@@ -9,11 +9,10 @@ use core::{cell::RefCell, str::FromStr};
 
 use midenc_dialect_cf::ControlFlowOpBuilder;
 use midenc_hir::{
-    FunctionType, Op, SmallVec, SymbolPath, ValueRef, Visibility,
+    Op, SmallVec, SymbolPath, ValueRef, Visibility,
     diagnostics::WrapErr,
     dialects::builtin::{BuiltinOpBuilder, FunctionRef, ModuleBuilder, attributes::Signature},
 };
-use midenc_hir_symbol::symbols;
 use wasmparser::{FunctionBody, Operator};
 
 use crate::{
@@ -22,8 +21,7 @@ use crate::{
         Intrinsic, IntrinsicsConversionResult, attach_effects_to_function, convert_intrinsics_call,
     },
     miden_abi::{
-        is_miden_abi_module, miden_abi_function_effects, miden_abi_function_type,
-        transform::transform_miden_abi_call,
+        import_map::MappedImport, miden_abi_function_effects, transform::transform_miden_abi_call,
     },
     module::{
         function_builder_ext::{FunctionBuilderContext, FunctionBuilderExt, SSABuilderListener},
@@ -56,6 +54,14 @@ pub fn is_unreachable_stub(body: &FunctionBody<'_>) -> bool {
     saw_unreachable
 }
 
+/// What calls to a linker stub are lowered to
+enum StubTarget {
+    /// A compiler intrinsic
+    Intrinsic(Intrinsic),
+    /// A MASM procedure, according to the [crate::ImportMap] of the translation
+    Procedure(MappedImport),
+}
+
 /// If `body` looks like a linker stub, lowers `function_ref` to a call to the
 /// MASM callee derived from the function name and applies the appropriate
 /// TransformStrategy. Returns `true` if handled, `false` otherwise.
@@ -79,31 +85,27 @@ pub fn maybe_lower_linker_stub(
         Err(_) => return Ok(false),
     };
     let import_path: SymbolPath = SymbolPath::from_masm_function_id(func_ident);
-    // Ensure the stub targets a known Miden ABI module or a recognized intrinsic.
-    let is_intrinsic = Intrinsic::try_from(&import_path).is_ok();
-    if !is_miden_abi_module(&import_path) && !is_intrinsic {
-        if import_path.namespace() == Some(symbols::Miden) {
-            panic!(
-                "Failed to recognize miden stub: {}, check that symbols.toml (used to \
-                 generate`symbols::<Symbol>` values) has all the parts right and it's signature \
-                 is defined in the frontend/wasm/src/miden_abi/",
-                import_path.to_library_path()
-            );
-        }
-        return Ok(false);
-    }
+    // Ensure the stub is a recognized intrinsic, or is mapped to a MASM procedure. Unmapped stubs
+    // in the `miden` namespace are reported before any body is lowered, see `build_ir_module`.
+    let target = match Intrinsic::try_from(&import_path) {
+        Ok(intr) => StubTarget::Intrinsic(intr),
+        Err(_) => match module_state.import_map.get(&import_path) {
+            Some(mapped) => StubTarget::Procedure(mapped.clone()),
+            None => return Ok(false),
+        },
+    };
 
     let context = function_ref.borrow().as_operation().context_rc();
 
     // Classify intrinsics and obtain signature when needed
-    let (import_sig, intrinsic): (Signature, Option<Intrinsic>) =
-        match Intrinsic::try_from(&import_path) {
-            Ok(intr) => (function_ref.borrow().get_signature().clone(), Some(intr)),
-            Err(_) => {
-                let import_ft: FunctionType = miden_abi_function_type(&import_path);
-                (Signature::new(&context, import_ft.params, import_ft.results), None)
-            }
-        };
+    let import_sig = match &target {
+        StubTarget::Intrinsic(_) => function_ref.borrow().get_signature().clone(),
+        StubTarget::Procedure(mapped) => Signature::new(
+            &context,
+            mapped.signature.params.iter().cloned(),
+            mapped.signature.results.iter().cloned(),
+        ),
+    };
 
     // Build the function body for the stub and replace it with an exec to MASM
     let span = function_ref.borrow().name().span;
@@ -124,46 +126,62 @@ pub fn maybe_lower_linker_stub(
         .collect();
 
     // Declare MASM import callee in world and exec via TransformStrategy
-    let results: Vec<ValueRef> = if let Some(intr) = intrinsic {
-        // Decide whether the intrinsic is implemented as a function or an operation
-        let Some(conv) = intr.conversion_result() else {
-            return Ok(false);
-        };
-        if let IntrinsicsConversionResult::FunctionType { effects, .. } = conv {
-            // Declare callee and call via convert_intrinsics_call with function_ref
+    let results: Vec<ValueRef> = match target {
+        StubTarget::Intrinsic(intr) => {
+            // Decide whether the intrinsic is implemented as a function or an operation
+            let Some(conv) = intr.conversion_result() else {
+                return Ok(false);
+            };
+            if let IntrinsicsConversionResult::FunctionType { effects, .. } = conv {
+                // Declare callee and call via convert_intrinsics_call with function_ref
+                let import_module_ref = module_state
+                    .world_builder
+                    .declare_module_tree(&import_path.without_leaf())
+                    .wrap_err("failed to create module for intrinsics imports")?;
+                let mut import_module_builder = ModuleBuilder::new(import_module_ref);
+                let mut intrinsic_func_ref = import_module_builder
+                    .define_function(
+                        import_path.name().into(),
+                        Visibility::Public,
+                        import_sig.clone(),
+                    )
+                    .wrap_err("failed to create intrinsic function ref")?;
+                {
+                    let mut intrinsic_func = intrinsic_func_ref.borrow_mut();
+                    attach_effects_to_function(&mut intrinsic_func, effects.iter());
+                }
+                convert_intrinsics_call(intr, Some(intrinsic_func_ref), &args, &mut fb, span)?
+                    .to_vec()
+            } else {
+                // Inline conversion of intrinsic operation
+                convert_intrinsics_call(intr, None, &args, &mut fb, span)?.to_vec()
+            }
+        }
+        StubTarget::Procedure(mapped) => {
+            // Miden ABI path: exec the mapped procedure with its TransformStrategy
             let import_module_ref = module_state
                 .world_builder
-                .declare_module_tree(&import_path.without_leaf())
-                .wrap_err("failed to create module for intrinsics imports")?;
+                .declare_module_tree(&mapped.procedure.without_leaf())
+                .wrap_err("failed to create module for MASM imports")?;
             let mut import_module_builder = ModuleBuilder::new(import_module_ref);
-            let mut intrinsic_func_ref = import_module_builder
-                .define_function(import_path.name().into(), Visibility::Public, import_sig.clone())
-                .wrap_err("failed to create intrinsic function ref")?;
-            {
-                let mut intrinsic_func = intrinsic_func_ref.borrow_mut();
-                attach_effects_to_function(&mut intrinsic_func, effects.iter());
-            }
-            convert_intrinsics_call(intr, Some(intrinsic_func_ref), &args, &mut fb, span)?.to_vec()
-        } else {
-            // Inline conversion of intrinsic operation
-            convert_intrinsics_call(intr, None, &args, &mut fb, span)?.to_vec()
-        }
-    } else {
-        // Miden ABI path: exec import with TransformStrategy
-        let import_module_ref = module_state
-            .world_builder
-            .declare_module_tree(&import_path.without_leaf())
-            .wrap_err("failed to create module for MASM imports")?;
-        let mut import_module_builder = ModuleBuilder::new(import_module_ref);
-        let mut import_func_ref = import_module_builder
-            .define_function(import_path.name().into(), Visibility::Public, import_sig)
-            .wrap_err("failed to create MASM import function ref")?;
-        {
-            let effects = miden_abi_function_effects(&import_path);
-            let mut import_func = import_func_ref.borrow_mut();
-            attach_effects_to_function(&mut import_func, effects.iter());
+            let name = mapped.procedure.name();
+            // Several stubs may be mapped to the same procedure
+            let import_func_ref = match import_module_builder.get_function(name.as_str()) {
+                Some(import_func_ref) => import_func_ref,
+                None => {
+                    let mut import_func_ref = import_module_builder
+                        .define_function(name.into(), Visibility::Public, import_sig)
+                        .wrap_err("failed to create MASM import function ref")?;
+                    {
+                        let effects = miden_abi_function_effects(&import_path);
+                        let mut import_func = import_func_ref.borrow_mut();
+                        attach_effects_to_function(&mut import_func, effects.iter());
+                    }
+                    import_func_ref
+                }
+            };
+            transform_miden_abi_call(import_func_ref, mapped.strategy, &args, &mut fb)?
         }
-        transform_miden_abi_call(import_func_ref, &import_path, &args, &mut fb)?
     };
 
     // Return
@@ -16,6 +16,7 @@ pub mod debug_info;
 pub mod func_translation_state;
 pub mod func_translator;
 pub mod function_builder_ext;
+pub mod function_table;
 pub mod instance;
 pub mod linker_stubs;
 pub mod module_env;
@@ -113,9 +114,16 @@ pub struct Module {
     /// `passive_elements`.
     pub passive_elements_map: BTreeMap<ElemIndex, usize>,
 
-    /// The map from passive data index (data segment index space) to index in `passive_data`.
+    /// The map from passive data index (data segment index space) to the range of its contents
+    /// among the contents of all passive data segments, which are laid out one after the other
+    /// from `passive_data_base`.
     pub passive_data_map: BTreeMap<DataIndex, Range<u32>>,
 
+    /// The address in linear memory at which the contents of the passive data segments are placed,
+    /// i.e. past the static data and stack of the module, as given by its `__heap_base` export,
+    /// which is moved past them.
+    pub passive_data_base: u32,
+
     /// Types declared in the wasm module.
     pub types: PrimaryMap<TypeIndex, ModuleType>,
 
@@ -337,6 +345,12 @@ impl Module {
             .unwrap_or_else(|| Symbol::intern(format!("data{}", index.as_u32())))
     }
 
+    /// Returns the name of the global variable recording whether the given passive data segment
+    /// was dropped with `data.drop`.
+    pub fn passive_data_dropped_name(&self, index: DataIndex) -> Symbol {
+        Symbol::intern(format!("__data_dropped{}", index.as_u32()))
+    }
+
     /// Returns the name of the given local (including parameters) if available in the name section.
     pub fn local_name(&self, func: FuncIndex, index: u32) -> Option<Symbol> {
         self.name_section
@@ -5,11 +5,14 @@ use std::path::PathBuf;
 use cranelift_entity::{PrimaryMap, packed_option::ReservedValue};
 use midenc_frontend_wasm_metadata::{FrontendMetadata, WASM_FRONTEND_METADATA_CUSTOM_SECTION_NAME};
 use midenc_hir::{FxHashMap, FxHashSet, Ident, interner::Symbol};
-use midenc_session::diagnostics::{DiagnosticsHandler, IntoDiagnostic, Report, Severity};
+use midenc_session::{
+    Producers,
+    diagnostics::{DiagnosticsHandler, IntoDiagnostic, Report, Severity},
+};
 use wasmparser::{
     CustomSectionReader, DataKind, ElementItems, ElementKind, Encoding, ExternalKind,
-    FuncToValidate, FunctionBody, NameSectionReader, Naming, Operator, Parser, Payload, TypeRef,
-    Validator, ValidatorResources, types::CoreTypeId,
+    FuncToValidate, FunctionBody, NameSectionReader, Naming, Operator, Parser, Payload,
+    ProducersSectionReader, TypeRef, Validator, ValidatorResources, types::CoreTypeId,
 };
 
 use super::{
@@ -23,9 +26,10 @@ use crate::{
     module::{
         FuncRefIndex, Module, ModuleType, TableSegment,
         types::{
-            DataSegmentOffset, DefinedFuncIndex, ElemIndex, EntityIndex, EntityType, FuncIndex,
-            GlobalIndex, GlobalInit, MemoryIndex, ModuleTypesBuilder, TableIndex, TypeIndex,
-            WasmType, convert_func_type, convert_global_type, convert_table_type, convert_valtype,
+            DataIndex, DataSegmentOffset, DefinedFuncIndex, ElemIndex, EntityIndex, EntityType,
+            FuncIndex, GlobalIndex, GlobalInit, MemoryIndex, ModuleTypesBuilder, TableIndex,
+            TypeIndex, WasmType, convert_func_type, convert_global_type, convert_table_type,
+            convert_valtype,
         },
     },
     unsupported_diag,
@@ -78,6 +82,10 @@ pub struct ParsedModule<'data> {
     /// List of data segments found in this module
     pub data_segments: PrimaryMap<DataSegmentIndex, DataSegment<'data>>,
 
+    /// The contents of the passive data segments found in this module, in order, see
+    /// [Module::passive_data_map]
+    pub passive_data: Vec<&'data [u8]>,
+
     /// When we're parsing the code section this will be incremented so we know
     /// which function is currently being defined.
     code_index: u32,
@@ -86,6 +94,8 @@ pub struct ParsedModule<'data> {
     pub account_component_metadata_bytes: Option<&'data [u8]>,
     /// Frontend-only component metadata emitted by SDK macros.
     pub component_frontend_metadata: Option<FrontendMetadata>,
+    /// The toolchains which produced this module, from its `producers` section
+    pub producers: Producers,
 }
 
 /// Aggregates frontend metadata from all core modules that participate in one component.
@@ -326,6 +336,14 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                 }
             }
             Payload::CustomSection(s) if s.name().starts_with(".debug_") => self.dwarf_section(&s),
+            Payload::CustomSection(s) if s.name() == Producers::SECTION_NAME => {
+                let reader = wasmparser::BinaryReader::new(s.data(), s.data_offset());
+                let result = ProducersSectionReader::new(reader)
+                    .and_then(|producers| self.producers_section(producers));
+                if let Err(e) = result {
+                    log::warn!(target: "module-parser", "failed to parse producers section {e:?}");
+                }
+            }
             Payload::CustomSection(s) if s.name() == "rodata,miden_account" => {
                 self.result.account_component_metadata_bytes = Some(s.data());
             }
@@ -732,7 +750,7 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         self.validator.data_section(&data_section).into_diagnostic()?;
         let cnt = usize::try_from(data_section.count()).unwrap();
         self.result.data_segments.reserve_exact(cnt);
-        for entry in data_section {
+        for (index, entry) in data_section.into_iter().enumerate() {
             let wasmparser::Data {
                 kind,
                 data,
@@ -766,21 +784,106 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     self.result.data_segments.push(segment);
                 }
                 DataKind::Passive => {
-                    unsupported_diag!(
-                        diagnostics,
-                        "wasm error: unsupported passive data segment in data section"
-                    );
+                    let module = &mut self.result.module;
+                    let start =
+                        module.passive_data_map.values().last().map_or(0, |range| range.end);
+                    let end = start + u32::try_from(data.len()).into_diagnostic()?;
+                    module.passive_data_map.insert(DataIndex::from_u32(index as u32), start..end);
+                    self.result.passive_data.push(data);
                 }
             }
         }
+
+        if !self.result.passive_data.is_empty() {
+            self.place_passive_data(diagnostics)?;
+        }
+        Ok(())
+    }
+
+    /// Place the contents of the passive data segments at the end of the memory used by the
+    /// module for its static data and stack, as exported by the linker in `__heap_base`, or in
+    /// `__data_end` if the stack is laid out before the static data.
+    ///
+    /// As `__heap_base` is where allocators start the heap, it is moved past the contents of the
+    /// passive data segments, so that memory allocated by the module never overlaps them.
+    fn place_passive_data(&mut self, diagnostics: &DiagnosticsHandler) -> WasmResult<()> {
+        let (global, base) = self.passive_data_base(diagnostics)?;
+        let module = &mut self.result.module;
+        module.passive_data_base = base;
+
+        if module.exports.get("__heap_base") == Some(&EntityIndex::Global(global)) {
+            let size = module.passive_data_map.values().last().map_or(0, |range| range.end);
+            let heap_base = base
+                .checked_add(size)
+                .and_then(|end| end.checked_next_multiple_of(16))
+                .and_then(|end| i32::try_from(end).ok());
+            let Some(heap_base) = heap_base else {
+                return Err(diagnostics
+                    .diagnostic(Severity::Error)
+                    .with_message(format!(
+                        "wasm error: unable to place passive data segments: {size} bytes do not \
+                         fit in memory past `__heap_base` ({base:#x})"
+                    ))
+                    .into_report());
+            };
+            let index = module.defined_global_index(global).expect("`__heap_base` is defined");
+            module.global_initializers[index] = GlobalInit::I32Const(heap_base);
+        }
         Ok(())
     }
 
+    /// Get the global exporting the end of the memory used by the module for its static data and
+    /// stack, and the address at which the contents of the passive data segments are placed.
+    fn passive_data_base(
+        &self,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<(GlobalIndex, u32)> {
+        let module = &self.result.module;
+        let global = ["__heap_base", "__data_end"].into_iter().find_map(|name| {
+            match module.exports.get(name) {
+                Some(EntityIndex::Global(index)) => Some(*index),
+                _ => None,
+            }
+        });
+        let Some(global) = global else {
+            return Err(diagnostics
+                .diagnostic(Severity::Error)
+                .with_message(
+                    "wasm error: unable to place passive data segments: the module exports \
+                     neither `__heap_base` nor `__data_end`",
+                )
+                .with_help(
+                    "passive data segments are placed past the memory used by the module, which \
+                     the linker exports in `__heap_base` with `--export=__heap_base`",
+                )
+                .into_report());
+        };
+        let base = module
+            .try_global_initializer(global, diagnostics)?
+            .as_i32(module, diagnostics)?;
+        Ok((global, (base as u32).next_multiple_of(16)))
+    }
+
     /// Parses the Name section of the wasm module.
+    fn producers_section(
+        &mut self,
+        producers: ProducersSectionReader<'data>,
+    ) -> Result<(), wasmparser::BinaryReaderError> {
+        for field in producers {
+            let field = field?;
+            for value in field.values {
+                let value = value?;
+                self.result.producers.insert(field.name, value.name, value.version);
+            }
+        }
+        Ok(())
+    }
+
     fn name_section(&mut self, names: NameSectionReader<'data>) -> WasmResult<()> {
         for subsection in names {
             match subsection.into_diagnostic()? {
                 wasmparser::Name::Function(names) => {
+                    let mut seen = FxHashSet::default();
                     for name in names {
                         let Naming { index, name } = name.into_diagnostic()?;
                         // Skip this naming if it's naming a function that
@@ -788,17 +891,14 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                         if (index as usize) >= self.result.module.functions.len() {
                             continue;
                         }
+                        let name = unique_name(&mut seen, name, index);
 
                         // Store the name unconditionally, regardless of
                         // whether we're parsing debuginfo, since function
                         // names are almost always present in the
                         // final compilation artifact.
                         let index = FuncIndex::from_u32(index);
-                        self.result
-                            .module
-                            .name_section
-                            .func_names
-                            .insert(index, Symbol::intern(name));
+                        self.result.module.name_section.func_names.insert(index, name);
                     }
                 }
                 wasmparser::Name::Module { name, .. } => {
@@ -832,14 +932,16 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     }
                 }
                 wasmparser::Name::Global(names) => {
+                    let mut seen = FxHashSet::default();
                     for name in names {
                         let Naming { index, name } = name.into_diagnostic()?;
                         if index != u32::MAX {
+                            let name = unique_name(&mut seen, name, index);
                             self.result
                                 .module
                                 .name_section
                                 .globals_names
-                                .insert(GlobalIndex::from_u32(index), Symbol::intern(name));
+                                .insert(GlobalIndex::from_u32(index), name);
                         }
                     }
                 }
@@ -968,3 +1070,15 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
         Ok(())
     }
 }
+
+/// Returns `name` as a symbol, suffixed with `index` if it was already `seen`.
+///
+/// Names in the name section are not required to be unique, but the symbols derived from them
+/// are, so all but the first entity of a given name are disambiguated by their index.
+fn unique_name<'data>(seen: &mut FxHashSet<&'data str>, name: &'data str, index: u32) -> Symbol {
+    if seen.insert(name) {
+        Symbol::intern(name)
+    } else {
+        Symbol::intern(format!("{name}_{index}"))
+    }
+}
@@ -121,3 +121,32 @@ fn component_frontend_metadata_reports_missing_lifted_exports() {
         "unexpected error: {err:?}"
     );
 }
+
+/// Ensures entities sharing a name in the name section are given distinct names.
+#[test]
+fn name_section_disambiguates_duplicate_names() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (global (@name "counter") (mut i32) i32.const 0)
+            (global (@name "counter") (mut i32) i32.const 1)
+            (func (@name "dup"))
+            (func (@name "dup"))
+            (func (@name "other"))
+        )"#,
+    )
+    .unwrap();
+    let config = WasmTranslationConfig::default();
+    let mut validator = Validator::new_with_features(crate::supported_features());
+    let mut types = ModuleTypesBuilder::default();
+    let parsed = ModuleEnvironment::new(&config, &mut validator, &mut types)
+        .parse(Parser::new(0), &wasm, &DiagnosticsHandler::default())
+        .unwrap();
+
+    let module = &parsed.module;
+    assert_eq!(module.func_name(FuncIndex::from_u32(0)).as_str(), "dup");
+    assert_eq!(module.func_name(FuncIndex::from_u32(1)).as_str(), "dup_1");
+    assert_eq!(module.func_name(FuncIndex::from_u32(2)).as_str(), "other");
+    assert_eq!(module.global_name(GlobalIndex::from_u32(0)).as_str(), "counter");
+    assert_eq!(module.global_name(GlobalIndex::from_u32(1)).as_str(), "counter_1");
+}
@@ -1,3 +1,5 @@
+use alloc::sync::Arc;
+
 use midenc_hir::{
     CallConv, FunctionType, FxHashMap, SymbolNameComponent, SymbolPath, Visibility,
     diagnostics::WrapErr,
@@ -5,22 +7,42 @@ use midenc_hir::{
     interner::Symbol,
     smallvec,
 };
-use midenc_session::diagnostics::{DiagnosticsHandler, Severity};
+use midenc_session::{
+    FloatMode,
+    diagnostics::{DiagnosticsHandler, Severity},
+};
 
-use super::{FuncIndex, Module, instance::ModuleArgument, ir_func_type, types::ModuleTypesBuilder};
+use super::{
+    FuncIndex, Module,
+    function_table::FunctionTable,
+    instance::ModuleArgument,
+    ir_func_type,
+    types::{EntityIndex, ModuleTypesBuilder, TableIndex},
+};
 use crate::{
+    WasmTranslationConfig,
     callable::CallableFunction,
-    component::lower_imports::generate_import_lowering_function,
+    component::{byte_lists::has_byte_lists, lower_imports::generate_import_lowering_function},
     error::WasmResult,
     intrinsics::{Intrinsic, IntrinsicsConversionResult, attach_effects_to_function},
+    miden_abi::{
+        import_map::{ImportMap, MappedImport},
+        transform::TransformStrategy,
+    },
     translation_utils::sig_from_func_type,
 };
 
 pub struct ModuleTranslationState<'a> {
     /// Imported and local functions
     functions: FxHashMap<FuncIndex, CallableFunction>,
+    /// The contents of the tables used by `call_indirect`, computed on first use
+    tables: FxHashMap<TableIndex, FunctionTable>,
     pub module_builder: &'a mut ModuleBuilder,
     pub world_builder: &'a mut WorldBuilder,
+    /// How floating-point operations are translated
+    pub float_mode: FloatMode,
+    /// The Miden Assembly procedures that linker stubs are lowered to
+    pub import_map: Arc<ImportMap>,
 }
 
 impl<'a> ModuleTranslationState<'a> {
@@ -32,32 +54,44 @@ impl<'a> ModuleTranslationState<'a> {
     /// `world_builder` - the Miden IR World builder
     /// `mod_types` - the Miden IR module types builder
     /// `module_args` - the module instantiation arguments, i.e. entities to "fill" module imports
+    /// `config` - the translation configuration, see [WasmTranslationConfig]
     pub fn new(
         module: &Module,
         module_builder: &'a mut ModuleBuilder,
         world_builder: &'a mut WorldBuilder,
         mod_types: &ModuleTypesBuilder,
         module_args: FxHashMap<SymbolPath, ModuleArgument>,
+        config: &WasmTranslationConfig,
         diagnostics: &DiagnosticsHandler,
     ) -> WasmResult<Self> {
         let mut functions = FxHashMap::default();
-        for (index, func_type) in &module.functions {
-            let wasm_func_type = mod_types[func_type.signature].clone();
-            let ir_func_type = ir_func_type(&wasm_func_type, diagnostics)?;
-            let func_name = module.func_name(index);
-            let path = SymbolPath {
-                path: smallvec![
-                    SymbolNameComponent::Root,
-                    SymbolNameComponent::Component(module.name().as_symbol()),
-                    SymbolNameComponent::Leaf(func_name)
-                ],
-            };
-            let visibility = if module.is_exported(index.into()) {
-                Visibility::Public
-            } else {
-                Visibility::Private
-            };
-            let sig = sig_from_func_type(&ir_func_type, CallConv::C);
+        // The imports passing a `string` or `list<u8>` allocate the bytes they receive with
+        // `cabi_realloc`, which is defined after them, as imported functions come first
+        let realloc = match module.exports.get("cabi_realloc") {
+            Some(EntityIndex::Function(index)) if needs_realloc(&module_args) => {
+                let (path, sig) =
+                    function_path_and_signature(module, *index, mod_types, diagnostics)?;
+                let func = define_local_function(
+                    module_builder,
+                    path,
+                    Visibility::Public,
+                    sig,
+                    diagnostics,
+                )?;
+                let CallableFunction::Function { function_ref, .. } = &func else {
+                    unreachable!("expected a function")
+                };
+                let realloc = *function_ref;
+                functions.insert(*index, func);
+                Some(realloc)
+            }
+            _ => None,
+        };
+        for index in module.functions.keys() {
+            if functions.contains_key(&index) {
+                continue;
+            }
+            let (path, sig) = function_path_and_signature(module, index, mod_types, diagnostics)?;
             if module.is_imported_function(index) {
                 assert!((index.as_u32() as usize) < module.num_imported_funcs);
                 let import = &module.imports[index.as_u32() as usize];
@@ -68,33 +102,29 @@ impl<'a> ModuleTranslationState<'a> {
                     path,
                     sig,
                     import,
+                    realloc,
+                    &config.import_map,
                     diagnostics,
                 )?;
                 functions.insert(index, func);
             } else {
-                let function_ref = module_builder
-                    .define_function(path.name().into(), visibility, sig.clone())
-                    .map_err(|e| {
-                        diagnostics
-                            .diagnostic(Severity::Error)
-                            .with_message(format!(
-                                "Failed to add new function '{}' to module: {e:?}",
-                                path.name()
-                            ))
-                            .into_report()
-                    })?;
-                let defined_function = CallableFunction::Function {
-                    wasm_id: path,
-                    function_ref,
-                    signature: sig.clone(),
+                let visibility = if module.is_exported(index.into()) {
+                    Visibility::Public
+                } else {
+                    Visibility::Private
                 };
+                let defined_function =
+                    define_local_function(module_builder, path, visibility, sig, diagnostics)?;
                 functions.insert(index, defined_function);
             };
         }
         Ok(Self {
             functions,
+            tables: FxHashMap::default(),
             module_builder,
             world_builder,
+            float_mode: config.float_mode,
+            import_map: Arc::clone(&config.import_map),
         })
     }
 
@@ -104,6 +134,20 @@ impl<'a> ModuleTranslationState<'a> {
         Ok(defined_func)
     }
 
+    /// Get the contents of the table `index` of `module`, for the translation of `call_indirect`
+    pub(crate) fn function_table(
+        &mut self,
+        module: &Module,
+        index: TableIndex,
+        diagnostics: &DiagnosticsHandler,
+    ) -> WasmResult<&FunctionTable> {
+        if !self.tables.contains_key(&index) {
+            let table = FunctionTable::build(module, index, diagnostics)?;
+            self.tables.insert(index, table);
+        }
+        Ok(&self.tables[&index])
+    }
+
     /// Register a linker stub function as an intrinsic so that calls to it will be inlined.
     ///
     /// This updates the function's entry in the functions map from `CallableFunction::Function`
@@ -182,7 +226,65 @@ impl<'a> ModuleTranslationState<'a> {
     }
 }
 
+/// Returns the path and the signature of the function `index` of the core Wasm `module`
+fn function_path_and_signature(
+    module: &Module,
+    index: FuncIndex,
+    mod_types: &ModuleTypesBuilder,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<(SymbolPath, Signature)> {
+    let func_type = &module.functions[index];
+    let wasm_func_type = mod_types[func_type.signature].clone();
+    let ir_func_type = ir_func_type(&wasm_func_type, diagnostics)?;
+    let func_name = module.func_name(index);
+    let path = SymbolPath {
+        path: smallvec![
+            SymbolNameComponent::Root,
+            SymbolNameComponent::Component(module.name().as_symbol()),
+            SymbolNameComponent::Leaf(func_name)
+        ],
+    };
+    Ok((path, sig_from_func_type(&ir_func_type, CallConv::C)))
+}
+
+/// Returns [`CallableFunction`] for the function defined by the core Wasm module at `path`
+fn define_local_function(
+    module_builder: &mut ModuleBuilder,
+    path: SymbolPath,
+    visibility: Visibility,
+    sig: Signature,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<CallableFunction> {
+    let function_ref = module_builder
+        .define_function(path.name().into(), visibility, sig.clone())
+        .map_err(|e| {
+            diagnostics
+                .diagnostic(Severity::Error)
+                .with_message(format!(
+                    "Failed to add new function '{}' to module: {e:?}",
+                    path.name()
+                ))
+                .into_report()
+        })?;
+    Ok(CallableFunction::Function {
+        wasm_id: path,
+        function_ref,
+        signature: sig,
+    })
+}
+
+/// Returns true if a component import among `module_args` passes a `string` or `list<u8>`
+fn needs_realloc(module_args: &FxHashMap<SymbolPath, ModuleArgument>) -> bool {
+    module_args
+        .values()
+        .any(|arg| matches!(arg, ModuleArgument::ComponentImport(ty) if has_byte_lists(&ty.ir)))
+}
+
 /// Returns [`CallableFunction`] translated from the core Wasm module import
+///
+/// `realloc` is the `cabi_realloc` function of the module, used to allocate the bytes of a
+/// `string` or `list<u8>` returned by a component import.
+#[allow(clippy::too_many_arguments)]
 fn process_import(
     module_builder: &mut ModuleBuilder,
     world_builder: &mut WorldBuilder,
@@ -190,6 +292,8 @@ fn process_import(
     core_func_id: SymbolPath,
     core_func_sig: Signature,
     import: &super::ModuleImport,
+    realloc: Option<FunctionRef>,
+    import_map: &ImportMap,
     diagnostics: &DiagnosticsHandler,
 ) -> WasmResult<CallableFunction> {
     let import_path = SymbolPath {
@@ -199,6 +303,24 @@ fn process_import(
             SymbolNameComponent::Leaf(Symbol::intern(&import.field))
         ],
     };
+    if import.module == crate::intrinsics::INLINE_ASM_MODULE {
+        return Ok(CallableFunction::InlineAsm {
+            wasm_id: import_path,
+            signature: core_func_sig,
+        });
+    }
+    // The procedures exported by linked libraries are imported from the module of the procedure
+    if let Some(mapped) = import_map.get_import(&import.module, &import.field)
+        && mapped.strategy == TransformStrategy::NoTransform
+    {
+        return process_library_import(
+            world_builder,
+            core_func_id,
+            core_func_sig,
+            mapped,
+            diagnostics,
+        );
+    }
     let Some(module_arg) = module_args.get(&import_path) else {
         crate::unsupported_diag!(diagnostics, "unexpected import '{import_path:?}'");
     };
@@ -209,9 +331,57 @@ fn process_import(
         core_func_sig,
         import_path,
         module_arg,
+        realloc,
     )
 }
 
+/// Returns [`CallableFunction`] calling the procedure of a linked library that `mapped` refers to
+fn process_library_import(
+    world_builder: &mut WorldBuilder,
+    core_func_id: SymbolPath,
+    core_func_sig: Signature,
+    mapped: &MappedImport,
+    diagnostics: &DiagnosticsHandler,
+) -> WasmResult<CallableFunction> {
+    let params = &mapped.signature.params;
+    let results = &mapped.signature.results;
+    if params.len() != core_func_sig.params().len()
+        || results.len() != core_func_sig.results().len()
+    {
+        return Err(diagnostics
+            .diagnostic(Severity::Error)
+            .with_message(format!(
+                "the signature of the import '{}' does not match that of the procedure '{}': \
+                 expected {} parameter(s) and {} result(s)",
+                core_func_id.name(),
+                mapped.procedure,
+                params.len(),
+                results.len()
+            ))
+            .into_report());
+    }
+
+    let context = world_builder.context_rc();
+    let signature = Signature::new(&context, params.iter().cloned(), results.iter().cloned());
+    let import_module_ref = world_builder
+        .declare_module_tree(&mapped.procedure.without_leaf())
+        .wrap_err("failed to create module for library imports")?;
+    let mut import_module_builder = ModuleBuilder::new(import_module_ref);
+    let name = mapped.procedure.name();
+    // Several functions may import the same procedure
+    let function_ref = match import_module_builder.get_function(name.as_str()) {
+        Some(function_ref) => function_ref,
+        None => import_module_builder
+            .define_function(name.into(), Visibility::Public, signature.clone())
+            .wrap_err("failed to create library import function ref")?,
+    };
+    Ok(CallableFunction::Function {
+        wasm_id: core_func_id,
+        function_ref,
+        signature,
+    })
+}
+
 fn process_module_arg(
     module_builder: &mut ModuleBuilder,
     world_builder: &mut WorldBuilder,
@@ -219,6 +389,7 @@ fn process_module_arg(
     sig: Signature,
     wasm_import_path: SymbolPath,
     module_arg: &ModuleArgument,
+    realloc: Option<FunctionRef>,
 ) -> WasmResult<CallableFunction> {
     Ok(match module_arg {
         ModuleArgument::Function(_) => {
@@ -232,6 +403,7 @@ fn process_module_arg(
             signature,
             path,
             sig,
+            realloc,
         )?,
         ModuleArgument::Table => {
             todo!("implement the table import module arguments")
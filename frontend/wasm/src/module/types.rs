@@ -348,7 +348,8 @@ impl GlobalInit {
             GlobalInit::I32Const(x) => x.into(),
             GlobalInit::I64Const(x) => x.into(),
             GlobalInit::F32Const(x) => x.into(),
-            GlobalInit::F64Const(x) => x.into(),
+            // `f64` values are represented by their bit pattern, as `i64`
+            GlobalInit::F64Const(x) => (x as i64).into(),
             GlobalInit::V128Const(x) => x.into(),
             GlobalInit::GetGlobal(global_idx) => {
                 let global_init = module.try_global_initializer(global_idx, diagnostics)?;
@@ -606,7 +607,9 @@ pub fn ir_type(ty: WasmType, diagnostics: &DiagnosticsHandler) -> WasmResult<hir
         WasmType::I32 => hir::Type::I32,
         WasmType::I64 => hir::Type::I64,
         WasmType::F32 => hir::Type::Felt,
-        ty @ (WasmType::F64 | WasmType::V128 | WasmType::Ref(_)) => {
+        // `f64` values are represented by their bit pattern, see `FloatMode`
+        WasmType::F64 => hir::Type::I64,
+        ty @ (WasmType::V128 | WasmType::Ref(_)) => {
             unsupported_diag!(diagnostics, "wasm error: unsupported type '{}'", ty)
         }
     })
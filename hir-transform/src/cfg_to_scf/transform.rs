@@ -197,24 +197,24 @@ impl<'a> TransformationContext<'a> {
     fn combine_exit(&mut self, mut return_like_op_ref: OperationRef) -> Result<(), Report> {
         use midenc_hir::hashbrown::hash_map::Entry;
 
-        log::trace!(target: "cfg-to-scf", "combining exit for {}", return_like_op_ref.borrow());
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "combining exit for {}", return_like_op_ref.borrow());
         let key = ReturnLikeOpKey(return_like_op_ref);
         match self.return_like_to_combined_exit.entry(key) {
             Entry::Occupied(entry) => {
                 if OperationRef::ptr_eq(&entry.key().0, &return_like_op_ref) {
-                    log::trace!(target: "cfg-to-scf", "exit already combined for {}", return_like_op_ref.borrow());
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf", "exit already combined for {}", return_like_op_ref.borrow());
                     return Ok(());
                 }
 
                 let exit_block = *entry.get();
-                log::trace!(target: "cfg-to-scf", "found equivalent return-like exit in {exit_block}");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "found equivalent return-like exit in {exit_block}");
                 let mut builder = OpBuilder::new(self.context.clone());
                 builder.set_insertion_point_to_end(return_like_op_ref.parent().unwrap());
                 let dummy_value = self.get_switch_value(0);
                 let return_like_op = return_like_op_ref.borrow();
                 let operands = return_like_op.operands().as_value_range().into_owned();
                 let span = return_like_op.span();
-                log::trace!(target: "cfg-to-scf", "creating branch to return-like exit in {exit_block} from {} with operands {operands}", return_like_op.parent().unwrap());
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "creating branch to return-like exit in {exit_block} from {} with operands {operands}", return_like_op.parent().unwrap());
                 let parent_region = return_like_op.parent_region().unwrap();
                 drop(return_like_op);
                 self.interface.create_single_destination_branch(
@@ -227,7 +227,7 @@ impl<'a> TransformationContext<'a> {
 
                 return_like_op_ref.borrow_mut().erase();
 
-                log::trace!(target: "cfg-to-scf", "return-like rewritten: {}", parent_region.borrow().print(self.context.clone(), &Default::default()));
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "return-like rewritten: {}", parent_region.borrow().print(self.context.clone(), &Default::default()));
             }
             Entry::Vacant(entry) => {
                 let mut return_like_op = return_like_op_ref.borrow_mut();
@@ -238,10 +238,10 @@ impl<'a> TransformationContext<'a> {
 
                 let mut builder = OpBuilder::new(self.context.clone());
                 let exit_block = builder.create_block(self.region, None, &args);
-                log::trace!(target: "cfg-to-scf", "no equivalent return-like exit exists yet, created {exit_block} for this purpose");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "no equivalent return-like exit exists yet, created {exit_block} for this purpose");
                 entry.insert(exit_block);
 
-                log::trace!(target: "cfg-to-scf", "creating branch to return-like exit in {exit_block} from {} with operands {operands}", return_like_op_ref.parent().unwrap());
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "creating branch to return-like exit in {exit_block} from {} with operands {operands}", return_like_op_ref.parent().unwrap());
                 builder.set_insertion_point_to_end(return_like_op_ref.parent().unwrap());
                 let dummy_value = self.get_switch_value(0);
                 let span = return_like_op.span();
@@ -253,11 +253,11 @@ impl<'a> TransformationContext<'a> {
                     operands,
                 )?;
 
-                log::trace!(target: "cfg-to-scf", "moving original return-like op to {exit_block}");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "moving original return-like op to {exit_block}");
                 return_like_op.move_to(ProgramPoint::at_end_of(exit_block));
                 let exit_block = exit_block.borrow();
                 let exit_args = exit_block.arguments().as_value_range();
-                log::trace!(target: "cfg-to-scf", "rewriting original return-like op operands to {exit_args}");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "rewriting original return-like op operands to {exit_args}");
                 return_like_op.set_operands(exit_args);
             }
         }
@@ -276,7 +276,7 @@ impl<'a> TransformationContext<'a> {
     ) -> Result<SmallVec<[BlockRef; 4]>, Report> {
         use midenc_hir::cfg::StronglyConnectedComponents;
 
-        log::trace!(
+        midenc_hir::scoped_trace!(
             target: "cfg-to-scf",
             "transforming cycles to structured loops from region entry {region_entry}"
         );
@@ -418,14 +418,14 @@ impl<'a> TransformationContext<'a> {
         &mut self,
         mut region_entry: BlockRef,
     ) -> Result<SmallVec<[BlockRef; 4]>, Report> {
-        log::trace!(
+        midenc_hir::scoped_trace!(
             target: "cfg-to-scf",
             "transforming conditional control flow for region reachable from {region_entry}"
         );
 
         let num_successors = region_entry.borrow().num_successors();
 
-        log::trace!(target: "cfg-to-scf", "{region_entry} has {num_successors} successors");
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "{region_entry} has {num_successors} successors");
 
         // Trivial region.
         if num_successors == 0 {
@@ -504,11 +504,11 @@ impl<'a> TransformationContext<'a> {
                     }
                 }
 
-                log::trace!(target: "cfg-to-scf", "computed region for successor {dest} as [{}]", DisplayValues::new(block_list.iter()));
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "computed region for successor {dest} as [{}]", DisplayValues::new(block_list.iter()));
             }
         }
 
-        log::trace!(target: "cfg-to-scf", "non-continuation blocks: [{}]", DisplayValues::new(not_continuation.iter()));
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "non-continuation blocks: [{}]", DisplayValues::new(not_continuation.iter()));
 
         // Finds all relevant edges and checks the shape of the control flow graph at this point.
         //
@@ -581,7 +581,7 @@ impl<'a> TransformationContext<'a> {
         for (entry_edge, branch_region) in
             SuccessorEdges::new(region_entry).zip(successor_branch_regions.iter_mut())
         {
-            log::trace!(
+            midenc_hir::scoped_trace!(
                 target: "cfg-to-scf",
                 "analyzing branch region for edge {entry_edge}: [{}]",
                 DisplayValues::new(branch_region.iter())
@@ -591,7 +591,7 @@ impl<'a> TransformationContext<'a> {
             // continuation.
             if branch_region.is_empty() {
                 continuation_edges.push(entry_edge);
-                log::trace!(target: "cfg-to-scf", " branch region is empty");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", " branch region is empty");
                 no_successor_has_continuation_edge = false;
                 continue;
             }
@@ -599,7 +599,7 @@ impl<'a> TransformationContext<'a> {
             for block_ref in branch_region.iter() {
                 let block = block_ref.borrow();
                 if is_region_exit_block(&block) {
-                    log::trace!(target: "cfg-to-scf", " {block} is a region exit");
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf", " {block} is a region exit");
                     // If a return-like op is part of the branch region then the continuation no
                     // longer post-dominates the branch region. Add all its incoming edges to edge
                     // list to create the single-exit block for all branch regions.
@@ -614,7 +614,7 @@ impl<'a> TransformationContext<'a> {
                 }
 
                 for edge in SuccessorEdges::new(*block_ref) {
-                    log::trace!(target: "cfg-to-scf",  "analyzing successor edge {edge}");
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf",  "analyzing successor edge {edge}");
                     if not_continuation.contains(&edge.get_successor()) {
                         continue;
                     }
@@ -625,14 +625,14 @@ impl<'a> TransformationContext<'a> {
             }
         }
 
-        log::trace!(
+        midenc_hir::scoped_trace!(
             target: "cfg-to-scf",
             " found continuation edges: [{}]", DisplayValues::new(continuation_edges.iter())
         );
 
         // Case 2: Keep the control flow op but process its successors further.
         if no_successor_has_continuation_edge {
-            log::trace!(target: "cfg-to-scf", " no successor has a continuation edge");
+            midenc_hir::scoped_trace!(target: "cfg-to-scf", " no successor has a continuation edge");
             let term = region_entry.borrow().terminator().unwrap();
             let term = term.borrow();
             return Ok(term.successor_iter().map(|s| s.dest.borrow().successor()).collect());
@@ -656,8 +656,8 @@ impl<'a> TransformationContext<'a> {
             }
         }
 
-        log::trace!(target: "cfg-to-scf", " continuation = {:?}", continuation.map(|c| c.borrow().id()));
-        log::trace!(target: "cfg-to-scf", " continuation_post_dominates_all_regions = {continuation_post_dominates_all_regions}");
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", " continuation = {:?}", continuation.map(|c| c.borrow().id()));
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", " continuation_post_dominates_all_regions = {continuation_post_dominates_all_regions}");
 
         // In Case 3, or if not all continuation edges have the same entry block, create a single
         // entry block as continuation for all branch regions.
@@ -666,7 +666,7 @@ impl<'a> TransformationContext<'a> {
             let span = term.borrow().span();
             let multiplexer = self.create_single_entry_block(span, &continuation_edges)?;
             continuation = Some(multiplexer.get_multiplexer_block());
-            log::trace!(target: "cfg-to-scf", " created new single entry continuation = {}", multiplexer.get_multiplexer_block());
+            midenc_hir::scoped_trace!(target: "cfg-to-scf", " created new single entry continuation = {}", multiplexer.get_multiplexer_block());
         }
 
         // Trigger reprocessing of Case 3 after creating the single entry block.
@@ -929,12 +929,12 @@ impl<'a> TransformationContext<'a> {
                 match dominance_cache.entry(block) {
                     Entry::Occupied(entry) => {
                         let dominates = *entry.get();
-                        log::trace!(target: "cfg-to-scf", "{loop_block_ref} dominates {block}: {dominates}");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "{loop_block_ref} dominates {block}: {dominates}");
                         dominates
                     }
                     Entry::Vacant(entry) => {
                         let dominates = dominance_info.dominates(loop_block_ref, &block);
-                        log::trace!(target: "cfg-to-scf", "{loop_block_ref} dominates {block}: {dominates}");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "{loop_block_ref} dominates {block}: {dominates}");
                         entry.insert(dominates);
                         dominates
                     }
@@ -942,15 +942,15 @@ impl<'a> TransformationContext<'a> {
             };
 
             let mut check_value = |ctx: &mut TransformationContext<'_>, value: ValueRef| {
-                log::trace!(target: "cfg-to-scf", "checking if value {value} escapes loop");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "checking if value {value} escapes loop");
                 let mut block_argument = None;
                 let mut next_use = { value.borrow().uses().front().as_pointer() };
                 while let Some(mut user) = next_use.take() {
                     next_use = user.next();
-                    log::trace!(target: "cfg-to-scf", "  checking use of {value} by {}", user.borrow().owner());
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf", "  checking use of {value} by {}", user.borrow().owner());
 
                     if user.borrow().owner.borrow().implements::<dyn Transparent>() {
-                        log::trace!(target: "cfg-to-scf", "  use is transparent metadata");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "  use is transparent metadata");
                         continue;
                     }
 
@@ -969,10 +969,10 @@ impl<'a> TransformationContext<'a> {
 
                     let curr_block = curr_block.unwrap();
                     if loop_blocks.contains(&curr_block) {
-                        log::trace!(target: "cfg-to-scf", "  use is within loop");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "  use is within loop");
                         continue;
                     }
-                    log::trace!(target: "cfg-to-scf", "  use in {curr_block} escapes loop {}", DisplayValues::new(loop_blocks.iter()));
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf", "  use in {curr_block} escapes loop {}", DisplayValues::new(loop_blocks.iter()));
 
                     // Block argument is only created the first time it is required.
                     if block_argument.is_none() {
@@ -985,11 +985,11 @@ impl<'a> TransformationContext<'a> {
                             value_ty.clone(),
                             span,
                         ));
-                        log::trace!(target: "cfg-to-scf", "introducing block argument to prevent escape of {value}");
-                        log::trace!(target: "cfg-to-scf", "  created block argument {} in user's block", block_argument.unwrap());
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "introducing block argument to prevent escape of {value}");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "  created block argument {} in user's block", block_argument.unwrap());
                         let _loop_header_arg =
                             ctx.context.append_block_argument(loop_header, value_ty.clone(), span);
-                        log::trace!(target: "cfg-to-scf", "  created block argument {_loop_header_arg} in loop header");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "  created block argument {_loop_header_arg} in loop header");
 
                         // `value` might be defined in a block that does not dominate `latch` but
                         // previously dominated an exit block with a use. In this case, add a block
@@ -1003,16 +1003,16 @@ impl<'a> TransformationContext<'a> {
                                 !loop_block_dominates(pred.predecessor(), ctx.dominance_info)
                             })
                         {
-                            log::trace!(target: "cfg-to-scf", "  {argument} is defined in {value_block}, and at least one predecessor of the latch {latch} is not dominated by {loop_block_ref}");
+                            midenc_hir::scoped_trace!(target: "cfg-to-scf", "  {argument} is defined in {value_block}, and at least one predecessor of the latch {latch} is not dominated by {loop_block_ref}");
                             argument =
                                 ctx.context.append_block_argument(latch, value_ty.clone(), span);
-                            log::trace!(target: "cfg-to-scf", "  creating block argument {argument} in latch");
+                            midenc_hir::scoped_trace!(target: "cfg-to-scf", "  creating block argument {argument} in latch");
                             for pred in latch.borrow().predecessors() {
                                 let mut succ_operand = value;
-                                log::trace!(target: "cfg-to-scf", "  initializing predecessor operand for {argument} with {succ_operand}");
+                                midenc_hir::scoped_trace!(target: "cfg-to-scf", "  initializing predecessor operand for {argument} with {succ_operand}");
                                 if !loop_block_dominates(pred.predecessor(), ctx.dominance_info) {
                                     succ_operand = ctx.get_undef_value(&value_ty);
-                                    log::trace!(target: "cfg-to-scf", "  predecessor {} is not dominated by {loop_block_ref}, successor operand changed to {succ_operand}", pred.predecessor());
+                                    midenc_hir::scoped_trace!(target: "cfg-to-scf", "  predecessor {} is not dominated by {loop_block_ref}, successor operand changed to {succ_operand}", pred.predecessor());
                                 }
 
                                 let succ_operand =
@@ -1025,11 +1025,11 @@ impl<'a> TransformationContext<'a> {
                             }
                         }
 
-                        log::trace!(target: "cfg-to-scf", "  appending {argument} to loop header successor operands");
+                        midenc_hir::scoped_trace!(target: "cfg-to-scf", "  appending {argument} to loop header successor operands");
                         loop_header_successor_operands.push(argument);
                         for edge in SuccessorEdges::new(latch) {
                             let mut pred = edge.from_block.borrow().terminator().unwrap();
-                            log::trace!(target: "cfg-to-scf", "  appending {argument} to successor operands of {edge}");
+                            midenc_hir::scoped_trace!(target: "cfg-to-scf", "  appending {argument} to successor operands of {edge}");
                             let operand = ctx.context.make_operand(argument, pred, 0);
                             let mut pred = pred.borrow_mut();
                             let mut succ = pred.successor_mut(edge.successor_index);
@@ -1037,7 +1037,7 @@ impl<'a> TransformationContext<'a> {
                         }
                     }
 
-                    log::trace!(target: "cfg-to-scf", "  setting use of {value} to {}", block_argument.unwrap());
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf", "  setting use of {value} to {}", block_argument.unwrap());
                     user.borrow_mut().set(block_argument.unwrap());
                 }
             };
@@ -1070,7 +1070,7 @@ impl<'a> TransformationContext<'a> {
         // New block arguments may have been added to the loop header. Adjust the entry edges to
         // pass undef values to these.
         let loop_header = loop_header.borrow();
-        log::trace!(target: "cfg-to-scf", "checking that all predecessors of {loop_header} pass {} successor operands", loop_header.num_arguments());
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "checking that all predecessors of {loop_header} pass {} successor operands", loop_header.num_arguments());
         for pred in loop_header.predecessors() {
             // Latch successor arguments have already been handled.
             if pred.predecessor() == latch {
@@ -1081,12 +1081,12 @@ impl<'a> TransformationContext<'a> {
             let mut op = op.borrow_mut();
             let mut succ = op.successor_mut(pred.index as usize);
             if cfg!(debug_assertions) && succ.arguments.len() != loop_header.num_arguments() {
-                log::trace!(target: "cfg-to-scf", "  {} has only {} successor operands", pred.predecessor(), succ.arguments.len());
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "  {} has only {} successor operands", pred.predecessor(), succ.arguments.len());
             }
             succ.arguments
                 .extend(loop_header.arguments().iter().skip(succ.arguments.len()).map(|arg| {
                     let val = self.get_undef_value(arg.borrow().ty());
-                    log::trace!(target: "cfg-to-scf", "  appending {val} to successor operands for missing parameter {arg}");
+                    midenc_hir::scoped_trace!(target: "cfg-to-scf", "  appending {val} to successor operands for missing parameter {arg}");
                     self.context.make_operand(val, pred.owner, 0)
                 }));
         }
@@ -1138,13 +1138,13 @@ impl<'a> TransformationContext<'a> {
         let mut previous_edge_to_continuation = None;
         let mut branch_region_parent = branch_region[0].parent().unwrap();
 
-        log::trace!(target: "cfg-to-scf", "creating single-exit branch region");
-        log::trace!(target: "cfg-to-scf", "  continuation = {continuation}");
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "creating single-exit branch region");
+        midenc_hir::scoped_trace!(target: "cfg-to-scf", "  continuation = {continuation}");
         for mut block_ref in branch_region.iter().copied() {
-            log::trace!(target: "cfg-to-scf", "  processing region block: {block_ref}");
+            midenc_hir::scoped_trace!(target: "cfg-to-scf", "  processing region block: {block_ref}");
             for edge in SuccessorEdges::new(block_ref) {
-                log::trace!(target: "cfg-to-scf", "    processing edge: {} -> {}", edge.from_block, edge.get_successor());
-                log::trace!(target: "cfg-to-scf", "    single-exit block: {single_exit_block:?}");
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "    processing edge: {} -> {}", edge.from_block, edge.get_successor());
+                midenc_hir::scoped_trace!(target: "cfg-to-scf", "    single-exit block: {single_exit_block:?}");
                 if !BlockRef::ptr_eq(&edge.get_successor(), &continuation) {
                     continue;
                 }
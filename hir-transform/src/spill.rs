@@ -624,7 +624,7 @@ fn merge_op_nested_region_uses(
             continue;
         }
         if !region_borrowed.has_one_block() {
-            log::trace!(
+            midenc_hir::scoped_trace!(
                 target: "insert-spills",
                 "skipping multi-block nested region {region} when collecting spill uses"
             );
@@ -3,7 +3,9 @@ use alloc::{format, rc::Rc, vec::Vec};
 use smallvec::SmallVec;
 
 use super::rewriter::ConversionPatternRewriter;
-use crate::{Report, SourceSpan, Type, ValueRef, dialects::builtin::UnrealizedConversionCast};
+use crate::{
+    Report, SourceSpan, Type, ValueRef, dialects::builtin::UnrealizedConversionCast, same_type,
+};
 
 type TypeConversionFn = Rc<dyn Fn(&Type) -> Option<TypeConversion>>;
 type ValueConversionFn = Rc<dyn Fn(ValueRef) -> Option<TypeConversion>>;
@@ -239,7 +241,7 @@ impl TypeConverter {
         span: SourceSpan,
         kind: &'static str,
     ) -> Result<ValueRef, Report> {
-        if *value.borrow().ty() == ty {
+        if same_type(value.borrow().ty(), &ty) {
             return Ok(value);
         }
 
@@ -252,7 +254,7 @@ impl TypeConverter {
         let op = rewriter.create_op::<UnrealizedConversionCast, _>(span, (value, ty.clone()))?;
         rewriter.mark_materialization_op(op.as_operation_ref());
         let result = op.borrow().result().as_value_ref();
-        if *result.borrow().ty() != ty {
+        if !same_type(result.borrow().ty(), &ty) {
             return Err(Report::msg(format!(
                 "{kind} materialization produced type '{}', expected '{ty}'",
                 result.borrow().ty()
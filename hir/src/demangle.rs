@@ -1,4 +1,5 @@
 use alloc::{string::String, vec::Vec};
+use core::fmt;
 
 /// Demangle `name`, where `name` was mangled using Rust's mangling scheme
 #[inline]
@@ -6,11 +7,26 @@ pub fn demangle<S: AsRef<str>>(name: S) -> String {
     demangle_impl(name.as_ref())
 }
 
+/// Displays the wrapped value with any mangled Rust symbols in it demangled, e.g. a procedure path
+/// whose last component is a mangled symbol.
+///
+/// The value is only demangled when displayed, so this can be used in the arguments of an
+/// assertion without any cost unless it fails.
+pub struct Demangled<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Demangled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use alloc::string::ToString;
+
+        f.write_str(&demangle(self.0.to_string()))
+    }
+}
+
 #[cfg(not(feature = "std"))]
 fn demangle_impl(name: &str) -> String {
     use alloc::string::ToString;
 
-    rustc_demangle::demangle(s).as_str().to_string();
+    rustc_demangle::demangle(name).to_string()
 }
 
 #[cfg(feature = "std")]
@@ -281,8 +281,9 @@ impl ConditionallySpeculatable for GlobalSymbol {
 }
 
 impl InferTypeOpInterface for GlobalSymbol {
-    fn infer_return_types(&mut self, _context: &Context) -> Result<(), Report> {
-        self.addr_mut().set_type(Type::from(PointerType::new(Type::U8)));
+    fn infer_return_types(&mut self, context: &Context) -> Result<(), Report> {
+        self.addr_mut()
+            .set_type(context.intern_type(Type::from(PointerType::new(Type::U8))));
         Ok(())
     }
 }
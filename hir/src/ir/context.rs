@@ -10,7 +10,7 @@ use traits::BranchOpInterface;
 
 use super::{traits::BuildableTypeConstraint, *};
 use crate::{
-    AttributeRef, AttributeRegistration, FxHashMap,
+    AttributeRef, AttributeRegistration, FxHashMap, FxHashSet,
     attributes::{AttributeName, DerivableTypeAttribute, Marker},
     constants::{ConstantData, ConstantId, ConstantPool},
 };
@@ -22,6 +22,7 @@ use crate::{
 /// * Provide storage/memory for all allocated IR entities for the lifetime of the session.
 /// * Provide unique value and block identifiers for printing the IR
 /// * Provide a uniqued constant pool
+/// * Provide canonical, shared instances of aggregate types, see [Context::intern_type]
 /// * Provide configuration used during compilation
 ///
 /// # Safety
@@ -33,6 +34,7 @@ pub struct Context {
     registered_dialects: RefCell<FxHashMap<interner::Symbol, Rc<dyn Dialect>>>,
     constants: RefCell<ConstantPool>,
     type_cache: RefCell<FxHashMap<core::any::TypeId, Arc<Type>>>,
+    interned_types: RefCell<FxHashSet<Type>>,
     uniqued_attr_cache: RefCell<FxHashMap<AttributeName, AttributeRef>>,
     next_block_id: Cell<u32>,
     next_value_id: Cell<u32>,
@@ -83,6 +85,7 @@ impl Context {
             registered_dialects: RefCell::new(registered_dialects),
             constants: Default::default(),
             type_cache: Default::default(),
+            interned_types: Default::default(),
             uniqued_attr_cache: Default::default(),
             next_block_id: Cell::new(0),
             next_value_id: Cell::new(0),
@@ -205,6 +208,30 @@ impl Context {
         }
     }
 
+    /// Returns the canonical instance of `ty`, interning it if no equal type was interned before.
+    ///
+    /// Aggregate types, i.e. pointers, structs, arrays, lists and functions, are reference-counted,
+    /// so constructing the same type twice allocates it twice, and comparing the two requires a
+    /// deep comparison. Interned types which are equal share the same allocation instead, so
+    /// comparing them with [crate::same_type] only requires comparing pointers.
+    ///
+    /// Primitive types are returned as-is, as there is nothing to share.
+    pub fn intern_type(&self, ty: Type) -> Type {
+        if !matches!(
+            ty,
+            Type::Ptr(_) | Type::Struct(_) | Type::Array(_) | Type::List(_) | Type::Function(_)
+        ) {
+            return ty;
+        }
+
+        let mut interned = self.interned_types.borrow_mut();
+        if let Some(canonical) = interned.get(&ty) {
+            return canonical.clone();
+        }
+        interned.insert(ty.clone());
+        ty
+    }
+
     /// Get a new [OpBuilder] for this context
     pub fn builder(self: Rc<Self>) -> OpBuilder {
         OpBuilder::new(Rc::clone(&self))
@@ -490,3 +517,51 @@ impl<T: AttributeRegistration + Marker> UniquedAttribute for T {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use super::*;
+    use crate::{PointerType, StructType};
+
+    fn struct_ty() -> Type {
+        Type::from(StructType::new([Type::U8, Type::U32, Type::Felt]))
+    }
+
+    #[test]
+    fn interned_types_are_shared() {
+        let context = Context::default();
+
+        let (a, b) = (struct_ty(), struct_ty());
+        let (Type::Struct(a_struct), Type::Struct(b_struct)) = (&a, &b) else {
+            unreachable!()
+        };
+        assert!(!Arc::ptr_eq(a_struct, b_struct));
+
+        let a = context.intern_type(a);
+        let b = context.intern_type(b);
+        let (Type::Struct(a_struct), Type::Struct(b_struct)) = (&a, &b) else {
+            unreachable!()
+        };
+        assert!(Arc::ptr_eq(a_struct, b_struct));
+        assert_eq!(a, b);
+        assert!(crate::same_type(&a, &b));
+    }
+
+    #[test]
+    fn interning_distinguishes_types() {
+        let context = Context::default();
+
+        let byte_ptr = context.intern_type(Type::from(PointerType::new(struct_ty())));
+        let felt_ptr = context.intern_type(Type::from(PointerType::new_with_address_space(
+            struct_ty(),
+            crate::AddressSpace::Element,
+        )));
+        assert_ne!(byte_ptr, felt_ptr);
+        assert!(!crate::same_type(&byte_ptr, &felt_ptr));
+        assert!(crate::same_type(&byte_ptr, &Type::from(PointerType::new(struct_ty()))));
+        assert_eq!(context.intern_type(Type::from(PointerType::new(struct_ty()))), byte_ptr);
+        assert_eq!(context.intern_type(Type::U32), Type::U32);
+    }
+}
@@ -4,7 +4,7 @@ use bitflags::bitflags;
 use smallvec::SmallVec;
 
 use super::Operation;
-use crate::{OpOperand, Region, Value, ValueRef, traits::Commutative};
+use crate::{OpOperand, Region, Value, ValueRef, same_type, traits::Commutative};
 
 bitflags! {
     #[derive(Copy, Clone)]
@@ -164,7 +164,7 @@ pub struct ValueTypeEquivalence;
 
 impl ValueEquivalence for ValueTypeEquivalence {
     fn is_equivalent(&self, lhs: &dyn Value, rhs: &dyn Value) -> bool {
-        lhs.ty() == rhs.ty()
+        same_type(lhs.ty(), rhs.ty())
     }
 }
 
@@ -296,7 +296,7 @@ impl Operation {
         {
             let lhs_r = lhs_r.borrow();
             let rhs_r = rhs_r.borrow();
-            if lhs_r.ty() != rhs_r.ty() {
+            if !same_type(lhs_r.ty(), rhs_r.ty()) {
                 return false;
             }
         }
@@ -225,6 +225,37 @@ fn parse_source_generic(
         .map_err(|err| Report::from(err).with_source_code(source_file.clone()))
 }
 
+/// Parse a [Type] from `source`, in the format used to print types in IR assembly, e.g.
+/// `ptr<struct<u32, felt>, byte>`, with the provided `uri` and `config`
+///
+/// The type must span the entire input. On failure, the labels of the returned [Report] give the
+/// byte offset in `source` at which the error occurred.
+pub fn parse_type(
+    config: ParserConfig,
+    uri: Uri,
+    source: impl Into<String>,
+) -> Result<Type, Report> {
+    use midenc_session::diagnostics::SourceLanguage;
+    let source_manager = &config.context.session().source_manager;
+    let source_file = source_manager.load(SourceLanguage::Other("hir"), uri, source.into());
+
+    let scanner = Scanner::new(source_file.as_str());
+    let token_stream = TokenStream::new(source_file.id(), scanner);
+    let mut parser = DefaultParser::new(ParserState::new(config, token_stream));
+    let result = parser.parse_type().and_then(|ty| match parser.token_stream_mut().next()? {
+        None => Ok(ty.into_inner()),
+        Some(next) => {
+            let (span, token) = next.into_parts();
+            Err(ParserError::UnexpectedToken {
+                span,
+                token: token.to_string(),
+                expected: Some("end of input".to_string()),
+            })
+        }
+    });
+    result.map_err(|err| Report::from(err).with_source_code(source_file.clone()))
+}
+
 /// Parse an operation of type `T` from `source` with the provided `uri` and `config`
 pub fn parse<T: OpParser + OpRegistration>(
     config: ParserConfig,
@@ -708,7 +708,9 @@ pub trait Parser<'input> {
             });
         };
 
-        Ok(ty)
+        // Identical aggregate types are frequently repeated in IR, so share their allocations
+        let (span, ty) = ty.into_parts();
+        Ok(Span::new(span, self.context().intern_type(ty)))
     }
 
     fn parse_optional_type(&mut self) -> ParseResult<Option<Span<Type>>> {
@@ -717,56 +719,36 @@ pub trait Parser<'input> {
             return Ok(Some(Span::new(start, Type::Unknown)));
         }
 
-        if let Some(ty) = self.token_stream_mut().next_if_map(|tok| match tok {
-            Token::I1 => Some(Type::I1),
-            Token::I8 => Some(Type::I8),
-            Token::U8 => Some(Type::U8),
-            Token::I16 => Some(Type::I16),
-            Token::U16 => Some(Type::U16),
-            Token::I32 => Some(Type::I32),
-            Token::U32 => Some(Type::U32),
-            Token::I64 => Some(Type::I64),
-            Token::U64 => Some(Type::U64),
-            Token::I128 => Some(Type::I128),
-            Token::U128 => Some(Type::U128),
-            Token::Felt => Some(Type::Felt),
-            _ => None,
-        })? {
-            return Ok(Some(ty));
-        }
-
-        let start = self.current_location();
-        if self.token_stream_mut().next_if_eq(Token::Ptr)? {
-            self.parse_langle()?;
-            let pointee = self.parse_type()?;
-            self.parse_comma()?;
-            let addrspace =
-                self.token_stream_mut().expect_map("one of 'byte' or 'felt'", |tok| match tok {
-                    Token::Byte => Some(AddressSpace::Byte),
-                    Token::Element | Token::Felt => Some(AddressSpace::Element),
-                    _ => None,
-                })?;
-            self.parse_rangle()?;
-            let end = self.current_location().end();
-            let span = SourceSpan::new(start.source_id(), start.start()..end);
-            return Ok(Some(Span::new(
-                span,
-                Type::Ptr(
-                    PointerType::new_with_address_space(
-                        pointee.into_inner(),
-                        addrspace.into_inner(),
-                    )
-                    .into(),
-                ),
-            )));
+        if self.token_stream_mut().is_next(|tok| {
+            tok.as_type().is_some()
+                || matches!(
+                    tok,
+                    Token::Never | Token::Ptr | Token::Array | Token::List | Token::Struct
+                )
+        }) {
+            return self.parse_non_function_type().map(Some);
         }
 
-        if self.token_stream_mut().next_if_eq(Token::Struct)? {
-            todo!()
+        if self.token_stream_mut().is_next(|tok| matches!(tok, Token::BareIdent("extern"))) {
+            let (span, ty) = self.parse_function_type()?.into_parts();
+            return Ok(Some(Span::new(span, Type::Function(Arc::new(ty)))));
         }
 
-        if self.token_stream_mut().next_if_eq(Token::Array)? {
-            todo!()
+        // A parenthesized type list is either the parameters of a function type, or a tuple type
+        if self.token_stream_mut().is_next(|tok| matches!(tok, Token::Lparen)) {
+            let start = self.token_stream().current_position();
+            let mut params = SmallVec::<[Type; 4]>::default();
+            self.parse_type_list(&mut params)?;
+            let ty = if self.token_stream_mut().is_next(|tok| matches!(tok, Token::Rstab)) {
+                let mut results = SmallVec::<[Type; 4]>::default();
+                self.parse_arrow_type_list(&mut results)?;
+                Type::Function(Arc::new(FunctionType::new(CallConv::default(), params, results)))
+            } else {
+                Type::Struct(Arc::new(StructType::new(params)))
+            };
+            let end = self.token_stream().current_span();
+            let span = SourceSpan::new(end.source_id(), start..end.end());
+            return Ok(Some(Span::new(span, ty)));
         }
 
         Ok(None)
@@ -1021,6 +1003,22 @@ pub trait Parser<'input> {
             return Ok(tok);
         }
 
+        let start = self.current_location();
+        if self.token_stream_mut().next_if_eq(Token::Never)? {
+            return Ok(Span::new(start, Type::Never));
+        }
+
+        if self.token_stream_mut().next_if_eq(Token::List)? {
+            let start = self.token_stream().current_position();
+            self.parse_langle()?;
+            let element_ty = self.parse_type()?;
+            self.parse_rangle()?;
+            let end = self.token_stream().current_span();
+
+            let span = SourceSpan::new(end.source_id(), start..end.end());
+            return Ok(Span::new(span, Type::List(Arc::new(element_ty.into_inner()))));
+        }
+
         if self.token_stream_mut().next_if_eq(Token::Ptr)? {
             let start = self.token_stream().current_position();
             self.parse_langle()?;
@@ -1106,7 +1104,6 @@ pub trait Parser<'input> {
                                 });
                             };
                             self.parse_rparen()?;
-                            self.parse_semicolon()?;
                             crate::TypeRepr::Packed(alignment)
                         }
                         _ => unreachable!(),
@@ -1116,10 +1113,11 @@ pub trait Parser<'input> {
                 }
             };
 
+            // NOTE: The closing `>` is consumed along with the fields
             let mut fields = SmallVec::<[Type; 4]>::default();
             self.parse_comma_separated_list_until(
                 Token::Rangle,
-                /*allow_empty=*/ false,
+                /*allow_empty=*/ true,
                 |parser| {
                     let ty = parser.parse_type()?.into_inner();
                     if parser.token_stream_mut().next_if_eq(Token::BareIdent("align"))? {
@@ -1132,7 +1130,6 @@ pub trait Parser<'input> {
                 },
             )?;
 
-            self.parse_rangle()?;
             let end = self.token_stream().current_span();
 
             let span = SourceSpan::new(end.source_id(), start..end.end());
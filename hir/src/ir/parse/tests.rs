@@ -1,12 +1,16 @@
-use alloc::{format, rc::Rc, string::ToString};
-use core::ops::{Deref, DerefMut};
+use alloc::{format, rc::Rc, string::ToString, sync::Arc, vec, vec::Vec};
+use core::{
+    num::NonZeroU16,
+    ops::{Deref, DerefMut},
+};
 
 use litcheck_filecheck::{filecheck, litcheck};
 use pretty_assertions::assert_eq;
 
 use crate::{
-    BuilderExt, CallConv, Context, FunctionType, Immediate, OpParser, OpRegistration, OperationRef,
-    Symbol, SymbolTable, Type, UnsafeIntrusiveEntityRef, ValueRef, Visibility,
+    AddressSpace, ArrayType, BuilderExt, CallConv, Context, FunctionType, Immediate, OpParser,
+    OpRegistration, OperationRef, PointerType, StructType, Symbol, SymbolTable, Type, TypeRepr,
+    UnsafeIntrusiveEntityRef, ValueRef, Visibility,
     attributes::IntegerLikeAttr,
     diagnostics::{Report, SourceSpan, Uri},
     dialects::builtin::{
@@ -14,7 +18,7 @@ use crate::{
         attributes::{AbiParam, Signature},
     },
     parse::{self, ParseResult, ParserConfig},
-    print::AsmPrinter,
+    print::{AsmPrinter, TypePrinter},
     testing::Test,
 };
 
@@ -188,6 +192,99 @@ builtin.function public extern(\"C\") @retconst() -> u8 {
     Ok(())
 }
 
+/// Generate a corpus of types covering every kind of type that can be printed, nested up to
+/// `depth` levels deep
+fn type_corpus(depth: usize) -> Vec<Type> {
+    let mut corpus = vec![
+        Type::Unknown,
+        Type::Never,
+        Type::I1,
+        Type::I8,
+        Type::U8,
+        Type::I16,
+        Type::U16,
+        Type::I32,
+        Type::U32,
+        Type::I64,
+        Type::U64,
+        Type::I128,
+        Type::U128,
+        Type::U256,
+        Type::F64,
+        Type::Felt,
+    ];
+    if depth == 0 {
+        return corpus;
+    }
+
+    // Limit the element types used at each level of nesting to keep the corpus a reasonable size
+    let elements = type_corpus(depth - 1).into_iter().step_by(depth * 2 - 1).collect::<Vec<_>>();
+    for ty in elements.iter() {
+        for addrspace in [AddressSpace::Byte, AddressSpace::Element] {
+            corpus.push(Type::Ptr(Arc::new(PointerType::new_with_address_space(
+                ty.clone(),
+                addrspace,
+            ))));
+        }
+        corpus.push(Type::List(Arc::new(ty.clone())));
+        corpus.push(Type::Function(Arc::new(FunctionType::new(
+            CallConv::default(),
+            [ty.clone(), Type::Felt],
+            [ty.clone()],
+        ))));
+        // Aggregates require their elements to have a known layout
+        if !matches!(ty, Type::Unknown | Type::Never) {
+            corpus.push(Type::Array(Arc::new(ArrayType::new(ty.clone(), 3))));
+            corpus.push(Type::Struct(Arc::new(StructType::new([ty.clone()]))));
+            corpus.push(Type::Struct(Arc::new(StructType::new([Type::U8, ty.clone(), Type::U32]))));
+        }
+    }
+    corpus.push(Type::Struct(Arc::new(StructType::new([]))));
+    corpus.push(Type::Function(Arc::new(FunctionType::new(CallConv::default(), [], []))));
+    corpus.push(Type::Function(Arc::new(FunctionType::new(
+        CallConv::default(),
+        [],
+        [Type::U32, Type::Felt],
+    ))));
+    for repr in [
+        TypeRepr::Align(NonZeroU16::new(16).unwrap()),
+        TypeRepr::Packed(NonZeroU16::new(1).unwrap()),
+    ] {
+        corpus.push(Type::Struct(Arc::new(StructType::new_with_repr(
+            repr,
+            [Type::U8, Type::U32, Type::U64],
+        ))));
+    }
+    corpus
+}
+
+#[test]
+fn parse_type_roundtrip() -> TestResult {
+    let test = ParserTest::default();
+
+    let corpus = type_corpus(2);
+    for ty in corpus.iter() {
+        let printed = TypePrinter(ty).to_string();
+        let parsed = test.parse_type(&printed)?;
+        assert_eq!(&parsed, ty, "type did not round-trip: {printed}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_type_errors_report_offset() {
+    let test = ParserTest::default();
+
+    let offset = |source: &str| {
+        let err = test.parse_type(source).expect_err("expected parsing to fail");
+        err.labels().and_then(|mut labels| labels.next()).map(|label| label.offset())
+    };
+
+    assert_eq!(offset("ptr<u32, word>"), Some(9));
+    assert_eq!(offset("struct<u32, felt> u8"), Some(18));
+}
+
 #[derive(Default)]
 struct ParserTest {
     test: Test,
@@ -227,4 +324,9 @@ impl ParserTest {
         let config = ParserConfig::new(self.test.context_rc());
         parse::parse_any(config, Uri::new(name), source)
     }
+
+    pub fn parse_type(&self, source: &str) -> TestResult<Type> {
+        let config = ParserConfig::new(self.test.context_rc());
+        parse::parse_type(config, Uri::new("type.hir"), source)
+    }
 }
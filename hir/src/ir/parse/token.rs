@@ -55,6 +55,8 @@ pub enum Token<'input> {
     U64,
     I128,
     U128,
+    U256,
+    F64,
     Felt,
     Never,
     Ptr,
@@ -156,6 +158,8 @@ impl Token<'_> {
             Self::U64 => CompactString::const_new("u64"),
             Self::I128 => CompactString::const_new("i128"),
             Self::U128 => CompactString::const_new("u128"),
+            Self::U256 => CompactString::const_new("u256"),
+            Self::F64 => CompactString::const_new("f64"),
             Self::Felt => CompactString::const_new("felt"),
             Self::Never => CompactString::const_new("never"),
             Self::Ptr => CompactString::const_new("ptr"),
@@ -227,6 +231,8 @@ impl fmt::Display for Token<'_> {
             Self::U64 => f.write_str("u64"),
             Self::I128 => f.write_str("i128"),
             Self::U128 => f.write_str("u128"),
+            Self::U256 => f.write_str("u256"),
+            Self::F64 => f.write_str("f64"),
             Self::Felt => f.write_str("felt"),
             Self::Never => f.write_str("never"),
             Self::Ptr => f.write_str("ptr"),
@@ -287,7 +293,10 @@ impl<'input> Token<'input> {
                 | Token::U32
                 | Token::U64
                 | Token::U128
+                | Token::U256
+                | Token::F64
                 | Token::Felt
+                | Token::Never
                 | Token::Ptr
                 | Token::Array
                 | Token::List
@@ -308,6 +317,8 @@ impl<'input> Token<'input> {
             Token::U32 => Some(Type::U32),
             Token::U64 => Some(Type::U64),
             Token::U128 => Some(Type::U128),
+            Token::U256 => Some(Type::U256),
+            Token::F64 => Some(Type::F64),
             Token::Felt => Some(Type::Felt),
             _ => None,
         }
@@ -351,7 +362,10 @@ impl<'input> Token<'input> {
             "u64" => Self::U64,
             "i128" => Self::I128,
             "u128" => Self::U128,
+            "u256" => Self::U256,
+            "f64" => Self::F64,
             "felt" => Self::Felt,
+            "never" => Self::Never,
             "ptr" => Self::Ptr,
             "struct" => Self::Struct,
             "array" => Self::Array,
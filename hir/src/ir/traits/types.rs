@@ -6,7 +6,7 @@ use midenc_session::diagnostics::Severity;
 
 use crate::{
     CompactString, Context, Op, Operation, Report, Type, ValueRef, derive::operation_trait,
-    ir::value::Value,
+    ir::value::Value, same_type,
 };
 
 /// OpInterface to compute the return type(s) of an operation.
@@ -179,7 +179,7 @@ pub trait SameTypeOperands {
             let operand = operand.borrow();
             let value = operand.value();
             let value_ty = value.ty();
-            if value_ty != &expected_ty {
+            if !same_type(value_ty, &expected_ty) {
                 return Err(context
                     .session()
                     .diagnostics
@@ -248,7 +248,7 @@ pub trait SameOperandsAndResultType: SameTypeOperands {
             let value = result.as_value_ref().borrow();
             let result_ty = result.ty();
 
-            if result_ty != &expected_ty {
+            if !same_type(result_ty, &expected_ty) {
                 return Err(context
                     .session()
                     .diagnostics
@@ -588,7 +588,8 @@ impl<T: TypeConstraint> TypeConstraint for PointerOf<T> {
 }
 impl<T: BuildableTypeConstraint> BuildableTypeConstraint for PointerOf<T> {
     fn build(context: &Context) -> crate::Type {
-        crate::Type::from(PointerType::new(<T as BuildableTypeConstraint>::build(context)))
+        let pointee = <T as BuildableTypeConstraint>::build(context);
+        context.intern_type(crate::Type::from(PointerType::new(pointee)))
     }
 }
 
@@ -1 +1,21 @@
+use alloc::sync::Arc;
+
 pub use midenc_hir_type::*;
+
+/// Returns true if `a` and `b` are the same type.
+///
+/// Equal aggregate types interned with [`Context::intern_type`](super::Context::intern_type) share
+/// the same allocation, so they are first compared by pointer, and only compared structurally when
+/// they are distinct allocations.
+#[inline]
+pub fn same_type(a: &Type, b: &Type) -> bool {
+    let same_allocation = match (a, b) {
+        (Type::Ptr(a), Type::Ptr(b)) => Arc::ptr_eq(a, b),
+        (Type::Struct(a), Type::Struct(b)) => Arc::ptr_eq(a, b),
+        (Type::Array(a), Type::Array(b)) => Arc::ptr_eq(a, b),
+        (Type::List(a), Type::List(b)) => Arc::ptr_eq(a, b),
+        (Type::Function(a), Type::Function(b)) => Arc::ptr_eq(a, b),
+        _ => false,
+    };
+    same_allocation || a == b
+}
@@ -74,6 +74,7 @@ pub mod formatter;
 mod hash;
 mod ir;
 pub mod itertools;
+pub mod logging;
 pub mod matchers;
 pub mod pass;
 pub mod patterns;
@@ -166,7 +167,31 @@ impl AsRef<str> for TraceTarget {
 }
 
 impl core::fmt::Debug for TraceTarget {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.write_target(f)
+    }
+}
+
+impl core::fmt::Display for TraceTarget {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(cached) = self._cached.get() {
+            return f.write_str(cached.as_str());
+        }
+
+        self.write_target(f)
+    }
+}
+
+impl TraceTarget {
+    /// Renders this target in the structured format expected by `midenc-log`.
+    ///
+    /// The relevant symbol, if present, is always the last topic, so that it can be matched by
+    /// `MIDENC_TRACE_FILTER`. If there is no topic, `*` is used in its place, as otherwise the
+    /// symbol would be treated as the topic. Likewise, if the topic is nested, e.g.
+    /// `solver:linear`, but there is no symbol, `*` is used in place of the symbol, so that the
+    /// last segment of the topic is not mistaken for one.
+    fn write_target(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self {
                 category,
@@ -179,42 +204,25 @@ impl core::fmt::Debug for TraceTarget {
                 topic: None,
                 relevant_symbol: Some(sym),
                 _cached: _,
-            } => write!(f, "{category}:{sym}"),
+            } => write!(f, "{category}:*:{sym}"),
             Self {
                 category,
                 topic: Some(topic),
                 relevant_symbol: None,
                 _cached: _,
-            } => write!(f, "{category}:{topic}"),
+            } if topic.as_str().contains(':') => write!(f, "{category}:{topic}:*"),
             Self {
                 category,
                 topic: Some(topic),
-                relevant_symbol: Some(sym),
-                _cached: _,
-            } => write!(f, "{category}:{topic}:{sym}"),
-        }
-    }
-}
-
-impl core::fmt::Display for TraceTarget {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        if let Some(cached) = self._cached.get() {
-            return f.write_str(cached.as_str());
-        }
-
-        match self {
-            Self {
-                category,
-                topic: None,
-                relevant_symbol: _,
+                relevant_symbol: None,
                 _cached: _,
-            } => f.write_str(category.as_str()),
+            } => write!(f, "{category}:{topic}"),
             Self {
                 category,
                 topic: Some(topic),
-                relevant_symbol: _,
+                relevant_symbol: Some(sym),
                 _cached: _,
-            } => write!(f, "{category}:{topic}"),
+            } => write!(f, "{category}:{topic}:{sym}"),
         }
     }
 }
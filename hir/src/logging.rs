@@ -0,0 +1,76 @@
+//! Logging scopes, and the macros which compose the target of a record from the logging scope opened by the pass
+//! manager around each pass, i.e. `pass:<name>:<symbol>`, so that the output of a pass can be
+//! selected with `MIDENC_TRACE=pass:<name>` and `MIDENC_TRACE_FILTER=symbol:<symbol>`.
+//!
+//! Scopes require the `logging` feature. Without it, or outside of any scope, these macros log
+//! with the given `target:`, or the module path of the call site, like their `log` counterparts.
+
+#[cfg(feature = "logging")]
+pub use midenc_log::{LogScope, scope};
+
+/// A guard which keeps a logging scope active until it is dropped, see [scope].
+///
+/// Without the `logging` feature, scopes have no effect.
+#[cfg(not(feature = "logging"))]
+#[must_use = "the scope is only active until the guard is dropped"]
+pub struct LogScope;
+
+/// Open a logging scope for `component`, `topic`, and optionally, the symbol being processed.
+///
+/// Without the `logging` feature, scopes have no effect.
+#[cfg(not(feature = "logging"))]
+pub fn scope<'a>(component: &str, topic: &str, symbol: impl Into<Option<&'a str>>) -> LogScope {
+    let _ = (component, topic, symbol.into());
+    LogScope
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use log;
+    #[cfg(feature = "logging")]
+    pub use midenc_log;
+}
+
+/// Logs a message at the trace level, with a target composed from the active logging scope.
+///
+/// See `midenc_log::scoped_trace`.
+#[cfg(feature = "logging")]
+#[macro_export]
+macro_rules! scoped_trace {
+    ($($arg:tt)+) => {
+        $crate::logging::__private::midenc_log::scoped_trace!($($arg)+)
+    };
+}
+
+/// Logs a message at the trace level, with a target composed from the active logging scope.
+///
+/// See `midenc_log::scoped_trace`.
+#[cfg(not(feature = "logging"))]
+#[macro_export]
+macro_rules! scoped_trace {
+    ($($arg:tt)+) => {
+        $crate::logging::__private::log::trace!($($arg)+)
+    };
+}
+
+/// Logs a message at the debug level, with a target composed from the active logging scope.
+///
+/// See `midenc_log::scoped_debug`.
+#[cfg(feature = "logging")]
+#[macro_export]
+macro_rules! scoped_debug {
+    ($($arg:tt)+) => {
+        $crate::logging::__private::midenc_log::scoped_debug!($($arg)+)
+    };
+}
+
+/// Logs a message at the debug level, with a target composed from the active logging scope.
+///
+/// See `midenc_log::scoped_debug`.
+#[cfg(not(feature = "logging"))]
+#[macro_export]
+macro_rules! scoped_debug {
+    ($($arg:tt)+) => {
+        $crate::logging::__private::log::debug!($($arg)+)
+    };
+}
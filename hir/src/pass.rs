@@ -7,7 +7,9 @@ pub mod registry;
 mod specialization;
 pub mod statistics;
 
-use alloc::{borrow::Cow, string::String};
+use alloc::borrow::Cow;
+
+use midenc_session::PassFilter;
 
 pub use self::{
     analysis::{Analysis, AnalysisManager, OperationAnalysis, PreservedAnalyses},
@@ -48,8 +50,8 @@ pub struct Print {
 pub enum SelectedPasses {
     /// Enable all passes for IR Printing.
     All,
-    /// Just select a subset of passes for IR printing.
-    Just(SmallVec<[String; 1]>),
+    /// Just select the subset of passes matching any of the given filters for IR printing.
+    Just(SmallVec<[PassFilter; 1]>),
 }
 
 #[derive(Default, Debug, Clone)]
@@ -192,7 +194,7 @@ impl Print {
     fn pass_filter(&self, pass: &dyn OperationPass) -> bool {
         match &self.selected_passes {
             Some(SelectedPasses::All) => true,
-            Some(SelectedPasses::Just(passes)) => passes.iter().any(|p| pass.name() == *p),
+            Some(SelectedPasses::Just(passes)) => passes.iter().any(|p| p.matches(pass.name())),
             None => false,
         }
     }
@@ -247,3 +249,98 @@ impl PassInstrumentation for Print {
         }
     }
 }
+
+/// Writes the IR to numbered files after each selected pass, as configured via
+/// `--emit=ir-after=PASS[:DIR]`.
+///
+/// Unlike [Print], which writes to the log, this routes the IR through
+/// [midenc_session::Session::dump_ir], so that the output of successive passes can be diffed.
+#[cfg(feature = "std")]
+pub struct DumpIr {
+    context: alloc::rc::Rc<crate::Context>,
+}
+
+#[cfg(feature = "std")]
+impl DumpIr {
+    /// Returns a [DumpIr] instrumentation, if IR dumps were requested for the current session
+    pub fn new(context: alloc::rc::Rc<crate::Context>) -> Option<Self> {
+        context.session().options.ir_dumps.is_some().then_some(Self { context })
+    }
+}
+
+#[cfg(feature = "std")]
+impl PassInstrumentation for DumpIr {
+    fn run_after_pass(
+        &mut self,
+        pass: &dyn OperationPass,
+        op: &OperationRef,
+        _post_execution_state: &PassExecutionState,
+    ) {
+        use alloc::string::ToString;
+
+        let session = self.context.session();
+        if !session.should_dump_ir(pass.name()) {
+            return;
+        }
+
+        let ir = op.borrow().to_string();
+        if let Err(err) = session.dump_ir(ir, pass.name()) {
+            session
+                .diagnostics
+                .warn(alloc::format!("failed to dump ir after pass '{}': {err}", pass.name()));
+        }
+    }
+}
+
+/// Records the wall time spent in each pass into the session statistics, as configured via
+/// `-Z time-passes`.
+///
+/// Passes may be nested, e.g. a pass adaptor running a nested pipeline, so the start times of
+/// running passes are tracked as a stack.
+#[cfg(feature = "std")]
+pub struct PassTiming {
+    context: alloc::rc::Rc<crate::Context>,
+    running: SmallVec<[std::time::Instant; 4]>,
+}
+
+#[cfg(feature = "std")]
+impl PassTiming {
+    pub fn new(context: alloc::rc::Rc<crate::Context>) -> Self {
+        Self {
+            context,
+            running: SmallVec::default(),
+        }
+    }
+
+    fn record(&mut self, pass: &dyn OperationPass) {
+        use alloc::string::ToString;
+
+        if let Some(start) = self.running.pop() {
+            self.context.session().statistics.timings.record(
+                midenc_session::TimingKind::Pass,
+                pass.name().to_string(),
+                start.elapsed(),
+            );
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl PassInstrumentation for PassTiming {
+    fn run_before_pass(&mut self, _pass: &dyn OperationPass, _op: &OperationRef) {
+        self.running.push(std::time::Instant::now());
+    }
+
+    fn run_after_pass(
+        &mut self,
+        pass: &dyn OperationPass,
+        _op: &OperationRef,
+        _post_execution_state: &PassExecutionState,
+    ) {
+        self.record(pass);
+    }
+
+    fn run_after_pass_failed(&mut self, pass: &dyn OperationPass, _op: &OperationRef) {
+        self.record(pass);
+    }
+}
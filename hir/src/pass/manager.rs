@@ -1,13 +1,7 @@
-use alloc::{
-    boxed::Box,
-    collections::BTreeMap,
-    format,
-    rc::Rc,
-    string::{String, ToString},
-};
+use alloc::{boxed::Box, collections::BTreeMap, format, rc::Rc, string::ToString};
 
 use compact_str::{CompactString, ToCompactString};
-use midenc_session::{Options, diagnostics::Severity};
+use midenc_session::{Options, PassFilter, diagnostics::Severity};
 use smallvec::{SmallVec, smallvec};
 
 use super::{
@@ -41,7 +35,7 @@ pub struct IRPrintingConfig {
     pub print_after_only_on_failure: bool,
     // NOTE: Taken from the Options struct
     pub print_ir_after_all: bool,
-    pub print_ir_after_pass: SmallVec<[String; 1]>,
+    pub print_ir_after_pass: SmallVec<[PassFilter; 1]>,
     pub print_ir_after_modified: bool,
     pub print_ir_filters: SmallVec<[OpFilter; 1]>,
     pub flags: OpPrintingFlags,
@@ -93,8 +87,19 @@ pub struct PassManager {
     statistics: Option<PassDisplayMode>,
     /// Indicates whether or not pass timing is enabled
     timing: bool,
-    /// Indicates whether or not to run verification between passes
-    verification: bool,
+    /// Indicates whether or not, and how often, to run verification between passes
+    verification: Verification,
+}
+
+/// Controls when the verifier is run by a [PassManager]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Verification {
+    /// The verifier is never run
+    Off,
+    /// The verifier is run after every pass which may have modified the IR
+    Modified,
+    /// The verifier is run after every pass, even those which claim to preserve all analyses
+    Each,
 }
 
 impl PassManager {
@@ -108,7 +113,7 @@ impl PassManager {
             instrumentor: Default::default(),
             statistics: None,
             timing: false,
-            verification: true,
+            verification: Verification::Modified,
         }
     }
 
@@ -202,8 +207,28 @@ impl PassManager {
     }
 
     /// Runs the verifier after each individual pass.
+    ///
+    /// To avoid the cost of verification, passes which claim to preserve all analyses are assumed
+    /// not to have modified the IR, and are not verified, see [Self::enable_verify_each].
     pub fn enable_verifier(&mut self, yes: bool) -> &mut Self {
-        self.verification = yes;
+        self.verification = match (yes, self.verification) {
+            (false, _) => Verification::Off,
+            (true, Verification::Each) => Verification::Each,
+            (true, _) => Verification::Modified,
+        };
+        self
+    }
+
+    /// Runs the verifier after every pass, including those which claim to preserve all analyses,
+    /// so that IR broken by a pass is reported as soon as it runs.
+    ///
+    /// Disabling this reverts to the default behavior of [Self::enable_verifier].
+    pub fn enable_verify_each(&mut self, yes: bool) -> &mut Self {
+        self.verification = match (yes, self.verification) {
+            (true, _) => Verification::Each,
+            (false, Verification::Off) => Verification::Off,
+            (false, _) => Verification::Modified,
+        };
         self
     }
 
@@ -219,10 +244,20 @@ impl PassManager {
             let print = Box::new(print);
             self.add_instrumentation(print);
         }
+
+        #[cfg(feature = "std")]
+        if let Some(dump) = super::DumpIr::new(self.context.clone()) {
+            self.add_instrumentation(Box::new(dump));
+        }
         self
     }
 
+    /// Record the wall time spent in each pass into the session statistics
     pub fn enable_timing(&mut self, yes: bool) -> &mut Self {
+        #[cfg(feature = "std")]
+        if yes && !self.timing {
+            self.add_instrumentation(Box::new(super::PassTiming::new(self.context.clone())));
+        }
         self.timing = yes;
         self
     }
@@ -854,11 +889,11 @@ impl OpToOpPassAdaptor {
         pm: &mut OpPassManager,
         op: OperationRef,
         analysis_manager: AnalysisManager,
-        verify: bool,
+        verify: Verification,
         instrumentor: Option<Rc<PassInstrumentor>>,
         parent_info: Option<&PipelineParentInfo>,
     ) -> Result<(), Report> {
-        if verify {
+        if verify != Verification::Off {
             // We run an initial recursive verification, since this is the first verification done
             // to the operations
             Self::verify(&op, true)?;
@@ -883,7 +918,10 @@ impl OpToOpPassAdaptor {
             instrumentor.run_before_pipeline(op_name.as_ref(), parent_info.as_ref().unwrap(), op);
         }
 
+        // Cancellation is checked between passes, as passes themselves are not interruptible
+        let cancel_token = op.borrow().context().session().options.cancel_token.clone();
         for pass in pm.passes_mut() {
+            cancel_token.check()?;
             Self::run(&mut **pass, op, analysis_manager.clone(), verify)?;
         }
 
@@ -899,7 +937,7 @@ impl OpToOpPassAdaptor {
         pass: &mut dyn OperationPass,
         op: OperationRef,
         analysis_manager: AnalysisManager,
-        verify: bool,
+        verify: Verification,
     ) -> Result<(), Report> {
         use crate::Spanned;
 
@@ -977,6 +1015,15 @@ impl OpToOpPassAdaptor {
             Some(pipeline_callback),
         );
 
+        // Scope any logs emitted by the pass to the pass and the symbol it is running on, so that
+        // they can be selected using `MIDENC_TRACE=pass:<name>` and `MIDENC_TRACE_FILTER`. Adaptors
+        // are skipped, as the passes they run open their own scope for each nested operation.
+        #[cfg(feature = "logging")]
+        let _log_scope = (!pass.as_any().is::<OpToOpPassAdaptor>()).then(|| {
+            let symbol = op.borrow().symbol_name_if_symbol();
+            midenc_log::scope("pass", pass.name(), symbol.map(|symbol| symbol.as_str()))
+        });
+
         // Instrument before the pass has run
         if let Some(instrumentor) = pi.as_deref() {
             instrumentor.run_before_pass(pass, &op);
@@ -992,20 +1039,22 @@ impl OpToOpPassAdaptor {
         // Invalidate any non-preserved analyses
         analysis_manager.invalidate(execution_state.preserved_analyses_mut());
 
-        // When `verify == true`, we run the verifier (unless the pass failed)
-        if result.is_ok() && verify {
+        // When verification is enabled, we run the verifier (unless the pass failed)
+        if result.is_ok() && verify != Verification::Off {
             // If the pass is an adaptor pass, we don't run the verifier recursively because the
             // nested operations should have already been verified after nested passes had run
             let run_verifier_recursively = !pass.as_any().is::<OpToOpPassAdaptor>();
 
             // Reduce compile time by avoiding running the verifier if the pass didn't change the
-            // IR since the last time the verifier was run:
+            // IR since the last time the verifier was run, unless every pass is to be verified:
             //
             // * If the pass said that it preserved all analyses then it can't have permuted the IR
-            let run_verifier_now = !execution_state.preserved_analyses().is_all();
+            let run_verifier_now =
+                verify == Verification::Each || !execution_state.preserved_analyses().is_all();
 
             if run_verifier_now
-                && let Err(verification_result) = Self::verify(&op, run_verifier_recursively)
+                && let Err(verification_result) =
+                    Self::verify_after_pass(&op, run_verifier_recursively, pass.name())
             {
                 result = Err(verification_result);
             }
@@ -1032,11 +1081,43 @@ impl OpToOpPassAdaptor {
         }
     }
 
+    /// Verify `op` after running the pass named `pass` on it, attributing any failure to that pass
+    fn verify_after_pass(
+        op: &OperationRef,
+        verify_recursively: bool,
+        pass: &str,
+    ) -> Result<(), Report> {
+        let op = op.borrow();
+        let mut invalid = None;
+        let result: Result<(), Report> = if verify_recursively {
+            op.postwalk(|op: &Operation| {
+                let result = op.verify();
+                if result.is_err() {
+                    invalid = Some(op.as_operation_ref());
+                }
+                result.into()
+            })
+            .into_result()
+        } else {
+            op.verify().inspect_err(|_| invalid = Some(op.as_operation_ref()))
+        };
+
+        let Err(err) = result else {
+            return Ok(());
+        };
+        let invalid = invalid.expect("expected verification failure to have an operation");
+        let invalid = invalid.borrow();
+        // The verifier's report is kept as the cause, so that its labels and source are rendered
+        Err(err
+            .wrap_err(format!("the invalid operation is:\n{}", &*invalid))
+            .wrap_err(format!("IR verification failed after pass '{pass}'")))
+    }
+
     fn run_on_operation(
         &mut self,
         op: OperationRef,
         state: &mut PassExecutionState,
-        verify: bool,
+        verify: Verification,
     ) -> Result<(), Report> {
         let analysis_manager = state.analysis_manager();
         let instrumentor = analysis_manager.pass_instrumentor();
@@ -1118,9 +1199,13 @@ impl Pass for OpToOpPassAdaptor {
 mod tests {
     use alloc::boxed::Box;
 
+    use super::{Nesting, PassManager};
     use crate::{
         EntityMut, Operation, OperationName, Report, SourceSpan, Type,
-        dialects::{builtin::BuiltinOpBuilder, test::TestOpBuilder},
+        dialects::{
+            builtin::{BuiltinOpBuilder, Function},
+            test::TestOpBuilder,
+        },
         pass::{Pass, PassExecutionState},
         testing::Test,
     };
@@ -1165,10 +1250,8 @@ mod tests {
         }
     }
 
-    #[test]
-    fn pass_manager_detects_broken_invariant() {
+    fn build_test_function() -> Test {
         let mut test = Test::new("test_verify", &[Type::I32, Type::I32], &[Type::I32]);
-
         {
             let entry = test.entry_block();
             let lhs = entry.borrow().get_argument(0).borrow().as_value_ref();
@@ -1177,9 +1260,75 @@ mod tests {
             let sum = fb.add(lhs, rhs, SourceSpan::UNKNOWN).unwrap();
             fb.ret([sum], SourceSpan::UNKNOWN).unwrap();
         }
+        test
+    }
+
+    #[test]
+    fn pass_manager_detects_broken_invariant() {
+        let test = build_test_function();
 
         // Run the corrupting pass with verification enabled
         let result = test.apply_boxed_pass(Box::new(CorruptBinaryOp), true);
         assert!(result.is_err(), "PassManager should detect broken BinaryOp invariant");
     }
+
+    /// Like [CorruptBinaryOp], but claims to have preserved all analyses
+    struct CorruptBinaryOpPreservingAnalyses;
+
+    impl Pass for CorruptBinaryOpPreservingAnalyses {
+        type Target = Operation;
+
+        fn name(&self) -> &'static str {
+            "corrupt-binary-op-preserving-analyses"
+        }
+
+        fn can_schedule_on(&self, _name: &OperationName) -> bool {
+            true
+        }
+
+        fn run_on_operation(
+            &mut self,
+            op: EntityMut<'_, Operation>,
+            state: &mut PassExecutionState,
+        ) -> Result<(), Report> {
+            CorruptBinaryOp.run_on_operation(op, state)?;
+            state.preserved_analyses_mut().preserve_all();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pass_manager_attributes_verification_failure_to_pass() {
+        let test = build_test_function();
+
+        let err = test
+            .apply_boxed_pass(Box::new(CorruptBinaryOp), true)
+            .expect_err("PassManager should detect broken BinaryOp invariant");
+        assert_eq!(err.to_string(), "IR verification failed after pass 'corrupt-binary-op'");
+        // The verifier's own report is the root cause
+        let cause = err.chain().last().unwrap().to_string();
+        assert!(cause.starts_with("invalid operation"), "unexpected cause: {cause}");
+    }
+
+    #[test]
+    fn pass_manager_verify_each_verifies_passes_preserving_analyses() {
+        let test = build_test_function();
+        let function = test.function().as_operation_ref();
+
+        // By default, passes which preserve all analyses are assumed not to modify the IR
+        let mut pm = PassManager::on::<Function>(test.context_rc(), Nesting::Implicit);
+        pm.add_pass(Box::new(CorruptBinaryOpPreservingAnalyses));
+        pm.run(function).expect("expected verification to be skipped");
+
+        let test = build_test_function();
+        let function = test.function().as_operation_ref();
+        let mut pm = PassManager::on::<Function>(test.context_rc(), Nesting::Implicit);
+        pm.add_pass(Box::new(CorruptBinaryOpPreservingAnalyses));
+        pm.enable_verify_each(true);
+        let err = pm.run(function).expect_err("expected verification to fail");
+        assert_eq!(
+            err.to_string(),
+            "IR verification failed after pass 'corrupt-binary-op-preserving-analyses'"
+        );
+    }
 }
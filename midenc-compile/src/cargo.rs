@@ -30,6 +30,22 @@ pub struct CargoOptions {
     pub workspace: bool,
     /// Packages to build
     pub packages: Vec<CargoPackageSpec>,
+    /// Features to activate
+    pub features: Vec<String>,
+    /// Activate all available features
+    pub all_features: bool,
+    /// Do not activate the `default` feature
+    pub no_default_features: bool,
+    /// Require `Cargo.lock` to be up to date
+    pub locked: bool,
+    /// Run without accessing the network
+    pub offline: bool,
+    /// Directory for all generated artifacts
+    pub target_dir: Option<PathBuf>,
+    /// Run `cargo check` rather than `cargo build`, i.e. do not produce any Wasm artifacts
+    pub check: bool,
+    /// Directory the Miden SDK macros write the WIT worlds they synthesize to, if any
+    pub wit_dir: Option<PathBuf>,
 }
 
 /// Represents a cargo package specifier.
@@ -101,8 +117,66 @@ impl CargoOptions {
             manifest_path: options.manifest_path.clone(),
             workspace: options.workspace,
             packages,
+            features: options.features.clone(),
+            all_features: options.all_features,
+            no_default_features: options.no_default_features,
+            locked: options.locked,
+            offline: options.offline,
+            target_dir: options.cargo_target_dir.clone(),
+            check: false,
+            wit_dir: None,
         })
     }
+
+    /// Returns the arguments which forward these options to `cargo build`.
+    pub fn to_cargo_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.release {
+            args.push("--release".to_string());
+        }
+
+        if let Some(ref manifest_path) = self.manifest_path {
+            args.push("--manifest-path".to_string());
+            args.push(manifest_path.to_string_lossy().to_string());
+        }
+
+        if self.workspace {
+            args.push("--workspace".to_string());
+        }
+
+        for package in &self.packages {
+            args.push("--package".to_string());
+            args.push(package.to_string());
+        }
+
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+
+        if self.all_features {
+            args.push("--all-features".to_string());
+        }
+
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+
+        if self.locked {
+            args.push("--locked".to_string());
+        }
+
+        if self.offline {
+            args.push("--offline".to_string());
+        }
+
+        if let Some(ref target_dir) = self.target_dir {
+            args.push("--target-dir".to_string());
+            args.push(target_dir.to_string_lossy().to_string());
+        }
+
+        args
+    }
 }
 
 pub fn load_cargo_based_source_dependencies(
@@ -207,9 +281,22 @@ fn cargo_build(
         midenup_home: options.midenup_home.clone(),
         toolchain: options.toolchain.clone(),
         color: options.color,
-        diagnostics: options.diagnostics,
+        diagnostics_format: options.diagnostics_format,
+        diagnostics: options.diagnostics.clone(),
         remap_path_prefixes: options.remap_path_prefixes.clone(),
+        deterministic: options.deterministic,
+        nest_outputs: options.nest_outputs,
+        no_cache: options.no_cache,
+        package_cache: options.package_cache,
+        no_masm_opt: options.no_masm_opt,
+        demangle_procedure_names: options.demangle_procedure_names,
+        float_mode: options.float_mode,
+        kernel_abi: options.kernel_abi.clone(),
         rustflags: options.rustflags.clone(),
+        // The feature selection applies to the root package only, but dependencies are resolved
+        // from the same lock file and under the same network policy
+        locked: options.locked,
+        offline: options.offline,
         link_libraries: vec![LinkLibrary::core()],
         ..midenc_session::Options::new(
             Some(package_name.clone()),
@@ -224,6 +311,9 @@ fn cargo_build(
     if nested_options.target_requires_protocol() {
         nested_options.link_libraries.push(LinkLibrary::protocol());
     }
+    if nested_options.float_mode == midenc_session::FloatMode::SoftFloat {
+        nested_options.link_libraries.push(LinkLibrary::softfloat());
+    }
     // Inherit release/debug profile from parent build
     if cargo_opts.release {
         nested_options.profile = "release".to_string();
@@ -247,7 +337,7 @@ fn cargo_build(
     let context = Rc::new(midenc_hir::Context::new(session));
 
     // We expect dependencies to *always* produce packages (.masp)
-    let Artifact::Assembled(package) = crate::cargo_project_pipeline(input, context)? else {
+    let Artifact::Assembled { package, .. } = crate::cargo_project_pipeline(input, context)? else {
         panic!(
             "expected cargo build of {package_name} to produce assembled artifact, but got HIR \
              output instead",
@@ -8,10 +8,14 @@ use std::ffi::OsString;
 use clap::{Parser, builder::ArgPredicate};
 use miden_mast_package::TargetType;
 use midenc_session::{
-    ColorChoice, DebugInfo, InputFile, IrFilter, LinkLibrary, OptLevel, Options, OutputFile,
-    OutputTypeSpec, OutputTypes, PathBuf, RemapPathPrefix, Session, Verbosity, Warnings,
-    add_target_link_libraries, diagnostics::Emitter,
+    CancellationToken, Checkpoint, ColorChoice, DebugInfo, DiagnosticsFormat, FloatMode, InputFile,
+    IrDumps, IrFilter, LinkLibrary, LintLevel, OptLevel, Options, OutputFile, OutputTypeSpec,
+    OutputTypes, PassFilter, PathBuf, RemapPathPrefix, Session, Verbosity, Warnings,
+    add_target_link_libraries,
+    diagnostics::{DiagnosticsConfig, Emitter},
 };
+#[cfg(feature = "std")]
+use midenc_session::{ConfigFile, FlagAction};
 
 /// Compile a program from WebAssembly or Miden IR, to Miden Assembly.
 #[derive(Debug, Clone)]
@@ -96,6 +100,62 @@ pub struct Compiler {
         arg(long, conflicts_with("output_file"), help_heading = "Output")
     )]
     pub stdout: bool,
+    /// Produce byte-identical artifacts regardless of the machine or directory used to build them
+    ///
+    /// Paths under the working directory are always remapped to be relative to it, even when
+    /// `--remap-path-prefix` is given. When building a Cargo project, the Cargo home directory,
+    /// which holds the sources of dependencies, is remapped to `/cargo`, and the sysroot of the
+    /// Rust toolchain to `/rustc-sysroot`. This is the default for `cargo miden build --release`.
+    #[cfg_attr(feature = "std", arg(long, help_heading = "Output"))]
+    pub deterministic: bool,
+    /// Write the outputs of items whose names have namespace components, delimited by `::` or
+    /// `/`, to nested subdirectories, e.g. `std::math::u64` to `std/math/u64.masm`
+    ///
+    /// By default, such a name is flattened into a single file name, e.g. `std__math__u64.masm`.
+    #[cfg_attr(feature = "std", arg(long, help_heading = "Output"))]
+    pub nest_outputs: bool,
+    /// Always compile the inputs, rather than reusing translations, or packages compiled from Cargo
+    /// projects, cached in the target directory
+    #[cfg_attr(feature = "std", arg(long, help_heading = "Compiler"))]
+    pub no_cache: bool,
+    /// Do not optimize the emitted Miden Assembly, while still optimizing the IR as requested by
+    /// `--optimize`
+    ///
+    /// Use this to tell whether a miscompilation is caused by lowering to Miden Assembly, or by
+    /// the optimizations applied to the emitted code.
+    #[cfg_attr(feature = "std", arg(long, help_heading = "Compiler"))]
+    pub no_masm_opt: bool,
+    /// Rename procedures which are not exported to the demangled names of the Rust functions they
+    /// were compiled from, for readability of the emitted Miden Assembly
+    ///
+    /// A hash of the mangled name is appended to each name, so that distinct instantiations of a
+    /// generic function keep distinct names. Exported procedures are never renamed.
+    #[cfg_attr(feature = "std", arg(long, help_heading = "Output"))]
+    pub demangle_procedure_names: bool,
+    /// Specify how floating-point operations in the input are translated
+    ///
+    /// Miden Assembly has no native floating-point support, so by default functions which perform
+    /// floating-point operations are rejected. `soft-float` implements them in software, by
+    /// linking against the `miden-softfloat` library, while `unreachable` turns them into traps,
+    /// for code which is known to never execute them.
+    #[cfg_attr(feature = "std", arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        default_value_t = FloatMode::Reject,
+        help_heading = "Compiler"
+    ))]
+    pub float_mode: FloatMode,
+    /// Map Wasm imports to the procedures of a custom transaction kernel, as described in `<FILE>`
+    ///
+    /// The file is a TOML document whose `[[imports]]` entries map the module and name of an
+    /// import to a Miden Assembly library path and procedure, along with its signature and how
+    /// calls are adapted to it. Imports which are not listed keep their built-in mapping.
+    #[cfg_attr(
+        feature = "std",
+        arg(long, value_name = "FILE", help_heading = "Compiler")
+    )]
+    pub kernel_abi: Option<PathBuf>,
     /// Specify the name of the project target being compiled
     ///
     /// By default, if this is not specified, then the target is inferred based on the type of
@@ -150,6 +210,51 @@ pub struct Compiler {
         help_heading = "Diagnostics"
     ))]
     pub warn: Warnings,
+    /// Promote warnings with the given diagnostic code to errors.
+    ///
+    /// `-D warnings` promotes all warnings to errors, and is equivalent to `--warn error`.
+    #[cfg_attr(feature = "std", arg(
+        long = "deny",
+        short = 'D',
+        value_name = "LINT",
+        action = clap::ArgAction::Append,
+        help_heading = "Diagnostics"
+    ))]
+    pub deny: Vec<String>,
+    /// Silence warnings with the given diagnostic code.
+    ///
+    /// `-A warnings` silences all warnings, and is equivalent to `--warn none`. Lints which are
+    /// also given to `-D` are denied.
+    #[cfg_attr(feature = "std", arg(
+        long = "allow",
+        short = 'A',
+        value_name = "LINT",
+        action = clap::ArgAction::Append,
+        help_heading = "Diagnostics"
+    ))]
+    pub allow: Vec<String>,
+    /// Specify the format in which diagnostics are emitted
+    ///
+    /// The `json` format emits one JSON object per diagnostic, per line, to stderr.
+    #[cfg_attr(feature = "std", arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = DiagnosticsFormat::Human,
+        help_heading = "Diagnostics"
+    ))]
+    pub diagnostic_format: DiagnosticsFormat,
+    /// Stop rendering errors after the first N, though compilation still stops with a count of
+    /// all errors raised.
+    ///
+    /// Pass `0` to render all errors.
+    #[cfg_attr(feature = "std", arg(
+        long,
+        value_name = "N",
+        default_value_t = DiagnosticsConfig::DEFAULT_ERROR_LIMIT,
+        help_heading = "Diagnostics"
+    ))]
+    pub error_limit: usize,
     /// Whether, and how, to color terminal output
     #[cfg_attr(feature = "std", arg(
         long,
@@ -162,8 +267,17 @@ pub struct Compiler {
     pub color: ColorChoice,
     /// Specify the function to call as the entrypoint for the program
     /// in the format `<module_name>::<function>`
-    #[cfg_attr(feature = "std", arg(long, help_heading = "Compiler", hide(true)))]
-    pub entrypoint: Option<String>,
+    ///
+    /// May be given more than once, in which case a library exporting each entrypoint is
+    /// produced, or one package per entrypoint with `--emit=masp-per-entrypoint`.
+    #[cfg_attr(feature = "std", arg(
+        long,
+        value_name = "FUNCTION",
+        action = clap::ArgAction::Append,
+        help_heading = "Compiler",
+        hide(true)
+    ))]
+    pub entrypoint: Vec<String>,
     /// Tells the compiler to produce an executable Miden program
     ///
     /// Implied by `--entrypoint`, defaults to true for non-rollup targets.
@@ -238,6 +352,10 @@ pub struct Compiler {
     /// multiple times.
     ///
     /// PATH must be a directory in which to place the outputs, or `-` for stdout.
+    ///
+    /// The special SPEC `ir-after=PASS[:DIR]` writes the IR after each application of PASS (or
+    /// every pass, if PASS is `all`) to numbered files in DIR, which defaults to
+    /// `<target-dir>/ir`.
     #[cfg_attr(
         feature = "std",
         arg(
@@ -264,17 +382,20 @@ pub struct Compiler {
     pub debug: DebugInfo,
     /// Specify what type, and to what degree, of optimizations to apply to code during
     /// compilation.
+    ///
+    /// When not specified, `balanced` is used, except by `cargo miden build`, which uses `none`
+    /// for the `dev` profile.
     #[cfg_attr(feature = "std", arg(
         long = "optimize",
+        visible_alias = "opt-level",
         value_enum,
         value_name = "LEVEL",
         next_line_help(true),
-        default_value_t = OptLevel::None,
         default_missing_value = "balanced",
         num_args(0..=1),
         help_heading = "Output"
     ))]
-    pub opt_level: OptLevel,
+    pub opt_level: Option<OptLevel>,
     /// Set a codegen option
     ///
     /// Use `-C help` to print available options
@@ -346,11 +467,60 @@ pub struct Compiler {
         arg(long, short = 'p', value_name = "SPEC", conflicts_with("workspace"),)
     )]
     pub package: Vec<String>,
+    /// Space or comma separated list of Cargo features to activate (used by cargo miden build)
+    #[cfg_attr(
+        feature = "std",
+        arg(
+            long,
+            short = 'F',
+            value_name = "FEATURES",
+            value_delimiter = ',',
+            help_heading = "Compiler"
+        )
+    )]
+    pub features: Vec<String>,
+    /// Activate all available Cargo features (used by cargo miden build)
+    #[cfg_attr(
+        feature = "std",
+        arg(long, help_heading = "Compiler", default_value_t = false)
+    )]
+    pub all_features: bool,
+    /// Do not activate the `default` Cargo feature (used by cargo miden build)
+    #[cfg_attr(
+        feature = "std",
+        arg(long, help_heading = "Compiler", default_value_t = false)
+    )]
+    pub no_default_features: bool,
+    /// Assert that `Cargo.lock` will remain unchanged (used by cargo miden build)
+    #[cfg_attr(
+        feature = "std",
+        arg(long, help_heading = "Compiler", default_value_t = false)
+    )]
+    pub locked: bool,
+    /// Run Cargo without accessing the network (used by cargo miden build)
+    #[cfg_attr(
+        feature = "std",
+        arg(long, help_heading = "Compiler", default_value_t = false)
+    )]
+    pub offline: bool,
+    /// The directory in which Cargo places its build artifacts, when `--target-dir` is given to
+    /// `cargo miden build`
+    #[cfg_attr(feature = "std", arg(skip))]
+    pub cargo_target_dir: Option<PathBuf>,
     /// Path to the package/project manifest
     ///
     /// If unspecified, the compiler will create a virtual manifest for the given input
     #[cfg_attr(feature = "std", arg(long, value_name = "PATH",))]
     pub manifest_path: Option<PathBuf>,
+    /// Path to a `midenc.toml` file from which to load default options
+    ///
+    /// If unspecified, the `midenc.toml` file in the project directory is used, if present.
+    /// Options given on the command line always take precedence over those in this file.
+    #[cfg_attr(
+        feature = "std",
+        arg(long, value_name = "PATH", help_heading = "Compiler")
+    )]
+    pub config: Option<PathBuf>,
     /// Specify path prefixes to remap for any file paths encoded in debug info
     #[cfg_attr(
         feature = "std",
@@ -364,6 +534,9 @@ pub struct Compiler {
         )
     )]
     pub remap_path_prefixes: Vec<RemapPathPrefix>,
+    /// The token which can be used to cancel compilations configured from these options
+    #[cfg_attr(feature = "std", arg(skip))]
+    pub cancel_token: CancellationToken,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -394,6 +567,24 @@ pub struct CodegenOptions {
     /// Tell the compiler to generate Miden Assembly from the inputs without linking them
     #[cfg_attr(feature = "std", arg(long, default_value_t = false))]
     pub no_link: bool,
+    /// Tell the compiler to save its state under the target directory after the given stage, and
+    /// exit
+    ///
+    /// The latest stage is `rewrite`, the output of codegen cannot be saved
+    #[cfg_attr(feature = "std", arg(
+        long,
+        value_name = "STAGE",
+        conflicts_with_all(["parse_only", "analyze_only", "link_only"]),
+    ))]
+    pub stop_after: Option<Checkpoint>,
+    /// Tell the compiler to resume from the state saved by a previous compilation with
+    /// `stop-after=<STAGE>`, rather than compiling the inputs from scratch
+    #[cfg_attr(feature = "std", arg(
+        long,
+        value_name = "STAGE",
+        conflicts_with_all(["parse_only", "analyze_only", "link_only"]),
+    ))]
+    pub start_from: Option<Checkpoint>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -419,6 +610,9 @@ pub struct UnstableOptions {
     )]
     pub print_cfg_after_all: bool,
     /// Print the CFG after running a specific HIR pass
+    ///
+    /// PASS may be an exact pass name, a glob such as `lift-*`, or a regular expression delimited
+    /// by slashes, such as `/scf.*/`.
     #[cfg_attr(
         feature = "std",
         arg(
@@ -428,7 +622,7 @@ pub struct UnstableOptions {
             help_heading = "Passes"
         )
     )]
-    pub print_cfg_after_pass: Vec<String>,
+    pub print_cfg_after_pass: Vec<PassFilter>,
     /// Print the IR before each compiler stage.
     ///
     /// The available stages are:
@@ -458,6 +652,9 @@ pub struct UnstableOptions {
     )]
     pub print_ir_after_all: bool,
     /// Print the IR after running a specific pass
+    ///
+    /// PASS may be an exact pass name, a glob such as `lift-*`, or a regular expression delimited
+    /// by slashes, such as `/scf.*/`.
     #[cfg_attr(
         feature = "std",
         arg(
@@ -467,7 +664,7 @@ pub struct UnstableOptions {
             help_heading = "Passes"
         )
     )]
-    pub print_ir_after_pass: Vec<String>,
+    pub print_ir_after_pass: Vec<PassFilter>,
     /// Only print the IR if the pass modified the IR structure. If this flag is set, and no IR
     /// filter flag is; then the default behavior is to print the IR after every pass.
     #[cfg_attr(
@@ -511,6 +708,20 @@ pub struct UnstableOptions {
         )
     )]
     pub print_hir_source_locations: bool,
+    /// Record the wall time spent in each compiler stage and pass, and print a report to stderr
+    /// once compilation finishes
+    #[cfg_attr(
+        feature = "std",
+        arg(long, default_value_t = false, help_heading = "Passes")
+    )]
+    pub time_passes: bool,
+    /// Run the IR verifier after every pass, so that a pass which produces invalid IR is reported
+    /// as soon as it runs
+    #[cfg_attr(
+        feature = "std",
+        arg(long, default_value_t = false, help_heading = "Passes")
+    )]
+    pub verify_each: bool,
 }
 
 impl CodegenOptions {
@@ -598,6 +809,14 @@ NOTE: When specifying these options, strip the leading '--'",
 }
 
 impl Compiler {
+    /// Get a handle to the [CancellationToken] of this configuration.
+    ///
+    /// Calling [CancellationToken::cancel] on it, from any thread, stops an in-progress
+    /// compilation at the next stage, pass, or function boundary, with [crate::CompilerStopped].
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.cancel_token.clone()
+    }
+
     /// Parse command-line arguments into compiler [Options].
     ///
     /// Returns the parsed options or an error if parsing failed.
@@ -606,26 +825,231 @@ impl Compiler {
     /// selectively forwarding them to `cargo build` and `midenc`.
     #[cfg(feature = "std")]
     pub fn try_parse_from<I, T>(cwd: PathBuf, iter: I) -> Result<Box<Options>, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Self::try_parse_from_with(cwd, iter, |_, _| ())
+    }
+
+    /// Same as [Compiler::try_parse_from], but applies the defaults implied by the selected Cargo
+    /// profile to any options which were not set explicitly.
+    ///
+    /// Currently, this disables optimizations for the `dev` profile, and enables deterministic
+    /// builds for the `release` profile.
+    ///
+    /// As with `cargo build`, an explicit `--target-dir` is the directory in which Cargo places
+    /// its build artifacts, and the compiler artifacts are placed in its `miden` sub-directory.
+    #[cfg(feature = "std")]
+    pub fn try_parse_cargo_from<I, T>(cwd: PathBuf, iter: I) -> Result<Box<Options>, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Self::try_parse_cargo_with_defaults_from(cwd, core::iter::empty::<OsString>(), iter)
+    }
+
+    /// Same as [Compiler::try_parse_cargo_from], but with `defaults` parsed as if they preceded
+    /// the arguments in `iter`, e.g. the compiler flags set in the metadata of a Cargo package.
+    ///
+    /// An option given in both takes its value from `iter`, except for options which may be
+    /// given multiple times, e.g. `--emit`, whose values are merged.
+    #[cfg(feature = "std")]
+    pub fn try_parse_cargo_with_defaults_from<D, I, T, U>(
+        cwd: PathBuf,
+        defaults: D,
+        iter: I,
+    ) -> Result<Box<Options>, clap::Error>
+    where
+        D: IntoIterator<Item = U>,
+        U: Into<OsString> + Clone,
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        use clap::parser::ValueSource;
+
+        let argv = defaults
+            .into_iter()
+            .map(Into::into)
+            .chain(iter.into_iter().map(Into::into))
+            .collect::<Vec<OsString>>();
+        Self::try_parse_from_with(cwd, argv, |opts, matches| {
+            if opts.opt_level.is_none() && opts.profile == "dev" {
+                opts.opt_level = Some(OptLevel::None);
+            }
+            opts.deterministic |= opts.profile == "release";
+            if matches.value_source("target_dir") == Some(ValueSource::CommandLine) {
+                opts.cargo_target_dir = Some(opts.target_dir.clone());
+                opts.target_dir = opts.target_dir.join("miden");
+            }
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn try_parse_from_with<I, T>(
+        cwd: PathBuf,
+        iter: I,
+        configure: impl FnOnce(&mut Self, &clap::ArgMatches),
+    ) -> Result<Box<Options>, clap::Error>
     where
         I: IntoIterator<Item = T>,
         T: Into<OsString> + Clone,
     {
         let argv = [OsString::from("midenc")]
             .into_iter()
-            .chain(iter.into_iter().map(|arg| arg.into()));
-        let command = <Self as clap::CommandFactory>::command();
+            .chain(iter.into_iter().map(|arg| arg.into()))
+            .collect::<Vec<_>>();
+        // Options given more than once take their last value, so that later arguments, e.g.
+        // those given on the command line, override earlier defaults
+        let command = <Self as clap::CommandFactory>::command().args_override_self(true);
         let command = midenc_session::flags::register_flags(command);
-        let mut matches = command.try_get_matches_from(argv)?;
+        let (mut matches, config) = Self::try_get_matches_with_config(command, &argv, &cwd)?;
         let compile_matches = matches.clone();
 
-        let opts = <Self as clap::FromArgMatches>::from_arg_matches_mut(&mut matches)
+        let mut opts = <Self as clap::FromArgMatches>::from_arg_matches_mut(&mut matches)
             .map_err(format_error::<Self>)?;
+        configure(&mut opts, &compile_matches);
 
         let mut opts = opts.into_options(cwd);
         opts.set_extra_flags(compile_matches.into());
+        opts.config = config.map(Arc::new);
         Ok(opts)
     }
 
+    /// Parses `argv`, the arguments of a compiler invocation starting with the program name, with
+    /// `command`, which must include the [Compiler] options.
+    ///
+    /// The options of the `midenc.toml` file which applies to the invocation, i.e. the one given by
+    /// `--config`, or found in the project directory, are parsed as if they preceded those in
+    /// `argv`. Returns the matches, along with the config file, if there is one.
+    #[cfg(feature = "std")]
+    pub fn try_get_matches_with_config(
+        command: clap::Command,
+        argv: &[OsString],
+        cwd: &std::path::Path,
+    ) -> Result<(clap::ArgMatches, Option<ConfigFile>), clap::Error> {
+        let matches = command.clone().try_get_matches_from(argv)?;
+        let Some((config, config_argv)) = Self::load_config(&matches, cwd)? else {
+            return Ok((matches, None));
+        };
+        let argv = argv
+            .iter()
+            .take(1)
+            .cloned()
+            .chain(config_argv.iter().map(OsString::from))
+            .chain(argv.iter().skip(1).cloned());
+        let matches = command.try_get_matches_from(argv)?;
+        Ok((matches, Some(config)))
+    }
+
+    /// Load the `midenc.toml` file which applies to the compiler invocation that produced
+    /// `matches`, if there is one.
+    ///
+    /// The file is the one given by `--config`, otherwise it is looked for in the project
+    /// directory, i.e. the directory containing `--manifest-path`, or the working directory.
+    ///
+    /// Returns the config file, along with the arguments it implies for any options that were not
+    /// given on the command line. These arguments must be parsed as if they came before those
+    /// given on the command line.
+    #[cfg(feature = "std")]
+    fn load_config(
+        matches: &clap::ArgMatches,
+        cwd: &std::path::Path,
+    ) -> Result<Option<(ConfigFile, Vec<String>)>, clap::Error> {
+        use clap::parser::ValueSource;
+
+        let working_dir = matches.get_one::<PathBuf>("working_dir").map(|dir| cwd.join(dir));
+        let working_dir = working_dir.as_deref().unwrap_or(cwd);
+        let config = match matches.get_one::<PathBuf>("config") {
+            Some(path) => ConfigFile::load(working_dir.join(path)).map(Some),
+            None => {
+                let project_dir = matches
+                    .get_one::<PathBuf>("manifest_path")
+                    .and_then(|path| working_dir.join(path).parent().map(|dir| dir.to_path_buf()));
+                ConfigFile::find(project_dir.as_deref().unwrap_or(working_dir))
+            }
+        }
+        .map_err(|err| clap::Error::raw(clap::error::ErrorKind::Io, format!("{err}\n")))?;
+        let Some(config) = config else {
+            return Ok(None);
+        };
+
+        let is_explicit = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        };
+
+        let mut argv = vec![];
+        if let Some(target) = config.target.as_ref()
+            && !is_explicit("target")
+        {
+            argv.push(format!("--target={target}"));
+        }
+        // `--exe`, `--lib` and `--entrypoint` all select a target type, and conflict with it
+        let selects_target_type = ["target_type", "is_program", "is_library", "entrypoint"]
+            .into_iter()
+            .any(is_explicit);
+        if let Some(project_type) = config.project_type.as_ref()
+            && !selects_target_type
+        {
+            argv.push(format!("--target-type={project_type}"));
+        }
+        if !is_explicit("link_libraries") {
+            argv.extend(config.link_libraries.iter().map(|lib| format!("--link-library={lib}")));
+        }
+        if !is_explicit("output_types") {
+            argv.extend(config.output_types.iter().map(|spec| format!("--emit={spec}")));
+        }
+        let prints_ir_after_pass = matches
+            .get_many::<String>("unstable")
+            .into_iter()
+            .flatten()
+            .any(|opt| opt.starts_with("print-ir-after-pass"));
+        if !prints_ir_after_pass {
+            argv.extend(
+                config
+                    .print_ir_after_pass
+                    .iter()
+                    .map(|pass| format!("-Zprint-ir-after-pass={pass}")),
+            );
+        }
+        for (flag, values) in config.flags.iter().filter(|(flag, _)| !is_explicit(flag.name)) {
+            let long = flag.long.unwrap_or(flag.name);
+            let invalid = |value: &str| {
+                clap::Error::raw(
+                    clap::error::ErrorKind::InvalidValue,
+                    format!(
+                        "invalid value '{value}' for flag '{long}' in config file '{}'\n",
+                        config.path.display()
+                    ),
+                )
+            };
+            match flag.action {
+                FlagAction::Set | FlagAction::Append => {
+                    argv.extend(values.iter().map(|value| format!("--{long}={value}")));
+                }
+                FlagAction::SetTrue | FlagAction::SetFalse => {
+                    for value in values {
+                        let value = value.parse::<bool>().map_err(|_| invalid(value))?;
+                        if value == flag.action.as_boolean_value() {
+                            argv.push(format!("--{long}"));
+                        }
+                    }
+                }
+                FlagAction::Count => {
+                    for value in values {
+                        let count = value.parse::<usize>().map_err(|_| invalid(value))?;
+                        argv.extend(core::iter::repeat_n(format!("--{long}"), count));
+                    }
+                }
+            }
+        }
+
+        Ok(Some((config, argv)))
+    }
+
     /// Construct a [Compiler] programatically
     #[cfg(feature = "std")]
     pub fn new_session<A, S>(
@@ -652,10 +1076,21 @@ impl Compiler {
             output_dir,
             output_file,
             stdout,
+            deterministic,
+            nest_outputs,
+            no_cache,
+            no_masm_opt,
+            demangle_procedure_names,
+            float_mode,
+            kernel_abi,
             target,
             target_type,
             verbosity,
             warn,
+            deny,
+            allow,
+            diagnostic_format,
+            error_limit,
             color,
             entrypoint,
             is_program: _,
@@ -671,14 +1106,24 @@ impl Compiler {
             release: _,
             workspace,
             package,
+            features,
+            all_features,
+            no_default_features,
+            locked,
+            offline,
+            cargo_target_dir,
             manifest_path,
+            config: _,
             remap_path_prefixes,
+            cancel_token,
         } = self;
         let CodegenOptions {
             parse_only,
             analyze_only,
             link_only,
             no_link,
+            stop_after,
+            start_from,
         } = CodegenOptions::parse_argv(codegen);
         let UnstableOptions {
             cargo_frontmatter,
@@ -691,6 +1136,8 @@ impl Compiler {
             print_ir_after_modified,
             print_ir_filter,
             print_hir_source_locations,
+            time_passes,
+            verify_each,
         } = UnstableOptions::parse_argv(unstable);
 
         // Determine if a specific output file has been requested
@@ -701,6 +1148,10 @@ impl Compiler {
         };
 
         // Initialize output types
+        let ir_dumps = IrDumps::from_specs(&output_types).unwrap_or_else(|err| err.exit());
+        let masp_per_entrypoint = output_types
+            .iter()
+            .any(|spec| matches!(spec, OutputTypeSpec::MaspPerEntrypoint { .. }));
         let output_types = OutputTypes::new(output_types).unwrap_or_else(|err| err.exit());
 
         let cwd = working_dir.unwrap_or(cwd);
@@ -711,6 +1162,7 @@ impl Compiler {
         } else {
             cwd.join(&target_dir)
         };
+        let cargo_target_dir = cargo_target_dir.map(|dir| cwd.join(dir));
 
         // Consolidate all compiler options
         let mut options = Box::new(Options::new(
@@ -724,24 +1176,50 @@ impl Compiler {
         .with_color(color)
         .with_verbosity(verbosity)
         .with_warnings(warn)
+        .with_diagnostics_format(diagnostic_format)
+        .with_error_limit((error_limit > 0).then_some(error_limit))
         .with_debug_info(debug)
-        .with_optimization(opt_level)
+        .with_optimization(opt_level.unwrap_or_default())
         .with_output_types(output_types, output_file);
+        // Apply lint levels, with `-D` taking precedence over `-A`
+        for lint in allow {
+            options = options.with_lint_level(lint, LintLevel::Allow);
+        }
+        for lint in deny {
+            options = options.with_lint_level(lint, LintLevel::Deny);
+        }
         options.target = target;
         options.profile = profile;
         options.manifest_path = manifest_path;
         options.midenup_home = midenup_home;
         options.toolchain = toolchain;
         options.search_paths.extend(search_path);
-        add_target_link_libraries(&mut link_libraries, options.target_requires_protocol());
+        add_target_link_libraries(
+            &mut link_libraries,
+            options.target_requires_protocol(),
+            float_mode == FloatMode::SoftFloat,
+        );
         options.link_libraries = link_libraries;
-        options.entrypoint = entrypoint;
+        // Multiple entrypoints can only be exported from a library
+        if entrypoint.len() > 1 && options.target_type.is_some_and(|ty| ty.is_executable()) {
+            options.target_type = Some(TargetType::Library);
+        }
+        options.entrypoints = entrypoint;
+        options.masp_per_entrypoint = masp_per_entrypoint;
         options.workspace = workspace;
         options.packages = package;
+        options.features = features;
+        options.all_features = all_features;
+        options.no_default_features = no_default_features;
+        options.locked = locked;
+        options.offline = offline;
+        options.cargo_target_dir = cargo_target_dir;
         options.parse_only = parse_only;
         options.analyze_only = analyze_only;
         options.link_only = link_only;
         options.no_link = no_link;
+        options.stop_after = stop_after;
+        options.start_from = start_from;
         options.lint = lint;
         options.cargo_frontmatter = cargo_frontmatter;
         options.print_cfg_after_all = print_cfg_after_all;
@@ -750,16 +1228,24 @@ impl Compiler {
         options.print_ir_after_all = print_ir_after_all;
         options.print_ir_after_pass = print_ir_after_pass;
         options.print_ir_after_modified = print_ir_after_modified;
+        options.ir_dumps = ir_dumps;
+        options.time_passes = time_passes;
+        options.verify_each = verify_each;
         options.print_ir_filters = print_ir_filter;
         options.print_hir_source_locations = print_hir_source_locations;
         options.remap_path_prefixes = remap_path_prefixes;
+        options.deterministic = deterministic;
+        options.nest_outputs = nest_outputs;
+        options.no_cache = no_cache;
+        options.no_masm_opt = no_masm_opt;
+        options.demangle_procedure_names = demangle_procedure_names;
+        options.float_mode = float_mode;
+        options.kernel_abi = kernel_abi;
+        options.cancel_token = cancel_token;
 
         #[cfg(feature = "std")]
         if options.remap_path_prefixes.is_empty() {
-            options.remap_path_prefixes.push(RemapPathPrefix {
-                from: options.current_dir.clone().into_boxed_path(),
-                to: None,
-            });
+            options.remap_working_dir();
         }
 
         options
@@ -45,8 +45,15 @@ pub fn compile(context: Rc<Context>) -> CompilerResult<()> {
     log::info!(target: "driver", "starting compilation session");
 
     let session = context.session();
-    match compile_to_memory(context.clone())? {
-        Artifact::Assembled(ref package) => {
+    let result = compile_to_memory(context.clone());
+
+    #[cfg(feature = "std")]
+    if session.options.time_passes {
+        std::eprintln!("{}", session.statistics.timings_report());
+    }
+
+    match result? {
+        Artifact::Assembled { ref package, .. } => {
             log::info!(
                 "succesfully assembled mast package '{}' with digest {}",
                 package.name,
@@ -56,6 +63,10 @@ pub fn compile(context: Rc<Context>) -> CompilerResult<()> {
                 .emit(OutputMode::Text, package)
                 .map_err(Report::msg)
                 .wrap_err("failed to pretty print 'mast' artifact")?;
+            // With `--emit=masp-per-entrypoint`, the packages were already written during assembly
+            if session.options.masp_per_entrypoint {
+                return Ok(());
+            }
             session
                 .emit(OutputMode::Binary, package)
                 .map_err(Report::msg)
@@ -99,6 +110,33 @@ where
     stages.run(input, context)
 }
 
+/// Same as `compile_to_memory`, but allows registering a callback which will be used as an extra
+/// compiler stage immediately after the inputs are parsed and linked, and prior to applying the
+/// rewrite pipeline, e.g. to instrument or legalize the IR of the linked component.
+pub fn compile_to_memory_with_pre_rewrite_stage<F>(
+    context: Rc<Context>,
+    pre_rewrite_stage: &mut F,
+) -> CompilerResult<Artifact>
+where
+    F: FnMut(MidenComponent, Rc<Context>) -> CompilerResult<MidenComponent>,
+{
+    let mut stages = ParseComponentStage
+        .next(
+            pre_rewrite_stage
+                as &mut (
+                         dyn FnMut(MidenComponent, Rc<Context>) -> CompilerResult<MidenComponent>
+                             + '_
+                     ),
+        )
+        .map(stages::apply_rewrites_to_miden_component)
+        .next(CodegenStage)
+        .next(AssembleStage);
+
+    let session = context.session_rc();
+    let input = session.input.clone().ok_or_else(|| Report::msg("no inputs"))?;
+    stages.run(input, context)
+}
+
 /// Compile the current inputs without lowering to Miden Assembly.
 ///
 /// Returns the translated pre-link outputs of the compiler's link stage.
@@ -121,6 +159,34 @@ pub fn compile_to_unoptimized_hir(context: Rc<Context>) -> CompilerResult<MidenC
     stages.run(input, context)
 }
 
+/// Check the Cargo project given as the input of the current session for errors, by running
+/// `cargo check` for the Wasm target, without producing any artifacts.
+#[cfg(feature = "std")]
+pub fn check_cargo_project(context: Rc<Context>) -> CompilerResult<()> {
+    let session = context.session_rc();
+    let input = session.input.clone().ok_or_else(|| Report::msg("no inputs"))?;
+    let midenc_session::InputType::Real(manifest_path) = &input.file else {
+        return Err(Report::msg("expected the path to a Cargo manifest as input"));
+    };
+    stages::cargo_check(manifest_path, session.options.clone())
+}
+
+/// Check the Cargo project given as the input of the current session like
+/// [`check_cargo_project`], writing the WIT worlds synthesized by the Miden SDK macros of the
+/// project to `wit_dir`.
+#[cfg(feature = "std")]
+pub fn expand_cargo_project_wit(
+    context: Rc<Context>,
+    wit_dir: &std::path::Path,
+) -> CompilerResult<()> {
+    let session = context.session_rc();
+    let input = session.input.clone().ok_or_else(|| Report::msg("no inputs"))?;
+    let midenc_session::InputType::Real(manifest_path) = &input.file else {
+        return Err(Report::msg("expected the path to a Cargo manifest as input"));
+    };
+    stages::cargo_expand_wit(manifest_path, session.options.clone(), wit_dir)
+}
+
 /// Lowers previously-generated pre-link outputs of the compiler to Miden Assembly/MAST.
 ///
 /// Returns the compiled artifact, just like `compile_to_memory` would.
@@ -87,6 +87,14 @@ pub fn get_sysroot(toolchain: Option<&str>) -> CompilerResult<PathBuf> {
     Ok(sysroot)
 }
 
+/// Returns the Cargo home directory, which holds the sources of the dependencies fetched by Cargo
+pub fn cargo_home() -> Option<PathBuf> {
+    match env::var_os("CARGO_HOME") {
+        Some(cargo_home) if !cargo_home.is_empty() => Some(PathBuf::from(cargo_home)),
+        _ => env::home_dir().map(|home| home.join(".cargo")),
+    }
+}
+
 pub fn spawn_cargo(mut cmd: Command, cargo: &Path) -> CompilerResult<Vec<Artifact>> {
     use std::io::BufRead;
 
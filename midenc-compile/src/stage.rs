@@ -14,9 +14,61 @@ pub trait Stage {
         true
     }
 
+    /// The name of this stage, used to report the time spent in it when `-Z time-passes` is set
+    ///
+    /// Stages which are composed of other stages, e.g. [Chain], should not have a name, so that
+    /// time is not counted more than once.
+    fn name(&self) -> Option<&'static str> {
+        None
+    }
+
     /// Run this stage
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output>;
 
+    /// Run this stage, recording the time spent in it if this stage has a name, and
+    /// `-Z time-passes` is set
+    fn run_timed(
+        &mut self,
+        input: Self::Input,
+        context: Rc<Context>,
+    ) -> CompilerResult<Self::Output> {
+        let session = context.session_rc();
+
+        // Stage boundaries are where cancellation is checked most often
+        if session.options.cancel_token.is_canceled() {
+            return Err(CompilerStopped("canceled").into());
+        }
+
+        #[cfg(feature = "std")]
+        let result = if let Some(name) = self.name().filter(|_| session.options.time_passes) {
+            let start = std::time::Instant::now();
+            let result = self.run(input, context);
+            session.statistics.timings.record(
+                midenc_session::TimingKind::Stage,
+                name,
+                start.elapsed(),
+            );
+            result
+        } else {
+            self.run(input, context)
+        };
+        #[cfg(not(feature = "std"))]
+        let result = self.run(input, context);
+
+        // Don't carry on compiling once the errors which are shown are all the user will see
+        if session.diagnostics.error_limit_reached() {
+            return Err(CompilerStopped("error limit reached").into());
+        }
+
+        // Cancellation observed within the stage, e.g. between passes, also stops the compiler
+        match result {
+            Err(err) if err.downcast_ref::<midenc_session::Canceled>().is_some() => {
+                Err(CompilerStopped("canceled").into())
+            }
+            result => result,
+        }
+    }
+
     fn next<S>(self, stage: S) -> Chain<Self, S>
     where
         Self: Sized,
@@ -102,11 +154,11 @@ where
         if !self.a.enabled(&context) {
             return Err(CompilerStopped("first stage of chain is disabled").into());
         }
-        let output = self.a.run(input, context.clone())?;
+        let output = self.a.run_timed(input, context.clone())?;
         if !self.b.enabled(&context) {
             return Err(CompilerStopped("second stage of chain is disabled").into());
         }
-        self.b.run(output, context)
+        self.b.run_timed(output, context)
     }
 }
 
@@ -136,11 +188,11 @@ where
         if !self.a.enabled(&context) {
             return Err(CompilerStopped("required stage in optional chain was not enabled").into());
         }
-        let output = self.a.run(input, context.clone())?;
+        let output = self.a.run_timed(input, context.clone())?;
         if !self.b.enabled(&context) {
             Ok(output)
         } else {
-            self.b.run(output, context)
+            self.b.run_timed(output, context)
         }
     }
 }
@@ -177,8 +229,8 @@ where
     fn run(&mut self, inputs: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         let mut outputs = Vec::default();
         for input in inputs.into_iter() {
-            outputs.push(self.spread.run(input, context.clone())?);
+            outputs.push(self.spread.run_timed(input, context.clone())?);
         }
-        self.join.run(outputs, context)
+        self.join.run_timed(outputs, context)
     }
 }
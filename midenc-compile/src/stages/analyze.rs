@@ -9,6 +9,10 @@ impl Stage for MasmAnalysisStage {
     type Input = Option<ProjectSourceInputs>;
     type Output = Option<ProjectSourceInputs>;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("analyze")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         let session = context.session();
         if session.options.lint {
@@ -71,6 +75,10 @@ impl Stage for ComponentAnalysisStage {
     type Input = MidenComponent;
     type Output = MidenComponent;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("analyze")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         let session = context.session();
         if session.options.lint {
@@ -1,7 +1,12 @@
-use alloc::sync::Arc;
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+};
 
 use miden_assembly::{ProjectSourceInputs, ProjectTargetSelector, utils::DisplayHex};
 use miden_mast_package::Package;
+use midenc_codegen_masm::MasmComponent;
+use midenc_session::{Producers, diagnostics::WrapErr};
 
 use super::*;
 
@@ -10,17 +15,51 @@ use super::*;
 /// The type of artifact depends on what outputs were requested, and what options were specified.
 pub enum Artifact {
     Lowered(CodegenOutput),
-    Assembled(Arc<Package>),
+    /// The output of the assembler stage
+    Assembled {
+        /// The assembled package
+        package: Arc<Package>,
+        /// The Miden Assembly the package was assembled from, if the `keep_masm` option was set
+        masm: Option<Arc<MasmComponent>>,
+    },
 }
 impl Artifact {
     pub fn unwrap_mast(self) -> Arc<Package> {
         match self {
-            Self::Assembled(mast) => mast,
+            Self::Assembled { package, .. } => package,
             Self::Lowered(_) => {
                 panic!("expected 'mast' artifact, but assembler stage was not run")
             }
         }
     }
+
+    /// Returns the Miden Assembly component this artifact was produced from, if available.
+    ///
+    /// This is always available for [Artifact::Lowered], but only available for
+    /// [Artifact::Assembled] if the `keep_masm` option was set during compilation, and the package
+    /// was generated by the compiler, i.e. not assembled from a Miden Assembly project.
+    pub fn masm_component(&self) -> Option<&Arc<MasmComponent>> {
+        match self {
+            Self::Lowered(output) => Some(&output.component),
+            Self::Assembled { masm, .. } => masm.as_ref(),
+        }
+    }
+
+    /// Returns the Miden Assembly modules this artifact was produced from, if available.
+    ///
+    /// See [Self::masm_component].
+    pub fn masm_modules(&self) -> Option<&[Arc<miden_assembly::ast::Module>]> {
+        self.masm_component().map(|component| component.modules.as_slice())
+    }
+
+    /// Returns the Miden Assembly this artifact was produced from, pretty-printed, if available.
+    ///
+    /// As with `--emit=masm`, the standard library and compiler intrinsics modules are omitted.
+    ///
+    /// See [Self::masm_component].
+    pub fn masm_text(&self) -> Option<String> {
+        self.masm_component().map(|component| component.to_string())
+    }
 }
 
 /// Perform assembly of the generated Miden Assembly, producing MAST
@@ -30,6 +69,10 @@ impl Stage for AssembleStage {
     type Input = CodegenOutput;
     type Output = Artifact;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("assemble")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         use midenc_hir::formatter::DisplayHex;
 
@@ -46,24 +89,50 @@ impl Stage for AssembleStage {
         let CodegenOutput {
             component,
             account_component_metadata_bytes,
+            producers,
         } = input;
 
         let mut registry = session.package_registry()?;
         load_cargo_source_dependencies_for_assembly(session, &mut registry)?;
-        let package = component.assemble_with_registry(
+        let mut package = component.assemble_with_registry(
             account_component_metadata_bytes.as_deref(),
             session,
             &mut registry,
         )?;
+        attach_producers(&mut package, producers);
 
         log::debug!(
             "successfully assembled package with digest {}",
             DisplayHex::new(&package.digest().as_bytes())
         );
-        Ok(Artifact::Assembled(package))
+
+        if session.options.masp_per_entrypoint {
+            if component.entrypoints.is_empty() {
+                return Err(Report::msg(
+                    "--emit=masp-per-entrypoint requires at least one --entrypoint",
+                ));
+            }
+            for entrypoint_package in component.packages_per_entrypoint(&package)? {
+                session
+                    .emit(OutputMode::Binary, &entrypoint_package)
+                    .into_diagnostic()
+                    .wrap_err("failed to serialize entrypoint package")?;
+            }
+        }
+
+        // Only retain the Miden Assembly when requested, so that it can be freed otherwise
+        let masm = session.options.keep_masm.then_some(component);
+        Ok(Artifact::Assembled { package, masm })
     }
 }
 
+/// Record the toolchains which produced `package`, along with this version of midenc, in the
+/// `producers` section of the package
+fn attach_producers(package: &mut Arc<Package>, mut producers: Producers) {
+    producers.insert_midenc();
+    Arc::make_mut(package).sections.push(producers.to_section());
+}
+
 fn load_cargo_source_dependencies_for_assembly(
     session: &midenc_session::Session,
     registry: &mut midenc_session::registry::HybridPackageRegistry,
@@ -114,6 +183,10 @@ impl Stage for AssembleProjectStage {
     type Input = Option<ProjectSourceInputs>;
     type Output = Artifact;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("assemble")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         let session = context.session();
         let package = session.project.package();
@@ -128,16 +201,20 @@ impl Stage for AssembleProjectStage {
             ProjectTargetSelector::Library
         };
 
-        let package = match input {
+        let mut package = match input {
             Some(sources) => project_assembler.assemble_with_sources(selector, "dev", sources)?,
             None => project_assembler.assemble(selector, "dev")?,
         };
+        attach_producers(&mut package, Producers::default());
 
         log::debug!(
             "successfully assembled package with digest {}",
             DisplayHex::new(&package.digest().as_bytes())
         );
 
-        Ok(Artifact::Assembled(package))
+        Ok(Artifact::Assembled {
+            package,
+            masm: None,
+        })
     }
 }
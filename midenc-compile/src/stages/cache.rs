@@ -0,0 +1,488 @@
+#[cfg(feature = "std")]
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+
+use midenc_session::InputFile;
+#[cfg(feature = "std")]
+use midenc_session::InputType;
+
+use super::*;
+
+/// This stage wraps a frontend stage, so that the component it translates from a given input is
+/// saved to a cache in the target directory, and reused by subsequent compilations of the same
+/// input, with the same compiler and options, rather than translating it again.
+///
+/// The cache is bypassed when `--no-cache` is set, and when the wrapped stage has side effects
+/// that cannot be reproduced from the cached component, i.e. emitting intermediate outputs, or
+/// stopping the compiler early.
+///
+/// A component is only cached if translating it reported no diagnostics, as they would not be
+/// reported again when the cached component is reused.
+pub struct CachedStage<S>(pub S);
+
+impl<S> Stage for CachedStage<S>
+where
+    S: Stage<Input = InputFile, Output = MidenComponent>,
+{
+    type Input = InputFile;
+    type Output = MidenComponent;
+
+    fn enabled(&self, context: &Context) -> bool {
+        self.0.enabled(context)
+    }
+
+    fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
+        #[cfg(feature = "std")]
+        if let Some(key) = cache_key(&input, context.session())? {
+            let cache_dir = context.session().options.target_dir.join("cache");
+            let entry = cache_dir.join(&key);
+            if let Some(component) = read_cached_component(&entry, context.clone()) {
+                log::debug!(target: "driver", "reusing cached translation of '{}'", input.file_name());
+                return Ok(component);
+            }
+
+            let reported = reported_diagnostics(context.session());
+            let component = self.0.run_timed(input, context.clone())?;
+            if reported_diagnostics(context.session()) == reported {
+                let written = write_cache_entry(&cache_dir, &key, |dir| {
+                    super::checkpoint::write_component(
+                        dir,
+                        CACHED_COMPONENT_STEM,
+                        &component,
+                        &context,
+                    )
+                });
+                // Failing to populate the cache only costs the next compilation some time
+                if let Err(err) = written {
+                    log::warn!(target: "driver", "failed to cache translated component: {err}");
+                }
+                prune_cache(&cache_dir, CACHE_SIZE_LIMIT);
+            }
+            return Ok(component);
+        }
+
+        self.0.run_timed(input, context)
+    }
+}
+
+/// The stem of the files a cached component is stored in, in the cache entry named after its key,
+/// see [super::checkpoint::write_component]
+#[cfg(feature = "std")]
+const CACHED_COMPONENT_STEM: &str = "component";
+
+/// The name of the file a cached package is stored in, in the cache entry named after its key
+#[cfg(feature = "std")]
+const CACHED_PACKAGE_FILE: &str = "package.masp";
+
+/// The total size of the entries kept in the cache, beyond which the least recently used ones are
+/// removed from it
+#[cfg(feature = "std")]
+const CACHE_SIZE_LIMIT: u64 = 256 * 1024 * 1024;
+
+/// Compile `input` with `compile`, unless the package it produces was cached by a previous
+/// compilation of the same input, with the same compiler and options, in which case that package
+/// is reused instead.
+///
+/// The package cache is only used when requested with [midenc_session::Options::package_cache],
+/// as `cargo miden build` does. Packages are cached in `<target-dir>/cache/<key>`, and reusing
+/// one is recorded in the session statistics, see
+/// [midenc_session::Statistics::is_cached_package_reused]. Once the cache exceeds
+/// [CACHE_SIZE_LIMIT] in total, the least recently used entries are removed.
+///
+/// The cache is bypassed under the same conditions as [CachedStage], as well as when outputs
+/// other than the package itself are requested, or the compiler would otherwise have side effects
+/// that cannot be reproduced from the cached package, e.g. printing IR. Like translated
+/// components, a package is only cached if compiling it reported no diagnostics.
+pub(super) fn with_package_cache(
+    input: InputFile,
+    context: Rc<Context>,
+    compile: impl FnOnce(InputFile, Rc<Context>) -> CompilerResult<Artifact>,
+) -> CompilerResult<Artifact> {
+    #[cfg(feature = "std")]
+    if let Some(key) = package_cache_key(&input, context.session())? {
+        let cache_dir = context.session().options.target_dir.join("cache");
+        let entry = cache_dir.join(&key);
+        if let Some(package) = read_cached_package(&entry) {
+            log::debug!(target: "driver", "reusing cached package for '{}'", input.file_name());
+            context.session().statistics.cached_package_reused();
+            return Ok(Artifact::Assembled {
+                package,
+                masm: None,
+            });
+        }
+
+        let reported = reported_diagnostics(context.session());
+        let artifact = compile(input, context.clone())?;
+        if let Artifact::Assembled { package, .. } = &artifact
+            && reported_diagnostics(context.session()) == reported
+        {
+            let written = write_cache_entry(&cache_dir, &key, |dir| {
+                use miden_core::serde::Serializable;
+                use midenc_session::diagnostics::WrapErr;
+
+                let path = dir.join(CACHED_PACKAGE_FILE);
+                std::fs::write(&path, package.to_bytes())
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("failed to write '{}'", path.display()))
+            });
+            // Failing to populate the cache only costs the next compilation some time
+            if let Err(err) = written {
+                log::warn!(target: "driver", "failed to cache package: {err}");
+            }
+            prune_cache(&cache_dir, CACHE_SIZE_LIMIT);
+        }
+        return Ok(artifact);
+    }
+
+    compile(input, context)
+}
+
+/// The number of errors and warnings reported so far in `session`
+#[cfg(feature = "std")]
+fn reported_diagnostics(session: &Session) -> usize {
+    session.diagnostics.error_count() + session.diagnostics.warning_count()
+}
+
+/// Read the component cached in `entry`, if any, marking it as the most recently used.
+///
+/// Entries which cannot be read are treated as missing, and removed so that they are replaced.
+#[cfg(feature = "std")]
+fn read_cached_component(entry: &std::path::Path, context: Rc<Context>) -> Option<MidenComponent> {
+    match super::checkpoint::read_component(entry, CACHED_COMPONENT_STEM, context) {
+        Ok(component) => {
+            let component = component?;
+            touch(&entry.join(format!("{CACHED_COMPONENT_STEM}.hir")));
+            Some(component)
+        }
+        Err(err) => {
+            let path = entry.display();
+            log::warn!(target: "driver", "ignoring invalid cached component '{path}': {err}");
+            remove_cache_entry(entry);
+            None
+        }
+    }
+}
+
+/// Read the package cached in `entry`, if any, marking it as the most recently used.
+///
+/// Entries which cannot be read are treated as missing, and removed so that they are replaced.
+#[cfg(feature = "std")]
+fn read_cached_package(entry: &std::path::Path) -> Option<Arc<miden_mast_package::Package>> {
+    use miden_core::serde::Deserializable;
+
+    let path = entry.join(CACHED_PACKAGE_FILE);
+    let bytes = std::fs::read(&path).ok()?;
+    let package = match miden_mast_package::Package::read_from_bytes(&bytes) {
+        Ok(package) => package,
+        Err(err) => {
+            let path = path.display();
+            log::warn!(target: "driver", "ignoring invalid cached package '{path}': {err}");
+            remove_cache_entry(entry);
+            return None;
+        }
+    };
+    touch(&path);
+    Some(Arc::new(package))
+}
+
+/// Mark the cache entry containing `path` as the most recently used, see [prune_cache]
+#[cfg(feature = "std")]
+fn touch(path: &std::path::Path) {
+    let touched = std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(std::time::SystemTime::now()));
+    if let Err(err) = touched {
+        log::debug!(target: "driver", "failed to update '{}': {err}", path.display());
+    }
+}
+
+/// Populate the cache entry `key` of `cache_dir`, by writing its files with `write`.
+///
+/// The files are written to a temporary directory, which is then renamed to the entry, so that
+/// an entry is never read while it is being written, even by a concurrent compilation. If the
+/// entry was populated concurrently, it is kept as is.
+#[cfg(feature = "std")]
+fn write_cache_entry(
+    cache_dir: &std::path::Path,
+    key: &str,
+    write: impl FnOnce(&std::path::Path) -> CompilerResult<()>,
+) -> CompilerResult<()> {
+    use midenc_session::diagnostics::WrapErr;
+
+    std::fs::create_dir_all(cache_dir)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("failed to create directory '{}'", cache_dir.display()))?;
+    let temp_dir = tempfile::Builder::new()
+        .prefix(TEMP_ENTRY_PREFIX)
+        .tempdir_in(cache_dir)
+        .into_diagnostic()
+        .wrap_err("failed to create a temporary cache entry")?;
+    write(temp_dir.path())?;
+
+    let entry = cache_dir.join(key);
+    match std::fs::rename(temp_dir.path(), &entry) {
+        Ok(()) => {
+            // The temporary directory was moved, so there is nothing left to remove
+            let _ = temp_dir.keep();
+            Ok(())
+        }
+        Err(_) if entry.is_dir() => Ok(()),
+        Err(err) => Err(err)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to write cache entry '{}'", entry.display())),
+    }
+}
+
+/// Remove the cache entry `entry`, if it exists
+#[cfg(feature = "std")]
+fn remove_cache_entry(entry: &std::path::Path) {
+    match std::fs::remove_dir_all(entry) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            let entry = entry.display();
+            log::warn!(target: "driver", "failed to remove cache entry '{entry}': {err}");
+        }
+        _ => (),
+    }
+}
+
+/// The prefix of the temporary directories cache entries are written to, see [write_cache_entry]
+#[cfg(feature = "std")]
+const TEMP_ENTRY_PREFIX: &str = ".tmp-";
+
+/// The age beyond which a temporary cache entry is considered abandoned, by a compilation which
+/// was interrupted while writing it, and is removed by [prune_cache]
+#[cfg(feature = "std")]
+const ABANDONED_ENTRY_AGE: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Remove the least recently used entries from `cache_dir`, be they translated components or
+/// packages, until the entries left in it do not exceed `limit` bytes in total.
+///
+/// The most recently used entry is always kept, even if it exceeds `limit` on its own. Temporary
+/// entries abandoned by interrupted compilations are removed as well.
+#[cfg(feature = "std")]
+fn prune_cache(cache_dir: &std::path::Path, limit: u64) {
+    let Ok(dir_entries) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    let now = std::time::SystemTime::now();
+    let mut entries = Vec::new();
+    for dir_entry in dir_entries.filter_map(Result::ok) {
+        let path = dir_entry.path();
+        let Some((last_used, size)) = cache_entry_usage(&path) else {
+            continue;
+        };
+        if dir_entry.file_name().to_string_lossy().starts_with(TEMP_ENTRY_PREFIX) {
+            if now.duration_since(last_used).is_ok_and(|age| age > ABANDONED_ENTRY_AGE) {
+                remove_cache_entry(&path);
+            }
+            continue;
+        }
+        entries.push((last_used, size, path));
+    }
+    let mut total = entries.iter().map(|(_, size, _)| size).sum::<u64>();
+    if total <= limit {
+        return;
+    }
+
+    entries.sort_by_key(|(last_used, ..)| *last_used);
+    let evictable = entries.len().saturating_sub(1);
+    for (_, size, entry) in entries.into_iter().take(evictable) {
+        if total <= limit {
+            break;
+        }
+        remove_cache_entry(&entry);
+        if !entry.exists() {
+            total -= size;
+        }
+    }
+}
+
+/// Get when the cache entry `entry` was last used, i.e. the latest modification time of its
+/// files, and their total size, or `None` if it is not a cache entry
+#[cfg(feature = "std")]
+fn cache_entry_usage(entry: &std::path::Path) -> Option<(std::time::SystemTime, u64)> {
+    let mut last_used =
+        entry.metadata().ok().filter(|metadata| metadata.is_dir())?.modified().ok()?;
+    let mut size = 0;
+    for file in std::fs::read_dir(entry).ok()?.filter_map(Result::ok) {
+        let Ok(metadata) = file.metadata() else {
+            continue;
+        };
+        size += metadata.len();
+        if let Ok(modified) = metadata.modified() {
+            last_used = last_used.max(modified);
+        }
+    }
+    Some((last_used, size))
+}
+
+/// Compute the cache key of the package compiled from `input`, or return `None` if the cache
+/// should not be used.
+///
+/// The key extends that of the translated component, see [cache_key], with the options which
+/// affect the code generated from it, and how it is assembled.
+#[cfg(feature = "std")]
+fn package_cache_key(input: &InputFile, session: &Session) -> CompilerResult<Option<String>> {
+    use core::hash::Hash;
+
+    use midenc_session::OutputType;
+
+    let options = &session.options;
+    let has_side_effects = options.analyze_only
+        || options.no_link
+        || options.stop_after.is_some()
+        || options.masp_per_entrypoint
+        || options.keep_masm
+        || options.save_temps
+        || options.lint
+        || options.print_cfg_after_all
+        || !options.print_cfg_after_pass.is_empty()
+        || !options.print_ir_before_stage.is_empty()
+        || options.print_ir_after_all
+        || !options.print_ir_after_pass.is_empty()
+        || options.ir_dumps.is_some()
+        || options
+            .output_types
+            .keys()
+            .any(|ty| !matches!(ty, OutputType::Mast | OutputType::Masp));
+    if !options.package_cache || has_side_effects {
+        return Ok(None);
+    }
+    let Some(component_key) = cache_key(input, session)? else {
+        return Ok(None);
+    };
+
+    let mut hasher = CacheKeyHasher::default();
+    component_key.hash(&mut hasher);
+    options.name.hash(&mut hasher);
+    options.entrypoints.hash(&mut hasher);
+    options.target.hash(&mut hasher);
+    format!("{:?}", options.target_type).hash(&mut hasher);
+    format!("{:?}", options.optimize).hash(&mut hasher);
+    format!("{:?}", options.link_modules).hash(&mut hasher);
+    options.search_paths.hash(&mut hasher);
+    options.sysroot.hash(&mut hasher);
+    options.toolchain.hash(&mut hasher);
+    options.no_masm_opt.hash(&mut hasher);
+    options.demangle_procedure_names.hash(&mut hasher);
+    for library in options.link_libraries.iter() {
+        format!("{:?}", library.linkage).hash(&mut hasher);
+    }
+    // The unstable `-Z` flags
+    format!("{:?}", options.flags).hash(&mut hasher);
+
+    Ok(Some(hasher.finish_key()))
+}
+
+/// Compute the cache key for `input`, or return `None` if the cache should not be used.
+///
+/// The key is derived from the input itself, the compiler binary, and the options which affect
+/// translation, so that changing any of them invalidates the cached component.
+#[cfg(feature = "std")]
+fn cache_key(input: &InputFile, session: &Session) -> CompilerResult<Option<String>> {
+    use core::hash::Hash;
+
+    use midenc_session::{OutputType, diagnostics::WrapErr};
+
+    let options = &session.options;
+    let has_side_effects = options.parse_only
+        || options.link_only
+        || session.should_emit(OutputType::Wat)
+        || session.should_emit(OutputType::Hir);
+    if options.no_cache || has_side_effects {
+        return Ok(None);
+    }
+    // The version and revision of the compiler do not change while working on it, so the cache
+    // is keyed on the binary of the compiler itself, which does
+    let Some(compiler) = compiler_identity() else {
+        return Ok(None);
+    };
+
+    let mut hasher = CacheKeyHasher::default();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    midenc_session::MIDENC_BUILD_REV.hash(&mut hasher);
+    compiler.hash(&mut hasher);
+    match &input.file {
+        InputType::Real(path) => {
+            // The file name is used to name the translated component
+            path.file_stem().hash(&mut hasher);
+            let bytes = std::fs::read(path)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("failed to read input '{}'", path.display()))?;
+            bytes.hash(&mut hasher);
+        }
+        InputType::Stdin { name, input } => {
+            name.as_str().hash(&mut hasher);
+            input.hash(&mut hasher);
+        }
+    }
+    input.file_type().to_string().hash(&mut hasher);
+    // Options consulted by the Wasm frontend
+    format!("{:?}", options.debug).hash(&mut hasher);
+    options.current_dir.hash(&mut hasher);
+    options.deterministic.hash(&mut hasher);
+    format!("{:?}", options.remap_path_prefixes).hash(&mut hasher);
+    format!("{:?}", options.float_mode).hash(&mut hasher);
+    // The feature selection of `cargo miden build`, which affects the Wasm produced by Cargo
+    options.features.hash(&mut hasher);
+    options.all_features.hash(&mut hasher);
+    options.no_default_features.hash(&mut hasher);
+    if let Some(path) = options.kernel_abi.as_deref() {
+        let bytes = std::fs::read(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read kernel ABI '{}'", path.display()))?;
+        bytes.hash(&mut hasher);
+    }
+    // The procedures exported by the linked libraries are mapped by the Wasm frontend
+    for library in options.link_libraries.iter() {
+        library.name.hash(&mut hasher);
+        if let Some(bytes) = library.bytes.as_deref() {
+            bytes.hash(&mut hasher);
+        } else if let Some(path) = library.path.as_deref() {
+            let bytes = std::fs::read(path)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("failed to read library '{}'", path.display()))?;
+            bytes.hash(&mut hasher);
+        }
+    }
+
+    Ok(Some(hasher.finish_key()))
+}
+
+/// Identify the binary of the running compiler by its path, size and modification time, or
+/// return `None` if it cannot be identified, in which case nothing is cached.
+#[cfg(feature = "std")]
+fn compiler_identity() -> Option<(std::path::PathBuf, u64, std::time::SystemTime)> {
+    let path = std::env::current_exe().ok()?;
+    let metadata = path.metadata().ok()?;
+    Some((path, metadata.len(), metadata.modified().ok()?))
+}
+
+/// Computes cache keys as BLAKE3 digests of the values hashed into it
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct CacheKeyHasher(blake3::Hasher);
+
+#[cfg(feature = "std")]
+impl CacheKeyHasher {
+    /// Get the key, i.e. the hex-encoded digest of the values hashed so far
+    fn finish_key(&self) -> String {
+        self.0.finalize().to_hex().to_string()
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::hash::Hasher for CacheKeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.finalize();
+        u64::from_le_bytes(digest.as_bytes()[..8].try_into().unwrap())
+    }
+}
@@ -11,6 +11,10 @@ impl Stage for CargoBuildStage {
     type Input = InputFile;
     type Output = InputFile;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("cargo-build")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         match input.file {
             #[cfg(not(feature = "std"))]
@@ -53,13 +57,50 @@ pub mod support {
 
     /// Executes a Cargo-based build with the provided compiler options and package registry
     pub fn cargo_build(
+        manifest_path: &Path,
+        compiler_opts: Box<midenc_session::Options>,
+        registry: Option<&mut HybridPackageRegistry>,
+    ) -> CompilerResult<Vec<InputFile>> {
+        run_cargo_command(manifest_path, compiler_opts, registry, false, None)
+    }
+
+    /// Executes `cargo check` for the Wasm target with the provided compiler options, reporting
+    /// any errors in the Rust sources without producing Wasm artifacts.
+    ///
+    /// The Miden dependencies of the project are still compiled, as they are needed to expand
+    /// the macros of the project.
+    pub fn cargo_check(
+        manifest_path: &Path,
+        compiler_opts: Box<midenc_session::Options>,
+    ) -> CompilerResult<()> {
+        run_cargo_command(manifest_path, compiler_opts, None, true, None).map(|_| ())
+    }
+
+    /// Executes `cargo check` for the Wasm target like [`cargo_check`], with the Miden SDK macros
+    /// of the project writing the WIT worlds they synthesize to `wit_dir`.
+    ///
+    /// The WIT is written while the macros are expanded, so it is available even if `cargo check`
+    /// fails afterwards, e.g. because bindings could not be generated from it.
+    pub fn cargo_expand_wit(
+        manifest_path: &Path,
+        compiler_opts: Box<midenc_session::Options>,
+        wit_dir: &Path,
+    ) -> CompilerResult<()> {
+        run_cargo_command(manifest_path, compiler_opts, None, true, Some(wit_dir)).map(|_| ())
+    }
+
+    fn run_cargo_command(
         manifest_path: &Path,
         mut compiler_opts: Box<midenc_session::Options>,
         registry: Option<&mut HybridPackageRegistry>,
+        check: bool,
+        wit_dir: Option<&Path>,
     ) -> CompilerResult<Vec<InputFile>> {
         // Extract cargo-specific options from parsed Compiler struct
         compiler_opts.manifest_path = Some(manifest_path.to_path_buf());
-        let cargo_opts = CargoOptions::from_compiler(&compiler_opts)?;
+        let mut cargo_opts = CargoOptions::from_compiler(&compiler_opts)?;
+        cargo_opts.check = check;
+        cargo_opts.wit_dir = wit_dir.map(Path::to_path_buf);
 
         let cwd = compiler_opts.current_dir.clone();
         let (project_dir, project_manifest_path) = match compiler_opts.manifest_path.as_mut() {
@@ -146,7 +187,7 @@ pub mod support {
                         None,
                         Arc::clone(&source_manager),
                     )?;
-                    outputs.push(output);
+                    outputs.extend(output);
                 }
                 outputs
             } else {
@@ -170,7 +211,7 @@ pub mod support {
                 modify_midenc_options_for_target(&project, &mut compiler_opts)?;
                 let output =
                     build_project(project, &compiler_opts, &cargo_opts, registry, source_manager)?;
-                vec![output]
+                output.into_iter().collect()
             }
         };
 
@@ -206,7 +247,7 @@ pub mod support {
         cargo_opts: &CargoOptions,
         registry: Option<&mut HybridPackageRegistry>,
         source_manager: Arc<dyn SourceManager + Send + Sync>,
-    ) -> CompilerResult<InputFile> {
+    ) -> CompilerResult<Option<InputFile>> {
         let package = project.package();
 
         let tmp = TempDir::new()
@@ -261,6 +302,16 @@ pub mod support {
             extra_rust_flags.push(' ');
             extra_rust_flags.push_str(explicit);
         }
+        // Keep the paths which are remapped in the package out of the Wasm module as well
+        if compiler_opts.deterministic {
+            for remap_prefix in compiler_opts.remap_path_prefixes.iter() {
+                extra_rust_flags.push_str(&format!(
+                    " --remap-path-prefix={}={}",
+                    remap_prefix.source_prefix().display(),
+                    remap_prefix.to.as_deref().unwrap_or(Path::new(".")).display()
+                ));
+            }
+        }
 
         let wasi = if compiler_opts.target_requires_protocol() {
             "wasip2"
@@ -268,17 +319,21 @@ pub mod support {
             "wasip1"
         };
 
-        let mut wasm_outputs = run_cargo(
-            wasi,
-            rustup_toolchain.as_deref(),
-            &cargo_build_args,
-            [("RUSTFLAGS", extra_rust_flags)],
-        )?;
+        let mut cargo_env = vec![("RUSTFLAGS", extra_rust_flags)];
+        if let Some(wit_dir) = cargo_opts.wit_dir.as_deref() {
+            // Read by the Miden SDK macros, see `EXPAND_WIT_DIR_ENV` in `miden-base-macros`
+            cargo_env.push(("MIDENC_EXPAND_WIT_DIR", wit_dir.display().to_string()));
+        }
+        let mut wasm_outputs =
+            run_cargo(wasi, rustup_toolchain.as_deref(), &cargo_build_args, cargo_env)?;
+        if cargo_opts.check {
+            return Ok(None);
+        }
 
         assert_eq!(wasm_outputs.len(), 1, "expected only one Wasm artifact");
         let wasm_output = wasm_outputs.pop().expect("expected at least one Wasm artifact");
 
-        Ok(InputFile::from_path(wasm_output).unwrap())
+        Ok(Some(InputFile::from_path(wasm_output).unwrap()))
     }
 
     /// Returns the Cargo profile value for a compiler optimization level.
@@ -294,7 +349,8 @@ pub mod support {
 
     /// Builds the argument vector for the underlying `cargo build` invocation.
     fn build_cargo_args(cargo_opts: &CargoOptions, opt_level: OptLevel) -> Vec<String> {
-        let mut args = vec!["build".to_string()];
+        let command = if cargo_opts.check { "check" } else { "build" };
+        let mut args = vec![command.to_string()];
 
         // Add build-std flags required for Miden compilation
         args.extend(
@@ -327,23 +383,7 @@ pub mod support {
         }
 
         // Forward cargo-specific options
-        if cargo_opts.release {
-            args.push("--release".to_string());
-        }
-
-        if let Some(ref manifest_path) = cargo_opts.manifest_path {
-            args.push("--manifest-path".to_string());
-            args.push(manifest_path.to_string_lossy().to_string());
-        }
-
-        if cargo_opts.workspace {
-            args.push("--workspace".to_string());
-        }
-
-        for package in &cargo_opts.packages {
-            args.push("--package".to_string());
-            args.push(package.to_string());
-        }
+        args.extend(cargo_opts.to_cargo_args());
 
         args
     }
@@ -400,6 +440,32 @@ pub mod support {
         Ok(outputs)
     }
 
+    /// Remaps the paths of the Cargo home directory, which holds the sources of dependencies, and
+    /// of the sysroot of the Rust toolchain, which differ from one machine to the next.
+    fn remap_toolchain_paths(options: &mut midenc_session::Options) {
+        let toolchain = crate::rust::rustup_toolchain();
+        let sysroot = match crate::rust::get_sysroot(toolchain.as_deref()) {
+            Ok(sysroot) => Some(sysroot),
+            Err(err) => {
+                log::warn!(target: "driver", "the Rust sysroot is not remapped: {err}");
+                None
+            }
+        };
+        let remaps = [(crate::rust::cargo_home(), "/cargo"), (sysroot, "/rustc-sysroot")];
+        for (from, to) in remaps {
+            let Some(from) = from else {
+                continue;
+            };
+            if options.remap_path_prefixes.iter().any(|remap| *remap.source_prefix() == *from) {
+                continue;
+            }
+            options.remap_path_prefixes.push(RemapPathPrefix {
+                from: from.into_boxed_path(),
+                to: Some(Path::new(to).to_path_buf().into_boxed_path()),
+            });
+        }
+    }
+
     /// Produces the `midenc` CLI flags implied by the detected target environment and project type.
     fn modify_midenc_options_for_target(
         project: &miden_project::Project,
@@ -409,7 +475,7 @@ pub mod support {
 
         // source paths in debug information.
         let package_source_dir = project.manifest_path().and_then(|path| path.parent());
-        if options.debug != midenc_session::DebugInfo::None
+        if (options.debug != midenc_session::DebugInfo::None || options.deterministic)
             && let Some(source_dir) = package_source_dir
         {
             options.remap_path_prefixes.push(RemapPathPrefix {
@@ -417,6 +483,9 @@ pub mod support {
                 to: None,
             });
         }
+        if options.deterministic {
+            remap_toolchain_paths(options);
+        }
 
         let target_type = match options.target_type {
             None => project
@@ -445,14 +514,14 @@ pub mod support {
                     ));
                 };
                 let masm_module_name = target.name.inner().replace('-', "_");
-                options.entrypoint = Some(format!("{masm_module_name}::entrypoint"));
+                options.entrypoints = vec![format!("{masm_module_name}::entrypoint")];
             }
             TargetType::Kernel => {
                 return Err(Report::msg("kernels are not currently supported via midenc"));
             }
             TargetType::Library | TargetType::AccountComponent | TargetType::Note => (),
             TargetType::TransactionScript => {
-                options.entrypoint = Some("miden:base/transaction-script@1.0.0::run".to_string());
+                options.entrypoints = vec!["miden:base/transaction-script@1.0.0::run".to_string()];
             }
             _ => return Err(Report::msg("unsupported --target-type: {target_type}")),
         }
@@ -0,0 +1,250 @@
+#[cfg(feature = "std")]
+use alloc::{string::String, vec::Vec};
+
+use midenc_session::Checkpoint;
+
+use super::*;
+
+/// This stage saves the linked component to the target directory, and stops the compiler, if
+/// `-C stop-after` names the checkpoint at which it was placed in the pipeline.
+///
+/// Otherwise, the component is passed through unchanged.
+pub struct SaveCheckpointStage(pub Checkpoint);
+
+impl Stage for SaveCheckpointStage {
+    type Input = MidenComponent;
+    type Output = MidenComponent;
+
+    fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
+        let checkpoint = self.0;
+        if context.session().options.stop_after != Some(checkpoint) {
+            return Ok(input);
+        }
+
+        save_checkpoint(checkpoint, &input, &context)?;
+
+        log::debug!(target: "driver", "stopping compiler early (stop-after={checkpoint})");
+        Err(CompilerStopped(match checkpoint {
+            Checkpoint::Link => "stop-after=link",
+            Checkpoint::Rewrite => "stop-after=rewrite",
+        })
+        .into())
+    }
+}
+
+/// This stage loads the component saved at the given checkpoint by a previous compilation with
+/// `-C stop-after`, in place of the stages which would have produced it.
+pub struct LoadCheckpointStage;
+
+impl Stage for LoadCheckpointStage {
+    type Input = Checkpoint;
+    type Output = MidenComponent;
+
+    fn name(&self) -> Option<&'static str> {
+        Some("checkpoint")
+    }
+
+    fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
+        log::debug!(target: "driver", "resuming compilation from checkpoint (start-from={input})");
+        load_checkpoint(input, context)
+    }
+}
+
+/// The files of a checkpoint are stored in `<target-dir>/checkpoints/<name>`, where `<name>` is
+/// the name of the current session:
+///
+/// * `<checkpoint>.hir`, the world containing the linked component, in IR assembly format
+/// * `<checkpoint>.component`, the identifier of the component to compile, if any
+/// * `<checkpoint>.metadata`, the serialized account component metadata, if any
+/// * `<checkpoint>.producers`, the serialized toolchains which produced the input, if known
+#[cfg(feature = "std")]
+fn checkpoint_dir(session: &Session) -> std::path::PathBuf {
+    session.options.target_dir.join("checkpoints").join(&session.name)
+}
+
+#[cfg(feature = "std")]
+fn save_checkpoint(
+    checkpoint: Checkpoint,
+    component: &MidenComponent,
+    context: &Rc<Context>,
+) -> CompilerResult<()> {
+    let dir = checkpoint_dir(context.session());
+    write_component(&dir, checkpoint.as_str(), component, context)?;
+
+    log::info!(target: "driver", "saved '{checkpoint}' checkpoint to '{}'", dir.display());
+
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+fn save_checkpoint(
+    _checkpoint: Checkpoint,
+    _component: &MidenComponent,
+    _context: &Rc<Context>,
+) -> CompilerResult<()> {
+    Err(Report::msg("checkpoints are only supported when the 'std' feature is enabled"))
+}
+
+#[cfg(feature = "std")]
+fn load_checkpoint(checkpoint: Checkpoint, context: Rc<Context>) -> CompilerResult<MidenComponent> {
+    let dir = checkpoint_dir(context.session());
+    read_component(&dir, checkpoint.as_str(), context)?.ok_or_else(|| {
+        Report::msg(format!(
+            "no '{checkpoint}' checkpoint was found in '{}': it must first be saved by compiling \
+             with `-C stop-after={checkpoint}`",
+            dir.display()
+        ))
+    })
+}
+
+#[cfg(not(feature = "std"))]
+fn load_checkpoint(
+    _checkpoint: Checkpoint,
+    _context: Rc<Context>,
+) -> CompilerResult<MidenComponent> {
+    Err(Report::msg("checkpoints are only supported when the 'std' feature is enabled"))
+}
+
+/// Write `component` to `dir`, as the files `<stem>.hir`, `<stem>.component`, `<stem>.metadata`
+/// and `<stem>.producers`, see [checkpoint_dir].
+#[cfg(feature = "std")]
+pub(super) fn write_component(
+    dir: &std::path::Path,
+    stem: &str,
+    component: &MidenComponent,
+    context: &Rc<Context>,
+) -> CompilerResult<()> {
+    use alloc::string::ToString;
+
+    use midenc_hir::{
+        Op, OpPrintingFlags,
+        print::{AsmPrinter, OpPrinter},
+    };
+    use midenc_session::diagnostics::WrapErr;
+
+    std::fs::create_dir_all(dir)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("failed to create directory '{}'", dir.display()))?;
+
+    // Source locations are included, so that the debug info of the final artifact is the same
+    // as if the component had not been written out
+    let flags = OpPrintingFlags {
+        print_entry_block_headers: false,
+        print_source_locations: true,
+    };
+    let mut printer = AsmPrinter::new(context.clone(), &flags);
+    component.world.borrow().as_operation().print(&mut printer);
+    let hir = printer.finish().to_string();
+
+    let component_id = component.component.as_ref().map(|component| component.borrow().id());
+    let component_id = component_id.map(|id| id.to_string());
+    write_checkpoint_file(
+        &dir.join(format!("{stem}.component")),
+        component_id.as_ref().map(|id| id.as_bytes()),
+    )?;
+    write_checkpoint_file(
+        &dir.join(format!("{stem}.metadata")),
+        component.account_component_metadata_bytes.as_deref(),
+    )?;
+    let producers = (!component.producers.is_empty()).then(|| component.producers.to_bytes());
+    write_checkpoint_file(&dir.join(format!("{stem}.producers")), producers.as_deref())?;
+    // The IR is written last, as its presence marks the component as complete
+    write_checkpoint_file(&dir.join(format!("{stem}.hir")), Some(hir.as_bytes()))
+}
+
+/// Read a component written to `dir` by [write_component], returning `None` if there is none.
+#[cfg(feature = "std")]
+pub(super) fn read_component(
+    dir: &std::path::Path,
+    stem: &str,
+    context: Rc<Context>,
+) -> CompilerResult<Option<MidenComponent>> {
+    use midenc_hir::{SymbolName, SymbolTable};
+
+    let path = dir.join(format!("{stem}.hir"));
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let config = midenc_hir::parse::ParserConfig {
+        context: context.clone(),
+        verify: true,
+    };
+    let op = midenc_hir::parse::parse_file_any(config, &path)?;
+    let world = op.try_downcast_op::<builtin::World>().map_err(|op| {
+        Report::msg(format!(
+            "invalid component in '{}': expected a world, got '{}'",
+            path.display(),
+            op.borrow().name()
+        ))
+    })?;
+
+    let component = match read_checkpoint_file(&dir.join(format!("{stem}.component")))? {
+        Some(id) => {
+            let id = String::from_utf8(id).into_diagnostic()?;
+            let symbol = world.borrow().get(SymbolName::intern(id.as_str())).ok_or_else(|| {
+                Report::msg(format!(
+                    "invalid component in '{}': component '{id}' is not defined",
+                    path.display()
+                ))
+            })?;
+            let component = symbol
+                .borrow()
+                .as_operation_ref()
+                .try_downcast_op::<builtin::Component>()
+                .map_err(|_| {
+                    Report::msg(format!(
+                        "invalid component in '{}': '{id}' is not a component",
+                        path.display()
+                    ))
+                })?;
+            Some(component)
+        }
+        None => None,
+    };
+    let account_component_metadata_bytes =
+        read_checkpoint_file(&dir.join(format!("{stem}.metadata")))?;
+    let producers = match read_checkpoint_file(&dir.join(format!("{stem}.producers")))? {
+        Some(bytes) => midenc_session::Producers::from_bytes(&bytes).map_err(|err| {
+            Report::msg(format!("invalid producers in '{}': {err}", path.display()))
+        })?,
+        None => Default::default(),
+    };
+
+    Ok(Some(MidenComponent {
+        world,
+        component,
+        account_component_metadata_bytes,
+        producers,
+    }))
+}
+
+/// Write `contents` to `path`, or remove a stale file at `path` if there are no contents
+#[cfg(feature = "std")]
+fn write_checkpoint_file(path: &std::path::Path, contents: Option<&[u8]>) -> CompilerResult<()> {
+    use midenc_session::diagnostics::WrapErr;
+
+    let result = match contents {
+        Some(contents) => std::fs::write(path, contents),
+        None => match std::fs::remove_file(path) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        },
+    };
+    result
+        .into_diagnostic()
+        .wrap_err_with(|| format!("failed to write checkpoint file '{}'", path.display()))
+}
+
+#[cfg(feature = "std")]
+fn read_checkpoint_file(path: &std::path::Path) -> CompilerResult<Option<Vec<u8>>> {
+    use midenc_session::diagnostics::WrapErr;
+
+    match std::fs::read(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read checkpoint file '{}'", path.display())),
+    }
+}
@@ -2,14 +2,14 @@ use alloc::{boxed::Box, sync::Arc, vec::Vec};
 
 use miden_assembly::ast::Module;
 use midenc_codegen_masm::{
-    self as masm, LegalizeForMasm, MasmComponent, ToMasmComponent,
+    self as masm, CycleReport, LegalizeForMasm, MasmComponent, ToMasmComponent,
     intrinsics::{
         ADVICE_INTRINSICS_MODULE_NAME, I32_INTRINSICS_MODULE_NAME, I64_INTRINSICS_MODULE_NAME,
         MEM_INTRINSICS_MODULE_NAME,
     },
 };
 use midenc_hir::pass::{AnalysisManager, IRPrintingConfig, Nesting, OpPassManager, PassManager};
-use midenc_session::OutputType;
+use midenc_session::{OutputFile, OutputType, Producers};
 
 use super::*;
 
@@ -17,6 +17,8 @@ pub struct CodegenOutput {
     pub component: Arc<MasmComponent>,
     /// The serialized AccountComponentMetadata (name, description, storage layout, etc.)
     pub account_component_metadata_bytes: Option<Vec<u8>>,
+    /// The toolchains which produced the input, to be recorded in the assembled package
+    pub producers: Producers,
 }
 
 /// Perform code generation on the possibly-linked output of previous stages
@@ -26,6 +28,10 @@ impl Stage for CodegenStage {
     type Input = MidenComponent;
     type Output = CodegenOutput;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("codegen")
+    }
+
     fn enabled(&self, context: &Context) -> bool {
         context.session().should_codegen()
     }
@@ -35,6 +41,7 @@ impl Stage for CodegenStage {
             world,
             component,
             account_component_metadata_bytes,
+            producers,
         } = input;
 
         log::debug!("lowering miden component to masm");
@@ -65,6 +72,20 @@ impl Stage for CodegenStage {
             session.emit(OutputMode::Text, masm_component.as_ref()).into_diagnostic()?;
         }
 
+        if session.should_emit(OutputType::CycleReport) {
+            // The report is emitted as JSON if written to a `.json` file, otherwise as a table
+            let mode = match session.output_files.output_file(OutputType::CycleReport, None) {
+                OutputFile::Real(path)
+                    if path.extension().and_then(|ext| ext.to_str()) == Some("json") =>
+                {
+                    OutputMode::Binary
+                }
+                _ => OutputMode::Text,
+            };
+            let report = CycleReport::new(&masm_component);
+            session.emit(mode, &report).into_diagnostic()?;
+        }
+
         if session.options.link_only {
             log::debug!("stopping compiler early (link-only=true)");
             return Err(CompilerStopped("link-only=true").into());
@@ -73,14 +94,19 @@ impl Stage for CodegenStage {
         Ok(CodegenOutput {
             component: Arc::from(masm_component),
             account_component_metadata_bytes,
+            producers,
         })
     }
 }
 
 fn legalize_for_masm(anchor: midenc_hir::OperationRef, context: Rc<Context>) -> CompilerResult<()> {
     let ir_print_config = IRPrintingConfig::try_from(context.session().options.as_ref())?;
+    let time_passes = context.session().options.time_passes;
+    let verify_each = context.session().options.verify_each;
     let mut pm = PassManager::new(context, OpPassManager::ANY, Nesting::Implicit)
         .enable_ir_printing(ir_print_config);
+    pm.enable_timing(time_passes);
+    pm.enable_verify_each(verify_each);
     pm.add_pass(Box::new(LegalizeForMasm));
     pm.run(anchor)?;
 
@@ -3,7 +3,7 @@ use alloc::{format, rc::Rc};
 use miden_assembly::ProjectSourceInputs;
 use midenc_hir::{Context, dialects::builtin};
 use midenc_session::{
-    FileType, OutputMode, Session,
+    Checkpoint, FileType, OutputMode, Session,
     diagnostics::{IntoDiagnostic, Report},
 };
 
@@ -12,15 +12,21 @@ use crate::{CompilerResult, CompilerStopped};
 
 mod analyze;
 mod assemble;
+mod cache;
 mod cargo;
+mod checkpoint;
 mod codegen;
 mod parse;
 mod rewrite;
 
+#[cfg(feature = "std")]
+pub use self::cargo::support::{cargo_check, cargo_expand_wit};
 pub use self::{
     analyze::{ComponentAnalysisStage, MasmAnalysisStage},
     assemble::{Artifact, AssembleProjectStage, AssembleStage},
+    cache::CachedStage,
     cargo::CargoBuildStage,
+    checkpoint::{LoadCheckpointStage, SaveCheckpointStage},
     codegen::{CodegenOutput, CodegenStage},
     parse::{
         MidenComponent, ParseComponentStage, ParseHirStage, ParseMasmStage, ParseRustStage,
@@ -35,6 +41,10 @@ pub fn run_default_pipeline(
 ) -> CompilerResult<Artifact> {
     use midenc_session::FileType;
 
+    if let Some(checkpoint) = context.session().options.start_from {
+        return resume_pipeline(checkpoint, context);
+    }
+
     let Some(input) = input else {
         return masm_project_pipeline(None, context);
     };
@@ -64,8 +74,9 @@ pub(crate) fn cargo_project_pipeline(
     context: Rc<Context>,
 ) -> CompilerResult<Artifact> {
     let mut build_project_stage = CargoBuildStage;
-    let wasm = build_project_stage.run(input, context.clone())?;
-    wasm_pipeline(wasm, context)
+    let wasm = build_project_stage.run_timed(input, context.clone())?;
+    // Cargo only rebuilds the Wasm when needed, so avoid compiling it again if it is unchanged
+    cache::with_package_cache(wasm, context, wasm_pipeline)
 }
 
 fn hir_pipeline(
@@ -73,8 +84,10 @@ fn hir_pipeline(
     context: Rc<Context>,
 ) -> CompilerResult<Artifact> {
     let mut stages = ParseHirStage
-        .next_optional(ApplyRewritesStage)
         .map(extract_miden_component_or_bail)
+        .next(SaveCheckpointStage(Checkpoint::Link))
+        .map(apply_rewrites_to_miden_component)
+        .next(SaveCheckpointStage(Checkpoint::Rewrite))
         .next(CodegenStage)
         .next(AssembleStage);
 
@@ -85,15 +98,48 @@ fn wasm_pipeline(
     input: midenc_session::InputFile,
     context: Rc<Context>,
 ) -> CompilerResult<Artifact> {
-    let mut stages = ParseWasmStage
+    let mut stages = CachedStage(ParseWasmStage)
         .next(ComponentAnalysisStage)
+        .next(SaveCheckpointStage(Checkpoint::Link))
         .map(apply_rewrites_to_miden_component)
+        .next(SaveCheckpointStage(Checkpoint::Rewrite))
         .next(CodegenStage)
         .next(AssembleStage);
 
     stages.run(input, context)
 }
 
+/// Resume compilation from the state saved at `checkpoint` by a previous compilation with
+/// `-C stop-after`, rather than from the session inputs
+fn resume_pipeline(checkpoint: Checkpoint, context: Rc<Context>) -> CompilerResult<Artifact> {
+    if let Some(stop_after) = context
+        .session()
+        .options
+        .stop_after
+        .filter(|stop_after| *stop_after <= checkpoint)
+    {
+        return Err(Report::msg(format!(
+            "cannot stop after the '{stop_after}' stage when starting from the '{checkpoint}' \
+             stage"
+        )));
+    }
+
+    match checkpoint {
+        Checkpoint::Link => {
+            let mut stages = LoadCheckpointStage
+                .map(apply_rewrites_to_miden_component)
+                .next(SaveCheckpointStage(Checkpoint::Rewrite))
+                .next(CodegenStage)
+                .next(AssembleStage);
+            stages.run(checkpoint, context)
+        }
+        Checkpoint::Rewrite => {
+            let mut stages = LoadCheckpointStage.next(CodegenStage).next(AssembleStage);
+            stages.run(checkpoint, context)
+        }
+    }
+}
+
 fn masm_source_pipeline(
     input: midenc_session::InputFile,
     context: Rc<Context>,
@@ -115,7 +161,7 @@ fn masm_project_pipeline(
         Box::new(|input: Option<midenc_session::InputFile>, context| match input {
             Some(input) if input.file_type() == FileType::Masm => {
                 let mut parse = ParseMasmStage;
-                parse.run(input, context).map(Some)
+                parse.run_timed(input, context).map(Some)
             }
             _ => Ok(None),
         })
@@ -135,7 +181,7 @@ fn rust_pipeline(
     context: Rc<Context>,
 ) -> CompilerResult<Artifact> {
     let mut parse_rust = ParseRustStage;
-    let output = parse_rust.run(input, context.clone())?;
+    let output = parse_rust.run_timed(input, context.clone())?;
     wasm_pipeline(output, context)
 }
 
@@ -174,7 +220,7 @@ pub fn apply_rewrites_to_miden_component(
         return Err(CompilerStopped("parse-only=true").into());
     }
     let mut rewrites = ApplyRewritesStage;
-    rewrites.run(component.world.as_operation_ref(), context)?;
+    rewrites.run_timed(component.world.as_operation_ref(), context)?;
 
     Ok(component)
 }
@@ -193,6 +239,7 @@ pub fn extract_miden_component_or_bail(
             world,
             component: None,
             account_component_metadata_bytes: None,
+            producers: Default::default(),
         })
     } else if let Ok(component) = op.try_downcast_op::<builtin::Component>() {
         let world = ensure_world_for_operation(op, context.clone())?;
@@ -200,6 +247,7 @@ pub fn extract_miden_component_or_bail(
             world,
             component: Some(component),
             account_component_metadata_bytes: None,
+            producers: Default::default(),
         })
     } else if let Ok(module) = op.try_downcast_op::<builtin::Module>() {
         if let Some(parent) = op.parent_op() {
@@ -209,12 +257,14 @@ pub fn extract_miden_component_or_bail(
                     world,
                     component: Some(component),
                     account_component_metadata_bytes: None,
+                    producers: Default::default(),
                 })
             } else if let Ok(world) = parent.try_downcast_op::<builtin::World>() {
                 Ok(MidenComponent {
                     world,
                     component: None,
                     account_component_metadata_bytes: None,
+                    producers: Default::default(),
                 })
             } else {
                 Err(Report::msg(format!(
@@ -228,6 +278,7 @@ pub fn extract_miden_component_or_bail(
                 world,
                 component: None,
                 account_component_metadata_bytes: None,
+                producers: Default::default(),
             })
         }
     } else {
@@ -16,7 +16,7 @@ use midenc_hir::{
 #[cfg(feature = "std")]
 use midenc_session::{FileName, Path};
 use midenc_session::{
-    InputFile, InputType,
+    InputFile, InputType, Producers,
     diagnostics::{IntoDiagnostic, WrapErr},
 };
 
@@ -30,6 +30,8 @@ pub struct MidenComponent {
     pub world: builtin::WorldRef,
     pub component: Option<builtin::ComponentRef>,
     pub account_component_metadata_bytes: Option<Vec<u8>>,
+    /// The toolchains which produced the input, to be recorded in the assembled package
+    pub producers: Producers,
 }
 
 /// Parses any input that can be converted to a [MidenComponent]
@@ -50,12 +52,21 @@ impl Stage for ParseComponentStage {
             }
             FileType::Wasm | FileType::Wat => {
                 let mut stage = ParseWasmStage;
-                stage.run(input, context)
+                stage.run_timed(input, context)
             }
             FileType::Rust => {
                 let mut stage = ParseRustStage.next(ParseWasmStage);
                 stage.run(input, context)
             }
+            FileType::Toml
+                if input
+                    .file_name()
+                    .file_name()
+                    .is_some_and(|name| name.eq_ignore_ascii_case("Cargo.toml")) =>
+            {
+                let mut stage = super::CargoBuildStage.next(ParseWasmStage);
+                stage.run(input, context)
+            }
             file_type => Err(Report::msg(format!(
                 "unsupported file type '{file_type}' for parsing miden components"
             ))),
@@ -11,6 +11,10 @@ impl Stage for ParseMasmStage {
     type Input = InputFile;
     type Output = ProjectSourceInputs;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("parse")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         let file_type = input.file_type();
         if !matches!(input.file_type(), midenc_session::FileType::Masm) {
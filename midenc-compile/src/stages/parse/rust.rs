@@ -20,6 +20,10 @@ impl Stage for ParseRustStage {
     type Input = InputFile;
     type Output = InputFile;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("cargo-build")
+    }
+
     #[cfg(not(feature = "std"))]
     fn run(&mut self, _input: Self::Input, _context: Rc<Context>) -> CompilerResult<Self::Output> {
         Err(Report::msg("compilation of Rust sources in no-std builds is unsupported"))
@@ -1,4 +1,6 @@
-use midenc_frontend_wasm::{FrontendOutput, WasmTranslationConfig};
+use alloc::sync::Arc;
+
+use midenc_frontend_wasm::{FrontendOutput, ImportMap, WasmTranslationConfig};
 #[cfg(feature = "std")]
 use midenc_frontend_wasm::{WatEmit, wasm_to_wat};
 #[cfg(feature = "std")]
@@ -15,6 +17,10 @@ impl Stage for ParseWasmStage {
     type Input = InputFile;
     type Output = MidenComponent;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("parse")
+    }
+
     fn run(&mut self, input: Self::Input, context: Rc<Context>) -> CompilerResult<Self::Output> {
         use midenc_hir::{BuilderExt, OpBuilder, SourceSpan};
 
@@ -57,6 +63,7 @@ impl Stage for ParseWasmStage {
         let FrontendOutput {
             component,
             account_component_metadata_bytes,
+            producers,
         } = match input {
             #[cfg(feature = "std")]
             InputType::Real(path) => {
@@ -70,6 +77,8 @@ impl Stage for ParseWasmStage {
                     remap_path_prefixes: context.session().options.remap_path_prefixes.clone(),
                     world: Some(world),
                     generate_native_debuginfo: context.session().options.emit_source_locations(),
+                    float_mode: context.session().options.float_mode,
+                    import_map: self.import_map(&context)?,
                     ..Default::default()
                 };
                 self.parse_hir_from_wasm_bytes(&input, context.clone(), &config)?
@@ -94,6 +103,7 @@ impl Stage for ParseWasmStage {
             world,
             component: Some(component),
             account_component_metadata_bytes,
+            producers,
         })
     }
 }
@@ -158,11 +168,67 @@ impl ParseWasmStage {
             remap_path_prefixes: context.session().options.remap_path_prefixes.clone(),
             world: Some(world),
             generate_native_debuginfo: context.session().options.emit_source_locations(),
+            float_mode: context.session().options.float_mode,
+            import_map: self.import_map(&context)?,
             ..Default::default()
         };
         self.parse_hir_from_wasm_bytes(&bytes, context, &config)
     }
 
+    /// Get the mapping of Wasm imports to Miden Assembly procedures, extended with the mappings of
+    /// the `--kernel-abi` file, if one was given, and with the procedures of the linked libraries
+    fn import_map(&self, context: &Rc<Context>) -> CompilerResult<Arc<ImportMap>> {
+        let session = context.session();
+        let import_map = match session.options.kernel_abi.as_deref() {
+            #[cfg(feature = "std")]
+            Some(path) => Arc::new(ImportMap::load(context, path)?),
+            #[cfg(not(feature = "std"))]
+            Some(path) => {
+                return Err(Report::msg(format!(
+                    "unable to load the kernel ABI file '{}': loading a kernel ABI file requires \
+                     the 'std' feature",
+                    path.display()
+                )));
+            }
+            None => ImportMap::builtin(),
+        };
+        #[cfg(feature = "std")]
+        let import_map = self.map_library_exports(import_map, session)?;
+        Ok(import_map)
+    }
+
+    /// Map the procedures exported with a signature by the linked libraries, other than those
+    /// shipped with the compiler, so that they can be called from Wasm
+    #[cfg(feature = "std")]
+    fn map_library_exports(
+        &self,
+        mut import_map: Arc<ImportMap>,
+        session: &midenc_session::Session,
+    ) -> CompilerResult<Arc<ImportMap>> {
+        use miden_mast_package::PackageExport;
+
+        for library in session.options.link_libraries.iter() {
+            if library.is_core() || library.is_protocol() || library.is_softfloat() {
+                continue;
+            }
+            let package = library.load(&session.options)?;
+            for export in package.manifest.exports() {
+                let PackageExport::Procedure(export) = export else {
+                    continue;
+                };
+                let Some(signature) = export.signature.clone() else {
+                    continue;
+                };
+                let path = export.path.to_string();
+                let Some((module, name)) = path.trim_start_matches("::").rsplit_once("::") else {
+                    continue;
+                };
+                Arc::make_mut(&mut import_map).insert_library_export(module, name, signature);
+            }
+        }
+        Ok(import_map)
+    }
+
     fn parse_hir_from_wasm_bytes(
         &self,
         bytes: &[u8],
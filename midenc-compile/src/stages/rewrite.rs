@@ -3,13 +3,14 @@ use alloc::boxed::Box;
 use midenc_dialect_hir::transforms::{Local2Reg, TransformSpills};
 use midenc_dialect_scf::transforms::LiftControlFlowToSCF;
 use midenc_hir::{
-    pass::{IRPrintingConfig, Nesting, PassManager},
+    pass::{IRPrintingConfig, Nesting, OpPassManager, PassManager},
     patterns::{GreedyRewriteConfig, RegionSimplificationLevel},
 };
 use midenc_hir_transform::{
-    Canonicalizer, CommonSubexpressionElimination, SinkOperandDefs,
+    Canonicalizer, CommonSubexpressionElimination, DeadCodeElimination, SinkOperandDefs,
     SparseConditionalConstantPropagation,
 };
+use midenc_session::OptLevel;
 
 use super::*;
 
@@ -20,6 +21,10 @@ impl Stage for ApplyRewritesStage {
     type Input = midenc_hir::OperationRef;
     type Output = midenc_hir::OperationRef;
 
+    fn name(&self) -> Option<&'static str> {
+        Some("rewrite")
+    }
+
     fn enabled(&self, context: &Context) -> bool {
         !context.session().options.parse_only
     }
@@ -54,49 +59,26 @@ impl Stage for ApplyRewritesStage {
         let ir_print_config = IRPrintingConfig::try_from(context.session().options.as_ref())?;
         let mut pm = PassManager::on::<builtin::World>(context.clone(), Nesting::Implicit)
             .enable_ir_printing(ir_print_config);
+        pm.enable_timing(context.session().options.time_passes);
+        pm.enable_verify_each(context.session().options.verify_each);
 
         let mut rewrite_config = GreedyRewriteConfig::default();
         rewrite_config.with_region_simplification_level(RegionSimplificationLevel::Normal);
 
         // Component passes
+        let opt_level = context.session().options.optimize;
         {
             let mut component_pm = pm.nest::<builtin::Component>();
             // Function passes for module-level functions
             {
                 let mut module_pm = component_pm.nest::<builtin::Module>();
                 let mut func_pm = module_pm.nest::<builtin::Function>();
-                func_pm.add_pass(Canonicalizer::create_with_config(&rewrite_config));
-                func_pm.add_pass(Box::new(CommonSubexpressionElimination));
-                func_pm.add_pass(Box::new(SparseConditionalConstantPropagation));
-                func_pm.add_pass(Box::new(SinkOperandDefs));
-                //func_pm.add_pass(Box::new(ControlFlowSink));
-                func_pm.add_pass(Box::new(Local2Reg));
-                func_pm.add_pass(Box::new(TransformSpills));
-                func_pm.add_pass(Box::new(LiftControlFlowToSCF));
-                // Re-run canonicalization to clean up generated structured control flow
-                func_pm.add_pass(Canonicalizer::create_with_config(&rewrite_config));
-                func_pm.add_pass(Box::new(SinkOperandDefs));
-                func_pm.add_pass(Box::new(TransformSpills));
-                //func_pm.add_pass(Box::new(ControlFlowSink));
-                //func_pm.add_pass(Box::new(DeadCodeElimination));
+                add_function_passes(&mut func_pm, opt_level, &rewrite_config);
             }
             // Function passes for component-level functions
             {
                 let mut func_pm = component_pm.nest::<builtin::Function>();
-                func_pm.add_pass(Canonicalizer::create_with_config(&rewrite_config));
-                func_pm.add_pass(Box::new(CommonSubexpressionElimination));
-                func_pm.add_pass(Box::new(SparseConditionalConstantPropagation));
-                func_pm.add_pass(Box::new(SinkOperandDefs));
-                //func_pm.add_pass(Box::new(ControlFlowSink));
-                func_pm.add_pass(Box::new(Local2Reg));
-                func_pm.add_pass(Box::new(TransformSpills));
-                func_pm.add_pass(Box::new(LiftControlFlowToSCF));
-                // Re-run canonicalization to clean up generated structured control flow
-                func_pm.add_pass(Canonicalizer::create_with_config(&rewrite_config));
-                func_pm.add_pass(Box::new(SinkOperandDefs));
-                func_pm.add_pass(Box::new(TransformSpills));
-                //func_pm.add_pass(Box::new(ControlFlowSink));
-                //func_pm.add_pass(Box::new(DeadCodeElimination));
+                add_function_passes(&mut func_pm, opt_level, &rewrite_config);
             }
         }
 
@@ -119,3 +101,40 @@ impl Stage for ApplyRewritesStage {
         }
     }
 }
+
+/// Populate `func_pm` with the function-level rewrite pipeline for `opt_level`
+///
+/// At [OptLevel::None], only the passes required to produce code we can lower to Miden Assembly
+/// are run, i.e. spill insertion and lifting unstructured control flow to SCF. All other levels
+/// additionally run canonicalization and constant propagation, with [OptLevel::Max] also
+/// eliminating dead code once control flow has been lifted.
+fn add_function_passes(
+    func_pm: &mut OpPassManager,
+    opt_level: OptLevel,
+    rewrite_config: &GreedyRewriteConfig,
+) {
+    if opt_level == OptLevel::None {
+        func_pm.add_pass(Box::new(Local2Reg));
+        func_pm.add_pass(Box::new(TransformSpills));
+        func_pm.add_pass(Box::new(LiftControlFlowToSCF));
+        func_pm.add_pass(Box::new(TransformSpills));
+        return;
+    }
+
+    func_pm.add_pass(Canonicalizer::create_with_config(rewrite_config));
+    func_pm.add_pass(Box::new(CommonSubexpressionElimination));
+    func_pm.add_pass(Box::new(SparseConditionalConstantPropagation));
+    func_pm.add_pass(Box::new(SinkOperandDefs));
+    //func_pm.add_pass(Box::new(ControlFlowSink));
+    func_pm.add_pass(Box::new(Local2Reg));
+    func_pm.add_pass(Box::new(TransformSpills));
+    func_pm.add_pass(Box::new(LiftControlFlowToSCF));
+    // Re-run canonicalization to clean up generated structured control flow
+    func_pm.add_pass(Canonicalizer::create_with_config(rewrite_config));
+    func_pm.add_pass(Box::new(SinkOperandDefs));
+    func_pm.add_pass(Box::new(TransformSpills));
+    //func_pm.add_pass(Box::new(ControlFlowSink));
+    if opt_level == OptLevel::Max {
+        func_pm.add_pass(Box::new(DeadCodeElimination));
+    }
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::{path::Path, rc::Rc};
+
+use midenc_compile::compile_to_memory;
+use midenc_hir::{Context, formatter::DisplayHex};
+use midenc_session::TimingKind;
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $two (result i32)
+    i32.const 2)
+  (func $five (export "five") (result i32)
+    call $two
+    i32.const 3
+    i32.add)
+)
+"#;
+
+struct Compiled {
+    /// The digest of the compiled package
+    digest: String,
+    /// Whether the frontend translated the input, rather than reusing a cached translation
+    translated: bool,
+}
+
+/// Compile [WAT] in `dir` with the given extra arguments
+fn compile(dir: &Path, args: &[&str]) -> Compiled {
+    let argv = [&["-Z", "time-passes"][..], args].concat();
+    let session = wat_session(dir, "five.wat", WAT, &argv);
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    let package = match compile_to_memory(context.clone()) {
+        Ok(artifact) => artifact.unwrap_mast(),
+        Err(err) => panic!("compilation failed: {err}"),
+    };
+    let translated = context.session().statistics.timings.get(TimingKind::Stage, "parse").is_some();
+    Compiled {
+        digest: DisplayHex::new(&package.digest().as_bytes()).to_string(),
+        translated,
+    }
+}
+
+#[test]
+fn unchanged_input_is_not_translated_again() {
+    let temp = tempfile::tempdir().unwrap();
+
+    let first = compile(temp.path(), &[]);
+    assert!(first.translated);
+
+    let second = compile(temp.path(), &[]);
+    assert!(!second.translated, "expected the cached translation to be reused");
+    assert_eq!(second.digest, first.digest);
+}
+
+#[test]
+fn cache_is_invalidated_by_changes() {
+    let temp = tempfile::tempdir().unwrap();
+
+    let first = compile(temp.path(), &[]);
+    assert!(first.translated);
+
+    // Options which affect translation
+    let changed = compile(temp.path(), &["--debug=none"]);
+    assert!(changed.translated);
+
+    // The input itself
+    let input = WAT.replace("i32.const 3", "i32.const 4");
+    std::fs::write(temp.path().join("five.wat"), input).unwrap();
+    let changed = compile(temp.path(), &[]);
+    assert!(changed.translated);
+    assert_ne!(changed.digest, first.digest);
+}
+
+#[test]
+fn no_cache_always_translates() {
+    let temp = tempfile::tempdir().unwrap();
+
+    let first = compile(temp.path(), &["--no-cache"]);
+    assert!(first.translated);
+
+    let second = compile(temp.path(), &["--no-cache"]);
+    assert!(second.translated);
+    assert_eq!(second.digest, first.digest);
+
+    // Nothing was cached by the previous compilations
+    let third = compile(temp.path(), &[]);
+    assert!(third.translated);
+}
+
+#[test]
+fn invalid_cache_entry_is_translated_again() {
+    let temp = tempfile::tempdir().unwrap();
+
+    let first = compile(temp.path(), &[]);
+    assert!(first.translated);
+
+    let cache_dir = temp.path().join("target").join("cache");
+    for entry in std::fs::read_dir(&cache_dir).unwrap() {
+        let hir = entry.unwrap().path().join("component.hir");
+        if hir.exists() {
+            std::fs::write(hir, "not a component").unwrap();
+        }
+    }
+
+    let second = compile(temp.path(), &[]);
+    assert!(second.translated, "expected the invalid cache entry to be ignored");
+    assert_eq!(second.digest, first.digest);
+
+    // The invalid entry was replaced
+    let third = compile(temp.path(), &[]);
+    assert!(!third.translated);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::{
+    rc::Rc,
+    sync::{
+        Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+};
+
+use midenc_compile::{CompilerStopped, compile_to_memory};
+use midenc_hir::Context;
+use midenc_session::Session;
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $the_answer (export "the_answer") (result i32)
+    i32.const 42)
+)
+"#;
+
+/// The message logged by the codegen stage before it runs its first pass
+const CODEGEN_STARTED: &str = "lowering miden component to masm";
+
+fn new_session(temp: &tempfile::TempDir) -> Session {
+    wat_session(temp.path(), "answer.wat", WAT, &[])
+}
+
+fn assert_canceled<T>(result: Result<T, midenc_session::diagnostics::Report>) {
+    match result {
+        Ok(_) => panic!("expected compilation to be canceled"),
+        Err(err) => {
+            let stopped = err.downcast_ref::<CompilerStopped>();
+            assert!(
+                stopped.is_some_and(|stopped| stopped.to_string().contains("canceled")),
+                "{err}"
+            );
+        }
+    }
+}
+
+#[test]
+fn canceled_compilation_stops() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = new_session(&temp);
+    let cancel_token = session.cancel_token();
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    std::thread::spawn(move || cancel_token.cancel()).join().unwrap();
+
+    assert_canceled(compile_to_memory(context));
+}
+
+/// A logger which, once the codegen stage starts, hands over to the thread which cancels the
+/// compilation, and waits for it to have done so before letting the compiler carry on
+struct HandOverAtCodegen {
+    started: Mutex<Option<Sender<()>>>,
+    canceled: Mutex<Receiver<()>>,
+}
+
+impl log::Log for HandOverAtCodegen {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.args().to_string() != CODEGEN_STARTED {
+            return;
+        }
+        if let Some(started) = self.started.lock().unwrap().take() {
+            started.send(()).unwrap();
+            self.canceled.lock().unwrap().recv().unwrap();
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn compilation_canceled_from_another_thread_stops_at_the_next_pass() {
+    let (started_tx, started_rx) = mpsc::channel();
+    let (canceled_tx, canceled_rx) = mpsc::channel();
+    let logger: &'static HandOverAtCodegen = Box::leak(Box::new(HandOverAtCodegen {
+        started: Mutex::new(Some(started_tx)),
+        canceled: Mutex::new(canceled_rx),
+    }));
+    log::set_logger(logger).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let temp = tempfile::tempdir().unwrap();
+    let session = new_session(&temp);
+    let cancel_token = session.cancel_token();
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    // The compilation is canceled while the compiler is between the start of codegen and its first
+    // pass, which must observe it
+    let canceler = std::thread::spawn(move || {
+        started_rx.recv().expect("the compiler never reached codegen");
+        cancel_token.cancel();
+        canceled_tx.send(()).unwrap();
+    });
+
+    let result = compile_to_memory(context);
+    // If codegen was never reached, this lets the canceling thread fail rather than wait forever
+    logger.started.lock().unwrap().take();
+    canceler.join().unwrap();
+    assert_canceled(result);
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use midenc_compile::{Compiler, cargo::CargoOptions};
+
+fn cwd() -> PathBuf {
+    std::env::temp_dir().join("cargo-options")
+}
+
+#[test]
+fn cargo_flags_are_forwarded_to_cargo_build() {
+    let cwd = cwd();
+    let argv = [
+        "--features",
+        "testing,extra",
+        "-F",
+        "more",
+        "--all-features",
+        "--no-default-features",
+        "--locked",
+        "--offline",
+        "--target-dir",
+        "out",
+    ];
+    let options = Compiler::try_parse_cargo_from(cwd.clone(), argv).unwrap();
+
+    assert_eq!(options.features, ["testing", "extra", "more"]);
+    assert!(options.all_features);
+    assert!(options.no_default_features);
+    assert!(options.locked);
+    assert!(options.offline);
+    assert_eq!(options.cargo_target_dir.as_deref(), Some(cwd.join("out").as_path()));
+    assert_eq!(options.target_dir, cwd.join("out").join("miden"));
+
+    let cargo_opts = CargoOptions::from_compiler(&options).unwrap();
+    let args = cargo_opts.to_cargo_args();
+    let target_dir = cwd.join("out").to_string_lossy().to_string();
+    assert_eq!(
+        args,
+        [
+            "--features",
+            "testing,extra,more",
+            "--all-features",
+            "--no-default-features",
+            "--locked",
+            "--offline",
+            "--target-dir",
+            target_dir.as_str(),
+        ]
+    );
+}
+
+#[test]
+fn cargo_flags_are_not_forwarded_by_default() {
+    let cwd = cwd();
+    let options = Compiler::try_parse_cargo_from(cwd.clone(), ["--release"]).unwrap();
+
+    assert!(options.features.is_empty());
+    assert!(!options.all_features);
+    assert!(!options.no_default_features);
+    assert!(!options.locked);
+    assert!(!options.offline);
+    assert_eq!(options.cargo_target_dir, None);
+    assert_eq!(options.target_dir, cwd.join("target").join("miden"));
+
+    let cargo_opts = CargoOptions::from_compiler(&options).unwrap();
+    assert_eq!(cargo_opts.to_cargo_args(), ["--release"]);
+}
+
+#[test]
+fn target_dir_is_used_as_is_by_midenc() {
+    let cwd = cwd();
+    let options = Compiler::try_parse_from(cwd.clone(), ["--target-dir", "out"]).unwrap();
+
+    assert_eq!(options.cargo_target_dir, None);
+    assert_eq!(options.target_dir, cwd.join("out"));
+}
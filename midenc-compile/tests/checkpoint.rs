@@ -0,0 +1,91 @@
+mod common;
+
+use std::{path::Path, rc::Rc};
+
+use midenc_compile::{CompilerStopped, compile_to_memory};
+use midenc_hir::{Context, formatter::DisplayHex};
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $two (result i32)
+    i32.const 2)
+  (func $five (export "five") (result i32)
+    call $two
+    i32.const 3
+    i32.add)
+)
+"#;
+
+/// Create a compiler context for [WAT] in `dir`, with the given `-C` options
+fn context(dir: &Path, codegen_options: &[&str]) -> Rc<Context> {
+    let argv = codegen_options.iter().flat_map(|opt| ["-C", *opt]).collect::<Vec<_>>();
+    let session = wat_session(dir, "five.wat", WAT, &argv);
+    Rc::new(Context::new(Rc::new(session)))
+}
+
+/// Compile [WAT] in `dir` to a package, returning its digest
+fn compile_to_digest(dir: &Path, codegen_options: &[&str]) -> String {
+    match compile_to_memory(context(dir, codegen_options)) {
+        Ok(artifact) => {
+            let package = artifact.unwrap_mast();
+            DisplayHex::new(&package.digest().as_bytes()).to_string()
+        }
+        Err(err) => panic!("compilation failed: {err}"),
+    }
+}
+
+fn resume_from_checkpoint(checkpoint: &str) {
+    let temp = tempfile::tempdir().unwrap();
+    let expected = compile_to_digest(temp.path(), &[]);
+
+    let stop_after = format!("stop-after={checkpoint}");
+    let result = compile_to_memory(context(temp.path(), &[&stop_after]));
+    match result {
+        Ok(_) => panic!("expected compilation to stop after the '{checkpoint}' stage"),
+        Err(err) => assert!(err.downcast_ref::<CompilerStopped>().is_some(), "{err}"),
+    }
+
+    let start_from = format!("start-from={checkpoint}");
+    let resumed = compile_to_digest(temp.path(), &[&start_from]);
+    assert_eq!(resumed, expected);
+}
+
+#[test]
+fn resume_from_link_checkpoint() {
+    resume_from_checkpoint("link");
+}
+
+#[test]
+fn resume_from_rewrite_checkpoint() {
+    resume_from_checkpoint("rewrite");
+}
+
+#[test]
+fn resume_from_link_checkpoint_and_stop_after_rewrite() {
+    let temp = tempfile::tempdir().unwrap();
+    let expected = compile_to_digest(temp.path(), &[]);
+
+    let result = compile_to_memory(context(temp.path(), &["stop-after=link"]));
+    assert!(result.is_err_and(|err| err.downcast_ref::<CompilerStopped>().is_some()));
+    let result =
+        compile_to_memory(context(temp.path(), &["start-from=link", "stop-after=rewrite"]));
+    assert!(result.is_err_and(|err| err.downcast_ref::<CompilerStopped>().is_some()));
+
+    let resumed = compile_to_digest(temp.path(), &["start-from=rewrite"]);
+    assert_eq!(resumed, expected);
+}
+
+#[test]
+fn resume_without_checkpoint_is_an_error() {
+    let temp = tempfile::tempdir().unwrap();
+
+    let result = compile_to_memory(context(temp.path(), &["start-from=link"]));
+    let err = match result {
+        Ok(_) => panic!("expected compilation to fail without a checkpoint"),
+        Err(err) => err,
+    };
+    assert!(err.downcast_ref::<CompilerStopped>().is_none());
+    assert!(err.to_string().contains("stop-after=link"), "{err}");
+}
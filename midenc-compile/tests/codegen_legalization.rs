@@ -3,7 +3,7 @@ use std::rc::Rc;
 use midenc_compile::{MidenComponent, Stage, stages::CodegenStage};
 use midenc_dialect_hir::HirOpBuilder;
 use midenc_hir::{
-    BuilderExt, Context, Ident, OpBuilder, SourceSpan, Visibility,
+    BuilderExt, Context, Ident, OpBuilder, SourceSpan, Type, ValueRef, Visibility,
     dialects::builtin::{
         self, BuiltinOpBuilder, ComponentBuilder, FunctionBuilder, ModuleBuilder, WorldBuilder,
         attributes::Signature,
@@ -41,6 +41,77 @@ fn codegen_stage_fails_on_ops_not_legal_for_masm() {
     assert!(message.contains("does not implement HirLowering"));
 }
 
+#[test]
+fn codegen_stage_rejects_cross_context_calls_with_overflow_arguments() {
+    let context = Rc::new(Context::default());
+    let component = build_cross_context_call_component(context.clone(), 17);
+
+    let err = match CodegenStage.run(component, context) {
+        Ok(_) => panic!("codegen unexpectedly accepted a call with 17 felts of arguments"),
+        Err(err) => err,
+    };
+    let message = format!("{err}");
+
+    assert!(message.contains("hir.call"));
+    assert!(message.contains("too many arguments"));
+}
+
+/// Builds a component whose `main` function passes its `num_args` felt parameters to a `callee`
+/// function of the same module with `hir.call`.
+fn build_cross_context_call_component(context: Rc<Context>, num_args: usize) -> MidenComponent {
+    let mut builder = OpBuilder::new(context.clone());
+    let world = builder.create::<builtin::World, ()>(SourceSpan::UNKNOWN)().unwrap();
+    let mut world_builder = WorldBuilder::new(world);
+    let component = world_builder
+        .define_component(
+            Ident::with_empty_span("test_ns".into()),
+            Ident::with_empty_span("test".into()),
+            Version::new(1, 0, 0),
+        )
+        .unwrap();
+
+    let mut component_builder = ComponentBuilder::new(component);
+    let module = component_builder.define_module(Ident::with_empty_span("test".into())).unwrap();
+    let signature = Signature::new(&context, vec![Type::Felt; num_args], []);
+    let mut module_builder = ModuleBuilder::new(module);
+    let callee = module_builder
+        .define_function(
+            Ident::with_empty_span("callee".into()),
+            Visibility::Public,
+            signature.clone(),
+        )
+        .unwrap();
+    let main = module_builder
+        .define_function(
+            Ident::with_empty_span("main".into()),
+            Visibility::Public,
+            signature.clone(),
+        )
+        .unwrap();
+
+    let mut builder = OpBuilder::new(context);
+    let mut callee_builder = FunctionBuilder::new(callee, &mut builder);
+    callee_builder.ret(None, SourceSpan::UNKNOWN).unwrap();
+
+    let mut main_builder = FunctionBuilder::new(main, &mut builder);
+    let args = main_builder
+        .entry_block()
+        .borrow()
+        .arguments()
+        .iter()
+        .map(|arg| *arg as ValueRef)
+        .collect::<Vec<_>>();
+    main_builder.call(callee, signature, args, SourceSpan::UNKNOWN).unwrap();
+    main_builder.ret(None, SourceSpan::UNKNOWN).unwrap();
+
+    MidenComponent {
+        world,
+        component: Some(component),
+        account_component_metadata_bytes: None,
+        producers: Default::default(),
+    }
+}
+
 fn build_test_component(
     context: Rc<Context>,
     build: impl FnOnce(&mut FunctionBuilder<'_, OpBuilder>),
@@ -72,5 +143,6 @@ fn build_test_component(
         world,
         component: Some(component),
         account_component_metadata_bytes: None,
+        producers: Default::default(),
     }
 }
@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+
+use std::{path::Path, sync::Arc};
+
+use midenc_compile::Compiler;
+use midenc_session::{InputFile, Session, diagnostics::Emitter};
+
+/// Write `wat` to `file_name` in `dir`, and return it as an input to the compiler.
+///
+/// An existing file is left as is, so that a test can modify the input between compilations.
+pub fn wat_input(dir: &Path, file_name: &str, wat: &str) -> InputFile {
+    let input_path = dir.join(file_name);
+    if !input_path.exists() {
+        std::fs::write(&input_path, wat).unwrap();
+    }
+    InputFile::from_path(&input_path).unwrap()
+}
+
+/// Construct a session compiling `input` from `dir`, with the given additional arguments.
+///
+/// The target directory is placed in `dir`, so that the compiler does not write to the workspace.
+pub fn new_session(
+    dir: &Path,
+    input: InputFile,
+    emitter: Option<Arc<dyn Emitter>>,
+    args: &[&str],
+) -> Session {
+    let target_dir = dir.join("target");
+    let argv = ["--target-dir".to_string(), target_dir.display().to_string()]
+        .into_iter()
+        .chain(args.iter().map(|arg| arg.to_string()));
+    Compiler::new_session(dir.to_path_buf(), Some(input), emitter, argv)
+}
+
+/// Construct a session compiling `wat`, written to `file_name` in `dir`, with the given additional
+/// arguments.
+pub fn wat_session(dir: &Path, file_name: &str, wat: &str, args: &[&str]) -> Session {
+    new_session(dir, wat_input(dir, file_name, wat), None, args)
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use midenc_compile::Compiler;
+use midenc_session::{CompileFlag, FlagAction, Options};
+
+midenc_hir::inventory::submit! {
+    CompileFlag::new("test_unroll_limit")
+        .long("test-unroll-limit")
+        .action(FlagAction::Set)
+        .value_type::<u32>()
+        .help("The unroll limit for the test pass")
+        .help_heading("Testing")
+}
+
+const CONFIG: &str = r#"
+target = "from-config"
+output_types = ["masm"]
+print_ir_after_pass = ["canonicalize"]
+
+[flags]
+test-unroll-limit = 4
+"#;
+
+fn parse(dir: &Path, argv: &[&str]) -> Result<Box<Options>, String> {
+    Compiler::try_parse_from(dir.to_path_buf(), argv).map_err(|err| err.to_string())
+}
+
+#[test]
+fn config_file_provides_defaults() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("midenc.toml"), CONFIG).unwrap();
+
+    let options = parse(temp.path(), &[]).unwrap();
+    assert_eq!(options.target.as_deref(), Some("from-config"));
+    assert!(options.output_types.contains_key(&midenc_session::OutputType::Masm));
+    assert_eq!(options.print_ir_after_pass.len(), 1);
+    assert_eq!(options.print_ir_after_pass[0].to_string(), "canonicalize");
+    assert_eq!(options.flags.get_flag_value::<u32>("test_unroll_limit").unwrap(), Some(4));
+    assert!(options.config.as_ref().is_some_and(|config| config.warnings.is_empty()));
+}
+
+#[test]
+fn command_line_takes_precedence_over_config_file() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("midenc.toml"), CONFIG).unwrap();
+
+    let options = parse(
+        temp.path(),
+        &[
+            "--target=from-cli",
+            "--emit=hir",
+            "-Zprint-ir-after-pass=cse",
+            "--test-unroll-limit=16",
+        ],
+    )
+    .unwrap();
+    assert_eq!(options.target.as_deref(), Some("from-cli"));
+    assert!(options.output_types.contains_key(&midenc_session::OutputType::Hir));
+    assert!(!options.output_types.contains_key(&midenc_session::OutputType::Masm));
+    assert_eq!(options.print_ir_after_pass.len(), 1);
+    assert_eq!(options.print_ir_after_pass[0].to_string(), "cse");
+    assert_eq!(options.flags.get_flag_values::<u32>("test_unroll_limit").unwrap(), vec![16]);
+}
+
+#[test]
+fn explicit_config_file_is_loaded() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("midenc.toml"), CONFIG).unwrap();
+    std::fs::write(temp.path().join("other.toml"), "target = \"from-other\"\n").unwrap();
+
+    let options = parse(temp.path(), &["--config", "other.toml"]).unwrap();
+    assert_eq!(options.target.as_deref(), Some("from-other"));
+    assert!(options.print_ir_after_pass.is_empty());
+}
+
+#[test]
+fn unknown_config_keys_are_reported_with_their_location() {
+    let temp = tempfile::tempdir().unwrap();
+    let config = "target = \"foo\"\ntraget = \"bar\"\n\n[flags]\n  no-such-flag = true\n";
+    std::fs::write(temp.path().join("midenc.toml"), config).unwrap();
+
+    let options = parse(temp.path(), &[]).unwrap();
+    let warnings = &options.config.as_ref().unwrap().warnings;
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].contains("midenc.toml:2:1"), "{}", warnings[0]);
+    assert!(warnings[0].contains("unknown key 'traget'"), "{}", warnings[0]);
+    assert!(warnings[1].contains("midenc.toml:5:3"), "{}", warnings[1]);
+    assert!(warnings[1].contains("unknown flag 'no-such-flag'"), "{}", warnings[1]);
+}
+
+#[test]
+fn malformed_config_file_is_rejected() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("midenc.toml"), "target = [\n").unwrap();
+
+    let err = parse(temp.path(), &[]).unwrap_err();
+    assert!(err.contains("invalid config file"), "{err}");
+    assert!(err.contains("midenc.toml"), "{err}");
+}
+
+#[test]
+fn mistyped_config_value_is_rejected() {
+    let temp = tempfile::tempdir().unwrap();
+    std::fs::write(temp.path().join("midenc.toml"), "\nlink_libraries = \"extra\"\n").unwrap();
+
+    let err = parse(temp.path(), &[]).unwrap_err();
+    assert!(err.contains("midenc.toml:2:18"), "{err}");
+    assert!(err.contains("expected 'link_libraries' to be an array of strings"), "{err}");
+}
@@ -0,0 +1,107 @@
+use std::{cell::Cell, rc::Rc};
+
+use midenc_compile::Compiler;
+use midenc_hir::{
+    BuilderExt, Context, EntityMut, OpBuilder, Operation, OperationName, Report, SourceSpan,
+    dialects::builtin,
+    pass::{Nesting, Pass, PassExecutionState, PassManager, PostPassStatus},
+};
+use midenc_session::{CompileFlag, FlagAction, InputFile, Session};
+
+midenc_hir::inventory::submit! {
+    CompileFlag::new("test_iteration_cap")
+        .long("test-iteration-cap")
+        .action(FlagAction::Set)
+        .value_type::<u32>()
+        .help("The maximum number of iterations for the test pass")
+        .help_heading("Testing")
+}
+
+midenc_hir::inventory::submit! {
+    CompileFlag::new("test_threshold")
+        .long("test-threshold")
+        .action(FlagAction::Append)
+        .value_type::<u32>()
+        .help("A threshold for the test pass, may be given more than once")
+        .help_heading("Testing")
+}
+
+/// A pass which records the value of `--test-iteration-cap` when it runs
+struct ReadIterationCap {
+    cap: Rc<Cell<Option<u32>>>,
+}
+
+impl Pass for ReadIterationCap {
+    type Target = Operation;
+
+    fn name(&self) -> &'static str {
+        "read-iteration-cap"
+    }
+
+    fn can_schedule_on(&self, _name: &OperationName) -> bool {
+        true
+    }
+
+    fn run_on_operation(
+        &mut self,
+        op: EntityMut<'_, Self::Target>,
+        state: &mut PassExecutionState,
+    ) -> Result<(), Report> {
+        let cap = op.context().session().get_flag_value::<u32>("test_iteration_cap")?;
+        self.cap.set(cap);
+        state.set_post_pass_status(PostPassStatus::Unchanged);
+        Ok(())
+    }
+}
+
+fn session(argv: &[&str]) -> Session {
+    let cwd = std::env::current_dir().unwrap();
+    Compiler::new_session(cwd, Some(InputFile::empty()), None, argv)
+}
+
+/// Run [ReadIterationCap] on an empty world, returning the value it observed
+fn run_pass(session: Session) -> Option<u32> {
+    let context = Rc::new(Context::new(Rc::new(session)));
+    let mut builder = OpBuilder::new(context.clone());
+    let world = builder.create::<builtin::World, ()>(SourceSpan::UNKNOWN)().unwrap();
+
+    let cap = Rc::new(Cell::new(None));
+    let mut pm = PassManager::on::<builtin::World>(context, Nesting::Implicit);
+    pm.add_pass(Box::new(ReadIterationCap { cap: cap.clone() }));
+    pm.run(world.as_operation_ref()).unwrap();
+    cap.get()
+}
+
+#[test]
+fn custom_flag_value_is_visible_to_passes() {
+    assert_eq!(run_pass(session(&["--test-iteration-cap=8"])), Some(8));
+    assert_eq!(run_pass(session(&["--test-iteration-cap", "16"])), Some(16));
+    assert_eq!(run_pass(session(&[])), None);
+}
+
+#[test]
+fn repeated_custom_flag_values_are_collected() {
+    let session = session(&["--test-threshold=1", "--test-threshold", "2"]);
+
+    assert_eq!(session.get_flag_values::<u32>("test_threshold").unwrap(), vec![1, 2]);
+    assert_eq!(session.get_flag_value::<u32>("test_threshold").unwrap(), Some(2));
+}
+
+#[test]
+fn custom_flag_value_of_the_wrong_type_is_an_error() {
+    let session = session(&["--test-threshold=300"]);
+
+    let err = session.get_flag_value::<u8>("test_threshold").unwrap_err();
+    assert!(err.to_string().contains("invalid value '300'"), "{err}");
+    assert!(session.get_flag_values::<u8>("test_threshold").is_err());
+}
+
+#[test]
+fn invalid_custom_flag_value_is_rejected() {
+    let cwd = std::env::current_dir().unwrap();
+    let err = Compiler::try_parse_from(cwd, ["--test-iteration-cap=many"]).unwrap_err();
+    let message = err.to_string();
+
+    assert!(message.contains("invalid value 'many'"), "{message}");
+    assert!(message.contains("--test-iteration-cap"), "{message}");
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::rc::Rc;
+
+use midenc_compile::{compile_to_memory, stages::Artifact};
+use midenc_hir::{Context, formatter::DisplayHex};
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $inc_a (param i32) (result i32)
+    local.get 0
+    i32.const 1
+    i32.add)
+  (func $inc_b (param i32) (result i32)
+    local.get 0
+    i32.const 1
+    i32.add)
+  (func $inc_twice (export "inc_twice") (param i32) (result i32)
+    local.get 0
+    call $inc_a
+    call $inc_b)
+)
+"#;
+
+/// Compile [WAT] to an in-memory artifact, retaining the generated Miden Assembly
+fn compile() -> Artifact {
+    let temp = tempfile::tempdir().unwrap();
+    let mut session = wat_session(temp.path(), "inc.wat", WAT, &[]);
+    session.options.keep_masm = true;
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    match compile_to_memory(context) {
+        Ok(artifact) => artifact,
+        Err(err) => panic!("compilation failed: {err}"),
+    }
+}
+
+#[test]
+fn identical_procedures_are_merged() {
+    let artifact = compile();
+    let component = artifact.masm_component().expect("expected masm to be retained");
+
+    let [merged] = component.merged_procedures.as_slice() else {
+        panic!(
+            "expected exactly one procedure to be merged: {:#?}",
+            component.merged_procedures
+        );
+    };
+    assert!(!merged.forwarded);
+    assert!(merged.merged.to_string().ends_with("inc_b"), "{merged:?}");
+    assert!(merged.canonical.to_string().ends_with("inc_a"), "{merged:?}");
+
+    // Only one body remains, and the call to the merged procedure was redirected
+    let masm = artifact.masm_text().unwrap();
+    assert!(masm.contains("proc inc_a"), "{masm}");
+    assert!(!masm.contains("inc_b"), "{masm}");
+}
+
+#[test]
+fn merging_procedures_is_deterministic() {
+    let first = compile().unwrap_mast();
+    let second = compile().unwrap_mast();
+    assert_eq!(
+        DisplayHex::new(&first.digest().as_bytes()).to_string(),
+        DisplayHex::new(&second.digest().as_bytes()).to_string()
+    );
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::{rc::Rc, sync::Arc};
+
+use midenc_compile::compile_to_memory;
+use midenc_hir::Context;
+use midenc_session::{DiagnosticsFormat, diagnostics::CaptureEmitter};
+
+use self::common::{new_session, wat_input};
+
+/// A module using SIMD, which is not supported
+const WAT: &str = r#"
+(module
+  (func $splat (export "splat") (param i32)
+    local.get 0
+    i32x4.splat
+    drop)
+)
+"#;
+
+/// Compile [WAT] with the additional `args`, capturing the diagnostics rendered in `format`.
+///
+/// Returns the captured diagnostics, along with the error summary of the session.
+fn compile_invalid_input(format: DiagnosticsFormat, args: &[&str]) -> (String, Option<String>) {
+    let temp = tempfile::tempdir().unwrap();
+    let input = wat_input(temp.path(), "splat.wat", WAT);
+    let emitter = Arc::new(CaptureEmitter::with_format(format));
+    let session = new_session(temp.path(), input, Some(emitter.clone()), args);
+    assert_eq!(session.options.diagnostics_format, format);
+    let session = Rc::new(session);
+    let context = Rc::new(Context::new(session.clone()));
+
+    assert!(compile_to_memory(context).is_err(), "expected compilation to fail");
+    (emitter.captured(), session.diagnostics.error_summary())
+}
+
+#[test]
+fn unsupported_features_are_rendered_for_humans() {
+    let (captured, summary) = compile_invalid_input(DiagnosticsFormat::Human, &[]);
+
+    assert!(
+        captured.contains("function 'splat' uses SIMD, which is not supported"),
+        "{captured}"
+    );
+    assert!(captured.contains("help:") && captured.contains("simd128"), "{captured}");
+    assert_eq!(summary.as_deref(), Some("aborting due to 1 previous error"));
+}
+
+#[test]
+fn unsupported_features_are_rendered_as_json() {
+    let (captured, summary) =
+        compile_invalid_input(DiagnosticsFormat::Json, &["--diagnostic-format=json"]);
+
+    let lines = captured.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "expected exactly one json object per diagnostic: {captured}");
+    let json = lines[0];
+    assert!(json.starts_with('{') && json.ends_with('}'), "not a json object: {json}");
+    assert!(json.contains(r#""severity":"error""#), "{json}");
+    assert!(
+        json.contains(r#""message":"function 'splat' uses SIMD, which is not supported"#),
+        "{json}"
+    );
+    assert!(json.contains("simd128"), "{json}");
+    assert_eq!(summary.as_deref(), Some("aborting due to 1 previous error"));
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use std::{rc::Rc, sync::Arc};
+
+use miden_core::serde::Serializable;
+use midenc_compile::{compile_to_memory, stages::Artifact};
+use midenc_hir::Context;
+use midenc_session::{FileName, InputFile, LinkLibrary, Session, miden_project::Linkage};
+
+use self::common::new_session;
+
+const WAT: &str = r#"
+(module
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+)
+"#;
+
+/// Construct a session for [WAT], which is provided to the compiler from memory
+fn session(dir: &std::path::Path) -> Session {
+    let wasm = wat::parse_str(WAT).unwrap();
+    let input = InputFile::from_bytes(wasm, FileName::from("add.wasm")).unwrap();
+    new_session(dir, input, None, &[])
+}
+
+/// Serialize the Miden core library shipped with the compiler to a package in memory
+fn core_library_bytes(session: &Session) -> Arc<[u8]> {
+    let package = LinkLibrary::core().load(&session.options).unwrap();
+    package.to_bytes().into()
+}
+
+#[test]
+fn compile_against_in_memory_core_library() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = session(temp.path());
+    let bytes = core_library_bytes(&session);
+    let session = session.link_library_from_bytes("miden-core", bytes, Linkage::Dynamic);
+
+    let in_memory = session
+        .options
+        .link_libraries
+        .iter()
+        .filter(|lib| lib.bytes.is_some())
+        .collect::<Vec<_>>();
+    assert_eq!(in_memory.len(), 1);
+    assert!(in_memory[0].is_core());
+    assert!(in_memory[0].path.is_none());
+
+    let context = Rc::new(Context::new(Rc::new(session)));
+    match compile_to_memory(context) {
+        Ok(Artifact::Assembled { package, .. }) => assert!(!package.is_program()),
+        Ok(Artifact::Lowered(_)) => panic!("expected compilation to produce an assembled package"),
+        Err(err) => panic!("compilation failed: {err}"),
+    }
+}
+
+#[test]
+fn invalid_in_memory_library_is_rejected() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = session(temp.path());
+    let lib = LinkLibrary::from_bytes("garbage", vec![0xde, 0xad, 0xbe, 0xef], Linkage::Dynamic);
+
+    let err = lib.load(&session.options).unwrap_err();
+    assert!(err.to_string().contains("failed to load Miden package from garbage"), "{err}");
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use std::{path::Path, rc::Rc};
+
+use midenc_compile::compile_to_optimized_hir;
+use midenc_hir::Context;
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+)
+"#;
+
+/// Compile [WAT] to optimized HIR with `--emit=ir-after=<spec>`, returning the names of the
+/// files found in `dump_dir`, in sorted order.
+fn compile_with_ir_dumps(spec: &str, dump_dir: &Path) -> Vec<String> {
+    let temp = tempfile::tempdir().unwrap();
+    let emit = format!("--emit=ir-after={spec}:{}", dump_dir.display());
+    let session = wat_session(temp.path(), "add.wat", WAT, &[&emit]);
+    let context = Rc::new(Context::new(Rc::new(session)));
+    if let Err(err) = compile_to_optimized_hir(context) {
+        panic!("compilation failed: {err}");
+    }
+
+    let mut files = std::fs::read_dir(dump_dir)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let len = entry.metadata().unwrap().len();
+            let name = entry.file_name().into_string().unwrap();
+            assert!(len > 0, "expected ir dump '{name}' to be non-empty");
+            name
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[test]
+fn ir_dumps_after_all_passes_are_numbered() {
+    let dumps = tempfile::tempdir().unwrap();
+    let files = compile_with_ir_dumps("all", dumps.path());
+
+    assert!(!files.is_empty(), "expected at least one ir dump");
+    for (index, file) in files.iter().enumerate() {
+        let prefix = format!("{index:03}-");
+        assert!(file.starts_with(&prefix), "expected '{file}' to start with '{prefix}'");
+        assert!(file.ends_with(".hir"), "expected '{file}' to be a .hir file");
+    }
+    assert!(files.iter().any(|file| file.ends_with("-lift-control-flow.hir")), "{files:?}");
+}
+
+#[test]
+fn ir_dumps_only_include_selected_pass() {
+    let dumps = tempfile::tempdir().unwrap();
+    let files = compile_with_ir_dumps("cse", dumps.path());
+
+    assert!(!files.is_empty(), "expected at least one ir dump");
+    for (index, file) in files.iter().enumerate() {
+        assert_eq!(file, &format!("{index:03}-cse.hir"));
+    }
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use std::rc::Rc;
+
+use midenc_compile::{compile_to_memory, stages::Artifact};
+use midenc_hir::Context;
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $the_answer (export "the_answer") (result i32)
+    i32.const 42)
+)
+"#;
+
+/// Compile [WAT] to an in-memory artifact, with the `keep_masm` option set to `keep_masm`
+fn compile(keep_masm: bool) -> Artifact {
+    let temp = tempfile::tempdir().unwrap();
+    let mut session = wat_session(temp.path(), "answer.wat", WAT, &[]);
+    session.options.keep_masm = keep_masm;
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    match compile_to_memory(context) {
+        Ok(artifact) => artifact,
+        Err(err) => panic!("compilation failed: {err}"),
+    }
+}
+
+#[test]
+fn assembled_artifact_retains_masm_when_requested() {
+    let artifact = compile(true);
+    assert!(matches!(artifact, Artifact::Assembled { .. }));
+
+    let masm = artifact.masm_text().expect("expected masm to be retained");
+    assert!(masm.contains("the_answer"), "{masm}");
+
+    let modules = artifact.masm_modules().expect("expected masm to be retained");
+    assert!(!modules.is_empty());
+}
+
+#[test]
+fn assembled_artifact_does_not_retain_masm_by_default() {
+    let artifact = compile(false);
+    assert!(matches!(artifact, Artifact::Assembled { .. }));
+    assert!(artifact.masm_component().is_none());
+    assert!(artifact.masm_text().is_none());
+}
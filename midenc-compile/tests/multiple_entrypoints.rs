@@ -0,0 +1,78 @@
+mod common;
+
+use std::{path::Path, rc::Rc};
+
+use miden_core::serde::Deserializable;
+use miden_mast_package::Package;
+use midenc_compile::{compile, compile_to_memory};
+use midenc_hir::Context;
+use midenc_session::{FileName, InputFile, Session};
+
+use self::common::new_session;
+
+const WAT: &str = r#"
+(module $entries
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+  (func $sub (export "sub") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.sub)
+)
+"#;
+
+/// Construct a session for [WAT] with both of its functions given as entrypoints
+fn session(dir: &Path, extra_args: &[&str]) -> Session {
+    let wasm = wat::parse_str(WAT).unwrap();
+    let input = InputFile::from_bytes(wasm, FileName::from("entries.wasm")).unwrap();
+    let entrypoints = ["--entrypoint=entries::add", "--entrypoint=entries::sub"];
+    new_session(dir, input, None, &[&entrypoints[..], extra_args].concat())
+}
+
+#[test]
+fn multiple_entrypoints_are_all_exported() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = session(temp.path(), &[]);
+    assert_eq!(session.options.entrypoints.len(), 2);
+
+    let context = Rc::new(Context::new(Rc::new(session)));
+    let package = compile_to_memory(context)
+        .unwrap_or_else(|err| panic!("compilation failed: {err}"))
+        .unwrap_mast();
+    assert!(!package.is_program());
+    for name in ["add", "sub"] {
+        let suffix = format!("entries::{name}");
+        assert!(
+            package.mast.exports().any(|export| export.path().as_str().ends_with(&suffix)),
+            "expected '{suffix}' to be exported"
+        );
+    }
+}
+
+#[test]
+fn masp_per_entrypoint_emits_a_package_for_each_entrypoint() {
+    let temp = tempfile::tempdir().unwrap();
+    let out_dir = temp.path().join("out");
+    let emit = format!("--emit=masp-per-entrypoint={}", out_dir.display());
+    let session = Rc::new(session(temp.path(), &[&emit]));
+
+    let context = Rc::new(Context::new(session.clone()));
+    compile(context).unwrap_or_else(|err| panic!("compilation failed: {err}"));
+
+    let emitted = session.output_files.all_emitted();
+    assert_eq!(emitted.len(), 2, "{emitted:?}");
+    for name in ["add", "sub"] {
+        let file_name = format!("{name}.masp");
+        let path = emitted
+            .iter()
+            .find(|path| path.file_name().is_some_and(|f| f == file_name.as_str()))
+            .unwrap_or_else(|| panic!("expected a package for '{name}' in {emitted:?}"));
+        assert!(path.starts_with(&out_dir));
+        let package = Package::read_from_bytes(&std::fs::read(path).unwrap()).unwrap();
+        let exports = package.mast.exports().collect::<Vec<_>>();
+        assert_eq!(exports.len(), 1);
+        assert!(exports[0].path().as_str().ends_with(&format!("entries::{name}")));
+    }
+}
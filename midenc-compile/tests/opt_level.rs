@@ -0,0 +1,52 @@
+mod common;
+
+use std::rc::Rc;
+
+use midenc_compile::compile_to_memory_with_pre_assembly_stage;
+use midenc_hir::Context;
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $five (export "five") (result i32)
+    i32.const 2
+    i32.const 3
+    i32.add)
+)
+"#;
+
+/// Compile [WAT] with `--optimize=<level>`, returning the emitted Miden Assembly
+fn compile_to_masm(level: &str) -> String {
+    let temp = tempfile::tempdir().unwrap();
+    let optimize = format!("--optimize={level}");
+    let session = wat_session(temp.path(), "five.wat", WAT, &[&optimize]);
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    let mut masm = None;
+    let result = compile_to_memory_with_pre_assembly_stage(context, &mut |output, _context| {
+        masm = Some(output.component.to_string());
+        Ok(output)
+    });
+    if let Err(err) = result {
+        panic!("compilation failed: {err}");
+    }
+    masm.expect("expected code generation to run")
+}
+
+#[test]
+fn constants_are_folded_when_optimizing() {
+    let masm = compile_to_masm("basic");
+
+    assert!(masm.contains("push.5"), "{masm}");
+    assert!(!masm.contains("push.3"), "{masm}");
+}
+
+#[test]
+fn constants_are_not_folded_without_optimizations() {
+    let masm = compile_to_masm("none");
+
+    assert!(masm.contains("push.2"), "{masm}");
+    assert!(masm.contains("push.3"), "{masm}");
+    assert!(!masm.contains("push.5"), "{masm}");
+}
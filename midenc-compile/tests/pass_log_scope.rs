@@ -0,0 +1,84 @@
+mod common;
+
+use std::{
+    io::{self, Write},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use log::LevelFilter;
+use midenc_compile::compile_to_memory;
+use midenc_hir::Context;
+use midenc_log::{Builder, Target};
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $pick (export "pick") (param i32) (result i32)
+    local.get 0
+    if (result i32)
+      i32.const 1
+    else
+      i32.const 2
+    end)
+  (func $choose (export "choose") (param i32) (result i32)
+    local.get 0
+    if (result i32)
+      i32.const 3
+    else
+      i32.const 4
+    end)
+)
+"#;
+
+/// A [Write] implementation which appends to a buffer shared with the test
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn pass_output_can_be_filtered_by_symbol() {
+    let buffer = SharedBuffer::default();
+    let logger = Builder::new()
+        .filter_level(LevelFilter::Off)
+        .parse_filters("pass:lift-control-flow=trace")
+        .parse_trace_filter("symbol:pick")
+        .format(|f, record| writeln!(f, "{}", record.target()))
+        .target(Target::Pipe(Box::new(buffer.clone())))
+        .build();
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(logger)).unwrap();
+
+    let temp = tempfile::tempdir().unwrap();
+    let session = wat_session(temp.path(), "pick.wat", WAT, &[]);
+    let context = Rc::new(Context::new(Rc::new(session)));
+    if let Err(err) = compile_to_memory(context) {
+        panic!("compilation failed: {err}");
+    }
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let targets = output.lines().collect::<Vec<_>>();
+    assert!(
+        targets.contains(&"pass:lift-control-flow:pick"),
+        "expected the pass to log records for 'pick', got {targets:?}"
+    );
+    // Records specific to other symbols are filtered out
+    for target in targets {
+        assert!(
+            target.starts_with("pass:lift-control-flow"),
+            "unexpected record target '{target}'"
+        );
+        assert!(!target.ends_with(":choose"), "unexpected record for 'choose'");
+    }
+}
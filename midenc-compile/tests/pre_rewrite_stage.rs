@@ -0,0 +1,90 @@
+mod common;
+
+use std::rc::Rc;
+
+use midenc_compile::{MidenComponent, compile_to_memory_with_pre_rewrite_stage};
+use midenc_hir::{
+    Context, Operation,
+    dialects::builtin::{Function, attributes::UnitAttr},
+};
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $two (result i32)
+    i32.const 2)
+  (func $five (export "five") (result i32)
+    call $two
+    i32.const 3
+    i32.add)
+)
+"#;
+
+const MARKER: &str = "instrumented";
+
+/// Compile [WAT], running `stage` on the linked component prior to the rewrite pipeline
+fn compile_with_pre_rewrite_stage(
+    mut stage: impl FnMut(MidenComponent, Rc<Context>) -> MidenComponent,
+) {
+    let temp = tempfile::tempdir().unwrap();
+    let session = wat_session(temp.path(), "five.wat", WAT, &[]);
+    let context = Rc::new(Context::new(Rc::new(session)));
+
+    let result = compile_to_memory_with_pre_rewrite_stage(context, &mut |component, context| {
+        Ok(stage(component, context))
+    });
+    if let Err(err) = result {
+        panic!("compilation failed: {err}");
+    }
+}
+
+fn for_each_function(component: &MidenComponent, mut callback: impl FnMut(&Operation)) {
+    component.world.borrow().as_operation().prewalk_all(|op: &Operation| {
+        if op.is::<Function>() {
+            callback(op);
+        }
+    });
+}
+
+#[test]
+fn pre_rewrite_stage_observes_linked_component() {
+    let mut functions = 0;
+    compile_with_pre_rewrite_stage(|component, _context| {
+        for_each_function(&component, |_| functions += 1);
+        component
+    });
+
+    // Both functions are present, as the rewrite pipeline has not had a chance to inline `two`
+    assert!(functions >= 2, "expected at least 2 functions, found {functions}");
+}
+
+#[test]
+fn pre_rewrite_stage_changes_are_seen_by_later_stages() {
+    let mut world = None;
+    compile_with_pre_rewrite_stage(|component, context| {
+        let mut functions = vec![];
+        for_each_function(&component, |op| functions.push(op.as_operation_ref()));
+        for function in functions {
+            let marker = context.create_attribute::<UnitAttr, _>(());
+            function.borrow_mut().set_attribute(MARKER, marker);
+        }
+        world = Some(component.world);
+        component
+    });
+
+    // The rewrite pipeline, codegen, and assembly all ran on the instrumented IR
+    let world = world.expect("expected the pre-rewrite stage to run");
+    let component = MidenComponent {
+        world,
+        component: None,
+        account_component_metadata_bytes: None,
+        producers: Default::default(),
+    };
+    let mut instrumented = 0;
+    for_each_function(&component, |op| {
+        assert!(op.has_attribute(MARKER), "{op}");
+        instrumented += 1;
+    });
+    assert!(instrumented > 0);
+}
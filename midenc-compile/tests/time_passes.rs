@@ -0,0 +1,62 @@
+mod common;
+
+use std::rc::Rc;
+
+use midenc_compile::compile_to_memory;
+use midenc_hir::Context;
+use midenc_session::{Session, TimingKind};
+
+use self::common::wat_session;
+
+const WAT: &str = r#"
+(module
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+)
+"#;
+
+const STAGES: &[&str] = &["parse", "analyze", "rewrite", "codegen", "assemble"];
+
+/// Compile [WAT] to memory with the given extra arguments, returning the session
+fn compile(extra_args: &[&str]) -> Rc<Session> {
+    let temp = tempfile::tempdir().unwrap();
+    let session = Rc::new(wat_session(temp.path(), "add.wat", WAT, extra_args));
+    let context = Rc::new(Context::new(session.clone()));
+    if let Err(err) = compile_to_memory(context) {
+        panic!("compilation failed: {err}");
+    }
+    session
+}
+
+#[test]
+fn time_passes_records_each_stage() {
+    let session = compile(&["-Ztime-passes"]);
+    let timings = &session.statistics.timings;
+
+    for stage in STAGES {
+        let timing = timings
+            .get(TimingKind::Stage, stage)
+            .unwrap_or_else(|| panic!("expected a timing for stage '{stage}'"));
+        assert!(timing.count > 0);
+    }
+    assert!(
+        timings.entries().iter().any(|(kind, ..)| *kind == TimingKind::Pass),
+        "expected timings for individual passes"
+    );
+
+    let report = session.statistics.timings_report().to_string();
+    assert!(report.contains("Total:"), "{report}");
+    for stage in STAGES {
+        assert!(report.lines().any(|line| line.trim_start().starts_with(stage)), "{report}");
+    }
+    assert!(report.contains('%'), "{report}");
+}
+
+#[test]
+fn time_passes_is_disabled_by_default() {
+    let session = compile(&[]);
+
+    assert!(session.statistics.timings.is_empty());
+}
@@ -0,0 +1,364 @@
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use midenc_compile as compile;
+use midenc_hir::{
+    CallableOpInterface, Context, Immediate, Op, Operation, OperationRef, Spanned,
+    dialects::builtin::attributes::Location,
+};
+use midenc_hir_eval::{Breakpoint, HirEvaluator, StepEvent, Value};
+use midenc_session::{
+    InputFile,
+    diagnostics::{IntoDiagnostic, Report, WrapErr},
+};
+
+/// Compile the input to optimized HIR, and evaluate a function in an interactive debugger
+#[derive(Debug, clap::Args)]
+pub struct DebugCommand {
+    /// The input file to compile and debug
+    ///
+    /// You may specify `-` to read from stdin, otherwise you must provide a path
+    #[arg(value_name = "FILE")]
+    pub input: InputFile,
+    /// The name of the function to evaluate, or its fully-qualified symbol path
+    #[arg(long, value_name = "NAME")]
+    function: String,
+    /// A file containing the arguments to pass to the function, as whitespace-separated integers
+    #[arg(long, value_name = "FILE")]
+    inputs: Option<PathBuf>,
+}
+
+const HELP: &str = "\
+commands:
+  break <function>|<line>  stop on entry to a function, or before any op on a source line
+  delete                   remove all breakpoints
+  step [n]                 evaluate the next n operations (default 1)
+  continue                 evaluate until the next breakpoint, or the end
+  print <value>            print the value bound to an SSA value, e.g. `print v3`
+  locals                   print all values bound in the current frame
+  memory <addr> [len]      print len bytes of memory starting at addr (default 16)
+  where                    print the call stack, and the next operation
+  restart                  restart evaluation from the beginning
+  quit                     exit the debugger";
+
+impl DebugCommand {
+    /// Compile the input in `context`, and debug the requested function, reading commands from
+    /// stdin until the user quits.
+    pub fn run(&self, context: Rc<Context>) -> Result<(), Report> {
+        let output = compile::compile_to_optimized_hir(context.clone())?;
+        let root = output.world.borrow().as_operation_ref();
+        let function = find_function(&root.borrow(), &self.function)?;
+
+        let args = match self.inputs.as_ref() {
+            Some(path) => {
+                let inputs = std::fs::read_to_string(path)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("could not read inputs from {}", path.display()))?;
+                parse_inputs(&function.borrow(), &inputs)?
+            }
+            None => vec![],
+        };
+
+        let mut debugger = Debugger {
+            evaluator: HirEvaluator::new(context.clone()),
+            context,
+            root,
+            function,
+            args,
+        };
+        debugger.start()?;
+        debugger.repl(io::stdin().lock(), io::stdout().lock()).into_diagnostic()
+    }
+}
+
+/// Find the defined function named `name`, or whose symbol path is `name`, in `root`
+fn find_function(root: &Operation, name: &str) -> Result<OperationRef, Report> {
+    let mut candidates = vec![];
+    root.prewalk_all(|op: &Operation| {
+        let is_definition = op
+            .as_trait::<dyn CallableOpInterface>()
+            .is_some_and(|callable| callable.get_callable_region().is_some());
+        if let Some(symbol) = op.as_symbol().filter(|_| is_definition) {
+            if symbol.path().to_string() == name {
+                // An exact match takes precedence over any matches by name
+                candidates.insert(0, (true, op.as_operation_ref()));
+            } else if symbol.name().as_str() == name {
+                candidates.push((false, op.as_operation_ref()));
+            }
+        }
+    });
+
+    match candidates.as_slice() {
+        [] => Err(Report::msg(format!("could not find a function named '{name}'"))),
+        [(_, function)] | [(true, function), ..] => Ok(*function),
+        _ => {
+            let paths = candidates
+                .iter()
+                .filter_map(|(_, op)| op.borrow().as_symbol().map(|symbol| symbol.path()))
+                .map(|path| format!("\n  {path}"))
+                .collect::<String>();
+            Err(Report::msg(format!(
+                "'{name}' is ambiguous, use the symbol path of one of:{paths}"
+            )))
+        }
+    }
+}
+
+/// Parse `inputs` as one argument per parameter of `function`
+fn parse_inputs(function: &Operation, inputs: &str) -> Result<Vec<Value>, Report> {
+    let callable = function.as_trait::<dyn CallableOpInterface>().unwrap();
+    let signature = callable.signature();
+    let tokens = inputs.split_whitespace().collect::<Vec<_>>();
+    if tokens.len() != signature.arity() {
+        return Err(Report::msg(format!(
+            "expected {} inputs, but {} were given",
+            signature.arity(),
+            tokens.len()
+        )));
+    }
+
+    signature
+        .params()
+        .iter()
+        .zip(tokens)
+        .map(|(param, token)| {
+            let value = match token.strip_prefix('-') {
+                Some(_) => token.parse::<i128>().ok().map(Immediate::I128),
+                None => match token.strip_prefix("0x") {
+                    Some(hex) => u128::from_str_radix(hex, 16).ok(),
+                    None => token.parse::<u128>().ok(),
+                }
+                .map(Immediate::U128),
+            }
+            .ok_or_else(|| Report::msg(format!("invalid input '{token}': expected an integer")))?;
+            Value::cast_immediate(value, &param.ty)
+                .map(Value::Immediate)
+                .map_err(|err| Report::msg(format!("invalid input '{token}': {err}")))
+        })
+        .collect()
+}
+
+/// Whether the debugger should keep reading commands
+enum Flow {
+    Continue,
+    Quit,
+}
+
+struct Debugger {
+    evaluator: HirEvaluator,
+    context: Rc<Context>,
+    /// The operation containing all of the compiled IR
+    root: OperationRef,
+    /// The function being debugged
+    function: OperationRef,
+    /// The arguments the function is evaluated with
+    args: Vec<Value>,
+}
+
+impl Debugger {
+    fn start(&mut self) -> Result<(), Report> {
+        let function = self.function.borrow();
+        let callable = function.as_trait::<dyn CallableOpInterface>().unwrap();
+        self.evaluator.start(callable, self.args.iter().copied())
+    }
+
+    /// Read commands from `input` until it is exhausted, or the user quits
+    fn repl(&mut self, mut input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "type `help` for a list of commands")?;
+        self.print_location(&mut out)?;
+        let mut line = String::new();
+        loop {
+            write!(out, "(midenc) ")?;
+            out.flush()?;
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            if let Flow::Quit = self.execute(line.trim(), &mut out)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Execute a single debugger command, writing any output to `out`
+    fn execute(&mut self, command: &str, out: &mut impl Write) -> io::Result<Flow> {
+        let mut words = command.split_whitespace();
+        let Some(name) = words.next() else {
+            return Ok(Flow::Continue);
+        };
+        let arg = words.next();
+        match name {
+            "help" | "h" => writeln!(out, "{HELP}")?,
+            "quit" | "q" => return Ok(Flow::Quit),
+            "break" | "b" => match arg {
+                Some(target) => self.set_breakpoint(target, out)?,
+                None => {
+                    for breakpoint in self.evaluator.breakpoints() {
+                        writeln!(out, "{breakpoint}")?;
+                    }
+                }
+            },
+            "delete" | "d" => {
+                for breakpoint in self.evaluator.breakpoints().to_vec() {
+                    self.evaluator.remove_breakpoint(&breakpoint);
+                }
+            }
+            "step" | "s" => {
+                let Some(count) = arg.map_or(Some(1), |count| count.parse::<usize>().ok()) else {
+                    writeln!(out, "invalid step count")?;
+                    return Ok(Flow::Continue);
+                };
+                for _ in 0..count {
+                    let event = self.evaluator.step();
+                    if !self.print_event(event, out)? {
+                        break;
+                    }
+                }
+                self.print_location(out)?;
+            }
+            "continue" | "c" => {
+                let event = self.evaluator.continue_to_breakpoint();
+                self.print_event(event, out)?;
+                self.print_location(out)?;
+            }
+            "print" | "p" => match arg {
+                Some(name) => {
+                    let name = name.trim_start_matches(['%', 'v']);
+                    let binding = self.evaluator.bindings().into_iter().find(|(value, _)| {
+                        value.borrow().id().to_string().trim_start_matches(['%', 'v']) == name
+                    });
+                    match binding {
+                        Some((value, binding)) => writeln!(out, "{value} = {binding}")?,
+                        None => writeln!(out, "no value named '{name}' in the current frame")?,
+                    }
+                }
+                None => writeln!(out, "expected a value to print")?,
+            },
+            "locals" | "l" => {
+                for (value, binding) in self.evaluator.bindings() {
+                    writeln!(out, "{value} = {binding}")?;
+                }
+            }
+            "memory" | "x" => {
+                let addr = arg.and_then(parse_u32);
+                let len = words.next().map_or(Some(16), parse_u32);
+                let (Some(addr), Some(len)) = (addr, len) else {
+                    writeln!(out, "usage: memory <addr> [len]")?;
+                    return Ok(Flow::Continue);
+                };
+                match self.evaluator.memory_range(addr, len) {
+                    Ok(bytes) => {
+                        for (offset, chunk) in bytes.chunks(16).enumerate() {
+                            write!(out, "{:#010x}:", addr as usize + offset * 16)?;
+                            for byte in chunk {
+                                write!(out, " {byte:02x}")?;
+                            }
+                            writeln!(out)?;
+                        }
+                    }
+                    Err(err) => writeln!(out, "{err}")?,
+                }
+            }
+            "where" | "w" | "bt" => {
+                for (depth, callee) in self.evaluator.backtrace().iter().rev().enumerate() {
+                    let callee = callee.borrow();
+                    match callee.as_symbol() {
+                        Some(symbol) => writeln!(out, "#{depth} {}", symbol.path())?,
+                        None => writeln!(out, "#{depth} {}", callee.name())?,
+                    }
+                }
+                self.print_location(out)?;
+            }
+            "restart" | "r" => match self.start() {
+                Ok(()) => self.print_location(out)?,
+                Err(err) => writeln!(out, "{err}")?,
+            },
+            other => {
+                writeln!(out, "unknown command '{other}', type `help` for a list of commands")?
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Set a breakpoint on `target`, a function name, or a line number
+    fn set_breakpoint(&mut self, target: &str, out: &mut impl Write) -> io::Result<()> {
+        if target.parse::<u32>().is_err() {
+            match find_function(&self.root.borrow(), target) {
+                Ok(function) => {
+                    let path = function.borrow().as_symbol().unwrap().path();
+                    writeln!(out, "breakpoint set on entry to {path}")?;
+                    self.evaluator.set_breakpoint(Breakpoint::Symbol(path));
+                }
+                Err(err) => writeln!(out, "{err}")?,
+            }
+            return Ok(());
+        }
+
+        // Stop before each operation whose location starts on the given line
+        let mut spans = vec![];
+        self.root.borrow().prewalk_all(|op: &Operation| {
+            let line = match Location::from_span(op.span(), &self.context) {
+                Location::FileLineCol { line, .. } => line,
+                Location::FileLineColRange { start_line, .. } => start_line,
+                _ => return,
+            };
+            if line.to_string() == target {
+                spans.push(op.span());
+            }
+        });
+        if spans.is_empty() {
+            writeln!(out, "no operations are on line {target}")?;
+        } else {
+            writeln!(out, "breakpoint set on {} operation(s) on line {target}", spans.len())?;
+        }
+        for span in spans {
+            self.evaluator.set_breakpoint(Breakpoint::Span(span));
+        }
+        Ok(())
+    }
+
+    /// Print the outcome of advancing evaluation, returning false if evaluation cannot advance
+    fn print_event(
+        &self,
+        event: Result<StepEvent, Report>,
+        out: &mut impl Write,
+    ) -> io::Result<bool> {
+        match event {
+            Ok(event @ StepEvent::Finished(_)) => {
+                writeln!(out, "{event}")?;
+                Ok(false)
+            }
+            Ok(event) => {
+                writeln!(out, "{event}")?;
+                Ok(true)
+            }
+            Err(err) => {
+                writeln!(out, "{err:?}")?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Print the operation at which evaluation is suspended, if any
+    fn print_location(&self, out: &mut impl Write) -> io::Result<()> {
+        match self.evaluator.next_op() {
+            Some(op) => {
+                let op = op.borrow();
+                let location = Location::from_span(op.span(), &self.context);
+                writeln!(out, "next: {} at {location}", op.name())
+            }
+            None => writeln!(out, "evaluation is not in progress, use `restart` to start over"),
+        }
+    }
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal `u32`
+fn parse_u32(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
@@ -1,5 +1,7 @@
 #![deny(warnings)]
 
+#[cfg(feature = "std")]
+mod debug;
 mod midenc;
 
 pub use clap::Error as ClapError;
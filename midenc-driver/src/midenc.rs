@@ -5,7 +5,7 @@ use log::Log;
 use midenc_compile as compile;
 use midenc_hir::Context;
 use midenc_session::{
-    InputFile,
+    InputFile, Verbosity,
     diagnostics::{Emitter, Report},
 };
 
@@ -29,6 +29,16 @@ pub struct Midenc {
     input: Option<InputFile>,
     #[command(flatten)]
     options: compile::Compiler,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Commands which do something other than compile the input
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Step through the evaluation of a function of the input, compiled to optimized HIR
+    #[cfg(feature = "std")]
+    Debug(crate::debug::DebugCommand),
 }
 
 impl Midenc {
@@ -63,16 +73,30 @@ impl Midenc {
         let command = <Self as clap::CommandFactory>::command();
         let command = midenc_session::flags::register_flags(command);
 
-        let mut matches = command.try_get_matches_from(args).map_err(ClapDiagnostic::from)?;
-        let compile_matches = matches.clone();
-        let Self { input, options } =
-            <Self as clap::FromArgMatches>::from_arg_matches_mut(&mut matches)
-                .map_err(format_error::<Self>)
+        let cwd = cwd.into();
+        let args = args.into_iter().collect::<Vec<_>>();
+        // Options from the config file are parsed as if they preceded those on the command line
+        let (mut matches, config) =
+            compile::Compiler::try_get_matches_with_config(command, &args, &cwd)
                 .map_err(ClapDiagnostic::from)?;
+        let compile_matches = matches.clone();
+        let Self {
+            input,
+            options,
+            command,
+        } = <Self as clap::FromArgMatches>::from_arg_matches_mut(&mut matches)
+            .map_err(format_error::<Self>)
+            .map_err(ClapDiagnostic::from)?;
 
-        let mut options = options.into_options(cwd.into());
+        let mut options = options.into_options(cwd);
         options.set_extra_flags(compile_matches.into());
+        options.config = config.map(Arc::new);
 
+        let input = match command.as_ref() {
+            #[cfg(feature = "std")]
+            Some(Command::Debug(debug)) => Some(debug.input.clone()),
+            None => input,
+        };
         let Some(input) = input else {
             let mut command = <Self as clap::CommandFactory>::command();
             command
@@ -82,7 +106,39 @@ impl Midenc {
 
         let session = Rc::new(options.into_session(input, emitter, None)?);
         let context = Rc::new(Context::new(session));
-        compile::compile(context)
+        let result = match command {
+            #[cfg(feature = "std")]
+            Some(Command::Debug(debug)) => debug.run(context.clone()),
+            None => compile::compile(context.clone()),
+        };
+        // The global logger is never dropped, so make sure any buffered log file is written out
+        log::logger().flush();
+
+        let session = context.session();
+        if result.is_ok() && session.options.verbosity == Verbosity::Debug {
+            for path in session.output_files.all_emitted() {
+                eprintln!("wrote {}", path.display());
+            }
+        }
+
+        // Errors reported via the diagnostics handler, including warnings promoted to errors,
+        // stop the compiler without failing it, so make sure such errors cause a failure here.
+        let stopped = match &result {
+            Ok(_) => true,
+            Err(report) => report.downcast_ref::<compile::CompilerStopped>().is_some(),
+        };
+
+        let summary = session.diagnostics.summary();
+        if summary.errors == 0
+            && summary.warnings > 0
+            && session.options.verbosity <= Verbosity::Warning
+        {
+            eprintln!("warning: {summary}");
+        }
+        match summary.error_summary() {
+            Some(summary) if stopped => Err(Report::msg(summary)),
+            _ => result,
+        }
     }
 }
 
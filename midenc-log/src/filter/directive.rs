@@ -1,6 +1,6 @@
 use log::{Level, LevelFilter};
 
-use super::FilterOp;
+use super::{TopicPattern, trace_filter::split_entity};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Directive {
@@ -12,9 +12,16 @@ pub struct Directive {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DirectiveKind {
     Any,
-    Module { module: String },
-    Component { component: String },
-    Topic { component: String, topic: FilterOp },
+    Module {
+        module: String,
+    },
+    Component {
+        component: String,
+    },
+    Topic {
+        component: String,
+        topic: TopicPattern,
+    },
 }
 
 impl PartialOrd for DirectiveKind {
@@ -69,35 +76,44 @@ impl DirectiveKind {
                 component == target_component
             }
             Self::Topic { component, topic } => {
-                let (target_component, target_topic) =
-                    target.split_once(':').unwrap_or((target, ""));
+                // The last topic names the entity the record relates to, which is matched by
+                // `MIDENC_TRACE_FILTER` rather than by topic directives
+                let (scope, _) = split_entity(target);
+                let (target_component, target_topic) = scope.split_once(':').unwrap_or((scope, ""));
                 component == target_component && topic.is_match(target_topic)
             }
         }
     }
 }
 
-// Check whether a level and target are enabled by the set of directives.
+// Check whether a level and target are enabled by the set of positive directives.
 pub fn enabled(directives: &[Directive], level: Level, target: &str) -> bool {
     // Search for the longest match, the vector is assumed to be pre-sorted.
     let mut was_matched = false;
     for directive in directives.iter().rev() {
-        // Don't bother applying further positive matches once we've had one positive match
-        if was_matched && !directive.negated {
-            continue;
-        }
-
-        // Setting level to `off` is equivalent to negation and takes precedence over it
-        let (matches, negated) = match directive.level {
-            LevelFilter::Off => (directive.kind.matches(target), true),
-            filter if level <= filter => (directive.kind.matches(target), directive.negated),
+        match directive.level {
+            // Setting level to `off` disables the target, and takes precedence over other matches
+            LevelFilter::Off => {
+                if directive.kind.matches(target) {
+                    return false;
+                }
+            }
+            // Don't bother applying further positive matches once we've had one positive match
+            _ if was_matched => continue,
+            filter if level <= filter => was_matched |= directive.kind.matches(target),
             _ => continue,
-        };
-        // If we find a negative match, we don't need to do any more checking
-        if matches && negated {
-            return false;
         }
-        was_matched |= matches;
     }
     was_matched
 }
+
+// Check whether a level and target are suppressed by any of the negated directives.
+//
+// A negated directive suppresses records at its level, and any more verbose level, e.g.
+// `-dataflow=debug` suppresses debug and trace records for the `dataflow` component. A negated
+// directive without a level has level `off`, and so suppresses records at every level.
+pub fn suppressed(negated: &[Directive], level: Level, target: &str) -> bool {
+    negated
+        .iter()
+        .any(|directive| level >= directive.level && directive.kind.matches(target))
+}
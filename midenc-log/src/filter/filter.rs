@@ -3,8 +3,8 @@ use std::{env, fmt, mem};
 use log::{LevelFilter, Metadata, Record};
 
 use crate::filter::{
-    Directive, DirectiveKind, FilterOp, KvFilter, ParseError, enabled, kv_filter::KvFilterOp,
-    parse_spec, parser::ParseResult,
+    Directive, DirectiveKind, FilterOp, KvFilter, ParseError, TopicPattern, TraceFilter, enabled,
+    kv_filter::KvFilterOp, parse_spec, parser::ParseResult, suppressed,
 };
 
 /// A builder for a log filter.
@@ -30,6 +30,7 @@ use crate::filter::{
 pub struct Builder {
     directives: Vec<Directive>,
     kv_filters: Vec<KvFilter>,
+    trace_filter: TraceFilter,
     filter: Option<FilterOp>,
     built: bool,
 }
@@ -40,6 +41,7 @@ impl Builder {
         Builder {
             directives: Vec::new(),
             kv_filters: Vec::new(),
+            trace_filter: TraceFilter::default(),
             filter: None,
             built: false,
         }
@@ -135,10 +137,14 @@ impl Builder {
         level: LevelFilter,
         negated: bool,
     ) -> &mut Self {
+        let topic = TopicPattern::new(topic);
+        if let Some(err) = topic.errors().next() {
+            panic!("invalid topic filter: {err}");
+        }
         self.insert_directive(Directive {
             kind: DirectiveKind::Topic {
                 component: component.to_string(),
-                topic: FilterOp::new(topic).expect("invalid topic filter"),
+                topic,
             },
             level,
             negated,
@@ -167,6 +173,26 @@ impl Builder {
         self
     }
 
+    /// Sets the filter on the entity, i.e. symbol or operation, that records relate to.
+    pub fn filter_trace(&mut self, filter: TraceFilter) -> &mut Self {
+        self.trace_filter = filter;
+        self
+    }
+
+    /// Parses a trace filter string, in the format of the `MIDENC_TRACE_FILTER` environment
+    /// variable.
+    ///
+    /// See [TraceFilter] for more details.
+    pub fn parse_trace_filter(&mut self, filter: &str) -> &mut Self {
+        #![allow(clippy::print_stderr)] // compatibility
+
+        match TraceFilter::parse(filter) {
+            Ok(filter) => self.trace_filter = filter,
+            Err(error) => eprintln!("warning: {error}, ignoring it"),
+        }
+        self
+    }
+
     /// Parses the directives string.
     ///
     /// See the [Enabling Logging] section for more details.
@@ -214,8 +240,10 @@ impl Builder {
         assert!(!self.built, "attempt to re-use consumed builder");
         self.built = true;
 
-        let mut directives = Vec::new();
-        if self.directives.is_empty() {
+        // Negated directives are applied after the positive ones, so are kept separately
+        let (mut directives, negated_directives): (Vec<_>, Vec<_>) =
+            mem::take(&mut self.directives).into_iter().partition(|d| !d.negated);
+        if directives.is_empty() {
             // Adds the default filter if none exist
             directives.push(Directive {
                 kind: DirectiveKind::Any,
@@ -223,15 +251,15 @@ impl Builder {
                 negated: false,
             });
         } else {
-            // Consume directives.
-            directives = mem::take(&mut self.directives);
             // Sort the directives to place more efficient matches at the start
             directives.sort();
         }
 
         Filter {
             directives,
+            negated_directives,
             kv_filters: mem::take(&mut self.kv_filters),
+            trace_filter: mem::take(&mut self.trace_filter),
             filter: mem::take(&mut self.filter),
         }
     }
@@ -251,6 +279,7 @@ impl fmt::Debug for Builder {
             f.debug_struct("Filter")
                 .field("filter", &self.filter)
                 .field("kv_filters", &self.kv_filters)
+                .field("trace_filter", &self.trace_filter)
                 .field("directives", &self.directives)
                 .finish()
         }
@@ -267,7 +296,9 @@ impl fmt::Debug for Builder {
 #[derive(Clone)]
 pub struct Filter {
     directives: Vec<Directive>,
+    negated_directives: Vec<Directive>,
     kv_filters: Vec<KvFilter>,
+    trace_filter: TraceFilter,
     filter: Option<FilterOp>,
 }
 
@@ -297,8 +328,7 @@ impl Filter {
         let level = record.metadata().level();
         let target = record.metadata().target();
 
-        let is_enabled = enabled(&self.directives, level, target);
-        if !is_enabled {
+        if !self.is_enabled(level, target) {
             return false;
         }
 
@@ -332,10 +362,13 @@ impl Filter {
 
     /// Determines if a log message with the specified metadata would be logged.
     pub fn enabled(&self, metadata: &Metadata<'_>) -> bool {
-        let level = metadata.level();
-        let target = metadata.target();
+        self.is_enabled(metadata.level(), metadata.target())
+    }
 
+    fn is_enabled(&self, level: log::Level, target: &str) -> bool {
         enabled(&self.directives, level, target)
+            && !suppressed(&self.negated_directives, level, target)
+            && self.trace_filter.matches(target)
     }
 }
 
@@ -343,7 +376,9 @@ impl fmt::Debug for Filter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Filter")
             .field("filter", &self.filter)
+            .field("trace_filter", &self.trace_filter)
             .field("directives", &self.directives)
+            .field("negated_directives", &self.negated_directives)
             .finish()
     }
 }
@@ -44,11 +44,16 @@ mod filtered_log;
 mod kv_filter;
 mod op;
 mod parser;
+mod topic;
+mod trace_filter;
 
-use directive::{Directive, DirectiveKind, enabled};
+use directive::{Directive, DirectiveKind, enabled, suppressed};
 pub use filter::{Builder, Filter};
 pub use filtered_log::FilteredLog;
 use kv_filter::KvFilter;
 use op::FilterOp;
 pub use parser::ParseError;
 use parser::parse_spec;
+use topic::TopicPattern;
+pub use trace_filter::TraceFilter;
+pub(crate) use trace_filter::split_entity;
@@ -6,7 +6,7 @@ use std::{
 use log::LevelFilter;
 
 use super::directive::DirectiveKind;
-use crate::filter::{Directive, FilterOp};
+use crate::filter::{Directive, FilterOp, TopicPattern};
 
 #[derive(Default, Debug)]
 pub(crate) struct ParseResult {
@@ -48,6 +48,12 @@ pub struct ParseError {
     details: String,
 }
 
+impl ParseError {
+    pub(crate) fn new(details: String) -> Self {
+        Self { details }
+    }
+}
+
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "error parsing logger filter: {}", self.details)
@@ -61,11 +67,10 @@ impl Error for ParseError {}
 pub(crate) fn parse_spec(s: &str) -> ParseResult {
     let mut result = ParseResult::default();
 
-    let (spec, filter) = s.rsplit_once('/').unwrap_or((s, ""));
-    if spec.contains('/') {
+    let Some((spec, filter)) = split_message_filter(s) else {
         result.add_error(format!("invalid logging spec '{s}': too many '/'"));
         return result;
-    }
+    };
     let filter = if filter.is_empty() {
         None
     } else {
@@ -87,7 +92,7 @@ pub(crate) fn parse_spec(s: &str) -> ParseResult {
                     continue;
                 }
                 if let Some(ms) = ms.strip_prefix('-') {
-                    (Some(ms), LevelFilter::max(), true)
+                    (Some(ms.trim()), LevelFilter::Off, true)
                 } else {
                     (Some(ms), LevelFilter::max(), false)
                 }
@@ -107,7 +112,7 @@ pub(crate) fn parse_spec(s: &str) -> ParseResult {
                 match level {
                     Ok(level) => {
                         if let Some(ms) = ms.strip_prefix('-') {
-                            (Some(ms), level, true)
+                            (Some(ms.trim()), level, true)
                         } else {
                             (Some(ms), level, false)
                         }
@@ -119,14 +124,16 @@ pub(crate) fn parse_spec(s: &str) -> ParseResult {
                 }
             }
             None => {
-                let (level, negated) = if let Some(level) = directive.strip_prefix('-') {
-                    (level.trim(), true)
+                let (spec, negated) = if let Some(spec) = directive.strip_prefix('-') {
+                    (spec.trim(), true)
                 } else {
                     (directive, false)
                 };
-                match level.parse::<LevelFilter>() {
+                match spec.parse::<LevelFilter>() {
                     Ok(level) => (None, level, negated),
-                    Err(_) => (Some(directive), LevelFilter::max(), negated),
+                    // A negated directive without a level suppresses records at any level
+                    Err(_) if negated => (Some(spec), LevelFilter::Off, true),
+                    Err(_) => (Some(spec), LevelFilter::max(), false),
                 }
             }
         };
@@ -155,13 +162,15 @@ pub(crate) fn parse_spec(s: &str) -> ParseResult {
                     continue;
                 }
                 Some((component, topic)) => {
-                    let topic = match FilterOp::new(topic) {
-                        Ok(topic) => topic,
-                        Err(err) => {
-                            result.add_error(format!("invalid logging spec '{directive}': {err}"));
-                            continue;
-                        }
-                    };
+                    // Invalid topic regexes are not fatal, they simply never match
+                    let topic = TopicPattern::new(topic);
+                    #[allow(clippy::print_stderr)]
+                    for err in topic.errors() {
+                        eprintln!(
+                            "warning: invalid logging spec '{directive}': {err}, it will never \
+                             match"
+                        );
+                    }
                     result.add_directive(Directive {
                         kind: DirectiveKind::Topic {
                             component: component.to_owned(),
@@ -200,13 +209,39 @@ pub(crate) fn parse_spec(s: &str) -> ParseResult {
     result
 }
 
+/// Split a logging specification into its directives and the message filter following the `/`.
+///
+/// A `/` which immediately follows a `:` starts a `/.../`-delimited topic regex, and so does not
+/// separate the message filter. Returns `None` if there is more than one separator.
+fn split_message_filter(s: &str) -> Option<(&str, &str)> {
+    let mut in_regex = false;
+    let mut prev = None;
+    for (index, c) in s.char_indices() {
+        match c {
+            '/' if in_regex => in_regex = false,
+            '/' if prev == Some(':') => in_regex = true,
+            '/' => {
+                let (spec, filter) = (&s[..index], &s[index + 1..]);
+                return if filter.contains('/') {
+                    None
+                } else {
+                    Some((spec, filter))
+                };
+            }
+            _ => (),
+        }
+        prev = Some(c);
+    }
+    Some((s, ""))
+}
+
 #[cfg(test)]
 mod tests {
     use log::LevelFilter;
     use snapbox::{Data, IntoData, assert_data_eq, str};
 
     use super::{ParseResult, parse_spec};
-    use crate::filter::{ParseError, directive::DirectiveKind, op::FilterOp};
+    use crate::filter::{ParseError, TopicPattern, directive::DirectiveKind};
 
     impl IntoData for ParseError {
         fn into_data(self) -> Data {
@@ -254,7 +289,7 @@ mod tests {
             dirs[3].kind,
             DirectiveKind::Topic {
                 component: "component".to_owned(),
-                topic: FilterOp::new("topic").unwrap()
+                topic: TopicPattern::new("topic")
             }
         );
         assert_eq!(dirs[3].level, LevelFilter::Trace);
@@ -526,6 +561,97 @@ mod tests {
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn parse_spec_negated_component() {
+        let ParseResult {
+            directives: dirs,
+            filter,
+            errors,
+        } = parse_spec("trace,-dataflow,-codegen=debug");
+        assert_eq!(dirs.len(), 3);
+        assert_eq!(dirs[0].kind, DirectiveKind::Any);
+        assert_eq!(dirs[0].level, LevelFilter::Trace);
+        assert!(!dirs[0].negated);
+        assert_eq!(
+            dirs[1].kind,
+            DirectiveKind::Component {
+                component: "dataflow".to_owned()
+            }
+        );
+        assert_eq!(dirs[1].level, LevelFilter::Off);
+        assert!(dirs[1].negated);
+        assert_eq!(
+            dirs[2].kind,
+            DirectiveKind::Component {
+                component: "codegen".to_owned()
+            }
+        );
+        assert_eq!(dirs[2].level, LevelFilter::Debug);
+        assert!(dirs[2].negated);
+        assert!(filter.is_none());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn parse_spec_negated_topic() {
+        let ParseResult {
+            directives: dirs,
+            filter,
+            errors,
+        } = parse_spec("dataflow=trace, -dataflow:solver=trace");
+        assert_eq!(dirs.len(), 2);
+        assert!(!dirs[0].negated);
+        assert_eq!(
+            dirs[1].kind,
+            DirectiveKind::Topic {
+                component: "dataflow".to_owned(),
+                topic: TopicPattern::new("solver"),
+            }
+        );
+        assert_eq!(dirs[1].level, LevelFilter::Trace);
+        assert!(dirs[1].negated);
+        assert!(filter.is_none());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn parse_spec_regex_topic() {
+        let ParseResult {
+            directives: dirs,
+            filter,
+            errors,
+        } = parse_spec("pass:.*=trace,codegen:/solver:lin.*/=debug/foo");
+        assert_eq!(dirs.len(), 2);
+        assert_eq!(
+            dirs[0].kind,
+            DirectiveKind::Topic {
+                component: "pass".to_owned(),
+                topic: TopicPattern::new(".*"),
+            }
+        );
+        assert_eq!(
+            dirs[1].kind,
+            DirectiveKind::Topic {
+                component: "codegen".to_owned(),
+                topic: TopicPattern::new("/solver:lin.*/"),
+            }
+        );
+        assert_eq!(dirs[1].level, LevelFilter::Debug);
+        assert!(filter.is_some_and(|filter| filter.to_string() == "foo"));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn parse_spec_invalid_regex_topic_is_not_an_error() {
+        let ParseResult {
+            directives: dirs,
+            errors,
+            ..
+        } = parse_spec("pass:(cse=trace");
+        assert_eq!(dirs.len(), 1);
+        assert!(errors.is_empty());
+    }
+
     #[test]
     fn parse_spec_valid_filter() {
         let ParseResult {
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use super::FilterOp;
+
+/// The characters which, when present in a topic segment, cause it to be treated as a regular
+/// expression rather than a literal.
+const META_CHARACTERS: &[char] =
+    &['\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$'];
+
+/// The topic portion of a directive, e.g. `solver:linear` in `codegen:solver:linear=trace`.
+///
+/// The pattern is split into `:`-separated segments, each of which is matched against the
+/// corresponding topic segment of a structured target. A pattern with fewer segments than the
+/// target matches any target whose leading topic segments match, e.g. `solver` matches the topic
+/// `solver:linear`.
+///
+/// Segments are matched literally, unless they contain regular expression metacharacters, or are
+/// wrapped in `/.../`, in which case they are compiled as a regular expression. Regular
+/// expressions are unanchored, i.e. `s.` matches `cse`, use `^` and `$` to anchor them. Segments
+/// which are not valid regular expressions never match.
+#[derive(Clone)]
+pub(crate) struct TopicPattern {
+    source: String,
+    segments: Vec<TopicSegment>,
+}
+
+#[derive(Clone)]
+enum TopicSegment {
+    Literal(String),
+    Regex(FilterOp),
+    Invalid(String),
+}
+
+impl TopicSegment {
+    fn new(segment: &str) -> Self {
+        let pattern = match segment.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+            Some(pattern) => pattern,
+            None if segment.contains(META_CHARACTERS) => segment,
+            None => return Self::Literal(segment.to_owned()),
+        };
+        match FilterOp::new(pattern) {
+            Ok(pattern) => Self::Regex(pattern),
+            Err(err) => Self::Invalid(format!("invalid topic pattern '{segment}': {err}")),
+        }
+    }
+
+    fn is_match(&self, topic: &str) -> bool {
+        match self {
+            Self::Literal(literal) => literal == topic,
+            Self::Regex(pattern) => pattern.is_match(topic),
+            Self::Invalid(_) => false,
+        }
+    }
+}
+
+impl TopicPattern {
+    pub fn new(pattern: &str) -> Self {
+        let segments = split_segments(pattern).map(TopicSegment::new).collect();
+        Self {
+            source: pattern.to_owned(),
+            segments,
+        }
+    }
+
+    /// Returns the errors for any segments of this pattern which are not valid regular expressions
+    pub fn errors(&self) -> impl Iterator<Item = &str> + '_ {
+        self.segments.iter().filter_map(|segment| match segment {
+            TopicSegment::Invalid(err) => Some(err.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Checks if `topic`, the `:`-separated topics of a structured target, matches this pattern
+    pub fn is_match(&self, topic: &str) -> bool {
+        let mut topics = topic.split(':');
+        self.segments
+            .iter()
+            .all(|segment| topics.next().is_some_and(|topic| segment.is_match(topic)))
+    }
+}
+
+/// Split `pattern` on `:`, except for those which appear in a `/.../`-delimited regex
+fn split_segments(pattern: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut rest = Some(pattern);
+    core::iter::from_fn(move || {
+        let pattern = rest?;
+        let end = if pattern.starts_with('/') {
+            pattern[1..]
+                .find('/')
+                .map(|close| close + 2)
+                .and_then(|close| pattern[close..].find(':').map(|separator| close + separator))
+        } else {
+            pattern.find(':')
+        };
+        match end {
+            Some(end) => {
+                rest = Some(&pattern[end + 1..]);
+                Some(&pattern[..end])
+            }
+            None => {
+                rest = None;
+                Some(pattern)
+            }
+        }
+    })
+}
+
+impl Eq for TopicPattern {}
+impl PartialEq for TopicPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl PartialOrd for TopicPattern {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopicPattern {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.source
+            .len()
+            .cmp(&other.source.len())
+            .then_with(|| self.source.cmp(&other.source))
+    }
+}
+
+impl fmt::Debug for TopicPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TopicPattern").field(&self.source).finish()
+    }
+}
+
+impl fmt::Display for TopicPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TopicPattern, split_segments};
+
+    #[test]
+    fn split_topic_segments() {
+        assert_eq!(split_segments("solver").collect::<Vec<_>>(), vec!["solver"]);
+        assert_eq!(split_segments("solver:linear").collect::<Vec<_>>(), vec!["solver", "linear"]);
+        assert_eq!(split_segments("/a:b/:c").collect::<Vec<_>>(), vec!["/a:b/", "c"]);
+        assert_eq!(split_segments("/a:b/").collect::<Vec<_>>(), vec!["/a:b/"]);
+    }
+
+    #[test]
+    fn literal_topics_match_exactly() {
+        let pattern = TopicPattern::new("cse");
+        assert!(pattern.is_match("cse"));
+        assert!(!pattern.is_match("cse2"));
+        assert!(!pattern.is_match("acse"));
+    }
+
+    #[test]
+    fn literal_topics_match_leading_segments() {
+        let pattern = TopicPattern::new("solver");
+        assert!(pattern.is_match("solver"));
+        assert!(pattern.is_match("solver:linear"));
+        assert!(!pattern.is_match("linear:solver"));
+
+        let pattern = TopicPattern::new("solver:linear");
+        assert!(pattern.is_match("solver:linear"));
+        assert!(!pattern.is_match("solver"));
+        assert!(!pattern.is_match("solver:copy-all"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_topics_are_unanchored() {
+        let pattern = TopicPattern::new("s.");
+        assert!(pattern.is_match("cse"));
+        assert!(!pattern.is_match("canonicalize"));
+
+        let pattern = TopicPattern::new(".*");
+        assert!(pattern.is_match("cse"));
+        assert!(pattern.is_match("solver:linear"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_topics_can_be_anchored() {
+        let pattern = TopicPattern::new("^cs");
+        assert!(pattern.is_match("cse"));
+        assert!(!pattern.is_match("ocse"));
+
+        let pattern = TopicPattern::new("cs$");
+        assert!(!pattern.is_match("cse"));
+
+        let pattern = TopicPattern::new("/^cse$/");
+        assert!(pattern.is_match("cse"));
+        assert!(!pattern.is_match("cse2"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn mixed_literal_and_regex_segments() {
+        let pattern = TopicPattern::new("solver:lin.*");
+        assert!(pattern.is_match("solver:linear"));
+        assert!(pattern.is_match("solver:linear-window"));
+        assert!(!pattern.is_match("solver:copy-all"));
+        assert!(!pattern.is_match("sol:linear"));
+
+        let pattern = TopicPattern::new("/solv/:linear");
+        assert!(pattern.is_match("solver:linear"));
+        assert!(!pattern.is_match("solver:linear-window"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn invalid_regex_topics_never_match() {
+        let pattern = TopicPattern::new("(cse");
+        assert!(!pattern.is_match("(cse"));
+        assert!(!pattern.is_match("cse"));
+        let errors = pattern.errors().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("invalid topic pattern '(cse'"), "{}", errors[0]);
+    }
+}
@@ -0,0 +1,203 @@
+use super::{FilterOp, ParseError};
+
+/// A filter on the entity a log record relates to, as given by `MIDENC_TRACE_FILTER`.
+///
+/// The entity is the last topic of a structured target, e.g. `foo` in `codegen:emit:foo`, and is
+/// either the name of a symbol, or the name of an operation. The filter is a comma-separated list
+/// of the following matchers, each of which may be negated with a leading `-`:
+///
+/// * `*` or `symbol:*`, matches any entity
+/// * `symbol:<regex>`, matches an entity whose name matches the regular expression
+/// * `op:<dialect.opname>`, matches an entity which is an operation of the given type
+///
+/// A record is rejected if it matches any negated matcher. Otherwise, if there are any positive
+/// matchers, at least one of them must match. Records whose target does not refer to an entity
+/// are never rejected by this filter, nor are those whose entity is given as `*`, which is used as
+/// a placeholder when the entity is unknown.
+#[derive(Debug, Clone, Default)]
+pub struct TraceFilter {
+    matchers: Vec<TraceMatcher>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TraceMatcher {
+    pub kind: TraceMatcherKind,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TraceMatcherKind {
+    Any,
+    Symbol(FilterOp),
+    Op(String),
+}
+
+impl TraceMatcherKind {
+    fn matches(&self, entity: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Symbol(pattern) => pattern.is_match(entity),
+            Self::Op(name) => name == entity,
+        }
+    }
+}
+
+impl TraceFilter {
+    /// Parse a filter from a string in the format of `MIDENC_TRACE_FILTER`
+    pub fn parse(spec: &str) -> Result<Self, ParseError> {
+        let mut matchers = Vec::new();
+        for matcher in spec.split(',').map(|m| m.trim()).filter(|m| !m.is_empty()) {
+            let (matcher, negated) =
+                matcher.strip_prefix('-').map(|m| (m, true)).unwrap_or((matcher, false));
+            let kind = match matcher.split_once(':') {
+                None if matcher == "*" => TraceMatcherKind::Any,
+                Some(("symbol", "*")) => TraceMatcherKind::Any,
+                Some(("symbol", pattern)) if !pattern.is_empty() => {
+                    let pattern = FilterOp::new(pattern).map_err(|err| {
+                        ParseError::new(format!("invalid trace filter '{matcher}': {err}"))
+                    })?;
+                    TraceMatcherKind::Symbol(pattern)
+                }
+                Some(("op", name)) if name.contains('.') => TraceMatcherKind::Op(name.to_owned()),
+                _ => {
+                    return Err(ParseError::new(format!(
+                        "invalid trace filter '{matcher}': expected `*`, `symbol:<regex>`, or \
+                         `op:<dialect.opname>`"
+                    )));
+                }
+            };
+            matchers.push(TraceMatcher { kind, negated });
+        }
+        Ok(Self { matchers })
+    }
+
+    /// Returns true if this filter has no matchers, i.e. it accepts everything
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Checks if records with the given `target` are accepted by this filter
+    pub fn matches(&self, target: &str) -> bool {
+        if self.matchers.is_empty() {
+            return true;
+        }
+        let Some(entity) = split_entity(target).1.filter(|entity| *entity != "*") else {
+            return true;
+        };
+
+        let mut was_matched = false;
+        let mut has_positive = false;
+        for matcher in self.matchers.iter() {
+            has_positive |= !matcher.negated;
+            if matcher.kind.matches(entity) {
+                if matcher.negated {
+                    return false;
+                }
+                was_matched = true;
+            }
+        }
+        was_matched || !has_positive
+    }
+}
+
+/// Split a structured `target` of the form `component:topic(:topic)*` into the scope and the
+/// entity given by its last topic, if it has more than one topic.
+///
+/// The entity may itself contain `::`, e.g. a qualified symbol name, so only a single `:` is
+/// treated as a separator. Targets which are not structured, e.g. Rust module paths, have no
+/// entity.
+pub(crate) fn split_entity(target: &str) -> (&str, Option<&str>) {
+    let Some((component, topics)) = target.split_once(':') else {
+        return (target, None);
+    };
+    if topics.starts_with(':') || !topics.contains(':') {
+        return (target, None);
+    }
+
+    let bytes = topics.as_bytes();
+    let mut separator = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b':' {
+            if bytes.get(i + 1) == Some(&b':') {
+                i += 2;
+                continue;
+            }
+            separator = Some(i);
+        }
+        i += 1;
+    }
+    match separator {
+        Some(index) => {
+            let split = component.len() + 1 + index;
+            (&target[..split], Some(&target[split + 1..]))
+        }
+        None => (target, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TraceFilter, TraceMatcherKind, split_entity};
+    use crate::filter::FilterOp;
+
+    #[test]
+    fn split_entity_structured_targets() {
+        assert_eq!(split_entity("codegen:emit:foo"), ("codegen:emit", Some("foo")));
+        assert_eq!(
+            split_entity("codegen:solver:linear:foo::bar"),
+            ("codegen:solver:linear", Some("foo::bar"))
+        );
+        assert_eq!(split_entity("pass:cse"), ("pass:cse", None));
+        assert_eq!(split_entity("cfg-to-scf"), ("cfg-to-scf", None));
+        assert_eq!(split_entity("crate1::mod1"), ("crate1::mod1", None));
+    }
+
+    #[test]
+    fn parse_trace_filter_valid() {
+        let filter = TraceFilter::parse("symbol:foo, -op:hir.call,*,symbol:*").unwrap();
+        let kinds = filter.matchers.iter().map(|m| (&m.kind, m.negated)).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (&TraceMatcherKind::Symbol(FilterOp::new("foo").unwrap()), false),
+                (&TraceMatcherKind::Op("hir.call".to_owned()), true),
+                (&TraceMatcherKind::Any, false),
+                (&TraceMatcherKind::Any, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_trace_filter_invalid() {
+        let err = TraceFilter::parse("function:foo").unwrap_err();
+        assert!(err.to_string().contains("invalid trace filter 'function:foo'"), "{err}");
+        assert!(TraceFilter::parse("op:call").is_err());
+        assert!(TraceFilter::parse("symbol:").is_err());
+        #[cfg(feature = "regex")]
+        assert!(TraceFilter::parse("symbol:(foo").is_err());
+    }
+
+    #[test]
+    fn trace_filter_matches_last_topic() {
+        let filter = TraceFilter::parse("symbol:foo").unwrap();
+        assert!(filter.matches("codegen:emit:foo"));
+        assert!(filter.matches("codegen:*:foo"));
+        assert!(!filter.matches("codegen:emit:bar"));
+        // Records which do not relate to an entity are unaffected
+        assert!(filter.matches("codegen:emit"));
+        assert!(filter.matches("codegen:solver:linear:*"));
+        assert!(filter.matches("codegen"));
+    }
+
+    #[test]
+    fn trace_filter_negated() {
+        let filter = TraceFilter::parse("-symbol:bar").unwrap();
+        assert!(filter.matches("codegen:emit:foo"));
+        assert!(!filter.matches("codegen:emit:bar"));
+
+        let filter = TraceFilter::parse("*,-op:hir.call").unwrap();
+        assert!(filter.matches("analysis:spills:hir.store"));
+        assert!(!filter.matches("analysis:spills:hir.call"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fmt::{self, Write as _},
+    io::{self, Write},
+};
+
+use log::Record;
+
+use super::{Formatter, TimestampPrecision};
+use crate::filter::split_entity;
+
+/// A format which writes each record as a single-line JSON object.
+///
+/// The structured target of the record is decomposed into its `component`, `topics`, and
+/// `symbol`, per the scope grammar described in the crate documentation, e.g. the target
+/// `codegen:solver:linear:foo` has component `codegen`, topics `["solver", "linear"]`, and symbol
+/// `foo`:
+///
+/// ```json
+/// {"timestamp":"2025-01-01T00:00:00Z","level":"TRACE","target":"codegen:solver:linear:foo","component":"codegen","topics":["solver","linear"],"symbol":"foo","module_path":"midenc_codegen_masm::opt","message":"..."}
+/// ```
+pub(crate) struct JsonFormat {
+    pub(crate) timestamp: Option<TimestampPrecision>,
+}
+
+impl super::RecordFormat for JsonFormat {
+    fn format(&self, formatter: &mut Formatter, record: &Record<'_>) -> io::Result<()> {
+        formatter.write_all(b"{")?;
+
+        #[cfg(feature = "humantime")]
+        if let Some(precision) = self.timestamp {
+            let timestamp = match precision {
+                TimestampPrecision::Seconds => formatter.timestamp_seconds(),
+                TimestampPrecision::Millis => formatter.timestamp_millis(),
+                TimestampPrecision::Micros => formatter.timestamp_micros(),
+                TimestampPrecision::Nanos => formatter.timestamp_nanos(),
+            };
+            write!(formatter, "\"timestamp\":\"{timestamp}\",")?;
+        }
+        #[cfg(not(feature = "humantime"))]
+        let _ = self.timestamp;
+
+        write!(formatter, "\"level\":\"{}\",", record.level())?;
+
+        let target = record.target();
+        write_field(formatter, "target", target)?;
+        let (scope, symbol) = split_entity(target);
+        let mut segments = scope.split(':');
+        write_field(formatter, "component", segments.next().unwrap_or_default())?;
+        formatter.write_all(b"\"topics\":[")?;
+        for (i, topic) in segments.enumerate() {
+            if i > 0 {
+                formatter.write_all(b",")?;
+            }
+            write_string(formatter, topic)?;
+        }
+        formatter.write_all(b"],")?;
+        // `*` is a placeholder used when the symbol is unknown
+        match symbol.filter(|symbol| *symbol != "*") {
+            Some(symbol) => write_field(formatter, "symbol", symbol)?,
+            None => formatter.write_all(b"\"symbol\":null,")?,
+        }
+        match record.module_path() {
+            Some(module_path) => write_field(formatter, "module_path", module_path)?,
+            None => formatter.write_all(b"\"module_path\":null,")?,
+        }
+
+        formatter.write_all(b"\"message\":")?;
+        write_string(formatter, record.args())?;
+        formatter.write_all(b"}\n")
+    }
+}
+
+/// Write `"name":value,` to `formatter`, escaping `value` as a JSON string
+fn write_field(formatter: &mut Formatter, name: &str, value: &str) -> io::Result<()> {
+    write!(formatter, "\"{name}\":")?;
+    write_string(formatter, value)?;
+    formatter.write_all(b",")
+}
+
+/// Write `value` to `formatter` as a JSON string, escaping it as it is rendered
+fn write_string(formatter: &mut Formatter, value: impl fmt::Display) -> io::Result<()> {
+    formatter.write_all(b"\"")?;
+    let mut escaper = JsonEscaper {
+        inner: formatter,
+        error: None,
+    };
+    if write!(escaper, "{value}").is_err() {
+        return Err(escaper.error.unwrap_or_else(|| io::Error::other("formatter error")));
+    }
+    formatter.write_all(b"\"")
+}
+
+/// An adapter which escapes everything written to it as the contents of a JSON string
+struct JsonEscaper<'a> {
+    inner: &'a mut Formatter,
+    error: Option<io::Error>,
+}
+
+impl fmt::Write for JsonEscaper<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let bytes = s.as_bytes();
+        let mut start = 0;
+        for (i, &byte) in bytes.iter().enumerate() {
+            let escape: &[u8] = match byte {
+                b'"' => b"\\\"",
+                b'\\' => b"\\\\",
+                b'\n' => b"\\n",
+                b'\r' => b"\\r",
+                b'\t' => b"\\t",
+                0x00..=0x1f => b"",
+                _ => continue,
+            };
+            let result = self.inner.write_all(&bytes[start..i]).and_then(|_| {
+                if escape.is_empty() {
+                    write!(self.inner, "\\u{byte:04x}")
+                } else {
+                    self.inner.write_all(escape)
+                }
+            });
+            if let Err(err) = result {
+                self.error = Some(err);
+                return Err(fmt::Error);
+            }
+            start = i + 1;
+        }
+        self.inner.write_all(&bytes[start..]).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
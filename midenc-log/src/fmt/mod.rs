@@ -65,6 +65,7 @@ use log::Record;
 
 #[cfg(feature = "humantime")]
 mod humantime;
+mod json;
 #[cfg(feature = "kv")]
 mod kv;
 
@@ -210,6 +211,7 @@ pub(crate) type FormatFn = Box<dyn RecordFormat + Sync + Send>;
 pub(crate) struct Builder {
     pub(crate) default_format: ConfigurableFormat,
     pub(crate) custom_format: Option<FormatFn>,
+    pub(crate) json: bool,
     built: bool,
 }
 
@@ -217,8 +219,9 @@ impl Builder {
     /// Convert the format into a callable function.
     ///
     /// If the `custom_format` is `Some`, then any `default_format` switches are ignored.
-    /// If the `custom_format` is `None`, then a default format is returned.
-    /// Any `default_format` switches set to `false` won't be written by the format.
+    /// If the `custom_format` is `None`, then a default format is returned, which is the JSON
+    /// format if `json` is set. Any `default_format` switches set to `false` won't be written by
+    /// the default text format, the JSON format only respects the timestamp precision.
     pub(crate) fn build(&mut self) -> FormatFn {
         assert!(!self.built, "attempt to re-use consumed builder");
 
@@ -232,6 +235,10 @@ impl Builder {
 
         if let Some(fmt) = built.custom_format {
             fmt
+        } else if built.json {
+            Box::new(json::JsonFormat {
+                timestamp: built.default_format.timestamp,
+            })
         } else {
             Box::new(built.default_format)
         }
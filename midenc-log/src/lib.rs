@@ -27,7 +27,10 @@
 //!    b. Emit everything except trace-level logs for the "dataflow" component: `MIDENC_TRACE=trace,-dataflow=trace`
 //!    c. Emit logs for the "dataflow" component, except trace-level logs for the "solver" topic: `MIDENC_TRACE=dataflow=trace,-dataflow:solver=trace`
 //!
-//! By default, logs are written to `stderr`, but this is configurable.
+//! By default, logs are written to `stderr`, but this is configurable. Setting `MIDENC_LOG_FILE` to
+//! a path writes logs to that file instead, and additionally setting `MIDENC_LOG_TEE=1` writes them
+//! to both. Setting `MIDENC_LOG_FORMAT=json` writes each record as a line of JSON, for analyzing
+//! logs programmatically.
 //!
 //! NOTE: The functionality here relies on `log` targets using a structured format that we can
 //! parse and extract the relevant pieces from. Any `log` targets which are not in this format
@@ -45,6 +48,11 @@
 //! that log message, and so will be filtered by `MIDENC_TRACE_FILTER`, not any topic patterns in
 //! `MIDENC_TRACE`.
 //!
+//! Rather than building these targets at each call site, code can open a [scope] for the
+//! component, topic, and symbol it is working on, and log using the [scoped_trace] and
+//! [scoped_debug] macros, which compose the target from the innermost active scope. The pass
+//! manager in `midenc-hir` opens a `pass:<name>:<symbol>` scope around each pass it runs.
+//!
 //! ## Example
 //!
 //! ```
@@ -315,6 +323,8 @@
 
 pub mod filter;
 mod logger;
+mod recent;
+mod scope;
 mod suppress;
 mod writer;
 
@@ -323,9 +333,18 @@ pub mod fmt;
 pub use self::{
     fmt::{Target, TimestampPrecision, WriteStyle},
     logger::*,
+    recent::{DEFAULT_RECENT_CAPACITY, DEFAULT_RECENT_LEVEL, RecentRecords, dump_recent},
+    scope::{LogScope, current_target, scope},
     suppress::SuppressKnownDependencyErrors,
 };
 
+#[doc(hidden)]
+pub mod __private {
+    pub use log;
+
+    pub use crate::scope::with_target;
+}
+
 #[doc = include_str!("../README.md")]
 #[cfg(doctest)]
 pub struct ReadmeDoctests;
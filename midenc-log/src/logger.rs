@@ -1,18 +1,29 @@
-use std::{borrow::Cow, cell::RefCell, env, io};
+use std::{borrow::Cow, cell::RefCell, env, fs, io, path::Path, sync::Arc};
 
 use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
 
 use crate::{
     fmt,
     fmt::{FormatFn, Formatter},
+    recent::{DEFAULT_RECENT_CAPACITY, DEFAULT_RECENT_LEVEL, RecentRecords},
     writer::{self, Writer},
 };
 
 /// The default name for the environment variable to read filters from.
 pub const DEFAULT_FILTER_ENV: &str = "MIDENC_TRACE";
 
-/// The default name for the environment variable to read kv filters from.
-pub const DEFAULT_KV_FILTER_ENV: &str = "MIDENC_TRACE_FILTER";
+/// The default name for the environment variable to read symbol/op filters from.
+pub const DEFAULT_TRACE_FILTER_ENV: &str = "MIDENC_TRACE_FILTER";
+
+/// The default name for the environment variable to read the path of a log file from.
+pub const DEFAULT_LOG_FILE_ENV: &str = "MIDENC_LOG_FILE";
+
+/// The default name for the environment variable which, when set, writes records to the log file
+/// as well as the target.
+pub const DEFAULT_LOG_TEE_ENV: &str = "MIDENC_LOG_TEE";
+
+/// The default name for the environment variable to read the log format from.
+pub const DEFAULT_LOG_FORMAT_ENV: &str = "MIDENC_LOG_FORMAT";
 
 /// The default name for the environment variable to read style preferences from.
 pub const DEFAULT_WRITE_STYLE_ENV: &str = "MIDENC_TRACE_STYLE";
@@ -39,14 +50,28 @@ pub const DEFAULT_WRITE_STYLE_ENV: &str = "MIDENC_TRACE_STYLE";
 /// error!("error message");
 /// info!("info message");
 /// ```
-#[derive(Default)]
 pub struct Builder {
     filter: crate::filter::Builder,
     writer: writer::Builder,
     format: fmt::Builder,
+    recent_capacity: usize,
+    recent_level: LevelFilter,
     built: bool,
 }
 
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            filter: Default::default(),
+            writer: Default::default(),
+            format: Default::default(),
+            recent_capacity: DEFAULT_RECENT_CAPACITY,
+            recent_level: DEFAULT_RECENT_LEVEL,
+            built: false,
+        }
+    }
+}
+
 impl Builder {
     /// Initializes the log builder with defaults.
     ///
@@ -161,14 +186,37 @@ impl Builder {
             self.parse_filters(&s);
         }
 
-        if let Some(s) = env.get_kv_filter() {
-            self.parse_kv_filters(&s);
+        if let Some(s) = env.get_trace_filter() {
+            self.parse_trace_filter(&s);
         }
 
         if let Some(s) = env.get_write_style() {
             self.parse_write_style(&s);
         }
 
+        if let Some(format) = env.get_log_format() {
+            match format.as_str() {
+                "json" => {
+                    self.format_json();
+                }
+                "" | "text" => (),
+                #[allow(clippy::print_stderr)] // there is nowhere else to report this
+                other => eprintln!("warning: unknown log format '{other}', ignoring it"),
+            }
+        }
+
+        if let Some(path) = env.get_log_file() {
+            let append = false;
+            if let Err(err) = self.target_file(&path, append) {
+                #[allow(clippy::print_stderr)] // there is nowhere else to report this
+                eprintln!("warning: unable to open log file '{path}': {err}, ignoring it");
+            }
+        }
+
+        if let Some(tee) = env.get_log_tee() {
+            self.tee(!matches!(tee.as_str(), "" | "0" | "false"));
+        }
+
         self
     }
 
@@ -265,6 +313,18 @@ impl Builder {
         self
     }
 
+    /// Writes each record as a single line of JSON, rather than in the default text format.
+    ///
+    /// Each object contains the timestamp (if enabled), level, target, the `component`, `topics`
+    /// and `symbol` of the structured target, module path, and message of the record. This is
+    /// intended for analyzing logs programmatically.
+    ///
+    /// This has no effect if a custom format is set via [`format`](Self::format).
+    pub fn format_json(&mut self) -> &mut Self {
+        self.format.json = true;
+        self
+    }
+
     /// Whether or not to write the level in the default format.
     pub fn format_level(&mut self, write: bool) -> &mut Self {
         self.format.default_format.level(write);
@@ -411,9 +471,17 @@ impl Builder {
         self
     }
 
-    /// Parses a kv filter string from the `MIDENC_TRACE_FILTER` environment variable.
+    /// Parses a symbol/op filter string in the same form as the `MIDENC_TRACE_FILTER`
+    /// environment variable.
     ///
     /// See the module documentation for more details.
+    pub fn parse_trace_filter(&mut self, filter: &str) -> &mut Self {
+        self.filter.parse_trace_filter(filter);
+        self
+    }
+
+    /// Parses a key-value filter string, in the form `key:value[,key2:value2,..]`, which is
+    /// matched against the key-value pairs attached to records.
     pub fn parse_kv_filters(&mut self, filters: &str) -> &mut Self {
         for filter in filters.split(',').map(|f| f.trim()) {
             let (filter, negated) =
@@ -449,6 +517,58 @@ impl Builder {
         self
     }
 
+    /// Configures the ring buffer of recent records dumped by [`dump_recent`](crate::dump_recent).
+    ///
+    /// Up to `capacity` records at `level` and below are retained, whether or not they are
+    /// filtered from the output. A `capacity` of zero disables the buffer. By default,
+    /// [`DEFAULT_RECENT_CAPACITY`](crate::DEFAULT_RECENT_CAPACITY) records are retained at
+    /// [`DEFAULT_RECENT_LEVEL`](crate::DEFAULT_RECENT_LEVEL).
+    ///
+    /// Note that [`Logger::filter`] takes `level` into account, so that the records are not
+    /// discarded by the `log` crate before they reach the logger.
+    pub fn recent_records(&mut self, capacity: usize, level: LevelFilter) -> &mut Self {
+        self.recent_capacity = capacity;
+        self.recent_level = level;
+        self
+    }
+
+    /// Writes log records to the file at `path`, rather than the target.
+    ///
+    /// The file is created if it does not exist, and is truncated unless `append` is true. Writes
+    /// to the file are buffered, and flushed when the logger is flushed or dropped. Styles are
+    /// not written to the file unless [`WriteStyle::Always`](fmt::WriteStyle::Always) is set.
+    ///
+    /// See [`tee`](Self::tee) to write records to both the file and the target.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use midenc_log::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    ///
+    /// builder.target_file("trace.log", false).expect("unable to open log file");
+    /// ```
+    pub fn target_file(&mut self, path: impl AsRef<Path>, append: bool) -> io::Result<&mut Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        self.writer.file(file);
+        Ok(self)
+    }
+
+    /// Sets whether or not records are written to the target as well as the log file given to
+    /// [`target_file`](Self::target_file).
+    ///
+    /// This has no effect if there is no log file.
+    pub fn tee(&mut self, tee: bool) -> &mut Self {
+        self.writer.tee(tee);
+        self
+    }
+
     /// Sets whether or not styles will be written.
     ///
     /// This can be useful in environments that don't support control characters
@@ -532,10 +652,17 @@ impl Builder {
         assert!(!self.built, "attempt to re-use consumed builder");
         self.built = true;
 
+        let recent = (self.recent_capacity > 0 && self.recent_level != LevelFilter::Off)
+            .then(|| Arc::new(RecentRecords::new(self.recent_capacity, self.recent_level)));
+        if let Some(recent) = recent.as_ref() {
+            recent.register();
+        }
+
         Logger {
             writer: self.writer.build(),
             filter: self.filter.build(),
             format: self.format.build(),
+            recent,
         }
     }
 }
@@ -548,6 +675,8 @@ impl std::fmt::Debug for Builder {
             f.debug_struct("Logger")
                 .field("filter", &self.filter)
                 .field("writer", &self.writer)
+                .field("recent_capacity", &self.recent_capacity)
+                .field("recent_level", &self.recent_level)
                 .finish()
         }
     }
@@ -576,6 +705,7 @@ pub struct Logger {
     writer: Writer,
     filter: crate::filter::Filter,
     format: FormatFn,
+    recent: Option<Arc<RecentRecords>>,
 }
 
 impl Logger {
@@ -634,15 +764,23 @@ impl Logger {
     }
 
     /// Returns the maximum `LevelFilter` that this env logger instance is
-    /// configured to output.
+    /// configured to output, or to retain in its buffer of recent records.
     pub fn filter(&self) -> LevelFilter {
-        self.filter.filter()
+        let recent = self.recent.as_ref().map(|recent| recent.level()).unwrap_or(LevelFilter::Off);
+        self.filter.filter().max(recent)
     }
 
     /// Checks if this record matches the configured filter.
     pub fn matches(&self, record: &Record<'_>) -> bool {
         self.filter.matches(record)
     }
+
+    /// Returns the buffer of recent records retained by this logger, if enabled.
+    ///
+    /// See [`Builder::recent_records`].
+    pub fn recent(&self) -> Option<&RecentRecords> {
+        self.recent.as_deref()
+    }
 }
 
 impl Log for Logger {
@@ -651,6 +789,10 @@ impl Log for Logger {
     }
 
     fn log(&self, record: &Record<'_>) {
+        if let Some(recent) = self.recent.as_ref() {
+            recent.push(record);
+        }
+
         if self.matches(record) {
             // Log records are written to a thread-local buffer before being printed
             // to the terminal. We clear these buffers afterwards, but they aren't shrunk
@@ -712,7 +854,15 @@ impl Log for Logger {
         }
     }
 
-    fn flush(&self) {}
+    fn flush(&self) {
+        let _ = self.writer.flush();
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
 }
 
 impl std::fmt::Debug for Logger {
@@ -728,15 +878,21 @@ impl std::fmt::Debug for Logger {
 /// By default, the `Env` will read the following environment variables:
 ///
 /// - `MIDENC_TRACE`: the level filter
-/// - `MIDENC_TRACE_FILTER`: a key-value filter to apply
+/// - `MIDENC_TRACE_FILTER`: a filter on the symbol or operation that records relate to
 /// - `MIDENC_TRACE_STYLE`: whether or not to print styles with records.
+/// - `MIDENC_LOG_FORMAT`: the format of records, either `text` (the default) or `json`
+/// - `MIDENC_LOG_FILE`: the path of a file to write records to, rather than the target
+/// - `MIDENC_LOG_TEE`: whether or not to write records to the target as well as the log file
 ///
 /// These sources can be configured using the builder methods on `Env`.
 #[derive(Debug)]
 pub struct Env<'a> {
     filter: Var<'a>,
-    kv_filter: Var<'a>,
+    trace_filter: Var<'a>,
     write_style: Var<'a>,
+    log_format: Var<'a>,
+    log_file: Var<'a>,
+    log_tee: Var<'a>,
 }
 
 impl<'a> Env<'a> {
@@ -784,8 +940,8 @@ impl<'a> Env<'a> {
         self.filter.get()
     }
 
-    fn get_kv_filter(&self) -> Option<String> {
-        self.kv_filter.get()
+    fn get_trace_filter(&self) -> Option<String> {
+        self.trace_filter.get()
     }
 
     /// Specify an environment variable to read the style from.
@@ -826,6 +982,28 @@ impl<'a> Env<'a> {
     fn get_write_style(&self) -> Option<String> {
         self.write_style.get()
     }
+
+    /// Specify an environment variable to read the path of a log file from.
+    pub fn log_file<E>(mut self, log_file_env: E) -> Self
+    where
+        E: Into<Cow<'a, str>>,
+    {
+        self.log_file = Var::new(log_file_env);
+
+        self
+    }
+
+    fn get_log_format(&self) -> Option<String> {
+        self.log_format.get()
+    }
+
+    fn get_log_file(&self) -> Option<String> {
+        self.log_file.get()
+    }
+
+    fn get_log_tee(&self) -> Option<String> {
+        self.log_tee.get()
+    }
 }
 
 impl<'a, T> From<T> for Env<'a>
@@ -841,8 +1019,11 @@ impl Default for Env<'_> {
     fn default() -> Self {
         Env {
             filter: Var::new(DEFAULT_FILTER_ENV),
-            kv_filter: Var::new(DEFAULT_KV_FILTER_ENV),
+            trace_filter: Var::new(DEFAULT_TRACE_FILTER_ENV),
             write_style: Var::new(DEFAULT_WRITE_STYLE_ENV),
+            log_format: Var::new(DEFAULT_LOG_FORMAT_ENV),
+            log_file: Var::new(DEFAULT_LOG_FILE_ENV),
+            log_tee: Var::new(DEFAULT_LOG_TEE_ENV),
         }
     }
 }
@@ -1053,4 +1234,12 @@ mod tests {
 
         assert_eq!(builder.filter.build().filter(), LevelFilter::Debug);
     }
+
+    #[test]
+    fn recent_records_do_not_raise_the_default_max_level() {
+        let mut builder = Builder::new();
+        builder.filter_level(LevelFilter::Error);
+
+        assert_eq!(builder.build().filter(), LevelFilter::Warn);
+    }
 }
@@ -0,0 +1,169 @@
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Mutex},
+};
+
+use log::{Level, LevelFilter, Metadata, Record};
+
+use crate::filter::Filter;
+
+/// The default number of records retained by [RecentRecords].
+pub const DEFAULT_RECENT_CAPACITY: usize = 4096;
+
+/// The default maximum level of records retained by [RecentRecords].
+///
+/// Retaining records at a level which is filtered from the output means formatting every record
+/// at that level, so by default only warnings and errors are retained.
+pub const DEFAULT_RECENT_LEVEL: LevelFilter = LevelFilter::Warn;
+
+/// The recent records of the most recently built [crate::Logger], used by [dump_recent].
+static RECENT: Mutex<Option<Arc<RecentRecords>>> = Mutex::new(None);
+
+/// A bounded ring buffer of the most recent log records.
+///
+/// Records are retained regardless of whether or not they are filtered from the output of the
+/// logger, so that they can be dumped when the compiler fails, without having to reproduce the
+/// failure with tracing enabled. See [dump_recent].
+#[derive(Debug)]
+pub struct RecentRecords {
+    capacity: usize,
+    level: LevelFilter,
+    records: Mutex<VecDeque<RecentRecord>>,
+}
+
+#[derive(Debug)]
+struct RecentRecord {
+    level: Level,
+    target: String,
+    message: String,
+}
+
+impl RecentRecords {
+    /// Create a buffer which retains up to `capacity` records at `level` and below
+    pub fn new(capacity: usize, level: LevelFilter) -> Self {
+        Self {
+            capacity,
+            level,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// The maximum level of records retained by this buffer
+    pub fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    /// The number of records currently retained
+    pub fn len(&self) -> usize {
+        self.records.lock().expect("no panics while held").len()
+    }
+
+    /// Returns true if no records are currently retained
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn push(&self, record: &Record<'_>) {
+        if self.capacity == 0 || record.level() > self.level {
+            return;
+        }
+        let record = RecentRecord {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        };
+        let mut records = self.records.lock().expect("no panics while held");
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Write the retained records to `writer`, oldest first, one per line.
+    ///
+    /// If `filter` is given, only records which it enables are written. This makes it possible to
+    /// narrow down the dump by structured target after the fact.
+    pub fn dump(&self, writer: &mut dyn io::Write, filter: Option<&Filter>) -> io::Result<usize> {
+        let records = self.records.lock().expect("no panics while held");
+        let mut written = 0;
+        for record in records.iter() {
+            let metadata = Metadata::builder().level(record.level).target(&record.target).build();
+            if filter.is_some_and(|filter| !filter.enabled(&metadata)) {
+                continue;
+            }
+            writeln!(writer, "[{:<5} {}] {}", record.level, record.target, record.message)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Register these records as the ones written by [dump_recent]
+    pub(crate) fn register(self: &Arc<Self>) {
+        *RECENT.lock().expect("no panics while held") = Some(Arc::clone(self));
+    }
+}
+
+/// Write the records retained by the most recently built [crate::Logger] to `writer`, returning
+/// the number of records written.
+///
+/// This is intended to be called when the compiler fails, e.g. from a panic hook, to include the
+/// log records leading up to the failure in the report, including those which were filtered from
+/// the output. See [crate::Builder::recent_records] for configuring what is retained.
+pub fn dump_recent(writer: &mut dyn io::Write) -> io::Result<usize> {
+    let recent = RECENT.lock().expect("no panics while held").clone();
+    match recent {
+        Some(recent) => recent.dump(writer, None),
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{Level, LevelFilter, Record};
+
+    use super::RecentRecords;
+    use crate::filter::Builder;
+
+    fn push(recent: &RecentRecords, level: Level, target: &str, message: &str) {
+        recent.push(
+            &Record::builder()
+                .level(level)
+                .target(target)
+                .args(format_args!("{message}"))
+                .build(),
+        );
+    }
+
+    fn dump(recent: &RecentRecords, filter: Option<&str>) -> String {
+        let filter = filter.map(|spec| Builder::new().parse(spec).build());
+        let mut output = Vec::new();
+        recent.dump(&mut output, filter.as_ref()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn recent_records_are_bounded() {
+        let recent = RecentRecords::new(2, LevelFilter::Debug);
+        push(&recent, Level::Info, "codegen", "first");
+        push(&recent, Level::Debug, "codegen", "second");
+        push(&recent, Level::Trace, "codegen", "too verbose");
+        push(&recent, Level::Warn, "codegen", "third");
+
+        assert_eq!(recent.len(), 2);
+        assert_eq!(dump(&recent, None), "[DEBUG codegen] second\n[WARN  codegen] third\n");
+    }
+
+    #[test]
+    fn recent_records_can_be_filtered_by_target() {
+        let recent = RecentRecords::new(8, LevelFilter::Debug);
+        push(&recent, Level::Debug, "codegen:emit:foo", "emitting foo");
+        push(&recent, Level::Debug, "dataflow:solver", "solving");
+        push(&recent, Level::Debug, "codegen:emit:bar", "emitting bar");
+
+        assert_eq!(
+            dump(&recent, Some("codegen=debug")),
+            "[DEBUG codegen:emit:foo] emitting foo\n[DEBUG codegen:emit:bar] emitting bar\n"
+        );
+    }
+}
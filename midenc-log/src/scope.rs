@@ -0,0 +1,247 @@
+use std::{cell::RefCell, fmt::Write, marker::PhantomData, rc::Rc};
+
+thread_local! {
+    /// The stack of active scopes on the current thread, innermost last
+    static SCOPES: RefCell<Vec<Rc<ScopeFrame>>> = const { RefCell::new(Vec::new()) };
+}
+
+struct ScopeFrame {
+    component: String,
+    topic: String,
+    symbol: Option<String>,
+    /// The structured target composed from the fields above
+    target: Rc<str>,
+}
+
+impl ScopeFrame {
+    fn new(
+        parent: Option<&ScopeFrame>,
+        component: &str,
+        topic: &str,
+        symbol: Option<&str>,
+    ) -> Self {
+        // Scopes nested in a scope of the same component refine its topic, otherwise the topic is
+        // replaced. In either case, the symbol is inherited unless a new one is given.
+        let topic = match parent {
+            Some(parent) if parent.component == component && !parent.topic.is_empty() => {
+                if topic.is_empty() {
+                    parent.topic.clone()
+                } else {
+                    format!("{}:{topic}", &parent.topic)
+                }
+            }
+            _ => topic.to_owned(),
+        };
+        let symbol = symbol.map(str::to_owned).or_else(|| parent.and_then(|p| p.symbol.clone()));
+
+        let mut target = String::from(component);
+        match (topic.as_str(), symbol.as_deref()) {
+            ("", None) => (),
+            ("", Some(symbol)) => write!(&mut target, ":*:{symbol}").unwrap(),
+            (topic, None) if topic.contains(':') => write!(&mut target, ":{topic}:*").unwrap(),
+            (topic, None) => write!(&mut target, ":{topic}").unwrap(),
+            (topic, Some(symbol)) => write!(&mut target, ":{topic}:{symbol}").unwrap(),
+        }
+
+        Self {
+            component: component.to_owned(),
+            topic,
+            symbol,
+            target: target.into(),
+        }
+    }
+}
+
+/// A guard which keeps a logging scope active on the current thread until it is dropped.
+///
+/// While a scope is active, the [crate::scoped_trace] and [crate::scoped_debug] macros (and
+/// [crate::scoped_log] more generally) log with a structured target composed from the scope, so
+/// that call sites need not build the target themselves. See [scope].
+///
+/// Scopes are strictly nested: dropping a scope also deactivates any scopes opened after it which
+/// are still active. This includes when the stack is unwound due to a panic.
+#[must_use = "the scope is only active until the guard is dropped"]
+pub struct LogScope {
+    depth: usize,
+    _marker: PhantomData<*const ()>,
+}
+
+impl Drop for LogScope {
+    fn drop(&mut self) {
+        let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Open a logging scope for `component`, `topic`, and optionally, the symbol being processed, on
+/// the current thread, e.g. `midenc_log::scope("codegen", "pass:cse", Some("my_func"))`.
+///
+/// The scope is active until the returned guard is dropped. Records logged using the scoped
+/// logging macros while it is active have the target `component:topic:symbol`, as described in
+/// the crate documentation, so that they can be selected with `MIDENC_TRACE` and
+/// `MIDENC_TRACE_FILTER` like any other structured target.
+///
+/// If a scope for the same component is already active, the topic of the new scope is nested
+/// under that of the enclosing scope, i.e. `scope("pass", "canonicalize", None)` followed by
+/// `scope("pass", "cse", None)` results in the target `pass:canonicalize:cse:*`. Otherwise, the
+/// component and topic are replaced. Nested scopes inherit the symbol of the enclosing scope
+/// when not given one.
+pub fn scope<'a>(component: &str, topic: &str, symbol: impl Into<Option<&'a str>>) -> LogScope {
+    let symbol = symbol.into();
+    SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        let frame = ScopeFrame::new(scopes.last().map(|s| &**s), component, topic, symbol);
+        let depth = scopes.len();
+        scopes.push(Rc::new(frame));
+        LogScope {
+            depth,
+            _marker: PhantomData,
+        }
+    })
+}
+
+/// Returns the structured target of the innermost active scope on the current thread, if any
+pub fn current_target() -> Option<Rc<str>> {
+    SCOPES.with(|scopes| scopes.borrow().last().map(|frame| Rc::clone(&frame.target)))
+}
+
+/// Invoke `f` with the target of the innermost active scope, or `default` if there is none.
+///
+/// This is used by the scoped logging macros, and is not intended to be called directly.
+#[doc(hidden)]
+pub fn with_target<R>(default: &str, f: impl FnOnce(&str) -> R) -> R {
+    // The target is cloned out of the stack, so that scopes may be opened while logging, e.g. by
+    // a `Display` implementation used in the message
+    match current_target() {
+        Some(target) => f(&target),
+        None => f(default),
+    }
+}
+
+/// Logs a message at the given level, with a target composed from the active [scope].
+///
+/// If there is no active scope, the target is the one given with `target:`, or the module path of
+/// the call site, as with [log::log].
+///
+/// ```
+/// use log::Level;
+///
+/// let _scope = midenc_log::scope("codegen", "emit", Some("my_func"));
+/// midenc_log::scoped_log!(Level::Trace, "emitting {}", "my_func");
+/// midenc_log::scoped_log!(target: "emitter", Level::Trace, "emitting {}", "my_func");
+/// ```
+#[macro_export]
+macro_rules! scoped_log {
+    (target: $target:expr, $lvl:expr, $($arg:tt)+) => {{
+        let lvl = $lvl;
+        if lvl <= $crate::__private::log::STATIC_MAX_LEVEL && lvl <= $crate::__private::log::max_level() {
+            $crate::__private::with_target($target, |target| {
+                $crate::__private::log::log!(target: target, lvl, $($arg)+)
+            })
+        }
+    }};
+    ($lvl:expr, $($arg:tt)+) => {
+        $crate::scoped_log!(target: ::core::module_path!(), $lvl, $($arg)+)
+    };
+}
+
+/// Logs a message at the trace level, with a target composed from the active [scope].
+///
+/// See [crate::scoped_log].
+#[macro_export]
+macro_rules! scoped_trace {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::scoped_log!(target: $target, $crate::__private::log::Level::Trace, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::scoped_log!($crate::__private::log::Level::Trace, $($arg)+)
+    };
+}
+
+/// Logs a message at the debug level, with a target composed from the active [scope].
+///
+/// See [crate::scoped_log].
+#[macro_export]
+macro_rules! scoped_debug {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::scoped_log!(target: $target, $crate::__private::log::Level::Debug, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::scoped_log!($crate::__private::log::Level::Debug, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use super::{current_target, scope};
+
+    fn target() -> Option<String> {
+        current_target().map(|target| target.to_string())
+    }
+
+    #[test]
+    fn scope_composes_target() {
+        assert_eq!(target(), None);
+        {
+            let _scope = scope("codegen", "emit", Some("foo"));
+            assert_eq!(target().as_deref(), Some("codegen:emit:foo"));
+        }
+        assert_eq!(target(), None);
+
+        let _scope = scope("codegen", "", Some("foo"));
+        assert_eq!(target().as_deref(), Some("codegen:*:foo"));
+        let _scope = scope("codegen", "solver:linear", None);
+        assert_eq!(target().as_deref(), Some("codegen:solver:linear:foo"));
+    }
+
+    #[test]
+    fn nested_scopes_compose_target() {
+        let _pass = scope("pass", "canonicalize", Some("foo"));
+        assert_eq!(target().as_deref(), Some("pass:canonicalize:foo"));
+        {
+            let _nested = scope("pass", "cse", None);
+            assert_eq!(target().as_deref(), Some("pass:canonicalize:cse:foo"));
+            {
+                let _other = scope("codegen", "solver", Some("bar"));
+                assert_eq!(target().as_deref(), Some("codegen:solver:bar"));
+            }
+            let _other = scope("codegen", "solver:linear", None);
+            assert_eq!(target().as_deref(), Some("codegen:solver:linear:foo"));
+        }
+        assert_eq!(target().as_deref(), Some("pass:canonicalize:foo"));
+
+        let _nested = scope("pass", "", None);
+        assert_eq!(target().as_deref(), Some("pass:canonicalize:foo"));
+    }
+
+    #[test]
+    fn nested_scopes_without_symbol() {
+        let _pass = scope("pass", "canonicalize", None);
+        assert_eq!(target().as_deref(), Some("pass:canonicalize"));
+        let _nested = scope("pass", "cse", None);
+        assert_eq!(target().as_deref(), Some("pass:canonicalize:cse:*"));
+    }
+
+    #[test]
+    fn dropping_scope_deactivates_nested_scopes() {
+        let outer = scope("pass", "cse", Some("foo"));
+        let inner = scope("codegen", "emit", None);
+        drop(outer);
+        assert_eq!(target(), None);
+        drop(inner);
+        assert_eq!(target(), None);
+    }
+
+    #[test]
+    fn scopes_are_unwound_on_panic() {
+        let _outer = scope("pass", "cse", Some("foo"));
+        let result = panic::catch_unwind(|| {
+            let _scope = scope("codegen", "emit", Some("bar"));
+            assert_eq!(target().as_deref(), Some("codegen:emit:bar"));
+            panic!("internal compiler error");
+        });
+        assert!(result.is_err());
+        assert_eq!(target().as_deref(), Some("pass:cse:foo"));
+    }
+}
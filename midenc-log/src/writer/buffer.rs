@@ -1,4 +1,8 @@
-use std::{io, sync::Mutex};
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    sync::Mutex,
+};
 
 use crate::writer::WriteStyle;
 
@@ -6,6 +10,26 @@ use crate::writer::WriteStyle;
 pub(crate) struct BufferWriter {
     target: WritableTarget,
     write_style: WriteStyle,
+    file: Option<FileWriter>,
+}
+
+/// A buffered file which records are written to, either instead of, or in addition to, the
+/// terminal target.
+#[derive(Debug)]
+pub(crate) struct FileWriter {
+    file: Mutex<BufWriter<File>>,
+    write_style: WriteStyle,
+    tee: bool,
+}
+
+impl FileWriter {
+    pub(crate) fn new(file: File, write_style: WriteStyle, tee: bool) -> Self {
+        Self {
+            file: Mutex::new(BufWriter::new(file)),
+            write_style,
+            tee,
+        }
+    }
 }
 
 impl BufferWriter {
@@ -17,6 +41,7 @@ impl BufferWriter {
                 WritableTarget::WriteStderr
             },
             write_style,
+            file: None,
         }
     }
 
@@ -28,6 +53,7 @@ impl BufferWriter {
                 WritableTarget::WriteStdout
             },
             write_style,
+            file: None,
         }
     }
 
@@ -38,13 +64,29 @@ impl BufferWriter {
         BufferWriter {
             target: WritableTarget::Pipe(pipe),
             write_style,
+            file: None,
         }
     }
 
+    /// Write records to `file`, as well as the terminal target if the file is in tee mode
+    pub(crate) fn with_file(mut self, file: Option<FileWriter>) -> Self {
+        self.file = file;
+        self
+    }
+
     pub(crate) fn write_style(&self) -> WriteStyle {
         self.write_style
     }
 
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        use std::io::Write as _;
+
+        if let Some(file) = self.file.as_ref() {
+            file.file.lock().expect("no panics while held").flush()?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn buffer(&self) -> Buffer {
         Buffer(Vec::new())
     }
@@ -56,6 +98,16 @@ impl BufferWriter {
         use std::io::Write as _;
 
         let buf = buf.as_bytes();
+        if let Some(file) = self.file.as_ref() {
+            // Files are flushed when the logger is, rather than after every record
+            #[cfg(feature = "color")]
+            let buf = &adapt(buf, file.write_style)?;
+            file.file.lock().expect("no panics while held").write_all(buf)?;
+            if !file.tee {
+                return Ok(());
+            }
+        }
+
         match &self.target {
             WritableTarget::WriteStdout => {
                 let stream = io::stdout();
@@ -1,10 +1,10 @@
 mod buffer;
 mod target;
 
-use std::{io, mem, sync::Mutex};
+use std::{fs::File, io, mem, sync::Mutex};
 
 pub(crate) use buffer::Buffer;
-use buffer::BufferWriter;
+use buffer::{BufferWriter, FileWriter};
 pub use target::Target;
 
 /// Whether or not to print styles to the target.
@@ -61,6 +61,10 @@ impl Writer {
     pub(crate) fn print(&self, buf: &Buffer) -> io::Result<()> {
         self.inner.print(buf)
     }
+
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 /// A builder for a terminal writer.
@@ -69,6 +73,8 @@ impl Writer {
 #[derive(Debug)]
 pub(crate) struct Builder {
     target: Target,
+    file: Option<File>,
+    tee: bool,
     write_style: WriteStyle,
     is_test: bool,
     built: bool,
@@ -79,6 +85,8 @@ impl Builder {
     pub(crate) fn new() -> Self {
         Builder {
             target: Default::default(),
+            file: None,
+            tee: false,
             write_style: Default::default(),
             is_test: false,
             built: false,
@@ -91,6 +99,18 @@ impl Builder {
         self
     }
 
+    /// Set a file to write to, instead of the target, unless in tee mode.
+    pub(crate) fn file(&mut self, file: File) -> &mut Self {
+        self.file = Some(file);
+        self
+    }
+
+    /// Whether or not to write to the target as well as the file, if there is one.
+    pub(crate) fn tee(&mut self, tee: bool) -> &mut Self {
+        self.tee = tee;
+        self
+    }
+
     /// Parses a style choice string.
     ///
     /// See the [Disabling colors] section for more details.
@@ -141,7 +161,17 @@ impl Builder {
             Target::Pipe(pipe) => BufferWriter::pipe(Box::new(Mutex::new(pipe)), color_choice),
         };
 
-        Writer { inner: writer }
+        // Styles are only written to files when explicitly requested
+        let file_style = if self.write_style == WriteStyle::Always {
+            WriteStyle::Always
+        } else {
+            WriteStyle::Never
+        };
+        let file = self.file.take().map(|file| FileWriter::new(file, file_style, self.tee));
+
+        Writer {
+            inner: writer.with_file(file),
+        }
     }
 }
 
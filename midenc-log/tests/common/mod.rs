@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use log::{Level, Log, Metadata, Record};
+use midenc_log::{Builder, Logger, Target};
+
+/// A [Write] implementation which appends to a buffer shared with the test
+#[derive(Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Build a logger from `builder` which writes just the message of each record to a buffer
+pub fn capture(builder: &mut Builder) -> (Logger, SharedBuffer) {
+    let buffer = SharedBuffer::default();
+    let logger = builder
+        .format(|f, record| writeln!(f, "{}", record.args()))
+        .target(Target::Pipe(Box::new(buffer.clone())))
+        .build();
+    (logger, buffer)
+}
+
+pub fn metadata(level: Level, target: &str) -> Metadata<'_> {
+    Metadata::builder().level(level).target(target).build()
+}
+
+pub fn log(logger: &impl Log, level: Level, target: &str, message: &str) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target(target)
+            .args(format_args!("{message}"))
+            .build(),
+    );
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use log::{Level, LevelFilter, Log, Record};
+use midenc_log::{Builder, Target};
+use serde_json::Value;
+
+use self::common::SharedBuffer;
+
+fn json_logger() -> (midenc_log::Logger, SharedBuffer) {
+    let buffer = SharedBuffer::default();
+    let logger = Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .format_json()
+        .target(Target::Pipe(Box::new(buffer.clone())))
+        .build();
+    (logger, buffer)
+}
+
+fn log(logger: &impl Log, target: &str, message: &str) {
+    logger.log(
+        &Record::builder()
+            .level(Level::Debug)
+            .target(target)
+            .module_path(Some("midenc_log::tests"))
+            .args(format_args!("{message}"))
+            .build(),
+    );
+}
+
+fn records(buffer: &SharedBuffer) -> Vec<Value> {
+    buffer
+        .contents()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|err| panic!("{err}: {line}")))
+        .collect()
+}
+
+#[test]
+fn json_records_decompose_structured_targets() {
+    let (logger, buffer) = json_logger();
+    log(&logger, "codegen:pass:cse", "eliminated 2 expressions");
+    log(&logger, "codegen:solver:linear:*", "no symbol");
+    log(&logger, "pass:cse", "one topic");
+    log(&logger, "codegen", "component only");
+
+    let records = records(&buffer);
+    assert_eq!(records.len(), 4);
+
+    let record = &records[0];
+    assert_eq!(record["level"], "DEBUG");
+    assert_eq!(record["target"], "codegen:pass:cse");
+    assert_eq!(record["component"], "codegen");
+    assert_eq!(record["topics"], serde_json::json!(["pass"]));
+    assert_eq!(record["symbol"], "cse");
+    assert_eq!(record["module_path"], "midenc_log::tests");
+    assert_eq!(record["message"], "eliminated 2 expressions");
+    assert!(record["timestamp"].is_string() || cfg!(not(feature = "humantime")));
+
+    assert_eq!(records[1]["topics"], serde_json::json!(["solver", "linear"]));
+    assert_eq!(records[1]["symbol"], Value::Null);
+
+    assert_eq!(records[2]["component"], "pass");
+    assert_eq!(records[2]["topics"], serde_json::json!(["cse"]));
+    assert_eq!(records[2]["symbol"], Value::Null);
+
+    assert_eq!(records[3]["component"], "codegen");
+    assert_eq!(records[3]["topics"], serde_json::json!([]));
+}
+
+#[test]
+fn json_records_are_escaped() {
+    let (logger, buffer) = json_logger();
+    let message = "quote \" backslash \\ newline \n tab \t bell \u{7} unicode \u{e9}";
+    log(&logger, "codegen:emit:foo::\"bar\"", message);
+
+    let output = buffer.contents();
+    assert_eq!(output.lines().count(), 1, "{output}");
+    let records = records(&buffer);
+    assert_eq!(records[0]["message"], message);
+    assert_eq!(records[0]["symbol"], "foo::\"bar\"");
+}
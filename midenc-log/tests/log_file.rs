@@ -0,0 +1,112 @@
+mod common;
+
+use std::{io::Write, path::PathBuf};
+
+use log::{Level, LevelFilter, Log};
+use midenc_log::{Builder, Target, WriteStyle};
+
+use self::common::{SharedBuffer, log};
+
+/// A path in the temporary directory which is unique to this test process
+fn temp_log_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("midenc-log-{}-{name}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn file_builder(path: &PathBuf, append: bool) -> Builder {
+    let mut builder = Builder::new();
+    builder
+        .filter_level(LevelFilter::Trace)
+        .format(|f, record| writeln!(f, "{}", record.args()))
+        .target_file(path, append)
+        .unwrap();
+    builder
+}
+
+#[test]
+fn records_are_written_to_the_log_file() {
+    let path = temp_log_file("records");
+    let logger = file_builder(&path, false).build();
+
+    log(&logger, Level::Info, "codegen", "first");
+    log(&logger, Level::Trace, "codegen:emit:foo", "second");
+    logger.flush();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn log_file_is_flushed_on_drop() {
+    let path = temp_log_file("drop");
+    std::fs::write(&path, "existing\n").unwrap();
+
+    let logger = file_builder(&path, true).build();
+    log(&logger, Level::Info, "codegen", "appended");
+    drop(logger);
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "existing\nappended\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn styles_are_stripped_from_the_log_file() {
+    let path = temp_log_file("styles");
+    let logger = file_builder(&path, false).build();
+
+    log(&logger, Level::Info, "codegen", "\x1b[1mbold\x1b[0m");
+    logger.flush();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents, "bold\n");
+    assert!(!contents.contains('\x1b'));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn styles_are_kept_in_the_log_file_when_requested() {
+    let path = temp_log_file("styles-always");
+    let logger = file_builder(&path, false).write_style(WriteStyle::Always).build();
+
+    log(&logger, Level::Info, "codegen", "\x1b[1mbold\x1b[0m");
+    logger.flush();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "\x1b[1mbold\x1b[0m\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn tee_writes_records_to_both_the_file_and_the_target() {
+    let path = temp_log_file("tee");
+    let buffer = SharedBuffer::default();
+    let logger = file_builder(&path, false)
+        .target(Target::Pipe(Box::new(buffer.clone())))
+        .tee(true)
+        .build();
+
+    log(&logger, Level::Info, "codegen", "both");
+    logger.flush();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "both\n");
+    assert_eq!(buffer.contents(), "both\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn without_tee_the_target_is_not_written() {
+    let path = temp_log_file("no-tee");
+    let buffer = SharedBuffer::default();
+    let logger = file_builder(&path, false)
+        .target(Target::Pipe(Box::new(buffer.clone())))
+        .build();
+
+    log(&logger, Level::Info, "codegen", "file only");
+    logger.flush();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "file only\n");
+    assert!(buffer.contents().is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use std::io::Write;
+
+use log::LevelFilter;
+use midenc_log::{Builder, Target, scoped_debug, scoped_trace};
+
+use self::common::SharedBuffer;
+
+#[test]
+fn scoped_macros_log_with_composed_target() {
+    let buffer = SharedBuffer::default();
+    let logger = Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .parse_trace_filter("symbol:my_func")
+        .format(|f, record| writeln!(f, "{} {}", record.target(), record.args()))
+        .target(Target::Pipe(Box::new(buffer.clone())))
+        .build();
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(logger)).unwrap();
+
+    {
+        let _scope = midenc_log::scope("pass", "cse", Some("my_func"));
+        scoped_trace!("eliminating common subexpressions");
+        {
+            let _scope = midenc_log::scope("pass", "canonicalize", None);
+            scoped_debug!("folded {} ops", 2);
+        }
+    }
+    {
+        let _scope = midenc_log::scope("pass", "cse", Some("other_func"));
+        scoped_trace!("eliminating common subexpressions");
+    }
+    scoped_debug!("outside of any scope");
+
+    assert_eq!(
+        buffer.contents(),
+        "pass:cse:my_func eliminating common subexpressions\npass:cse:canonicalize:my_func folded \
+         2 ops\nlog_scope outside of any scope\n"
+    );
+}
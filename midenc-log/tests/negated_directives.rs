@@ -0,0 +1,63 @@
+mod common;
+
+use log::Level;
+use midenc_log::Builder;
+
+use self::common::{capture, log};
+
+/// Log a record at every level for each of `targets`, returning the captured output
+fn log_all(spec: &str, targets: &[&str]) -> String {
+    let (logger, buffer) = capture(Builder::new().parse_filters(spec));
+    for target in targets {
+        for level in [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace] {
+            log(&logger, level, target, &format!("{target} {level}"));
+        }
+    }
+    buffer.contents()
+}
+
+#[test]
+fn negated_component_suppresses_all_levels() {
+    // Emit everything except logs for the "dataflow" component
+    let output = log_all("trace,-dataflow", &["dataflow", "codegen"]);
+    assert_eq!(
+        output,
+        "codegen ERROR\ncodegen WARN\ncodegen INFO\ncodegen DEBUG\ncodegen TRACE\n"
+    );
+}
+
+#[test]
+fn negated_component_with_level_suppresses_that_level() {
+    // Emit everything except trace-level logs for the "dataflow" component
+    let output = log_all("trace,-dataflow=trace", &["dataflow", "codegen"]);
+    assert_eq!(
+        output,
+        "dataflow ERROR\ndataflow WARN\ndataflow INFO\ndataflow DEBUG\ncodegen ERROR\ncodegen \
+         WARN\ncodegen INFO\ncodegen DEBUG\ncodegen TRACE\n"
+    );
+}
+
+#[test]
+fn negated_topic_suppresses_only_that_topic() {
+    // Emit logs for the "dataflow" component, except trace-level logs for the "solver" topic
+    let output = log_all(
+        "dataflow=trace,-dataflow:solver=trace",
+        &["dataflow:solver", "dataflow:liveness", "codegen"],
+    );
+    assert_eq!(
+        output,
+        "dataflow:solver ERROR\ndataflow:solver WARN\ndataflow:solver INFO\ndataflow:solver \
+         DEBUG\ndataflow:liveness ERROR\ndataflow:liveness WARN\ndataflow:liveness \
+         INFO\ndataflow:liveness DEBUG\ndataflow:liveness TRACE\n"
+    );
+}
+
+#[test]
+fn negated_directives_do_not_raise_the_max_level() {
+    let (logger, _) = capture(
+        Builder::new()
+            .parse_filters("info,-dataflow=trace")
+            .recent_records(0, log::LevelFilter::Off),
+    );
+    assert_eq!(logger.filter(), log::LevelFilter::Info);
+}
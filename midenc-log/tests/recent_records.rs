@@ -0,0 +1,46 @@
+mod common;
+
+use std::{
+    panic,
+    sync::{Arc, Mutex},
+};
+
+use log::{Level, LevelFilter};
+use midenc_log::Builder;
+
+use self::common::{capture, log};
+
+#[test]
+fn recent_records_are_dumped_on_failure() {
+    let (logger, output) = capture(
+        Builder::new()
+            .filter_level(LevelFilter::Warn)
+            .recent_records(16, LevelFilter::Debug),
+    );
+    assert_eq!(logger.filter(), LevelFilter::Debug);
+
+    log(&logger, Level::Debug, "codegen:emit:foo", "lowering foo");
+    log(&logger, Level::Trace, "codegen:emit:foo", "too verbose to retain");
+    log(&logger, Level::Info, "pass:cse", "eliminated 2 expressions");
+    log(&logger, Level::Warn, "codegen", "something looks off");
+
+    // Only the warning reaches the live output
+    assert_eq!(output.contents(), "something looks off\n");
+
+    // Simulate an internal compiler error, with a panic hook which dumps the recent records
+    let dump = Arc::new(Mutex::new(Vec::new()));
+    let hook_dump = Arc::clone(&dump);
+    panic::set_hook(Box::new(move |_| {
+        midenc_log::dump_recent(&mut *hook_dump.lock().unwrap()).unwrap();
+    }));
+    let result = panic::catch_unwind(|| panic!("internal compiler error"));
+    let _ = panic::take_hook();
+    assert!(result.is_err());
+
+    let dump = String::from_utf8(dump.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        dump,
+        "[DEBUG codegen:emit:foo] lowering foo\n[INFO  pass:cse] eliminated 2 expressions\n[WARN  \
+         codegen] something looks off\n"
+    );
+}
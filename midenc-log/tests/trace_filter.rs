@@ -0,0 +1,33 @@
+mod common;
+
+use log::{Level, LevelFilter, Log};
+use midenc_log::Builder;
+
+use self::common::{capture, log, metadata};
+
+#[test]
+fn trace_filter_suppresses_records_for_other_symbols() {
+    let (logger, buffer) =
+        capture(Builder::new().filter_level(LevelFilter::Trace).parse_trace_filter("symbol:foo"));
+
+    assert!(logger.enabled(&metadata(Level::Trace, "codegen:emit:foo")));
+    assert!(!logger.enabled(&metadata(Level::Trace, "codegen:emit:bar")));
+
+    log(&logger, Level::Trace, "codegen:emit:foo", "emitting foo");
+    log(&logger, Level::Trace, "codegen:emit:bar", "emitting bar");
+    log(&logger, Level::Trace, "codegen:solver:linear:bar", "solving bar");
+    log(&logger, Level::Trace, "codegen:*:foo", "lowering foo");
+    log(&logger, Level::Trace, "codegen", "not specific to any symbol");
+
+    assert_eq!(buffer.contents(), "emitting foo\nlowering foo\nnot specific to any symbol\n");
+}
+
+#[test]
+fn topic_directives_do_not_match_the_symbol() {
+    let (logger, buffer) = capture(Builder::new().parse_filters("codegen:emit=trace"));
+
+    log(&logger, Level::Trace, "codegen:emit:foo", "emitting foo");
+    log(&logger, Level::Trace, "codegen:solver:emit", "solving emit");
+
+    assert_eq!(buffer.contents(), "emitting foo\n");
+}
@@ -0,0 +1,44 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::diagnostics::Diagnostic;
+
+/// A handle which can be used to cancel an in-progress compilation, from any thread.
+///
+/// Cancellation is cooperative: the compiler checks the token between stages, between passes,
+/// and between the functions it generates code for, and stops as soon as it observes that the
+/// token has been canceled. Clones of a token share the same state, so a clone can be kept by the
+/// caller, while the original is moved into the compiler [crate::Options].
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that the compilation(s) observing this token be canceled
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Returns true if [Self::cancel] has been called on this token, or any of its clones
+    pub fn is_canceled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Returns [Canceled] if this token has been canceled
+    pub fn check(&self) -> Result<(), Canceled> {
+        if self.is_canceled() {
+            Err(Canceled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error raised when the compiler observes that its [CancellationToken] has been canceled
+#[derive(Debug, Copy, Clone, thiserror::Error, Diagnostic)]
+#[error("compilation was canceled")]
+#[diagnostic()]
+pub struct Canceled;
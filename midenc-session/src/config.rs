@@ -0,0 +1,191 @@
+#[cfg(feature = "std")]
+use alloc::{format, string::ToString};
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "std")]
+use crate::diagnostics::Report;
+use crate::{CompileFlag, PathBuf};
+
+/// The name of the project configuration file loaded from the working directory
+pub const CONFIG_FILE_NAME: &str = "midenc.toml";
+
+/// Default compiler options for a project, loaded from a `midenc.toml` file.
+///
+/// Each key mirrors a field of [crate::Options], and its value uses the same syntax as the
+/// equivalent command-line option. Options given on the command line take precedence over those
+/// given here.
+///
+/// ```toml
+/// target = "counter"
+/// project_type = "library"
+/// link_libraries = ["extra", "masp=deps/util.masp"]
+/// output_types = ["masm=out", "hir"]
+/// print_ir_after_pass = ["canonicalize"]
+///
+/// [flags]
+/// my-flag = 8
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ConfigFile {
+    /// The path from which this file was loaded
+    pub path: PathBuf,
+    /// The name of the project target being compiled, as given to `--target`
+    pub target: Option<String>,
+    /// The type of target to produce, as given to `--target-type`
+    pub project_type: Option<String>,
+    /// Libraries to link against, as given to `--link-library`
+    pub link_libraries: Vec<String>,
+    /// Output types to emit, as given to `--emit`
+    pub output_types: Vec<String>,
+    /// Passes after which to print IR, as given to `-Z print-ir-after-pass`
+    pub print_ir_after_pass: Vec<String>,
+    /// Values for custom compiler flags, from the `[flags]` table
+    pub flags: Vec<(&'static CompileFlag, Vec<String>)>,
+    /// Problems found in the file which do not prevent it from being used, e.g. unknown keys
+    pub warnings: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl ConfigFile {
+    /// Load the `midenc.toml` file in `dir`, if there is one
+    pub fn find(dir: &std::path::Path) -> Result<Option<Self>, Report> {
+        let path = dir.join(CONFIG_FILE_NAME);
+        if path.is_file() {
+            Self::load(path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Load the configuration file at `path`
+    pub fn load(path: PathBuf) -> Result<Self, Report> {
+        let source = std::fs::read_to_string(&path).map_err(|err| {
+            Report::msg(format!("unable to read config file '{}': {err}", path.display()))
+        })?;
+        Self::parse(path, &source)
+    }
+
+    /// Parse `source`, the contents of the configuration file at `path`
+    pub fn parse(path: PathBuf, source: &str) -> Result<Self, Report> {
+        let document = toml_edit::Document::parse(source).map_err(|err| {
+            Report::msg(format!("invalid config file '{}': {err}", path.display()))
+        })?;
+
+        let mut parser = ConfigParser {
+            source,
+            config: Self {
+                path,
+                ..Default::default()
+            },
+        };
+        let table = document.as_table();
+        for (key, item) in table.iter() {
+            let span = table.key(key).and_then(|key| key.span());
+            match key {
+                "target" => parser.config.target = Some(parser.string(key, item)?),
+                "project_type" => parser.config.project_type = Some(parser.string(key, item)?),
+                "link_libraries" => parser.config.link_libraries = parser.strings(key, item)?,
+                "output_types" => parser.config.output_types = parser.strings(key, item)?,
+                "print_ir_after_pass" => {
+                    parser.config.print_ir_after_pass = parser.strings(key, item)?
+                }
+                "flags" => parser.flags(item)?,
+                _ => parser.warn(span, format!("unknown key '{key}'")),
+            }
+        }
+
+        Ok(parser.config)
+    }
+}
+
+#[cfg(feature = "std")]
+struct ConfigParser<'a> {
+    source: &'a str,
+    config: ConfigFile,
+}
+
+#[cfg(feature = "std")]
+impl ConfigParser<'_> {
+    fn flags(&mut self, item: &toml_edit::Item) -> Result<(), Report> {
+        let Some(table) = item.as_table_like() else {
+            return Err(self.error(item.span(), "expected 'flags' to be a table"));
+        };
+        for (key, item) in table.iter() {
+            let span = table.key(key).and_then(|key| key.span());
+            let flag = inventory::iter::<CompileFlag>
+                .into_iter()
+                .find(|flag| flag.long.unwrap_or(flag.name) == key || flag.name == key);
+            let Some(flag) = flag else {
+                self.warn(span, format!("unknown flag '{key}'"));
+                continue;
+            };
+            let values = match (item.as_array(), item.as_value()) {
+                (Some(array), _) => {
+                    array.iter().map(|value| self.scalar(key, value)).collect::<Result<_, _>>()?
+                }
+                (None, Some(value)) => vec![self.scalar(key, value)?],
+                (None, None) => {
+                    return Err(self.error(item.span(), format!("invalid value for '{key}'")));
+                }
+            };
+            self.config.flags.push((flag, values));
+        }
+        Ok(())
+    }
+
+    fn string(&self, key: &str, item: &toml_edit::Item) -> Result<String, Report> {
+        item.as_str()
+            .map(ToString::to_string)
+            .ok_or_else(|| self.error(item.span(), format!("expected '{key}' to be a string")))
+    }
+
+    fn strings(&self, key: &str, item: &toml_edit::Item) -> Result<Vec<String>, Report> {
+        let invalid =
+            || self.error(item.span(), format!("expected '{key}' to be an array of strings"));
+        let array = item.as_array().ok_or_else(invalid)?;
+        array
+            .iter()
+            .map(|value| value.as_str().map(ToString::to_string).ok_or_else(invalid))
+            .collect()
+    }
+
+    fn scalar(&self, key: &str, value: &toml_edit::Value) -> Result<String, Report> {
+        use toml_edit::Value;
+
+        match value {
+            Value::String(value) => Ok(value.value().clone()),
+            Value::Integer(value) => Ok(value.value().to_string()),
+            Value::Float(value) => Ok(value.value().to_string()),
+            Value::Boolean(value) => Ok(value.value().to_string()),
+            _ => Err(self.error(
+                value.span(),
+                format!("expected '{key}' to be a string, number, or boolean"),
+            )),
+        }
+    }
+
+    fn warn(&mut self, span: Option<core::ops::Range<usize>>, message: String) {
+        let warning = format!("{}: {message}", self.location(span));
+        self.config.warnings.push(warning);
+    }
+
+    fn error(
+        &self,
+        span: Option<core::ops::Range<usize>>,
+        message: impl core::fmt::Display,
+    ) -> Report {
+        Report::msg(format!("invalid config file {}: {message}", self.location(span)))
+    }
+
+    /// Render `span` as `path:line:column`, or just `path` if the span is unknown
+    fn location(&self, span: Option<core::ops::Range<usize>>) -> String {
+        let path = self.config.path.display();
+        let Some(span) = span else {
+            return format!("'{path}'");
+        };
+        let before = &self.source[..span.start];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rfind('\n').map_or(before.len(), |nl| before.len() - nl - 1) + 1;
+        format!("'{path}:{line}:{column}'")
+    }
+}
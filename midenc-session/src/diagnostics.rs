@@ -9,6 +9,8 @@ use alloc::{
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 
+mod json;
+
 pub use miden_assembly_syntax::diagnostics::{
     Diagnostic, Label, LabeledSpan, RelatedError, RelatedLabel, Report, Severity, WrapErr, miette,
     miette::MietteDiagnostic as AdHocDiagnostic,
@@ -19,18 +21,38 @@ pub use miden_core::*;
 pub use miden_debug_types::*;
 pub use midenc_hir_macros::Spanned;
 
+pub use self::json::JsonDiagnostic;
 #[cfg(feature = "std")]
 pub use crate::emitter::CaptureEmitter;
-pub use crate::emitter::{Buffer, DefaultEmitter, Emitter, NullEmitter};
-use crate::{ColorChoice, Verbosity, Warnings};
+pub use crate::emitter::{Buffer, DefaultEmitter, Emitter, JsonEmitter, NullEmitter};
+use crate::{ColorChoice, DiagnosticsFormat, LintLevel, Verbosity, Warnings};
 
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct DiagnosticsConfig {
     pub verbosity: Verbosity,
     pub warnings: Warnings,
+    /// Per-lint overrides of how warnings are treated, keyed by diagnostic code
+    pub lint_levels: BTreeMap<String, LintLevel>,
+    /// The maximum number of errors to render, after which further errors are counted, but not
+    /// shown. If `None`, all errors are rendered.
+    pub error_limit: Option<usize>,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            verbosity: Default::default(),
+            warnings: Default::default(),
+            lint_levels: Default::default(),
+            error_limit: Some(Self::DEFAULT_ERROR_LIMIT),
+        }
+    }
 }
 
 impl DiagnosticsConfig {
+    /// The number of errors rendered by default, see [DiagnosticsConfig::error_limit]
+    pub const DEFAULT_ERROR_LIMIT: usize = 20;
+
     #[inline]
     pub const fn is_verbose(&self) -> bool {
         matches!(self.verbosity, Verbosity::Debug)
@@ -41,8 +63,15 @@ pub struct DiagnosticsHandler {
     emitter: Arc<dyn Emitter>,
     source_manager: Arc<dyn SourceManager + Send + Sync>,
     err_count: AtomicUsize,
+    /// The number of errors in `err_count` which were warnings promoted to errors
+    promoted_count: AtomicUsize,
+    /// The number of errors in `err_count` which were not rendered due to `error_limit`
+    suppressed_count: AtomicUsize,
+    warn_count: AtomicUsize,
     verbosity: Verbosity,
     warnings: Warnings,
+    lint_levels: BTreeMap<String, LintLevel>,
+    error_limit: Option<usize>,
     silent: bool,
 }
 
@@ -56,7 +85,7 @@ impl Default for DiagnosticsHandler {
 }
 
 // We can safely implement these traits for DiagnosticsHandler,
-// as the only two non-atomic fields are read-only after creation
+// as the non-atomic fields are read-only after creation
 unsafe impl Send for DiagnosticsHandler {}
 unsafe impl Sync for DiagnosticsHandler {}
 
@@ -77,8 +106,13 @@ impl DiagnosticsHandler {
             emitter,
             source_manager,
             err_count: AtomicUsize::new(0),
+            promoted_count: AtomicUsize::new(0),
+            suppressed_count: AtomicUsize::new(0),
+            warn_count: AtomicUsize::new(0),
             verbosity: config.verbosity,
             warnings,
+            lint_levels: config.lint_levels,
+            error_limit: config.error_limit,
             silent: config.verbosity == Verbosity::Silent,
         }
     }
@@ -98,11 +132,53 @@ impl DiagnosticsHandler {
         self.err_count.load(Ordering::Relaxed) > 0
     }
 
+    /// Returns the number of error diagnostics emitted, including promoted warnings
+    pub fn error_count(&self) -> usize {
+        self.err_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of warnings which were promoted to errors, either via
+    /// `--warn error`/`-D warnings`, or a per-lint `-D <lint>`
+    pub fn warnings_as_errors_count(&self) -> usize {
+        self.promoted_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of warnings emitted, not including those promoted to errors
+    pub fn warning_count(&self) -> usize {
+        self.warn_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if the number of errors emitted has exceeded the configured error limit, in
+    /// which case further errors are counted, but not rendered.
+    pub fn error_limit_reached(&self) -> bool {
+        self.error_limit.is_some_and(|limit| self.error_count() > limit)
+    }
+
+    /// Returns the number of diagnostics of each kind emitted by this handler so far
+    pub fn summary(&self) -> DiagnosticsSummary {
+        DiagnosticsSummary {
+            errors: self.error_count(),
+            warnings: self.warning_count(),
+            warnings_as_errors: self.warnings_as_errors_count(),
+            errors_not_shown: self.suppressed_count.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns a summary of the errors emitted by this handler, if any were emitted.
+    ///
+    /// For example: `aborting due to 2 previous errors (1 warning treated as error)`
+    pub fn error_summary(&self) -> Option<String> {
+        self.summary().error_summary()
+    }
+
     /// Triggers a panic if the [DiagnosticsHandler] has emitted any error diagnostics
     #[track_caller]
     pub fn abort_if_errors(&self) {
-        if self.has_errors() {
-            panic!("Compiler has encountered unexpected errors. See diagnostics for details.")
+        if let Some(summary) = self.error_summary() {
+            panic!(
+                "Compiler has encountered unexpected errors: {summary}. See diagnostics for \
+                 details."
+            )
         }
     }
 
@@ -120,9 +196,6 @@ impl DiagnosticsHandler {
     ///
     /// If `warnings_as_errors` is set, it produces an error diagnostic instead.
     pub fn warn(&self, warning: impl ToString) {
-        if matches!(self.warnings, Warnings::Error) {
-            return self.error(warning);
-        }
         let diagnostic = AdHocDiagnostic::new(warning.to_string()).with_severity(Severity::Warning);
         self.emit(diagnostic);
     }
@@ -150,16 +223,24 @@ impl DiagnosticsHandler {
         let diagnostic: Report = diagnostic.into();
         let diagnostic = match diagnostic.severity() {
             Some(Severity::Advice) if self.verbosity > Verbosity::Info => return,
-            Some(Severity::Warning) => match self.warnings {
-                Warnings::None => return,
-                Warnings::All => diagnostic,
-                Warnings::Error => {
-                    self.err_count.fetch_add(1, Ordering::Relaxed);
+            Some(Severity::Warning) => match self.warning_level(&diagnostic) {
+                LintLevel::Allow => return,
+                LintLevel::Warn => {
+                    self.warn_count.fetch_add(1, Ordering::Relaxed);
+                    diagnostic
+                }
+                LintLevel::Deny => {
+                    self.promoted_count.fetch_add(1, Ordering::Relaxed);
+                    if self.count_error() {
+                        return;
+                    }
                     Report::from(WarningAsError::from(diagnostic))
                 }
             },
             Some(Severity::Error) => {
-                self.err_count.fetch_add(1, Ordering::Relaxed);
+                if self.count_error() {
+                    return;
+                }
                 diagnostic
             }
             _ => diagnostic,
@@ -172,13 +253,47 @@ impl DiagnosticsHandler {
         self.write_report(diagnostic);
     }
 
+    /// Record an error, returning true if it should not be rendered due to the error limit
+    fn count_error(&self) -> bool {
+        let count = self.err_count.fetch_add(1, Ordering::Relaxed) + 1;
+        let suppressed = self.error_limit.is_some_and(|limit| count > limit);
+        if suppressed {
+            self.suppressed_count.fetch_add(1, Ordering::Relaxed);
+        }
+        suppressed
+    }
+
+    /// Determine how the given warning should be treated.
+    ///
+    /// A lint level specified for the diagnostic code takes precedence over the global warnings
+    /// configuration, except that `--warn error` promotes all emitted warnings to errors.
+    fn warning_level(&self, diagnostic: &Report) -> LintLevel {
+        let lint_level = diagnostic
+            .code()
+            .and_then(|code| self.lint_levels.get(code.to_string().as_str()).copied());
+        match (lint_level, self.warnings) {
+            (Some(LintLevel::Allow), _) => LintLevel::Allow,
+            (Some(LintLevel::Deny), _) | (_, Warnings::Error) => LintLevel::Deny,
+            (Some(LintLevel::Warn), _) | (None, Warnings::All) => LintLevel::Warn,
+            (None, Warnings::None) => LintLevel::Allow,
+        }
+    }
+
     #[cfg(feature = "std")]
     fn write_report(&self, diagnostic: Report) {
         use std::io::Write;
 
         let mut buffer = self.emitter.buffer();
-        let printer = PrintDiagnostic::new(diagnostic);
-        write!(&mut buffer, "{printer}").expect("failed to write diagnostic to buffer");
+        match self.emitter.format() {
+            DiagnosticsFormat::Human => {
+                let printer = PrintDiagnostic::new(diagnostic);
+                write!(&mut buffer, "{printer}")
+            }
+            DiagnosticsFormat::Json => {
+                write!(&mut buffer, "{}", JsonDiagnostic::new(&*diagnostic))
+            }
+        }
+        .expect("failed to write diagnostic to buffer");
         self.emitter.print(buffer).unwrap();
     }
 
@@ -187,17 +302,78 @@ impl DiagnosticsHandler {
         use core::fmt::Write;
 
         let mut buffer = self.emitter.buffer();
-        let printer = PrintDiagnostic::new(diagnostic);
-        write!(&mut buffer, "{printer}").expect("failed to write diagnostic to buffer");
+        match self.emitter.format() {
+            DiagnosticsFormat::Human => {
+                let printer = PrintDiagnostic::new(diagnostic);
+                write!(&mut buffer, "{printer}")
+            }
+            DiagnosticsFormat::Json => {
+                write!(&mut buffer, "{}", JsonDiagnostic::new(&*diagnostic))
+            }
+        }
+        .expect("failed to write diagnostic to buffer");
         self.emitter.print(buffer).unwrap();
     }
 }
 
+/// The number of diagnostics of each kind emitted by a [DiagnosticsHandler]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DiagnosticsSummary {
+    /// The number of errors emitted, including promoted warnings
+    pub errors: usize,
+    /// The number of warnings emitted, not including promoted warnings
+    pub warnings: usize,
+    /// The number of warnings which were promoted to errors
+    pub warnings_as_errors: usize,
+    /// The number of errors which were not rendered due to the error limit
+    pub errors_not_shown: usize,
+}
+
+impl DiagnosticsSummary {
+    /// Returns a summary of the errors emitted, if any were emitted.
+    ///
+    /// For example: `aborting due to 25 previous errors; 5 not shown; pass --error-limit 0 to see
+    /// all`
+    pub fn error_summary(&self) -> Option<String> {
+        let errors = self.errors;
+        if errors == 0 {
+            return None;
+        }
+        let plural = if errors == 1 { "" } else { "s" };
+        let mut summary = format!("aborting due to {errors} previous error{plural}");
+        match self.warnings_as_errors {
+            0 => (),
+            1 => summary.push_str(" (1 warning treated as error)"),
+            n => summary.push_str(&format!(" ({n} warnings treated as errors)")),
+        }
+        if self.errors_not_shown > 0 {
+            summary.push_str(&format!(
+                "; {} not shown; pass --error-limit 0 to see all",
+                self.errors_not_shown
+            ));
+        }
+        Some(summary)
+    }
+}
+
+impl Display for DiagnosticsSummary {
+    /// Formats the summary as e.g. `2 errors, 1 warning emitted`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        match (self.errors, self.warnings) {
+            (0, 0) => f.write_str("no diagnostics emitted"),
+            (0, w) => write!(f, "{w} warning{} emitted", plural(w)),
+            (e, 0) => write!(f, "{e} error{} emitted", plural(e)),
+            (e, w) => write!(f, "{e} error{}, {w} warning{} emitted", plural(e), plural(w)),
+        }
+    }
+}
+
 #[derive(thiserror::Error, Diagnostic, Debug)]
 #[error("{}", .report)]
 #[diagnostic(
     severity(Error),
-    help("this warning was promoted to an error via --warnings-as-errors")
+    help("this warning was promoted to an error via `--warn error` or `-D`")
 )]
 struct WarningAsError {
     #[diagnostic_source]
@@ -0,0 +1,141 @@
+use alloc::string::ToString;
+use core::fmt::{self, Write};
+
+use miden_assembly_syntax::diagnostics::{Diagnostic, LabeledSpan, Severity, miette::SourceCode};
+
+/// Renders a [Diagnostic] as a single-line JSON object, suitable for consumption by tools.
+///
+/// The object has the following shape:
+///
+/// ```text
+/// {
+///   "severity": "error" | "warning" | "advice",
+///   "code": string | null,
+///   "message": string,
+///   "labels": [{
+///     "label": string | null,
+///     "primary": bool,
+///     "offset": number,
+///     "length": number,
+///     "file": string | null,
+///     "line": number | null,
+///     "column": number | null
+///   }],
+///   "help": string | null,
+///   "url": string | null,
+///   "related": [<diagnostic>]
+/// }
+/// ```
+///
+/// Line and column numbers are 1-based, and are resolved from the source file attached to the
+/// diagnostic (or its parent, for related diagnostics). If the location cannot be resolved, they
+/// are `null`.
+pub struct JsonDiagnostic<'a> {
+    diagnostic: &'a dyn Diagnostic,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    pub fn new(diagnostic: &'a dyn Diagnostic) -> Self {
+        Self { diagnostic }
+    }
+}
+
+impl fmt::Display for JsonDiagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_diagnostic(f, self.diagnostic, None)?;
+        f.write_char('\n')
+    }
+}
+
+fn write_diagnostic(
+    f: &mut fmt::Formatter<'_>,
+    diagnostic: &dyn Diagnostic,
+    parent_source: Option<&dyn SourceCode>,
+) -> fmt::Result {
+    let source = diagnostic.source_code().or(parent_source);
+
+    let severity = match diagnostic.severity().unwrap_or(Severity::Error) {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "advice",
+    };
+    write!(f, "{{\"severity\":\"{severity}\",\"code\":")?;
+    write_optional_string(f, diagnostic.code().map(|code| code.to_string()).as_deref())?;
+    f.write_str(",\"message\":")?;
+    write_string(f, &diagnostic.to_string())?;
+
+    f.write_str(",\"labels\":[")?;
+    if let Some(labels) = diagnostic.labels() {
+        for (i, label) in labels.enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write_label(f, &label, source)?;
+        }
+    }
+    f.write_char(']')?;
+
+    f.write_str(",\"help\":")?;
+    write_optional_string(f, diagnostic.help().map(|help| help.to_string()).as_deref())?;
+    f.write_str(",\"url\":")?;
+    write_optional_string(f, diagnostic.url().map(|url| url.to_string()).as_deref())?;
+
+    f.write_str(",\"related\":[")?;
+    if let Some(related) = diagnostic.related() {
+        for (i, related) in related.enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write_diagnostic(f, related, source)?;
+        }
+    }
+    f.write_str("]}")
+}
+
+fn write_label(
+    f: &mut fmt::Formatter<'_>,
+    label: &LabeledSpan,
+    source: Option<&dyn SourceCode>,
+) -> fmt::Result {
+    f.write_str("{\"label\":")?;
+    write_optional_string(f, label.label())?;
+    write!(
+        f,
+        ",\"primary\":{},\"offset\":{},\"length\":{}",
+        label.primary(),
+        label.offset(),
+        label.len()
+    )?;
+
+    match source.and_then(|source| source.read_span(label.inner(), 0, 0).ok()) {
+        Some(contents) => {
+            f.write_str(",\"file\":")?;
+            write_optional_string(f, contents.name())?;
+            write!(f, ",\"line\":{},\"column\":{}}}", contents.line() + 1, contents.column() + 1)
+        }
+        None => f.write_str(",\"file\":null,\"line\":null,\"column\":null}"),
+    }
+}
+
+fn write_optional_string(f: &mut fmt::Formatter<'_>, s: Option<&str>) -> fmt::Result {
+    match s {
+        Some(s) => write_string(f, s),
+        None => f.write_str("null"),
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
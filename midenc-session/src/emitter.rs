@@ -10,7 +10,7 @@ use midenc_hir_symbol::sync::RwLock;
 
 #[cfg(feature = "std")]
 use crate::diagnostics::IntoDiagnostic;
-use crate::{ColorChoice, diagnostics::Report};
+use crate::{ColorChoice, DiagnosticsFormat, diagnostics::Report};
 
 /// The [Emitter] trait is used for controlling how diagnostics are displayed.
 ///
@@ -25,6 +25,10 @@ pub trait Emitter: Send + Sync {
     fn buffer(&self) -> Buffer;
     /// Display the contents of the given [Buffer]
     fn print(&self, buffer: Buffer) -> Result<(), Report>;
+    /// The format in which diagnostics should be rendered into a [Buffer] for this emitter
+    fn format(&self) -> DiagnosticsFormat {
+        DiagnosticsFormat::Human
+    }
 }
 
 /// [DefaultEmitter] is used for rendering to stderr, and as is implied
@@ -121,6 +125,39 @@ impl Emitter for DefaultEmitterImpl {
     }
 }
 
+/// [JsonEmitter] is used for rendering diagnostics to stderr as JSON, one object per line.
+///
+/// This is intended for consumption by tools, e.g. IDEs and CI, rather than humans. See
+/// [crate::diagnostics::JsonDiagnostic] for a description of the format.
+pub struct JsonEmitter(DefaultEmitterImpl);
+impl JsonEmitter {
+    /// Construct a new [JsonEmitter]
+    pub fn new() -> Self {
+        Self(DefaultEmitterImpl::new(ColorChoice::Never))
+    }
+}
+impl Default for JsonEmitter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Emitter for JsonEmitter {
+    #[inline(always)]
+    fn buffer(&self) -> Buffer {
+        self.0.buffer()
+    }
+
+    #[inline(always)]
+    fn print(&self, buffer: Buffer) -> Result<(), Report> {
+        self.0.print(buffer)
+    }
+
+    #[inline(always)]
+    fn format(&self) -> DiagnosticsFormat {
+        DiagnosticsFormat::Json
+    }
+}
+
 /// [CaptureEmitter] is used to capture diagnostics which are emitted, for later examination.
 ///
 /// This is intended for use in testing, where it is desirable to emit diagnostics
@@ -129,6 +166,7 @@ impl Emitter for DefaultEmitterImpl {
 #[cfg(feature = "std")]
 pub struct CaptureEmitter {
     buffer: parking_lot::Mutex<Vec<u8>>,
+    format: DiagnosticsFormat,
 }
 #[cfg(feature = "std")]
 impl CaptureEmitter {
@@ -138,6 +176,15 @@ impl CaptureEmitter {
         Self::default()
     }
 
+    /// Create a new [CaptureEmitter] which captures diagnostics rendered in the given format
+    #[inline]
+    pub fn with_format(format: DiagnosticsFormat) -> Self {
+        Self {
+            buffer: Default::default(),
+            format,
+        }
+    }
+
     pub fn captured(&self) -> String {
         let buf = self.buffer.lock();
         String::from_utf8_lossy(buf.as_slice()).into_owned()
@@ -157,6 +204,11 @@ impl Emitter for CaptureEmitter {
         buf.append(&mut bytes);
         Ok(())
     }
+
+    #[inline]
+    fn format(&self) -> DiagnosticsFormat {
+        self.format
+    }
 }
 
 /// [NullEmitter] is used to silence diagnostics entirely, without changing
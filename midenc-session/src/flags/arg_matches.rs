@@ -97,7 +97,13 @@ mod fallback {
                         );
                         continue;
                     }
-                    Some(name) => flags.get(name).copied(),
+                    Some(name) => match name.split_once('=') {
+                        Some((name, value)) => {
+                            argv.push_front(Cow::Owned(value.into()));
+                            flags.get(name).copied()
+                        }
+                        None => flags.get(name).copied(),
+                    },
                     None => match arg.strip_prefix("-") {
                         Some("") => {
                             return Err(Report::msg(format!(
@@ -131,6 +137,7 @@ mod fallback {
                             .or(flag.default_missing_value.map(Cow::Borrowed))
                             .or(flag.default_value.map(Cow::Borrowed));
                         if let Some(value) = value {
+                            validate_value(flag, &value)?;
                             flag_matches.raw_vals.push(vec![value]);
                         } else {
                             return Err(Report::msg(format!(
@@ -151,6 +158,7 @@ mod fallback {
                             .or(flag.default_missing_value.map(Cow::Borrowed))
                             .or(flag.default_value.map(Cow::Borrowed));
                         if let Some(value) = value {
+                            validate_value(flag, &value)?;
                             flag_matches.raw_vals.push(vec![value]);
                         } else {
                             return Err(Report::msg(format!(
@@ -171,6 +179,15 @@ mod fallback {
             Ok(this)
         }
 
+        /// Get the raw values given for `id`, in the order they appeared
+        pub fn get_raw_values(&self, id: &str) -> impl Iterator<Item = &str> + '_ {
+            self.args
+                .get(id)
+                .into_iter()
+                .flat_map(|arg| arg.raw_vals.iter().flatten())
+                .map(|value| value.as_ref())
+        }
+
         pub fn iter(&self) -> impl Iterator<Item = (&'static str, &[Vec<Cow<'static, str>>])> + '_ {
             self.args.iter().map(|(k, matched)| (*k, matched.raw_vals.as_slice()))
         }
@@ -229,6 +246,15 @@ mod fallback {
         }
     }
 
+    fn validate_value(flag: &CompileFlag, value: &str) -> Result<(), Report> {
+        match flag.validate {
+            Some(validate) => validate(value).map_err(|err| {
+                Report::msg(format!("invalid value '{value}' for '--{}': {err}", flag.name))
+            }),
+            None => Ok(()),
+        }
+    }
+
     #[derive(Default, Debug, Clone)]
     struct MatchedArg {
         source: Option<ValueSource>,
@@ -1,3 +1,6 @@
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+
 #[derive(Debug, Clone)]
 pub struct CompileFlag {
     pub name: &'static str,
@@ -10,6 +13,8 @@ pub struct CompileFlag {
     pub default_missing_value: Option<&'static str>,
     pub default_value: Option<&'static str>,
     pub hide: Option<bool>,
+    /// Validates values given to flags with action `FlagAction::Set` or `FlagAction::Append`
+    pub validate: Option<fn(&str) -> Result<(), String>>,
 }
 impl CompileFlag {
     pub const fn new(name: &'static str) -> Self {
@@ -24,6 +29,7 @@ impl CompileFlag {
             default_missing_value: None,
             default_value: None,
             hide: None,
+            validate: None,
         }
     }
 
@@ -71,6 +77,25 @@ impl CompileFlag {
         self.hide = Some(yes);
         self
     }
+
+    /// Require values of this flag to parse as `T`, rejecting invalid values during argument
+    /// parsing rather than when the value is first read.
+    pub const fn value_type<T>(mut self) -> Self
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.validate = Some(validate_value::<T>);
+        self
+    }
+}
+
+fn validate_value<T>(value: &str) -> Result<(), String>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value.parse::<T>().map(|_| ()).map_err(|err| err.to_string())
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -4,7 +4,7 @@ mod flag;
 #[cfg(not(feature = "std"))]
 use alloc::borrow::Cow;
 use alloc::vec::Vec;
-use core::fmt;
+use core::{fmt, str::FromStr};
 
 pub use self::{
     arg_matches::ArgMatches,
@@ -94,12 +94,54 @@ impl CompileFlags {
         self.arg_matches.get_count(name) as usize
     }
 
+    /// Get the value of a custom flag with action `FlagAction::Set`, parsed as `T`
+    ///
+    /// Returns `Ok(None)` if the flag was not given and has no default value, and an error if the
+    /// last occurrence of the flag has a value which is not a valid `T`. Flags read this way should
+    /// be registered with [CompileFlag::value_type], so that such values are rejected up front.
+    pub fn get_flag_value<T: FromStr>(&self, name: &str) -> Result<Option<T>, Report> {
+        self.raw_values(name)
+            .into_iter()
+            .last()
+            .map(|value| parse_flag_value(name, value))
+            .transpose()
+    }
+
+    /// Get all values of a custom flag with action `FlagAction::Append`, parsed as `T`
+    ///
+    /// See [CompileFlags::get_flag_value] for details on how values are parsed.
+    pub fn get_flag_values<T: FromStr>(&self, name: &str) -> Result<Vec<T>, Report> {
+        self.raw_values(name)
+            .into_iter()
+            .map(|value| parse_flag_value(name, value))
+            .collect()
+    }
+
+    #[cfg(feature = "std")]
+    fn raw_values(&self, name: &str) -> Vec<&str> {
+        self.arg_matches
+            .get_many::<alloc::string::String>(name)
+            .map(|values| values.map(|value| value.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn raw_values(&self, name: &str) -> Vec<&str> {
+        self.arg_matches.get_raw_values(name).collect()
+    }
+
     /// Get the remaining [ArgMatches] left after parsing the base session configuration
     pub fn matches(&self) -> &ArgMatches {
         &self.arg_matches
     }
 }
 
+fn parse_flag_value<T: FromStr>(name: &str, value: &str) -> Result<T, Report> {
+    value
+        .parse()
+        .map_err(|_| Report::msg(format!("invalid value '{value}' for custom flag '{name}'")))
+}
+
 impl fmt::Debug for CompileFlags {
     #[cfg(feature = "std")]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -192,6 +234,13 @@ pub fn register_flags(cmd: clap::Command) -> clap::Command {
         } else {
             arg
         };
+        let arg = if let Some(validate) = flag.validate {
+            arg.value_parser(move |value: &str| {
+                validate(value).map(|_| alloc::string::String::from(value))
+            })
+        } else {
+            arg
+        };
         cmd.arg(arg)
     })
 }
@@ -11,12 +11,14 @@ extern crate alloc;
 extern crate std;
 
 use alloc::{
-    borrow::ToOwned,
+    borrow::{Cow, ToOwned},
     format,
     string::{String, ToString},
 };
 
+mod cancel;
 mod color;
+mod config;
 pub mod diagnostics;
 #[cfg(feature = "std")]
 mod duration;
@@ -28,11 +30,16 @@ mod libs;
 mod options;
 mod outputs;
 pub mod path;
+mod producers;
 pub mod registry;
 #[cfg(feature = "std")]
 mod statistics;
 
-use alloc::{boxed::Box, fmt, sync::Arc};
+use alloc::{boxed::Box, fmt, sync::Arc, vec::Vec};
+use core::{
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 /// The version associated with the current compiler toolchain
 pub const MIDENC_BUILD_VERSION: &str = env!("MIDENC_BUILD_VERSION");
@@ -45,21 +52,31 @@ pub use miden_assembly_syntax;
 pub use miden_mast_package::PackageId;
 pub use miden_package_registry;
 pub use miden_project;
+use miden_project::Linkage;
 use midenc_hir_symbol::Symbol;
 
 pub use self::{
+    cancel::{Canceled, CancellationToken},
     color::ColorChoice,
+    config::{CONFIG_FILE_NAME, ConfigFile},
     diagnostics::{DiagnosticsHandler, Emitter, Report, SourceManager},
     emit::{Emit, Writer},
     flags::{ArgMatches, CompileFlag, CompileFlags, FlagAction},
     inputs::{FileName, FileType, InputFile, InputType, InvalidInputError},
     libs::{LibraryPath, LibraryPathComponent, LinkLibrary, STDLIB, add_target_link_libraries},
     options::*,
-    outputs::{OutputFile, OutputFiles, OutputMode, OutputType, OutputTypeSpec, OutputTypes},
+    outputs::{
+        IrDumps, OutputFile, OutputFiles, OutputMode, OutputType, OutputTypeSpec, OutputTypes,
+    },
     path::{Path, PathBuf},
+    producers::Producers,
 };
 #[cfg(feature = "std")]
-pub use self::{duration::HumanDuration, emit::EmitExt, statistics::Statistics};
+pub use self::{
+    duration::HumanDuration,
+    emit::EmitExt,
+    statistics::{Statistics, Timing, TimingKind, Timings, TimingsReport},
+};
 
 /// This struct provides access to all of the metadata and configuration
 /// needed during a single compilation session.
@@ -84,6 +101,8 @@ pub struct Session {
     /// Statistics gathered from the current compiler session
     #[cfg(feature = "std")]
     pub statistics: Statistics,
+    /// The number of IR dumps written so far, see [Session::dump_ir]
+    ir_dumps: AtomicUsize,
 }
 
 impl fmt::Debug for Session {
@@ -322,10 +341,16 @@ impl Session {
             log::debug!(target: "driver", " | target_dir = {}", options.target_dir.display());
         }
         let diagnostics = Arc::new(DiagnosticsHandler::new(
-            options.diagnostics,
+            options.diagnostics.clone(),
             source_manager.clone(),
             emitter.unwrap_or_else(|| options.default_emitter()),
         ));
+        if let Some(config) = options.config.as_deref() {
+            log::debug!(target: "driver", " | config = {}", config.path.display());
+            for warning in config.warnings.iter() {
+                diagnostics.warn(warning);
+            }
+        }
 
         let output_dir = options
             .output_dir
@@ -342,7 +367,9 @@ impl Session {
         log::debug!(target: "driver", " | target = {}", options.target_type.map(|tt| tt.to_string()).unwrap_or("none specified".to_string()));
         if log::log_enabled!(target: "driver", log::Level::Debug) {
             for lib in options.link_libraries.iter() {
-                if let Some(path) = lib.path.as_deref() {
+                if lib.bytes.is_some() {
+                    log::debug!(target: "driver", " | linking library '{}' from memory", &lib.name);
+                } else if let Some(path) = lib.path.as_deref() {
                     log::debug!(target: "driver", " | linking library '{}' from {}", &lib.name, path.display());
                 } else {
                     log::debug!(target: "driver", " | linking library '{}'", &lib.name);
@@ -357,13 +384,24 @@ impl Session {
             options.output_file.clone(),
             options.target_dir.clone(),
             options.output_types.clone(),
+            options.nest_outputs,
         );
 
         create_target_dir(options.target_dir.as_path());
 
+        // Deterministic builds must never encode paths under the working directory as-is
+        if options.deterministic {
+            options.remap_working_dir();
+        }
+
         // Linka against implicitly required libraries
         let requires_protocol = options.target_requires_protocol();
-        add_target_link_libraries(&mut options.link_libraries, requires_protocol);
+        let requires_softfloat = options.float_mode == FloatMode::SoftFloat;
+        add_target_link_libraries(
+            &mut options.link_libraries,
+            requires_protocol,
+            requires_softfloat,
+        );
 
         Self {
             name,
@@ -375,6 +413,7 @@ impl Session {
             project,
             #[cfg(feature = "std")]
             statistics: Default::default(),
+            ir_dumps: AtomicUsize::new(0),
         }
     }
 
@@ -385,6 +424,21 @@ impl Session {
         self
     }
 
+    /// Link against the library contained in `bytes`, a serialized Miden package, without
+    /// requiring it to be written to disk first.
+    ///
+    /// Libraries linked this way coexist with those requested via `-l`. If more than one of them
+    /// resolves to the same package digest, it is only linked once.
+    pub fn link_library_from_bytes(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        bytes: impl Into<Arc<[u8]>>,
+        linkage: Linkage,
+    ) -> Self {
+        self.options.link_libraries.push(LinkLibrary::from_bytes(name, bytes, linkage));
+        self
+    }
+
     #[doc(hidden)]
     pub fn with_extra_flags(mut self, flags: CompileFlags) -> Self {
         self.options.set_extra_flags(flags);
@@ -403,6 +457,20 @@ impl Session {
         self.options.flags.get_flag_count(name)
     }
 
+    /// Get the value of a custom flag with action `FlagAction::Set`, parsed as `T`
+    ///
+    /// See [CompileFlags::get_flag_value] for details.
+    #[inline]
+    pub fn get_flag_value<T: FromStr>(&self, name: &str) -> Result<Option<T>, Report> {
+        self.options.flags.get_flag_value(name)
+    }
+
+    /// Get all values of a custom flag with action `FlagAction::Append`, parsed as `T`
+    #[inline]
+    pub fn get_flag_values<T: FromStr>(&self, name: &str) -> Result<Vec<T>, Report> {
+        self.options.flags.get_flag_values(name)
+    }
+
     /// Get the remaining [ArgMatches] left after parsing the base session configuration
     #[inline]
     pub fn matches(&self) -> &ArgMatches {
@@ -414,6 +482,14 @@ impl Session {
         &self.name
     }
 
+    /// Get a handle to the [CancellationToken] of this session.
+    ///
+    /// The session is shared by the compiler, and cannot leave the thread it runs on, but the
+    /// token can be sent to another thread, and canceled from there while the compiler runs.
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.options.cancel_token.clone()
+    }
+
     /// Get a new package registry instance for this session
     pub fn package_registry(&self) -> Result<Box<registry::HybridPackageRegistry>, Report> {
         registry::HybridPackageRegistry::new(&self.options).map(Box::new)
@@ -487,7 +563,7 @@ impl Session {
     /// Returns true if IR should be printed to stdout, after executing a pass named `pass`
     pub fn should_print_ir(&self, pass: &str) -> bool {
         self.options.print_ir_after_all
-            || self.options.print_ir_after_pass.iter().any(|p| p == pass)
+            || self.options.print_ir_after_pass.iter().any(|p| p.matches(pass))
     }
 
     /// Returns true if IR should be printed to stdout, at the start of `stage`
@@ -498,18 +574,58 @@ impl Session {
     /// Returns true if CFG should be printed to stdout, after executing a pass named `pass`
     pub fn should_print_cfg(&self, pass: &str) -> bool {
         self.options.print_cfg_after_all
-            || self.options.print_cfg_after_pass.iter().any(|p| p == pass)
+            || self.options.print_cfg_after_pass.iter().any(|p| p.matches(pass))
+    }
+
+    /// Returns true if IR should be dumped to a file, after executing a pass named `pass`
+    pub fn should_dump_ir(&self, pass: &str) -> bool {
+        self.options.ir_dumps.as_ref().is_some_and(|dumps| dumps.should_dump(pass))
+    }
+
+    /// Get the directory to which IR dumps are written, if IR dumps were requested
+    pub fn ir_dump_dir(&self) -> Option<PathBuf> {
+        let dumps = self.options.ir_dumps.as_ref()?;
+        Some(match dumps.dir.as_ref() {
+            Some(dir) if dir.is_absolute() => dir.clone(),
+            Some(dir) => self.options.current_dir.join(dir),
+            None => self.options.target_dir.join("ir"),
+        })
     }
 
     /// Print the given emittable IR to stdout, as produced by a pass with name `pass`
+    ///
+    /// If IR dumps were requested for `pass`, the IR is also written to a file, see
+    /// [Self::dump_ir].
     #[cfg(feature = "std")]
     pub fn print(&self, ir: impl Emit, pass: &str) -> anyhow::Result<()> {
         if self.should_print_ir(pass) {
             ir.write_to_stdout(self)?;
         }
+        self.dump_ir(&ir, pass)?;
         Ok(())
     }
 
+    /// Write the given emittable IR to a file in [Self::ir_dump_dir], as produced by a pass with
+    /// name `pass`.
+    ///
+    /// Files are named `<index>-<pass>.<ext>`, where `index` increases monotonically over the
+    /// lifetime of the session, so that the order in which passes were applied can be
+    /// reconstructed from the file names alone.
+    ///
+    /// Returns the path of the file written, or `None` if IR dumps were not requested for `pass`.
+    #[cfg(feature = "std")]
+    pub fn dump_ir(&self, ir: impl Emit, pass: &str) -> anyhow::Result<Option<PathBuf>> {
+        let Some(dir) = self.ir_dump_dir().filter(|_| self.should_dump_ir(pass)) else {
+            return Ok(None);
+        };
+        let index = self.ir_dumps.fetch_add(1, Ordering::Relaxed);
+        let pass = outputs::escape_path_component(pass);
+        let extension = ir.output_type(OutputMode::Text).extension();
+        let path = dir.join(format!("{index:03}-{pass}.{extension}"));
+        ir.write_to_file(&path, OutputMode::Text, self)?;
+        Ok(Some(path))
+    }
+
     /// Get the path to emit the given [OutputType] to
     pub fn emit_to(&self, ty: OutputType, name: Option<Symbol>) -> Option<PathBuf> {
         if self.should_emit(ty) {
@@ -533,6 +649,15 @@ impl Session {
             let name = item.name().map(|n| n.as_str());
             match self.output_files.output_file(output_type, name) {
                 OutputFile::Real(path) => {
+                    let (path, collided) = self.output_files.reserve(output_type, path, name);
+                    if collided {
+                        self.diagnostics.warn(format!(
+                            "output for '{}' collides with a previously emitted file, writing it \
+                             to '{}' instead",
+                            name.unwrap_or(self.name.as_str()),
+                            path.display()
+                        ));
+                    }
                     item.write_to_file(&path, mode, self)?;
                 }
                 OutputFile::Directory(_) => {
@@ -565,7 +690,7 @@ fn infer_cargo_project_entrypoint(
     project: &miden_project::Project,
     options: &mut Options,
 ) -> Result<(), Report> {
-    if options.entrypoint.is_some() {
+    if !options.entrypoints.is_empty() {
         return Ok(());
     }
 
@@ -587,10 +712,10 @@ fn infer_cargo_project_entrypoint(
             };
 
             let masm_module_name = target.name.inner().replace('-', "_");
-            options.entrypoint = Some(format!("{masm_module_name}::entrypoint"));
+            options.entrypoints = vec![format!("{masm_module_name}::entrypoint")];
         }
         Some(miden_project::TargetType::TransactionScript) => {
-            options.entrypoint = Some("miden:base/transaction-script@1.0.0::run".to_string());
+            options.entrypoints = vec!["miden:base/transaction-script@1.0.0::run".to_string()];
         }
         _ => (),
     }
@@ -32,6 +32,11 @@ pub struct LinkLibrary {
     pub name: Cow<'static, str>,
     /// If specified, the path from which this library should be loaded
     pub path: Option<PathBuf>,
+    /// If specified, the serialized package from which this library should be loaded
+    ///
+    /// This takes precedence over `path`, and allows linking against libraries which are only
+    /// available in memory, e.g. when the compiler is used as a library.
+    pub bytes: Option<Arc<[u8]>>,
     /// How to link against this library
     pub linkage: Linkage,
 }
@@ -45,11 +50,16 @@ impl LinkLibrary {
         matches!(self.name.as_ref(), "miden-protocol" | "protocol" | "base")
     }
 
+    pub fn is_softfloat(&self) -> bool {
+        matches!(self.name.as_ref(), "miden-softfloat" | "softfloat")
+    }
+
     /// Construct a LinkLibrary for Miden stdlib
     pub fn core() -> Self {
         LinkLibrary {
             name: "miden-core".into(),
             path: None,
+            bytes: None,
             linkage: Linkage::Dynamic,
         }
     }
@@ -59,10 +69,40 @@ impl LinkLibrary {
         LinkLibrary {
             name: "miden-protocol".into(),
             path: None,
+            bytes: None,
             linkage: Linkage::Dynamic,
         }
     }
 
+    /// Construct a LinkLibrary for the software floating-point library, see
+    /// [crate::FloatMode::SoftFloat]
+    ///
+    /// This library is not shipped with the compiler, it is located using the library search
+    /// paths, which include the `lib` directory of the current toolchain. The procedures it must
+    /// export are described in the documentation of `--float-mode`.
+    pub fn softfloat() -> Self {
+        LinkLibrary {
+            name: "miden-softfloat".into(),
+            path: None,
+            bytes: None,
+            linkage: Linkage::Dynamic,
+        }
+    }
+
+    /// Construct a LinkLibrary which is loaded from `bytes`, a serialized Miden package
+    pub fn from_bytes(
+        name: impl Into<Cow<'static, str>>,
+        bytes: impl Into<Arc<[u8]>>,
+        linkage: Linkage,
+    ) -> Self {
+        LinkLibrary {
+            name: name.into(),
+            path: None,
+            bytes: Some(bytes.into()),
+            linkage,
+        }
+    }
+
     #[cfg(not(feature = "std"))]
     pub fn load(&self, _options: &Options) -> Result<CompiledLibrary, Report> {
         // Handle libraries shipped with the compiler, or via Miden crates
@@ -105,6 +145,17 @@ impl LinkLibrary {
     pub fn load(&self, options: &Options) -> Result<Arc<Package>, Report> {
         use miden_mast_package::{Dependency, Version};
 
+        if let Some(bytes) = self.bytes.as_deref() {
+            let package = load_package_from_bytes(bytes, &self.name)?;
+            if package.is_program() {
+                return Err(Report::msg(format!(
+                    "Expected Miden package to contain a Library, got Program: '{}'",
+                    &self.name
+                )));
+            }
+            return Ok(package);
+        }
+
         if let Some(path) = self.path.as_deref() {
             return self.load_from_path(path, options);
         }
@@ -142,7 +193,18 @@ impl LinkLibrary {
         }
 
         // Search for library among specified search paths
-        let path = self.find(options)?;
+        let path = self.find(options).map_err(|err| {
+            if self.is_softfloat() {
+                Report::msg(format!(
+                    "{err}: the software floating-point library required by \
+                     `--float-mode=soft-float` is not shipped with the compiler, it must be \
+                     provided with `-l masp=<path>/miden-softfloat.masp`, or placed in a library \
+                     search path given with `-L`"
+                ))
+            } else {
+                err
+            }
+        })?;
 
         self.load_from_path(&path, options)
     }
@@ -203,10 +265,16 @@ impl LinkLibrary {
 #[cfg(feature = "std")]
 pub(crate) fn load_package_from_path(path: &Path) -> Result<Arc<Package>, Report> {
     let bytes = std::fs::read(path).into_diagnostic()?;
-    miden_mast_package::Package::read_from_bytes(&bytes)
-        .map_err(|e| {
-            Report::msg(format!("failed to load Miden package from {}: {e}", path.display()))
-        })
+    load_package_from_bytes(&bytes, path.display())
+}
+
+#[cfg(feature = "std")]
+fn load_package_from_bytes(
+    bytes: &[u8],
+    source: impl core::fmt::Display,
+) -> Result<Arc<Package>, Report> {
+    miden_mast_package::Package::read_from_bytes(bytes)
+        .map_err(|e| Report::msg(format!("failed to load Miden package from {source}: {e}")))
         .map(Arc::new)
 }
 
@@ -313,6 +381,7 @@ impl clap::builder::TypedValueParser for LinkLibraryParser {
             Ok(LinkLibrary {
                 name: name.into(),
                 path: Some(maybe_path.to_path_buf()),
+                bytes: None,
                 linkage,
             })
         } else if extension.is_some() {
@@ -323,12 +392,14 @@ impl clap::builder::TypedValueParser for LinkLibraryParser {
             Ok(LinkLibrary {
                 name: name.into(),
                 path: None,
+                bytes: None,
                 linkage,
             })
         } else {
             Ok(LinkLibrary {
                 name: name.to_string().into(),
                 path: None,
+                bytes: None,
                 linkage,
             })
         }
@@ -337,11 +408,18 @@ impl clap::builder::TypedValueParser for LinkLibraryParser {
 
 /// Add libraries required by the target environment to the list of libraries to link against only
 /// if they are not already present.
-pub fn add_target_link_libraries(link_libraries: &mut Vec<LinkLibrary>, requires_protocol: bool) {
+pub fn add_target_link_libraries(
+    link_libraries: &mut Vec<LinkLibrary>,
+    requires_protocol: bool,
+    requires_softfloat: bool,
+) {
     if !link_libraries.iter().any(LinkLibrary::is_core) {
         link_libraries.push(LinkLibrary::core());
     }
     if requires_protocol && !link_libraries.iter().any(LinkLibrary::is_protocol) {
         link_libraries.push(LinkLibrary::protocol());
     }
+    if requires_softfloat && !link_libraries.iter().any(LinkLibrary::is_softfloat) {
+        link_libraries.push(LinkLibrary::softfloat());
+    }
 }
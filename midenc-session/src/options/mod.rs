@@ -13,9 +13,10 @@ use alloc::{
 use miden_debug_types::SourceManager;
 use miden_project::TargetType;
 
-pub use self::printing::IrFilter;
+pub use self::printing::{IrFilter, PassFilter};
 use crate::{
-    ColorChoice, CompileFlags, InputFile, LinkLibrary, OutputFile, OutputTypes, PathBuf,
+    CancellationToken, ColorChoice, CompileFlags, ConfigFile, InputFile, IrDumps, LinkLibrary,
+    OutputFile, OutputTypes, PathBuf,
     diagnostics::{DiagnosticsConfig, Emitter, Report},
 };
 
@@ -24,16 +25,36 @@ use crate::{
 pub struct Options {
     /// The path to the current project manifest, if present
     pub manifest_path: Option<PathBuf>,
+    /// The `midenc.toml` file from which defaults for these options were loaded, if any
+    pub config: Option<Arc<ConfigFile>>,
     /// The name of the program being compiled
     pub name: Option<String>,
-    /// The name of the function to call as the entrypoint
-    pub entrypoint: Option<String>,
+    /// The names of the functions to call as entrypoints
+    ///
+    /// A single entrypoint produces an executable program. When more than one is given, the
+    /// output is a library exporting each of them, see also [Self::masp_per_entrypoint].
+    pub entrypoints: Vec<String>,
+    /// Whether to emit a separate package for each of [Self::entrypoints]
+    pub masp_per_entrypoint: bool,
     /// The name of the build profile to use
     pub profile: String,
     /// Build all packages in the current workspace (used by `cargo miden`)
     pub workspace: bool,
     /// Build the specified packages in the current workspace (used by `cargo miden`)
     pub packages: Vec<String>,
+    /// The Cargo features to activate (used by `cargo miden`)
+    pub features: Vec<String>,
+    /// Activate all available Cargo features (used by `cargo miden`)
+    pub all_features: bool,
+    /// Do not activate the `default` Cargo feature (used by `cargo miden`)
+    pub no_default_features: bool,
+    /// Require `Cargo.lock` to be up to date (used by `cargo miden`)
+    pub locked: bool,
+    /// Prevent Cargo from accessing the network (used by `cargo miden`)
+    pub offline: bool,
+    /// The directory in which Cargo places its build artifacts, if not the default (used by
+    /// `cargo miden`)
+    pub cargo_target_dir: Option<PathBuf>,
     /// The name of the current project target being compiled
     pub target: Option<String>,
     /// The type of target that was requested
@@ -65,6 +86,8 @@ pub struct Options {
     pub toolchain: Option<String>,
     /// Whether, and how, to color terminal output
     pub color: ColorChoice,
+    /// The format in which the emitter returned by [Self::default_emitter] renders diagnostics
+    pub diagnostics_format: DiagnosticsFormat,
     /// The current diagnostics configuration
     pub diagnostics: DiagnosticsConfig,
     /// The current working directory of the compiler
@@ -77,6 +100,14 @@ pub struct Options {
     pub output_file: Option<OutputFile>,
     /// Path prefixes to remap for any file paths encoded in debug info
     pub remap_path_prefixes: Vec<RemapPathPrefix>,
+    /// Produce artifacts which do not depend on the machine, or directory, they were built in
+    ///
+    /// This ensures paths under the working directory are always remapped, and, when building a
+    /// Cargo project, so are the Cargo home directory and the sysroot of the Rust toolchain.
+    pub deterministic: bool,
+    /// Write the outputs of items whose names have namespace components, delimited by `::` or
+    /// `/`, to nested subdirectories, see [crate::OutputFiles::nest_outputs]
+    pub nest_outputs: bool,
     /// Print source location information in HIR output
     pub print_hir_source_locations: bool,
     /// Only parse inputs
@@ -87,6 +118,11 @@ pub struct Options {
     pub link_only: bool,
     /// Generate Miden Assembly from the inputs without the linker
     pub no_link: bool,
+    /// Save the intermediate state of the compiler at the given checkpoint, and stop
+    pub stop_after: Option<Checkpoint>,
+    /// Resume compilation from the state saved at the given checkpoint by a previous compilation
+    /// with [Self::stop_after], skipping all of the stages before it
+    pub start_from: Option<Checkpoint>,
     /// Run the experimental Miden Assembly linter prior to codegen
     ///
     /// This linter uses the HIR dataflow analysis framework to check for issues such as
@@ -94,20 +130,52 @@ pub struct Options {
     pub lint: bool,
     /// Print CFG to stdout after each pass
     pub print_cfg_after_all: bool,
-    /// Print CFG to stdout each time the named passes are applied
-    pub print_cfg_after_pass: Vec<String>,
+    /// Print CFG to stdout each time the passes matching any of these filters are applied
+    pub print_cfg_after_pass: Vec<PassFilter>,
     /// Print IR to stdout at the start of each stage
     pub print_ir_before_stage: Vec<String>,
     /// Print IR to stdout after each pass
     pub print_ir_after_all: bool,
-    /// Print IR to stdout each time the named passes are applied
-    pub print_ir_after_pass: Vec<String>,
+    /// Print IR to stdout each time the passes matching any of these filters are applied
+    pub print_ir_after_pass: Vec<PassFilter>,
     /// Only print the IR if the pass modified the IR structure.
     pub print_ir_after_modified: bool,
     /// Apply filters to what IR is printed, when printing is enabled
     pub print_ir_filters: Vec<IrFilter>,
+    /// Dump IR to numbered files after the selected passes, see `--emit=ir-after`
+    pub ir_dumps: Option<IrDumps>,
+    /// Record the time spent in each compiler stage and pass, and print a report when done
+    pub time_passes: bool,
+    /// Run the IR verifier after every pass, rather than only after passes which may have
+    /// modified the IR
+    pub verify_each: bool,
     /// Save intermediate artifacts in memory during compilation
     pub save_temps: bool,
+    /// Retain the Miden Assembly that was assembled in the artifact returned by the compiler
+    pub keep_masm: bool,
+    /// Do not reuse, or save, translated inputs and compiled packages in the cache under
+    /// [Self::target_dir]
+    pub no_cache: bool,
+    /// Reuse, and save, the packages compiled from Cargo projects in the cache under
+    /// [Self::target_dir], unless [Self::no_cache] is set
+    ///
+    /// This is only enabled by `cargo miden build`, which rebuilds the Wasm of the project before
+    /// every compilation, so that unchanged projects are not compiled again.
+    pub package_cache: bool,
+    /// Do not optimize the emitted Miden Assembly, regardless of [Self::optimize]
+    ///
+    /// This is useful to determine whether a miscompilation originates in lowering, or in the
+    /// optimizations applied to the emitted code.
+    pub no_masm_opt: bool,
+    /// Rename the procedures which are not exported from their module to the demangled names of
+    /// the functions they were lowered from, followed by a hash of the original name
+    pub demangle_procedure_names: bool,
+    /// How floating-point operations in the input are translated, see [FloatMode]
+    pub float_mode: FloatMode,
+    /// A TOML file mapping Wasm imports to the procedures of a custom transaction kernel
+    pub kernel_abi: Option<PathBuf>,
+    /// The token used to cancel this compilation, see [CancellationToken]
+    pub cancel_token: CancellationToken,
     /// Custom RUSTFLAGS to set when building Rust
     pub rustflags: Option<String>,
     /// Look for `cargo -Zscript`-style frontmatter when compiling standalone Rust sources
@@ -147,14 +215,22 @@ impl Options {
 
         Self {
             manifest_path: None,
+            config: None,
             name,
             profile: "dev".to_string(),
             workspace: false,
             packages: vec![],
+            features: vec![],
+            all_features: false,
+            no_default_features: false,
+            locked: false,
+            offline: false,
+            cargo_target_dir: None,
             target: None,
             target_type: target,
-            entrypoint: None,
-            optimize: OptLevel::None,
+            entrypoints: vec![],
+            masp_per_entrypoint: false,
+            optimize: OptLevel::default(),
             debug: DebugInfo::None,
             output_types: Default::default(),
             search_paths,
@@ -164,6 +240,7 @@ impl Options {
             midenup_home: None,
             toolchain: None,
             color: Default::default(),
+            diagnostics_format: Default::default(),
             diagnostics: Default::default(),
             current_dir,
             target_dir,
@@ -174,7 +251,17 @@ impl Options {
             analyze_only: false,
             link_only: false,
             no_link: false,
+            stop_after: None,
+            start_from: None,
             save_temps: false,
+            keep_masm: false,
+            no_cache: false,
+            package_cache: false,
+            no_masm_opt: false,
+            demangle_procedure_names: false,
+            float_mode: FloatMode::default(),
+            kernel_abi: None,
+            cancel_token: CancellationToken::new(),
             lint: false,
             cargo_frontmatter: false,
             print_cfg_after_all: false,
@@ -184,8 +271,13 @@ impl Options {
             print_ir_after_pass: vec![],
             print_ir_after_modified: false,
             print_ir_filters: vec![],
+            ir_dumps: None,
+            time_passes: false,
+            verify_each: false,
             rustflags: None,
             remap_path_prefixes: vec![],
+            deterministic: false,
+            nest_outputs: false,
             flags: CompileFlags::default(),
         }
     }
@@ -202,6 +294,19 @@ impl Options {
         self
     }
 
+    #[inline(always)]
+    pub fn with_diagnostics_format(mut self: Box<Self>, format: DiagnosticsFormat) -> Box<Self> {
+        self.diagnostics_format = format;
+        self
+    }
+
+    /// Set the maximum number of errors to render, or `None` to render all of them
+    #[inline(always)]
+    pub fn with_error_limit(mut self: Box<Self>, error_limit: Option<usize>) -> Box<Self> {
+        self.diagnostics.error_limit = error_limit;
+        self
+    }
+
     #[inline(always)]
     pub fn with_debug_info(mut self: Box<Self>, debug: DebugInfo) -> Box<Self> {
         self.debug = debug;
@@ -219,6 +324,27 @@ impl Options {
         self
     }
 
+    /// Set the [LintLevel] for warnings with the diagnostic code `lint`.
+    ///
+    /// The special lint name `warnings` applies to all warnings, i.e. `Deny` is equivalent to
+    /// `--warn error`, and `Allow` is equivalent to `--warn none`.
+    pub fn with_lint_level(
+        mut self: Box<Self>,
+        lint: impl Into<String>,
+        level: LintLevel,
+    ) -> Box<Self> {
+        let lint = lint.into();
+        match (lint.as_str(), level) {
+            ("warnings", LintLevel::Allow) => self.diagnostics.warnings = Warnings::None,
+            ("warnings", LintLevel::Warn) => self.diagnostics.warnings = Warnings::All,
+            ("warnings", LintLevel::Deny) => self.diagnostics.warnings = Warnings::Error,
+            _ => {
+                self.diagnostics.lint_levels.insert(lint, level);
+            }
+        }
+        self
+    }
+
     pub fn with_output_types(
         mut self: Box<Self>,
         mut output_types: OutputTypes,
@@ -264,11 +390,12 @@ impl Options {
 
     /// Get a new [Emitter] based on the current options.
     pub fn default_emitter(&self) -> Arc<dyn Emitter> {
-        use crate::diagnostics::{DefaultEmitter, NullEmitter};
+        use crate::diagnostics::{DefaultEmitter, JsonEmitter, NullEmitter};
 
-        match self.diagnostics.verbosity {
-            Verbosity::Silent => Arc::new(NullEmitter::new(self.color)),
-            _ => Arc::new(DefaultEmitter::new(self.color)),
+        match (self.diagnostics.verbosity, self.diagnostics_format) {
+            (Verbosity::Silent, _) => Arc::new(NullEmitter::new(self.color)),
+            (_, DiagnosticsFormat::Human) => Arc::new(DefaultEmitter::new(self.color)),
+            (_, DiagnosticsFormat::Json) => Arc::new(JsonEmitter::new()),
         }
     }
 
@@ -291,6 +418,24 @@ impl Options {
         self.debug != DebugInfo::None && matches!(self.optimize, OptLevel::None | OptLevel::Basic)
     }
 
+    /// Returns true if the emitted Miden Assembly should be optimized
+    #[inline(always)]
+    pub fn optimize_masm(&self) -> bool {
+        self.optimize != OptLevel::None && !self.no_masm_opt
+    }
+
+    /// Remap paths under the current working directory to be relative to it, unless an identical
+    /// remapping was already requested.
+    pub fn remap_working_dir(&mut self) {
+        let cwd = self.current_dir.as_path();
+        if !self.remap_path_prefixes.iter().any(|remap| remap.source_prefix() == cwd) {
+            self.remap_path_prefixes.push(RemapPathPrefix {
+                from: cwd.to_path_buf().into_boxed_path(),
+                to: None,
+            });
+        }
+    }
+
     /// Returns true if the requested target type is a protocol target
     pub fn target_requires_protocol(&self) -> bool {
         use miden_project::TargetType;
@@ -302,10 +447,10 @@ impl Options {
 }
 
 /// This enum describes the degree to which compiled programs will be optimized
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(clap::ValueEnum))]
 pub enum OptLevel {
-    /// No optimizations at all
+    /// No optimizations at all, only the rewrites required to lower to Miden Assembly are run
     None,
     /// Only basic optimizations are applied, e.g. constant propagation
     Basic,
@@ -333,6 +478,61 @@ pub enum DebugInfo {
     Full,
 }
 
+/// This enum describes how floating-point operations are translated, as Miden Assembly has no
+/// native support for them
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(clap::ValueEnum))]
+pub enum FloatMode {
+    /// Raise an error naming the function which performs floating-point operations
+    #[default]
+    Reject,
+    /// Translate floating-point operations to calls into the `miden-softfloat` library, which is
+    /// linked against like the standard library
+    SoftFloat,
+    /// Translate floating-point operations to a trap, for code which is known to never execute
+    /// them, e.g. float formatting pulled in by a dependency
+    Unreachable,
+}
+
+/// This enum represents the points in the compilation pipeline at which the intermediate state of
+/// the compiler can be saved, and later resumed from, see `-C stop-after` and `-C start-from`
+///
+/// There is no checkpoint after codegen: the generated component also carries its kernel, data
+/// segments and entrypoints, which have no serialized form, so resuming from [Self::Rewrite] is the
+/// latest point from which compilation can resume, and re-runs only codegen and assembly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(clap::ValueEnum))]
+pub enum Checkpoint {
+    /// After the inputs have been parsed and linked into a single component
+    Link,
+    /// After the rewrite pipeline has been applied to the linked component
+    Rewrite,
+}
+impl Checkpoint {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Link => "link",
+            Self::Rewrite => "rewrite",
+        }
+    }
+}
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl FromStr for Checkpoint {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "link" => Ok(Self::Link),
+            "rewrite" => Ok(Self::Rewrite),
+            _ => Err(()),
+        }
+    }
+}
+
 /// This enum represents the behavior of the compiler with regard to warnings
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(clap::ValueEnum))]
@@ -378,6 +578,57 @@ impl FromStr for Warnings {
     }
 }
 
+/// This enum represents how a specific lint, i.e. a warning with a given diagnostic code, is
+/// treated by the compiler
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LintLevel {
+    /// Silence the lint
+    Allow,
+    /// Emit the lint as a warning
+    Warn,
+    /// Promote the lint to an error
+    Deny,
+}
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Allow => f.write_str("allow"),
+            Self::Warn => f.write_str("warn"),
+            Self::Deny => f.write_str("deny"),
+        }
+    }
+}
+
+/// This enum represents the format in which diagnostics are rendered
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(clap::ValueEnum))]
+pub enum DiagnosticsFormat {
+    /// Render diagnostics for humans, with source snippets
+    #[default]
+    Human,
+    /// Render diagnostics as JSON objects, one per line
+    Json,
+}
+impl fmt::Display for DiagnosticsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Human => f.write_str("human"),
+            Self::Json => f.write_str("json"),
+        }
+    }
+}
+impl FromStr for DiagnosticsFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
 /// This enum represents the type of messages produced by the compiler during execution
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(clap::ValueEnum))]
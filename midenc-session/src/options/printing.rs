@@ -3,7 +3,7 @@ use alloc::{
     format,
     string::{String, ToString},
 };
-use core::str::FromStr;
+use core::{fmt, str::FromStr};
 
 /// ColorChoice represents the color preferences of an end user.
 ///
@@ -56,3 +56,128 @@ impl FromStr for IrFilter {
         }
     }
 }
+
+/// A pattern used to select passes by name, e.g. `--print-ir-after-pass`
+///
+/// The following forms are supported:
+///
+/// * `/<regex>/` - matches pass names against a regular expression, e.g. `/scf.*/`
+/// * `<glob>` - if the pattern contains `*` or `?`, matches pass names against a glob, where `*`
+///   matches any sequence of characters, and `?` matches any single character, e.g. `lift-*`
+/// * `<name>` - matches the pass with the given name exactly
+///
+/// Regular expressions and globs must match the entire pass name.
+#[derive(Debug, Clone)]
+pub enum PassFilter {
+    /// Matches a pass by its exact name
+    Exact(String),
+    /// Matches pass names using a glob pattern
+    Glob(String),
+    /// Matches pass names using a regular expression
+    #[cfg(feature = "std")]
+    Regex(regex::Regex),
+}
+
+impl PassFilter {
+    /// Returns true if `pass` is selected by this filter
+    pub fn matches(&self, pass: &str) -> bool {
+        match self {
+            Self::Exact(name) => name == pass,
+            Self::Glob(pattern) => glob_matches(pattern.as_bytes(), pass.as_bytes()),
+            #[cfg(feature = "std")]
+            Self::Regex(regex) => regex.is_match(pass),
+        }
+    }
+}
+
+impl fmt::Display for PassFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(name) => f.write_str(name),
+            Self::Glob(pattern) => f.write_str(pattern),
+            #[cfg(feature = "std")]
+            Self::Regex(regex) => {
+                // Strip the anchors added when the regex was compiled
+                let pattern = regex.as_str();
+                let pattern = pattern
+                    .strip_prefix("^(?:")
+                    .and_then(|p| p.strip_suffix(")$"))
+                    .unwrap_or(pattern);
+                write!(f, "/{pattern}/")
+            }
+        }
+    }
+}
+
+impl FromStr for PassFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("invalid pass filter: expected a pass name, glob, or `/regex/`".to_string());
+        }
+
+        if let Some(pattern) = s.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+            return Self::parse_regex(pattern);
+        }
+
+        if s.contains(['*', '?']) {
+            Ok(Self::Glob(s.to_string()))
+        } else {
+            Ok(Self::Exact(s.to_string()))
+        }
+    }
+}
+
+impl PassFilter {
+    #[cfg(feature = "std")]
+    fn parse_regex(pattern: &str) -> Result<Self, String> {
+        if pattern.is_empty() {
+            return Err("invalid pass filter: regular expression `//` is empty".to_string());
+        }
+        regex::Regex::new(&format!("^(?:{pattern})$"))
+            .map(Self::Regex)
+            .map_err(|err| format!("invalid pass filter `/{pattern}/`: {err}"))
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn parse_regex(pattern: &str) -> Result<Self, String> {
+        Err(format!(
+            "invalid pass filter `/{pattern}/`: regular expressions require the `std` feature"
+        ))
+    }
+}
+
+/// Match `input` against `pattern`, where `*` matches any sequence of bytes, and `?` matches
+/// any single byte.
+fn glob_matches(pattern: &[u8], input: &[u8]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // The position of the last `*` seen in `pattern`, and the position in `input` it was matched at
+    let mut backtrack = None;
+    while i < input.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, i));
+                p += 1;
+            }
+            Some(b'?') => {
+                p += 1;
+                i += 1;
+            }
+            Some(c) if *c == input[i] => {
+                p += 1;
+                i += 1;
+            }
+            _ => match backtrack {
+                // Retry by having the last `*` consume one more byte of `input`
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    i = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
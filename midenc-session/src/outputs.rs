@@ -4,8 +4,11 @@ use alloc::{
     fmt, format,
     str::FromStr,
     string::String,
+    sync::Arc,
+    vec::Vec,
 };
 
+use midenc_hir_symbol::sync::RwLock;
 use smallvec::SmallVec;
 
 use crate::{Path, PathBuf};
@@ -15,7 +18,7 @@ use crate::{Path, PathBuf};
 /// This is used when emitting artifacts whose names may contain characters that are legal in
 /// compiler/session identifiers, but are problematic (or even invalid) as filenames on common
 /// filesystems.
-fn escape_path_component(name: &str) -> Cow<'_, str> {
+pub(crate) fn escape_path_component(name: &str) -> Cow<'_, str> {
     if name.is_empty() {
         return Cow::Borrowed("_");
     }
@@ -42,6 +45,25 @@ fn escape_path_component(name: &str) -> Cow<'_, str> {
     }
 }
 
+/// Convert the name of an emitted item to a relative file path (without extension), when
+/// [OutputFiles::nest_outputs] is set.
+///
+/// Each namespace component of `name`, delimited by `::` or `/`, becomes a directory, so that
+/// items with the same name in different namespaces do not clobber each other.
+fn output_name_to_path(name: &str) -> PathBuf {
+    let components = name
+        .split("::")
+        .flat_map(|component| component.split('/'))
+        .filter(|component| !component.is_empty())
+        .map(escape_path_component)
+        .collect::<Vec<_>>();
+    if components.is_empty() {
+        PathBuf::from("_")
+    } else {
+        PathBuf::from(components.join("/").as_str())
+    }
+}
+
 /// The type of output to produce for a given [OutputType], when multiple options are available
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum OutputMode {
@@ -68,6 +90,10 @@ pub enum OutputType {
     /// The compiler will emit a MAST package in binary form
     #[default]
     Masp,
+    /// The compiler will emit a static estimate of the VM cycles spent in each procedure, as a
+    /// table, or as JSON if written to a file with the `.json` extension
+    #[cfg_attr(feature = "std", value(name = "cycles"))]
+    CycleReport,
 }
 impl OutputType {
     /// Returns true if this output type is an intermediate artifact produced during compilation
@@ -83,18 +109,20 @@ impl OutputType {
             Self::Masm => "masm",
             Self::Mast => "mast",
             Self::Masp => "masp",
+            Self::CycleReport => "cycles",
         }
     }
 
     pub fn shorthand_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             Self::Ast,
             Self::Wat,
             Self::Hir,
             Self::Masm,
             Self::Mast,
             Self::Masp,
+            Self::CycleReport,
         )
     }
 
@@ -106,6 +134,7 @@ impl OutputType {
             OutputType::Masm,
             OutputType::Mast,
             OutputType::Masp,
+            OutputType::CycleReport,
         ]
     }
 
@@ -124,6 +153,7 @@ impl fmt::Display for OutputType {
             Self::Masm => f.write_str("masm"),
             Self::Mast => f.write_str("mast"),
             Self::Masp => f.write_str("masp"),
+            Self::CycleReport => f.write_str("cycles"),
         }
     }
 }
@@ -138,6 +168,7 @@ impl FromStr for OutputType {
             "masm" => Ok(Self::Masm),
             "mast" => Ok(Self::Mast),
             "masp" => Ok(Self::Masp),
+            "cycles" => Ok(Self::CycleReport),
             _ => Err(()),
         }
     }
@@ -211,8 +242,7 @@ impl OutputFile {
                 } else {
                     outputs.cwd.join(dir)
                 };
-                let stem = escape_path_component(name.unwrap_or(outputs.stem.as_str()));
-                dir.join(stem.as_ref()).with_extension(ty.extension())
+                dir.join(outputs.output_name(name)).with_extension(ty.extension())
             }
             Self::Stdout => outputs.temp_path(ty, name),
         }
@@ -247,6 +277,11 @@ pub struct OutputFiles {
     pub out_file: Option<OutputFile>,
     /// The raw output types requested by the user on the command line
     pub outputs: OutputTypes,
+    /// Whether the namespace components of the name of an item, delimited by `::` or `/`, are
+    /// written to nested subdirectories, rather than flattened into a single file name
+    pub nest_outputs: bool,
+    /// The files written so far, along with the name of the item written to each
+    emitted: Arc<RwLock<BTreeMap<PathBuf, Option<String>>>>,
 }
 impl OutputFiles {
     pub fn new(
@@ -256,6 +291,7 @@ impl OutputFiles {
         out_file: Option<OutputFile>,
         tmp_dir: PathBuf,
         outputs: OutputTypes,
+        nest_outputs: bool,
     ) -> Self {
         Self {
             stem,
@@ -264,6 +300,8 @@ impl OutputFiles {
             out_dir,
             out_file,
             outputs,
+            nest_outputs,
+            emitted: Default::default(),
         }
     }
 
@@ -272,7 +310,7 @@ impl OutputFiles {
     /// specific path was not provided.
     pub fn output_file(&self, ty: OutputType, name: Option<&str>) -> OutputFile {
         let requested = self.outputs.contains_key(&ty);
-        let default_name = escape_path_component(name.unwrap_or(self.stem.as_str()));
+        let default_name = self.output_name(name);
         match self.outputs.get(&ty).and_then(|p| p.to_owned()) {
             Some(OutputFile::Real(path)) => OutputFile::Real({
                 let path = if path.is_absolute() {
@@ -281,7 +319,7 @@ impl OutputFiles {
                     self.cwd.join(path)
                 };
                 if path.is_dir() {
-                    path.join(default_name.as_ref()).with_extension(ty.extension())
+                    path.join(&default_name).with_extension(ty.extension())
                 } else {
                     path
                 }
@@ -292,7 +330,7 @@ impl OutputFiles {
                 } else {
                     self.cwd.join(dir)
                 };
-                dir.join(default_name.as_ref()).with_extension(ty.extension())
+                dir.join(&default_name).with_extension(ty.extension())
             }),
             Some(OutputFile::Stdout) => OutputFile::Stdout,
             None => {
@@ -310,11 +348,9 @@ impl OutputFiles {
                 } else {
                     self.with_directory_and_extension(&self.out_dir, ty.extension())
                 };
-                OutputFile::Real(if let Some(name) = name {
-                    let name = escape_path_component(name);
-                    out.with_stem(name.as_ref())
-                } else {
-                    out
+                OutputFile::Real(match (name, out.parent()) {
+                    (Some(_), Some(dir)) => dir.join(&default_name).with_extension(ty.extension()),
+                    _ => out,
                 })
             }
         }
@@ -344,13 +380,75 @@ impl OutputFiles {
         }
     }
 
+    /// Reserve `path` as the destination for the item of type `ty` called `name`, returning the
+    /// path to which the item should actually be written, and whether `path` was already taken.
+    ///
+    /// If a different item was already written to `path` during this session, the returned path
+    /// has a hash of `name` appended to its file stem, so that neither item clobbers the other.
+    /// A file explicitly chosen by the user for `ty` is always written to as given.
+    pub fn reserve(&self, ty: OutputType, path: PathBuf, name: Option<&str>) -> (PathBuf, bool) {
+        use core::hash::BuildHasher;
+
+        let mut emitted = self.emitted.write();
+        let collides = !self.is_explicit_file(ty, &path)
+            && emitted.get(&path).is_some_and(|prev| prev.as_deref() != name);
+        let path = if collides {
+            let hash = rustc_hash::FxBuildHasher.hash_one(name.unwrap_or(self.stem.as_str()));
+            let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("_");
+            let file_name = match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ext) => format!("{stem}-{:08x}.{ext}", hash as u32),
+                None => format!("{stem}-{:08x}", hash as u32),
+            };
+            match path.parent() {
+                Some(dir) => dir.join(file_name.as_str()),
+                None => PathBuf::from(file_name.as_str()),
+            }
+        } else {
+            path
+        };
+        emitted.insert(path.clone(), name.map(String::from));
+        (path, collides)
+    }
+
+    /// Returns true if `path` is the file the user chose to write outputs of type `ty` to, with
+    /// `--emit <ty>=<path>`, or with `-o` for the final output
+    fn is_explicit_file(&self, ty: OutputType, path: &Path) -> bool {
+        let resolve = |file: &PathBuf| {
+            if file.is_absolute() {
+                file.clone()
+            } else {
+                self.cwd.join(file)
+            }
+        };
+        if let Some(Some(OutputFile::Real(file))) = self.outputs.get(&ty) {
+            return resolve(file) == path;
+        }
+        match self.out_file.as_ref() {
+            Some(OutputFile::Real(file)) if !ty.is_intermediate() => file == path,
+            _ => false,
+        }
+    }
+
+    /// Returns the paths of all files written during this session, in sorted order
+    pub fn all_emitted(&self) -> Vec<PathBuf> {
+        self.emitted.read().keys().cloned().collect()
+    }
+
     /// Constructs a file path for a temporary file of the given output type, with an optional name,
     /// falling back to `self.stem` if no name is provided.
     ///
     /// The file path is always a child of `self.tmp_dir`
     pub fn temp_path(&self, ty: OutputType, name: Option<&str>) -> PathBuf {
-        let name = escape_path_component(name.unwrap_or(self.stem.as_str()));
-        self.tmp_dir.join(name.as_ref()).with_extension(ty.extension())
+        self.tmp_dir.join(self.output_name(name)).with_extension(ty.extension())
+    }
+
+    /// Get the relative path (without extension) for an output called `name`, falling back to
+    /// `self.stem` if no name is provided.
+    fn output_name(&self, name: Option<&str>) -> PathBuf {
+        match name {
+            Some(name) if self.nest_outputs => output_name_to_path(name),
+            name => PathBuf::from(escape_path_component(name.unwrap_or(&self.stem)).as_ref()),
+        }
     }
 
     /// Build a file path which is either:
@@ -448,6 +546,22 @@ impl OutputTypes {
                         }
                     }
                 }
+                // IR dumps are not an output of the compilation as a whole, see [IrDumps]
+                OutputTypeSpec::IrAfter { .. } => continue,
+                // One package is emitted per entrypoint, so the destination must be a directory
+                OutputTypeSpec::MaspPerEntrypoint { path } => {
+                    if matches!(map.get(&OutputType::Masp), Some(Some(_))) && path.is_some() {
+                        return Err(clap::Error::raw(
+                            clap::error::ErrorKind::ValueValidation,
+                            "conflicting --emit options given for output type 'masp'",
+                        ));
+                    }
+                    let path = path.map(|path| match path {
+                        OutputFile::Real(path) => OutputFile::Directory(path),
+                        path => path,
+                    });
+                    map.insert(OutputType::Masp, path);
+                }
                 OutputTypeSpec::Typed { output_type, path } => {
                     if path.is_some() {
                         if matches!(map.get(&output_type), Some(Some(_))) {
@@ -508,14 +622,24 @@ impl OutputTypes {
 
     pub fn should_link(&self) -> bool {
         self.0.keys().any(|k| {
-            matches!(k, OutputType::Hir | OutputType::Masm | OutputType::Mast | OutputType::Masp)
+            matches!(
+                k,
+                OutputType::Hir
+                    | OutputType::Masm
+                    | OutputType::Mast
+                    | OutputType::Masp
+                    | OutputType::CycleReport
+            )
         })
     }
 
     pub fn should_codegen(&self) -> bool {
-        self.0
-            .keys()
-            .any(|k| matches!(k, OutputType::Masm | OutputType::Mast | OutputType::Masp))
+        self.0.keys().any(|k| {
+            matches!(
+                k,
+                OutputType::Masm | OutputType::Mast | OutputType::Masp | OutputType::CycleReport
+            )
+        })
     }
 
     pub fn should_assemble(&self) -> bool {
@@ -541,6 +665,64 @@ pub enum OutputTypeSpec {
         output_type: OutputType,
         path: Option<OutputFile>,
     },
+    /// Dump the IR to numbered files after each application of the named pass, optionally in a
+    /// specific directory, i.e. `--emit=ir-after=PASS[:DIR]`.
+    ///
+    /// The pass name `all` selects every pass.
+    IrAfter {
+        pass: String,
+        dir: Option<PathBuf>,
+    },
+    /// Emit a separate package for each entrypoint given via `--entrypoint`, optionally in a
+    /// specific directory, i.e. `--emit=masp-per-entrypoint[=DIR]`.
+    MaspPerEntrypoint {
+        path: Option<OutputFile>,
+    },
+}
+
+/// The configuration for dumping IR to files after passes are applied, as requested via
+/// `--emit=ir-after=PASS[:DIR]`
+#[derive(Debug, Clone, Default)]
+pub struct IrDumps {
+    /// The names of the passes after which IR should be dumped, `all` selects every pass
+    pub passes: Vec<String>,
+    /// The directory in which to write the IR dumps.
+    ///
+    /// Defaults to `<target-dir>/ir` if unset.
+    pub dir: Option<PathBuf>,
+}
+impl IrDumps {
+    /// Collect the IR dump configuration from the `ir-after` specs in `specs`, if there are any
+    #[cfg(feature = "std")]
+    pub fn from_specs<'a, I>(specs: I) -> Result<Option<Self>, clap::Error>
+    where
+        I: IntoIterator<Item = &'a OutputTypeSpec>,
+    {
+        let mut dumps = None::<Self>;
+        for spec in specs {
+            let OutputTypeSpec::IrAfter { pass, dir } = spec else {
+                continue;
+            };
+            let dumps = dumps.get_or_insert_default();
+            match (dumps.dir.as_ref(), dir) {
+                (Some(prev), Some(dir)) if prev != dir => {
+                    return Err(clap::Error::raw(
+                        clap::error::ErrorKind::ValueValidation,
+                        "conflicting directories given for --emit=ir-after",
+                    ));
+                }
+                (_, Some(dir)) => dumps.dir = Some(dir.clone()),
+                (_, None) => (),
+            }
+            dumps.passes.push(pass.clone());
+        }
+        Ok(dumps)
+    }
+
+    /// Returns true if IR should be dumped after executing a pass named `pass`
+    pub fn should_dump(&self, pass: &str) -> bool {
+        self.passes.iter().any(|p| p == "all" || p == pass)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -576,6 +758,12 @@ impl clap::builder::TypedValueParser for OutputTypeParser {
                 PossibleValue::new("mast").help("Merkelized Abstract Syntax Tree (text)"),
                 PossibleValue::new("masp").help("Miden Assembly Package Format (binary)"),
                 PossibleValue::new("ir").help("WAT + HIR + MASM (text, optional directory)"),
+                PossibleValue::new("masp-per-entrypoint")
+                    .help("One package per entrypoint (binary, optional directory)"),
+                PossibleValue::new("ir-after").help(
+                    "IR after the given pass, or `all`, to numbered files (text, optional \
+                     directory)",
+                ),
                 PossibleValue::new("all").help("All of the above"),
             ]
             .into_iter(),
@@ -592,6 +780,26 @@ impl clap::builder::TypedValueParser for OutputTypeParser {
 
         let output_type = value.to_str().ok_or_else(|| Error::new(ErrorKind::InvalidUtf8))?;
 
+        if output_type == "ir-after" || output_type.starts_with("ir-after=") {
+            let spec = output_type.strip_prefix("ir-after=").unwrap_or_default();
+            let (pass, dir) = match spec.split_once(':') {
+                None => (spec, None),
+                Some((pass, dir)) => (pass, Some(PathBuf::from(dir))),
+            };
+            if pass.is_empty() || dir.as_ref().is_some_and(|dir| dir.as_os_str().is_empty()) {
+                return Err(Error::raw(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "invalid output type: `{output_type}` - expected `ir-after=PASS[:DIR]`"
+                    ),
+                ));
+            }
+            return Ok(OutputTypeSpec::IrAfter {
+                pass: pass.to_owned(),
+                dir,
+            });
+        }
+
         let (shorthand, path) = match output_type.split_once('=') {
             None => (output_type, None),
             Some((shorthand, "-")) => (shorthand, Some(OutputFile::Stdout)),
@@ -606,6 +814,18 @@ impl clap::builder::TypedValueParser for OutputTypeParser {
             };
             return Ok(OutputTypeSpec::All { path });
         }
+        if shorthand == "masp-per-entrypoint" {
+            if matches!(path, Some(OutputFile::Stdout)) {
+                return Err(Error::raw(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "invalid output type: `{shorthand}=-` - expected \
+                         `masp-per-entrypoint[=DIR]`"
+                    ),
+                ));
+            }
+            return Ok(OutputTypeSpec::MaspPerEntrypoint { path });
+        }
         if shorthand == "ir" {
             let path = match path {
                 None => None,
@@ -626,7 +846,7 @@ impl clap::builder::TypedValueParser for OutputTypeParser {
                 ErrorKind::InvalidValue,
                 format!(
                     "invalid output type: `{shorthand}` - expected one of: {display}, `all`, \
-                     `ir[=PATH]`",
+                     `ir[=PATH]`, `ir-after=PASS[:DIR]`, `masp-per-entrypoint[=DIR]`",
                     display = OutputType::shorthand_display(),
                 ),
             )
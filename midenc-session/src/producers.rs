@@ -0,0 +1,140 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use miden_core::serde::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
+use miden_mast_package::{Package, Section, SectionId};
+
+use crate::{MIDENC_BUILD_REV, MIDENC_BUILD_VERSION};
+
+/// The toolchains which produced a package, e.g. the versions of rustc and midenc.
+///
+/// This mirrors the `producers` custom section of WebAssembly, which records, for each field (i.e.
+/// `language`, `processed-by` and `sdk`), the name and version of each tool involved. It is carried
+/// from the Wasm input to the assembled package, in a section of the same name, so that it is
+/// possible to audit how deployed code was built.
+///
+/// The contents are kept sorted, so that the package is the same from one build to the next.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Producers {
+    /// The tools of each field, as a map from tool name to version
+    fields: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Producers {
+    /// The field of the source languages of the input
+    pub const LANGUAGE: &str = "language";
+    /// The field of the tools which processed the input, e.g. rustc, wasm-bindgen and midenc
+    pub const PROCESSED_BY: &str = "processed-by";
+    /// The field of the SDKs used to produce the input
+    pub const SDK: &str = "sdk";
+    /// The name of the package section, as well as of the Wasm custom section, holding producers
+    pub const SECTION_NAME: &str = "producers";
+
+    /// Returns true if no producers were recorded
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Record that version `version` of the tool `name` contributed to `field`, replacing any
+    /// version recorded previously
+    pub fn insert(
+        &mut self,
+        field: impl Into<String>,
+        name: impl Into<String>,
+        version: impl Into<String>,
+    ) {
+        self.fields.entry(field.into()).or_default().insert(name.into(), version.into());
+    }
+
+    /// Returns the version of the tool `name` recorded in `field`, if any
+    pub fn get(&self, field: &str, name: &str) -> Option<&str> {
+        self.fields.get(field)?.get(name).map(String::as_str)
+    }
+
+    /// Returns all the producers recorded, as `(field, name, version)`, sorted by field and name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &str)> + '_ {
+        self.fields.iter().flat_map(|(field, tools)| {
+            tools
+                .iter()
+                .map(move |(name, version)| (field.as_str(), name.as_str(), version.as_str()))
+        })
+    }
+
+    /// Record all the producers of `other`, e.g. those of another core module of a component
+    pub fn extend(&mut self, other: &Self) {
+        for (field, name, version) in other.iter() {
+            self.insert(field, name, version);
+        }
+    }
+
+    /// Record the version of midenc, along with the revision it was built from, as having
+    /// processed the input
+    pub fn insert_midenc(&mut self) {
+        self.insert(
+            Self::PROCESSED_BY,
+            "midenc",
+            format!("{MIDENC_BUILD_VERSION} ({MIDENC_BUILD_REV})"),
+        );
+    }
+
+    /// Serialize these producers, as they are stored in the package section
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Serializable::to_bytes(self)
+    }
+
+    /// Deserialize producers serialized with [Self::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        Self::read_from_bytes(bytes)
+    }
+
+    /// The id of the package section holding producers
+    pub fn section_id() -> SectionId {
+        SectionId::custom(Self::SECTION_NAME).expect("invalid section id")
+    }
+
+    /// Returns the package section holding these producers
+    pub fn to_section(&self) -> Section {
+        Section::new(Self::section_id(), self.to_bytes())
+    }
+
+    /// Read the producers recorded in `package`, if any
+    pub fn from_package(package: &Package) -> Result<Option<Self>, DeserializationError> {
+        let id = Self::section_id();
+        let Some(section) = package.sections.iter().find(|section| section.id == id) else {
+            return Ok(None);
+        };
+        Self::from_bytes(&section.data).map(Some)
+    }
+}
+
+impl Serializable for Producers {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.fields.len());
+        for (field, tools) in self.fields.iter() {
+            field.write_into(target);
+            target.write_usize(tools.len());
+            for (name, version) in tools.iter() {
+                name.write_into(target);
+                version.write_into(target);
+            }
+        }
+    }
+}
+
+impl Deserializable for Producers {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let mut producers = Self::default();
+        let num_fields = source.read_usize()?;
+        for _ in 0..num_fields {
+            let field = String::read_from(source)?;
+            let num_tools = source.read_usize()?;
+            for _ in 0..num_tools {
+                let name = String::read_from(source)?;
+                let version = String::read_from(source)?;
+                producers.insert(field.clone(), name, version);
+            }
+        }
+        Ok(producers)
+    }
+}
@@ -1,7 +1,11 @@
 use std::{
+    borrow::Cow,
+    collections::BTreeMap,
     fmt,
-    sync::atomic::{AtomicU64, Ordering},
+    string::ToString,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
     time::{Duration, Instant},
+    vec::Vec,
 };
 
 use crate::HumanDuration;
@@ -23,6 +27,10 @@ pub struct Statistics {
     opt_time: AtomicU64,
     /// The elapsed time at which codegen started
     codegen_time: AtomicU64,
+    /// Whether the package was reused from the compilation cache, rather than compiled
+    cached_package_reused: AtomicBool,
+    /// The wall time spent in individual compiler stages and passes, see `-Z time-passes`
+    pub timings: Timings,
 }
 impl fmt::Debug for Statistics {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -31,6 +39,7 @@ impl fmt::Debug for Statistics {
             .field("parsing", &self.parse_time())
             .field("optimization", &self.opt_time())
             .field("codegen", &self.codegen_time())
+            .field("cached_package_reused", &self.is_cached_package_reused())
             .finish()
     }
 }
@@ -46,6 +55,8 @@ impl Clone for Statistics {
             parse_time: AtomicU64::new(self.parse_time.load(Ordering::Relaxed)),
             opt_time: AtomicU64::new(self.opt_time.load(Ordering::Relaxed)),
             codegen_time: AtomicU64::new(self.codegen_time.load(Ordering::Relaxed)),
+            cached_package_reused: AtomicBool::new(self.is_cached_package_reused()),
+            timings: self.timings.clone(),
         }
     }
 }
@@ -56,6 +67,8 @@ impl Statistics {
             parse_time: AtomicU64::new(NOT_STARTED),
             opt_time: AtomicU64::new(NOT_STARTED),
             codegen_time: AtomicU64::new(NOT_STARTED),
+            cached_package_reused: AtomicBool::new(false),
+            timings: Timings::default(),
         }
     }
 
@@ -79,6 +92,11 @@ impl Statistics {
         load_duration(&self.codegen_time)
     }
 
+    /// Returns true if the package was reused from the compilation cache, rather than compiled
+    pub fn is_cached_package_reused(&self) -> bool {
+        self.cached_package_reused.load(Ordering::Relaxed)
+    }
+
     /// Record that parsing/loading inputs has completed
     pub fn parsing_completed(&self) {
         store_duration(&self.parse_time, self.elapsed())
@@ -93,6 +111,131 @@ impl Statistics {
     pub fn codegen_completed(&self) {
         store_duration(&self.codegen_time, self.elapsed())
     }
+
+    /// Record that the package was reused from the compilation cache
+    pub fn cached_package_reused(&self) {
+        self.cached_package_reused.store(true, Ordering::Relaxed)
+    }
+
+    /// Get a report of the time spent in each compiler stage and pass, suitable for display
+    pub fn timings_report(&self) -> TimingsReport {
+        TimingsReport {
+            total: self.elapsed(),
+            entries: self.timings.entries(),
+        }
+    }
+}
+
+/// The kind of scope for which a [Timing] was recorded
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimingKind {
+    /// A compiler stage, e.g. parsing or codegen
+    Stage,
+    /// An individual IR pass
+    Pass,
+}
+impl fmt::Display for TimingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stage => f.write_str("stage"),
+            Self::Pass => f.write_str("pass"),
+        }
+    }
+}
+
+/// The accumulated wall time spent in a single scope, e.g. a specific pass
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Timing {
+    /// The number of times the scope was entered
+    pub count: usize,
+    /// The total wall time spent in the scope
+    pub total: Duration,
+}
+
+/// Wall times recorded for compiler stages and passes.
+///
+/// Times are accumulated per scope, so a pass which is run on many operations is reported once,
+/// with the total time spent across all runs.
+#[derive(Debug, Default)]
+pub struct Timings {
+    entries: parking_lot::Mutex<BTreeMap<(TimingKind, Cow<'static, str>), Timing>>,
+}
+impl Clone for Timings {
+    fn clone(&self) -> Self {
+        Self {
+            entries: parking_lot::Mutex::new(self.entries.lock().clone()),
+        }
+    }
+}
+impl Timings {
+    /// Record `duration` as time spent in the scope `name` of the given `kind`
+    pub fn record(&self, kind: TimingKind, name: impl Into<Cow<'static, str>>, duration: Duration) {
+        let mut entries = self.entries.lock();
+        let timing = entries.entry((kind, name.into())).or_default();
+        timing.count += 1;
+        timing.total += duration;
+    }
+
+    /// Returns true if no timings have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+
+    /// Get the timing recorded for the scope `name` of the given `kind`, if any
+    pub fn get(&self, kind: TimingKind, name: &str) -> Option<Timing> {
+        self.entries
+            .lock()
+            .iter()
+            .find_map(|((k, n), timing)| (*k == kind && n == name).then_some(*timing))
+    }
+
+    /// Get all recorded timings, ordered by kind, and then by descending total time
+    pub fn entries(&self) -> Vec<(TimingKind, Cow<'static, str>, Timing)> {
+        let mut entries = self
+            .entries
+            .lock()
+            .iter()
+            .map(|((kind, name), timing)| (*kind, name.clone(), *timing))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.total.cmp(&a.2.total)));
+        entries
+    }
+}
+
+/// A table of the time spent in each compiler stage and pass, as printed by `-Z time-passes`
+pub struct TimingsReport {
+    total: HumanDuration,
+    entries: Vec<(TimingKind, Cow<'static, str>, Timing)>,
+}
+impl fmt::Display for TimingsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total.as_secs_f64();
+        let name_width = self.entries.iter().map(|(_, name, _)| name.len()).max().unwrap_or(0);
+
+        writeln!(f, "===- Compilation time report -===")?;
+        writeln!(f, "  Total: {:#}", self.total)?;
+        let mut current_kind = None;
+        for (kind, name, timing) in self.entries.iter() {
+            if current_kind != Some(*kind) {
+                current_kind = Some(*kind);
+                let kind = kind.to_string();
+                writeln!(f)?;
+                writeln!(
+                    f,
+                    "  {kind:<name_width$}  {:>10}  {:>7}  {:>6}",
+                    "wall time", "%", "count"
+                )?;
+            }
+            let percent = if total > 0.0 {
+                timing.total.as_secs_f64() / total * 100.0
+            } else {
+                0.0
+            };
+            let time = format!("{:#}", HumanDuration::from(timing.total));
+            writeln!(f, "  {name:<name_width$}  {time:>10}  {percent:>6.1}%  {:>6}", timing.count)?;
+        }
+        Ok(())
+    }
 }
 
 fn store_duration(raw_secs_f64: &AtomicU64, duration: HumanDuration) {
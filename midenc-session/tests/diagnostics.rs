@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use midenc_session::{
+    DiagnosticsFormat, LintLevel, Options, Warnings,
+    diagnostics::{
+        CaptureEmitter, DefaultSourceManager, DiagnosticsConfig, DiagnosticsHandler,
+        DiagnosticsSummary, Severity, SourceLanguage, SourceManager, SourceSpan, Uri,
+    },
+};
+
+const SOURCE: &str = "fn foo() {\n    let x: i32 = true;\n}\n";
+
+fn json_handler() -> (DiagnosticsHandler, Arc<CaptureEmitter>, SourceSpan) {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let file = source_manager.load(
+        SourceLanguage::Other("rust"),
+        Uri::new("src/lib.rs"),
+        SOURCE.to_string(),
+    );
+    // The span of `true` on line 2
+    let start = SOURCE.find("true").unwrap() as u32;
+    let span = SourceSpan::new(file.id(), start..(start + 4));
+
+    let emitter = Arc::new(CaptureEmitter::with_format(DiagnosticsFormat::Json));
+    let handler = DiagnosticsHandler::new(Default::default(), source_manager, emitter.clone());
+    (handler, emitter, span)
+}
+
+#[test]
+fn json_diagnostics_include_resolved_labels() {
+    let (handler, emitter, span) = json_handler();
+
+    handler
+        .diagnostic(Severity::Error)
+        .with_message("type mismatch")
+        .with_code("E0308")
+        .with_primary_label(span, "expected `i32`, found `bool`")
+        .with_help("change the type of `x` to `bool`")
+        .emit();
+
+    let captured = emitter.captured();
+    let lines = captured.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "expected exactly one json object per diagnostic: {captured}");
+
+    let json = lines[0];
+    assert!(json.starts_with('{') && json.ends_with('}'), "not a json object: {json}");
+    assert!(json.contains(r#""severity":"error""#), "{json}");
+    assert!(json.contains(r#""code":"E0308""#), "{json}");
+    assert!(json.contains(r#""message":"type mismatch""#), "{json}");
+    assert!(json.contains(r#""label":"expected `i32`, found `bool`""#), "{json}");
+    assert!(json.contains(r#""primary":true"#), "{json}");
+    let offset = SOURCE.find("true").unwrap();
+    assert!(json.contains(&format!(r#""offset":{offset},"length":4"#)), "{json}");
+    assert!(json.contains(r#""line":2,"column":18"#), "{json}");
+    assert!(json.contains(r#""help":"change the type of `x` to `bool`""#), "{json}");
+    assert!(handler.has_errors());
+}
+
+#[test]
+fn json_diagnostics_escape_strings() {
+    let (handler, emitter, _span) = json_handler();
+
+    handler.warn("unused variable `\"x\"`\nconsider removing it");
+
+    let captured = emitter.captured();
+    assert_eq!(captured.lines().count(), 1, "{captured}");
+    assert!(captured.contains(r#""severity":"warning""#), "{captured}");
+    assert!(
+        captured.contains(r#""message":"unused variable `\"x\"`\nconsider removing it""#),
+        "{captured}"
+    );
+    assert!(captured.contains(r#""labels":[]"#), "{captured}");
+}
+
+#[test]
+fn default_emitter_respects_diagnostics_format() {
+    let options = Box::new(Options::default());
+    assert_eq!(options.default_emitter().format(), DiagnosticsFormat::Human);
+
+    let options = options.with_diagnostics_format(DiagnosticsFormat::Json);
+    assert_eq!(options.default_emitter().format(), DiagnosticsFormat::Json);
+}
+
+fn lint_handler(options: Box<Options>) -> DiagnosticsHandler {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let emitter = Arc::new(CaptureEmitter::new());
+    DiagnosticsHandler::new(options.diagnostics.clone(), source_manager, emitter)
+}
+
+fn emit_lint(handler: &DiagnosticsHandler, code: &str) {
+    handler
+        .diagnostic(Severity::Warning)
+        .with_message("this is a lint")
+        .with_code(code)
+        .emit();
+}
+
+#[test]
+fn warnings_only_succeed_by_default() {
+    let handler = lint_handler(Box::new(Options::default()));
+
+    handler.warn("unused variable `x`");
+    emit_lint(&handler, "unused_import");
+
+    assert!(!handler.has_errors());
+    assert_eq!(handler.warnings_as_errors_count(), 0);
+    assert_eq!(handler.error_summary(), None);
+}
+
+#[test]
+fn deny_warnings_promotes_warnings_to_errors() {
+    let options = Box::new(Options::default()).with_lint_level("warnings", LintLevel::Deny);
+    assert_eq!(options.diagnostics.warnings, Warnings::Error);
+    let handler = lint_handler(options);
+
+    handler.warn("unused variable `x`");
+    emit_lint(&handler, "unused_import");
+    handler.error("type mismatch");
+
+    assert!(handler.has_errors());
+    assert_eq!(handler.error_count(), 3);
+    assert_eq!(handler.warnings_as_errors_count(), 2);
+    assert_eq!(
+        handler.error_summary().as_deref(),
+        Some("aborting due to 3 previous errors (2 warnings treated as errors)")
+    );
+}
+
+#[test]
+fn lint_levels_apply_per_diagnostic_code() {
+    let options = Box::new(Options::default())
+        .with_lint_level("unused_import", LintLevel::Deny)
+        .with_lint_level("dead_code", LintLevel::Allow);
+    let handler = lint_handler(options);
+
+    emit_lint(&handler, "dead_code");
+    emit_lint(&handler, "unreachable");
+    assert!(!handler.has_errors());
+
+    emit_lint(&handler, "unused_import");
+    assert_eq!(handler.error_count(), 1);
+    assert_eq!(handler.warnings_as_errors_count(), 1);
+    assert_eq!(
+        handler.error_summary().as_deref(),
+        Some("aborting due to 1 previous error (1 warning treated as error)")
+    );
+}
+
+#[test]
+fn allowed_lints_are_silenced_even_when_denying_warnings() {
+    let options = Box::new(Options::default())
+        .with_lint_level("warnings", LintLevel::Deny)
+        .with_lint_level("dead_code", LintLevel::Allow);
+    let handler = lint_handler(options);
+
+    emit_lint(&handler, "dead_code");
+    assert!(!handler.has_errors());
+
+    emit_lint(&handler, "unreachable");
+    assert_eq!(handler.warnings_as_errors_count(), 1);
+}
+
+/// Construct a handler which renders diagnostics as JSON, one per line, to simplify counting them
+fn limited_handler(error_limit: Option<usize>) -> (DiagnosticsHandler, Arc<CaptureEmitter>) {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let emitter = Arc::new(CaptureEmitter::with_format(DiagnosticsFormat::Json));
+    let options = Box::new(Options::default())
+        .with_diagnostics_format(DiagnosticsFormat::Json)
+        .with_error_limit(error_limit);
+    let handler =
+        DiagnosticsHandler::new(options.diagnostics.clone(), source_manager, emitter.clone());
+    (handler, emitter)
+}
+
+#[test]
+fn errors_beyond_the_limit_are_counted_but_not_shown() {
+    let (handler, emitter) = limited_handler(Some(3));
+
+    handler.warn("unused variable `x`");
+    for i in 0..10 {
+        handler.error(format!("error #{i}"));
+    }
+    handler.warn("unused variable `y`");
+
+    let captured = emitter.captured();
+    let rendered_errors = captured.lines().filter(|line| line.contains(r#""severity":"error""#));
+    assert_eq!(rendered_errors.count(), 3, "{captured}");
+    assert!(!captured.contains("error #3"), "{captured}");
+    // Warnings are still rendered after the limit is reached
+    assert_eq!(captured.lines().count(), 5, "{captured}");
+
+    assert!(handler.error_limit_reached());
+    assert_eq!(
+        handler.summary(),
+        DiagnosticsSummary {
+            errors: 10,
+            warnings: 2,
+            warnings_as_errors: 0,
+            errors_not_shown: 7,
+        }
+    );
+    assert_eq!(handler.summary().to_string(), "10 errors, 2 warnings emitted");
+    assert_eq!(
+        handler.error_summary().as_deref(),
+        Some("aborting due to 10 previous errors; 7 not shown; pass --error-limit 0 to see all")
+    );
+}
+
+#[test]
+fn all_errors_are_shown_without_a_limit() {
+    let (handler, emitter) = limited_handler(None);
+
+    for i in 0..(DiagnosticsConfig::DEFAULT_ERROR_LIMIT * 2) {
+        handler.error(format!("error #{i}"));
+    }
+
+    assert_eq!(emitter.captured().lines().count(), DiagnosticsConfig::DEFAULT_ERROR_LIMIT * 2);
+    assert!(!handler.error_limit_reached());
+    assert_eq!(handler.summary().errors_not_shown, 0);
+    assert_eq!(handler.error_summary().as_deref(), Some("aborting due to 40 previous errors"));
+}
+
+#[test]
+fn error_limit_is_enabled_by_default() {
+    let options = Box::new(Options::default());
+    assert_eq!(options.diagnostics.error_limit, Some(DiagnosticsConfig::DEFAULT_ERROR_LIMIT));
+
+    let handler = lint_handler(options);
+    handler.warn("unused variable `x`");
+    assert_eq!(handler.summary().to_string(), "1 warning emitted");
+    assert_eq!(handler.error_summary(), None);
+}
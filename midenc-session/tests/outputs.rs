@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use midenc_hir_symbol::Symbol;
+use midenc_session::{
+    Emit, InputFile, Options, OutputFile, OutputMode, OutputType, Session, Writer,
+    diagnostics::{DefaultSourceManager, SourceManager},
+};
+
+/// A stand-in for a Miden Assembly module with the given fully-qualified path
+struct MasmModule {
+    path: &'static str,
+}
+
+impl Emit for MasmModule {
+    fn name(&self) -> Option<Symbol> {
+        Some(Symbol::intern(self.path))
+    }
+
+    fn output_type(&self, _mode: OutputMode) -> OutputType {
+        OutputType::Masm
+    }
+
+    fn write_to<W: Writer>(
+        &self,
+        mut writer: W,
+        _mode: OutputMode,
+        _session: &Session,
+    ) -> anyhow::Result<()> {
+        writer.write_fmt(format_args!("# {}\n", self.path))
+    }
+}
+
+fn session(output_dir: &std::path::Path, nest_outputs: bool) -> Session {
+    session_with_masm_output(
+        output_dir,
+        nest_outputs,
+        OutputFile::Directory(output_dir.join("masm")),
+    )
+}
+
+fn session_with_masm_output(
+    output_dir: &std::path::Path,
+    nest_outputs: bool,
+    masm_output: OutputFile,
+) -> Session {
+    let cwd = std::env::current_dir().unwrap();
+    let target_dir = output_dir.join("target");
+    let mut options = Box::new(Options::new(None, None, cwd, target_dir, None, None));
+    options.nest_outputs = nest_outputs;
+    let source_manager: Arc<dyn SourceManager + Send + Sync> =
+        Arc::new(DefaultSourceManager::default());
+    Session::new(InputFile::empty(), options, None, source_manager)
+        .unwrap()
+        .with_output_type(OutputType::Masm, Some(masm_output))
+}
+
+#[test]
+fn namespaced_outputs_are_flattened_by_default() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = session(temp.path(), false);
+
+    session.emit(OutputMode::Text, &MasmModule { path: "ns_a::util" }).unwrap();
+    session.emit(OutputMode::Text, &MasmModule { path: "ns_b::util" }).unwrap();
+
+    let masm_dir = temp.path().join("masm");
+    let a = masm_dir.join("ns_a__util.masm");
+    let b = masm_dir.join("ns_b__util.masm");
+    assert_eq!(std::fs::read_to_string(&a).unwrap(), "# ns_a::util\n");
+    assert_eq!(std::fs::read_to_string(&b).unwrap(), "# ns_b::util\n");
+    assert_eq!(session.output_files.all_emitted(), vec![a, b]);
+}
+
+#[test]
+fn namespaced_outputs_are_written_to_subdirectories() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = session(temp.path(), true);
+
+    session.emit(OutputMode::Text, &MasmModule { path: "ns_a::util" }).unwrap();
+    session.emit(OutputMode::Text, &MasmModule { path: "ns_b::util" }).unwrap();
+
+    let masm_dir = temp.path().join("masm");
+    let a = masm_dir.join("ns_a").join("util.masm");
+    let b = masm_dir.join("ns_b").join("util.masm");
+    assert_eq!(std::fs::read_to_string(&a).unwrap(), "# ns_a::util\n");
+    assert_eq!(std::fs::read_to_string(&b).unwrap(), "# ns_b::util\n");
+    assert_eq!(session.output_files.all_emitted(), vec![a, b]);
+}
+
+#[test]
+fn colliding_outputs_are_disambiguated() {
+    let temp = tempfile::tempdir().unwrap();
+    let session = session(temp.path(), true);
+    let path = temp.path().join("masm").join("util.masm");
+
+    let (first, collided) =
+        session.output_files.reserve(OutputType::Masm, path.clone(), Some("util"));
+    assert_eq!(first, path);
+    assert!(!collided);
+
+    // Emitting the same item again reuses its file
+    let (again, collided) =
+        session.output_files.reserve(OutputType::Masm, path.clone(), Some("util"));
+    assert_eq!(again, path);
+    assert!(!collided);
+
+    let (second, collided) =
+        session.output_files.reserve(OutputType::Masm, path.clone(), Some("other/util"));
+    assert!(collided);
+    assert_ne!(second, path);
+    assert_eq!(second.parent(), path.parent());
+    assert_eq!(second.extension(), path.extension());
+    assert!(second.file_stem().unwrap().to_str().unwrap().starts_with("util-"));
+    assert_eq!(session.output_files.all_emitted().len(), 2);
+}
+
+#[test]
+fn explicit_output_files_are_written_as_given() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("module.txt");
+    let session = session_with_masm_output(temp.path(), true, OutputFile::Real(path.clone()));
+
+    session.emit(OutputMode::Text, &MasmModule { path: "ns_a::util" }).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "# ns_a::util\n");
+    assert_eq!(session.output_files.all_emitted(), vec![path]);
+}
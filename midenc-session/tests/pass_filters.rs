@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use midenc_session::{
+    InputFile, Options, PassFilter, Session,
+    diagnostics::{DefaultSourceManager, SourceManager},
+};
+
+fn filter(pattern: &str) -> PassFilter {
+    pattern
+        .parse()
+        .unwrap_or_else(|err| panic!("failed to parse '{pattern}': {err}"))
+}
+
+fn session(configure: impl FnOnce(&mut Options)) -> Session {
+    let cwd = std::env::current_dir().unwrap();
+    let target_dir = std::env::temp_dir().join("midenc-session-pass-filters");
+    let mut options = Box::new(Options::new(None, None, cwd, target_dir, None, None));
+    configure(&mut options);
+    let source_manager: Arc<dyn SourceManager + Send + Sync> =
+        Arc::new(DefaultSourceManager::default());
+    Session::new(InputFile::empty(), options, None, source_manager).unwrap()
+}
+
+#[test]
+fn exact_pass_filter() {
+    let exact = filter("canonicalize");
+    assert!(matches!(exact, PassFilter::Exact(_)));
+    assert!(exact.matches("canonicalize"));
+    assert!(!exact.matches("canonicalizer"));
+    assert!(!exact.matches("canon"));
+    assert_eq!(exact.to_string(), "canonicalize");
+}
+
+#[test]
+fn glob_pass_filter() {
+    let glob = filter("lift-*");
+    assert!(matches!(glob, PassFilter::Glob(_)));
+    assert!(glob.matches("lift-control-flow"));
+    assert!(glob.matches("lift-"));
+    assert!(!glob.matches("lift"));
+    assert!(!glob.matches("uplift-control-flow"));
+
+    let glob = filter("*-to-s?f");
+    assert!(glob.matches("lift-cf-to-scf"));
+    assert!(!glob.matches("lift-cf-to-scf2"));
+
+    let glob = filter("*cse*");
+    assert!(glob.matches("cse"));
+    assert!(glob.matches("common-cse-pass"));
+    assert!(!glob.matches("sccp"));
+}
+
+#[test]
+fn regex_pass_filter() {
+    let regex = filter("/scf.*/");
+    assert!(matches!(regex, PassFilter::Regex(_)));
+    assert!(regex.matches("scf-lowering"));
+    assert!(regex.matches("scf"));
+    // Regular expressions must match the entire pass name
+    assert!(!regex.matches("lift-cf-to-scf"));
+    assert_eq!(regex.to_string(), "/scf.*/");
+
+    let regex = filter("/(cse|sccp)/");
+    assert!(regex.matches("cse"));
+    assert!(regex.matches("sccp"));
+    assert!(!regex.matches("canonicalize"));
+}
+
+#[test]
+fn invalid_pass_filters_are_rejected() {
+    let err = "/scf(/".parse::<PassFilter>().unwrap_err();
+    assert!(err.contains("invalid pass filter `/scf(/`"), "{err}");
+    assert!("//".parse::<PassFilter>().is_err());
+    assert!("".parse::<PassFilter>().is_err());
+}
+
+#[test]
+fn should_print_ir_matches_pass_filters() {
+    let session = session(|options| {
+        options.print_ir_after_pass = vec![filter("cse"), filter("lift-*"), filter("/scf.*/")];
+        options.print_cfg_after_pass = vec![filter("sink-*")];
+    });
+
+    assert!(session.should_print_ir("cse"));
+    assert!(session.should_print_ir("lift-control-flow"));
+    assert!(session.should_print_ir("scf-to-hir"));
+    assert!(!session.should_print_ir("canonicalize"));
+
+    assert!(session.should_print_cfg("sink-operand-defs"));
+    assert!(!session.should_print_cfg("cse"));
+}
+
+#[test]
+fn print_ir_after_all_selects_every_pass() {
+    let session = session(|options| {
+        options.print_ir_after_all = true;
+        options.print_ir_after_pass = vec![filter("cse")];
+    });
+
+    assert!(session.should_print_ir("cse"));
+    assert!(session.should_print_ir("canonicalize"));
+    assert!(!session.should_print_cfg("canonicalize"));
+}
@@ -10,6 +10,15 @@ pub fn main() -> Result<(), Report> {
         human_panic::setup_panic!();
     }
 
+    // Include the recent log records, which may have been filtered from the output, when reporting
+    // an internal compiler error
+    let panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        panic_hook(info);
+        eprintln!("note: the most recent log records were:");
+        let _ = midenc_log::dump_recent(&mut std::io::stderr());
+    }));
+
     // Initialize logger, but do not install it, leave that up to the command handler
     let mut builder = midenc_log::Builder::from_env("MIDENC_TRACE");
     builder.format_indent(Some(2));
@@ -106,10 +106,11 @@ impl AccountComponentMetadataBuilder {
                     .first()
                     .map(word_schema_from_storage_type_arg)
                     .unwrap_or_else(|| WordSchema::new_simple(SchemaType::native_word()));
+                // `StorageMap` defaults to `Word` keys and `Felt` values
                 let value_schema = args
                     .get(1)
                     .map(word_schema_from_storage_type_arg)
-                    .unwrap_or_else(|| WordSchema::new_simple(SchemaType::native_word()));
+                    .unwrap_or_else(|| WordSchema::new_simple(SchemaType::native_felt()));
                 let slot_schema = StorageSlotSchema::Map(MapSlotSchema::new(
                     description,
                     None,
@@ -138,11 +139,35 @@ impl AccountComponentMetadataBuilder {
                     StorageSlotSchema::Value(ValueSlotSchema::new(description, word_schema));
                 self.storage.push((slot_name, slot_schema));
             }
+            StorageFieldType::MultiSlotValue => {
+                unreachable!("multi-slot values are added with `add_multi_slot_value_entries`")
+            }
         }
 
         Ok(())
     }
 
+    /// Adds one storage-schema entry for each of the slots a `MultiSlotValue` field spans.
+    ///
+    /// Each slot holds one word of the value's felt representation, so slots are described as
+    /// plain words, and their descriptions are suffixed with the position of their word.
+    pub fn add_multi_slot_value_entries(
+        &mut self,
+        slot_names: Vec<StorageSlotName>,
+        description: Option<String>,
+    ) {
+        let slot_count = slot_names.len();
+        for (index, slot_name) in slot_names.into_iter().enumerate() {
+            let description = description
+                .as_ref()
+                .map(|description| format!("{description} (word {} of {slot_count})", index + 1));
+            let word_schema = WordSchema::new_simple(SchemaType::native_word());
+            let slot_schema =
+                StorageSlotSchema::Value(ValueSlotSchema::new(description, word_schema));
+            self.storage.push((slot_name, slot_schema));
+        }
+    }
+
     /// Builds the final [`AccountComponentMetadata`].
     pub fn build(self, span: Span) -> Result<AccountComponentMetadata, syn::Error> {
         let storage_schema = StorageSchema::new(self.storage).map_err(|err| {
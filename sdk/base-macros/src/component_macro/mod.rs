@@ -27,7 +27,7 @@ use crate::{
     types::{
         ExportedTypeDef, ExportedTypeKind, TypeRef, map_type_to_type_ref, registered_export_types,
     },
-    util::generate_frontend_link_section,
+    util::{expanded_wit_env_dependency, generate_frontend_link_section, write_expanded_wit},
 };
 
 mod generate_wit;
@@ -589,6 +589,7 @@ fn expand_component_trait_impl(
         exported_types: &exported_types,
     })?;
     write_component_wit_file(call_site_span, &public_wit_source, &package_name)?;
+    write_expanded_wit(&inline_wit_source, &world_name)?;
     let inline_literal = Literal::string(&inline_wit_source);
 
     let interface_path =
@@ -615,9 +616,11 @@ fn expand_component_trait_impl(
 
     let marker_check = render_trait_marker_check(&component_type, &trait_path);
     let storage_marker_check = render_storage_marker_check(&component_type);
+    let expanded_wit_env = expanded_wit_env_dependency();
 
     Ok(quote! {
         ::miden::generate!(inline = #inline_literal, with = { #(#custom_with_entries)* });
+        #expanded_wit_env
         // Bring account traits into scope so users can call `self.add_asset()`, etc.
         #[allow(unused_imports)]
         use ::miden::native_account::NativeAccount as _;
@@ -53,6 +53,19 @@ fn derive_storage_slot_name(
     format!("{namespace}::{interface_component}::{field_component}")
 }
 
+/// Derives the slot names of a field stored across `slot_count` slots.
+///
+/// The first slot keeps the slot name of the field, and the `i`-th following slot appends `_{i}`
+/// to it, so the names of a field's slots only depend on the field's name and slot count.
+fn derive_multi_slot_names(slot_name: &str, slot_count: usize) -> Vec<String> {
+    (0..slot_count)
+        .map(|index| match index {
+            0 => slot_name.to_string(),
+            _ => format!("{slot_name}_{index}"),
+        })
+        .collect()
+}
+
 /// Parsed arguments collected from a `#[storage(...)]` attribute.
 struct StorageAttributeArgs {
     description: Option<String>,
@@ -128,10 +141,13 @@ pub fn process_storage_fields(
     let mut slot_ids = HashMap::<(u64, u64), String>::new();
 
     for field in fields.named.iter_mut() {
-        if let Err(err) = typecheck_storage_field(field) {
-            errors.push(err);
-            continue;
-        }
+        let field_type = match typecheck_storage_field(field) {
+            Ok(field_type) => field_type,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
         let field_name = field.ident.as_ref().expect("Named field must have an identifier");
         let field_name_str = field_name.to_string();
         let mut storage_args = None;
@@ -156,53 +172,85 @@ pub fn process_storage_fields(
         }
 
         if let Some(args) = storage_args {
+            let slot_count = match field_type {
+                StorageFieldType::MultiSlotValue => match multi_slot_count(field) {
+                    Ok(slot_count) => slot_count,
+                    Err(err) => {
+                        errors.push(err);
+                        continue;
+                    }
+                },
+                StorageFieldType::StorageMap | StorageFieldType::StorageValue => 1,
+            };
+
             // `StorageSlotId` values are derived from slot names, so keep this format stable.
-            let slot_name_str =
+            let field_slot_name =
                 derive_storage_slot_name(storage_namespace, component_interface, &field_name_str);
-            if let Some(existing_field) = slot_names.get(&slot_name_str) {
-                errors.push(syn::Error::new(
-                    field.span(),
-                    format!(
-                        "storage slot name '{slot_name_str}' for field '{field_name_str}' \
-                         conflicts with field '{existing_field}'"
-                    ),
-                ));
-                continue;
-            }
+            let mut field_slots = Vec::with_capacity(slot_count);
+            for slot_name_str in derive_multi_slot_names(&field_slot_name, slot_count) {
+                if let Some(existing_field) = slot_names.get(&slot_name_str) {
+                    errors.push(syn::Error::new(
+                        field.span(),
+                        format!(
+                            "storage slot name '{slot_name_str}' for field '{field_name_str}' \
+                             conflicts with field '{existing_field}'"
+                        ),
+                    ));
+                    break;
+                }
 
-            let slot_name = miden_protocol::account::StorageSlotName::new(slot_name_str.clone())
+                let slot_name = miden_protocol::account::StorageSlotName::new(
+                    slot_name_str.clone(),
+                )
                 .map_err(|err| {
                     syn::Error::new(
                         field.span(),
                         format!("failed to construct storage slot name: {err}"),
                     )
                 })?;
-            let slot_id = slot_name.id();
-            let slot_id_key =
-                (slot_id.suffix().as_canonical_u64(), slot_id.prefix().as_canonical_u64());
-            if let Some(existing_field) = slot_ids.get(&slot_id_key) {
-                errors.push(syn::Error::new(
-                    field.span(),
-                    format!(
-                        "storage slot id for field '{field_name_str}' conflicts with field \
-                         '{existing_field}'"
-                    ),
-                ));
-                continue;
+                let slot_id = slot_name.id();
+                let slot_id_key =
+                    (slot_id.suffix().as_canonical_u64(), slot_id.prefix().as_canonical_u64());
+                if let Some(existing_field) = slot_ids.get(&slot_id_key) {
+                    errors.push(syn::Error::new(
+                        field.span(),
+                        format!(
+                            "storage slot id for field '{field_name_str}' conflicts with field \
+                             '{existing_field}'"
+                        ),
+                    ));
+                    break;
+                }
+                slot_names.insert(slot_name_str, field_name_str.clone());
+                slot_ids.insert(slot_id_key, field_name_str.clone());
+                field_slots.push(slot_name);
             }
-            slot_names.insert(slot_name_str, field_name_str.clone());
-            slot_ids.insert(slot_id_key, field_name_str);
-
-            if let Err(err) = builder.add_storage_entry(
-                slot_name.clone(),
-                args.description,
-                field,
-                args.type_attr,
-            ) {
-                errors.push(err);
+            if field_slots.len() != slot_count {
+                continue;
             }
 
-            field_infos.push((field_name.clone(), slot_id));
+            let field_slot_ids =
+                field_slots.iter().map(|slot_name| slot_name.id()).collect::<Vec<_>>();
+            if matches!(field_type, StorageFieldType::MultiSlotValue) {
+                if args.type_attr.is_some() {
+                    errors.push(syn::Error::new(
+                        field.span(),
+                        "`type` is not supported on `MultiSlotValue` fields, whose slots hold \
+                         words of the value's felt representation",
+                    ));
+                    continue;
+                }
+                builder.add_multi_slot_value_entries(field_slots, args.description);
+                field_infos.push((field_name.clone(), field_slot_ids, true));
+            } else {
+                let slot_name = field_slots.pop().expect("single-slot fields have one slot");
+                if let Err(err) =
+                    builder.add_storage_entry(slot_name, args.description, field, args.type_attr)
+                {
+                    errors.push(err);
+                }
+                field_infos.push((field_name.clone(), field_slot_ids, false));
+            }
         } else {
             errors
                 .push(syn::Error::new(field.span(), "field is missing the `#[storage]` attribute"));
@@ -214,18 +262,60 @@ pub fn process_storage_fields(
     }
 
     let mut field_inits = Vec::with_capacity(field_infos.len());
-    for (field_name, slot_id) in field_infos.into_iter() {
-        let slot = slot_id_tokens(slot_id);
+    for (field_name, slot_ids, is_multi_slot) in field_infos.into_iter() {
+        let mut slots = slot_ids.into_iter().map(slot_id_tokens);
+        let init = if is_multi_slot {
+            quote! { [#(#slots),*] }
+        } else {
+            slots.next().expect("single-slot fields have one slot")
+        };
         field_inits.push(quote! {
-            #field_name: ::core::convert::From::from(#slot)
+            #field_name: ::core::convert::From::from(#init)
         });
     }
 
     Ok(field_inits)
 }
 
-/// Checks that the type of `field` is either `StorageMap` or `StorageValue` from the `miden`
-/// crate.
+/// Returns the number of slots `N` of a `MultiSlotValue<T, N>` field.
+///
+/// As types and constants are not resolved during macro expansion, `N` must be an integer literal.
+fn multi_slot_count(field: &Field) -> Result<usize, syn::Error> {
+    let error = || {
+        syn::Error::new(
+            field.ty.span(),
+            "the number of slots of a `MultiSlotValue` must be an integer literal, e.g. \
+             `MultiSlotValue<T, 3>`",
+        )
+    };
+    let Type::Path(type_path) = &field.ty else {
+        return Err(error());
+    };
+    let Some(syn::PathArguments::AngleBracketed(args)) =
+        type_path.path.segments.last().map(|segment| &segment.arguments)
+    else {
+        return Err(error());
+    };
+    let slot_count = args
+        .args
+        .iter()
+        .find_map(|arg| match arg {
+            syn::GenericArgument::Const(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(slot_count),
+                ..
+            })) => Some(slot_count),
+            _ => None,
+        })
+        .ok_or_else(error)?
+        .base10_parse::<usize>()?;
+    if slot_count == 0 {
+        return Err(syn::Error::new(field.ty.span(), "a `MultiSlotValue` needs at least one slot"));
+    }
+    Ok(slot_count)
+}
+
+/// Checks that the type of `field` is either `StorageMap`, `StorageValue` or `MultiSlotValue` from
+/// the `miden` crate.
 ///
 /// # Limitations
 ///
@@ -233,7 +323,7 @@ pub fn process_storage_fields(
 /// written in the struct correspond to one of the expected values. Hence the following cannot
 /// be detected:
 ///
-/// * A developer defines their own `StorageMap` or `StorageValue`
+/// * A developer defines their own `StorageMap`, `StorageValue` or `MultiSlotValue`
 /// * A developer uses a valid type from miden but aliases it
 pub(crate) fn typecheck_storage_field(field: &Field) -> Result<StorageFieldType, syn::Error> {
     let type_path = match &field.ty {
@@ -253,17 +343,22 @@ pub(crate) fn typecheck_storage_field(field: &Field) -> Result<StorageFieldType,
     const BASE_CRATE: &str = "miden";
     const TYPENAME_MAP: &str = "StorageMap";
     const TYPENAME_VALUE: &str = "StorageValue";
+    const TYPENAME_MULTI_SLOT_VALUE: &str = "MultiSlotValue";
 
     match segments.as_slice() {
         [a] if a == TYPENAME_MAP => Ok(StorageFieldType::StorageMap),
         [a] if a == TYPENAME_VALUE => Ok(StorageFieldType::StorageValue),
         [a, b] if a == BASE_CRATE && b == TYPENAME_MAP => Ok(StorageFieldType::StorageMap),
         [a, b] if a == BASE_CRATE && b == TYPENAME_VALUE => Ok(StorageFieldType::StorageValue),
+        [a] if a == TYPENAME_MULTI_SLOT_VALUE => Ok(StorageFieldType::MultiSlotValue),
+        [a, b] if a == BASE_CRATE && b == TYPENAME_MULTI_SLOT_VALUE => {
+            Ok(StorageFieldType::MultiSlotValue)
+        }
         _ => Err(syn::Error::new(
             field.span(),
             format!(
-                "storage field type can only be `{TYPENAME_MAP}` or `{TYPENAME_VALUE}` from \
-                 `{BASE_CRATE}` crate"
+                "storage field type can only be `{TYPENAME_MAP}`, `{TYPENAME_VALUE}` or \
+                 `{TYPENAME_MULTI_SLOT_VALUE}` from `{BASE_CRATE}` crate"
             ),
         )),
     }
@@ -271,7 +366,7 @@ pub(crate) fn typecheck_storage_field(field: &Field) -> Result<StorageFieldType,
 
 #[cfg(test)]
 mod tests {
-    use super::derive_storage_slot_name;
+    use super::{derive_multi_slot_names, derive_storage_slot_name};
 
     #[test]
     fn derives_slot_name_from_component_package_interface_and_field() {
@@ -292,4 +387,16 @@ mod tests {
             "miden_counter_contract::counter_contract::count_map"
         );
     }
+
+    #[test]
+    fn derives_multi_slot_names_from_field_slot_name() {
+        assert_eq!(
+            derive_multi_slot_names("miden_vesting::vesting::schedule", 3),
+            [
+                "miden_vesting::vesting::schedule",
+                "miden_vesting::vesting::schedule_1",
+                "miden_vesting::vesting::schedule_2",
+            ]
+        );
+    }
 }
@@ -375,8 +375,7 @@ fn render_resolved_inline_wit(
 }
 
 /// Converts a package or world name into a portable filename component.
-#[cfg(any(test, feature = "internal-wit-emit"))]
-fn sanitize_wit_filename_component(name: &str) -> String {
+pub(crate) fn sanitize_wit_filename_component(name: &str) -> String {
     let mut out = String::with_capacity(name.len());
     for ch in name.chars() {
         match ch {
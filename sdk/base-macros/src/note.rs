@@ -9,7 +9,7 @@ use syn::{
 
 use crate::{
     boilerplate::runtime_boilerplate,
-    util::generate_frontend_link_section,
+    util::{expanded_wit_env_dependency, generate_frontend_link_section, write_expanded_wit},
     wit_builder::WitBuilder,
     wit_world::{ManifestPackage, write_world_block},
 };
@@ -279,6 +279,9 @@ fn expand_note_impl(item_impl: ItemImpl) -> TokenStream2 {
         &export_name,
         &dependency_imports,
     );
+    if let Err(err) = write_expanded_wit(&inline_wit, &world_name) {
+        return err.into_compile_error();
+    }
     let inline_literal = Literal::string(&inline_wit);
     let guest_trait_path = match build_guest_trait_path(&component_package, &interface_module) {
         Ok(path) => path,
@@ -287,12 +290,14 @@ fn expand_note_impl(item_impl: ItemImpl) -> TokenStream2 {
     let runtime_boilerplate = runtime_boilerplate();
     let frontend_metadata = note_script_frontend_metadata(&note_ty, entrypoint_ident, &export_name);
     let frontend_link_section = generate_frontend_link_section(&frontend_metadata);
+    let expanded_wit_env = expanded_wit_env_dependency();
 
     quote! {
         #runtime_boilerplate
         #item_impl
 
         ::miden::generate!(inline = #inline_literal);
+        #expanded_wit_env
         self::bindings::export!(#guest_struct_ident);
 
         // Bring ActiveAccount trait into scope so users can call account.get_id(), etc.
@@ -4,6 +4,7 @@ use syn::{FnArg, ItemFn, Pat, PatIdent, parse_macro_input, spanned::Spanned};
 
 use crate::{
     boilerplate::runtime_boilerplate,
+    util::{expanded_wit_env_dependency, write_expanded_wit},
     wit_builder::WitBuilder,
     wit_world::{ManifestPackage, write_world_block},
 };
@@ -47,6 +48,7 @@ pub(crate) fn expand_guest_wrapper(
     })?;
 
     let runtime_boilerplate = runtime_boilerplate();
+    let expanded_wit_env = expanded_wit_env_dependency();
     let guest_struct_ident = config.guest_struct_ident;
     let doc = config.guest_struct_doc;
 
@@ -56,6 +58,7 @@ pub(crate) fn expand_guest_wrapper(
         #user_items
 
         ::miden::generate!(inline = #inline_literal);
+        #expanded_wit_env
         self::bindings::export!(#guest_struct_ident);
 
         #trait_impls
@@ -284,7 +287,9 @@ pub(crate) fn build_script_wit(
     let mut wit = WitBuilder::new("#[tx_script]", &component_package, component_version);
     write_world_block(&mut wit, &world_name, &imports, &exports);
 
-    Ok(wit.finish())
+    let wit = wit.finish();
+    write_expanded_wit(&wit, &world_name)?;
+    Ok(wit)
 }
 
 #[cfg(test)]
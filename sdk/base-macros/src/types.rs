@@ -70,6 +70,7 @@ pub(crate) struct ExportedTypeDef {
 pub(crate) enum StorageFieldType {
     StorageMap,
     StorageValue,
+    MultiSlotValue,
 }
 
 pub(crate) fn register_export_type(def: ExportedTypeDef, _span: Span) -> Result<(), syn::Error> {
@@ -5,6 +5,8 @@ use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
 use syn::Error;
 
+use crate::generate::sanitize_wit_filename_component;
+
 /// Folder within a project that holds bundled WIT files
 const BUNDLED_WIT_DEPS_DIR: &str = "bundled-miden-wit";
 
@@ -15,6 +17,10 @@ const FRONTEND_METADATA_BYTES_STATIC_IDENT: &str = "__miden_frontend_metadata_by
 /// Linker symbol used to reject multiple frontend-marked procedures in one project.
 pub(crate) const FRONTEND_METADATA_UNIQUENESS_GUARD_SYMBOL: &str =
     "__MIDEN_FRONTEND_METADATA_UNIQUENESS_GUARD";
+/// Environment variable naming the directory the WIT worlds passed to `generate!` are written to.
+///
+/// Set by `cargo miden expand`, which must agree on the name.
+pub(crate) const EXPAND_WIT_DIR_ENV: &str = "MIDENC_EXPAND_WIT_DIR";
 
 fn target_folder() -> PathBuf {
     let mut manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set");
@@ -52,6 +58,45 @@ pub fn generated_wit_folder() -> Result<PathBuf, Error> {
     Ok(wit_deps_dir)
 }
 
+/// Writes the inline WIT world a macro passes to `generate!` to
+/// `$MIDENC_EXPAND_WIT_DIR/<package>.<world>.wit`, if that variable is set.
+///
+/// The world is written before any bindings are generated from it, so that it can be inspected
+/// even when binding generation fails.
+pub(crate) fn write_expanded_wit(wit_source: &str, world_name: &str) -> Result<(), Error> {
+    let Some(out_dir) = env::var_os(EXPAND_WIT_DIR_ENV).filter(|dir| !dir.is_empty()) else {
+        return Ok(());
+    };
+    let out_dir = PathBuf::from(out_dir);
+    fs::create_dir_all(&out_dir).map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format!("failed to create expanded WIT directory '{}': {err}", out_dir.display()),
+        )
+    })?;
+
+    let package_name = env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "generated".to_string());
+    let out_file = out_dir.join(format!(
+        "{}.{}.wit",
+        sanitize_wit_filename_component(&package_name),
+        sanitize_wit_filename_component(world_name)
+    ));
+    fs::write(&out_file, wit_source).map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format!("failed to write expanded WIT to '{}': {err}", out_file.display()),
+        )
+    })
+}
+
+/// Makes Cargo re-run the macro when `MIDENC_EXPAND_WIT_DIR` changes, so that toggling it emits
+/// the WIT of crates which were already compiled.
+pub(crate) fn expanded_wit_env_dependency() -> TokenStream2 {
+    quote! {
+        const _: Option<&str> = option_env!(#EXPAND_WIT_DIR_ENV);
+    }
+}
+
 /// Emits frontend-only metadata into the shared component frontend custom section.
 pub(crate) fn generate_frontend_link_section(metadata: &FrontendMetadata) -> TokenStream2 {
     let metadata_bytes = metadata.to_bytes().unwrap_or_else(|err| panic!("{err}"));
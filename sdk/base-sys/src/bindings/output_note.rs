@@ -118,7 +118,7 @@ unsafe extern "C" {
 /// let recipient = note::build_recipient(serial_num, note_script_root, storage);
 ///
 /// let tag = Tag::from(felt!(0));
-/// let note_type = NoteType::from(felt!(1)); // public note type (0b01)
+/// let note_type = NoteType::public();
 ///
 /// let note_idx = output_note::create(tag, note_type, recipient);
 /// output_note::add_asset(
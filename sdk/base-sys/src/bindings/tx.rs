@@ -1,6 +1,11 @@
-use miden_stdlib_sys::{Felt, Word, WordAligned};
+use alloc::vec::Vec;
 
-use super::types::AccountId;
+use miden_stdlib_sys::{
+    Felt, Word, WordAligned,
+    intrinsics::advice::{adv_insert, emit, emit_with_commitment},
+};
+
+use super::{ToFeltRepr, types::AccountId};
 
 /// Marker trait for raw FPI input array lengths supported by the protocol executor.
 #[doc(hidden)]
@@ -225,6 +230,37 @@ pub fn get_output_notes_commitment() -> Word {
     }
 }
 
+/// Emits the event `event_id`, which the host of the transaction can react to.
+///
+/// Hosts identify events by the id derived from the event name, i.e.
+/// `EventName::new("my_project::transfer").to_event_id()`, so `event_id` is a felt rather than a
+/// small integer.
+///
+/// When not targeting the Miden VM, the event is recorded instead, and can be retrieved with
+/// `miden_stdlib_sys::intrinsics::advice::take_emitted_events`.
+pub fn emit_event(event_id: Felt) {
+    emit(event_id);
+}
+
+/// Emits the event `event_id` with `data` as its payload.
+///
+/// The felt representation of `data`, zero-padded to whole words, is inserted in the advice map
+/// under its commitment, the hash of these words, which is passed to the host with the event.
+pub fn emit_event_with_data<T: ToFeltRepr>(event_id: Felt, data: &T) {
+    let felts = data.to_felt_repr();
+    let words = felts
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [Felt::ZERO; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            Word::new(word)
+        })
+        .collect::<Vec<_>>();
+    let commitment = Word::from(miden_stdlib_sys::hash_words(&words));
+    adv_insert(commitment, &words);
+    emit_with_commitment(event_id, commitment);
+}
+
 /// Executes `foreign_proc_root` against `foreign_account_id` with raw felt inputs.
 ///
 /// The protocol executor always consumes exactly 16 input felts and returns exactly 16 output
@@ -250,3 +286,42 @@ pub fn execute_foreign_procedure(
         ret_area.into_inner().assume_init()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use miden_stdlib_sys::{felt, intrinsics::advice::take_emitted_events};
+
+    use super::*;
+
+    #[derive(ToFeltRepr)]
+    struct Transfer {
+        amount: u32,
+        recipient: u32,
+        is_refund: bool,
+    }
+
+    /// Ensures emitted events are recorded with their ids, and their zero-padded payloads.
+    #[test]
+    fn emitted_events_are_recorded_with_payloads() {
+        emit_event(felt!(1));
+        emit_event_with_data(
+            felt!(2),
+            &Transfer {
+                amount: 50,
+                recipient: 9,
+                is_refund: true,
+            },
+        );
+
+        let events = take_emitted_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_id, felt!(1));
+        assert_eq!(events[0].data, None);
+        assert_eq!(events[1].event_id, felt!(2));
+        assert_eq!(
+            events[1].data.as_deref(),
+            Some([felt!(50), felt!(9), felt!(1), felt!(0)].as_slice())
+        );
+        assert!(take_emitted_events().is_empty());
+    }
+}
@@ -123,6 +123,30 @@ impl NoteMetadata {
     pub fn new(header: Word) -> Self {
         Self { header }
     }
+
+    /// Returns the account which created the note.
+    #[inline]
+    pub fn sender(&self) -> AccountId {
+        super::note::metadata_into_sender(self.header)
+    }
+
+    /// Returns the tag of the note.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        super::note::metadata_into_tag(self.header)
+    }
+
+    /// Returns the type of the note.
+    #[inline]
+    pub fn note_type(&self) -> NoteType {
+        super::note::metadata_into_note_type(self.header)
+    }
+
+    /// Returns the schemes of the four attachments of the note.
+    #[inline]
+    pub fn attachment_schemes(&self) -> Word {
+        super::note::metadata_into_attachment_schemes(self.header)
+    }
 }
 
 /// Result of searching note metadata for an attachment scheme.
@@ -164,12 +188,44 @@ impl From<Recipient> for Word {
     }
 }
 
+/// A note tag, a 32-bit value which hints at the accounts a note is meant for.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct Tag {
     pub inner: Felt,
 }
 
+impl Tag {
+    /// The number of high bits of an account id prefix which a tag targeting the account holds.
+    pub const ACCOUNT_TARGET_BITS: u32 = 14;
+
+    /// Creates a tag targeting `account_id`, whose remaining low bits hold `use_case`.
+    ///
+    /// The high [`Self::ACCOUNT_TARGET_BITS`] bits of the tag are those of the account id prefix,
+    /// as in a tag created by `NoteTag::with_account_target`, and the 16 low bits are `use_case`.
+    pub fn for_local_use(account_id: AccountId, use_case: u16) -> Self {
+        let prefix_high = (account_id.prefix.as_canonical_u64() >> 32) as u32;
+        let target = prefix_high & !(u32::MAX >> Self::ACCOUNT_TARGET_BITS);
+        Tag {
+            inner: Felt::from_u32(target | use_case as u32),
+        }
+    }
+
+    /// Creates a tag from its felt encoding, which must fit in 32 bits.
+    pub fn from_felt_checked(value: Felt) -> Result<Self, &'static str> {
+        if value.as_canonical_u64() > u32::MAX as u64 {
+            return Err("expected a note tag to fit in 32 bits");
+        }
+        Ok(Tag { inner: value })
+    }
+
+    /// Returns the 32-bit value of the tag.
+    #[inline]
+    pub fn as_u32(&self) -> u32 {
+        self.inner.as_canonical_u64() as u32
+    }
+}
+
 impl From<Felt> for Tag {
     fn from(value: Felt) -> Self {
         Tag { inner: value }
@@ -218,12 +274,47 @@ impl TryFrom<Word> for NoteIdx {
     }
 }
 
+/// The type of a note, which determines what the chain stores about it.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct NoteType {
     pub inner: Felt,
 }
 
+impl NoteType {
+    /// Returns the type of notes whose details are stored on chain.
+    #[inline]
+    pub fn public() -> Self {
+        NoteType {
+            inner: Felt::from_u32(0b01),
+        }
+    }
+
+    /// Returns the type of notes of which only a commitment is stored on chain.
+    #[inline]
+    pub fn private() -> Self {
+        NoteType {
+            inner: Felt::from_u32(0b10),
+        }
+    }
+
+    /// Returns the type of notes whose details are stored encrypted on chain.
+    #[inline]
+    pub fn encrypted() -> Self {
+        NoteType {
+            inner: Felt::from_u32(0b11),
+        }
+    }
+
+    /// Creates a note type from its felt encoding, which must be one of the known note types.
+    pub fn from_felt_checked(value: Felt) -> Result<Self, &'static str> {
+        match value.as_canonical_u64() {
+            0b01..=0b11 => Ok(NoteType { inner: value }),
+            _ => Err("expected a note type of 1 (public), 2 (private) or 3 (encrypted)"),
+        }
+    }
+}
+
 impl From<Felt> for NoteType {
     fn from(value: Felt) -> Self {
         NoteType { inner: value }
@@ -299,9 +390,9 @@ impl StorageSlotId {
 
 #[cfg(test)]
 mod tests {
-    use miden_stdlib_sys::{Word, felt};
+    use miden_stdlib_sys::{Felt, Word, felt};
 
-    use super::{felt_from_padded_word, padded_word_from_felt};
+    use super::{AccountId, NoteType, Tag, felt_from_padded_word, padded_word_from_felt};
 
     /// Ensures `padded_word_from_felt` zero-pads the trailing three limbs.
     #[test]
@@ -321,6 +412,36 @@ mod tests {
         assert_eq!(err, "expected zero padding in the trailing three felts");
     }
 
+    /// Ensures local-use tags hold the high bits of the account id prefix, and the use case.
+    #[test]
+    fn tag_for_local_use_targets_account_prefix() {
+        let account_id = AccountId::new(Felt::new(0xabcd_ef01_2345_6789).unwrap(), felt!(0));
+
+        let tag = Tag::for_local_use(account_id, 0x1234);
+
+        assert_eq!(tag.as_u32(), 0xabcc_1234);
+    }
+
+    /// Ensures tags are only created from felts which fit in 32 bits.
+    #[test]
+    fn tag_from_felt_checked_rejects_wide_values() {
+        assert_eq!(
+            Tag::from_felt_checked(felt!(0xc000_0001)).map(|tag| tag.as_u32()),
+            Ok(0xc000_0001)
+        );
+        assert!(Tag::from_felt_checked(Felt::new(1 << 32).unwrap()).is_err());
+    }
+
+    /// Ensures note types are only created from the known encodings.
+    #[test]
+    fn note_type_from_felt_checked_accepts_known_types() {
+        assert_eq!(NoteType::from_felt_checked(felt!(1)), Ok(NoteType::public()));
+        assert_eq!(NoteType::from_felt_checked(felt!(2)), Ok(NoteType::private()));
+        assert_eq!(NoteType::from_felt_checked(felt!(3)), Ok(NoteType::encrypted()));
+        assert!(NoteType::from_felt_checked(felt!(0)).is_err());
+        assert!(NoteType::from_felt_checked(felt!(4)).is_err());
+    }
+
     /// Ensures the felt-padding helpers form a lossless roundtrip for scalar values.
     #[test]
     fn felt_padding_helpers_roundtrip() {
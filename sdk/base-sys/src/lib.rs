@@ -3,4 +3,6 @@
 #![cfg_attr(target_family = "wasm", feature(linkage))]
 #![deny(warnings)]
 
+extern crate alloc;
+
 pub mod bindings;
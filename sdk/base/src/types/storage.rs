@@ -1,7 +1,10 @@
+use alloc::vec::Vec;
+
 use miden_base_sys::bindings::{
     StorageSlotId, felt_from_padded_word, padded_word_from_felt, storage,
 };
-use miden_stdlib_sys::{Digest, Felt, Word};
+use miden_field_repr::{FeltReader, FromFeltRepr, ToFeltRepr};
+use miden_stdlib_sys::{Digest, Felt, Word, felt, hash_elements};
 
 /// A type that can be stored in (or loaded from) account storage.
 ///
@@ -140,6 +143,75 @@ impl WordKey for miden_base_sys::bindings::NoteType {
     }
 }
 
+/// A type stored in account storage through its felt representation.
+///
+/// Implementing this marker trait for a type deriving [`ToFeltRepr`] makes it usable as a
+/// [`StorageMap`] key, and, if it also derives [`FromFeltRepr`], as the value of a [`StorageMap`]
+/// or [`StorageValue`]:
+///
+/// ```ignore
+/// #[derive(ToFeltRepr, FromFeltRepr)]
+/// struct Position {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// impl StorageRepr for Position {}
+/// ```
+///
+/// Values are stored as their felt representation, zero-padded to a single [`Word`]. Storing a
+/// value whose representation is longer than four felts traps.
+///
+/// Keys whose representation fits in a single word are zero-padded to it as well, while longer
+/// keys are hashed with [`hash_elements`]. As the felt representation of a type is never a prefix
+/// of the representation of another value of the same type, distinct keys map to distinct words.
+pub trait StorageRepr: ToFeltRepr {}
+
+impl<T: StorageRepr + FromFeltRepr> WordValue for T {
+    fn try_into_word(self) -> Result<Word, &'static str> {
+        let felts = self.to_felt_repr();
+        if felts.len() > 4 {
+            return Err("felt representation of the value does not fit in a single word");
+        }
+        Ok(padded_word_from_felts(&felts))
+    }
+
+    fn try_from_word(word: Word) -> Result<Self, &'static str> {
+        let felts = [word[0], word[1], word[2], word[3]];
+        let mut reader = FeltReader::new(&felts);
+        let value = T::from_felt_repr(&mut reader)
+            .map_err(|_| "word is not the felt representation of the value type")?;
+        if felts[reader.pos()..].iter().any(|felt| *felt != felt!(0)) {
+            return Err("expected zero padding after the felt representation");
+        }
+        Ok(value)
+    }
+}
+
+impl<T: StorageRepr + Copy> WordKey for T {
+    fn try_into_word(self) -> Result<Word, &'static str> {
+        let felts = self.to_felt_repr();
+        if felts.len() > 4 {
+            Ok(hash_elements(felts).into())
+        } else {
+            Ok(padded_word_from_felts(&felts))
+        }
+    }
+}
+
+/// The word held by absent storage map keys.
+#[inline(always)]
+fn empty_word() -> Word {
+    Word::new([felt!(0); 4])
+}
+
+/// Builds a word from at most four `felts`, padding it with trailing zeros.
+fn padded_word_from_felts(felts: &[Felt]) -> Word {
+    let mut word = [felt!(0); 4];
+    word[..felts.len()].copy_from_slice(felts);
+    Word::new(word)
+}
+
 /// Typed access to a single account storage value.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct StorageValue<T: WordValue> {
@@ -183,9 +255,88 @@ impl<T: WordValue> StorageValue<T> {
     }
 }
 
+/// Typed access to a value spanning `N` account storage slots.
+///
+/// This holds values whose felt representation is longer than the single word of a
+/// [`StorageValue`]: the representation is zero-padded to `N` words, the `i`-th of which is stored
+/// in the `i`-th slot. Storing a value whose representation is longer than `N` words traps.
+///
+/// In a `#[component_storage]` struct, `N` must be an integer literal, and a field `name` is
+/// assigned the slot names `name`, `name_1`, ..., `name_{N-1}` of its component.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MultiSlotValue<T: ToFeltRepr + FromFeltRepr, const N: usize> {
+    /// The underlying storage slot ids, in the order of the words they hold.
+    pub slots: [StorageSlotId; N],
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: ToFeltRepr + FromFeltRepr, const N: usize> MultiSlotValue<T, N> {
+    /// Creates a new typed multi-slot value handle for `slots`.
+    pub const fn new(slots: [StorageSlotId; N]) -> Self {
+        Self {
+            slots,
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: ToFeltRepr + FromFeltRepr, const N: usize> From<[StorageSlotId; N]>
+    for MultiSlotValue<T, N>
+{
+    fn from(slots: [StorageSlotId; N]) -> Self {
+        Self::new(slots)
+    }
+}
+
+impl<T: ToFeltRepr + FromFeltRepr, const N: usize> MultiSlotValue<T, N> {
+    /// Reads the current value from account storage.
+    #[inline(always)]
+    pub fn get(&self) -> T {
+        let mut felts = Vec::with_capacity(N * 4);
+        for slot in self.slots.iter() {
+            let word = storage::get_item(*slot);
+            felts.extend([word[0], word[1], word[2], word[3]]);
+        }
+        self.decode(&felts)
+    }
+
+    /// Sets the value in account storage and returns the previous value.
+    #[inline(always)]
+    pub fn set(&mut self, value: T) -> T {
+        let felts = value.to_felt_repr();
+        if felts.len() > N * 4 {
+            panic!(
+                "felt representation of the value for storage slots {:?} does not fit in {N} words",
+                self.slots
+            );
+        }
+
+        let mut previous = Vec::with_capacity(N * 4);
+        for (index, slot) in self.slots.iter().enumerate() {
+            let start = (index * 4).min(felts.len());
+            let end = (start + 4).min(felts.len());
+            let word = padded_word_from_felts(&felts[start..end]);
+            let word = storage::set_item(*slot, word);
+            previous.extend([word[0], word[1], word[2], word[3]]);
+        }
+        self.decode(&previous)
+    }
+
+    /// Decodes a value from the felts of its slots, which must be zero after its representation.
+    fn decode(&self, felts: &[Felt]) -> T {
+        let mut reader = FeltReader::new(felts);
+        let value = T::from_felt_repr(&mut reader)
+            .ok()
+            .filter(|_| felts[reader.pos()..].iter().all(|felt| *felt == felt!(0)));
+        value.unwrap_or_else(|| panic!("storage slots {:?} contained an invalid value", self.slots))
+    }
+}
+
 /// Typed access to an account storage map.
+///
+/// Without type arguments, a map from [`Word`] keys to [`Felt`] values.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct StorageMap<K: WordKey, V: WordValue> {
+pub struct StorageMap<K: WordKey = Word, V: WordValue = Felt> {
     /// The underlying storage slot id.
     pub slot: StorageSlotId,
     _marker: core::marker::PhantomData<(K, V)>,
@@ -214,28 +365,91 @@ impl<K: WordKey, V: WordValue> StorageMap<K, V> {
     /// At the protocol layer, absent keys read as the default word value.
     #[inline(always)]
     pub fn get(&self, key: K) -> V {
-        let key = key.try_into_word().unwrap_or_else(|_| {
-            panic!("failed to convert key for storage map slot {:?}", self.slot)
-        });
+        let key = self.key_word(key);
         V::try_from_word(storage::get_map_item(self.slot, &key)).unwrap_or_else(|_| {
             panic!("storage map slot {:?} contained an invalid word", self.slot)
         })
     }
 
+    /// Returns the value associated with `key` from the account storage map, or `None` if the map
+    /// holds the empty word for `key`.
+    ///
+    /// As the protocol does not distinguish "missing" from "default", this also returns `None` for
+    /// a value which was set, but is represented by the empty word.
+    #[inline(always)]
+    pub fn try_get(&self, key: K) -> Option<V> {
+        let key = self.key_word(key);
+        let word = storage::get_map_item(self.slot, &key);
+        if word == empty_word() {
+            return None;
+        }
+        Some(V::try_from_word(word).unwrap_or_else(|_| {
+            panic!("storage map slot {:?} contained an invalid word", self.slot)
+        }))
+    }
+
+    /// Returns the value associated with `key` from the account storage map, or `default` if the
+    /// map holds the empty word for `key`.
+    #[inline(always)]
+    pub fn get_or(&self, key: K, default: V) -> V {
+        self.try_get(key).unwrap_or(default)
+    }
+
+    /// Returns the value associated with `key` from the account storage map, or `V::default()` if
+    /// the map holds the empty word for `key`.
+    #[inline(always)]
+    pub fn get_or_default(&self, key: K) -> V
+    where
+        V: Default,
+    {
+        self.try_get(key).unwrap_or_default()
+    }
+
+    /// Returns `true` if the account storage map holds a value for `key`.
+    ///
+    /// Storage maps are sparse Merkle trees in which every absent key holds the empty word, so the
+    /// transaction kernel cannot tell an absent key from one set to the empty word. By convention,
+    /// a key is thus present if and only if its value is not the empty word: setting a key to a
+    /// value represented by the empty word (e.g. a zero [`Felt`]) removes it.
+    #[inline(always)]
+    pub fn contains_key(&self, key: K) -> bool {
+        let key = self.key_word(key);
+        storage::get_map_item(self.slot, &key) != empty_word()
+    }
+
+    /// Removes `key` from the account storage map, by setting its value to the empty word, and
+    /// returns the previous value.
+    ///
+    /// See [`Self::contains_key`] for why removing a key and setting it to the empty word are the
+    /// same.
+    #[inline(always)]
+    pub fn remove(&mut self, key: K) -> V {
+        let key = self.key_word(key);
+        V::try_from_word(storage::set_map_item(self.slot, key, empty_word())).unwrap_or_else(|_| {
+            panic!("storage map slot {:?} contained an invalid word", self.slot)
+        })
+    }
+
     /// Sets `value` for `key` in the account storage map and returns the previous value.
     ///
     /// This is analogous to `HashMap::insert`, except it always returns a value (the protocol does
     /// not distinguish "missing" from "default").
     #[inline(always)]
     pub fn set(&mut self, key: K, value: V) -> V {
-        let key = key.try_into_word().unwrap_or_else(|_| {
-            panic!("failed to convert key for storage map slot {:?}", self.slot)
-        });
-        let value = value.try_into_word().unwrap_or_else(|_| {
-            panic!("failed to convert value for storage map slot {:?}", self.slot)
+        let key = self.key_word(key);
+        let value = value.try_into_word().unwrap_or_else(|err| {
+            panic!("failed to convert value for storage map slot {:?}: {err}", self.slot)
         });
         V::try_from_word(storage::set_map_item(self.slot, key, value)).unwrap_or_else(|_| {
             panic!("storage map slot {:?} contained an invalid word", self.slot)
         })
     }
+
+    /// Converts `key` into the word it is stored under.
+    #[inline(always)]
+    fn key_word(&self, key: K) -> Word {
+        key.try_into_word().unwrap_or_else(|err| {
+            panic!("failed to convert key for storage map slot {:?}: {err}", self.slot)
+        })
+    }
 }
@@ -63,7 +63,7 @@
 //! - `Felt`: encoded as a single `Felt`
 //! - `u64`: encoded as 2 `Felt`s (low `u32`, then high `u32`)
 //! - `u32`, `u8`: encoded as a single `Felt`
-//! - `bool`: encoded as a single `Felt` (`0` = `false`, non-zero = `true`)
+//! - `bool`: encoded as a single `Felt` (`0` = `false`, `1` = `true`)
 //!
 //! ## Structs
 //!
@@ -104,6 +104,27 @@
 //! `ToFeltRepr`/`FromFeltRepr`. The overall encoding is always the concatenation of the nested
 //! encodings.
 //!
+//! ## Borrowed fields
+//!
+//! Fields of type `FeltSlice<'a>` (or other types implementing `FromFeltReprBorrowed<'a>`) can be
+//! annotated with `#[felt_repr(borrow)]`. The derived decoder then returns a subslice of the input
+//! for that field instead of copying it, and the type implements `FromFeltReprBorrowed<'a>` (for
+//! its first lifetime parameter) rather than `FromFeltRepr`:
+//!
+//! ```ignore
+//! use miden_field_repr::{FeltSlice, FromFeltRepr, ToFeltRepr};
+//!
+//! #[derive(FromFeltRepr, ToFeltRepr)]
+//! struct Payload<'a> {
+//!     kind: u32,
+//!     #[felt_repr(borrow)]
+//!     data: FeltSlice<'a>,
+//! }
+//! ```
+//!
+//! `FeltSlice<'a>` is encoded exactly like `Vec<Felt>`, so borrowing is purely a decoding concern
+//! and does not affect the wire format.
+//!
 //! ## Unsupported items
 //!
 //! - Unions are not supported.
@@ -206,6 +227,9 @@ fn ensure_no_explicit_discriminants(
 /// Enums are encoded as a `u32` tag (variant ordinal, starting from `0`)
 /// followed by the selected variant payload encoded in declaration order.
 ///
+/// If any field is annotated with `#[felt_repr(borrow)]`, `FromFeltReprBorrowed` is derived
+/// instead, for the first lifetime parameter of the type.
+///
 /// # Example
 ///
 /// ```ignore
@@ -217,7 +241,7 @@ fn ensure_no_explicit_discriminants(
 ///     pub suffix: Felt,
 /// }
 /// ```
-#[proc_macro_derive(DeriveFromFeltRepr)]
+#[proc_macro_derive(DeriveFromFeltRepr, attributes(felt_repr))]
 pub fn derive_from_felt_repr(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -242,35 +266,29 @@ fn derive_from_felt_repr_impl(
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let trait_name = "FromFeltRepr";
-    let expanded = match &input.data {
+    let mut has_borrowed_fields = false;
+    let body = match &input.data {
         Data::Struct(_) => match extract_struct_fields(input, trait_name)? {
             StructFields::Named(fields) => {
                 let field_names: Vec<_> =
                     fields.iter().map(|field| field.ident.as_ref().unwrap()).collect();
-                let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+                let reads = fields
+                    .iter()
+                    .map(|field| read_field(field, &felt_repr_crate, &mut has_borrowed_fields))
+                    .collect::<Result<Vec<_>, _>>()?;
                 quote! {
-                    impl #impl_generics #felt_repr_crate::FromFeltRepr for #name #ty_generics #where_clause {
-                        #[inline(always)]
-                        fn from_felt_repr(reader: &mut #felt_repr_crate::FeltReader<'_>) -> #felt_repr_crate::FeltReprResult<Self> {
-                            Ok(Self {
-                                #(#field_names: <#field_types as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)?),*
-                            })
-                        }
-                    }
+                    Ok(Self {
+                        #(#field_names: #reads),*
+                    })
                 }
             }
             StructFields::Unnamed(fields) => {
-                let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
-                let reads = field_types.iter().map(|ty| {
-                    quote! { <#ty as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)? }
-                });
+                let reads = fields
+                    .iter()
+                    .map(|field| read_field(field, &felt_repr_crate, &mut has_borrowed_fields))
+                    .collect::<Result<Vec<_>, _>>()?;
                 quote! {
-                    impl #impl_generics #felt_repr_crate::FromFeltRepr for #name #ty_generics #where_clause {
-                        #[inline(always)]
-                        fn from_felt_repr(reader: &mut #felt_repr_crate::FeltReader<'_>) -> #felt_repr_crate::FeltReprResult<Self> {
-                            Ok(Self(#(#reads),*))
-                        }
-                    }
+                    Ok(Self(#(#reads),*))
                 }
             }
         },
@@ -278,16 +296,18 @@ fn derive_from_felt_repr_impl(
             let variants = extract_enum_variants(input, trait_name)?;
             ensure_no_explicit_discriminants(variants, trait_name, name)?;
 
-            let arms = variants.iter().enumerate().map(|(variant_ordinal, variant)| {
+            let mut arms = Vec::with_capacity(variants.len());
+            for (variant_ordinal, variant) in variants.iter().enumerate() {
                 let variant_ident = &variant.ident;
                 let tag = variant_ordinal as u32;
-                match &variant.fields {
+                let arm = match &variant.fields {
                     Fields::Unit => quote! { #tag => Ok(Self::#variant_ident) },
                     Fields::Unnamed(fields) => {
-                        let field_types: Vec<_> = fields.unnamed.iter().map(|f| &f.ty).collect();
-                        let reads = field_types.iter().map(|ty| {
-                            quote! { <#ty as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)? }
-                        });
+                        let reads = fields
+                            .unnamed
+                            .iter()
+                            .map(|f| read_field(f, &felt_repr_crate, &mut has_borrowed_fields))
+                            .collect::<Result<Vec<_>, _>>()?;
                         quote! { #tag => Ok(Self::#variant_ident(#(#reads),*)) }
                     }
                     Fields::Named(fields) => {
@@ -296,32 +316,29 @@ fn derive_from_felt_repr_impl(
                             .iter()
                             .map(|f| f.ident.as_ref().expect("named field"))
                             .collect();
-                        let field_types: Vec<_> = fields.named.iter().map(|f| &f.ty).collect();
-                        let reads = field_idents.iter().zip(field_types.iter()).map(|(ident, ty)| {
-                            quote! { #ident: <#ty as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)? }
-                        });
-                        quote! { #tag => Ok(Self::#variant_ident { #(#reads),* }) }
+                        let reads = fields
+                            .named
+                            .iter()
+                            .map(|f| read_field(f, &felt_repr_crate, &mut has_borrowed_fields))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        quote! { #tag => Ok(Self::#variant_ident { #(#field_idents: #reads),* }) }
                     }
-                }
-            });
+                };
+                arms.push(arm);
+            }
 
             quote! {
-                impl #impl_generics #felt_repr_crate::FromFeltRepr for #name #ty_generics #where_clause {
-                    #[inline(always)]
-                    fn from_felt_repr(reader: &mut #felt_repr_crate::FeltReader<'_>) -> #felt_repr_crate::FeltReprResult<Self> {
-                        let tag_pos = reader.pos();
-                        let len = reader.len();
-                        let tag: u32 = <u32 as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)?;
-                        match tag {
-                            #(#arms,)*
-                            other => Err(#felt_repr_crate::FeltReprError::UnknownEnumTag {
-                                pos: tag_pos,
-                                len,
-                                ty: stringify!(#name),
-                                tag: other,
-                            }),
-                        }
-                    }
+                let tag_pos = reader.pos();
+                let len = reader.len();
+                let tag: u32 = <u32 as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)?;
+                match tag {
+                    #(#arms,)*
+                    other => Err(#felt_repr_crate::FeltReprError::UnknownEnumTag {
+                        pos: tag_pos,
+                        len,
+                        ty: stringify!(#name),
+                        tag: other,
+                    }),
                 }
             }
         }
@@ -333,18 +350,57 @@ fn derive_from_felt_repr_impl(
         }
     };
 
-    let expanded = quote! {
-        #expanded
+    // Types with borrowed fields decode from a reader tied to the lifetime of the input, and so
+    // implement `FromFeltReprBorrowed` for that lifetime instead of `FromFeltRepr`.
+    let expanded = if has_borrowed_fields {
+        let Some(lifetime) = generics.lifetimes().next().map(|param| &param.lifetime) else {
+            return Err(Error::new(
+                input.span(),
+                format!(
+                    "`#[felt_repr(borrow)]` requires `{name}` to have a lifetime parameter to \
+                     borrow from"
+                ),
+            ));
+        };
+        quote! {
+            impl #impl_generics #felt_repr_crate::FromFeltReprBorrowed<#lifetime> for #name #ty_generics #where_clause {
+                #[inline(always)]
+                fn from_felt_repr_borrowed(reader: &mut #felt_repr_crate::FeltReader<#lifetime>) -> #felt_repr_crate::FeltReprResult<Self> {
+                    #body
+                }
+            }
+
+            impl #impl_generics ::core::convert::TryFrom<&#lifetime [#felt_ty]> for #name #ty_generics #where_clause {
+                type Error = #felt_repr_crate::FeltReprError;
+
+                #[inline(always)]
+                fn try_from(felts: &#lifetime [#felt_ty]) -> Result<Self, Self::Error> {
+                    let mut reader = #felt_repr_crate::FeltReader::new(felts);
+                    let value = <Self as #felt_repr_crate::FromFeltReprBorrowed<#lifetime>>::from_felt_repr_borrowed(&mut reader)?;
+                    reader.ensure_eof()?;
+                    Ok(value)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #felt_repr_crate::FromFeltRepr for #name #ty_generics #where_clause {
+                #[inline(always)]
+                fn from_felt_repr(reader: &mut #felt_repr_crate::FeltReader<'_>) -> #felt_repr_crate::FeltReprResult<Self> {
+                    #body
+                }
+            }
 
-        impl #impl_generics ::core::convert::TryFrom<&[#felt_ty]> for #name #ty_generics #where_clause {
-            type Error = #felt_repr_crate::FeltReprError;
+            impl #impl_generics ::core::convert::TryFrom<&[#felt_ty]> for #name #ty_generics #where_clause {
+                type Error = #felt_repr_crate::FeltReprError;
 
-            #[inline(always)]
-            fn try_from(felts: &[#felt_ty]) -> Result<Self, Self::Error> {
-                let mut reader = #felt_repr_crate::FeltReader::new(felts);
-                let value = <Self as #felt_repr_crate::FromFeltRepr>::from_felt_repr(&mut reader)?;
-                reader.ensure_eof()?;
-                Ok(value)
+                #[inline(always)]
+                fn try_from(felts: &[#felt_ty]) -> Result<Self, Self::Error> {
+                    let mut reader = #felt_repr_crate::FeltReader::new(felts);
+                    let value = <Self as #felt_repr_crate::FromFeltRepr>::from_felt_repr(&mut reader)?;
+                    reader.ensure_eof()?;
+                    Ok(value)
+                }
             }
         }
     };
@@ -352,6 +408,42 @@ fn derive_from_felt_repr_impl(
     Ok(expanded.into())
 }
 
+/// Generates the expression decoding `field` from `reader`.
+///
+/// Fields annotated with `#[felt_repr(borrow)]` are decoded via `FromFeltReprBorrowed`, in which
+/// case `has_borrowed_fields` is set.
+fn read_field(
+    field: &Field,
+    felt_repr_crate: &TokenStream2,
+    has_borrowed_fields: &mut bool,
+) -> Result<TokenStream2, Error> {
+    let ty = &field.ty;
+    if is_borrowed_field(field)? {
+        *has_borrowed_fields = true;
+        Ok(quote! {
+            <#ty as #felt_repr_crate::FromFeltReprBorrowed<'_>>::from_felt_repr_borrowed(reader)?
+        })
+    } else {
+        Ok(quote! { <#ty as #felt_repr_crate::FromFeltRepr>::from_felt_repr(reader)? })
+    }
+}
+
+/// Returns `true` if `field` is annotated with `#[felt_repr(borrow)]`.
+fn is_borrowed_field(field: &Field) -> Result<bool, Error> {
+    let mut borrowed = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("felt_repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("borrow") {
+                borrowed = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `felt_repr` attribute, expected `borrow`"))
+            }
+        })?;
+    }
+    Ok(borrowed)
+}
+
 /// Derives `ToFeltRepr` trait for a struct with named fields, or an enum.
 ///
 /// Structs are encoded by serializing their fields in declaration order.
@@ -370,7 +462,7 @@ fn derive_from_felt_repr_impl(
 ///     pub suffix: Felt,
 /// }
 /// ```
-#[proc_macro_derive(DeriveToFeltRepr)]
+#[proc_macro_derive(DeriveToFeltRepr, attributes(felt_repr))]
 pub fn derive_to_felt_repr(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
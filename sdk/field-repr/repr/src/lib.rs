@@ -179,6 +179,24 @@ impl<'a> FeltReader<'a> {
         Ok(felt)
     }
 
+    /// Reads the next `len` elements as a subslice of the underlying data, advancing the position.
+    ///
+    /// No copying takes place; the returned slice borrows from the data this reader was created
+    /// from.
+    #[inline(always)]
+    pub fn read_slice(&mut self, len: usize) -> FeltReprResult<&'a [Felt]> {
+        if len > self.remaining() {
+            return Err(FeltReprError::UnexpectedEof {
+                pos: self.pos,
+                len: self.data.len(),
+            });
+        }
+
+        let start = self.pos;
+        self.pos += len;
+        Ok(&self.data[start..self.pos])
+    }
+
     /// Reads the next element and decodes it as a `u32`.
     #[inline(always)]
     pub fn read_u32(&mut self) -> FeltReprResult<u32> {
@@ -301,6 +319,63 @@ impl FromFeltRepr for bool {
     }
 }
 
+/// Trait for deserialization from felt memory representation, borrowing from the input.
+///
+/// This is the zero-copy counterpart of [`FromFeltRepr`]: implementations may return values that
+/// reference the felts of the reader's underlying slice (see [`FeltSlice`]), rather than copying
+/// them into newly allocated storage.
+pub trait FromFeltReprBorrowed<'a>: Sized {
+    /// Deserializes from a `FeltReader`, consuming the required elements.
+    fn from_felt_repr_borrowed(reader: &mut FeltReader<'a>) -> FeltReprResult<Self>;
+}
+
+/// A borrowed, length-prefixed sequence of felts.
+///
+/// `FeltSlice` is encoded exactly like `Vec<Felt>`, i.e. `[len, elem0, ..., elemN-1]`, so the two
+/// are interchangeable on the wire. Decoding a `FeltSlice` via [`FromFeltReprBorrowed`] returns a
+/// subslice of the reader's input instead of copying the elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeltSlice<'a>(pub &'a [Felt]);
+
+impl<'a> FeltSlice<'a> {
+    /// Returns the underlying slice of felts.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &'a [Felt] {
+        self.0
+    }
+}
+
+impl core::ops::Deref for FeltSlice<'_> {
+    type Target = [Felt];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<'a> From<&'a [Felt]> for FeltSlice<'a> {
+    #[inline(always)]
+    fn from(data: &'a [Felt]) -> Self {
+        Self(data)
+    }
+}
+
+impl<'a> From<&'a Vec<Felt>> for FeltSlice<'a> {
+    #[inline(always)]
+    fn from(data: &'a Vec<Felt>) -> Self {
+        Self(data.as_slice())
+    }
+}
+
+impl<'a> FromFeltReprBorrowed<'a> for FeltSlice<'a> {
+    #[inline(always)]
+    fn from_felt_repr_borrowed(reader: &mut FeltReader<'a>) -> FeltReprResult<Self> {
+        let len = reader.read_len_u32()?;
+        reader.read_slice(len).map(Self)
+    }
+}
+
 /// Encodes an `Option<T>` as a 1-felt tag followed by the payload (if present).
 ///
 /// Format:
@@ -417,17 +492,17 @@ where
     }
 }
 
-/// Encodes a `Vec<T>` as a length prefix followed by elements.
+/// Encodes a slice `[T]` as a length prefix followed by elements.
 ///
 /// Format: `[len, elem0..., elemN-1...]` where `len` is a `u32` encoded in a single `Felt`.
-impl<T> ToFeltRepr for Vec<T>
+impl<T> ToFeltRepr for [T]
 where
     T: ToFeltRepr,
 {
     #[inline(always)]
     fn write_felt_repr(&self, writer: &mut FeltWriter<'_>) {
         let len = self.len();
-        assert!(len <= u32::MAX as usize, "Vec: length out of range");
+        assert!(len <= u32::MAX as usize, "slice: length out of range");
         writer.write(Felt::new(len as u64).unwrap());
 
         let mut i = 0usize;
@@ -437,3 +512,24 @@ where
         }
     }
 }
+
+/// Encodes a `Vec<T>` as a length prefix followed by elements.
+///
+/// Format: `[len, elem0..., elemN-1...]` where `len` is a `u32` encoded in a single `Felt`.
+impl<T> ToFeltRepr for Vec<T>
+where
+    T: ToFeltRepr,
+{
+    #[inline(always)]
+    fn write_felt_repr(&self, writer: &mut FeltWriter<'_>) {
+        self.as_slice().write_felt_repr(writer);
+    }
+}
+
+/// Encodes a `FeltSlice` identically to `Vec<Felt>`.
+impl ToFeltRepr for FeltSlice<'_> {
+    #[inline(always)]
+    fn write_felt_repr(&self, writer: &mut FeltWriter<'_>) {
+        self.0.write_felt_repr(writer);
+    }
+}
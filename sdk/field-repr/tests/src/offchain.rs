@@ -3,8 +3,44 @@
 //! These tests verify the correctness of `ToFeltRepr` and `FromFeltRepr` implementations without
 //! involving on-chain execution.
 
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
 use miden_field::Felt;
-use miden_field_repr::{FeltReader, FromFeltRepr, ToFeltRepr};
+use miden_field_repr::{FeltReader, FeltSlice, FromFeltRepr, FromFeltReprBorrowed, ToFeltRepr};
+
+/// A global allocator that counts allocations made on the current thread.
+///
+/// Used to verify that borrowed decoding does not allocate.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Returns the number of allocations made on the current thread while running `f`.
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    (result, after - before)
+}
 
 /// Serializes `value` off-chain and deserializes it back, asserting equality.
 fn assert_roundtrip<T>(value: &T)
@@ -460,3 +496,112 @@ fn test_u64_roundtrip_uses_u32_limbs() {
         assert_eq!(roundtripped, value);
     }
 }
+
+#[test]
+fn test_u64_roundtrip_around_field_modulus() {
+    // The Goldilocks modulus `M = 2^64 - 2^32 + 1`. Values `>= M` cannot be stored in a single
+    // felt, which is why `u64` is always split into two `u32` limbs.
+    const M: u64 = 0xffff_ffff_0000_0001;
+    let test_cases: [u64; 4] = [M - 1, M, M + 1, u64::MAX - 1];
+
+    for value in test_cases {
+        let felts = value.to_felt_repr();
+        assert_eq!(felts.len(), 2);
+        assert_eq!(felts[0].as_canonical_u64(), value & 0xffff_ffff);
+        assert_eq!(felts[1].as_canonical_u64(), value >> 32);
+
+        let mut reader = FeltReader::new(&felts);
+        let roundtripped = u64::from_felt_repr(&mut reader).unwrap();
+        assert_eq!(roundtripped, value);
+        reader.ensure_eof().unwrap();
+    }
+}
+
+#[test]
+fn test_u64_rejects_single_felt_encoding() {
+    // A `u64` written as a single felt (i.e. not split into limbs) must not silently decode
+    let felts = [Felt::new(u32::MAX as u64 + 1).unwrap(), Felt::new(0).unwrap()];
+    let mut reader = FeltReader::new(&felts);
+
+    let err = u64::from_felt_repr(&mut reader).unwrap_err();
+    assert_eq!(
+        err,
+        miden_field_repr::FeltReprError::ValueOutOfRange {
+            pos: 0,
+            len: 2,
+            ty: "u32",
+            value: u32::MAX as u64 + 1,
+            max: u32::MAX as u64,
+        }
+    );
+}
+
+/// Struct with a borrowed field between owned fields.
+#[derive(Debug, Clone, PartialEq, Eq, FromFeltRepr, ToFeltRepr)]
+struct WithBorrowed<'a> {
+    prefix: u64,
+    #[felt_repr(borrow)]
+    items: FeltSlice<'a>,
+    suffix: u32,
+}
+
+/// Owned counterpart of [`WithBorrowed`], with an identical encoding.
+#[derive(Debug, Clone, PartialEq, Eq, FromFeltRepr, ToFeltRepr)]
+struct WithOwned {
+    prefix: u64,
+    items: Vec<Felt>,
+    suffix: u32,
+}
+
+#[test]
+fn test_borrowed_field_offsets() {
+    let owned = WithOwned {
+        prefix: u64::MAX,
+        items: (0..5).map(|i| Felt::new(100 + i).unwrap()).collect(),
+        suffix: 7,
+    };
+    let felts = owned.to_felt_repr();
+    // `prefix` occupies felts 0..2, the length prefix of `items` is felt 2
+    assert_eq!(felts.len(), 2 + 1 + 5 + 1);
+
+    let mut reader = FeltReader::new(&felts);
+    let borrowed = WithBorrowed::from_felt_repr_borrowed(&mut reader).unwrap();
+    reader.ensure_eof().unwrap();
+
+    assert_eq!(borrowed.prefix, owned.prefix);
+    assert_eq!(borrowed.suffix, owned.suffix);
+    assert_eq!(borrowed.items.as_slice(), owned.items.as_slice());
+    assert!(core::ptr::eq(borrowed.items.as_slice(), &felts[3..8]));
+
+    // Owned and borrowed forms share the same encoding
+    assert_eq!(borrowed.to_felt_repr(), felts);
+    let roundtrip = WithOwned::try_from(borrowed.to_felt_repr().as_slice()).unwrap();
+    assert_eq!(roundtrip, owned);
+}
+
+#[test]
+fn test_borrowed_field_does_not_allocate() {
+    let items: Vec<Felt> = (0..1024).map(|i| Felt::new(i).unwrap()).collect();
+    let value = WithBorrowed {
+        prefix: 1,
+        items: FeltSlice::from(&items),
+        suffix: 2,
+    };
+    let felts = value.to_felt_repr();
+
+    let (decoded, allocations) = count_allocations(|| WithBorrowed::try_from(felts.as_slice()));
+    assert_eq!(allocations, 0);
+    assert_eq!(decoded.unwrap(), value);
+
+    // The owned decoder copies the items into a new `Vec`
+    let (_, allocations) = count_allocations(|| WithOwned::try_from(felts.as_slice()).unwrap());
+    assert_eq!(allocations, 1);
+}
+
+#[test]
+fn test_borrowed_field_truncated_input() {
+    let felts = [Felt::new(0).unwrap(), Felt::new(0).unwrap(), Felt::new(3).unwrap()];
+
+    let err = WithBorrowed::try_from(&felts[..]).unwrap_err();
+    assert_eq!(err, miden_field_repr::FeltReprError::UnexpectedEof { pos: 3, len: 3 });
+}
@@ -1,7 +1,23 @@
 //! Contains intrinsics for advice operations with the advice provider.
+//!
+//! When not targeting the Miden VM, e.g. in unit tests, the advice stack and map are those of the
+//! [`MockAdviceProvider`] of the current thread, which can be installed with
+//! [`set_mock_advice_provider`], and emitted events are recorded until retrieved with
+//! [`take_emitted_events`].
 
+use alloc::vec::Vec;
+
+use miden_field_repr::{FeltReader, FromFeltRepr};
+
+#[cfg(not(all(target_family = "wasm", miden)))]
+pub use self::mock::{
+    EmittedEvent, MockAdviceProvider, set_mock_advice_provider, take_emitted_events,
+};
 use crate::intrinsics::{Felt, Word};
 
+#[cfg(not(all(target_family = "wasm", miden)))]
+mod mock;
+
 #[cfg(all(target_family = "wasm", miden))]
 unsafe extern "C" {
     /// Pushes a list of field elements onto the advice stack.
@@ -23,8 +39,93 @@ pub fn adv_push_mapvaln(key: Word) -> Felt {
 
 #[inline]
 #[cfg(not(all(target_family = "wasm", miden)))]
-pub fn adv_push_mapvaln(_key: Word) -> Felt {
-    unimplemented!("advice intrinsics are only available when targeting the Miden VM")
+pub fn adv_push_mapvaln(key: Word) -> Felt {
+    mock::with_provider(|provider| {
+        let len = provider.push_mapval(&key);
+        Felt::new(len as u64).unwrap()
+    })
+}
+
+#[cfg(all(target_family = "wasm", miden))]
+unsafe extern "C" {
+    /// Pushes the list of field elements stored in the advice map under `key` onto the advice
+    /// stack, such that the first element of the list is on top of the advice stack.
+    #[cfg_attr(all(target_family = "wasm", miden), linkage = "extern_weak")]
+    #[link_name = "intrinsics::advice::adv_push_mapval"]
+    fn extern_adv_push_mapval(key0: Felt, key1: Felt, key2: Felt, key3: Felt);
+
+    /// Pops the field element on top of the advice stack.
+    #[cfg_attr(all(target_family = "wasm", miden), linkage = "extern_weak")]
+    #[link_name = "intrinsics::advice::adv_pop"]
+    fn extern_adv_pop() -> Felt;
+}
+
+/// Pushes the list of field elements stored in the advice map under `key` onto the advice stack,
+/// such that the first element of the list is on top of the advice stack.
+///
+/// Fails if the advice map has no entry for `key`.
+#[inline]
+#[cfg(all(target_family = "wasm", miden))]
+pub fn adv_push_mapval(key: Word) {
+    unsafe { extern_adv_push_mapval(key[0], key[1], key[2], key[3]) }
+}
+
+/// Pushes the list of field elements stored in the advice map under `key` onto the advice stack,
+/// such that the first element of the list is on top of the advice stack.
+///
+/// Fails if the advice map has no entry for `key`.
+#[inline]
+#[cfg(not(all(target_family = "wasm", miden)))]
+pub fn adv_push_mapval(key: Word) {
+    mock::with_provider(|provider| {
+        provider.push_mapval(&key);
+    })
+}
+
+/// Pops the field element on top of the advice stack.
+///
+/// Fails if the advice stack is empty.
+#[inline]
+#[cfg(all(target_family = "wasm", miden))]
+pub fn adv_pop() -> Felt {
+    unsafe { extern_adv_pop() }
+}
+
+/// Pops the field element on top of the advice stack.
+///
+/// Fails if the advice stack is empty.
+#[inline]
+#[cfg(not(all(target_family = "wasm", miden)))]
+pub fn adv_pop() -> Felt {
+    mock::with_provider(|provider| provider.pop())
+}
+
+/// Pops four field elements from the advice stack, the first one popped being the first element
+/// of the returned word.
+#[inline]
+pub fn adv_pop_word() -> Word {
+    let a = adv_pop();
+    let b = adv_pop();
+    let c = adv_pop();
+    let d = adv_pop();
+    Word::from([a, b, c, d])
+}
+
+/// Pops `len` field elements from the advice stack, and decodes them as the felt representation
+/// of a `T`, the first element popped being the first element of the representation.
+///
+/// This is typically preceded by [`adv_push_mapval`], to read a value which was inserted in the
+/// advice map off-chain, e.g. with `ToFeltRepr::to_felt_repr`.
+///
+/// # Panics
+///
+/// Panics if the elements are not exactly the felt representation of a `T`.
+pub fn adv_read<T: FromFeltRepr>(len: usize) -> T {
+    let felts = (0..len).map(|_| adv_pop()).collect::<Vec<_>>();
+    let mut reader = FeltReader::new(&felts);
+    T::from_felt_repr(&mut reader)
+        .and_then(|value| reader.ensure_eof().map(|_| value))
+        .unwrap_or_else(|err| panic!("invalid felt representation on the advice stack: {err}"))
 }
 
 #[cfg(all(target_family = "wasm", miden))]
@@ -64,6 +165,64 @@ pub fn emit_falcon_sig_to_stack(_msg: Word, _pub_key: Word) {
     unimplemented!("advice intrinsics are only available when targeting the Miden VM")
 }
 
+#[cfg(all(target_family = "wasm", miden))]
+unsafe extern "C" {
+    /// Emits the event `event_id`.
+    /// This maps to the MASM instruction: `emit`.
+    #[cfg_attr(all(target_family = "wasm", miden), linkage = "extern_weak")]
+    #[link_name = "intrinsics::advice::emit"]
+    fn extern_emit(event_id: Felt);
+
+    /// Emits the event `event_id`, with `commitment` on the operand stack under the event id.
+    #[cfg_attr(all(target_family = "wasm", miden), linkage = "extern_weak")]
+    #[link_name = "intrinsics::advice::emit_with_commitment"]
+    fn extern_emit_with_commitment(event_id: Felt, c0: Felt, c1: Felt, c2: Felt, c3: Felt);
+}
+
+/// Emits the event `event_id`, which the host can react to.
+#[inline]
+#[cfg(all(target_family = "wasm", miden))]
+pub fn emit(event_id: Felt) {
+    unsafe { extern_emit(event_id) }
+}
+
+/// Emits the event `event_id`, which the host can react to.
+#[inline]
+#[cfg(not(all(target_family = "wasm", miden)))]
+pub fn emit(event_id: Felt) {
+    mock::record_event(EmittedEvent {
+        event_id,
+        data: None,
+    });
+}
+
+/// Emits the event `event_id`, with `commitment` on the operand stack just under the event id.
+///
+/// The host can retrieve the payload of the event from the advice map, under `commitment`.
+#[inline]
+#[cfg(all(target_family = "wasm", miden))]
+pub fn emit_with_commitment(event_id: Felt, commitment: Word) {
+    unsafe {
+        extern_emit_with_commitment(
+            event_id,
+            commitment[0],
+            commitment[1],
+            commitment[2],
+            commitment[3],
+        )
+    }
+}
+
+/// Emits the event `event_id`, with `commitment` on the operand stack just under the event id.
+///
+/// The host can retrieve the payload of the event from the advice map, under `commitment`.
+#[inline]
+#[cfg(not(all(target_family = "wasm", miden)))]
+pub fn emit_with_commitment(event_id: Felt, commitment: Word) {
+    let data = mock::with_provider(|provider| provider.get(&commitment).map(<[Felt]>::to_vec));
+    mock::record_event(EmittedEvent { event_id, data });
+}
+
 #[cfg(all(target_family = "wasm", miden))]
 unsafe extern "C" {
     /// Inserts values from memory into the advice map using the provided key and memory range.
@@ -106,6 +265,7 @@ pub fn adv_insert(key: Word, values: &[Word]) {
 
 /// Insert values into advice map under the given key.
 #[cfg(not(all(target_family = "wasm", miden)))]
-pub fn adv_insert(_key: Word, _values: &[Word]) {
-    unimplemented!("advice intrinsics are only available when targeting the Miden VM")
+pub fn adv_insert(key: Word, values: &[Word]) {
+    let values = values.iter().flat_map(|word| [word[0], word[1], word[2], word[3]]).collect();
+    mock::with_provider(|provider| provider.insert(key, values));
 }
@@ -0,0 +1,161 @@
+//! A mock advice provider, backing the advice intrinsics when not targeting the Miden VM, and a
+//! sink recording the events emitted meanwhile.
+
+extern crate std;
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::intrinsics::{Felt, Word};
+
+std::thread_local! {
+    static PROVIDER: RefCell<MockAdviceProvider> = RefCell::new(MockAdviceProvider::default());
+    static EVENTS: RefCell<Vec<EmittedEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The advice stack and advice map used by the advice intrinsics when not targeting the Miden VM.
+///
+/// Each thread has its own provider, which starts out empty. Code consuming advice can be unit
+/// tested by installing a provider holding its inputs with [`set_mock_advice_provider`].
+#[derive(Debug, Default, Clone)]
+pub struct MockAdviceProvider {
+    /// The advice stack, whose last element is the top of the stack
+    stack: Vec<Felt>,
+    /// The entries of the advice map, with unique keys
+    map: Vec<(Word, Vec<Felt>)>,
+}
+
+impl MockAdviceProvider {
+    /// Creates a provider with an empty advice stack and advice map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes `values` onto the advice stack, such that the first of them is on top of the stack.
+    pub fn with_stack(mut self, values: impl IntoIterator<Item = Felt>) -> Self {
+        let values = values.into_iter().collect::<Vec<_>>();
+        self.push_stack(&values);
+        self
+    }
+
+    /// Inserts `values` in the advice map under `key`, replacing any previous entry for `key`.
+    pub fn with_map_entry(mut self, key: Word, values: impl IntoIterator<Item = Felt>) -> Self {
+        self.insert(key, values.into_iter().collect());
+        self
+    }
+
+    /// Returns the elements on the advice stack, from the top of the stack to its bottom.
+    pub fn stack(&self) -> impl Iterator<Item = Felt> + '_ {
+        self.stack.iter().rev().copied()
+    }
+
+    /// Returns the values stored in the advice map under `key`, if any.
+    pub fn get(&self, key: &Word) -> Option<&[Felt]> {
+        self.map
+            .iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, values)| values.as_slice())
+    }
+
+    /// Inserts `values` in the advice map under `key`, replacing any previous entry for `key`.
+    pub(super) fn insert(&mut self, key: Word, values: Vec<Felt>) {
+        self.map.retain(|(entry_key, _)| *entry_key != key);
+        self.map.push((key, values));
+    }
+
+    /// Pushes the values stored under `key` onto the advice stack, returning how many there were.
+    pub(super) fn push_mapval(&mut self, key: &Word) -> usize {
+        let values = self.get(key).expect("no entry for the key in the advice map").to_vec();
+        self.push_stack(&values);
+        values.len()
+    }
+
+    /// Pops the element on top of the advice stack.
+    pub(super) fn pop(&mut self) -> Felt {
+        self.stack.pop().expect("the advice stack is empty")
+    }
+
+    fn push_stack(&mut self, values: &[Felt]) {
+        self.stack.extend(values.iter().rev().copied());
+    }
+}
+
+/// Installs `provider` as the advice provider of the current thread, returning the previous one.
+pub fn set_mock_advice_provider(provider: MockAdviceProvider) -> MockAdviceProvider {
+    PROVIDER.with(|cell| cell.replace(provider))
+}
+
+/// Runs `f` with the advice provider of the current thread.
+pub(super) fn with_provider<R>(f: impl FnOnce(&mut MockAdviceProvider) -> R) -> R {
+    PROVIDER.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+/// An event emitted by the current thread when not targeting the Miden VM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmittedEvent {
+    /// The id of the event.
+    pub event_id: Felt,
+    /// The payload of the event, as stored in the advice map under its commitment, if the event
+    /// was emitted with one.
+    pub data: Option<Vec<Felt>>,
+}
+
+/// Returns the events emitted by the current thread since the last call, in emission order.
+pub fn take_emitted_events() -> Vec<EmittedEvent> {
+    EVENTS.with(|events| events.take())
+}
+
+/// Records an event emitted by the current thread.
+pub(super) fn record_event(event: EmittedEvent) {
+    EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_field_repr::{FromFeltRepr, ToFeltRepr};
+
+    use super::*;
+    use crate::intrinsics::advice::{adv_insert, adv_pop, adv_pop_word, adv_push_mapval, adv_read};
+
+    #[derive(Debug, PartialEq, Eq, FromFeltRepr, ToFeltRepr)]
+    struct Witness {
+        index: u32,
+        flag: bool,
+    }
+
+    fn felt(value: u64) -> Felt {
+        Felt::new(value).unwrap()
+    }
+
+    #[test]
+    fn adv_read_decodes_map_value() {
+        let key = Word::from([felt(1), felt(2), felt(3), felt(4)]);
+        let witness = Witness {
+            index: 9,
+            flag: true,
+        };
+        set_mock_advice_provider(
+            MockAdviceProvider::new()
+                .with_stack([felt(5)])
+                .with_map_entry(key, witness.to_felt_repr()),
+        );
+
+        adv_push_mapval(key);
+        assert_eq!(adv_read::<Witness>(2), witness);
+        assert_eq!(adv_pop(), felt(5));
+
+        let previous = set_mock_advice_provider(MockAdviceProvider::new());
+        assert_eq!(previous.stack().count(), 0);
+    }
+
+    #[test]
+    fn adv_insert_replaces_map_entry() {
+        let key = Word::from([felt(7), felt(0), felt(0), felt(0)]);
+        let word = Word::from([felt(1), felt(2), felt(3), felt(4)]);
+        set_mock_advice_provider(MockAdviceProvider::new().with_map_entry(key, [felt(9)]));
+
+        adv_insert(key, &[word]);
+        adv_push_mapval(key);
+        assert_eq!(adv_pop_word(), word);
+    }
+}
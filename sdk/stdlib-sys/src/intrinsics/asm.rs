@@ -0,0 +1,42 @@
+/// Splices raw Miden Assembly into the calling function, passing it the given local variables,
+/// and evaluating to its result, if any.
+///
+/// The arguments are placed on top of the operand stack, with the first argument on top, and the
+/// assembly must replace them with the result. The assembly is parsed when compiling to Miden
+/// Assembly, and may not invoke other procedures.
+///
+/// ```ignore
+/// let value = 0x00ff_ffffu32;
+/// let zeros = masm!("u32clz", (value: u32) -> u32);
+/// assert_eq!(zeros, 8);
+/// ```
+///
+/// Each distinct template is imported by the compiled Wasm module under its source, so a template
+/// must be used with the same signature everywhere.
+#[macro_export]
+macro_rules! masm {
+    ($template:literal, ($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?) => {{
+        #[cfg(all(target_family = "wasm", miden))]
+        let result = {
+            #[link(wasm_import_module = "miden:intrinsics/inline-asm")]
+            unsafe extern "C" {
+                #[link_name = $template]
+                fn inline_asm($($arg: $ty),*) $(-> $ret)?;
+            }
+            unsafe { inline_asm($($arg),*) }
+        };
+        #[cfg(not(all(target_family = "wasm", miden)))]
+        let result = {
+            let _ = ($($arg,)*);
+            $crate::intrinsics::asm::unsupported()
+        };
+        result
+    }};
+}
+
+/// The result of inline assembly when not targeting the Miden VM.
+#[doc(hidden)]
+#[cfg(not(all(target_family = "wasm", miden)))]
+pub fn unsupported<T>() -> T {
+    unimplemented!("inline assembly is only available when targeting the Miden VM")
+}
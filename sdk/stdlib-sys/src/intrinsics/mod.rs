@@ -8,6 +8,7 @@ pub use self::{
 };
 
 pub mod advice;
+pub mod asm;
 pub mod crypto;
 pub mod debug;
 pub mod felt;
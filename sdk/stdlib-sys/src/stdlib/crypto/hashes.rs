@@ -1,6 +1,14 @@
 //! Contains procedures for computing hashes using BLAKE3 and SHA256 hash
 //! functions. The input and output elements are assumed to contain one 32-bit
 //! value per element.
+//!
+//! When compiling natively, i.e. off-chain, the BLAKE3 and field element hashes are computed
+//! natively, so that code shared between on-chain and off-chain contexts computes the same
+//! digests.
+
+use alloc::vec::Vec;
+
+use crate::intrinsics::{Digest, Felt};
 
 #[cfg(all(target_family = "wasm", miden))]
 mod imp {
@@ -108,8 +116,7 @@ mod imp {
     }
 
     unsafe extern "C" {
-        /// Computes the hash of a sequence of field elements using the Rescue Prime Optimized (RPO)
-        /// hash function.
+        /// Computes the hash of a sequence of field elements using the Poseidon2 hash function.
         ///
         /// This maps to the `miden::core::crypto::hashes::poseidon2::hash_elements` procedure.
         ///
@@ -120,8 +127,7 @@ mod imp {
         #[link_name = "miden::core::crypto::hashes::poseidon2::hash_elements"]
         pub fn extern_hash_elements(ptr: u32, num_elements: u32, result_ptr: *mut Felt);
 
-        /// Computes the hash of a sequence of words using the Rescue Prime Optimized (RPO) hash
-        /// function.
+        /// Computes the hash of a sequence of words using the Poseidon2 hash function.
         ///
         /// This maps to the `miden::core::crypto::hashes::poseidon2::hash_words` procedure.
         ///
@@ -178,7 +184,7 @@ mod imp {
 
     /// Hashes a 32-byte input to a 32-byte output using the BLAKE3 hash function.
     #[inline]
-    pub fn blake3_hash(input: [u8; 32]) -> [u8; 32] {
+    pub fn blake3_hash_32(input: [u8; 32]) -> [u8; 32] {
         let lanes = bytes_to_u32_le_8(input);
         unsafe {
             let mut ret_area = ::core::mem::MaybeUninit::<WordAligned<[u8; 32]>>::uninit();
@@ -240,8 +246,7 @@ mod imp {
         }
     }
 
-    /// Computes the hash of a sequence of field elements using the Rescue Prime Optimized (RPO)
-    /// hash function.
+    /// Computes the hash of a sequence of field elements using the Poseidon2 hash function.
     ///
     /// This maps to the `miden::core::crypto::hashes::poseidon2::hash_elements` procedure and to the
     /// `miden::core::crypto::hashes::poseidon2::hash_words` word-optimized variant when the input
@@ -274,8 +279,7 @@ mod imp {
         }
     }
 
-    /// Computes the hash of a sequence of words using the Rescue Prime Optimized (RPO)
-    /// hash function.
+    /// Computes the hash of a sequence of words using the Poseidon2 hash function.
     ///
     /// This maps to the `miden::core::crypto::hashes::poseidon2::hash_words` procedure.
     ///
@@ -301,7 +305,79 @@ mod imp {
     }
 }
 
-#[cfg(not(all(target_family = "wasm", miden)))]
+#[cfg(not(target_family = "wasm"))]
+mod imp {
+    use alloc::vec::Vec;
+
+    use miden_core::crypto::hash::Poseidon2;
+
+    use crate::intrinsics::{Digest, Felt, Word};
+
+    /// Hashes a 32-byte input to a 32-byte output using the BLAKE3 hash function.
+    #[inline]
+    pub fn blake3_hash_32(input: [u8; 32]) -> [u8; 32] {
+        *blake3::hash(&input).as_bytes()
+    }
+
+    /// Hashes a 64-byte input to a 32-byte output using the BLAKE3 hash function.
+    #[inline]
+    pub fn blake3_merge(input: [u8; 64]) -> [u8; 32] {
+        *blake3::hash(&input).as_bytes()
+    }
+
+    /// Computes SHA256 1-to-1 hash.
+    #[inline]
+    pub fn sha256_hash(_input: [u8; 32]) -> [u8; 32] {
+        unimplemented!("SHA256 bindings are only available when targeting the Miden VM")
+    }
+
+    /// Computes SHA256 2-to-1 hash.
+    #[inline]
+    pub fn sha256_merge(_input: [u8; 64]) -> [u8; 32] {
+        unimplemented!("SHA256 bindings are only available when targeting the Miden VM")
+    }
+
+    /// Computes the hash of a sequence of field elements using the Poseidon2 hash function.
+    ///
+    /// This computes the same digest as the `miden::core::crypto::hashes::poseidon2::hash_elements`
+    /// procedure.
+    #[inline]
+    pub fn hash_elements(elements: Vec<Felt>) -> Digest {
+        let elements = elements
+            .iter()
+            .map(|felt| miden_core::Felt::new_unchecked(felt.as_canonical_u64()))
+            .collect::<Vec<_>>();
+        let digest = Poseidon2::hash_elements(&elements);
+        Digest::new(core::array::from_fn(|i| Felt::new(digest[i].as_canonical_u64()).unwrap()))
+    }
+
+    /// Computes the hash of a sequence of words using the Poseidon2 hash function.
+    ///
+    /// This computes the same digest as the `miden::core::crypto::hashes::poseidon2::hash_words`
+    /// procedure.
+    #[inline]
+    pub fn hash_words(words: &[Word]) -> Digest {
+        hash_elements(words.iter().flat_map(|word| [word[0], word[1], word[2], word[3]]).collect())
+    }
+
+    /// ABI helper for `miden::core::crypto::hashes::poseidon2::hash_elements`.
+    #[inline]
+    pub fn extern_hash_elements(_ptr: u32, _num_elements: u32, _result_ptr: *mut Felt) {
+        unimplemented!(
+            "miden::core::crypto::hashes bindings are only available when targeting the Miden VM"
+        )
+    }
+
+    /// ABI helper for `miden::core::crypto::hashes::poseidon2::hash_words`.
+    #[inline]
+    pub fn extern_hash_words(_start_addr: u32, _end_addr: u32, _result_ptr: *mut Felt) {
+        unimplemented!(
+            "miden::core::crypto::hashes bindings are only available when targeting the Miden VM"
+        )
+    }
+}
+
+#[cfg(all(target_family = "wasm", not(miden)))]
 mod imp {
     use alloc::vec::Vec;
 
@@ -309,7 +385,7 @@ mod imp {
 
     /// Computes BLAKE3 1-to-1 hash.
     #[inline]
-    pub fn blake3_hash(_input: [u8; 32]) -> [u8; 32] {
+    pub fn blake3_hash_32(_input: [u8; 32]) -> [u8; 32] {
         unimplemented!(
             "miden::core::crypto::hashes bindings are only available when targeting the Miden VM"
         )
@@ -339,8 +415,7 @@ mod imp {
         )
     }
 
-    /// Computes the hash of a sequence of field elements using the Rescue Prime Optimized (RPO)
-    /// hash function.
+    /// Computes the hash of a sequence of field elements using the Poseidon2 hash function.
     #[inline]
     pub fn hash_elements(_elements: Vec<Felt>) -> Digest {
         unimplemented!(
@@ -348,8 +423,7 @@ mod imp {
         )
     }
 
-    /// Computes the hash of a sequence of words using the Rescue Prime Optimized (RPO) hash
-    /// function.
+    /// Computes the hash of a sequence of words using the Poseidon2 hash function.
     #[inline]
     pub fn hash_words(_words: &[Word]) -> Digest {
         unimplemented!(
@@ -375,3 +449,45 @@ mod imp {
 }
 
 pub use imp::*;
+
+/// Computes the hash of a sequence of bytes using the Poseidon2 hash function, via
+/// [`hash_elements`].
+///
+/// The bytes are packed into field elements the same way on-chain and off-chain: the first element
+/// is the number of bytes, and each following element holds four bytes as a little-endian `u32`,
+/// the last one being padded with zero bytes.
+pub fn hash_bytes(bytes: &[u8]) -> Digest {
+    let mut elements = Vec::with_capacity(1 + bytes.len().div_ceil(4));
+    elements.push(Felt::new(bytes.len() as u64).unwrap());
+    elements.extend(bytes.chunks(4).map(|chunk| {
+        let mut lane = [0u8; 4];
+        lane[..chunk.len()].copy_from_slice(chunk);
+        Felt::new(u32::from_le_bytes(lane) as u64).unwrap()
+    }));
+    hash_elements(elements)
+}
+
+/// Hashes a byte slice of any length to a 32-byte output using the BLAKE3 hash function.
+///
+/// The core library only provides BLAKE3 over exactly 32 or 64 bytes, so a 32-byte input is hashed
+/// directly, and gets the standard BLAKE3 digest, while other inputs are hashed by chaining:
+/// starting from the digest of their length as a little-endian `u64` zero-padded to 32 bytes, each
+/// 32-byte chunk of the input, the last one zero-padded, is merged with the previous digest. The
+/// same construction is used on-chain and off-chain, but for inputs of any other length it is not
+/// the standard BLAKE3 digest of the bytes.
+pub fn blake3_hash(bytes: &[u8]) -> [u8; 32] {
+    if let Ok(block) = <[u8; 32]>::try_from(bytes) {
+        return blake3_hash_32(block);
+    }
+
+    let mut length = [0u8; 32];
+    length[..8].copy_from_slice(&(bytes.len() as u64).to_le_bytes());
+    let mut digest = blake3_hash_32(length);
+    for chunk in bytes.chunks(32) {
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(&digest);
+        block[32..32 + chunk.len()].copy_from_slice(chunk);
+        digest = blake3_merge(block);
+    }
+    digest
+}
@@ -12,6 +12,40 @@ pub extern "C" fn advice_adv_push_mapvaln_stub(
     unsafe { core::hint::unreachable_unchecked() }
 }
 
+#[unsafe(export_name = "intrinsics::advice::adv_push_mapval")]
+#[optimize(none)]
+#[inline(never)]
+pub extern "C" fn advice_adv_push_mapval_stub(_key0: f32, _key1: f32, _key2: f32, _key3: f32) {
+    unsafe { core::hint::unreachable_unchecked() }
+}
+
+#[unsafe(export_name = "intrinsics::advice::adv_pop")]
+#[optimize(none)]
+#[inline(never)]
+pub extern "C" fn advice_adv_pop_stub() -> f32 {
+    unsafe { core::hint::unreachable_unchecked() }
+}
+
+#[unsafe(export_name = "intrinsics::advice::emit")]
+#[optimize(none)]
+#[inline(never)]
+pub extern "C" fn advice_emit_stub(_event_id: f32) {
+    unsafe { core::hint::unreachable_unchecked() }
+}
+
+#[unsafe(export_name = "intrinsics::advice::emit_with_commitment")]
+#[optimize(none)]
+#[inline(never)]
+pub extern "C" fn advice_emit_with_commitment_stub(
+    _event_id: f32,
+    _c0: f32,
+    _c1: f32,
+    _c2: f32,
+    _c3: f32,
+) {
+    unsafe { core::hint::unreachable_unchecked() }
+}
+
 #[unsafe(export_name = "intrinsics::advice::emit_falcon_sig_to_stack")]
 #[optimize(none)]
 #[inline(never)]
@@ -0,0 +1,45 @@
+// Do not link against libstd (i.e. anything defined in `std::`)
+#![no_std]
+#![feature(alloc_error_handler)]
+
+// Global allocator to use heap memory in no-std environment
+#[global_allocator]
+static ALLOC: miden::BumpAlloc = miden::BumpAlloc::new();
+
+// Required for no-std crates
+#[cfg(not(test))]
+#[panic_handler]
+fn my_panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+// Required for no-std crates
+#[cfg(not(test))]
+#[alloc_error_handler]
+fn my_alloc_error(_info: core::alloc::Layout) -> ! {
+    loop {}
+}
+
+use bindings::exports::miden::cross_ctx_account_flags::*;
+
+miden::generate!();
+bindings::export!(MyFoo);
+
+use foo::Permissions;
+
+struct MyFoo;
+
+impl foo::Guest for MyFoo {
+    fn update(input: Permissions, set: Permissions, clear: Permissions) -> Permissions {
+        (input | set) - clear
+    }
+
+    fn contains_all(input: Permissions, other: Permissions) -> bool {
+        input.contains(other)
+    }
+
+    // Exposes the bits the account received, which must not include any bit past the last flag
+    fn bits(input: Permissions) -> u64 {
+        input.bits()
+    }
+}
@@ -0,0 +1,48 @@
+// Do not link against libstd (i.e. anything defined in `std::`)
+#![no_std]
+#![feature(alloc_error_handler)]
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+// Global allocator to use heap memory in no-std environment
+#[global_allocator]
+static ALLOC: miden::BumpAlloc = miden::BumpAlloc::new();
+
+// Required for no-std crates
+#[cfg(not(test))]
+#[panic_handler]
+fn my_panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+// Required for no-std crates
+#[cfg(not(test))]
+#[alloc_error_handler]
+fn my_alloc_error(_info: core::alloc::Layout) -> ! {
+    loop {}
+}
+
+use bindings::exports::miden::cross_ctx_account_string::*;
+
+miden::generate!();
+bindings::export!(MyFoo);
+
+struct MyFoo;
+
+impl foo::Guest for MyFoo {
+    fn byte_len(s: String) -> u32 {
+        s.len() as u32
+    }
+
+    fn shout(s: String) -> String {
+        let mut shouted = s.to_ascii_uppercase();
+        shouted.push('!');
+        shouted
+    }
+
+    fn reverse_bytes(mut bytes: Vec<u8>) -> Vec<u8> {
+        bytes.reverse();
+        bytes
+    }
+}
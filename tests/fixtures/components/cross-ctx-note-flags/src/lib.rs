@@ -0,0 +1,42 @@
+// Do not link against libstd (i.e. anything defined in `std::`)
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::*;
+
+use crate::bindings::miden::cross_ctx_account_flags::foo::*;
+
+#[note]
+struct MyNote;
+
+#[note]
+impl MyNote {
+    #[note_script]
+    pub fn execute(self, _arg: Word) {
+        let empty = Permissions::empty();
+        // One flag at each end of both limbs
+        let set =
+            Permissions::FLAG0 | Permissions::FLAG31 | Permissions::FLAG32 | Permissions::FLAG39;
+
+        let updated = update(empty, set, empty);
+        assert!(updated == set);
+        assert!(contains_all(updated, Permissions::FLAG32));
+        assert!(contains_all(updated, Permissions::FLAG0 | Permissions::FLAG39));
+        assert!(!contains_all(updated, Permissions::FLAG1));
+
+        let cleared = update(updated, empty, Permissions::FLAG31 | Permissions::FLAG39);
+        assert!(cleared == Permissions::FLAG0 | Permissions::FLAG32);
+        assert!(!contains_all(cleared, Permissions::FLAG39));
+        assert!(contains_all(cleared, Permissions::FLAG32));
+
+        // Setting and clearing the same flag clears it
+        let updated =
+            update(cleared, Permissions::FLAG20, Permissions::FLAG20 | Permissions::FLAG0);
+        assert!(updated == Permissions::FLAG32);
+
+        assert!(bits(Permissions::all()) == (1 << 40) - 1);
+        // The bits past the last flag are cleared when the account lifts the value
+        assert!(bits(Permissions::from_bits_retain(u64::MAX)) == (1 << 40) - 1);
+        assert!(bits(Permissions::from_bits_retain(1 << 40 | 1 << 63)) == 0);
+    }
+}
@@ -0,0 +1,42 @@
+// Do not link against libstd (i.e. anything defined in `std::`)
+#![no_std]
+#![feature(alloc_error_handler)]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use miden::*;
+
+use crate::bindings::miden::cross_ctx_account_string::foo::*;
+
+#[note]
+struct MyNote;
+
+#[note]
+impl MyNote {
+    #[note_script]
+    pub fn execute(self, _arg: Word) {
+        assert_eq(Felt::from(byte_len("")), felt!(0));
+        assert_eq(Felt::from(byte_len("hello, miden")), felt!(12));
+        // Spans several words of memory
+        let long = "the quick brown fox jumps over the lazy dog";
+        assert_eq(Felt::from(byte_len(long)), felt!(43));
+
+        let output = shout("");
+        assert!(output == "!");
+
+        let output = shout("hello, miden");
+        assert!(output == "HELLO, MIDEN!");
+
+        // Starts in the middle of a word of memory
+        let output = shout(&long[5..]);
+        assert!(output == "QUICK BROWN FOX JUMPS OVER THE LAZY DOG!");
+
+        let bytes: Vec<u8> = (0..37).collect();
+        let reversed = reverse_bytes(&bytes);
+        assert_eq(Felt::from(reversed.len() as u32), felt!(37));
+        for (i, byte) in reversed.iter().enumerate() {
+            assert_eq(Felt::from(*byte as u32), Felt::from(36 - i as u32));
+        }
+    }
+}
@@ -212,19 +212,10 @@ fn create_swapp_note(
 
 /// Extracts the note tag from the active note metadata.
 fn get_note_tag() -> Tag {
-    let metadata = active_note::get_metadata().header;
-    let left_shifted_32 = metadata[2] * Felt::new(2u64.pow(32)).unwrap();
-    let tag_felt = left_shifted_32 / (Felt::new(2u64.pow(32)).unwrap());
-    Tag::from(tag_felt)
+    active_note::get_metadata().tag()
 }
 
 /// Extracts the note type from the active note metadata.
 fn get_note_type() -> NoteType {
-    let metadata = active_note::get_metadata().header;
-    let second_felt = metadata[1];
-    let pow_56 = Felt::new(2u64.pow(56)).unwrap();
-    let pow_62 = Felt::new(2u64.pow(62)).unwrap();
-    let left_shifted_56 = second_felt * pow_56;
-    let note_type_felt = left_shifted_56 / pow_62;
-    NoteType::from(note_type_felt)
+    active_note::get_metadata().note_type()
 }
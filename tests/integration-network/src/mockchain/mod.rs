@@ -2,6 +2,9 @@
 
 mod counter;
 mod fpi;
+mod multi_slot_value;
 mod notes;
 mod sibling;
+mod storage_map;
 mod support;
+mod typed_storage;
@@ -0,0 +1,172 @@
+//! Multi-slot storage value test, storing a 12-felt struct across three storage slots.
+
+use miden_client::{
+    Word,
+    account::{
+        AccountComponent,
+        component::{BasicWallet, InitStorageData},
+    },
+    transaction::RawOutputNote,
+};
+use miden_core::Felt;
+use miden_protocol::account::{AccountBuilder, AccountType, StorageSlotName, auth::AuthScheme};
+use miden_testing::{AccountState, Auth, MockChain};
+
+use super::support::*;
+
+/// Deploys an account storing a vesting schedule of six `u64` fields in a
+/// `MultiSlotValue<VestingSchedule, 3>`, then consumes two notes in two transactions: the first
+/// stores the schedule, and the second reads it back and checks it.
+#[test]
+fn multi_slot_value_stores_struct_across_slots() {
+    let (vesting_package, note_package) =
+        build_account_and_note_packages("multi_slot_value", "vesting", VESTING_SOURCE, NOTE_SOURCE);
+
+    let vesting_component =
+        AccountComponent::from_package(&vesting_package, &InitStorageData::default()).unwrap();
+
+    let mut builder = MockChain::builder();
+    let account_builder = AccountBuilder::new([1_u8; 32])
+        .account_type(AccountType::Public)
+        .with_component(BasicWallet)
+        .with_component(vesting_component);
+    let account = builder
+        .add_account_from_builder(
+            Auth::BasicAuth {
+                auth_scheme: AuthScheme::Falcon512Poseidon2,
+            },
+            account_builder,
+            AccountState::Exists,
+        )
+        .expect("failed to add the vesting account to the mock chain builder");
+
+    let store_note = build_targeted_note(&note_package, account.id(), vec![Felt::ONE]);
+    let check_note = build_targeted_note(&note_package, account.id(), vec![Felt::new(2).unwrap()]);
+    builder.add_output_note(RawOutputNote::Full(store_note.clone()));
+    builder.add_output_note(RawOutputNote::Full(check_note.clone()));
+
+    let mut chain = builder.build().expect("failed to build mock chain");
+    chain.prove_next_block().unwrap();
+    chain.prove_next_block().unwrap();
+
+    let tx_context_builder =
+        chain.build_tx_context(account.clone(), &[store_note.id()], &[]).unwrap();
+    execute_tx(&mut chain, tx_context_builder);
+
+    // Each `u64` is represented by its low and high 32-bit limbs, and the 12 felts of the schedule
+    // fill the three slots in order
+    let felt = |value: u64| Felt::new(value).unwrap();
+    let expected_words = [
+        ("schedule", [felt(1_000), Felt::ZERO, felt(2_000), Felt::ZERO]),
+        ("schedule_1", [felt(5_000), Felt::ZERO, Felt::ZERO, felt(256)]),
+        ("schedule_2", [felt(3), Felt::ZERO, felt(100), Felt::ZERO]),
+    ];
+    for (field, expected) in expected_words {
+        let slot = StorageSlotName::new(format!("multi_slot_value_account::vesting::{field}"))
+            .expect("vesting storage slot name should be valid");
+        let word = chain
+            .committed_account(account.id())
+            .unwrap()
+            .storage()
+            .get_item(&slot)
+            .expect("failed to get the schedule word from storage");
+        assert_eq!(word, Word::new(expected), "unexpected word in slot {slot}");
+    }
+
+    let tx_context_builder =
+        chain.build_tx_context(account.clone(), &[check_note.id()], &[]).unwrap();
+    execute_tx(&mut chain, tx_context_builder);
+}
+
+/// Account component storing a 12-felt vesting schedule across three storage slots.
+const VESTING_SOURCE: &str = r#"
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::{
+    component, component_storage, felt, felt_repr as miden_field_repr, Felt, FromFeltRepr,
+    MultiSlotValue, ToFeltRepr,
+};
+
+/// Vesting schedule, whose felt representation is 12 felts long.
+#[derive(Clone, Copy, PartialEq, Eq, FromFeltRepr, ToFeltRepr)]
+struct VestingSchedule {
+    start: u64,
+    cliff: u64,
+    end: u64,
+    total: u64,
+    released: u64,
+    interval: u64,
+}
+
+/// The schedule stored by the first note, and checked by the second.
+const SCHEDULE: VestingSchedule = VestingSchedule {
+    start: 1_000,
+    cliff: 2_000,
+    end: 5_000,
+    total: 1 << 40,
+    released: 3,
+    interval: 100,
+};
+
+/// Storage of the vesting component.
+#[component_storage]
+struct VestingStorage {
+    /// The vesting schedule, spanning three storage slots.
+    #[storage(description = "vesting schedule")]
+    schedule: MultiSlotValue<VestingSchedule, 3>,
+}
+
+/// Account component holding a vesting schedule.
+#[component]
+trait Vesting {
+    /// Stores the schedule, returning 1 if no schedule was stored before, and 0 otherwise.
+    fn store_schedule(&mut self) -> Felt;
+    /// Returns 1 if the stored schedule is the expected one, and 0 otherwise.
+    fn check_schedule(&self) -> Felt;
+}
+
+#[component]
+impl Vesting for VestingStorage {
+    fn store_schedule(&mut self) -> Felt {
+        let previous = self.schedule.set(SCHEDULE);
+        if previous.total == 0 { felt!(1) } else { felt!(0) }
+    }
+
+    fn check_schedule(&self) -> Felt {
+        if self.schedule.get() == SCHEDULE { felt!(1) } else { felt!(0) }
+    }
+}
+"#;
+
+/// Note script storing or checking the schedule of the active account.
+const NOTE_SOURCE: &str = r#"
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::*;
+
+/// Native (active) account of the note: the vesting account.
+#[account(multi_slot_value_account::Vesting)]
+struct Account;
+
+/// Note storing the schedule of the vesting account, or checking it.
+#[note]
+struct VestingNote {
+    /// 1 to store the schedule, 2 to check it.
+    phase: Felt,
+}
+
+#[note]
+impl VestingNote {
+    /// Runs the phase of the test selected by the note storage.
+    #[note_script]
+    pub fn run(self, _arg: Word, account: &mut Account) {
+        if self.phase == felt!(1) {
+            assert_eq(account.store_schedule(), felt!(1));
+        } else {
+            assert_eq(account.check_schedule(), felt!(1));
+        }
+    }
+}
+"#;
@@ -0,0 +1,197 @@
+//! Storage map test removing keys and checking their presence, within one transaction and across
+//! two transactions.
+
+use miden_client::{
+    Word,
+    account::{
+        AccountComponent,
+        component::{BasicWallet, InitStorageData},
+    },
+    transaction::RawOutputNote,
+};
+use miden_core::Felt;
+use miden_protocol::account::{
+    AccountBuilder, AccountId, AccountType, StorageSlotName, auth::AuthScheme,
+};
+use miden_testing::{AccountState, Auth, MockChain};
+
+use super::support::*;
+
+/// Deploys an account with a `StorageMap<Word, Felt>`, then consumes two notes in two
+/// transactions:
+///
+/// - the first sets, checks and removes key A, and sets key B;
+/// - the second checks and removes key B, which the first transaction committed.
+#[test]
+fn storage_map_remove_and_contains_key() {
+    let (store_package, note_package) = build_account_and_note_packages(
+        "storage_map",
+        "key-value-store",
+        KEY_VALUE_STORE_SOURCE,
+        NOTE_SOURCE,
+    );
+
+    let store_component =
+        AccountComponent::from_package(&store_package, &InitStorageData::default()).unwrap();
+
+    let mut builder = MockChain::builder();
+    let account_builder = AccountBuilder::new([1_u8; 32])
+        .account_type(AccountType::Public)
+        .with_component(BasicWallet)
+        .with_component(store_component);
+    let account = builder
+        .add_account_from_builder(
+            Auth::BasicAuth {
+                auth_scheme: AuthScheme::Falcon512Poseidon2,
+            },
+            account_builder,
+            AccountState::Exists,
+        )
+        .expect("failed to add the key-value store account to the mock chain builder");
+
+    let first_note = build_targeted_note(&note_package, account.id(), vec![Felt::ONE]);
+    let second_note = build_targeted_note(&note_package, account.id(), vec![Felt::new(2).unwrap()]);
+    builder.add_output_note(RawOutputNote::Full(first_note.clone()));
+    builder.add_output_note(RawOutputNote::Full(second_note.clone()));
+
+    let mut chain = builder.build().expect("failed to build mock chain");
+    chain.prove_next_block().unwrap();
+    chain.prove_next_block().unwrap();
+
+    let slot = StorageSlotName::new("storage_map_account::key_value_store::entries")
+        .expect("key-value store storage slot name should be valid");
+
+    let tx_context_builder =
+        chain.build_tx_context(account.clone(), &[first_note.id()], &[]).unwrap();
+    execute_tx(&mut chain, tx_context_builder);
+    assert_entry(&chain, account.id(), &slot, key_a(), 0);
+    assert_entry(&chain, account.id(), &slot, key_b(), 7);
+
+    let tx_context_builder =
+        chain.build_tx_context(account.clone(), &[second_note.id()], &[]).unwrap();
+    execute_tx(&mut chain, tx_context_builder);
+    assert_entry(&chain, account.id(), &slot, key_a(), 0);
+    assert_entry(&chain, account.id(), &slot, key_b(), 0);
+}
+
+/// Asserts the felt committed under `key` by `account`, where removed keys hold the empty word.
+fn assert_entry(
+    chain: &MockChain,
+    account: AccountId,
+    slot: &StorageSlotName,
+    key: Word,
+    expected: u64,
+) {
+    let word = chain
+        .committed_account(account)
+        .unwrap()
+        .storage()
+        .get_map_item(slot, key)
+        .expect("failed to get the entry from storage");
+    let expected = Word::new([Felt::new(expected).unwrap(), Felt::ZERO, Felt::ZERO, Felt::ZERO]);
+    assert_eq!(word, expected, "unexpected value stored under {key:?}");
+}
+
+/// Key which is set and removed within the first transaction.
+fn key_a() -> Word {
+    Word::new([Felt::ONE, Felt::ZERO, Felt::ZERO, Felt::ZERO])
+}
+
+/// Key which is set in the first transaction, and removed in the second.
+fn key_b() -> Word {
+    Word::new([Felt::new(2).unwrap(), Felt::ZERO, Felt::ZERO, Felt::ZERO])
+}
+
+/// Account component exposing the storage map operations over a `Word -> Felt` map.
+const KEY_VALUE_STORE_SOURCE: &str = r#"
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::{component, component_storage, felt, Felt, StorageMap, Word};
+
+/// Storage of the key-value store component.
+#[component_storage]
+struct KeyValueStoreStorage {
+    /// Storage map holding the entries of the store.
+    #[storage(description = "key-value store entries")]
+    entries: StorageMap,
+}
+
+/// Account component storing felts under words.
+#[component]
+trait KeyValueStore {
+    /// Sets the value of `key`, returning its previous value.
+    fn insert(&mut self, key: Word, value: Felt) -> Felt;
+    /// Removes `key`, returning its previous value.
+    fn remove(&mut self, key: Word) -> Felt;
+    /// Returns 1 if the store holds a value for `key`, and 0 otherwise.
+    fn contains(&self, key: Word) -> Felt;
+    /// Returns the value of `key`, or `default` if the store holds no value for it.
+    fn get_or(&self, key: Word, default: Felt) -> Felt;
+}
+
+#[component]
+impl KeyValueStore for KeyValueStoreStorage {
+    fn insert(&mut self, key: Word, value: Felt) -> Felt {
+        self.entries.set(key, value)
+    }
+
+    fn remove(&mut self, key: Word) -> Felt {
+        self.entries.remove(key)
+    }
+
+    fn contains(&self, key: Word) -> Felt {
+        if self.entries.contains_key(key) { felt!(1) } else { felt!(0) }
+    }
+
+    fn get_or(&self, key: Word, default: Felt) -> Felt {
+        self.entries.get_or(key, default)
+    }
+}
+"#;
+
+/// Note script running one phase of the test against the active account.
+const NOTE_SOURCE: &str = r#"
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::*;
+
+/// Native (active) account of the note: the key-value store account.
+#[account(storage_map_account::KeyValueStore)]
+struct Account;
+
+/// Note running one phase of the storage map test.
+#[note]
+struct StorageMapNote {
+    /// 1 to set, check and remove key A, and set key B; 2 to check and remove key B.
+    phase: Felt,
+}
+
+#[note]
+impl StorageMapNote {
+    /// Runs the phase of the test selected by the note storage.
+    #[note_script]
+    pub fn run(self, _arg: Word, account: &mut Account) {
+        let key_a = Word::new([felt!(1), felt!(0), felt!(0), felt!(0)]);
+        let key_b = Word::new([felt!(2), felt!(0), felt!(0), felt!(0)]);
+
+        if self.phase == felt!(1) {
+            assert_eq(account.contains(key_a), felt!(0));
+            assert_eq(account.insert(key_a, felt!(5)), felt!(0));
+            assert_eq(account.contains(key_a), felt!(1));
+            assert_eq(account.get_or(key_a, felt!(9)), felt!(5));
+            assert_eq(account.remove(key_a), felt!(5));
+            assert_eq(account.contains(key_a), felt!(0));
+            assert_eq(account.get_or(key_a, felt!(9)), felt!(9));
+
+            assert_eq(account.insert(key_b, felt!(7)), felt!(0));
+        } else {
+            assert_eq(account.contains(key_b), felt!(1));
+            assert_eq(account.get_or(key_b, felt!(9)), felt!(7));
+            assert_eq(account.remove(key_b), felt!(7));
+            assert_eq(account.contains(key_b), felt!(0));
+        }
+    }
+}
+"#;
@@ -8,7 +8,7 @@ use miden_client::{
     asset::FungibleAsset,
     auth::AuthSecretKey,
     crypto::FeltRng,
-    note::{Note, NoteType},
+    note::{Note, NoteTag, NoteType},
     transaction::RawOutputNote,
 };
 use miden_core::Felt;
@@ -19,6 +19,7 @@ use miden_protocol::{
         AccountStorage, AccountType, StorageSlot, StorageSlotName,
     },
     asset::{Asset, AssetAmount},
+    crypto::rand::RandomCoin,
     note::{NoteScript, PartialNote},
     transaction::{TransactionMeasurements, TransactionScript},
 };
@@ -28,9 +29,14 @@ use miden_standards::{
 };
 use miden_testing::{MockChain, TransactionContextBuilder};
 use midenc_frontend_wasm::WasmTranslationConfig;
-use midenc_integration_test_support::CompilerTestBuilder;
+use midenc_integration_test_support::{CompilerTestBuilder, project};
 use rand::{SeedableRng, rngs::StdRng};
 
+use super::projects::{
+    account_cargo_toml_for, account_miden_project_toml_with_interface,
+    note_cargo_toml_for_dependency, note_miden_project_toml_for_dependency,
+};
+
 /// Converts a value's felt representation into `miden_core::Felt` elements.
 pub(crate) fn to_core_felts(value: &AccountId) -> Vec<Felt> {
     vec![value.prefix().as_felt(), value.suffix()]
@@ -87,6 +93,76 @@ pub(crate) fn compile_rust_package(project_path: impl AsRef<Path>, release: bool
     package
 }
 
+/// Builds and compiles an account component project exporting `interface`, and a note project
+/// depending on it, named after `test_name`.
+///
+/// Returns the account component package, followed by the note package.
+pub(crate) fn build_account_and_note_packages(
+    test_name: &str,
+    interface: &str,
+    account_source: &str,
+    note_source: &str,
+) -> (Arc<Package>, Arc<Package>) {
+    let base_name = test_name.replace('_', "-");
+    let account_name = format!("{base_name}-account");
+    let account_package = format!("miden:{account_name}");
+    let note_name = format!("{base_name}-note");
+    let note_package = format!("miden:{note_name}");
+
+    let account_project = project(&account_name)
+        .file(
+            "miden-project.toml",
+            &account_miden_project_toml_with_interface(&account_name, &account_package, interface),
+        )
+        .file("Cargo.toml", &account_cargo_toml_for(&account_name, &account_package))
+        .file("src/lib.rs", account_source)
+        .build();
+    let account_package_artifact = compile_rust_package(account_project.root(), true);
+
+    let account_root = account_project.root();
+    let note_project = project(&note_name)
+        .file(
+            "miden-project.toml",
+            &note_miden_project_toml_for_dependency(
+                &note_name,
+                &note_package,
+                &account_package,
+                account_root.as_path(),
+            ),
+        )
+        .file(
+            "Cargo.toml",
+            &note_cargo_toml_for_dependency(
+                &note_name,
+                &note_package,
+                &account_package,
+                account_root.as_path(),
+            ),
+        )
+        .file("src/lib.rs", note_source)
+        .build();
+    let note_package_artifact = compile_rust_package(note_project.root(), true);
+
+    (account_package_artifact, note_package_artifact)
+}
+
+/// Builds a note from the note script `package` targeting the account `target`, with `storage`
+/// as its note storage.
+pub(crate) fn build_targeted_note(
+    package: &Package,
+    target: AccountId,
+    storage: Vec<Felt>,
+) -> Note {
+    let rng = RandomCoin::new(note_script_root(package));
+    NoteBuilder::new(target, rng)
+        .package(package.clone())
+        .note_storage(storage)
+        .unwrap()
+        .tag(NoteTag::with_account_target(target).into())
+        .build()
+        .unwrap()
+}
+
 /// Returns the root of the note script exported by the compiled package.
 pub(crate) fn note_script_root(package: &Package) -> Word {
     NoteScript::from_package(package)
@@ -0,0 +1,154 @@
+//! Typed storage map test, storing a derived struct under a derived key through their felt
+//! representation.
+
+use miden_client::{
+    Word,
+    account::{
+        AccountComponent,
+        component::{BasicWallet, InitStorageData},
+    },
+    transaction::RawOutputNote,
+};
+use miden_core::Felt;
+use miden_protocol::account::{AccountBuilder, AccountType, StorageSlotName, auth::AuthScheme};
+use miden_testing::{AccountState, Auth, MockChain};
+
+use super::support::*;
+
+/// Deploys an account storing player positions in a `StorageMap<PlayerId, Position>`, then
+/// consumes a note which moves a player twice. The component checks the typed values it reads
+/// back, and the test checks the words they were stored as.
+#[test]
+fn typed_storage_map_stores_derived_struct() {
+    let (registry_package, note_package) = build_account_and_note_packages(
+        "typed_storage",
+        "position-registry",
+        POSITION_REGISTRY_SOURCE,
+        NOTE_SOURCE,
+    );
+
+    let registry_component =
+        AccountComponent::from_package(&registry_package, &InitStorageData::default()).unwrap();
+
+    let mut builder = MockChain::builder();
+    let account_builder = AccountBuilder::new([1_u8; 32])
+        .account_type(AccountType::Public)
+        .with_component(BasicWallet)
+        .with_component(registry_component);
+    let account = builder
+        .add_account_from_builder(
+            Auth::BasicAuth {
+                auth_scheme: AuthScheme::Falcon512Poseidon2,
+            },
+            account_builder,
+            AccountState::Exists,
+        )
+        .expect("failed to add the position registry account to the mock chain builder");
+
+    let note = build_targeted_note(&note_package, account.id(), vec![]);
+    builder.add_output_note(RawOutputNote::Full(note.clone()));
+
+    let mut chain = builder.build().expect("failed to build mock chain");
+    chain.prove_next_block().unwrap();
+    chain.prove_next_block().unwrap();
+
+    let tx_context_builder = chain.build_tx_context(account.clone(), &[note.id()], &[]).unwrap();
+    execute_tx(&mut chain, tx_context_builder);
+
+    // `PlayerId { id: 7 }` is stored under `[7, 0, 0, 0]`, and the last position of the player,
+    // `Position { x: 4, y: 5, active: true }`, as `[4, 5, 1, 0]`
+    let slot = StorageSlotName::new("typed_storage_account::position_registry::positions")
+        .expect("position registry storage slot name should be valid");
+    let key = Word::new([Felt::new(7).unwrap(), Felt::ZERO, Felt::ZERO, Felt::ZERO]);
+    let expected = Word::new([Felt::new(4).unwrap(), Felt::new(5).unwrap(), Felt::ONE, Felt::ZERO]);
+    let stored = chain
+        .committed_account(account.id())
+        .unwrap()
+        .storage()
+        .get_map_item(&slot, key)
+        .expect("failed to get the player position from storage");
+    assert_eq!(stored, expected);
+}
+
+/// Account component storing a derived struct for each player in a typed storage map.
+const POSITION_REGISTRY_SOURCE: &str = r#"
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::{
+    component, component_storage, felt, felt_repr as miden_field_repr, Felt, FromFeltRepr,
+    StorageMap, StorageRepr, ToFeltRepr,
+};
+
+/// Key of the position of a player.
+#[derive(Clone, Copy, FromFeltRepr, ToFeltRepr)]
+struct PlayerId {
+    id: u32,
+}
+
+impl StorageRepr for PlayerId {}
+
+/// Position of a player, and whether it is still in play.
+#[derive(Clone, Copy, PartialEq, Eq, FromFeltRepr, ToFeltRepr)]
+struct Position {
+    x: u32,
+    y: u32,
+    active: bool,
+}
+
+impl StorageRepr for Position {}
+
+/// Storage of the position registry component.
+#[component_storage]
+struct PositionRegistryStorage {
+    /// Storage map holding the position of each player.
+    #[storage(description = "player positions")]
+    positions: StorageMap<PlayerId, Position>,
+}
+
+/// Account component recording the position of players.
+#[component]
+trait PositionRegistry {
+    /// Moves `player` to (`x`, `y`), returning 1 if it had a position before, and 0 otherwise.
+    fn move_player(&mut self, player: u32, x: u32, y: u32) -> Felt;
+}
+
+#[component]
+impl PositionRegistry for PositionRegistryStorage {
+    fn move_player(&mut self, player: u32, x: u32, y: u32) -> Felt {
+        let player = PlayerId { id: player };
+        let previous = self.positions.try_get(player);
+        let position = Position { x, y, active: true };
+        self.positions.set(player, position);
+        assert!(self.positions.try_get(player) == Some(position));
+        assert!(self.positions.get(player) == position);
+        if previous.is_some() { felt!(1) } else { felt!(0) }
+    }
+}
+"#;
+
+/// Note script which moves a player of the active account twice.
+const NOTE_SOURCE: &str = r#"
+#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::*;
+
+/// Native (active) account of the note: the position registry account.
+#[account(typed_storage_account::PositionRegistry)]
+struct Account;
+
+/// Input-less note moving a player of the position registry.
+#[note]
+struct MovePlayerNote;
+
+#[note]
+impl MovePlayerNote {
+    /// Places player 7 at (3, 5), then moves it to (4, 5).
+    #[note_script]
+    pub fn run(self, _arg: Word, account: &mut Account) {
+        assert_eq(account.move_player(7, 3, 5), felt!(0));
+        assert_eq(account.move_player(7, 4, 5), felt!(1));
+    }
+}
+"#;
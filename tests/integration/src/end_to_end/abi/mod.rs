@@ -1,4 +1,5 @@
 mod arrays;
 mod enums;
 mod functions;
+mod overflow_arguments;
 mod transforms;
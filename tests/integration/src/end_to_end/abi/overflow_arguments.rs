@@ -0,0 +1,106 @@
+//! Calls to functions whose flattened parameters do not fit on the operand stack, which pass the
+//! arguments which do not fit in memory.
+use miden_core::Felt;
+use midenc_frontend_wasm::WasmTranslationConfig;
+
+use crate::{
+    CompilerTest,
+    testing::{eval_package, setup},
+};
+
+#[test]
+fn call_with_20_felt_arguments() {
+    let main_fn = r#"
+        (seed: Felt) -> Felt {
+            let f = |i: u32| seed + miden_stdlib_sys::felt!(i);
+            callee_20(
+                f(1), f(2), f(3), f(4), f(5), f(6), f(7), f(8), f(9), f(10),
+                f(11), f(12), f(13), f(14), f(15), f(16), f(17), f(18), f(19), f(20),
+            )
+        }
+
+        // Weight each argument by its position, so that arguments passed out of order are
+        // detected
+        #[inline(never)]
+        fn callee_20(
+            a1: Felt, a2: Felt, a3: Felt, a4: Felt, a5: Felt, a6: Felt, a7: Felt, a8: Felt,
+            a9: Felt, a10: Felt, a11: Felt, a12: Felt, a13: Felt, a14: Felt, a15: Felt, a16: Felt,
+            a17: Felt, a18: Felt, a19: Felt, a20: Felt,
+        ) -> Felt {
+            let args = [
+                a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11, a12, a13, a14, a15, a16, a17, a18,
+                a19, a20,
+            ];
+            let mut sum = miden_stdlib_sys::felt!(0);
+            let mut i = 0;
+            while i < args.len() {
+                sum = sum + args[i] * miden_stdlib_sys::felt!((i + 1) as u32);
+                i += 1;
+            }
+            sum
+        }
+    "#;
+
+    setup::enable_compiler_instrumentation();
+    let config = WasmTranslationConfig::default();
+    let mut test =
+        CompilerTest::rust_fn_body_with_stdlib_sys("overflow_arguments_20", main_fn, config, []);
+    let package = test.compile_package();
+
+    let seed = 100u32;
+    let expected =
+        (1u32..=20).fold(Felt::ZERO, |sum, i| sum + Felt::from(seed + i) * Felt::from(i));
+    eval_package::<Felt, _, _>(&package, [], &[Felt::from(seed)], &test.session, |trace| {
+        let res: Felt = trace.parse_result().unwrap();
+        assert_eq!(res, expected);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn call_with_32_felt_arguments() {
+    // Each `u64` is passed as two felts
+    let main_fn = r#"
+        (seed: u32) -> u32 {
+            let f = |i: u64| ((seed as u64) << 32) | i;
+            let res = callee_32(
+                f(1), f(2), f(3), f(4), f(5), f(6), f(7), f(8),
+                f(9), f(10), f(11), f(12), f(13), f(14), f(15), f(16),
+            );
+            (res >> 32) as u32 ^ res as u32
+        }
+
+        #[inline(never)]
+        fn callee_32(
+            a1: u64, a2: u64, a3: u64, a4: u64, a5: u64, a6: u64, a7: u64, a8: u64,
+            a9: u64, a10: u64, a11: u64, a12: u64, a13: u64, a14: u64, a15: u64, a16: u64,
+        ) -> u64 {
+            let args = [a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11, a12, a13, a14, a15, a16];
+            let mut sum = 0u64;
+            let mut i = 0;
+            while i < args.len() {
+                sum = sum.wrapping_add(args[i].wrapping_mul(i as u64 + 1));
+                i += 1;
+            }
+            sum
+        }
+    "#;
+
+    setup::enable_compiler_instrumentation();
+    let config = WasmTranslationConfig::default();
+    let mut test =
+        CompilerTest::rust_fn_body_with_stdlib_sys("overflow_arguments_32", main_fn, config, []);
+    let package = test.compile_package();
+
+    let seed = 7u32;
+    let sum = (1u64..=16)
+        .fold(0u64, |sum, i| sum.wrapping_add((((seed as u64) << 32) | i).wrapping_mul(i)));
+    let expected = (sum >> 32) as u32 ^ sum as u32;
+    eval_package::<u32, _, _>(&package, [], &[Felt::from(seed)], &test.session, |trace| {
+        let res: u32 = trace.parse_result().unwrap();
+        assert_eq!(res, expected);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,112 @@
+use miden_debug::{DebugQuery, ExecutionTrace};
+use midenc_frontend_wasm::WasmTranslationConfig;
+use midenc_hir::Felt;
+use proptest::{prop_assert_eq, test_runner::TestCaseError};
+
+use crate::{
+    CompilerTest,
+    testing::{Initializer, eval_package},
+};
+
+/// The byte slices hashed by the tests below: empty input, a single byte, a single word of packed
+/// elements (the length and 3 lanes), exactly 32 bytes, 40 bytes packed into 11 elements spanning
+/// 3 words, and 100 bytes spanning several 32-byte chunks
+fn inputs() -> [Vec<u8>; 6] {
+    [
+        vec![],
+        vec![0xab],
+        (1..=12).collect(),
+        (0..32).map(|i| i * 5 + 1).collect(),
+        (0..40).map(|i| i * 7 + 3).collect(),
+        (0..100).map(|i| i * 3 + 11).collect(),
+    ]
+}
+
+/// Compiles `main_fn`, which takes a `Vec<u8>` and returns its digest, then hashes each of
+/// [`inputs`] on-chain, and calls `check` with the trace, the address of the digest, and the input
+fn hash_inputs_on_chain<F>(name: &'static str, main_fn: &str, check: F)
+where
+    F: Fn(&ExecutionTrace, u32, &[u8]) -> Result<(), TestCaseError>,
+{
+    let config = WasmTranslationConfig::default();
+    let mut test = CompilerTest::rust_fn_body_with_stdlib_sys(
+        name,
+        main_fn,
+        config,
+        ["--test-harness".into()],
+    );
+
+    let package = test.compile_package();
+
+    for bytes in inputs() {
+        let wide_ptr_addr = 20u32 * 65536;
+        let out_addr = 21u32 * 65536;
+        // The bytes are placed just past the `Vec` header: capacity, pointer and length
+        let header = [
+            Felt::from(bytes.len() as u32),
+            Felt::from(wide_ptr_addr + 16),
+            Felt::from(bytes.len() as u32),
+            Felt::ZERO,
+        ];
+        let initializers = [
+            Initializer::MemoryFelts {
+                addr: wide_ptr_addr / 4,
+                felts: (&header[..]).into(),
+            },
+            Initializer::MemoryBytes {
+                addr: wide_ptr_addr + 16,
+                bytes: &bytes,
+            },
+        ];
+
+        // The generated `entrypoint` uses the `(out_ptr, in_ptr)` convention.
+        let args =
+            [Felt::new_unchecked(out_addr as u64), Felt::new_unchecked(wide_ptr_addr as u64)];
+        eval_package::<Felt, _, _>(&package, initializers, &args, &test.session, |trace| {
+            check(trace, out_addr, &bytes)
+        })
+        .unwrap_or_else(|err| panic!("failed to hash {} bytes on-chain: {err}", bytes.len()));
+    }
+}
+
+/// Hashes byte slices on-chain with `hash_bytes`, and checks the digests match those computed
+/// off-chain by the same SDK function.
+#[test]
+fn hash_bytes() {
+    let main_fn = r#"
+	    (input: alloc::vec::Vec<u8>) -> miden_stdlib_sys::Word {
+	        miden_stdlib_sys::hash_bytes(&input).inner
+	    }"#;
+    hash_inputs_on_chain("hash_bytes", main_fn, |trace, out_addr, bytes| {
+        let digest = miden_stdlib_sys::hash_bytes(bytes);
+        let expected: [Felt; 4] =
+            core::array::from_fn(|i| Felt::new_unchecked(digest.inner[i].as_canonical_u64()));
+        let vm_digest: [miden_debug::Felt; 4] = trace
+            .read_from_rust_memory(out_addr)
+            .expect("expected the digest to have been written");
+        let vm_digest = vm_digest.map(|felt| felt.0);
+        prop_assert_eq!(vm_digest, expected, "digest mismatch for {} bytes", bytes.len());
+        Ok(())
+    });
+}
+
+/// Hashes byte slices on-chain with `blake3_hash`, and checks the digests match those computed
+/// off-chain by the same SDK function, which is the standard BLAKE3 digest for 32-byte inputs.
+#[test]
+fn blake3_hash_bytes() {
+    let main_fn = r#"
+	    (input: alloc::vec::Vec<u8>) -> [u8; 32] {
+	        miden_stdlib_sys::blake3_hash(&input)
+	    }"#;
+    hash_inputs_on_chain("blake3_hash_bytes", main_fn, |trace, out_addr, bytes| {
+        let expected = miden_stdlib_sys::blake3_hash(bytes);
+        if bytes.len() == 32 {
+            assert_eq!(&expected, blake3::hash(bytes).as_bytes(), "off-chain digest mismatch");
+        }
+        let vm_digest: [u8; 32] = trace
+            .read_from_rust_memory(out_addr)
+            .expect("expected the digest to have been written");
+        prop_assert_eq!(vm_digest, expected, "digest mismatch for {} bytes", bytes.len());
+        Ok(())
+    });
+}
@@ -1,3 +1,4 @@
+mod hash_bytes;
 mod hash_elements;
 mod hash_words;
 mod pipe_double_words_to_memory;
@@ -0,0 +1,59 @@
+use std::rc::Rc;
+
+use midenc_frontend_wasm::{ImportMap, WasmTranslationConfig};
+use midenc_hir::Context;
+
+use crate::CompilerTestBuilder;
+
+#[test]
+fn custom_kernel_abi() {
+    // Map the `tx::get_block_number` binding to a procedure of a renamed, fake kernel.
+    let kernel_abi = r#"
+[[imports]]
+module = "miden::protocol::tx"
+name = "get_block_number"
+library = "fake_kernel::tx"
+procedure = "block_height"
+params = []
+results = ["felt"]
+adaptor = "no-transform"
+"#;
+    let masm = r#"
+pub proc block_height
+    # Stack input: []
+    # Stack output: [block_height]
+    push.7
+end
+"#;
+
+    let wasm = wat::parse_str(
+        r#"(module
+  (func $miden::protocol::tx::get_block_number (result f32)
+    unreachable
+  )
+  (func $block_number (export "block_number") (result f32)
+    call $miden::protocol::tx::get_block_number
+  )
+)"#,
+    )
+    .expect("failed to parse WAT module");
+
+    let config = WasmTranslationConfig {
+        import_map: ImportMap::from_toml(&Rc::new(Context::default()), kernel_abi)
+            .expect("invalid kernel ABI")
+            .into(),
+        ..Default::default()
+    };
+    let mut builder = CompilerTestBuilder::from_wasm("custom_kernel_abi", wasm, []);
+    builder
+        .with_wasm_translation_config(config)
+        .link_with_masm_module("fake_kernel::tx", masm);
+    let mut test = builder.build();
+
+    let masm = test.masm_src();
+    assert!(
+        masm.lines()
+            .any(|line| line.contains("exec.") && line.contains("fake_kernel::tx::block_height")),
+        "expected a call to the mapped kernel procedure:\n{masm}"
+    );
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+
+use miden_core::{Felt, Word, crypto::hash::Poseidon2, events::EventName, field::PrimeField64};
+use miden_core_lib::CoreLibrary;
+use miden_debug::DebugQuery;
+use miden_processor::{ProcessorState, advice::AdviceMutation, event::EventError};
+use midenc_frontend_wasm::WasmTranslationConfig;
+
+use crate::{CompilerTestBuilder, testing::executor_with_std};
+
+/// An event observed by the host: its id, and the word below it on the operand stack, which is
+/// the payload commitment for events emitted with data.
+type ObservedEvent = (Felt, Word);
+
+#[test]
+fn emitted_events_reach_the_host_with_payload_in_advice_map() {
+    let main_fn = r#"(event_id: Felt, event_with_data_id: Felt) -> Felt {
+        tx::emit_event(event_id);
+        let payload = alloc::vec![felt!(50), felt!(9), felt!(1), felt!(0), felt!(7), felt!(3)];
+        tx::emit_event_with_data(event_with_data_id, &payload);
+
+        // The payload is `[len, elements...]`, zero-padded to two words
+        let expected = [
+            felt!(6), felt!(50), felt!(9), felt!(1),
+            felt!(0), felt!(7), felt!(3), felt!(0),
+        ];
+        let words = [
+            Word::from([expected[0], expected[1], expected[2], expected[3]]),
+            Word::from([expected[4], expected[5], expected[6], expected[7]]),
+        ];
+        let commitment = Word::from(hash_words(&words));
+        let num_felts = intrinsics::advice::adv_push_mapvaln(commitment.clone());
+        let loaded = adv_load_preimage(felt!(2), commitment);
+        for i in 0..8 {
+            assert_eq(loaded[i], expected[i]);
+        }
+        num_felts
+    }"#
+    .to_string();
+
+    let config = WasmTranslationConfig::default();
+    let mut test = CompilerTestBuilder::rust_fn_body_with_sdk(
+        "abi_transform_tx_kernel_emit_event",
+        &main_fn,
+        config,
+        [],
+    )
+    .build();
+
+    let package = test.compile_package();
+
+    let event = EventName::new("miden::compiler::test::plain_event");
+    let event_with_data = EventName::new("miden::compiler::test::event_with_data");
+    let event_id = event.to_event_id().as_felt();
+    let event_with_data_id = event_with_data.to_event_id().as_felt();
+
+    let observed = Arc::new(Mutex::new(Vec::<ObservedEvent>::new()));
+    let mut exec = executor_with_std(vec![event_id, event_with_data_id], Some(&package));
+    for (event, handler) in CoreLibrary::default().handlers() {
+        exec.register_event_handler(event, handler)
+            .expect("failed to register core library event handler");
+    }
+    for name in [event, event_with_data] {
+        let observed = Arc::clone(&observed);
+        let handler = move |process: &ProcessorState| -> Result<Vec<AdviceMutation>, EventError> {
+            let id = process.get_stack_item(0);
+            let word = Word::new(core::array::from_fn(|i| process.get_stack_item(i + 1)));
+            observed.lock().unwrap().push((id, word));
+            Ok(Vec::new())
+        };
+        exec.register_event_handler(name, Arc::new(handler))
+            .expect("failed to register test event handler");
+    }
+
+    let trace = exec.execute(&package.unwrap_program(), test.session.source_manager.clone());
+    let num_felts: Felt = trace.parse_result().expect("expected a result");
+    assert_eq!(num_felts.as_canonical_u64(), 8, "payload not found in the advice map");
+
+    let payload = [6u32, 50, 9, 1, 0, 7, 3, 0].map(Felt::from);
+    let expected_commitment = Poseidon2::hash_elements(&payload);
+
+    let observed = observed.lock().unwrap();
+    assert_eq!(observed.len(), 2, "expected exactly two events, got {observed:?}");
+    assert_eq!(observed[0].0, event_id);
+    assert_eq!(observed[1].0, event_with_data_id);
+    assert_eq!(observed[1].1, expected_commitment, "event payload commitment mismatch");
+}
@@ -1,3 +1,6 @@
+mod custom_kernel_abi;
+mod emit_event;
 mod get_inputs;
 mod get_metadata;
 mod note_build_recipient;
+mod note_metadata;
@@ -0,0 +1,85 @@
+use miden_core::{Felt, field::PrimeField64};
+use miden_core_lib::CoreLibrary;
+use miden_debug::DebugQuery;
+use miden_protocol::{
+    account::AccountId,
+    note::{NoteMetadata, NoteTag, NoteType},
+    testing::account_id::ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_IMMUTABLE_CODE,
+};
+use midenc_frontend_wasm::WasmTranslationConfig;
+
+use crate::{CompilerTestBuilder, testing::executor_with_std};
+
+/// Decodes a metadata header built by `miden-protocol` with the SDK's `NoteMetadata` accessors, and
+/// checks the SDK's `Tag` and `NoteType` constructors against the protocol encodings, on-chain.
+#[test]
+fn note_metadata_matches_protocol_encodings() {
+    let main_fn = r#"(
+        h0: Felt, h1: Felt, h2: Felt, h3: Felt,
+        sender_prefix: Felt, sender_suffix: Felt,
+        tag: Felt, note_type: Felt,
+        public: Felt, private: Felt, encrypted: Felt,
+    ) -> Felt {
+        let metadata = NoteMetadata::new(Word::from([h0, h1, h2, h3]));
+
+        let sender = metadata.sender();
+        assert_eq(sender.prefix, sender_prefix);
+        assert_eq(sender.suffix, sender_suffix);
+        assert_eq(metadata.tag().inner, tag);
+        assert_eq(metadata.note_type().inner, note_type);
+        // The note has no attachments
+        let schemes = metadata.attachment_schemes();
+        for i in 0..4 {
+            assert_eq(schemes[i], felt!(0));
+        }
+
+        // The tag was created with `NoteTag::with_account_target(sender)`
+        assert_eq(Tag::for_local_use(sender, 0).inner, tag);
+
+        assert_eq(NoteType::public().inner, public);
+        assert_eq(NoteType::private().inner, private);
+        assert_eq(NoteType::encrypted().inner, encrypted);
+        felt!(1)
+    }"#
+    .to_string();
+
+    let config = WasmTranslationConfig::default();
+    let mut test = CompilerTestBuilder::rust_fn_body_with_sdk(
+        "abi_transform_tx_kernel_note_metadata",
+        &main_fn,
+        config,
+        [],
+    )
+    .build();
+
+    let package = test.compile_package();
+
+    let sender = AccountId::try_from(ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_IMMUTABLE_CODE).unwrap();
+    let tag = NoteTag::with_account_target(sender);
+    let metadata = NoteMetadata::new(sender, NoteType::Private).with_tag(tag);
+    let header: [Felt; 4] = metadata.to_header_word().into();
+
+    let args = vec![
+        header[0],
+        header[1],
+        header[2],
+        header[3],
+        sender.prefix().as_felt(),
+        sender.suffix(),
+        Felt::from(u32::from(tag)),
+        Felt::from(NoteType::Private),
+        Felt::from(NoteType::Public),
+        Felt::from(NoteType::Private),
+        Felt::from(NoteType::Encrypted),
+    ];
+    assert_eq!(Felt::from(NoteType::Encrypted).as_canonical_u64(), 0b11);
+
+    let mut exec = executor_with_std(args, Some(&package));
+    for (event, handler) in CoreLibrary::default().handlers() {
+        exec.register_event_handler(event, handler)
+            .expect("failed to register core library event handler");
+    }
+    let trace = exec.execute(&package.unwrap_program(), test.session.source_manager.clone());
+    let result: Felt = trace.parse_result().expect("expected a result");
+    assert_eq!(result, Felt::ONE);
+}
@@ -0,0 +1,46 @@
+//! Readability of the procedures emitted for Rust functions, whose names are mangled symbols.
+use miden_core::Felt;
+
+use crate::{CompilerTest, testing::eval_package};
+
+const GENERIC: &str = r#"
+    (a: u32, b: u32) -> u32 {
+        #[inline(never)]
+        fn generic<T: core::ops::BitXor<Output = T>>(a: T, b: T) -> T {
+            a ^ b
+        }
+
+        generic(a, b) + generic(a as u64, b as u64) as u32
+    }
+"#;
+
+#[test]
+fn demangled_names_are_attached_to_procedures() {
+    let mut test = CompilerTest::rust_fn_body(GENERIC, []);
+    let masm = test.masm_src();
+    assert!(
+        masm.lines()
+            .any(|line| line.trim_start().starts_with("#!") && line.contains("::generic")),
+        "expected the demangled name of `generic` to be attached to its procedures:\n{masm}"
+    );
+}
+
+#[test]
+fn demangle_procedure_names() {
+    let mut test = CompilerTest::rust_fn_body(GENERIC, ["--demangle-procedure-names".to_string()]);
+    let masm = test.masm_src();
+    assert!(
+        masm.lines()
+            .any(|line| line.trim_start().starts_with("proc") && line.contains("generic.h")),
+        "expected the procedures of `generic` to be renamed after their demangled name:\n{masm}"
+    );
+
+    let package = test.compile_package();
+    let args = [Felt::from(0b1010u32), Felt::from(0b0110u32)];
+    eval_package::<u32, _, _>(&package, [], &args, &test.session, |trace| {
+        let res: u32 = trace.parse_result().unwrap();
+        assert_eq!(res, 0b1100 * 2);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,56 @@
+//! Evaluator vs. VM cases. The arithmetic-heavy cases under `cases/`, driven by `run_eval_case`.
+//!
+//! Case names are prefixed with `eval_`, so that their generated projects do not collide with
+//! those of the same case in `tests.rs`.
+
+use proptest::prelude::*;
+
+use super::harness::run_eval_case;
+
+#[test]
+fn eval_add() {
+    run_eval_case("eval_add", include_str!("cases/case_add.rs"), (any::<u32>(), any::<u32>()));
+}
+
+#[test]
+fn eval_sub() {
+    run_eval_case("eval_sub", include_str!("cases/case_sub.rs"), (any::<u32>(), any::<u32>()));
+}
+
+#[test]
+fn eval_xor() {
+    run_eval_case("eval_xor", include_str!("cases/case_xor.rs"), (any::<u32>(), any::<u32>()));
+}
+
+#[test]
+fn eval_muladd() {
+    run_eval_case(
+        "eval_muladd",
+        include_str!("cases/case_muladd.rs"),
+        (any::<u32>(), any::<u32>()),
+    );
+}
+
+/// Shift amounts are masked by the case, so small second inputs cover every amount more densely.
+#[test]
+fn eval_bitops() {
+    run_eval_case("eval_bitops", include_str!("cases/case_bitops.rs"), (any::<u32>(), 0u32..64));
+}
+
+#[test]
+fn eval_widening() {
+    run_eval_case(
+        "eval_widening",
+        include_str!("cases/case_widening.rs"),
+        (any::<u32>(), any::<u32>()),
+    );
+}
+
+#[test]
+fn eval_u128_mix() {
+    run_eval_case(
+        "eval_u128_mix",
+        include_str!("cases/case_u128_mix.rs"),
+        (any::<u32>(), any::<u32>()),
+    );
+}
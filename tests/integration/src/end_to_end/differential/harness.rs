@@ -8,6 +8,11 @@
 //! and via `cargo-miden` to a MASM package — and compares outputs across
 //! random `(u32, u32)` inputs. [`run_case_with_inputs`] does the same but
 //! against an explicit list of inputs, for pinning a known divergence.
+//!
+//! [`run_eval_case`] instead compares the MASM package against the HIR
+//! evaluator, run on the optimized HIR the package was lowered from, which
+//! separates miscompilations in the frontend and HIR passes from those in
+//! MASM codegen.
 
 use std::{
     path::PathBuf,
@@ -16,13 +21,23 @@ use std::{
 
 use miden_core::Felt;
 use midenc_frontend_wasm::WasmTranslationConfig;
+use midenc_hir::{Immediate, SymbolNameComponent, SymbolPath};
+use midenc_hir_eval::{HirEvaluator, Value};
 use proptest::{
     prelude::*,
-    test_runner::{Config, FileFailurePersistence, TestRunner},
+    test_runner::{Config, FileFailurePersistence, RngAlgorithm, TestRng, TestRunner},
 };
 
 use crate::{CompilerTest, project, testing::executor_with_std};
 
+/// When set to a `u64`, seeds the inputs generated by [`run_eval_case`], so
+/// that a failure reported in CI can be reproduced exactly.
+const SEED_VAR: &str = "MIDEN_DIFFERENTIAL_SEED";
+
+/// The number of operations the HIR evaluator may evaluate per input, so that
+/// a miscompiled loop fails the case rather than hanging it.
+const EVAL_FUEL: u64 = 10_000_000;
+
 /// How [`run_case_inner`] supplies the `(input1, input2)` pairs to compare.
 enum Inputs<'a> {
     /// 16 random pairs via proptest — the default fuzzing mode.
@@ -57,19 +72,9 @@ pub(super) fn run_case_with_inputs(name: &str, source: &str, inputs: &[(u32, u32
 fn run_case_inner(name: &str, source: &str, inputs: Inputs<'_>) {
     let pkg_name = format!("differential_{name}");
     let manifest = cargo_toml(&pkg_name);
-    let miden_project_manifest = miden_project_toml(&pkg_name);
     let full_source = format!("{CASE_HEADER}{source}");
 
-    let masm_proj = project(&format!("{pkg_name}_masm"))
-        .file("miden-project.toml", &miden_project_manifest)
-        .file("Cargo.toml", &manifest)
-        .file("src/lib.rs", &full_source)
-        .build();
-    let mut test = CompilerTest::rust_source_cargo_miden(
-        masm_proj.root(),
-        WasmTranslationConfig::default(),
-        [],
-    );
+    let mut test = masm_case(&pkg_name, &full_source);
     let package = test.compile_package();
 
     let native_proj = project(&format!("{pkg_name}_native"))
@@ -135,6 +140,106 @@ fn run_case_inner(name: &str, source: &str, inputs: Inputs<'_>) {
     }
 }
 
+/// Compiles `source` to MASM, then compares the `entrypoint(u32, u32) -> u32`
+/// outputs of the HIR evaluator and the VM across random input pairs drawn
+/// from `inputs`, e.g. `(any::<u32>(), 0u32..64)`.
+///
+/// Unlike [`run_case`], failing inputs are shrunk before being reported: both
+/// sides are compiled from the same HIR, so the smallest diverging input is
+/// the most useful starting point for bisecting the passes in between. The
+/// seed used is printed on failure, and can be pinned via `MIDEN_DIFFERENTIAL_SEED`.
+pub(super) fn run_eval_case<S>(name: &str, source: &str, inputs: S)
+where
+    S: Strategy<Value = (u32, u32)>,
+{
+    let pkg_name = format!("differential_{name}");
+    let full_source = format!("{CASE_HEADER}{source}");
+
+    let mut test = masm_case(&pkg_name, &full_source);
+    let package = test.compile_package();
+    let hir = test.hir();
+
+    let entrypoint = hir
+        .borrow()
+        .symbol_manager()
+        .lookup_symbol_ref(
+            &SymbolPath::new([
+                SymbolNameComponent::Component(pkg_name.as_str().into()),
+                SymbolNameComponent::Leaf("entrypoint".into()),
+            ])
+            .unwrap(),
+        )
+        .unwrap_or_else(|| panic!("{name}: expected an 'entrypoint' function"));
+
+    // Run the case for one input pair and return `(eval_out, masm_out)`.
+    let eval = |a: u32, b: u32| -> Result<(u32, u32), TestCaseError> {
+        let mut evaluator =
+            HirEvaluator::new(hir.borrow().as_operation().context_rc()).with_fuel(EVAL_FUEL);
+        let args = [Value::Immediate((a as i32).into()), Value::Immediate((b as i32).into())];
+        let results = evaluator
+            .eval(&entrypoint.borrow(), args)
+            .map_err(|err| TestCaseError::fail(format!("evaluation failed: {err}")))?;
+        let eval_out = match results.as_slice() {
+            [Value::Immediate(Immediate::I32(out))] => *out as u32,
+            other => {
+                return Err(TestCaseError::fail(format!("expected a single i32, got {other:?}")));
+            }
+        };
+
+        let exec = executor_with_std(
+            vec![Felt::new_unchecked(a as u64), Felt::new_unchecked(b as u64)],
+            Some(&package),
+        );
+        let masm_out: u32 =
+            exec.execute_into(&package.unwrap_program(), test.session.source_manager.clone());
+        Ok((eval_out, masm_out))
+    };
+
+    let seed = std::env::var(SEED_VAR)
+        .map(|seed| {
+            seed.parse::<u64>()
+                .unwrap_or_else(|e| panic!("invalid {SEED_VAR} '{seed}': {e}"))
+        })
+        .unwrap_or_else(|_| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+    let mut seed_bytes = [0u8; 32];
+    seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+
+    let cfg = Config {
+        cases: 32,
+        failure_persistence: Some(Box::new(FileFailurePersistence::Off)),
+        ..Config::default()
+    };
+    TestRunner::new_with_rng(cfg, TestRng::from_seed(RngAlgorithm::ChaCha, &seed_bytes))
+        .run(&inputs, |(a, b)| {
+            let (eval_out, masm_out) = eval(a, b)?;
+            prop_assert_eq!(
+                eval_out,
+                masm_out,
+                "evaluator vs masm mismatch for inputs ({}, {})",
+                a,
+                b
+            );
+            Ok(())
+        })
+        .unwrap_or_else(|err| panic!("{name}: {err}\nreproduce with {SEED_VAR}={seed}"));
+}
+
+/// Write `full_source` as `src/lib.rs` of a generated `cargo-miden` project
+/// named `pkg_name`, ready to be compiled to a MASM package.
+fn masm_case(pkg_name: &str, full_source: &str) -> CompilerTest {
+    let masm_proj = project(&format!("{pkg_name}_masm"))
+        .file("miden-project.toml", &miden_project_toml(pkg_name))
+        .file("Cargo.toml", &cargo_toml(pkg_name))
+        .file("src/lib.rs", full_source)
+        .build();
+    CompilerTest::rust_source_cargo_miden(masm_proj.root(), WasmTranslationConfig::default(), [])
+}
+
 /// Prepended to every case source before compilation — supplies the
 /// crate-level `#![no_std]` attribute and a minimal `#[panic_handler]` so each
 /// case file only has to contain the entrypoint function and its helpers.
@@ -7,6 +7,12 @@
 //! drives 16 random inputs through proptest. A divergence is a likely
 //! compiler bug; the case file itself is the minimal reproducer (proptest
 //! shrinking is disabled).
+//!
+//! The cases in `eval_tests.rs` instead compare the MASM package against the
+//! HIR evaluator, run on the optimized HIR. There, failing inputs are shrunk,
+//! and the inputs are seeded from `MIDEN_DIFFERENTIAL_SEED` when it is set, so
+//! a failure seen in CI can be replayed locally.
 
+mod eval_tests;
 mod harness;
 mod tests;
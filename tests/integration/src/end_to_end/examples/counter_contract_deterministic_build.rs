@@ -0,0 +1,64 @@
+use std::path::{Path, PathBuf};
+
+use midenc_frontend_wasm::WasmTranslationConfig;
+
+use crate::CompilerTestBuilder;
+
+#[test]
+fn counter_contract_deterministic_build() {
+    // Building the same contract from two different directories must produce identical packages
+    // when `--deterministic` is given, so that deployed account code can be verified
+    let root = tempfile::tempdir().unwrap();
+    let first = copy_counter_contract(&root.path().join("first"));
+    let second = copy_counter_contract(&root.path().join("second").join("nested"));
+
+    let first = build(&first);
+    let second = build(&second);
+    assert_eq!(first.digest(), second.digest());
+}
+
+fn build(project_dir: &Path) -> std::sync::Arc<miden_mast_package::Package> {
+    let config = WasmTranslationConfig::default();
+    let mut builder = CompilerTestBuilder::rust_source_cargo_miden(
+        project_dir,
+        config,
+        ["--deterministic".to_string()],
+    );
+    builder.with_release(true);
+    let mut test = builder.build();
+    test.compile_package()
+}
+
+/// Copy `examples/counter-contract` to `dest`, returning the path to the copy
+fn copy_counter_contract(dest: &Path) -> PathBuf {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+    let source = examples.join("counter-contract");
+    let sdk = examples.join("../sdk/sdk").canonicalize().unwrap();
+
+    copy_dir(&source, dest);
+
+    // The SDK dependency is given relative to the original location of the example
+    let manifest_path = dest.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+    let manifest = manifest.replace("../../sdk/sdk", sdk.to_str().unwrap());
+    std::fs::write(&manifest_path, manifest).unwrap();
+
+    dest.to_path_buf()
+}
+
+fn copy_dir(source: &Path, dest: &Path) {
+    std::fs::create_dir_all(dest).unwrap();
+    for entry in std::fs::read_dir(source).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        if entry.file_name() == "target" {
+            continue;
+        }
+        let dest = dest.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &dest);
+        } else {
+            std::fs::copy(&path, &dest).unwrap();
+        }
+    }
+}
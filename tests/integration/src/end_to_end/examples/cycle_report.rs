@@ -0,0 +1,45 @@
+use miden_mast_package::PackageExport;
+use midenc_frontend_wasm::WasmTranslationConfig;
+
+use crate::CompilerTest;
+
+#[test]
+fn cycle_report_includes_every_exported_procedure() {
+    let config = WasmTranslationConfig::default();
+    let mut test =
+        CompilerTest::rust_source_cargo_miden("../../examples/counter-contract", config, []);
+    let package = test.compile_package();
+    let report = test.cycle_report();
+
+    let reported = report
+        .procedures
+        .iter()
+        .map(|procedure| procedure.name.trim_start_matches("::").to_string())
+        .collect::<Vec<_>>();
+    let exports = package
+        .manifest
+        .exports()
+        .filter_map(|export| match export {
+            PackageExport::Procedure(export) => Some(export.path.as_ref().as_str().to_string()),
+            PackageExport::Constant(_) | PackageExport::Type(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(!exports.is_empty());
+    for export in exports.iter() {
+        assert!(
+            reported.iter().any(|name| name == export.trim_start_matches("::")),
+            "expected '{export}' to be included in the cycle report:\n{report}"
+        );
+    }
+}
+
+#[test]
+fn cycle_report_flags_loops_as_dynamic() {
+    let config = WasmTranslationConfig::default();
+    let mut test = CompilerTest::rust_source_cargo_miden("../../examples/collatz", config, []);
+    let report = test.cycle_report();
+
+    // The number of iterations of the collatz loop depends on its input
+    assert!(report.procedures.iter().any(|procedure| procedure.dynamic), "{report}");
+    assert!(report.to_string().contains("× dynamic"), "{report}");
+}
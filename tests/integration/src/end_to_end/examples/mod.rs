@@ -5,10 +5,13 @@ mod auth_component_rpo_falcon512;
 mod basic_wallet_package_sizes;
 mod collatz;
 mod counter_contract_debug_build;
+mod counter_contract_deterministic_build;
 mod counter_metadata;
 mod counter_note;
+mod cycle_report;
 mod fibonacci;
 mod is_prime;
+mod producers;
 mod storage_metadata;
 
 fn persist_cargo_miden_dependency(
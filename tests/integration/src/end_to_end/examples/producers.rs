@@ -0,0 +1,32 @@
+use midenc_frontend_wasm::WasmTranslationConfig;
+use midenc_session::{MIDENC_BUILD_REV, MIDENC_BUILD_VERSION, Producers};
+
+use crate::CompilerTestBuilder;
+
+#[test]
+fn counter_contract_producers() {
+    let config = WasmTranslationConfig::default();
+    let mut builder =
+        CompilerTestBuilder::rust_source_cargo_miden("../../examples/counter-contract", config, []);
+    builder.with_release(true);
+    let mut test = builder.build();
+    let package = test.compile_package();
+
+    let producers = Producers::from_package(&package)
+        .expect("invalid producers section")
+        .expect("expected the package to record its producers");
+
+    // Recorded by rustc in the `producers` section of the Wasm, e.g. `1.90.0-nightly (<rev> <date>)`
+    let rustc = producers
+        .get(Producers::PROCESSED_BY, "rustc")
+        .expect("expected the rustc version to be recorded");
+    assert!(
+        rustc.starts_with(|c: char| c.is_ascii_digit()),
+        "unexpected rustc version: {rustc}"
+    );
+
+    let midenc = producers
+        .get(Producers::PROCESSED_BY, "midenc")
+        .expect("expected the midenc version to be recorded");
+    assert_eq!(midenc, format!("{MIDENC_BUILD_VERSION} ({MIDENC_BUILD_REV})"));
+}
@@ -0,0 +1,69 @@
+//! Calls through function tables, i.e. Wasm `call_indirect`, evaluated both by the HIR evaluator
+//! and by the VM.
+use super::support::assert_evaluator_matches_vm;
+
+#[test]
+fn dyn_fn() {
+    let main_fn = r#"(n: u32) -> u32 {
+        #[inline(never)]
+        fn apply(f: &dyn Fn(u32) -> u32, n: u32) -> u32 {
+            f(n)
+        }
+        let k = core::hint::black_box(3u32);
+        let add = |x: u32| x.wrapping_add(k);
+        let mul = |x: u32| x.wrapping_mul(k);
+        let xor = |x: u32| x ^ k;
+        let ops: [&dyn Fn(u32) -> u32; 3] = [&add, &mul, &xor];
+        apply(core::hint::black_box(ops[(n % 3) as usize]), n)
+    }"#;
+
+    assert_evaluator_matches_vm("dyn_fn", main_fn, &[0, 1, 2, 7, 11, 0xffff_ffff], |n| {
+        match n % 3 {
+            0 => n.wrapping_add(3),
+            1 => n.wrapping_mul(3),
+            _ => n ^ 3,
+        }
+    });
+}
+
+#[test]
+fn fmt_debug() {
+    let main_fn = r#"(n: u32) -> u32 {
+        use core::fmt::Write;
+
+        #[derive(Debug)]
+        struct Point {
+            x: u32,
+            y: u32,
+        }
+
+        struct Buffer {
+            bytes: [u8; 64],
+            len: usize,
+        }
+
+        impl Write for Buffer {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let end = self.len + s.len();
+                if end > self.bytes.len() {
+                    return Err(core::fmt::Error);
+                }
+                self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+
+        let mut buf = Buffer { bytes: [0; 64], len: 0 };
+        if write!(buf, "{:?}", Point { x: n, y: n / 2 }).is_err() {
+            return u32::MAX;
+        }
+        buf.bytes[..buf.len].iter().fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(*b as u32))
+    }"#;
+
+    assert_evaluator_matches_vm("fmt_debug", main_fn, &[0, 5, 1234, 0xffff_ffff], |n| {
+        format!("Point {{ x: {n}, y: {} }}", n / 2)
+            .bytes()
+            .fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32))
+    });
+}
@@ -0,0 +1,72 @@
+//! Raw Miden Assembly spliced into Rust functions, either via the `masm!` macro of
+//! `miden-stdlib-sys`, or by importing it from the `miden:intrinsics/inline-asm` module directly.
+use miden_core::Felt;
+use midenc_frontend_wasm::WasmTranslationConfig;
+
+use crate::{CompilerTest, testing::eval_package};
+
+#[test]
+fn inline_asm_clz() {
+    let main_fn = r#"(value: u32) -> u32 {
+        miden_stdlib_sys::masm!("u32clz", (value: u32) -> u32)
+    }"#;
+    let mut test = CompilerTest::rust_fn_body_with_stdlib_sys(
+        "inline_asm_clz",
+        main_fn,
+        WasmTranslationConfig::default(),
+        [],
+    );
+
+    let masm = test.masm_src();
+    assert!(masm.contains("u32clz"), "expected the template to be spliced in:\n{masm}");
+
+    let package = test.compile_package();
+    for value in [0u32, 1, 0x00ff_ffff, 0x8000_0000, u32::MAX] {
+        eval_package::<u32, _, _>(&package, [], &[Felt::from(value)], &test.session, |trace| {
+            let res: u32 = trace.parse_result().unwrap();
+            assert_eq!(res, value.leading_zeros(), "unexpected result for {value:#x}");
+            Ok(())
+        })
+        .unwrap();
+    }
+}
+
+/// The first argument is on top of the operand stack when the assembly is entered, so
+/// `u32wrapping_sub` subtracts the first argument from the second.
+#[test]
+fn inline_asm_operand_order() {
+    let main_fn = r#"(a: u32, b: u32) -> u32 {
+        #[link(wasm_import_module = "miden:intrinsics/inline-asm")]
+        unsafe extern "C" {
+            #[link_name = "u32wrapping_sub"]
+            fn sub(a: u32, b: u32) -> u32;
+        }
+        unsafe { sub(a, b) }
+    }"#;
+    let mut test = CompilerTest::rust_fn_body(main_fn, []);
+
+    let package = test.compile_package();
+    let args = [Felt::from(10u32), Felt::from(3u32)];
+    eval_package::<u32, _, _>(&package, [], &args, &test.session, |trace| {
+        let res: u32 = trace.parse_result().unwrap();
+        assert_eq!(res, 3u32.wrapping_sub(10));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "invalid hir.inline_asm template")]
+fn inline_asm_malformed_template() {
+    let main_fn = r#"(value: u32) -> u32 {
+        #[link(wasm_import_module = "miden:intrinsics/inline-asm")]
+        unsafe extern "C" {
+            #[link_name = "u32clz.foo"]
+            fn clz(value: u32) -> u32;
+        }
+        unsafe { clz(value) }
+    }"#;
+    let mut test = CompilerTest::rust_fn_body(main_fn, []);
+
+    test.compile_package();
+}
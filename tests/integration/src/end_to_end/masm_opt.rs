@@ -0,0 +1,69 @@
+//! Compiling with `--no-masm-opt`, which skips the optimizations applied to the emitted Miden
+//! Assembly, while leaving the IR optimizations as-is.
+use miden_core::Felt;
+
+use crate::{CompilerTest, testing::eval_package};
+
+const FIBONACCI: &str = r#"
+    (n: u32) -> u32 {
+        let mut a = 0u32;
+        let mut b = 1u32;
+        for _ in 0..n {
+            let c = a.wrapping_add(b);
+            a = b;
+            b = c;
+        }
+        a
+    }
+"#;
+
+/// The number of instructions in `masm`, not counting comments, or the delimiters of procedures
+/// and control flow blocks
+fn instruction_count(masm: &str) -> usize {
+    masm.lines()
+        .map(str::trim)
+        .filter(|line| {
+            !(line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with('@')
+                || line.starts_with("use ")
+                || line.starts_with("proc ")
+                || line.starts_with("pub proc ")
+                || matches!(*line, "begin" | "end" | "else" | "if.true" | "while.true")
+                || line.starts_with("repeat."))
+        })
+        .count()
+}
+
+#[test]
+fn no_masm_opt_preserves_behavior() {
+    let mut optimized = CompilerTest::rust_fn_body(FIBONACCI, []);
+    let mut unoptimized = CompilerTest::rust_fn_body(FIBONACCI, ["--no-masm-opt".to_string()]);
+
+    let optimized_masm = optimized.masm_src();
+    let unoptimized_masm = unoptimized.masm_src();
+    assert!(optimized_masm.starts_with("# masm optimizations: enabled"), "{optimized_masm}");
+    assert!(
+        unoptimized_masm.starts_with("# masm optimizations: disabled"),
+        "{unoptimized_masm}"
+    );
+
+    for test in [&mut optimized, &mut unoptimized] {
+        let package = test.compile_package();
+        eval_package::<u32, _, _>(&package, [], &[Felt::from(10u32)], &test.session, |trace| {
+            let res: u32 = trace.parse_result().unwrap();
+            assert_eq!(res, 55);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    // The optimizations only ever remove instructions
+    let optimized_count = instruction_count(&optimized_masm);
+    let unoptimized_count = instruction_count(&unoptimized_masm);
+    assert!(
+        unoptimized_count > optimized_count,
+        "expected fewer instructions with optimizations enabled, got {optimized_count}, and \
+         {unoptimized_count} without"
+    );
+}
@@ -0,0 +1,86 @@
+use super::support::assert_memory_test_returns_zero;
+
+#[test]
+fn memory_copy_word_aligned_structs() {
+    // 16, 48 and 64 bytes are copied with unrolled word copies, 80 bytes with the word copy
+    // intrinsic
+    let main_fn = r#"() -> Felt {
+        #[repr(C, align(16))]
+        struct Words<const N: usize>([u32; N]);
+
+        #[inline(never)]
+        fn do_copy<const N: usize>(dst: &mut Words<N>, src: &Words<N>) {
+            unsafe {
+                core::ptr::copy_nonoverlapping(src, dst, 1);
+            }
+        }
+
+        fn mismatches<const N: usize>() -> u32 {
+            let mut src = Words([0u32; N]);
+            let mut i = 0usize;
+            while i < N {
+                src.0[i] = (i as u32 + 1).wrapping_mul(0x01010101);
+                i += 1;
+            }
+            let mut dst = Words([0u32; N]);
+            do_copy(&mut dst, &src);
+
+            let mut mismatches = 0u32;
+            let mut i = 0usize;
+            while i < N {
+                if dst.0[i] != src.0[i] {
+                    mismatches += 1;
+                }
+                i += 1;
+            }
+            mismatches
+        }
+
+        let total = mismatches::<4>() + mismatches::<12>() + mismatches::<16>();
+        Felt::from_u32(total + mismatches::<20>())
+    }"#;
+
+    assert_memory_test_returns_zero("memory_copy_word_aligned_structs", main_fn);
+}
+
+#[test]
+fn memory_copy_word_aligned_dynamic_count() {
+    // The length is not known statically, so the word copy intrinsic is used once it is found to
+    // be a multiple of the word size at runtime, and the byte loop otherwise
+    let main_fn = r#"() -> Felt {
+        #[repr(C, align(16))]
+        struct Buffer([u8; 64]);
+
+        #[inline(never)]
+        fn do_copy(dst: &mut Buffer, src: &Buffer, len: usize) {
+            unsafe {
+                core::ptr::copy_nonoverlapping(src.0.as_ptr(), dst.0.as_mut_ptr(), len);
+            }
+        }
+
+        let mut src = Buffer([0u8; 64]);
+        let mut i = 0usize;
+        while i < 64 {
+            src.0[i] = i as u8 + 1;
+            i += 1;
+        }
+
+        let mut mismatches = 0u32;
+        for len in [48usize, 37] {
+            let mut dst = Buffer([0u8; 64]);
+            do_copy(&mut dst, &src, core::hint::black_box(len));
+            let mut i = 0usize;
+            while i < 64 {
+                let expected = if i < len { src.0[i] } else { 0 };
+                if dst.0[i] != expected {
+                    mismatches += 1;
+                }
+                i += 1;
+            }
+        }
+
+        Felt::from_u32(mismatches)
+    }"#;
+
+    assert_memory_test_returns_zero("memory_copy_word_aligned_dynamic_count", main_fn);
+}
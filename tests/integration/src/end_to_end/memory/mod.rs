@@ -6,7 +6,10 @@ mod copy_unaligned;
 mod copy_unaligned_dst;
 mod copy_unaligned_dst_short_count;
 mod copy_unaligned_zero_count;
+mod copy_word_aligned;
 mod global_variables;
 mod set_unaligned;
 mod set_unaligned_zero_count;
+mod set_word_aligned;
+mod static_lookup_table;
 mod support;
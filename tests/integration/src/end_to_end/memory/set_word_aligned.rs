@@ -0,0 +1,36 @@
+use super::support::assert_memory_test_returns_zero;
+
+#[test]
+fn memory_set_word_aligned() {
+    let main_fn = r#"() -> Felt {
+        #[repr(C, align(16))]
+        struct Buffer([u8; 96]);
+
+        #[inline(never)]
+        fn do_set(dst: &mut Buffer, len: usize) {
+            unsafe {
+                core::ptr::write_bytes(dst.0.as_mut_ptr(), 0x5a, len);
+            }
+        }
+
+        let mut mismatches = 0u32;
+        // The first two lengths are a multiple of the word size, so are written a word at a
+        // time, the last a byte at a time
+        for len in [32usize, 80, 21] {
+            let mut dst = Buffer([0xffu8; 96]);
+            do_set(&mut dst, core::hint::black_box(len));
+            let mut i = 0usize;
+            while i < 96 {
+                let expected = if i < len { 0x5a } else { 0xff };
+                if dst.0[i] != expected {
+                    mismatches += 1;
+                }
+                i += 1;
+            }
+        }
+
+        Felt::from_u32(mismatches)
+    }"#;
+
+    assert_memory_test_returns_zero("memory_set_word_aligned", main_fn);
+}
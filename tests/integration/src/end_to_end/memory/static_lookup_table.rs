@@ -0,0 +1,20 @@
+use super::super::support::assert_evaluator_matches_vm;
+
+/// Indexing into a `static` table reads the data segment emitted for it, which the evaluator must
+/// place in memory just like the VM does.
+#[test]
+fn static_lookup_table() {
+    let main_fn = r#"(n: u32) -> u32 {
+        static TABLE: [u32; 8] = [
+            0x0001_0203, 0xdead_beef, 7, 0x8000_0000, 65_537, 0x00ff_ff00, 42, u32::MAX,
+        ];
+        TABLE[(n % 8) as usize]
+    }"#;
+
+    const TABLE: [u32; 8] =
+        [0x0001_0203, 0xdead_beef, 7, 0x8000_0000, 65_537, 0x00ff_ff00, 42, u32::MAX];
+
+    assert_evaluator_matches_vm("static_lookup_table", main_fn, &[0, 1, 3, 6, 7, 8, 13], |n| {
+        TABLE[(n % 8) as usize]
+    });
+}
@@ -2,10 +2,16 @@ mod abi;
 mod arithmetic;
 mod crypto;
 mod debuginfo;
+mod demangle;
 mod differential;
 mod examples;
+mod indirect_calls;
+mod inline_asm;
 mod intrinsics;
+mod masm_opt;
 mod memory;
+mod name_section;
+mod recursion;
 mod regressions;
 pub(crate) mod support;
 mod wasm_translation;
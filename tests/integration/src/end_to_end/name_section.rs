@@ -0,0 +1,31 @@
+//! Names of the functions of core Wasm modules, taken from the name section.
+use crate::CompilerTestBuilder;
+
+#[test]
+fn name_section_names_procedures() {
+    let wasm = wat::parse_str(
+        r#"(module
+  (func $add_one (export "add_one") (param i32) (result i32)
+    local.get 0
+    i32.const 1
+    i32.add
+  )
+  (func $twice_plus_one (export "twice_plus_one") (param i32) (result i32)
+    local.get 0
+    local.get 0
+    i32.add
+    call $add_one
+  )
+)"#,
+    )
+    .expect("failed to parse WAT module");
+
+    let mut test = CompilerTestBuilder::from_wasm("name_section", wasm, []).build();
+    let masm = test.masm_src();
+    for name in ["add_one", "twice_plus_one"] {
+        assert!(
+            masm.lines().any(|line| line.contains("proc") && line.contains(name)),
+            "expected a procedure named after `{name}`:\n{masm}"
+        );
+    }
+}
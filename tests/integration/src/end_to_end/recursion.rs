@@ -0,0 +1,42 @@
+//! Recursive calls, evaluated both by the HIR evaluator and by the VM.
+use super::support::assert_evaluator_matches_vm;
+
+#[test]
+fn recursive_fib() {
+    let main_fn = r#"(n: u32) -> u32 {
+        #[inline(never)]
+        fn fib(n: u32) -> u32 {
+            if n < 2 { n } else { fib(n - 1).wrapping_add(fib(n - 2)) }
+        }
+        fib(n)
+    }"#;
+
+    fn fib(n: u32) -> u32 {
+        if n < 2 {
+            n
+        } else {
+            fib(n - 1).wrapping_add(fib(n - 2))
+        }
+    }
+
+    assert_evaluator_matches_vm("recursive_fib", main_fn, &[0, 1, 2, 7, 12], fib);
+}
+
+#[test]
+fn mutual_recursion() {
+    let main_fn = r#"(n: u32) -> u32 {
+        #[inline(never)]
+        fn is_even(n: u32) -> bool {
+            if n == 0 { true } else { is_odd(n - 1) }
+        }
+        #[inline(never)]
+        fn is_odd(n: u32) -> bool {
+            if n == 0 { false } else { is_even(n - 1) }
+        }
+        is_even(core::hint::black_box(n)) as u32
+    }"#;
+
+    assert_evaluator_matches_vm("mutual_recursion", main_fn, &[0, 1, 2, 9, 24], |n| {
+        (n % 2 == 0) as u32
+    });
+}
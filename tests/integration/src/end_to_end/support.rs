@@ -3,18 +3,68 @@ use std::{marker::PhantomData, sync::Arc};
 use miden_assembly::{Assembler, DefaultSourceManager, Parse, ParseOptions, ast::ModuleKind};
 use miden_core::Felt;
 use miden_core_lib::{CoreLibrary, handlers::u64_div::U64DivError};
+use miden_debug::ToMidenRepr;
 use miden_processor::{DefaultHost, ExecutionError, Program, operation::OperationError};
+use midenc_hir::{Immediate, Op, SymbolNameComponent, SymbolPath, SymbolTable};
+use midenc_hir_eval::{HirEvaluator, Value};
 use num_traits::{PrimInt, Unsigned};
 use proptest::{
     prelude::*,
     test_runner::{Config, TestRunner},
 };
 
-use crate::compiler_test::{sdk_alloc_crate_path, sdk_crate_path};
+use crate::{
+    CompilerTestBuilder,
+    compiler_test::{sdk_alloc_crate_path, sdk_crate_path},
+    testing::executor_with_std,
+};
 
 const I32_INTRINSICS_MASM: &str = include_str!("../../../../codegen/masm/intrinsics/i32.masm");
 const I64_INTRINSICS_MASM: &str = include_str!("../../../../codegen/masm/intrinsics/i64.masm");
 
+/// Compile `body` as the body of `entrypoint(n: u32) -> u32`, and check that both the HIR
+/// evaluator and the VM produce `expected(n)` for each of `inputs`.
+pub(super) fn assert_evaluator_matches_vm(
+    name: &'static str,
+    body: &str,
+    inputs: &[u32],
+    expected: impl Fn(u32) -> u32,
+) {
+    let mut test = CompilerTestBuilder::rust_fn_body_with_artifact_name(name, body, []).build();
+    let package = test.compile_package();
+    let hir = test.hir();
+
+    let entrypoint = hir
+        .borrow()
+        .symbol_manager()
+        .lookup_symbol_ref(
+            &SymbolPath::new([
+                SymbolNameComponent::Component(name.into()),
+                SymbolNameComponent::Leaf("entrypoint".into()),
+            ])
+            .unwrap(),
+        )
+        .expect("expected an 'entrypoint' function");
+
+    for &n in inputs {
+        let expected = expected(n);
+
+        let mut evaluator = HirEvaluator::new(hir.borrow().as_operation().context_rc());
+        let result = evaluator
+            .eval(&entrypoint.borrow(), [Value::Immediate((n as i32).into())])
+            .unwrap_or_else(|err| panic!("{err}"));
+        let Value::Immediate(Immediate::I32(result)) = result[0] else {
+            panic!("expected i32 immediate for input {n}, got {:?}", result[0]);
+        };
+        assert_eq!(result as u32, expected, "unexpected evaluator result for input {n}");
+
+        let exec = executor_with_std(n.to_felts().to_vec(), Some(&package));
+        let output: u32 =
+            exec.execute_into(&package.unwrap_program(), test.session.source_manager.clone());
+        assert_eq!(output, expected, "unexpected VM result for input {n}");
+    }
+}
+
 /// Assembles an executable program that wraps `procedure_body` inside a procedure that is called
 /// as entry point.
 ///
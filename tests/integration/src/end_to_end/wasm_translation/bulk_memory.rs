@@ -0,0 +1,133 @@
+use miden_core::Felt;
+use miden_processor::{ExecutionOptions, StackInputs, advice::AdviceInputs, execute_sync};
+use midenc_hir::{FunctionIdent, Ident, interner::Symbol};
+
+use super::wasm_interpreter::WasmInterpreter;
+use crate::{CompilerTestBuilder, end_to_end::support::default_host_with_core_lib};
+
+/// `memory.init` copies 4 bytes of a passive data segment from `offset` on, after dropping it if
+/// `drop` is non-zero, and the word they make up is returned.
+const PASSIVE_DATA_WAT: &str = r#"(module
+  (memory 18)
+  (global $__heap_base (export "__heap_base") i32 (i32.const 1114112))
+  (data $active (i32.const 1048576) "\ff\ff\ff\ff")
+  (data $passive "\01\02\03\04\05\06\07\08")
+  (func $entrypoint (export "entrypoint") (param $offset i32) (param $drop i32) (result i32)
+    local.get $drop
+    if
+      data.drop $passive
+    end
+    i32.const 256
+    local.get $offset
+    i32.const 4
+    memory.init $passive
+    i32.const 256
+    i32.load
+  )
+)"#;
+
+/// A word is allocated from `__heap_base` and overwritten before `memory.init` copies the first
+/// 4 bytes of a passive data segment, which must not have been clobbered by the allocation.
+const ALLOCATION_AFTER_PASSIVE_DATA_WAT: &str = r#"(module
+  (memory 18)
+  (global $__heap_base (export "__heap_base") i32 (i32.const 1114112))
+  (data $passive "\01\02\03\04\05\06\07\08")
+  (func $alloc (param $size i32) (result i32)
+    global.get $__heap_base
+  )
+  (func $entrypoint (export "entrypoint") (result i32)
+    (local $ptr i32)
+    i32.const 4
+    call $alloc
+    local.tee $ptr
+    i32.const -1
+    i32.store
+    i32.const 256
+    i32.const 0
+    i32.const 4
+    memory.init $passive
+    i32.const 256
+    i32.load
+  )
+)"#;
+
+#[test]
+fn memory_init_and_data_drop() {
+    let wasm = wat::parse_str(PASSIVE_DATA_WAT).expect("failed to parse WAT module");
+
+    let mut builder = CompilerTestBuilder::from_wasm("test", wasm.clone(), []);
+    builder.with_entrypoint(FunctionIdent {
+        module: Ident::with_empty_span(Symbol::intern("test")),
+        function: Ident::with_empty_span(Symbol::intern("entrypoint")),
+    });
+    let mut test = builder.build();
+    let package = test.compile_package();
+    let program = package.unwrap_program();
+
+    // In bounds, out of bounds, and after the segment was dropped
+    for (offset, drop) in [(0, 0), (4, 0), (5, 0), (0, 1)] {
+        // Each case starts from a fresh instance, as the segment stays dropped once dropped
+        let expected = WasmInterpreter::new(&wasm)
+            .call_entrypoint::<(i32, i32), i32>("entrypoint", (offset, drop));
+
+        let stack_inputs = StackInputs::new(&[
+            Felt::new(offset as u32 as u64).expect("u32 values fit in a felt"),
+            Felt::new(drop as u32 as u64).expect("u32 values fit in a felt"),
+        ])
+        .expect("invalid stack inputs");
+        let vm_result = execute_sync(
+            &program,
+            stack_inputs,
+            AdviceInputs::default(),
+            &mut default_host_with_core_lib(),
+            ExecutionOptions::default(),
+        );
+
+        match (expected, vm_result) {
+            (Ok(expected), Ok(output)) => {
+                let result = output.stack.get_num_elements(1)[0].as_canonical_u64() as u32 as i32;
+                assert_eq!(result, expected, "memory.init at offset {offset} (drop = {drop})");
+            }
+            (Err(_), Err(_)) => (),
+            (Ok(expected), Err(vm_err)) => panic!(
+                "memory.init at offset {offset} (drop = {drop}): expected {expected}, but the VM \
+                 trapped: {vm_err}"
+            ),
+            (Err(wasm_err), Ok(_)) => panic!(
+                "memory.init at offset {offset} (drop = {drop}): expected the VM to trap \
+                 ({wasm_err}), but it returned"
+            ),
+        }
+    }
+}
+
+#[test]
+fn allocation_does_not_overlap_passive_data() {
+    let wasm =
+        wat::parse_str(ALLOCATION_AFTER_PASSIVE_DATA_WAT).expect("failed to parse WAT module");
+
+    let mut builder = CompilerTestBuilder::from_wasm("test", wasm.clone(), []);
+    builder.with_entrypoint(FunctionIdent {
+        module: Ident::with_empty_span(Symbol::intern("test")),
+        function: Ident::with_empty_span(Symbol::intern("entrypoint")),
+    });
+    let mut test = builder.build();
+    let package = test.compile_package();
+    let program = package.unwrap_program();
+
+    let expected = WasmInterpreter::new(&wasm)
+        .call_entrypoint::<(), i32>("entrypoint", ())
+        .expect("the module should not trap");
+    assert_eq!(expected, 0x04030201);
+
+    let output = execute_sync(
+        &program,
+        StackInputs::default(),
+        AdviceInputs::default(),
+        &mut default_host_with_core_lib(),
+        ExecutionOptions::default(),
+    )
+    .expect("execution failed");
+    let result = output.stack.get_num_elements(1)[0].as_canonical_u64() as u32 as i32;
+    assert_eq!(result, expected, "the allocation overwrote the passive data");
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use miden_assembly::{Assembler, DefaultSourceManager, Parse, ParseOptions, ast::ModuleKind};
+use miden_core::{Felt, serde::Serializable};
+use miden_mast_package::{Package, PackageId, TargetType, Version};
+use miden_processor::{ExecutionOptions, StackInputs, advice::AdviceInputs, execute_sync};
+use midenc_hir::{FunctionIdent, Ident, interner::Symbol};
+
+use super::wasm_interpreter::WasmInterpreter;
+use crate::{CompilerTestBuilder, end_to_end::support::default_host_with_core_lib};
+
+/// The functions of the `miden-softfloat` library called by [FLOAT_WAT], which take and return
+/// `f32` values by their IEEE 754 bit pattern, as an `i32`
+const SOFTFLOAT_MASM: &str = r#"
+pub proc f32_abs
+    push.2147483647 u32and
+end
+
+pub proc f32_neg
+    push.2147483648 u32xor
+end
+"#;
+
+/// The absolute value of the negation of the `f32` with the bit pattern `$bits`, as a bit pattern
+const FLOAT_WAT: &str = r#"(module
+  (func $entrypoint (export "entrypoint") (param $bits i32) (result i32)
+    local.get $bits
+    f32.reinterpret_i32
+    f32.neg
+    f32.abs
+    i32.reinterpret_f32
+  )
+)"#;
+
+/// Assemble [SOFTFLOAT_MASM] to the `miden-softfloat` package in `dir`, returning its path
+fn write_softfloat_package(dir: &std::path::Path) -> std::path::PathBuf {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let module = SOFTFLOAT_MASM
+        .parse_with_options(
+            source_manager.clone(),
+            ParseOptions::new(ModuleKind::Library, "::miden::softfloat"),
+        )
+        .expect("failed to parse softfloat module");
+    let library = Assembler::new(source_manager)
+        .assemble_library([module])
+        .expect("failed to assemble softfloat library");
+    let package = Package::from_library(
+        PackageId::from("miden-softfloat"),
+        "0.1.0".parse::<Version>().unwrap(),
+        TargetType::Library,
+        library,
+        std::iter::empty(),
+    );
+    let path = dir.join("miden-softfloat.masp");
+    std::fs::write(&path, package.to_bytes()).unwrap();
+    path
+}
+
+#[test]
+fn soft_float_operators_call_softfloat_library() {
+    let temp = tempfile::tempdir().unwrap();
+    let softfloat = write_softfloat_package(temp.path());
+    let wasm = wat::parse_str(FLOAT_WAT).expect("failed to parse WAT module");
+
+    let mut builder = CompilerTestBuilder::from_wasm(
+        "test",
+        wasm.clone(),
+        [
+            "--float-mode=soft-float".to_string(),
+            format!("-lmasp:static={}", softfloat.display()),
+        ],
+    );
+    builder.with_entrypoint(FunctionIdent {
+        module: Ident::with_empty_span(Symbol::intern("test")),
+        function: Ident::with_empty_span(Symbol::intern("entrypoint")),
+    });
+    let mut test = builder.build();
+    let package = test.compile_package();
+    let program = package.unwrap_program();
+
+    for value in [1.5f32, -2.25, 0.0, -0.0, f32::INFINITY, f32::MIN] {
+        let bits = value.to_bits() as i32;
+        let expected = WasmInterpreter::new(&wasm)
+            .call_entrypoint::<i32, i32>("entrypoint", bits)
+            .expect("the module should not trap");
+
+        let stack_inputs = StackInputs::new(&[Felt::new(bits as u32 as u64).unwrap()])
+            .expect("invalid stack inputs");
+        let output = execute_sync(
+            &program,
+            stack_inputs,
+            AdviceInputs::default(),
+            &mut default_host_with_core_lib(),
+            ExecutionOptions::default(),
+        )
+        .unwrap_or_else(|err| panic!("execution failed for {value}: {err}"));
+        let result = output.stack.get_num_elements(1)[0].as_canonical_u64() as u32 as i32;
+        assert_eq!(result, expected, "|-({value})|");
+    }
+}
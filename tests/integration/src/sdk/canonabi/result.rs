@@ -324,6 +324,89 @@ match err {
     });
 }
 
+/// Tests a component method that accepts and returns a result of exported variants.
+#[test]
+fn result_with_variant_payloads() {
+    let account_source = r#"#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::{component, component_storage, export_type};
+
+/// Success payload variants carrying differently sized integers.
+#[export_type]
+pub enum Amount {
+    /// Carries an 8-bit amount.
+    Small(u8),
+    /// Carries a 64-bit amount.
+    Large(u64),
+}
+
+/// Error payload cases without payloads.
+#[export_type]
+pub enum Failure {
+    /// The request was denied.
+    Denied,
+    /// The request expired.
+    Expired,
+}
+
+#[component_storage]
+struct CanonabiAccount;
+
+#[component]
+trait CanonabiComponent {
+    /// Transforms variant payloads carried by a result.
+    fn roundtrip(&self, value: Result<Amount, Failure>) -> Result<Amount, Failure>;
+}
+
+#[component]
+impl CanonabiComponent for CanonabiAccount {
+    fn roundtrip(&self, value: Result<Amount, Failure>) -> Result<Amount, Failure> {
+        match value {
+            Ok(Amount::Small(amount)) => Ok(Amount::Large(amount as u64 + 1)),
+            Ok(Amount::Large(amount)) => Ok(Amount::Small((amount % 256) as u8)),
+            Err(Failure::Denied) => Err(Failure::Expired),
+            Err(Failure::Expired) => Err(Failure::Denied),
+        }
+    }
+}
+"#;
+    let note_body = r#"let small = roundtrip(Ok(Amount::Small(255)));
+match small {
+    Ok(Amount::Large(value)) => {
+        if value != 256 { assert_eq!(felt!(0), felt!(1)); }
+    }
+    _ => assert_eq!(felt!(0), felt!(1)),
+}
+
+let large = roundtrip(Ok(Amount::Large(u64::MAX)));
+match large {
+    Ok(Amount::Small(value)) => {
+        if value != 255 { assert_eq!(felt!(0), felt!(1)); }
+    }
+    _ => assert_eq!(felt!(0), felt!(1)),
+}
+
+let err = roundtrip(Err(Failure::Denied));
+match err {
+    Err(Failure::Expired) => {}
+    _ => assert_eq!(felt!(0), felt!(1)),
+}"#;
+
+    run_canonabi_case("result_variant", account_source, note_body, |wit| {
+        assert!(
+            wit.contains("variant amount {"),
+            "generated WIT did not define `amount` as a variant:\n{wit}"
+        );
+        assert!(
+            wit.contains(
+                "roundtrip: func(value: result<amount, failure>) -> result<amount, failure>;"
+            ),
+            "generated WIT did not use result<amount, failure>:\n{wit}"
+        );
+    });
+}
+
 /// Tests a component method that accepts and returns a record with a result field.
 #[test]
 fn record_with_result_field() {
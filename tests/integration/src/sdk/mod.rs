@@ -247,9 +247,9 @@ impl Note {
 /// Regression test for https://github.com/0xMiden/compiler/issues/831
 ///
 /// Previously, compilation could panic during MASM codegen with:
-/// `invalid stack offset for movup: 16 is out of range`.
+/// `invalid stack offset for movup: 16 is out of range`, as the `create_swapp_note` call requires
+/// operands deeper than the 16-element window, which are now reached by spilling to locals.
 #[test]
-#[ignore = "https://github.com/0xMiden/compiler/issues/1120"]
 fn rust_sdk_invalid_stack_offset_movup_16_issue_831() {
     let config = WasmTranslationConfig::default();
     let mut test = CompilerTest::rust_source_cargo_miden(
@@ -374,6 +374,80 @@ fn rust_sdk_cross_ctx_account_and_note_word() {
     let _trace = exec.execute(&program, test.session.source_manager.clone());
 }
 
+/// Passes `string` and `list<u8>` values from a note to an account and back, via the advice map
+#[test]
+fn rust_sdk_cross_ctx_account_and_note_string() {
+    let config = WasmTranslationConfig::default();
+    let mut test = CompilerTest::rust_source_cargo_miden(
+        "../fixtures/components/cross-ctx-account-string",
+        config.clone(),
+        [],
+    );
+    let account_package = test.compile_package();
+    persist_cargo_miden_dependency(
+        "../fixtures/components/cross-ctx-account-string",
+        account_package.as_ref(),
+    );
+    assert!(account_package.is_library());
+    find_manifest_procedure(account_package.as_ref(), "component export shout", |name| {
+        name.starts_with("::\"miden:cross-ctx-account-string/foo@1.0.0\"::")
+            && name.ends_with("::\"shout\"")
+    });
+
+    let builder = CompilerTestBuilder::rust_source_cargo_miden(
+        "../fixtures/components/cross-ctx-note-string",
+        config,
+        [],
+    );
+    let mut test = builder.build();
+    let package = test.compile_package();
+    assert!(package.is_library());
+    let program = note_script_program(package.as_ref());
+    let mut exec = executor_with_std(vec![], None);
+    exec.dependency_resolver_mut()
+        .insert(*account_package.mast.digest(), account_package.mast.clone());
+    exec.with_dependencies(package.manifest.dependencies())
+        .expect("failed to add package dependencies");
+    let _trace = exec.execute(&program, test.session.source_manager.clone());
+}
+
+/// Passes a `flags` value with 40 flags, i.e. two limbs, from a note to an account and back
+#[test]
+fn rust_sdk_cross_ctx_account_and_note_flags() {
+    let config = WasmTranslationConfig::default();
+    let mut test = CompilerTest::rust_source_cargo_miden(
+        "../fixtures/components/cross-ctx-account-flags",
+        config.clone(),
+        [],
+    );
+    let account_package = test.compile_package();
+    persist_cargo_miden_dependency(
+        "../fixtures/components/cross-ctx-account-flags",
+        account_package.as_ref(),
+    );
+    assert!(account_package.is_library());
+    find_manifest_procedure(account_package.as_ref(), "component export update", |name| {
+        name.starts_with("::\"miden:cross-ctx-account-flags/foo@1.0.0\"::")
+            && name.ends_with("::\"update\"")
+    });
+
+    let builder = CompilerTestBuilder::rust_source_cargo_miden(
+        "../fixtures/components/cross-ctx-note-flags",
+        config,
+        [],
+    );
+    let mut test = builder.build();
+    let package = test.compile_package();
+    assert!(package.is_library());
+    let program = note_script_program(package.as_ref());
+    let mut exec = executor_with_std(vec![], None);
+    exec.dependency_resolver_mut()
+        .insert(*account_package.mast.digest(), account_package.mast.clone());
+    exec.with_dependencies(package.manifest.dependencies())
+        .expect("failed to add package dependencies");
+    let _trace = exec.execute(&program, test.session.source_manager.clone());
+}
+
 /// Regression test for https://github.com/0xMiden/compiler/issues/1257
 ///
 /// Compiling the same account project several times must produce byte-identical package
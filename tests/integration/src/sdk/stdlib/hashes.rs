@@ -117,16 +117,17 @@ where
     assert!(res.is_ok(), "Unexpected test result: {res:?}");
 }
 
-/// Tests the BLAKE3 hash helper exported by the Rust stdlib bindings.
+/// Tests the BLAKE3 hash helper exported by the Rust stdlib bindings, on-chain and off-chain.
 #[test]
 fn blake3_1to1_hash() {
     run_stdlib_hash_test(
         "abi_transform_stdlib_blake3_hash",
-        "miden_stdlib_sys::blake3_hash(a)",
+        "miden_stdlib_sys::blake3_hash(&a)",
         |ibytes| {
             let hash = blake3::hash(ibytes);
             let mut output = [0u8; 32];
             output.copy_from_slice(hash.as_bytes());
+            assert_eq!(miden_stdlib_sys::blake3_hash(ibytes), output, "off-chain digest mismatch");
             output
         },
     );
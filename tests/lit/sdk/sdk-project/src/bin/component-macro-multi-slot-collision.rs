@@ -0,0 +1,11 @@
+use miden_base_macros::component_storage;
+
+#[component_storage]
+struct Contract {
+    #[storage(description = "vesting schedule")]
+    schedule: MultiSlotValue<VestingSchedule, 3>,
+    #[storage(description = "colliding value")]
+    schedule_1: StorageValue<Felt>,
+}
+
+fn main() {}
@@ -855,6 +855,8 @@ pub struct CompilerTest {
     masm_src: Option<String>,
     /// The compiled IR MASM program
     ir_masm_program: Option<Result<Arc<midenc_codegen_masm::MasmComponent>, String>>,
+    /// The MASM component, whether or not it is executable
+    masm_component: Option<Arc<midenc_codegen_masm::MasmComponent>>,
     /// The compiled package containing a program executable by the VM
     package: Option<Result<Arc<miden_mast_package::Package>, String>>,
 }
@@ -890,6 +892,7 @@ impl Default for CompilerTest {
             hir: None,
             masm_src: None,
             ir_masm_program: None,
+            masm_component: None,
             package: None,
         }
     }
@@ -1015,6 +1018,16 @@ impl CompilerTest {
         self.masm_src.clone().unwrap()
     }
 
+    /// Get a static estimate of the cycles spent in each procedure of the compiled MASM
+    pub fn cycle_report(&mut self) -> midenc_codegen_masm::CycleReport {
+        if self.masm_component.is_none()
+            && let Err(err) = self.compile_wasm_to_masm_program()
+        {
+            panic!("{err}");
+        }
+        midenc_codegen_masm::CycleReport::new(self.masm_component.as_ref().unwrap())
+    }
+
     /// Assemble the Wasm input to Miden Assembly
     ///
     /// If the Wasm has already been translated to the IR, it is just assembled, otherwise the
@@ -1025,9 +1038,11 @@ impl CompilerTest {
 
         let mut src = None;
         let mut masm_program = None;
+        let mut masm_component = None;
         let mut stage = |output: CodegenOutput, _context: Rc<Context>| {
             src = Some(output.component.to_string());
-            if output.component.entrypoint.is_some() {
+            masm_component = Some(Arc::clone(&output.component));
+            if output.component.entrypoint().is_some() {
                 masm_program = Some(Arc::clone(&output.component));
             }
             Ok(output)
@@ -1041,6 +1056,7 @@ impl CompilerTest {
         assert!(src.is_some(), "failed to pretty print masm artifact");
         self.masm_src = src;
         self.ir_masm_program = masm_program.map(Ok);
+        self.masm_component = masm_component;
         self.package = Some(Ok(package));
         Ok(())
     }
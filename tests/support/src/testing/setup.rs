@@ -76,6 +76,7 @@ pub fn build_empty_component_for_test(context: Rc<Context>) -> MidenComponent {
         world,
         component: Some(component),
         account_component_metadata_bytes: None,
+        producers: Default::default(),
     }
 }
 
@@ -1,6 +1,8 @@
 use clap::{Parser, Subcommand};
 
-use crate::commands::{BuildCommand, NewCommand, TestCommand};
+use crate::commands::{
+    BuildCommand, CheckCommand, ExpandCommand, InspectCommand, NewCommand, RunCommand, TestCommand,
+};
 
 /// Top-level command-line interface for `cargo-miden`.
 #[derive(Debug, Parser)]
@@ -23,6 +25,14 @@ pub enum CargoMidenCommand {
     New(NewCommand),
     /// Compile the current crate to Miden package.
     Build(BuildCommand),
+    /// Check the current crate for errors, without compiling it to a Miden package.
+    Check(CheckCommand),
+    /// Print the WIT worlds synthesized by the Miden SDK macros of the current crate.
+    Expand(ExpandCommand),
+    /// Compile the current crate to a Miden package, and execute it.
+    Run(RunCommand),
+    /// Print the exports, account component metadata and dependencies of a Miden package.
+    Inspect(InspectCommand),
     /// Run the miden-tests in the project.
     Test(TestCommand),
 }
@@ -1,44 +1,100 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use anyhow::{Context as _, Result, anyhow, bail};
 use clap::Args;
 use midenc_compile::{Compiler, stages::Artifact};
-use midenc_session::{InputFile, diagnostics::PrintDiagnostic};
+use midenc_session::{InputFile, Options, diagnostics::PrintDiagnostic};
 use toml_edit::DocumentMut;
 
+use crate::{
+    dependencies::link_library_dependencies,
+    outputs::BuiltPackage,
+    watch::{self, WatchOptions},
+};
+
 /// Command-line arguments accepted by `cargo miden build`.
 ///
 /// All arguments following `build` are parsed by the `midenc` compiler's argument parser.
-/// Cargo-specific options (`--release`, `--manifest-path`, `--workspace`, `--package`)
-/// are recognized and forwarded to the underlying `cargo build` invocation.
+/// Cargo-specific options (`--release`, `--manifest-path`, `--workspace`, `--package`,
+/// `--features`, `--all-features`, `--no-default-features`, `--locked`, `--offline` and
+/// `--target-dir`) are recognized and forwarded to the underlying `cargo build` invocation.
 /// All other options are passed to `midenc` for compilation.
+///
+/// The options of `build` itself, i.e. `--watch` and `--exec`, must precede those arguments.
+/// The arguments following `--midenc` are only passed to `midenc`, and those set in the
+/// `compiler-flags` array of `[package.metadata.miden]` are parsed as if they preceded the
+/// arguments given on the command line, which take precedence.
 #[derive(Clone, Debug, Args)]
 #[command(disable_version_flag = true, trailing_var_arg = true)]
 pub struct BuildCommand {
+    /// Rebuild the package each time its sources change, until interrupted
+    #[arg(long)]
+    pub watch: bool,
+    /// Run `<COMMAND>` with the shell after each successful rebuild in watch mode
+    #[arg(long, value_name = "COMMAND", requires = "watch")]
+    pub exec: Option<String>,
     /// Arguments parsed by midenc (includes cargo-compatible options).
+    ///
+    /// Use `--midenc <FLAGS>...` to pass the flags following it to midenc, e.g. `--midenc --emit
+    /// masm`.
     #[arg(value_name = "ARG", allow_hyphen_values = true)]
     pub args: Vec<String>,
 }
 
 impl BuildCommand {
     /// Executes `cargo miden build`, returning the resulting command output.
-    pub fn exec(self) -> Result<PathBuf> {
+    ///
+    /// In watch mode, this returns the package built last once the user stops watching.
+    pub fn exec(self) -> Result<BuiltPackage> {
+        if !self.watch {
+            return self.build();
+        }
+
+        let cwd = std::env::current_dir()?;
+        let (compiler_opts, _) = parse_compiler_options(&cwd, &self.args)?;
+        let project_dir =
+            match compiler_opts.manifest_path.as_deref().and_then(|path| path.parent()) {
+                Some(dir) => dir.to_path_buf(),
+                None => cwd,
+            };
+        // Ignore the changes made by the build itself
+        let mut ignored = vec![project_dir.join("target"), compiler_opts.target_dir.clone()];
+        ignored.extend(compiler_opts.cargo_target_dir.clone());
+        let options = WatchOptions {
+            project_dir,
+            ignored,
+            exec: self.exec.clone(),
+        };
+        let path = watch::watch(&options, || self.build().map(|built| built.path))?;
+        Ok(BuiltPackage { path, fresh: false })
+    }
+
+    /// Builds the package once, returning the path of the package.
+    ///
+    /// The package is reused from the compilation cache in the target directory when its Wasm and
+    /// the compiler options are unchanged since it was compiled, unless `--no-cache` is given.
+    fn build(&self) -> Result<BuiltPackage> {
         // Parse all arguments using midenc's Compiler parser.
         // This gives us a structured representation of all options.
         let cwd = std::env::current_dir()?;
-        let compiler_opts =
-            Compiler::try_parse_from(cwd.clone(), &self.args).unwrap_or_else(|err| err.exit());
+        let (mut compiler_opts, manifest_path) = parse_compiler_options(&cwd, &self.args)?;
 
         let metadata_out_dir = compiler_opts.target_dir.join(&compiler_opts.profile);
         if !metadata_out_dir.exists() {
             std::fs::create_dir_all(&metadata_out_dir)?;
         }
+        // Place the outputs requested with `--emit` next to the package, rather than in the
+        // project directory
+        if compiler_opts.output_dir.is_none() {
+            compiler_opts.output_dir = Some(metadata_out_dir.clone());
+        }
+        compiler_opts.package_cache = true;
 
-        let manifest_path = match compiler_opts.manifest_path.as_deref() {
-            Some(manifest_path) => manifest_path.to_path_buf(),
-            None => cwd.join("Cargo.toml"),
-        };
         reject_unselected_workspace_root(&manifest_path)?;
+        link_library_dependencies(&mut compiler_opts, &manifest_path)?;
         let input = InputFile::from_path(&manifest_path).unwrap();
         let session = Rc::new(
             compiler_opts
@@ -46,12 +102,17 @@ impl BuildCommand {
                 .map_err(|err| anyhow!("{}", PrintDiagnostic::new(err)))?,
         );
 
-        let artifact =
-            midenc_compile::compile_to_memory(Rc::new(midenc_hir::Context::new(session)))
-                .map_err(|err| anyhow!("{}", PrintDiagnostic::new(err)))?;
+        let context = Rc::new(midenc_hir::Context::new(session.clone()));
+        let artifact = midenc_compile::compile_to_memory(context).map_err(|err| {
+            // Report how many errors were raised, as not all of them may have been shown
+            match session.diagnostics.error_summary() {
+                Some(summary) => anyhow!("{}\n{summary}", PrintDiagnostic::new(err)),
+                None => anyhow!("{}", PrintDiagnostic::new(err)),
+            }
+        })?;
 
         match artifact {
-            Artifact::Assembled(package) => {
+            Artifact::Assembled { package, .. } => {
                 let output_path = metadata_out_dir
                     .join(&*package.name)
                     .with_extension(miden_mast_package::Package::EXTENSION);
@@ -61,14 +122,90 @@ impl BuildCommand {
                         &package.name, &package.version
                     )
                 })?;
-                Ok(output_path)
+                Ok(BuiltPackage {
+                    path: output_path,
+                    fresh: session.statistics.is_cached_package_reused(),
+                })
             }
             _ => unreachable!(),
         }
     }
 }
 
-fn reject_unselected_workspace_root(manifest_path: &std::path::Path) -> Result<()> {
+/// The argument of `cargo miden build` after which all arguments are passed to midenc.
+const MIDENC_FLAGS_ARG: &str = "--midenc";
+
+/// Parses the arguments of `cargo miden build` with midenc's argument parser, returning the
+/// compiler options and the path of the manifest of the project.
+///
+/// The `compiler-flags` set in `[package.metadata.miden]` are parsed as if they preceded `args`,
+/// so that the arguments given on the command line take precedence.
+pub(super) fn parse_compiler_options(
+    cwd: &Path,
+    args: &[String],
+) -> Result<(Box<Options>, PathBuf)> {
+    let args = args.iter().filter(|arg| *arg != MIDENC_FLAGS_ARG).collect::<Vec<_>>();
+    let compiler_opts =
+        Compiler::try_parse_cargo_from(cwd.to_path_buf(), &args).unwrap_or_else(|err| err.exit());
+    let manifest_path = match compiler_opts.manifest_path.as_deref() {
+        Some(manifest_path) => manifest_path.to_path_buf(),
+        None => cwd.join("Cargo.toml"),
+    };
+
+    let flags = compiler_flags(&manifest_path)?;
+    if flags.is_empty() {
+        return Ok((compiler_opts, manifest_path));
+    }
+    // Report invalid flags in the manifest as such, rather than as invalid arguments
+    if let Err(err) = Compiler::try_parse_cargo_from(cwd.to_path_buf(), &flags) {
+        bail!(
+            "invalid `compiler-flags` in '{}': {}",
+            manifest_path.display(),
+            err.render().to_string().trim_end()
+        );
+    }
+    let compiler_opts =
+        Compiler::try_parse_cargo_with_defaults_from(cwd.to_path_buf(), &flags, &args)
+            .unwrap_or_else(|err| err.exit());
+    Ok((compiler_opts, manifest_path))
+}
+
+/// Returns the `compiler-flags` set in the `[package.metadata.miden]` table of the Cargo
+/// manifest at `manifest_path`.
+pub(crate) fn compiler_flags(manifest_path: &Path) -> Result<Vec<String>> {
+    if !manifest_path.file_name().is_some_and(|name| name == "Cargo.toml") {
+        return Ok(Vec::new());
+    }
+    let Ok(manifest) = std::fs::read_to_string(manifest_path) else {
+        // A missing manifest is reported by the build itself
+        return Ok(Vec::new());
+    };
+    let manifest = manifest
+        .parse::<DocumentMut>()
+        .with_context(|| format!("failed to parse Cargo manifest '{}'", manifest_path.display()))?;
+    parse_compiler_flags(&manifest)
+}
+
+/// Parses the `compiler-flags` set in the `[package.metadata.miden]` table of `manifest`.
+pub(crate) fn parse_compiler_flags(manifest: &DocumentMut) -> Result<Vec<String>> {
+    let Some(flags) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("miden"))
+        .and_then(|miden| miden.get("compiler-flags"))
+    else {
+        return Ok(Vec::new());
+    };
+    let invalid = || anyhow!("expected `compiler-flags` to be an array of strings");
+    flags
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|flag| flag.as_str().map(str::to_string).ok_or_else(invalid))
+        .collect()
+}
+
+pub(super) fn reject_unselected_workspace_root(manifest_path: &std::path::Path) -> Result<()> {
     if !manifest_path.file_name().is_some_and(|name| name == "Cargo.toml") {
         return Ok(());
     }
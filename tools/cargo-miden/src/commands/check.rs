@@ -0,0 +1,61 @@
+use std::rc::Rc;
+
+use anyhow::{Result, anyhow, bail};
+use clap::Args;
+use midenc_session::{InputFile, diagnostics::PrintDiagnostic};
+
+use super::build::{parse_compiler_options, reject_unselected_workspace_root};
+use crate::dependencies::link_library_dependencies;
+
+/// Command-line arguments accepted by `cargo miden check`.
+///
+/// The arguments following `check` are parsed as those of `cargo miden build`. By default, only
+/// `cargo check` is run for the Wasm target, which reports errors in the Rust sources. With
+/// `--translate`, the package is built to Wasm and translated to Miden IR, which also reports the
+/// errors raised by the compiler frontend, e.g. unsupported Wasm constructs or ABI mismatches, but
+/// the IR is neither optimized nor lowered to Miden Assembly.
+#[derive(Clone, Debug, Args)]
+#[command(disable_version_flag = true, trailing_var_arg = true)]
+pub struct CheckCommand {
+    /// Also translate the Wasm produced by Cargo to Miden IR, to report frontend errors
+    #[arg(long)]
+    pub translate: bool,
+    /// Arguments parsed by midenc (includes cargo-compatible options).
+    #[arg(value_name = "ARG", allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+impl CheckCommand {
+    /// Executes `cargo miden check`, failing if any errors were reported.
+    pub fn exec(self) -> Result<()> {
+        let cwd = std::env::current_dir()?;
+        let (mut compiler_opts, manifest_path) = parse_compiler_options(&cwd, &self.args)?;
+        reject_unselected_workspace_root(&manifest_path)?;
+        link_library_dependencies(&mut compiler_opts, &manifest_path)?;
+        let input = InputFile::from_path(&manifest_path).unwrap();
+        let session = Rc::new(
+            compiler_opts
+                .into_session(input, None, None)
+                .map_err(|err| anyhow!("{}", PrintDiagnostic::new(err)))?,
+        );
+
+        let context = Rc::new(midenc_hir::Context::new(session.clone()));
+        let result = if self.translate {
+            midenc_compile::compile_to_unoptimized_hir(context).map(|_| ())
+        } else {
+            midenc_compile::check_cargo_project(context)
+        };
+        if let Err(err) = result {
+            // Report how many errors were raised, as not all of them may have been shown
+            return Err(match session.diagnostics.error_summary() {
+                Some(summary) => anyhow!("{}\n{summary}", PrintDiagnostic::new(err)),
+                None => anyhow!("{}", PrintDiagnostic::new(err)),
+            });
+        }
+        if let Some(summary) = session.diagnostics.error_summary() {
+            bail!("{summary}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fs, path::PathBuf, rc::Rc};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use clap::Args;
+use midenc_session::{InputFile, diagnostics::PrintDiagnostic};
+use toml_edit::DocumentMut;
+
+use super::build::{parse_compiler_options, reject_unselected_workspace_root};
+use crate::{dependencies::link_library_dependencies, outputs::ExpandedWit};
+
+/// Command-line arguments accepted by `cargo miden expand`.
+///
+/// The arguments following `expand` are parsed as those of `cargo miden build`. Only `cargo check`
+/// is run for the Wasm target, during which the `#[component]`, `#[note]` and script macros of the
+/// Miden SDK write the WIT worlds they synthesize, i.e. those they generate bindings from.
+///
+/// The worlds are written before the bindings are generated, so they are reported even when
+/// `cargo check` fails, e.g. because the bindings could not be generated.
+#[derive(Clone, Debug, Args)]
+#[command(disable_version_flag = true, trailing_var_arg = true)]
+pub struct ExpandCommand {
+    /// Write the WIT files to `<DIR>` instead of printing them
+    ///
+    /// By default, the WIT files are written to `target/miden/<profile>/wit`.
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+    /// Arguments parsed by midenc (includes cargo-compatible options).
+    #[arg(value_name = "ARG", allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+impl ExpandCommand {
+    /// Executes `cargo miden expand`, returning the WIT worlds synthesized for the package.
+    pub fn exec(self) -> Result<Vec<ExpandedWit>> {
+        let cwd = std::env::current_dir()?;
+        let (mut compiler_opts, manifest_path) = parse_compiler_options(&cwd, &self.args)?;
+        reject_unselected_workspace_root(&manifest_path)?;
+        link_library_dependencies(&mut compiler_opts, &manifest_path)?;
+
+        let wit_dir = match self.out_dir.as_deref() {
+            Some(out_dir) if out_dir.is_absolute() => out_dir.to_path_buf(),
+            Some(out_dir) => cwd.join(out_dir),
+            None => compiler_opts.target_dir.join(&compiler_opts.profile).join("wit"),
+        };
+        fs::create_dir_all(&wit_dir).with_context(|| {
+            format!("failed to create WIT output directory '{}'", wit_dir.display())
+        })?;
+
+        let input = InputFile::from_path(&manifest_path).unwrap();
+        let session = Rc::new(
+            compiler_opts
+                .into_session(input, None, None)
+                .map_err(|err| anyhow!("{}", PrintDiagnostic::new(err)))?,
+        );
+        let context = Rc::new(midenc_hir::Context::new(session.clone()));
+        let result = midenc_compile::expand_cargo_project_wit(context, &wit_dir);
+
+        let package_name = cargo_package_name(&manifest_path)?;
+        let worlds = collect_expanded_wit(&wit_dir, package_name.as_deref())?;
+        if let Err(err) = result {
+            if worlds.is_empty() {
+                bail!("{}", PrintDiagnostic::new(err));
+            }
+            // The synthesized WIT is most useful precisely when bindings fail to generate from it
+            eprintln!("{}", PrintDiagnostic::new(err));
+            log::warn!(
+                target: "cargo-miden",
+                "`cargo check` failed, but the macros wrote the WIT they synthesized"
+            );
+        }
+        if worlds.is_empty() {
+            bail!(
+                "no WIT was synthesized in '{}': the crate does not use the `#[component]`, \
+                 `#[note]` or script macros of the Miden SDK, or was already checked before '{}' \
+                 was cleaned",
+                manifest_path.display(),
+                wit_dir.display()
+            );
+        }
+
+        Ok(worlds)
+    }
+}
+
+/// Returns the name of the package defined by the Cargo manifest at `manifest_path`, if any.
+fn cargo_package_name(manifest_path: &std::path::Path) -> Result<Option<String>> {
+    if manifest_path.file_name().is_none_or(|name| name != "Cargo.toml") {
+        return Ok(None);
+    }
+    let manifest = fs::read_to_string(manifest_path)
+        .with_context(|| format!("failed to read '{}'", manifest_path.display()))?;
+    let manifest = manifest
+        .parse::<DocumentMut>()
+        .with_context(|| format!("failed to parse '{}'", manifest_path.display()))?;
+    Ok(manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str())
+        .map(str::to_string))
+}
+
+/// Reads the WIT files written to `wit_dir` for `package_name`, or for every package if `None`.
+///
+/// The macros name them `<package>.<world>.wit`.
+pub(crate) fn collect_expanded_wit(
+    wit_dir: &std::path::Path,
+    package_name: Option<&str>,
+) -> Result<Vec<ExpandedWit>> {
+    let mut paths = fs::read_dir(wit_dir)
+        .with_context(|| format!("failed to read WIT directory '{}'", wit_dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("failed to read WIT directory '{}'", wit_dir.display()))?;
+    paths.retain(|path| {
+        path.extension().is_some_and(|ext| ext == "wit")
+            && package_name.is_none_or(|package_name| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(package_name))
+                    .is_some_and(|rest| rest.starts_with('.'))
+            })
+    });
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let source = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read '{}'", path.display()))?;
+            Ok(ExpandedWit { path, source })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expanded_wit_is_selected_by_package() {
+        let dir = std::env::temp_dir().join(format!(
+            "cargo_miden_expand_unit_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("basic-wallet.basic-wallet-world.wit"), "package a:b;").unwrap();
+        fs::write(dir.join("basic-wallet-tx-script.world.wit"), "package c:d;").unwrap();
+        fs::write(dir.join("basic-wallet.notes.txt"), "").unwrap();
+
+        let worlds = collect_expanded_wit(&dir, Some("basic-wallet")).unwrap();
+        assert_eq!(worlds.len(), 1);
+        assert_eq!(worlds[0].source, "package a:b;");
+
+        let worlds = collect_expanded_wit(&dir, None).unwrap();
+        assert_eq!(worlds.len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
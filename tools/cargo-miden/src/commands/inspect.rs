@@ -0,0 +1,284 @@
+use std::{borrow::Borrow, fmt, path::PathBuf};
+
+use anyhow::{Context as _, Result, anyhow};
+use clap::Args;
+use miden_core::serde::Deserializable;
+use miden_mast_package::{Package, PackageExport, SectionId};
+use miden_protocol::account::AccountComponentMetadata;
+use serde::Serialize;
+use toml_edit::DocumentMut;
+
+/// Print the exports, account component metadata and dependencies of a Miden package.
+#[derive(Clone, Debug, Args)]
+#[command(disable_version_flag = true)]
+pub struct InspectCommand {
+    /// The `.masp` file to inspect
+    #[arg(value_name = "PACKAGE")]
+    pub package: PathBuf,
+    /// Print the contents of the package as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl InspectCommand {
+    /// Executes `cargo miden inspect`, returning the contents of the package.
+    pub fn exec(self) -> Result<PackageInfo> {
+        let bytes = std::fs::read(&self.package)
+            .with_context(|| format!("failed to read package '{}'", self.package.display()))?;
+        let package = Package::read_from_bytes(&bytes).map_err(|err| {
+            anyhow!("failed to decode package '{}': {err}", self.package.display())
+        })?;
+        PackageInfo::from_package(&package)
+    }
+}
+
+/// The contents of a Miden package, as shown by `cargo miden inspect`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageInfo {
+    /// The name of the package.
+    pub name: String,
+    /// The version of the package.
+    pub version: String,
+    /// The kind of package, e.g. `account-component` or `executable`.
+    pub kind: String,
+    /// The digest of the package's MAST, in hex.
+    pub digest: String,
+    /// The exported procedures, grouped by the interface that exports them.
+    pub interfaces: Vec<InterfaceInfo>,
+    /// The account component metadata embedded in the package, if any.
+    pub account_component: Option<AccountComponentInfo>,
+    /// The packages this package depends on.
+    pub dependencies: Vec<DependencyInfo>,
+}
+
+/// An interface exported by a package, i.e. the module containing some of its exports.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceInfo {
+    /// The path of the interface, e.g. `::"miden:counter-contract/counter-contract@0.1.0"`.
+    pub name: String,
+    /// The procedures exported by the interface.
+    pub procedures: Vec<ProcedureInfo>,
+}
+
+/// A procedure exported by a package.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcedureInfo {
+    /// The name of the procedure within its interface.
+    pub name: String,
+    /// The signature of the procedure, if recorded in the package.
+    pub signature: Option<String>,
+}
+
+/// The account component metadata of a package.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountComponentInfo {
+    /// The name of the component.
+    pub name: String,
+    /// The description of the component.
+    pub description: String,
+    /// The version of the component.
+    pub version: String,
+    /// The storage slots of the component, in order.
+    pub storage: Vec<StorageSlotInfo>,
+}
+
+/// A storage slot of an account component.
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageSlotInfo {
+    /// The name of the slot.
+    pub name: String,
+    /// The description of the slot, if any.
+    pub description: Option<String>,
+    /// The type of the slot, e.g. `word` or `map<word, felt>`.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// A package depended on by an inspected package.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyInfo {
+    /// The name of the dependency.
+    pub name: String,
+    /// The version of the dependency.
+    pub version: String,
+    /// The digest of the dependency, in hex.
+    pub digest: String,
+}
+
+impl PackageInfo {
+    /// Collect the contents of `package`.
+    pub fn from_package(package: &Package) -> Result<Self> {
+        let mut interfaces = Vec::<InterfaceInfo>::new();
+        for export in package.manifest.exports() {
+            let PackageExport::Procedure(procedure) = export else {
+                continue;
+            };
+            let path = procedure.path.as_ref().as_str();
+            let (interface, name) = path.rsplit_once("::").unwrap_or(("", path));
+            let signature = procedure.signature.as_ref().map(|signature| {
+                let params = signature.params.iter().map(ToString::to_string).collect::<Vec<_>>();
+                let results = signature.results.iter().map(ToString::to_string).collect::<Vec<_>>();
+                format!("({}) -> ({})", params.join(", "), results.join(", "))
+            });
+            let procedure = ProcedureInfo {
+                name: name.trim_matches('"').to_string(),
+                signature,
+            };
+            match interfaces.iter_mut().find(|info| info.name == interface) {
+                Some(info) => info.procedures.push(procedure),
+                None => interfaces.push(InterfaceInfo {
+                    name: interface.to_string(),
+                    procedures: vec![procedure],
+                }),
+            }
+        }
+
+        let account_component = package
+            .sections
+            .iter()
+            .find(|section| section.id == SectionId::ACCOUNT_COMPONENT_METADATA)
+            .map(|section| AccountComponentInfo::from_bytes(section.data.borrow()))
+            .transpose()?;
+
+        let dependencies = package
+            .manifest
+            .dependencies()
+            .map(|dependency| DependencyInfo {
+                name: dependency.name.to_string(),
+                version: dependency.version.to_string(),
+                digest: dependency.digest.to_hex(),
+            })
+            .collect();
+
+        Ok(Self {
+            name: package.name.to_string(),
+            version: package.version.to_string(),
+            kind: package.kind.to_string(),
+            digest: package.digest().to_hex(),
+            interfaces,
+            account_component,
+            dependencies,
+        })
+    }
+
+    /// Returns the exported procedure `name` of any interface, if present.
+    pub fn find_procedure(&self, name: &str) -> Option<&ProcedureInfo> {
+        self.interfaces
+            .iter()
+            .flat_map(|interface| interface.procedures.iter())
+            .find(|procedure| procedure.name == name)
+    }
+
+    /// Render the contents of the package as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("failed to serialize package contents")
+    }
+}
+
+impl AccountComponentInfo {
+    /// Decode the serialized `AccountComponentMetadata` in `bytes`.
+    ///
+    /// The metadata is read through its TOML representation, which is the stable format in which
+    /// account components are described.
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let metadata = AccountComponentMetadata::read_from_bytes(bytes)
+            .map_err(|err| anyhow!("invalid account component metadata: {err}"))?;
+        let toml = metadata
+            .to_toml()
+            .map_err(|err| anyhow!("invalid account component metadata: {err}"))?;
+        let document = toml.parse::<DocumentMut>().context("invalid account component metadata")?;
+
+        let string = |item: Option<&toml_edit::Item>| {
+            item.and_then(|item| item.as_str()).unwrap_or_default().to_string()
+        };
+        let storage = document
+            .get("storage")
+            .and_then(|storage| storage.get("slots"))
+            .and_then(|slots| slots.as_array_of_tables())
+            .map(|slots| {
+                slots
+                    .iter()
+                    .map(|slot| StorageSlotInfo {
+                        name: string(slot.get("name")),
+                        description: slot
+                            .get("description")
+                            .and_then(|item| item.as_str())
+                            .map(str::to_string),
+                        ty: slot.get("type").map(slot_type).unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            name: string(document.get("name")),
+            description: string(document.get("description")),
+            version: string(document.get("version")),
+            storage,
+        })
+    }
+}
+
+/// Render the type of a storage slot, given as either a type name, or a table of the key and value
+/// types of a storage map.
+fn slot_type(item: &toml_edit::Item) -> String {
+    if let Some(name) = item.as_str() {
+        return name.to_string();
+    }
+    let key = item.get("key").and_then(|key| key.as_str());
+    let value = item.get("value").and_then(|value| value.as_str());
+    match (key, value) {
+        (Some(key), Some(value)) => format!("map<{key}, {value}>"),
+        _ => item.to_string().trim().to_string(),
+    }
+}
+
+impl fmt::Display for PackageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Package: {}@{}", self.name, self.version)?;
+        writeln!(f, "Kind: {}", self.kind)?;
+        writeln!(f, "Digest: {}", self.digest)?;
+
+        writeln!(f)?;
+        writeln!(f, "Exports:")?;
+        for interface in self.interfaces.iter() {
+            writeln!(f, "  {}", interface.name)?;
+            for procedure in interface.procedures.iter() {
+                match procedure.signature.as_deref() {
+                    Some(signature) => writeln!(f, "    {}: {signature}", procedure.name)?,
+                    None => writeln!(f, "    {}", procedure.name)?,
+                }
+            }
+        }
+
+        if let Some(component) = self.account_component.as_ref() {
+            writeln!(f)?;
+            writeln!(f, "Account component: {} ({})", component.name, component.version)?;
+            if !component.description.is_empty() {
+                writeln!(f, "  {}", component.description)?;
+            }
+            writeln!(f, "Storage:")?;
+            for (index, slot) in component.storage.iter().enumerate() {
+                write!(f, "  [{index}] {}: {}", slot.name, slot.ty)?;
+                match slot.description.as_deref() {
+                    Some(description) => writeln!(f, " - {description}")?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+
+        if !self.dependencies.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Dependencies:")?;
+            for dependency in self.dependencies.iter() {
+                writeln!(
+                    f,
+                    "  {}@{} ({})",
+                    dependency.name, dependency.version, dependency.digest
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
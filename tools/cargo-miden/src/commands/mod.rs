@@ -1,7 +1,18 @@
 pub mod build;
+pub mod check;
+pub mod expand;
+pub mod inspect;
 pub mod new_project;
+pub mod run;
 pub mod test;
 
 pub use build::BuildCommand;
+pub use check::CheckCommand;
+pub use expand::ExpandCommand;
+pub use inspect::{
+    AccountComponentInfo, DependencyInfo, InspectCommand, InterfaceInfo, PackageInfo,
+    ProcedureInfo, StorageSlotInfo,
+};
 pub use new_project::NewCommand;
+pub use run::RunCommand;
 pub use test::TestCommand;
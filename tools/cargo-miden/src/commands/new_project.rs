@@ -1,6 +1,7 @@
 use std::{
     fmt, fs,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use anyhow::Context;
@@ -95,6 +96,24 @@ impl ProjectTemplate {
     }
 }
 
+impl FromStr for ProjectTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "program" => Ok(Self::program()),
+            "account" => Ok(Self::account()),
+            "note" | "note-script" => Ok(Self::note()),
+            "tx-script" | "transaction-script" => Ok(Self::tx_script()),
+            "auth-component" | "authentication-component" => Ok(Self::auth_component()),
+            _ => anyhow::bail!(
+                "unknown project template '{name}', expected one of `program`, `account`, \
+                 `note-script`, `tx-script` or `auth-component`, or the URL of a git repository"
+            ),
+        }
+    }
+}
+
 impl Default for ProjectTemplate {
     fn default() -> Self {
         Self::account()
@@ -129,6 +148,14 @@ pub struct NewCommand {
     /// The template name to use to generate the package
     #[clap(flatten)]
     pub template: Option<ProjectTemplate>,
+    /// The template to use to generate the project: one of `program`, `account`, `note-script`,
+    /// `tx-script` or `auth-component`, or the URL of a git repository containing a template
+    #[clap(
+        long = "template",
+        value_name = "NAME|GIT-URL",
+        conflicts_with_all(["template", "template_path"])
+    )]
+    pub template_name: Option<String>,
     /// The path to the template to use to generate the project
     #[clap(long, conflicts_with("template"))]
     pub template_path: Option<PathBuf>,
@@ -185,25 +212,7 @@ impl NewCommand {
             }
         }
 
-        let template_path = match self.template_path.as_ref() {
-            Some(template_path) => TemplatePath {
-                path: Some(template_path.display().to_string()),
-                ..Default::default()
-            },
-            None => match self.template.as_ref() {
-                Some(project_template) => TemplatePath {
-                    git: Some("https://github.com/0xMiden/rust-templates".into()),
-                    tag: Some(PROJECT_TEMPLATES_REPO_TAG.into()),
-                    auto_path: Some(project_template.to_string()),
-                    ..Default::default()
-                },
-                None => TemplatePath {
-                    git: Some("https://github.com/0xMiden/project-template".into()),
-                    tag: Some(MIDEN_PROJECT_TEMPLATE_REPO_TAG.into()),
-                    ..Default::default()
-                },
-            },
-        };
+        let template_path = self.template_source()?;
 
         let destination = self
             .path
@@ -258,6 +267,42 @@ impl NewCommand {
 
         Ok(self.path)
     }
+
+    /// Returns the location of the template selected by `--template-path`, `--template` or one of
+    /// the template flags, defaulting to the Miden project template.
+    fn template_source(&self) -> anyhow::Result<TemplatePath> {
+        if let Some(template_path) = self.template_path.as_ref() {
+            return Ok(TemplatePath {
+                path: Some(template_path.display().to_string()),
+                ..Default::default()
+            });
+        }
+
+        let project_template = match self.template_name.as_deref() {
+            Some(url) if url.contains("://") || url.starts_with("git@") => {
+                return Ok(TemplatePath {
+                    git: Some(url.to_string()),
+                    ..Default::default()
+                });
+            }
+            Some(name) => Some(name.parse::<ProjectTemplate>()?),
+            None => self.template.clone(),
+        };
+
+        Ok(match project_template {
+            Some(project_template) => TemplatePath {
+                git: Some("https://github.com/0xMiden/rust-templates".into()),
+                tag: Some(PROJECT_TEMPLATES_REPO_TAG.into()),
+                auto_path: Some(project_template.to_string()),
+                ..Default::default()
+            },
+            None => TemplatePath {
+                git: Some("https://github.com/0xMiden/project-template".into()),
+                tag: Some(MIDEN_PROJECT_TEMPLATE_REPO_TAG.into()),
+                ..Default::default()
+            },
+        })
+    }
 }
 
 /// Returns true if `path` is inside an existing Git repository.
@@ -0,0 +1,238 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use clap::{Args, Parser};
+use miden_core::{Felt, Word, serde::Deserializable};
+use miden_core_lib::CoreLibrary;
+use miden_debug::Executor;
+use miden_mast_package::Package;
+use miden_processor::advice::AdviceInputs;
+use midenc_session::{
+    STDLIB,
+    diagnostics::{DefaultSourceManager, PrintDiagnostic, SourceManager},
+};
+use toml_edit::DocumentMut;
+
+use super::BuildCommand;
+use crate::RunOutput;
+
+/// Command-line arguments accepted by `cargo miden run`.
+///
+/// The package is built exactly as `cargo miden build` would: the arguments following the options
+/// of `run` (e.g. `--release` or `--profile`) are parsed by the `midenc` compiler's argument
+/// parser, up to an optional `--`. The arguments after `--` configure the execution of the built
+/// program.
+#[derive(Clone, Debug, Args)]
+#[command(disable_version_flag = true)]
+pub struct RunCommand {
+    /// Read the program inputs from `<FILE>`, in the TOML format used by `midenc run`
+    #[arg(long, value_name = "FILE", conflicts_with = "stack")]
+    pub inputs: Option<PathBuf>,
+    /// The operand stack inputs of the program, as comma-separated felts
+    #[arg(long, value_name = "FELTS", value_delimiter = ',')]
+    pub stack: Vec<u64>,
+    /// Arguments parsed by midenc (includes cargo-compatible options).
+    #[arg(value_name = "ARG", allow_hyphen_values = true)]
+    pub args: Vec<String>,
+    /// Arguments of the execution of the program.
+    #[arg(value_name = "EXEC_ARG", last = true)]
+    pub execution_args: Vec<String>,
+}
+
+/// Arguments accepted by `cargo miden run` after `--`, which configure the execution.
+#[derive(Clone, Debug, Parser)]
+#[command(name = "cargo miden run --", disable_version_flag = true)]
+struct ExecutionArgs {
+    /// Place comma-separated felts on the advice stack, after those of `--inputs`
+    #[arg(long, value_name = "FELTS", value_delimiter = ',')]
+    advice_stack: Vec<u64>,
+}
+
+/// The operand stack, advice stack and advice map inputs of the program.
+#[derive(Debug, Default)]
+struct ProgramInputs {
+    stack: Vec<u64>,
+    advice_stack: Vec<u64>,
+    advice_map: BTreeMap<Word, Vec<Felt>>,
+}
+
+impl RunCommand {
+    /// Executes `cargo miden run`, returning the output stack and cycle count of the program.
+    pub fn exec(self) -> Result<RunOutput> {
+        // When preceded by midenc arguments, `--` may be parsed as one of them
+        let (build_args, mut execution_args) = match self.args.iter().position(|arg| arg == "--") {
+            Some(index) => (self.args[..index].to_vec(), self.args[index + 1..].to_vec()),
+            None => (self.args, Vec::new()),
+        };
+        execution_args.extend(self.execution_args);
+        let execution_args = ExecutionArgs::try_parse_from(
+            core::iter::once("cargo miden run --".to_string()).chain(execution_args),
+        )
+        .unwrap_or_else(|err| err.exit());
+
+        let mut inputs = match self.inputs.as_deref() {
+            Some(path) => ProgramInputs::load(path)?,
+            None => ProgramInputs {
+                stack: self.stack,
+                ..ProgramInputs::default()
+            },
+        };
+        inputs.advice_stack.extend(execution_args.advice_stack);
+
+        let package_path = BuildCommand {
+            watch: false,
+            exec: None,
+            args: build_args,
+        }
+        .exec()?
+        .path;
+        let bytes = std::fs::read(&package_path)
+            .with_context(|| format!("failed to read package '{}'", package_path.display()))?;
+        let package = Package::read_from_bytes(&bytes).map_err(|err| {
+            anyhow!("failed to decode package '{}': {err}", package_path.display())
+        })?;
+        if !package.is_program() {
+            bail!(
+                "unable to run '{}': the package is a library, only programs can be executed",
+                package.name
+            );
+        }
+
+        let mut exec = Executor::new(felts(&inputs.stack)?);
+        let core_library = CoreLibrary::default();
+        for (event, handler) in core_library.handlers() {
+            exec.register_event_handler(event, handler)
+                .map_err(|err| anyhow!("failed to register core library event handler: {err}"))?;
+        }
+        let std_library = (*STDLIB).clone();
+        exec.dependency_resolver_mut().insert(*std_library.digest(), std_library);
+        exec.with_dependencies(package.manifest.dependencies())
+            .map_err(|err| anyhow!("{}", PrintDiagnostic::new(err)))?;
+        exec.with_advice_inputs(
+            AdviceInputs::default()
+                .with_stack(felts(&inputs.advice_stack)?)
+                .with_map(inputs.advice_map),
+        );
+
+        let source_manager =
+            Arc::new(DefaultSourceManager::default()) as Arc<dyn SourceManager + Send + Sync>;
+        let mut executor = exec.capture_trace(&package.unwrap_program(), source_manager);
+        while !executor.stopped {
+            if let Err(err) = executor.step() {
+                bail!("execution failed at cycle {}: {err}", executor.cycle);
+            }
+        }
+        let cycles = executor.cycle;
+        let trace = executor.into_execution_trace();
+
+        Ok(RunOutput {
+            package: package_path,
+            stack: trace.outputs().as_int_vec(),
+            cycles,
+        })
+    }
+}
+
+impl ProgramInputs {
+    /// Load the `[inputs]` table of the TOML file at `path`, i.e. its operand `stack`, and the
+    /// `stack` and `[[inputs.advice.map]]` entries of its `[inputs.advice]` table, all of which are
+    /// optional.
+    fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read inputs '{}'", path.display()))?;
+        let document = source
+            .parse::<DocumentMut>()
+            .with_context(|| format!("failed to parse inputs '{}'", path.display()))?;
+
+        let mut inputs = Self::default();
+        let Some(table) = document.get("inputs") else {
+            return Ok(inputs);
+        };
+        if let Some(stack) = table.get("stack") {
+            inputs.stack = values(stack, "inputs.stack")?;
+        }
+        let Some(advice) = table.get("advice") else {
+            return Ok(inputs);
+        };
+        if let Some(stack) = advice.get("stack") {
+            inputs.advice_stack = values(stack, "inputs.advice.stack")?;
+        }
+        if let Some(map) = advice.get("map") {
+            let entries = map
+                .as_array_of_tables()
+                .ok_or_else(|| anyhow!("expected 'inputs.advice.map' to be an array of tables"))?;
+            // Entries with duplicate keys are handled on a last-write-wins basis
+            for entry in entries {
+                let digest = entry
+                    .get("digest")
+                    .and_then(|digest| digest.as_str())
+                    .ok_or_else(|| anyhow!("expected each advice map entry to have a 'digest'"))?;
+                let values = match entry.get("values") {
+                    Some(item) => values(item, "inputs.advice.map.values")?,
+                    None => Vec::new(),
+                };
+                inputs.advice_map.insert(parse_digest(digest)?, felts(&values)?);
+            }
+        }
+        Ok(inputs)
+    }
+}
+
+/// Parse `item` as an array of integers, given either as TOML integers or as decimal or
+/// `0x`-prefixed hexadecimal strings.
+fn values(item: &toml_edit::Item, key: &str) -> Result<Vec<u64>> {
+    let array = item
+        .as_array()
+        .ok_or_else(|| anyhow!("expected '{key}' to be an array of integers"))?;
+    array
+        .iter()
+        .map(|value| {
+            let parsed = match value {
+                toml_edit::Value::Integer(value) => u64::try_from(*value.value()).ok(),
+                toml_edit::Value::String(value) => {
+                    let value = value.value();
+                    match value.strip_prefix("0x") {
+                        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                        None => value.parse().ok(),
+                    }
+                }
+                _ => None,
+            };
+            parsed.ok_or_else(|| anyhow!("invalid value in '{key}': {value}"))
+        })
+        .collect()
+}
+
+/// Parse `digest`, a `0x`-prefixed hexadecimal string of 32 bytes, as a word whose elements are
+/// encoded in little-endian order.
+fn parse_digest(digest: &str) -> Result<Word> {
+    let hex = digest.strip_prefix("0x").unwrap_or(digest);
+    if hex.len() != 64 || !hex.is_ascii() {
+        bail!("invalid advice map digest '{digest}': expected 32 hexadecimal bytes");
+    }
+    let mut elements = [Felt::ZERO; 4];
+    for (element, chunk) in elements.iter_mut().zip(hex.as_bytes().chunks(16)) {
+        let chunk = core::str::from_utf8(chunk).expect("hex digest is ascii");
+        let value = u64::from_str_radix(chunk, 16)
+            .map_err(|_| {
+                anyhow!("invalid advice map digest '{digest}': {chunk} is not hexadecimal")
+            })?
+            .swap_bytes();
+        *element = Felt::new(value).map_err(|_| {
+            anyhow!("invalid advice map digest '{digest}': {value} is not a valid felt")
+        })?;
+    }
+    Ok(Word::new(elements))
+}
+
+/// Convert `values` to field elements, rejecting those which are not canonical.
+fn felts(values: &[u64]) -> Result<Vec<Felt>> {
+    values
+        .iter()
+        .map(|&value| Felt::new(value).map_err(|_| anyhow!("{value} is not a valid felt")))
+        .collect()
+}
@@ -0,0 +1,355 @@
+//! The Miden libraries declared in the `[package.metadata.miden.dependencies]` table of a Cargo
+//! manifest, which are linked against when building the package.
+//!
+//! Each library is declared by name, with one of the following sources:
+//!
+//! ```toml
+//! [package.metadata.miden.dependencies]
+//! # A compiled library (`.masl`) or package (`.masp`), relative to the manifest
+//! foo = { path = "libs/foo.masl" }
+//! # A compiled package, relative to the manifest
+//! bar = { package = "libs/bar.masp" }
+//! # The package with the given digest, among those in a directory
+//! baz = { digest = "0x...", registry-path = "libs" }
+//! ```
+//!
+//! A `digest` may be given for any of them, in which case the resolved package must have it.
+//! Entries with a `wit` key, or with the `path` of a Miden project, are dependencies of the Miden
+//! project instead, see [crate::template].
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use miden_core::serde::{Deserializable, Serializable};
+use miden_mast_package::{Package, TargetType, Version};
+use midenc_compile::Compiler;
+use midenc_session::{LinkLibrary, miden_assembly_syntax::Library, miden_project::Linkage};
+use toml_edit::DocumentMut;
+
+/// The file extension of a compiled Miden library
+const LIBRARY_EXTENSION: &str = "masl";
+
+/// Where a [LibraryDependency] is loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibrarySource {
+    /// A compiled library or package file
+    Path(PathBuf),
+    /// A compiled package file
+    Package(PathBuf),
+    /// The package with the digest of the dependency, among those in a directory
+    Registry(PathBuf),
+}
+
+/// A Miden library declared in `[package.metadata.miden.dependencies]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryDependency {
+    /// The name of the dependency
+    pub name: String,
+    /// Where the library is loaded from, with paths relative to the manifest resolved
+    pub source: LibrarySource,
+    /// The digest the library must have, if specified
+    pub digest: Option<String>,
+    /// How to link against the library, statically by default
+    pub linkage: Linkage,
+}
+
+impl LibraryDependency {
+    /// Load the library, checking that it has the expected digest, if any.
+    pub fn resolve(&self) -> Result<LinkLibrary> {
+        let package = match &self.source {
+            LibrarySource::Path(path) => self.load(path, true)?,
+            LibrarySource::Package(path) => self.load(path, false)?,
+            LibrarySource::Registry(dir) => self.find_in_registry(dir)?,
+        };
+        if package.is_program() {
+            bail!(
+                "Miden dependency '{}' is a program, only libraries can be linked against",
+                self.name
+            );
+        }
+        if let Some(expected) = self.digest.as_deref() {
+            let actual = package.digest().to_hex();
+            if !same_digest(expected, &actual) {
+                bail!(
+                    "Miden dependency '{}' has digest {actual}, but {expected} was expected",
+                    self.name
+                );
+            }
+        }
+
+        Ok(LinkLibrary::from_bytes(self.name.clone(), package.to_bytes(), self.linkage))
+    }
+
+    /// Load the package at `path`, or the library if `allow_library` is set and `path` has the
+    /// `.masl` extension.
+    fn load(&self, path: &Path, allow_library: bool) -> Result<Package> {
+        if !path.is_file() {
+            bail!(
+                "Miden dependency '{}' not found: '{}' does not exist",
+                self.name,
+                path.display()
+            );
+        }
+        let bytes = std::fs::read(path).with_context(|| {
+            format!("failed to read Miden dependency '{}' from '{}'", self.name, path.display())
+        })?;
+        let is_library = path.extension().is_some_and(|ext| ext == LIBRARY_EXTENSION);
+        if allow_library && is_library {
+            let library = Library::read_from_bytes(&bytes).map_err(|err| {
+                anyhow!("failed to decode Miden library '{}': {err}", path.display())
+            })?;
+            return Ok(Package::from_library(
+                self.name.clone().into(),
+                Version::new(0, 0, 0),
+                TargetType::Library,
+                Arc::new(library),
+                None,
+            ));
+        }
+        Package::read_from_bytes(&bytes)
+            .map_err(|err| anyhow!("failed to decode Miden package '{}': {err}", path.display()))
+    }
+
+    /// Find the package with the digest of this dependency among the packages in `dir`.
+    fn find_in_registry(&self, dir: &Path) -> Result<Package> {
+        let Some(expected) = self.digest.as_deref() else {
+            bail!(
+                "Miden dependency '{}' must specify the `digest` to find in its registry",
+                self.name
+            );
+        };
+        let entries = std::fs::read_dir(dir).with_context(|| {
+            format!(
+                "failed to read the registry of Miden dependency '{}' at '{}'",
+                self.name,
+                dir.display()
+            )
+        })?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != Package::EXTENSION) {
+                continue;
+            }
+            // Skip the files which are not valid packages, as they cannot be the dependency
+            let Ok(package) = std::fs::read(&path)
+                .map_err(|err| anyhow!(err))
+                .and_then(|bytes| Package::read_from_bytes(&bytes).map_err(|err| anyhow!(err)))
+            else {
+                continue;
+            };
+            if same_digest(expected, &package.digest().to_hex()) {
+                return Ok(package);
+            }
+        }
+        bail!(
+            "Miden dependency '{}' not found: no package in '{}' has digest {expected}",
+            self.name,
+            dir.display()
+        )
+    }
+}
+
+/// Returns true if the hex-encoded digests `a` and `b` are equal, ignoring case and `0x` prefixes
+fn same_digest(a: &str, b: &str) -> bool {
+    let strip = |digest: &str| {
+        let digest = digest.trim();
+        digest.strip_prefix("0x").unwrap_or(digest).to_ascii_lowercase()
+    };
+    strip(a) == strip(b)
+}
+
+/// Read the Miden libraries declared in the Cargo manifest at `manifest_path`.
+pub fn library_dependencies(manifest_path: &Path) -> Result<Vec<LibraryDependency>> {
+    let manifest = std::fs::read_to_string(manifest_path)
+        .with_context(|| format!("failed to read Cargo manifest '{}'", manifest_path.display()))?;
+    let manifest = manifest
+        .parse::<DocumentMut>()
+        .with_context(|| format!("failed to parse Cargo manifest '{}'", manifest_path.display()))?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new("."));
+    parse_library_dependencies(&manifest, manifest_dir)
+}
+
+/// Resolve the Miden libraries declared in the Cargo manifest at `manifest_path`, see
+/// [LibraryDependency::resolve].
+pub fn resolve_library_dependencies(manifest_path: &Path) -> Result<Vec<LinkLibrary>> {
+    library_dependencies(manifest_path)?
+        .iter()
+        .map(LibraryDependency::resolve)
+        .collect()
+}
+
+/// Add the Miden libraries declared in the manifest at `manifest_path`, if it is a Cargo
+/// manifest, to those linked against by `compiler`.
+///
+/// The libraries are resolved up front, so that missing libraries or digest mismatches are
+/// reported before anything is compiled.
+pub fn link_library_dependencies(compiler: &mut Compiler, manifest_path: &Path) -> Result<()> {
+    if !manifest_path.file_name().is_some_and(|name| name == "Cargo.toml") {
+        return Ok(());
+    }
+    compiler.link_libraries.extend(resolve_library_dependencies(manifest_path)?);
+    Ok(())
+}
+
+/// Returns true if `path` refers to a compiled Miden library or package, rather than a project.
+pub(crate) fn is_library_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext == LIBRARY_EXTENSION || ext == Package::EXTENSION)
+}
+
+/// Parse the Miden libraries of `manifest`, whose relative paths are resolved against
+/// `manifest_dir`.
+pub(crate) fn parse_library_dependencies(
+    manifest: &DocumentMut,
+    manifest_dir: &Path,
+) -> Result<Vec<LibraryDependency>> {
+    let Some(dependencies) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("miden"))
+        .and_then(|miden| miden.get("dependencies"))
+        .and_then(|dependencies| dependencies.as_table_like())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut libraries = Vec::new();
+    for (name, dependency) in dependencies.iter() {
+        let string = |key: &str| -> Result<Option<&str>> {
+            match dependency.get(key) {
+                None => Ok(None),
+                Some(item) => item.as_str().map(Some).ok_or_else(|| {
+                    anyhow!("expected '{key}' of Miden dependency '{name}' to be a string")
+                }),
+            }
+        };
+        let path = string("path")?.filter(|path| is_library_path(path));
+        let package = string("package")?;
+        let registry = string("registry-path")?;
+        let digest = string("digest")?;
+        let source = match (path, package, registry) {
+            (Some(path), None, None) => LibrarySource::Path(manifest_dir.join(path)),
+            (None, Some(package), None) => LibrarySource::Package(manifest_dir.join(package)),
+            (None, None, Some(registry)) => LibrarySource::Registry(manifest_dir.join(registry)),
+            (None, None, None) if digest.is_some() => {
+                bail!("Miden dependency '{name}' must specify the `registry-path` to find it in")
+            }
+            // A dependency of the Miden project, e.g. on WIT interfaces or another project
+            (None, None, None) => continue,
+            _ => bail!(
+                "Miden dependency '{name}' must specify only one of `path`, `package` or \
+                 `registry-path`"
+            ),
+        };
+        let linkage = match string("linkage")? {
+            None | Some("static") => Linkage::Static,
+            Some("dynamic") => Linkage::Dynamic,
+            Some(other) => bail!(
+                "unknown linkage '{other}' of Miden dependency '{name}', expected `static` or \
+                 `dynamic`"
+            ),
+        };
+        libraries.push(LibraryDependency {
+            name: name.to_string(),
+            source,
+            digest: digest.map(str::to_string),
+            linkage,
+        });
+    }
+    Ok(libraries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(manifest: &str) -> Result<Vec<LibraryDependency>> {
+        parse_library_dependencies(&manifest.parse().unwrap(), Path::new("/project"))
+    }
+
+    #[test]
+    fn library_dependencies_are_parsed() {
+        let dependencies = parse(
+            r#"
+[package]
+name = "contract"
+
+[package.metadata.miden.dependencies]
+foo = { path = "libs/foo.masl" }
+bar = { package = "libs/bar.masp", digest = "0xAB", linkage = "dynamic" }
+baz = { digest = "0xcd", registry-path = "/registry" }
+wallet = { path = "../basic-wallet" }
+account = { wit = "wit/account.wit" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            dependencies,
+            [
+                LibraryDependency {
+                    name: "foo".to_string(),
+                    source: LibrarySource::Path(PathBuf::from("/project/libs/foo.masl")),
+                    digest: None,
+                    linkage: Linkage::Static,
+                },
+                LibraryDependency {
+                    name: "bar".to_string(),
+                    source: LibrarySource::Package(PathBuf::from("/project/libs/bar.masp")),
+                    digest: Some("0xAB".to_string()),
+                    linkage: Linkage::Dynamic,
+                },
+                LibraryDependency {
+                    name: "baz".to_string(),
+                    source: LibrarySource::Registry(PathBuf::from("/registry")),
+                    digest: Some("0xcd".to_string()),
+                    linkage: Linkage::Static,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_library_dependencies_are_rejected() {
+        let err = parse(
+            r#"
+[package.metadata.miden.dependencies]
+foo = { path = "foo.masl", package = "foo.masp" }
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("only one of"), "{err}");
+
+        let err = parse(
+            r#"
+[package.metadata.miden.dependencies]
+foo = { digest = "0x01" }
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("registry-path"), "{err}");
+    }
+
+    #[test]
+    fn digests_are_compared_ignoring_case_and_prefix() {
+        assert!(same_digest("0xABcd", "abcd"));
+        assert!(same_digest("0xabcd", "0xABCD"));
+        assert!(!same_digest("0xabcd", "0xabce"));
+    }
+
+    #[test]
+    fn missing_library_is_reported() {
+        let dependency = LibraryDependency {
+            name: "foo".to_string(),
+            source: LibrarySource::Path(std::env::temp_dir().join("no-such-library.masl")),
+            digest: None,
+            linkage: Linkage::Static,
+        };
+        let err = dependency.resolve().unwrap_err();
+        assert!(err.to_string().contains("Miden dependency 'foo' not found"), "{err}");
+    }
+}
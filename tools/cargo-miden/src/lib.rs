@@ -8,12 +8,17 @@ use clap::Parser;
 
 mod cli;
 mod commands;
+mod dependencies;
 mod outputs;
 mod template;
 mod utils;
+mod watch;
 
-pub use commands::BuildCommand;
-pub use outputs::CommandOutput;
+pub use commands::{
+    AccountComponentInfo, BuildCommand, CheckCommand, DependencyInfo, ExpandCommand,
+    InspectCommand, InterfaceInfo, PackageInfo, ProcedureInfo, RunCommand, StorageSlotInfo,
+};
+pub use outputs::{BuiltPackage, CommandOutput, ExpandedWit, RunOutput};
 
 /// Requested output type for the `build` command.
 #[derive(Debug, Copy, Clone)]
@@ -43,11 +48,29 @@ where
             let project_path = cmd.exec()?;
             Ok(Some(CommandOutput::NewCommandOutput { project_path }))
         }
-        cli::CargoMidenCommand::Build(cmd) => cmd.exec().map(|output| {
+        cli::CargoMidenCommand::Build(cmd) => cmd.exec().map(|built| {
             Some(CommandOutput::BuildCommandOutput {
-                output: vec![output],
+                output: vec![built.path],
+                fresh: built.fresh,
             })
         }),
+        cli::CargoMidenCommand::Check(cmd) => {
+            cmd.exec()?;
+            Ok(None)
+        }
+        cli::CargoMidenCommand::Expand(cmd) => {
+            let written = cmd.out_dir.is_some();
+            cmd.exec()
+                .map(|output| Some(CommandOutput::ExpandCommandOutput { output, written }))
+        }
+        cli::CargoMidenCommand::Run(cmd) => {
+            cmd.exec().map(|output| Some(CommandOutput::RunCommandOutput { output }))
+        }
+        cli::CargoMidenCommand::Inspect(cmd) => {
+            let json = cmd.json;
+            cmd.exec()
+                .map(|output| Some(CommandOutput::InspectCommandOutput { output, json }))
+        }
         cli::CargoMidenCommand::Test(cmd) => {
             cmd.exec()?;
             Ok(None)
@@ -16,10 +16,46 @@ fn init_logger() {
 fn main() -> anyhow::Result<()> {
     init_logger();
 
-    match cargo_miden::run(std::env::args()) {
-        Ok(Some(CommandOutput::BuildCommandOutput { output })) => {
+    let result = cargo_miden::run(std::env::args());
+    // The global logger is never dropped, so make sure any buffered log file is written out
+    log::logger().flush();
+
+    match result {
+        Ok(Some(CommandOutput::BuildCommandOutput { output, fresh })) => {
+            // Like Cargo, report packages which did not need to be compiled again as fresh
+            let status = if fresh { "Fresh" } else { "Compiled" };
             for artifact_path in output {
-                println!("Compiled {}", artifact_path.display());
+                println!("{status} {}", artifact_path.display());
+            }
+        }
+        Ok(Some(CommandOutput::RunCommandOutput { output })) => {
+            println!("Executed {}", output.package.display());
+            let stack = output.stack.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+            println!("Output stack: [{}]", stack.join(", "));
+            println!(
+                "VM cycles: {} extended to {} steps",
+                output.cycles,
+                output.cycles.next_power_of_two()
+            );
+        }
+        Ok(Some(CommandOutput::InspectCommandOutput { output, json })) => {
+            if json {
+                println!("{}", output.to_json()?);
+            } else {
+                print!("{output}");
+            }
+        }
+        Ok(Some(CommandOutput::ExpandCommandOutput { output, written })) => {
+            for (index, world) in output.iter().enumerate() {
+                if written {
+                    println!("Wrote {}", world.path.display());
+                    continue;
+                }
+                if index > 0 {
+                    println!();
+                }
+                println!("// {}", world.path.display());
+                print!("{}", world.source);
             }
         }
         Ok(_) => {}
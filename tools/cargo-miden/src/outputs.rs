@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::PackageInfo;
+
 /// Represents the structured output of a successful `cargo miden` command.
 #[derive(Debug, Clone)]
 pub enum CommandOutput {
@@ -12,19 +14,94 @@ pub enum CommandOutput {
     BuildCommandOutput {
         /// The type and path of the artifact produced by the build.
         output: Vec<PathBuf>,
+        /// Whether the package was reused from the compilation cache, rather than compiled.
+        fresh: bool,
+    },
+    /// Output from the `run` command.
+    RunCommandOutput {
+        /// The package which was executed, and the result of its execution.
+        output: RunOutput,
+    },
+    /// Output from the `inspect` command.
+    InspectCommandOutput {
+        /// The contents of the inspected package.
+        output: PackageInfo,
+        /// Whether the contents were requested as JSON.
+        json: bool,
+    },
+    /// Output from the `expand` command.
+    ExpandCommandOutput {
+        /// The WIT worlds synthesized by the Miden SDK macros of the package.
+        output: Vec<ExpandedWit>,
+        /// Whether the WIT was written to a requested directory rather than to be printed.
+        written: bool,
     },
     // Add other variants here if other commands need structured output later.
 }
 
+/// The result of executing a program with `cargo miden run`.
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    /// The path of the package which was executed.
+    pub package: PathBuf,
+    /// The operand stack when the program finished, with the top of the stack first.
+    pub stack: Vec<u64>,
+    /// The number of VM cycles it took to execute the program.
+    pub cycles: usize,
+}
+
+/// A WIT world synthesized by the Miden SDK macros, as reported by `cargo miden expand`.
+#[derive(Debug, Clone)]
+pub struct ExpandedWit {
+    /// The path of the file the world was written to.
+    pub path: PathBuf,
+    /// The WIT source of the world.
+    pub source: String,
+}
+
+/// The package produced by `cargo miden build`.
+#[derive(Debug, Clone)]
+pub struct BuiltPackage {
+    /// The path the package was written to.
+    pub path: PathBuf,
+    /// Whether the package was reused from the compilation cache, rather than compiled.
+    pub fresh: bool,
+}
+
 impl CommandOutput {
     /// Panics if the output is not `BuildCommandOutput`, otherwise returns the inner `BuildOutput`.
     pub fn unwrap_build_output(self) -> Vec<PathBuf> {
         match self {
-            CommandOutput::BuildCommandOutput { output } => output,
+            CommandOutput::BuildCommandOutput { output, .. } => output,
             _ => panic!("called `unwrap_build_output()` on a non-BuildCommandOutput value"),
         }
     }
 
+    /// Panics if the output is not `RunCommandOutput`, otherwise returns the inner `RunOutput`.
+    pub fn unwrap_run_output(self) -> RunOutput {
+        match self {
+            CommandOutput::RunCommandOutput { output } => output,
+            _ => panic!("called `unwrap_run_output()` on a non-RunCommandOutput value"),
+        }
+    }
+
+    /// Panics if the output is not `InspectCommandOutput`, otherwise returns the inner
+    /// `PackageInfo`.
+    pub fn unwrap_inspect_output(self) -> PackageInfo {
+        match self {
+            CommandOutput::InspectCommandOutput { output, .. } => output,
+            _ => panic!("called `unwrap_inspect_output()` on a non-InspectCommandOutput value"),
+        }
+    }
+
+    /// Panics if the output is not `ExpandCommandOutput`, otherwise returns the inner WIT worlds.
+    pub fn unwrap_expand_output(self) -> Vec<ExpandedWit> {
+        match self {
+            CommandOutput::ExpandCommandOutput { output, .. } => output,
+            _ => panic!("called `unwrap_expand_output()` on a non-ExpandCommandOutput value"),
+        }
+    }
+
     /// Panics if the output is not `NewCommandOutput`, otherwise returns the inner project path.
     pub fn unwrap_new_output(self) -> PathBuf {
         match self {
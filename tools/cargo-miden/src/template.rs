@@ -140,7 +140,8 @@ version = \"{}\"
     );
 
     match project_kind {
-        "account" | "account-component" | "authentication-component" => {
+        // Faucets are account components which mint and burn assets
+        "account" | "account-component" | "authentication-component" | "faucet" => {
             manifest.push_str("[lib]\n");
             manifest.push_str("kind = \"account-component\"\n");
             manifest.push_str(&format!(
@@ -189,7 +190,12 @@ version = \"{}\"
     }
     if let Some(dependencies) = metadata_dependencies {
         for (name, dependency) in dependencies.iter() {
-            if let Some(path) = dependency.get("path").and_then(|path| path.as_str()) {
+            // Compiled libraries are linked against by `cargo miden build` instead
+            if let Some(path) = dependency
+                .get("path")
+                .and_then(|path| path.as_str())
+                .filter(|path| !crate::dependencies::is_library_path(path))
+            {
                 let name = miden_dependency_name(name);
                 manifest.push_str(&format!(
                     "{} = {{ path = \"{}\" }}\n",
@@ -938,6 +944,24 @@ ignore = ["skip-me"]
         Ok(())
     }
 
+    #[test]
+    fn faucet_projects_render_account_component_manifests() {
+        let cargo_manifest = r#"
+[package]
+name = "my-faucet"
+version = "0.2.0"
+
+[package.metadata.miden]
+project-kind = "faucet"
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+
+        let manifest = render_miden_project_manifest("my-faucet", &cargo_manifest);
+        assert!(manifest.contains("kind = \"account-component\""), "{manifest}");
+        assert!(!manifest.contains("[[bin]]"), "{manifest}");
+    }
+
     #[test]
     fn parse_define_rejects_invalid_inputs() {
         assert!(parse_define("missing_value").is_err());
@@ -0,0 +1,288 @@
+//! Watch mode for `cargo miden build --watch`: rebuild a package whenever its sources change.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow};
+use miden_core::serde::Deserializable;
+use miden_mast_package::Package;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// How long the sources must be left unchanged before a rebuild is started, so that a burst of
+/// events, e.g. from an editor saving several files, only triggers a single rebuild.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// How often the watcher checks whether it was interrupted while no events are received.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Options of the watch mode of `cargo miden build`.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// The directory of the package whose sources are watched
+    pub project_dir: PathBuf,
+    /// Directories whose changes are ignored, i.e. those the build writes to
+    pub ignored: Vec<PathBuf>,
+    /// A shell command to run after each successful build
+    pub exec: Option<String>,
+}
+
+/// Coalesces the change events received in quick succession into a single rebuild.
+#[derive(Debug)]
+pub(crate) struct Debouncer {
+    delay: Duration,
+    changed: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    /// Create a debouncer which waits for `delay` after the last change before rebuilding.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            changed: BTreeSet::new(),
+            last_change: None,
+        }
+    }
+
+    /// Record that `path` changed at `now`, postponing the pending rebuild.
+    pub fn record(&mut self, path: PathBuf, now: Instant) {
+        self.changed.insert(path);
+        self.last_change = Some(now);
+    }
+
+    /// Returns the time at which the pending rebuild is due, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_change.map(|last_change| last_change + self.delay)
+    }
+
+    /// If a rebuild is due at `now`, returns the paths which changed since the previous one.
+    pub fn poll(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        if self.deadline().is_some_and(|deadline| deadline <= now) {
+            self.last_change = None;
+            Some(core::mem::take(&mut self.changed).into_iter().collect())
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the paths of `event` which are sources of the package, i.e. which should trigger a
+/// rebuild when changed.
+pub(crate) fn changed_sources<'a>(
+    event: &'a Event,
+    ignored: &'a [PathBuf],
+) -> impl Iterator<Item = &'a PathBuf> + 'a {
+    let is_change =
+        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_));
+    event.paths.iter().filter(move |path| {
+        is_change
+            && !ignored.iter().any(|dir| path.starts_with(dir))
+            && !path.components().any(|component| component.as_os_str() == ".git")
+    })
+}
+
+/// Returns the flag set when the user interrupts the process, installing the handler setting it
+/// on first use.
+fn interrupted() -> Result<Arc<AtomicBool>> {
+    static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    if let Some(flag) = INTERRUPTED.get() {
+        return Ok(Arc::clone(flag));
+    }
+    let flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&flag);
+    ctrlc::set_handler(move || handler_flag.store(true, Ordering::SeqCst))
+        .context("failed to install the interrupt handler")?;
+    Ok(Arc::clone(INTERRUPTED.get_or_init(|| flag)))
+}
+
+/// Build the package with `build`, then rebuild it each time its sources change, until the user
+/// interrupts the process.
+///
+/// Returns the path of the last package which was built successfully.
+pub fn watch(
+    options: &WatchOptions,
+    mut build: impl FnMut() -> Result<PathBuf>,
+) -> Result<PathBuf> {
+    let interrupted = interrupted()?;
+    interrupted.store(false, Ordering::SeqCst);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("failed to create file watcher")?;
+    watcher
+        .watch(&options.project_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("failed to watch '{}'", options.project_dir.display()))?;
+    println!("Watching {} for changes, press Ctrl-C to stop", options.project_dir.display());
+
+    let mut status = BuildStatus::default();
+    status.rebuild(options, &mut build);
+    let mut debouncer = Debouncer::new(DEBOUNCE_DELAY);
+    while !interrupted.load(Ordering::SeqCst) {
+        let now = Instant::now();
+        let timeout = debouncer
+            .deadline()
+            .map_or(POLL_INTERVAL, |deadline| deadline.saturating_duration_since(now))
+            .min(POLL_INTERVAL);
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                for path in changed_sources(&event, &options.ignored) {
+                    debouncer.record(path.clone(), Instant::now());
+                }
+            }
+            Ok(Err(err)) => log::warn!(target: "cargo-miden", "file watcher error: {err}"),
+            Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Some(changed) = debouncer.poll(Instant::now()) {
+            let changed = changed
+                .iter()
+                .map(|path| path.strip_prefix(&options.project_dir).unwrap_or(path).display())
+                .map(|path| path.to_string())
+                .collect::<Vec<_>>();
+            println!("Changed: {}", changed.join(", "));
+            status.rebuild(options, &mut build);
+        }
+    }
+    println!("Stopped watching {}", options.project_dir.display());
+
+    status.last_package.ok_or_else(|| anyhow!("no package was built successfully"))
+}
+
+/// The outcome of the builds run so far in watch mode.
+#[derive(Debug, Default)]
+struct BuildStatus {
+    last_package: Option<PathBuf>,
+    last_digest: Option<String>,
+}
+
+impl BuildStatus {
+    /// Run `build`, and print its outcome, then run the `--exec` command if it succeeded.
+    fn rebuild(&mut self, options: &WatchOptions, build: &mut impl FnMut() -> Result<PathBuf>) {
+        let start = Instant::now();
+        let result = build().and_then(|path| package_digest(&path).map(|digest| (path, digest)));
+        let elapsed = start.elapsed();
+        let (path, digest) = match result {
+            Ok(built) => built,
+            Err(err) => {
+                eprintln!("{err:?}");
+                println!("Build failed after {elapsed:.2?}");
+                return;
+            }
+        };
+
+        let change = if self.last_digest.as_ref() == Some(&digest) {
+            "unchanged"
+        } else {
+            "changed"
+        };
+        println!("Built {} in {elapsed:.2?}: digest {digest} ({change})", path.display());
+        self.last_package = Some(path);
+        self.last_digest = Some(digest);
+
+        if let Some(command) = options.exec.as_deref()
+            && let Err(err) = run_command(command, &options.project_dir)
+        {
+            eprintln!("{err:?}");
+        }
+    }
+}
+
+/// Read the digest of the package at `path`.
+fn package_digest(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("failed to read package '{}'", path.display()))?;
+    let package = Package::read_from_bytes(&bytes)
+        .map_err(|err| anyhow!("failed to decode package '{}': {err}", path.display()))?;
+    Ok(package.digest().to_hex())
+}
+
+/// Run `command` with the shell of the platform, in `dir`.
+fn run_command(command: &str, dir: &Path) -> Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .current_dir(dir)
+        .status()
+        .with_context(|| format!("failed to run `{command}`"))?;
+    if !status.success() {
+        anyhow::bail!("`{command}` exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    use super::*;
+
+    #[test]
+    fn changes_are_debounced() {
+        let mut debouncer = Debouncer::new(Duration::from_millis(200));
+        let start = Instant::now();
+        assert_eq!(debouncer.poll(start), None);
+
+        debouncer.record(PathBuf::from("src/lib.rs"), start);
+        assert_eq!(debouncer.poll(start + Duration::from_millis(100)), None);
+
+        // A change during the delay postpones the rebuild
+        debouncer.record(PathBuf::from("Cargo.toml"), start + Duration::from_millis(150));
+        debouncer.record(PathBuf::from("src/lib.rs"), start + Duration::from_millis(150));
+        assert_eq!(debouncer.poll(start + Duration::from_millis(300)), None);
+        assert_eq!(
+            debouncer.poll(start + Duration::from_millis(350)),
+            Some(vec![PathBuf::from("Cargo.toml"), PathBuf::from("src/lib.rs")])
+        );
+
+        // Each batch of changes triggers a single rebuild
+        assert_eq!(debouncer.poll(start + Duration::from_millis(1000)), None);
+        assert_eq!(debouncer.deadline(), None);
+    }
+
+    #[test]
+    fn build_outputs_are_not_sources() {
+        let project = PathBuf::from("/project");
+        let ignored = vec![project.join("target")];
+
+        let event = Event::new(EventKind::Modify(ModifyKind::Any))
+            .add_path(project.join("src/lib.rs"))
+            .add_path(project.join("target/miden/dev/project.masp"))
+            .add_path(project.join(".git/index"));
+        let changed = changed_sources(&event, &ignored).collect::<Vec<_>>();
+        assert_eq!(changed, [&project.join("src/lib.rs")]);
+
+        let event =
+            Event::new(EventKind::Create(CreateKind::File)).add_path(project.join("build.rs"));
+        assert_eq!(changed_sources(&event, &ignored).count(), 1);
+
+        // Reading a file does not change it
+        let event =
+            Event::new(EventKind::Access(AccessKind::Any)).add_path(project.join("src/lib.rs"));
+        assert_eq!(changed_sources(&event, &ignored).count(), 0);
+    }
+
+    #[test]
+    fn exec_command_failures_are_reported() {
+        let dir = std::env::temp_dir();
+        assert!(run_command("exit 0", &dir).is_ok());
+        let err = run_command("exit 3", &dir).unwrap_err();
+        assert!(err.to_string().contains("exit 3"), "{err}");
+    }
+}
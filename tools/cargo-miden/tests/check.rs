@@ -0,0 +1,129 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use cargo_miden::run;
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+const PROGRAM_PRELUDE: &str = r#"#![no_std]
+#![feature(alloc_error_handler)]
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[cfg(not(test))]
+#[alloc_error_handler]
+fn alloc_error(_layout: core::alloc::Layout) -> ! {
+    loop {}
+}
+"#;
+
+/// Creates a project from the `program` template in a fresh temporary directory, replaces its
+/// `src/lib.rs` with `lib_rs`, and makes it the working directory.
+///
+/// Returns the temporary directory to clean up.
+fn new_program_project(project_name: &str, lib_rs: &str) -> PathBuf {
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_check_test_{project_name}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    env::set_current_dir(&temp_dir).unwrap();
+
+    let args = ["cargo", "miden", "new", project_name]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([project_template_arg("--program")]);
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+    fs::write(project_path.join("src/lib.rs"), lib_rs).unwrap();
+    env::set_current_dir(&project_path).unwrap();
+    temp_dir
+}
+
+/// Runs `cargo miden <args>`, returning how long it took.
+fn timed_run(args: &[&str]) -> Duration {
+    let start = Instant::now();
+    run(["cargo", "miden"].iter().chain(args).map(|s| s.to_string()))
+        .unwrap_or_else(|err| panic!("cargo miden {args:?} failed: {err:?}"));
+    start.elapsed()
+}
+
+#[test]
+fn check_skips_codegen() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    // A function which is cheap to type-check, but expensive to lower to Miden Assembly
+    let mut lib_rs = PROGRAM_PRELUDE.to_string();
+    lib_rs.push_str("#[unsafe(no_mangle)]\npub fn entrypoint(value: u32) -> u32 {\n");
+    lib_rs.push_str("    let mut x = core::hint::black_box(value);\n");
+    for i in 0..2000 {
+        lib_rs.push_str(&format!(
+            "    x = core::hint::black_box(x.wrapping_mul(31).wrapping_add({i}));\n"
+        ));
+    }
+    lib_rs.push_str("    x\n}\n");
+    let temp_dir = new_program_project("large_function", &lib_rs);
+
+    let check = timed_run(&["check"]);
+    let build = timed_run(&["build"]);
+    assert!(check < build, "expected check ({check:?}) to be faster than build ({build:?})");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+#[test]
+fn check_translate_reports_abi_errors() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    // A linker stub for a procedure which does not exist in the transaction kernel
+    let mut lib_rs = PROGRAM_PRELUDE.to_string();
+    lib_rs.push_str(
+        r#"
+#[unsafe(export_name = "miden::protocol::active_account::no_such_procedure")]
+#[inline(never)]
+pub extern "C" fn no_such_procedure() -> u32 {
+    core::arch::wasm32::unreachable()
+}
+
+#[unsafe(no_mangle)]
+pub fn entrypoint(value: u32) -> u32 {
+    value + no_such_procedure()
+}
+"#,
+    );
+    let temp_dir = new_program_project("abi_error", &lib_rs);
+
+    // The Rust sources are valid, so `cargo check` alone succeeds
+    timed_run(&["check"]);
+
+    let err = run(["cargo", "miden", "check", "--translate"].into_iter().map(|s| s.to_string()))
+        .expect_err("expected the unknown procedure to be reported");
+    let err = format!("{err:?}");
+    assert!(err.contains("no Miden Assembly procedure is known"), "{err}");
+    assert!(err.contains("no_such_procedure"), "{err}");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
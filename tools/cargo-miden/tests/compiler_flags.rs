@@ -0,0 +1,124 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use cargo_miden::run;
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+/// Creates a project from the `program` template in a fresh temporary directory, and makes it the
+/// working directory.
+///
+/// Returns the temporary directory to clean up, and the path of the project.
+fn new_program_project(name: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_compiler_flags_{name}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    env::set_current_dir(&temp_dir).unwrap();
+
+    let args = ["cargo", "miden", "new", name]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([project_template_arg("--program")]);
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+    env::set_current_dir(&project_path).unwrap();
+    (temp_dir, project_path)
+}
+
+/// Builds the project in the working directory with `args`, returning the directory the package
+/// was written to.
+fn build(args: &[&str]) -> PathBuf {
+    let package = run(["cargo", "miden", "build"].iter().chain(args).map(|s| s.to_string()))
+        .unwrap_or_else(|err| panic!("cargo miden build {args:?} failed: {err:?}"))
+        .expect("expected BuildCommandOutput")
+        .unwrap_build_output()
+        .pop()
+        .unwrap();
+    package.parent().unwrap().to_path_buf()
+}
+
+/// Returns the `.masm` files found under `dir`.
+fn masm_files(dir: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "masm"))
+        .collect()
+}
+
+#[test]
+fn midenc_flags_are_forwarded() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let (temp_dir, project_path) = new_program_project("midenc_flags");
+
+    let out_dir = build(&[]);
+    assert!(masm_files(&out_dir).is_empty(), "no Miden Assembly was requested");
+
+    let out_dir = build(&["--midenc", "--emit", "masm"]);
+    let emitted = masm_files(&out_dir);
+    assert!(
+        !emitted.is_empty(),
+        "expected `--emit masm` to write Miden Assembly to the target dir"
+    );
+    assert!(masm_files(&project_path.join("src")).is_empty());
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+#[test]
+fn compiler_flags_are_read_from_package_metadata() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let (temp_dir, project_path) = new_program_project("metadata_flags");
+    let cargo_toml = fs::read_to_string("Cargo.toml").unwrap();
+    let with_flags = |flags: &str| {
+        let metadata = format!("[package.metadata.miden]\ncompiler-flags = {flags}\n");
+        cargo_toml.replacen("[package.metadata.miden]\n", &metadata, 1)
+    };
+    assert!(cargo_toml.contains("[package.metadata.miden]\n"), "{cargo_toml}");
+
+    fs::write("Cargo.toml", with_flags(r#"["--emit", "masm"]"#)).unwrap();
+    let out_dir = build(&["--release"]);
+    assert!(!masm_files(&out_dir).is_empty(), "expected the metadata flags to be applied");
+
+    // Flags given on the command line take precedence over those in the metadata
+    fs::write(
+        "Cargo.toml",
+        with_flags(r#"["--emit", "masm", "--output-dir", "metadata-out"]"#),
+    )
+    .unwrap();
+    build(&["--release", "--midenc", "--output-dir", "cli-out"]);
+    assert!(!masm_files(&project_path.join("cli-out")).is_empty());
+    assert!(masm_files(&project_path.join("metadata-out")).is_empty());
+
+    fs::write("Cargo.toml", with_flags(r#"["--no-such-flag"]"#)).unwrap();
+    let err = run(["cargo", "miden", "build"].into_iter().map(|s| s.to_string()))
+        .expect_err("expected the invalid flag to be reported");
+    let err = format!("{err:?}");
+    assert!(err.contains("invalid `compiler-flags`"), "{err}");
+    assert!(err.contains("--no-such-flag"), "{err}");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
@@ -0,0 +1,73 @@
+use std::{env, fs, path::Path};
+
+use cargo_miden::{ExpandedWit, run};
+use midenc_expect_test::expect_file;
+
+use crate::utils::{current_dir_lock, workspace_root};
+
+/// Runs `cargo miden expand` in `project_dir`, writing the WIT to a fresh temporary directory.
+///
+/// A fresh directory is used so that the macros are expanded again even if the project was already
+/// checked.
+fn expand(project_dir: &Path, name: &str) -> Vec<ExpandedWit> {
+    let out_dir = env::temp_dir().join(format!(
+        "cargo_miden_expand_{name}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    env::set_current_dir(project_dir).unwrap();
+    let output = run(["cargo", "miden", "expand", "--out-dir"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([out_dir.display().to_string()]))
+    .expect("cargo miden expand failed")
+    .expect("expected ExpandCommandOutput")
+    .unwrap_expand_output();
+    for world in &output {
+        assert!(world.path.starts_with(&out_dir), "{}", world.path.display());
+    }
+    fs::remove_dir_all(out_dir).unwrap();
+    output
+}
+
+#[test]
+fn expand_basic_wallet_wit() {
+    let _cwd_lock = current_dir_lock();
+
+    let worlds = expand(&workspace_root().join("examples/basic-wallet"), "basic_wallet");
+    let [world] = worlds.as_slice() else {
+        panic!("expected a single WIT world, got {worlds:?}");
+    };
+    assert_eq!(
+        world.path.file_name().unwrap().to_str(),
+        Some("basic_wallet.basic-wallet-world.wit")
+    );
+    expect_file!["expected/basic_wallet.wit"].assert_eq(&world.source);
+}
+
+#[test]
+fn expand_exported_custom_types() {
+    let _cwd_lock = current_dir_lock();
+
+    let fixture = workspace_root().join("tests/fixtures/components/component-macros-account");
+    let worlds = expand(&fixture, "component_macros_account");
+    let [world] = worlds.as_slice() else {
+        panic!("expected a single WIT world, got {worlds:?}");
+    };
+    let wit = &world.source;
+    assert!(wit.contains("package miden:component-macros-account@0.1.0;"), "{wit}");
+    assert!(
+        wit.contains("    record struct-a {\n        foo: word,\n        asset: asset,\n    }"),
+        "{wit}"
+    );
+    assert!(
+        wit.contains("    variant enum-a {\n        variant-a,\n        variant-b,\n    }"),
+        "{wit}"
+    );
+    assert!(
+        wit.contains("test-custom-types: func(a: struct-a, asset: asset) -> struct-b;"),
+        "{wit}"
+    );
+}
@@ -0,0 +1,140 @@
+use std::{env, fs, path::Path};
+
+use cargo_miden::run;
+use miden_core::serde::Deserializable;
+use miden_mast_package::Package;
+use midenc_session::diagnostics::assert_matches;
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+/// An account component which exports `probe` only when the `testing` feature is enabled.
+const ACCOUNT_WITH_TESTING_FEATURE: &str = r#"#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::{component, component_storage, felt, Felt};
+
+#[component_storage]
+struct FeatureAccountStorage;
+
+#[cfg(not(feature = "testing"))]
+#[component]
+trait FeatureAccount {
+    fn value(&self) -> Felt;
+}
+
+#[cfg(not(feature = "testing"))]
+#[component]
+impl FeatureAccount for FeatureAccountStorage {
+    fn value(&self) -> Felt {
+        felt!(1)
+    }
+}
+
+#[cfg(feature = "testing")]
+#[component]
+trait FeatureAccount {
+    fn value(&self) -> Felt;
+    fn probe(&self) -> Felt;
+}
+
+#[cfg(feature = "testing")]
+#[component]
+impl FeatureAccount for FeatureAccountStorage {
+    fn value(&self) -> Felt {
+        felt!(1)
+    }
+
+    fn probe(&self) -> Felt {
+        felt!(2)
+    }
+}
+"#;
+
+/// Builds the project in the current directory with `cargo miden build <args>`, returning whether
+/// the resulting package exports `probe`.
+fn build_and_check_probe(args: &[&str], expected_dir: &Path) -> bool {
+    let output = run(["cargo", "miden", "build"]
+        .into_iter()
+        .chain(args.iter().copied())
+        .map(String::from))
+    .expect("cargo miden build failed")
+    .expect("expected BuildCommandOutput")
+    .unwrap_build_output();
+    assert_matches!(output.as_slice(), [_artifact_path]);
+    let artifact_path = &output[0];
+    assert!(artifact_path.starts_with(expected_dir), "{}", artifact_path.display());
+
+    let bytes = fs::read(artifact_path).unwrap();
+    let package = Package::read_from_bytes(&bytes).unwrap();
+    package.manifest.exports().any(|export| export.name().contains("probe"))
+}
+
+#[test]
+fn build_with_features_and_target_dir() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let restore_dir = env::current_dir().unwrap();
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_features_test_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    env::set_current_dir(&temp_dir).unwrap();
+
+    let args = ["cargo", "miden", "new", "feature_account"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([project_template_arg("--account")]);
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+
+    // declare the `testing` feature, and gate the `probe` export behind it
+    let manifest_path = project_path.join("Cargo.toml");
+    let mut manifest = fs::read_to_string(&manifest_path).unwrap();
+    manifest.push_str("\n[features]\ntesting = []\n");
+    fs::write(&manifest_path, manifest).unwrap();
+    fs::write(project_path.join("src/lib.rs"), ACCOUNT_WITH_TESTING_FEATURE).unwrap();
+    env::set_current_dir(&project_path).unwrap();
+
+    let default_dir = project_path.join("target").join("miden").join("dev");
+    assert!(
+        !build_and_check_probe(&[], &default_dir),
+        "`probe` must not be exported by default"
+    );
+
+    let custom_dir = temp_dir.join("custom-target");
+    let custom_dir_arg = custom_dir.display().to_string();
+    let exports_probe = build_and_check_probe(
+        &["--features", "testing", "--target-dir", &custom_dir_arg],
+        &custom_dir.join("miden").join("dev"),
+    );
+    assert!(exports_probe, "`probe` must be exported with `--features testing`");
+    assert!(
+        custom_dir.join("wasm32-wasip2").exists(),
+        "expected cargo to place its artifacts in the custom target directory"
+    );
+
+    // switching the feature off again must not reuse the Wasm built with it
+    assert!(
+        !build_and_check_probe(
+            &["--target-dir", &custom_dir_arg],
+            &custom_dir.join("miden").join("dev")
+        ),
+        "`probe` must not be exported once `--features testing` is dropped"
+    );
+
+    env::set_current_dir(restore_dir).unwrap();
+    fs::remove_dir_all(temp_dir).unwrap();
+}
@@ -0,0 +1,64 @@
+use std::env;
+
+use cargo_miden::run;
+
+use crate::utils::{current_dir_lock, workspace_root};
+
+/// `cargo miden inspect` shows the exports and storage layout of the counter-contract example.
+#[test]
+fn inspect_counter_contract() {
+    let _cwd_lock = current_dir_lock();
+
+    let project_dir = workspace_root().join("examples").join("counter-contract");
+    let restore_dir = env::current_dir().unwrap();
+    env::set_current_dir(&project_dir).unwrap();
+    let result = run(["cargo", "miden", "build", "--release"].into_iter().map(|s| s.to_string()));
+    env::set_current_dir(&restore_dir).unwrap();
+    let output = result
+        .expect("cargo miden build for counter-contract failed")
+        .expect("expected BuildCommandOutput")
+        .unwrap_build_output();
+    let package_path = output[0].display().to_string();
+
+    let info = run(["cargo", "miden", "inspect", &package_path].into_iter().map(|s| s.to_string()))
+        .expect("cargo miden inspect failed")
+        .expect("expected InspectCommandOutput")
+        .unwrap_inspect_output();
+
+    assert_eq!(info.name, "counter-contract");
+    assert!(!info.digest.is_empty());
+    for name in ["get-count", "increment-count"] {
+        let procedure = info
+            .find_procedure(name)
+            .unwrap_or_else(|| panic!("expected `{name}` to be exported: {info:#?}"));
+        assert!(procedure.signature.is_some(), "{procedure:?}");
+    }
+
+    let component = info.account_component.as_ref().expect("expected account component metadata");
+    assert_eq!(component.name, "counter-contract");
+    assert_eq!(component.storage.len(), 1, "{component:#?}");
+    let slot = &component.storage[0];
+    assert_eq!(slot.name, "counter_contract::counter_contract::count_map");
+    assert_eq!(slot.ty, "map<word, felt>");
+    assert_eq!(slot.description.as_deref(), Some("counter contract storage map"));
+
+    let text = info.to_string();
+    assert!(text.contains("get-count"), "{text}");
+    assert!(text.contains("counter_contract::counter_contract::count_map"), "{text}");
+
+    let output = run(["cargo", "miden", "inspect", "--json", &package_path]
+        .into_iter()
+        .map(|s| s.to_string()))
+    .expect("cargo miden inspect --json failed")
+    .expect("expected InspectCommandOutput");
+    let cargo_miden::CommandOutput::InspectCommandOutput {
+        output: info,
+        json: true,
+    } = &output
+    else {
+        panic!("expected JSON InspectCommandOutput, got {output:?}");
+    };
+    let json = info.to_json().unwrap();
+    assert!(json.contains("\"increment-count\""), "{json}");
+    assert!(json.contains("\"type\": \"map<word, felt>\""), "{json}");
+}
@@ -0,0 +1,186 @@
+use std::{env, fs, path::Path, rc::Rc};
+
+use cargo_miden::run;
+use midenc_compile::{Compiler, compile_to_memory, stages::Artifact};
+use midenc_session::InputFile;
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+const LIBRARY_MANIFEST: &str = r#"[package]
+name = "mathlib"
+version = "0.1.0"
+
+[lib]
+path = "lib/mod.masm"
+
+[dependencies]
+"#;
+
+const LIBRARY_MASM: &str = r#"#! Returns `(a + b) * 2`
+pub proc add_mul(a: felt, b: felt) -> felt
+    add
+    mul.2
+end
+"#;
+
+const PROGRAM_LIB_RS: &str = r#"#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::Felt;
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[cfg(not(test))]
+#[alloc_error_handler]
+fn alloc_error(_layout: core::alloc::Layout) -> ! {
+    loop {}
+}
+
+// Imported from the `mathlib` MASM library, declared in `[package.metadata.miden.dependencies]`
+#[link(wasm_import_module = "mathlib")]
+unsafe extern "C" {
+    fn add_mul(a: Felt, b: Felt) -> Felt;
+}
+
+#[unsafe(no_mangle)]
+pub fn entrypoint(value: u32) -> u32 {
+    let result = unsafe { add_mul(Felt::from_u32(value), Felt::from_u32(2)) };
+    result.as_canonical_u64() as u32
+}
+"#;
+
+/// Compiles the `mathlib` MASM library in `dir`, returning the digest of the package written to
+/// `dir/mathlib.masp`.
+fn build_masm_library(dir: &Path) -> String {
+    fs::create_dir_all(dir.join("lib")).unwrap();
+    fs::write(dir.join("miden-project.toml"), LIBRARY_MANIFEST).unwrap();
+    fs::write(dir.join("lib/mod.masm"), LIBRARY_MASM).unwrap();
+
+    let input = InputFile::from_path(dir.join("miden-project.toml")).unwrap();
+    let argv = ["--target-dir".to_string(), dir.join("target").display().to_string()];
+    let session = Compiler::new_session(dir.to_path_buf(), Some(input), None, argv);
+    let context = Rc::new(midenc_hir::Context::new(Rc::new(session)));
+    let package = match compile_to_memory(context) {
+        Ok(Artifact::Assembled { package, .. }) => package,
+        Ok(Artifact::Lowered(_)) => panic!("expected the library to be assembled"),
+        Err(err) => panic!("failed to compile the MASM library: {err}"),
+    };
+    package.write_masp_file(dir).unwrap();
+    assert!(dir.join("mathlib.masp").is_file());
+    package.digest().to_hex()
+}
+
+/// Creates a program project calling `mathlib` in `temp_dir`, declaring the library with
+/// `dependency`, and returns its path.
+fn new_program_project(temp_dir: &Path, dependency: &str) -> std::path::PathBuf {
+    env::set_current_dir(temp_dir).unwrap();
+    let args = ["cargo", "miden", "new", "mathlib_user"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([project_template_arg("--program")]);
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+    fs::write(project_path.join("src/lib.rs"), PROGRAM_LIB_RS).unwrap();
+    let mut cargo_toml = fs::read_to_string(project_path.join("Cargo.toml")).unwrap();
+    cargo_toml
+        .push_str(&format!("\n[package.metadata.miden.dependencies]\nmathlib = {dependency}\n"));
+    fs::write(project_path.join("Cargo.toml"), cargo_toml).unwrap();
+    project_path
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_library_dependencies_{name}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    temp_dir
+}
+
+/// A program calls a procedure of a MASM library declared in its Cargo metadata.
+#[test]
+fn call_masm_library_declared_in_metadata() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let temp_dir = temp_dir("call");
+    let digest = build_masm_library(&temp_dir.join("mathlib"));
+    let dependency = format!(r#"{{ package = "../mathlib/mathlib.masp", digest = "{digest}" }}"#);
+    let project_path = new_program_project(&temp_dir, &dependency);
+    env::set_current_dir(&project_path).unwrap();
+
+    let output = run(["cargo", "miden", "run", "--stack", "5", "--release"]
+        .into_iter()
+        .map(|s| s.to_string()))
+    .expect("cargo miden run failed")
+    .expect("expected RunCommandOutput")
+    .unwrap_run_output();
+    assert_eq!(output.stack.first(), Some(&14), "{output:?}");
+
+    // The library can also be found by its digest
+    let dependency = format!(r#"{{ digest = "{digest}", registry-path = "../mathlib" }}"#);
+    let mut cargo_toml = fs::read_to_string("Cargo.toml").unwrap();
+    let start = cargo_toml.find("mathlib = ").unwrap();
+    cargo_toml.replace_range(start.., &format!("mathlib = {dependency}\n"));
+    fs::write("Cargo.toml", cargo_toml).unwrap();
+    let output = run(["cargo", "miden", "run", "--stack", "20", "--release"]
+        .into_iter()
+        .map(|s| s.to_string()))
+    .expect("cargo miden run failed")
+    .expect("expected RunCommandOutput")
+    .unwrap_run_output();
+    assert_eq!(output.stack.first(), Some(&44), "{output:?}");
+
+    env::set_current_dir(env::temp_dir()).unwrap();
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+/// Missing libraries and digest mismatches are reported before compiling the package.
+#[test]
+fn unresolved_library_dependencies_are_reported() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let temp_dir = temp_dir("unresolved");
+    build_masm_library(&temp_dir.join("mathlib"));
+    let project_path =
+        new_program_project(&temp_dir, r#"{ path = "../mathlib/no-such-library.masl" }"#);
+    env::set_current_dir(&project_path).unwrap();
+    let err = run(["cargo", "miden", "build"].into_iter().map(|s| s.to_string()))
+        .expect_err("expected the missing library to be reported");
+    let err = format!("{err:?}");
+    assert!(err.contains("Miden dependency 'mathlib' not found"), "{err}");
+
+    let mut cargo_toml = fs::read_to_string("Cargo.toml").unwrap();
+    let start = cargo_toml.find("mathlib = ").unwrap();
+    cargo_toml.replace_range(
+        start..,
+        "mathlib = { path = \"../mathlib/mathlib.masp\", digest = \"0x1234\" }\n",
+    );
+    fs::write("Cargo.toml", cargo_toml).unwrap();
+    let err = run(["cargo", "miden", "build"].into_iter().map(|s| s.to_string()))
+        .expect_err("expected the digest mismatch to be reported");
+    let err = format!("{err:?}");
+    assert!(err.contains("but 0x1234 was expected"), "{err}");
+
+    env::set_current_dir(env::temp_dir()).unwrap();
+    fs::remove_dir_all(temp_dir).unwrap();
+}
@@ -1,3 +1,12 @@
+mod check;
+mod compiler_flags;
+mod expand;
+mod features;
+mod inspect;
+mod library_dependencies;
+mod new_project;
 mod p2id_cargo_miden_build;
+mod package_cache;
+mod run;
 mod utils;
 mod workspace;
@@ -0,0 +1,136 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use cargo_miden::run;
+use midenc_session::diagnostics::assert_matches;
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+/// Creates a fresh temporary directory for `test_name`, and makes it the working directory.
+fn enter_temp_dir(test_name: &str) -> PathBuf {
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_new_test_{test_name}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    env::set_current_dir(&temp_dir).unwrap();
+    temp_dir
+}
+
+/// Scaffolds `project_name` with `cargo miden new <template_args>`, and builds it with
+/// `cargo miden build`.
+fn new_and_build(project_name: &str, template_args: &[String]) {
+    let args = ["cargo", "miden", "new", project_name]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain(template_args.iter().cloned());
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+
+    let restore_dir = env::current_dir().unwrap();
+    env::set_current_dir(&project_path).unwrap();
+    let output = run(["cargo", "miden", "build"].into_iter().map(|s| s.to_string()))
+        .unwrap_or_else(|err| panic!("cargo miden build of {project_name} failed: {err:?}"))
+        .expect("expected BuildCommandOutput")
+        .unwrap_build_output();
+    assert_matches!(output.as_slice(), [_artifact_path]);
+    env::set_current_dir(restore_dir).unwrap();
+}
+
+/// Returns the directory of the local stand-in for the built-in template `template`.
+fn local_template_dir(template: &str) -> PathBuf {
+    let arg = project_template_arg(template);
+    PathBuf::from(arg.strip_prefix("--template-path=").expect("expected a --template-path arg"))
+}
+
+/// Commits the contents of `dir` to a new git repository.
+fn git_init(dir: &Path) {
+    for args in [
+        &["init", "--quiet"][..],
+        &["add", "."],
+        &[
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "template",
+        ],
+    ] {
+        let status = Command::new("git").args(args).current_dir(dir).status().unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+}
+
+#[test]
+fn new_project_from_each_builtin_template_builds() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let temp_dir = enter_temp_dir("builtin");
+    for (project_name, template) in [
+        ("new_account", "--account"),
+        ("new_note_script", "--note"),
+        ("new_tx_script", "--tx-script"),
+        ("new_auth_component", "--auth-component"),
+        ("new_program", "--program"),
+    ] {
+        new_and_build(project_name, &[project_template_arg(template)]);
+    }
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+#[test]
+fn new_project_from_git_template_builds() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let temp_dir = enter_temp_dir("git");
+    let repo = temp_dir.join("note-template");
+    fs::create_dir_all(repo.join("src")).unwrap();
+    let template = local_template_dir("note");
+    for file in ["Cargo.toml", "Cargo.lock", "src/lib.rs"] {
+        fs::copy(template.join(file), repo.join(file)).unwrap();
+    }
+    git_init(&repo);
+
+    let url = format!("file://{}", repo.display());
+    new_and_build("git_note_script", &["--template".to_string(), url]);
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+#[test]
+fn new_project_with_unknown_template_is_rejected() {
+    let _cwd_lock = current_dir_lock();
+    let temp_dir = enter_temp_dir("unknown");
+
+    let result = run(["cargo", "miden", "new", "unknown_template", "--template", "faucet"]
+        .into_iter()
+        .map(|s| s.to_string()));
+    let err = result.expect_err("expected an unknown template to be rejected");
+    assert!(err.to_string().contains("unknown project template 'faucet'"), "{err:?}");
+    assert!(!temp_dir.join("unknown_template").exists());
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
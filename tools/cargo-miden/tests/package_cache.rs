@@ -0,0 +1,75 @@
+use std::{env, fs, path::PathBuf};
+
+use cargo_miden::{CommandOutput, run};
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+/// Builds the project in the working directory with `args`, returning the path of the package,
+/// and whether it was reused from the compilation cache.
+fn build(args: &[&str]) -> (PathBuf, bool) {
+    let output = run(["cargo", "miden", "build"].iter().chain(args).map(|s| s.to_string()))
+        .unwrap_or_else(|err| panic!("cargo miden build {args:?} failed: {err:?}"))
+        .expect("expected BuildCommandOutput");
+    match output {
+        CommandOutput::BuildCommandOutput { mut output, fresh } => (output.pop().unwrap(), fresh),
+        output => panic!("expected BuildCommandOutput, got {output:?}"),
+    }
+}
+
+#[test]
+fn unchanged_package_is_not_compiled_again() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_package_cache_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    env::set_current_dir(&temp_dir).unwrap();
+
+    let args = ["cargo", "miden", "new", "package_cache"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([project_template_arg("--program")]);
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+    env::set_current_dir(&project_path).unwrap();
+
+    let (package, fresh) = build(&["--release"]);
+    assert!(!fresh, "nothing was cached yet");
+    let compiled = fs::read(&package).unwrap();
+
+    // The package is written again from the cache, so remove it to check that it is
+    fs::remove_file(&package).unwrap();
+    let (cached_package, fresh) = build(&["--release"]);
+    assert!(fresh, "expected the cached package to be reused");
+    assert_eq!(cached_package, package);
+    assert_eq!(fs::read(&package).unwrap(), compiled);
+
+    let (_, fresh) = build(&["--release", "--midenc", "--no-cache"]);
+    assert!(!fresh, "expected `--no-cache` to bypass the cache");
+
+    // Options affecting codegen invalidate the cached package
+    let (_, fresh) = build(&["--release", "--midenc", "--no-masm-opt"]);
+    assert!(!fresh, "expected the package to be compiled with the new options");
+    let (_, fresh) = build(&["--release", "--midenc", "--no-masm-opt"]);
+    assert!(fresh);
+
+    // Requesting other outputs than the package requires compiling it
+    let (_, fresh) = build(&["--release", "--midenc", "--emit", "masm"]);
+    assert!(!fresh, "expected the package to be compiled to emit Miden Assembly");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
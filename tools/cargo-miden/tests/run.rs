@@ -0,0 +1,163 @@
+use std::{env, fs, path::PathBuf};
+
+use cargo_miden::run;
+
+use crate::utils::{current_dir_lock, project_template_arg};
+
+/// Creates a project from the `program` template in a fresh temporary directory, and returns its
+/// path along with the temporary directory to clean up.
+fn new_program_project(project_name: &str) -> (PathBuf, PathBuf) {
+    let temp_dir = env::temp_dir().join(format!(
+        "cargo_miden_run_test_{project_name}_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+    fs::create_dir_all(&temp_dir).unwrap();
+    env::set_current_dir(&temp_dir).unwrap();
+
+    let args = ["cargo", "miden", "new", project_name]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([project_template_arg("--program")]);
+    let project_path = run(args)
+        .expect("cargo miden new failed")
+        .expect("expected NewCommandOutput")
+        .unwrap_new_output();
+    (project_path, temp_dir)
+}
+
+#[test]
+fn run_program_with_stack_inputs() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let (project_path, temp_dir) = new_program_project("run_stack_inputs");
+    env::set_current_dir(&project_path).unwrap();
+    let output = run(["cargo", "miden", "run", "--stack", "41", "--release"]
+        .into_iter()
+        .map(|s| s.to_string()))
+    .expect("cargo miden run failed")
+    .expect("expected RunCommandOutput")
+    .unwrap_run_output();
+
+    assert!(output.package.ends_with("release/run_stack_inputs.masp"), "{output:?}");
+    assert_eq!(output.stack.first(), Some(&42), "{output:?}");
+    assert!(output.cycles > 0, "{output:?}");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+#[test]
+fn run_program_with_inputs_file() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let (project_path, temp_dir) = new_program_project("run_inputs_file");
+    let inputs = temp_dir.join("inputs.toml");
+    fs::write(&inputs, "[inputs]\nstack = [\"0x63\"]\n").unwrap();
+    env::set_current_dir(&project_path).unwrap();
+    let output = run(["cargo", "miden", "run", "--inputs"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([inputs.display().to_string()]))
+    .expect("cargo miden run failed")
+    .expect("expected RunCommandOutput")
+    .unwrap_run_output();
+
+    assert_eq!(output.stack.first(), Some(&100), "{output:?}");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+#[test]
+fn run_program_reading_advice_map_from_inputs_file() {
+    let _cwd_lock = current_dir_lock();
+    // signal integration tests to the cargo-miden code path
+    unsafe {
+        env::set_var("TEST", "1");
+    }
+
+    let (project_path, temp_dir) = new_program_project("run_advice_map");
+    fs::write(project_path.join("src/lib.rs"), ADVICE_MAP_PROGRAM).unwrap();
+    // The order `{ price: 5 * 2^32 + 7, amount: 30, is_bid: true }`, stored under the key
+    // `[1, 2, 3, 4]`, with each element of the key encoded as 8 little-endian bytes
+    let inputs = temp_dir.join("inputs.toml");
+    fs::write(
+        &inputs,
+        r#"[inputs]
+stack = [1]
+
+[[inputs.advice.map]]
+digest = "0x0100000000000000020000000000000003000000000000000400000000000000"
+values = [7, 5, 30, 1]
+"#,
+    )
+    .unwrap();
+    env::set_current_dir(&project_path).unwrap();
+    let output = run(["cargo", "miden", "run", "--inputs"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .chain([inputs.display().to_string()]))
+    .expect("cargo miden run failed")
+    .expect("expected RunCommandOutput")
+    .unwrap_run_output();
+
+    assert_eq!(output.stack.first(), Some(&31), "{output:?}");
+
+    fs::remove_dir_all(temp_dir).unwrap();
+}
+
+/// Program decoding an order from the advice map with `adv_read`, and adding its amount to its
+/// input.
+const ADVICE_MAP_PROGRAM: &str = r#"#![no_std]
+#![feature(alloc_error_handler)]
+
+use miden::{
+    felt, felt_repr as miden_field_repr,
+    intrinsics::advice::{adv_push_mapval, adv_read},
+    FromFeltRepr, Word,
+};
+
+#[global_allocator]
+static ALLOC: miden::BumpAlloc = miden::BumpAlloc::new();
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[cfg(not(test))]
+#[alloc_error_handler]
+fn alloc_error(_layout: core::alloc::Layout) -> ! {
+    loop {}
+}
+
+/// An order, whose felt representation is 4 felts long.
+#[derive(FromFeltRepr)]
+struct Order {
+    price: u64,
+    amount: u32,
+    is_bid: bool,
+}
+
+#[unsafe(no_mangle)]
+pub fn entrypoint(value: u32) -> u32 {
+    adv_push_mapval(Word::from([felt!(1), felt!(2), felt!(3), felt!(4)]));
+    let order: Order = adv_read(4);
+    assert!(order.price == (5 << 32) + 7);
+    assert!(order.is_bid);
+    value + order.amount
+}
+"#;
@@ -1,4 +1,5 @@
 mod debuginfo;
+mod producers;
 
 use clap::{Subcommand, ValueEnum};
 
@@ -8,6 +9,8 @@ use clap::{Subcommand, ValueEnum};
 pub enum Dump {
     /// Dump debug information encoded in a .masp file
     DebugInfo(debuginfo::Config),
+    /// Dump the toolchains which produced a .masp file
+    Producers(producers::Config),
 }
 
 /// The set of known sections that we've added dump support for
@@ -35,6 +38,8 @@ pub enum DumpError {
     Parse(String),
     #[error("no debug_info section found in package")]
     NoDebugInfo,
+    #[error("no producers section found in package")]
+    NoProducers,
 }
 
 impl From<miden_core::serde::DeserializationError> for DumpError {
@@ -47,5 +52,6 @@ impl From<miden_core::serde::DeserializationError> for DumpError {
 pub fn run(command: &Dump) -> Result<(), DumpError> {
     match command {
         Dump::DebugInfo(config) => debuginfo::dump(config),
+        Dump::Producers(config) => producers::dump(config),
     }
 }
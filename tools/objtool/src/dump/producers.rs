@@ -0,0 +1,33 @@
+//! A command to dump the toolchains which produced a MASP package
+//!
+//! These are recorded in the `producers` section of the package, e.g. the versions of rustc and
+//! midenc, and are useful to audit how deployed code was built.
+use std::path::PathBuf;
+
+use clap::Args;
+use miden_core::serde::{Deserializable, SliceReader};
+use miden_mast_package::Package;
+use midenc_session::Producers;
+
+use super::DumpError;
+
+/// Dump the toolchains which produced a .masp file
+#[derive(Debug, Args)]
+pub struct Config {
+    /// The input package to dump info from
+    #[arg(required = true)]
+    input: PathBuf,
+}
+
+pub fn dump(config: &Config) -> Result<(), DumpError> {
+    let bytes = std::fs::read(&config.input)?;
+    let package: Package = Package::read_from(&mut SliceReader::new(&bytes))
+        .map_err(|e| DumpError::Parse(e.to_string()))?;
+
+    let producers = Producers::from_package(&package)?.ok_or(DumpError::NoProducers)?;
+    println!("Package: {}", &package.name);
+    for (field, name, version) in producers.iter() {
+        println!("  | {field}: {name} {version}");
+    }
+    Ok(())
+}